@@ -0,0 +1,134 @@
+//! Node.js bindings for [`docs_rs_mcp::DocsClient`], built with `napi build`. Every method
+//! returns a promise of the object the MCP tool would send as JSON.
+//!
+//! ```js
+//! const { DocsClient } = require("docs-rs-mcp");
+//!
+//! const client = new DocsClient();
+//! const docs = await client.structDocs("tokio", "sync::Mutex");
+//! const hits = await client.search("serde", "Deserializer", null, 5);
+//! ```
+//!
+//! Failed lookups reject with an error whose message starts with the error kind, e.g.
+//! `[not_found] Not found: ...`.
+
+use docs_rs_mcp::{DocsClient, DocsRsMcpError};
+use napi::{Error, Result, Status};
+use napi_derive::napi;
+use serde::Serialize;
+
+/// Looks up documentation of Rust crates
+#[napi(js_name = "DocsClient")]
+pub struct JsDocsClient {
+    client: DocsClient,
+}
+
+#[napi]
+impl JsDocsClient {
+    /// `registry` names a registry from the configuration to look every crate up in.
+    #[napi(constructor)]
+    pub fn new(registry: Option<String>) -> Self {
+        let client = match registry {
+            Some(registry) => DocsClient::new().with_registry(registry),
+            None => DocsClient::new(),
+        };
+        Self { client }
+    }
+
+    #[napi]
+    pub async fn struct_docs(
+        &self,
+        crate_name: String,
+        struct_name: String,
+        version: Option<String>,
+    ) -> Result<serde_json::Value> {
+        to_js(
+            self.client
+                .struct_docs(&crate_name, &struct_name, version.as_deref())
+                .await,
+        )
+    }
+
+    #[napi]
+    pub async fn crate_items(
+        &self,
+        crate_name: String,
+        version: Option<String>,
+    ) -> Result<serde_json::Value> {
+        to_js(
+            self.client
+                .crate_items(&crate_name, version.as_deref())
+                .await,
+        )
+    }
+
+    #[napi]
+    pub async fn search(
+        &self,
+        crate_name: String,
+        query: String,
+        version: Option<String>,
+        limit: Option<u32>,
+    ) -> Result<serde_json::Value> {
+        let limit = limit.map_or(10, |limit| limit as usize);
+        to_js(
+            self.client
+                .search(&crate_name, &query, version.as_deref(), limit)
+                .await,
+        )
+    }
+
+    #[napi]
+    pub async fn module_items(
+        &self,
+        crate_name: String,
+        path: String,
+        version: Option<String>,
+    ) -> Result<serde_json::Value> {
+        to_js(
+            self.client
+                .module_items(&crate_name, &path, version.as_deref())
+                .await,
+        )
+    }
+
+    #[napi]
+    pub async fn crate_features(
+        &self,
+        crate_name: String,
+        version: Option<String>,
+    ) -> Result<serde_json::Value> {
+        to_js(
+            self.client
+                .crate_features(&crate_name, version.as_deref())
+                .await,
+        )
+    }
+
+    #[napi]
+    pub async fn crate_readme(
+        &self,
+        crate_name: String,
+        version: Option<String>,
+    ) -> Result<serde_json::Value> {
+        to_js(
+            self.client
+                .crate_readme(&crate_name, version.as_deref())
+                .await,
+        )
+    }
+
+    #[napi]
+    pub async fn crate_info(&self, crate_name: String) -> Result<serde_json::Value> {
+        to_js(self.client.crate_info(&crate_name).await)
+    }
+}
+
+/// Converts a lookup result to a JS value, or to an error tagged with its kind.
+fn to_js<T: Serialize>(
+    result: std::result::Result<T, DocsRsMcpError>,
+) -> Result<serde_json::Value> {
+    let value =
+        result.map_err(|e| Error::new(Status::GenericFailure, format!("[{}] {}", e.kind(), e)))?;
+    serde_json::to_value(value).map_err(|e| Error::new(Status::GenericFailure, e.to_string()))
+}
@@ -0,0 +1,149 @@
+//! Python bindings for [`docs_rs_mcp::DocsClient`], built into the `docs_rs_mcp` module with
+//! maturin. Results are returned as the dicts and lists the MCP tools would send as JSON.
+//!
+//! ```python
+//! from docs_rs_mcp import DocsClient
+//!
+//! client = DocsClient()
+//! docs = client.struct_docs("tokio", "sync::Mutex")
+//! hits = client.search("serde", "Deserializer", limit=5)
+//! ```
+
+use std::future::Future;
+
+use docs_rs_mcp::{DocsClient, DocsRsMcpError};
+use pyo3::create_exception;
+use pyo3::exceptions::{PyException, PyRuntimeError};
+use pyo3::prelude::*;
+use serde::Serialize;
+use tokio::runtime::Runtime;
+
+create_exception!(
+    docs_rs_mcp,
+    DocsError,
+    PyException,
+    "A failed lookup; `args` holds the message and the error kind, e.g. `not_found`."
+);
+
+/// Looks up documentation of Rust crates. Every method blocks until the lookup is done,
+/// releasing the GIL meanwhile.
+#[pyclass(name = "DocsClient", frozen)]
+struct PyDocsClient {
+    client: DocsClient,
+    runtime: Runtime,
+}
+
+#[pymethods]
+impl PyDocsClient {
+    /// `registry` names a registry from the configuration to look every crate up in.
+    #[new]
+    #[pyo3(signature = (registry=None))]
+    fn new(registry: Option<String>) -> PyResult<Self> {
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .enable_all()
+            .build()
+            .map_err(|e| PyRuntimeError::new_err(format!("Failed to start a runtime: {}", e)))?;
+        let client = match registry {
+            Some(registry) => DocsClient::new().with_registry(registry),
+            None => DocsClient::new(),
+        };
+        Ok(Self { client, runtime })
+    }
+
+    #[pyo3(signature = (crate_name, struct_name, version=None))]
+    fn struct_docs(
+        &self,
+        py: Python<'_>,
+        crate_name: &str,
+        struct_name: &str,
+        version: Option<&str>,
+    ) -> PyResult<PyObject> {
+        self.run(
+            py,
+            self.client.struct_docs(crate_name, struct_name, version),
+        )
+    }
+
+    #[pyo3(signature = (crate_name, version=None))]
+    fn crate_items(
+        &self,
+        py: Python<'_>,
+        crate_name: &str,
+        version: Option<&str>,
+    ) -> PyResult<PyObject> {
+        self.run(py, self.client.crate_items(crate_name, version))
+    }
+
+    #[pyo3(signature = (crate_name, query, version=None, limit=10))]
+    fn search(
+        &self,
+        py: Python<'_>,
+        crate_name: &str,
+        query: &str,
+        version: Option<&str>,
+        limit: usize,
+    ) -> PyResult<PyObject> {
+        self.run(py, self.client.search(crate_name, query, version, limit))
+    }
+
+    #[pyo3(signature = (crate_name, path, version=None))]
+    fn module_items(
+        &self,
+        py: Python<'_>,
+        crate_name: &str,
+        path: &str,
+        version: Option<&str>,
+    ) -> PyResult<PyObject> {
+        self.run(py, self.client.module_items(crate_name, path, version))
+    }
+
+    #[pyo3(signature = (crate_name, version=None))]
+    fn crate_features(
+        &self,
+        py: Python<'_>,
+        crate_name: &str,
+        version: Option<&str>,
+    ) -> PyResult<PyObject> {
+        self.run(py, self.client.crate_features(crate_name, version))
+    }
+
+    #[pyo3(signature = (crate_name, version=None))]
+    fn crate_readme(
+        &self,
+        py: Python<'_>,
+        crate_name: &str,
+        version: Option<&str>,
+    ) -> PyResult<PyObject> {
+        self.run(py, self.client.crate_readme(crate_name, version))
+    }
+
+    fn crate_info(&self, py: Python<'_>, crate_name: &str) -> PyResult<PyObject> {
+        self.run(py, self.client.crate_info(crate_name))
+    }
+}
+
+impl PyDocsClient {
+    /// Runs a lookup with the GIL released and converts its result to Python objects.
+    fn run<T: Serialize>(
+        &self,
+        py: Python<'_>,
+        lookup: impl Future<Output = Result<T, DocsRsMcpError>> + Send,
+    ) -> PyResult<PyObject> {
+        let json = py
+            .allow_threads(|| {
+                self.runtime.block_on(lookup).and_then(|value| {
+                    serde_json::to_string(&value).map_err(|e| DocsRsMcpError::Other(e.to_string()))
+                })
+            })
+            .map_err(|e| DocsError::new_err((e.to_string(), e.kind())))?;
+        Ok(py.import("json")?.call_method1("loads", (json,))?.unbind())
+    }
+}
+
+#[pymodule]
+#[pyo3(name = "docs_rs_mcp")]
+fn docs_rs_mcp_py(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyDocsClient>()?;
+    m.add("DocsError", m.py().get_type::<DocsError>())?;
+    Ok(())
+}
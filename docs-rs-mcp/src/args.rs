@@ -0,0 +1,122 @@
+//! The command line: without a command the binary serves MCP, with one it runs a single task.
+//! Config flags are accepted before and after the command and take precedence over the config
+//! file and environment.
+//!
+//! ```text
+//! docs-rs-mcp [--offline] [--config path] ...
+//! docs-rs-mcp items --crate tokio --pretty --offline
+//! docs-rs-mcp check --version
+//! ```
+
+use std::path::PathBuf;
+
+use clap::{Parser, Subcommand};
+use clap_complete::Shell;
+
+use crate::cli::Lookup;
+use crate::config::ConfigOverrides;
+use crate::export::ExportOptions;
+use crate::index::IndexOptions;
+
+/// The parsed command line
+#[derive(Debug, Parser)]
+#[command(
+    name = "docs-rs-mcp",
+    version,
+    about,
+    long_about = "Without a command, serves documentation lookups for Rust crates on docs.rs and \
+        crates.io as an MCP server over stdio. The commands run a single task instead.",
+    propagate_version = true
+)]
+pub struct Cli {
+    #[command(flatten, next_help_heading = "Config options")]
+    pub overrides: ConfigOverrides,
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+/// One-off commands run instead of the MCP server
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    #[command(flatten)]
+    Lookup(Lookup),
+    /// Renders the documentation of a crate to Markdown files
+    #[command(disable_version_flag = true)]
+    Export(ExportOptions),
+    /// Fetches the item listings of crates into the cache ahead of time
+    Index(IndexOptions),
+    /// Runs the tool calls of a recorded session again against its recorded pages
+    Replay {
+        /// Session file written by --record
+        #[arg(value_name = "session.json")]
+        session: PathBuf,
+    },
+    /// Prints where the config file, cache and index are kept
+    Paths,
+    /// Checks that docs.rs, crates.io and the configured registries answer, and prints the
+    /// configuration in effect
+    Check,
+    /// Prints the completion script for a shell
+    Completions {
+        #[arg(value_enum)]
+        shell: Shell,
+    },
+    /// Prints the man page
+    Man {
+        /// Directory to write the man page and one per command to, instead of printing it
+        #[arg(long, value_name = "dir")]
+        out: Option<PathBuf>,
+    },
+}
+
+impl Cli {
+    /// Parses `args`, the program name first, as [`Parser::try_parse_from`] does, but with
+    /// errors as [`anyhow::Error`] for tests.
+    #[cfg(test)]
+    pub(crate) fn parse_args<'a>(args: impl IntoIterator<Item = &'a str>) -> anyhow::Result<Self> {
+        Ok(Self::try_parse_from(
+            std::iter::once("docs-rs-mcp").chain(args),
+        )?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Result;
+    use clap::error::ErrorKind;
+    use clap::CommandFactory;
+
+    #[test]
+    fn test_definitions() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn test_version() {
+        for args in [
+            vec!["--version"],
+            vec!["--offline", "--version"],
+            vec!["check", "--version"],
+            vec!["paths", "--offline", "--version"],
+        ] {
+            let error = Cli::parse_args(args.clone())
+                .unwrap_err()
+                .downcast::<clap::Error>()
+                .expect("Parsing fails with a clap error");
+            assert_eq!(error.kind(), ErrorKind::DisplayVersion, "{:?}", args);
+        }
+    }
+
+    #[test]
+    fn test_config_flags_anywhere() -> Result<()> {
+        let before = Cli::parse_args(["--offline", "check"])?;
+        let after = Cli::parse_args(["check", "--offline"])?;
+        assert_eq!(before.overrides, after.overrides);
+        assert!(matches!(after.command, Some(Command::Check)));
+        assert!(Cli::parse_args(["--offline", "--no-cache"]).is_err());
+        assert!(Cli::parse_args(["--verbose"]).is_err());
+        assert!(Cli::parse_args(["--log-level"]).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,475 @@
+//! Indexing of crates too large to list in one pass, such as `windows` or the `aws-sdk-*`
+//! crates, whose `all.html` and rustdoc JSON take minutes to fetch and parse.
+//!
+//! When crates.io reports a `.crate` file larger than `index.large_crate_bytes`, `crate_items`
+//! reads the crate's module pages instead, a chunk at a time on a thread of its own, and
+//! answers with the items found so far. Progress is saved after every chunk, in
+//! `<cache dir>/index/<registry>/<crate>/<version>.progress.json`, so indexing picks up where
+//! it stopped after a failed request or a restart. Once every module is read the listing is
+//! stored in the [index](crate::index) like any other.
+
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard, OnceLock};
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info, warn};
+
+use crate::error::DocsRsMcpError;
+use crate::tools::crate_items::{self, CrateItems, IndexingProgress};
+use crate::tools::get_struct_docs::{HtmlFetcher, HttpHtmlFetcher};
+use crate::tools::module_items::{self, ModuleItems};
+use crate::tools::{crates_io, docs_root, runtime};
+use crate::{cache_key, config, index, paths};
+
+/// Section of module pages listing items re-exported from elsewhere, indexed where they live
+const REEXPORTS: &str = "Re-exports";
+
+/// How far indexing of a crate has got
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Progress {
+    /// The parsers that read the modules so far, see [`cache_key::parser_revision`]
+    #[serde(default)]
+    parser: String,
+    registry: String,
+    crate_name: String,
+    version: String,
+    root_url: String,
+    /// Modules left to read, e.g. `Win32::Foundation`; the crate root is ``
+    pending: VecDeque<String>,
+    /// Modules read or queued, so modules linked from several places are read once
+    seen: BTreeSet<String>,
+    /// Pages of the items found, relative to the documentation root, e.g.
+    /// `Win32/Foundation/struct.HANDLE.html`
+    pages: BTreeSet<String>,
+}
+
+impl Progress {
+    fn new(registry: &str, crate_name: &str, version: &str, root_url: &str) -> Self {
+        Self {
+            parser: cache_key::parser_revision(),
+            registry: registry.to_string(),
+            crate_name: crate_name.to_string(),
+            version: version.to_string(),
+            root_url: root_url.to_string(),
+            pending: VecDeque::from([String::new()]),
+            seen: BTreeSet::from([String::new()]),
+            pages: BTreeSet::new(),
+        }
+    }
+
+    fn is_done(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    /// Records the items of a module page and queues the submodules it links to.
+    fn add_module(&mut self, module: &ModuleItems) {
+        let links = module
+            .sections()
+            .iter()
+            .filter(|section| section.title() != REEXPORTS)
+            .flat_map(|section| section.items())
+            .filter_map(|item| item.doc_link()?.strip_prefix(self.root_url.as_str()));
+        for page in links {
+            let page = page.split('#').next().unwrap_or(page);
+            match page.strip_suffix("/index.html") {
+                Some(submodule) => {
+                    let submodule = submodule.replace('/', "::");
+                    if self.seen.insert(submodule.clone()) {
+                        self.pending.push_back(submodule);
+                    }
+                }
+                None => {
+                    self.pages.insert(page.to_string());
+                }
+            }
+        }
+    }
+
+    /// Reads up to `modules` pending modules. Modules without items or a page are skipped; a
+    /// page that can't be fetched otherwise stays pending and ends the step.
+    async fn step(&mut self, modules: usize) -> Result<()> {
+        let fetcher = HttpHtmlFetcher::new();
+        for _ in 0..modules {
+            let Some(path) = self.pending.front().cloned() else {
+                break;
+            };
+            let url = module_items::module_url(&self.root_url, &path);
+            let module = match fetcher.fetch_html(&url).await {
+                Ok(html) => module_items::parse_module_page(
+                    &self.crate_name,
+                    &self.version,
+                    &path,
+                    &url,
+                    &html,
+                ),
+                Err(e) if matches!(DocsRsMcpError::classify(&e), DocsRsMcpError::NotFound(_)) => {
+                    Err(e)
+                }
+                Err(e) => return Err(e),
+            };
+            match module {
+                Ok(module) => self.add_module(&module),
+                Err(e) => debug!("Skipping module {}: {:#}", url, e),
+            }
+            self.pending.pop_front();
+        }
+        Ok(())
+    }
+
+    /// The items found so far, with how far indexing has got while it isn't done
+    fn listing(&self) -> CrateItems {
+        let modules_read = self.seen.len() - self.pending.len();
+        crate_items::from_pages(
+            &self.crate_name,
+            &self.version,
+            &self.root_url,
+            &self.pages,
+            (!self.is_done()).then(|| IndexingProgress::new(modules_read, self.pending.len())),
+        )
+    }
+
+    fn load(path: &Path) -> Option<Self> {
+        let contents = fs::read_to_string(path).ok()?;
+        match serde_json::from_str::<Self>(&contents) {
+            Ok(progress) if progress.parser == cache_key::parser_revision() => Some(progress),
+            Ok(_) => {
+                debug!("Starting over {}, read by other parsers", path.display());
+                None
+            }
+            Err(e) => {
+                warn!(
+                    "Ignoring corrupt indexing progress {}: {}",
+                    path.display(),
+                    e
+                );
+                None
+            }
+        }
+    }
+
+    /// Writes the progress to a temporary file first, so a crash leaves the previous one intact.
+    fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .context(format!("Failed to create directory: {}", parent.display()))?;
+        }
+        let partial = path.with_extension("tmp");
+        fs::write(&partial, serde_json::to_string(self)?).context(format!(
+            "Failed to write indexing progress: {}",
+            partial.display()
+        ))?;
+        fs::rename(&partial, path).context(format!(
+            "Failed to write indexing progress: {}",
+            path.display()
+        ))
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    match mutex.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    }
+}
+
+/// Progress files being worked on, so each crate is crawled by one thread at a time
+fn running() -> &'static Mutex<HashSet<PathBuf>> {
+    static RUNNING: OnceLock<Mutex<HashSet<PathBuf>>> = OnceLock::new();
+    RUNNING.get_or_init(|| Mutex::new(HashSet::new()))
+}
+
+/// Marks a progress file as worked on until dropped
+struct Running(PathBuf);
+
+impl Running {
+    fn claim(path: &Path) -> Option<Self> {
+        lock(running())
+            .insert(path.to_path_buf())
+            .then(|| Self(path.to_path_buf()))
+    }
+}
+
+impl Drop for Running {
+    fn drop(&mut self) {
+        lock(running()).remove(&self.0);
+    }
+}
+
+/// Reads the remaining modules of the crate whose progress is at `path`, saving after each
+/// chunk, then stores the complete listing in the index.
+async fn crawl(path: &Path) -> Result<CrateItems> {
+    let mut progress = Progress::load(path)
+        .ok_or_else(|| anyhow!("No indexing progress at {}", path.display()))?;
+    let chunk = config::current().index().chunk_pages();
+    while !progress.is_done() {
+        let stepped = progress.step(chunk).await;
+        progress.save(path)?;
+        stepped.context(format!(
+            "Indexing {} {} stopped",
+            progress.crate_name, progress.version
+        ))?;
+    }
+    let items = progress.listing();
+    index::store(&items)?;
+    fs::remove_file(path).context(format!(
+        "Failed to remove indexing progress: {}",
+        path.display()
+    ))?;
+    info!(
+        "Indexed {} {}: {} items",
+        progress.crate_name,
+        progress.version,
+        items.counts().total()
+    );
+    Ok(items)
+}
+
+/// Crawls on a thread of its own with `config`, unless the crate is being crawled already.
+fn spawn(path: PathBuf, config: config::Config) {
+    let Some(running) = Running::claim(&path) else {
+        return;
+    };
+    std::thread::spawn(move || {
+        let _running = running;
+        config::scoped(config, || {
+            if let Err(e) = runtime::block_on(crawl(&path)) {
+                warn!("{:#}; indexing resumes on the next lookup", e);
+            }
+        })
+    });
+}
+
+/// The release `version` stands for when it is too large to list in one pass. Answers are
+/// kept for the cache TTL, so crates.io is asked once per crate rather than on every lookup.
+async fn large_release(crate_name: &str, version: &str, threshold: u64) -> Option<String> {
+    type Releases = HashMap<(String, String, String), (Instant, Option<String>)>;
+    static RELEASES: OnceLock<Mutex<Releases>> = OnceLock::new();
+    let releases = RELEASES.get_or_init(|| Mutex::new(HashMap::new()));
+
+    let config = config::current();
+    let key = (
+        config.active_registry().to_string(),
+        crate_name.to_string(),
+        version.to_string(),
+    );
+    if let Some((at, release)) = lock(releases).get(&key) {
+        if at.elapsed() < config.cache().ttl() {
+            return release.clone();
+        }
+    }
+    let release = match crates_io::release_size(crate_name, version).await {
+        Ok((release, size)) => size.is_some_and(|size| size > threshold).then_some(release),
+        Err(e) => {
+            debug!("No release size for {} {}: {:#}", crate_name, version, e);
+            return None;
+        }
+    };
+    lock(releases).insert(key, (Instant::now(), release.clone()));
+    release
+}
+
+/// The listing of a crate too large to list in one pass: complete when indexing is done,
+/// otherwise the items found so far while a background thread reads the rest. `None` for
+/// crates listed the usual way, and when there is no cache to keep progress in.
+pub(crate) async fn listing(crate_name: &str, version: &str) -> Option<CrateItems> {
+    let config = config::current();
+    let threshold = config.index().large_crate_bytes()?;
+    if !config.cache().enabled() {
+        return None;
+    }
+    let cache_dir = config.cache().resolved_dir()?;
+    let version = large_release(crate_name, version, threshold).await?;
+    if let Some(items) = index::load(crate_name, &version) {
+        return Some(items);
+    }
+
+    let path =
+        index::progress_path(&cache_dir, config.active_registry(), crate_name, &version).ok()?;
+    let progress = match Progress::load(&path) {
+        Some(progress) => progress,
+        None => {
+            let root_url = docs_root::crate_root(crate_name, &version).await;
+            let mut progress =
+                Progress::new(config.active_registry(), crate_name, &version, &root_url);
+            // Reading the first chunk inline answers with the items of the crate root at least;
+            // a crate whose root can't be read is left to the usual listing and its errors
+            if let Err(e) = progress.step(config.index().chunk_pages()).await {
+                debug!("Not indexing {} {}: {:#}", crate_name, version, e);
+                return None;
+            }
+            if let Err(e) = progress.save(&path) {
+                warn!("{:#}", e);
+                return None;
+            }
+            progress
+        }
+    };
+    debug!(
+        "Indexing {} {} in the background, {} modules to go",
+        crate_name,
+        version,
+        progress.pending.len()
+    );
+    spawn(path, config.as_ref().clone());
+    Some(progress.listing())
+}
+
+/// Completes the indexing of a partial listing, waiting for the thread crawling it if there is
+/// one, and returns the complete listing.
+pub(crate) fn finish_blocking(items: &CrateItems) -> Result<CrateItems> {
+    let config = config::current();
+    let cache_dir = config
+        .cache()
+        .resolved_dir()
+        .ok_or_else(|| anyhow!("No cache directory is configured"))?;
+    let path = index::progress_path(
+        &cache_dir,
+        config.active_registry(),
+        items.crate_name(),
+        items.version(),
+    )?;
+    loop {
+        if let Some(items) = index::load(items.crate_name(), items.version()) {
+            return Ok(items);
+        }
+        match Running::claim(&path) {
+            Some(_running) if path.exists() => return runtime::block_on(crawl(&path)),
+            // The thread crawling it finished in between
+            Some(_running) => {
+                return index::load(items.crate_name(), items.version()).ok_or_else(|| {
+                    anyhow!(
+                        "Indexing {} {} didn't complete",
+                        items.crate_name(),
+                        items.version()
+                    )
+                })
+            }
+            None => std::thread::sleep(Duration::from_secs(1)),
+        }
+    }
+}
+
+/// Resumes indexing every crate whose progress was saved by an earlier run.
+pub fn resume() {
+    let config = config::current();
+    let Some(index_dir) = config
+        .cache()
+        .resolved_dir()
+        .filter(|_| config.cache().enabled())
+        .map(|dir| dir.join(paths::INDEX_DIR))
+    else {
+        return;
+    };
+    // <index>/<registry>/<crate>/<version>.progress.json
+    let files = fs::read_dir(index_dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .flat_map(|registry| {
+            fs::read_dir(registry.path())
+                .into_iter()
+                .flatten()
+                .flatten()
+        })
+        .flat_map(|krate| fs::read_dir(krate.path()).into_iter().flatten().flatten())
+        .map(|entry| entry.path())
+        .filter(|path| path.to_string_lossy().ends_with(".progress.json"));
+    for path in files {
+        let Some(progress) = Progress::load(&path) else {
+            continue;
+        };
+        match config.for_registry(&progress.registry) {
+            Ok(registry_config) => {
+                info!(
+                    "Resuming indexing of {} {}",
+                    progress.crate_name, progress.version
+                );
+                spawn(path, registry_config);
+            }
+            Err(e) => warn!("Not resuming {}: {:#}", path.display(), e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_add_module() -> Result<()> {
+        let url = "https://docs.rs/demo/0.1.0/demo/net/index.html";
+        let html = fs::read_to_string("test-data/module_items/module-index.html")?;
+        let module = module_items::parse_module_page("demo", "0.1.0", "net", url, &html)?;
+
+        let mut progress = Progress::new(
+            "crates-io",
+            "demo",
+            "0.1.0",
+            "https://docs.rs/demo/0.1.0/demo/",
+        );
+        progress.pending.pop_front();
+        progress.seen.insert("net".to_string());
+        progress.add_module(&module);
+        progress.add_module(&module);
+
+        assert_eq!(
+            Vec::from(progress.pending.clone()),
+            ["net::tcp", "net::udp"],
+            "Submodules are queued once"
+        );
+        assert_eq!(
+            progress.pages.iter().collect::<Vec<_>>(),
+            [
+                "net/fn.lookup_host.html",
+                "net/struct.TcpListener.html",
+                "net/struct.TcpStream.html",
+                "net/struct.UnixShim.html",
+            ],
+            "Re-exports are indexed where they live"
+        );
+
+        let listing = progress.listing();
+        assert_eq!(
+            listing.indexing(),
+            Some(&IndexingProgress::new(2, 2)),
+            "The root and net are read"
+        );
+        let (section, stream) = listing.find("net::TcpStream")?;
+        assert_eq!(section, "Structs");
+        assert_eq!(
+            stream.doc_link(),
+            "https://docs.rs/demo/0.1.0/demo/net/struct.TcpStream.html"
+        );
+
+        progress.pending.clear();
+        assert_eq!(progress.listing().indexing(), None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_save_and_load() -> Result<()> {
+        let path = std::env::temp_dir().join(format!(
+            "docs-rs-mcp-progress-test-{}/0.59.0.progress.json",
+            std::process::id()
+        ));
+        let mut progress = Progress::new(
+            "crates-io",
+            "windows",
+            "0.59.0",
+            "https://docs.rs/windows/0.59.0/windows/",
+        );
+        progress
+            .pages
+            .insert("Win32/Foundation/struct.HANDLE.html".to_string());
+        progress.save(&path)?;
+        let loaded = Progress::load(&path);
+        fs::remove_dir_all(path.parent().unwrap())?;
+        assert_eq!(loaded, Some(progress));
+        Ok(())
+    }
+}
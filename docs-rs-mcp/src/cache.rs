@@ -0,0 +1,321 @@
+//! Disk cache of fetched documentation pages, so lookups repeated across sessions don't hit
+//! docs.rs again while the cached copy is younger than the cache TTL. Offline, cached copies
+//! are served however old they are.
+//!
+//! Pages are stored under `<cache dir>/pages/<host>/<hash of the URL>`, with the URL on the
+//! first line to tell hash collisions apart. Data parsed out of pages is stored under
+//! `<cache dir>/derived/<digest of its key>.json`, addressed by what it was derived from and
+//! by which parsers (see [`DerivedKey`]), so it stays valid for as long as it is kept.
+
+use std::fs;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use anyhow::{anyhow, Context, Result};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, warn};
+use url::Url;
+
+use crate::cache_key::{fnv1a, mentions_crate, DerivedKey};
+use crate::{config, paths, session, telemetry};
+
+/// The cached body of `url`, unless caching is disabled or the entry is missing or stale.
+pub(crate) fn get(url: &str) -> Option<String> {
+    if !config::current().cache().enabled() {
+        return None;
+    }
+    let _span = tracing::info_span!("cache_get", url).entered();
+    let body = lookup(url);
+    telemetry::record_cache_lookup("pages", body.is_some());
+    if let Some(body) = &body {
+        session::record_fetch(session::Fetch::cached(url, body));
+    }
+    body
+}
+
+fn lookup(url: &str) -> Option<String> {
+    let config = config::current();
+    let path = entry_path(url)?;
+
+    let modified = fs::metadata(&path).and_then(|m| m.modified()).ok()?;
+    let age = SystemTime::now()
+        .duration_since(modified)
+        .unwrap_or_default();
+    if config.is_stale(age) {
+        debug!("Cached copy of {} is stale", url);
+        return None;
+    }
+
+    let contents = fs::read_to_string(&path).ok()?;
+    match contents.split_once('\n') {
+        Some((cached_url, body)) if cached_url == url => {
+            debug!("Serving {} from the cache", url);
+            Some(body.to_string())
+        }
+        _ => None,
+    }
+}
+
+/// Stores the body of `url`, logging rather than failing when it can't be written.
+pub(crate) fn put(url: &str, body: &str) {
+    if !config::current().cache().enabled() {
+        return;
+    }
+    if let Err(e) = write(url, body) {
+        warn!("Failed to cache {}: {:#}", url, e);
+    }
+}
+
+fn write(url: &str, body: &str) -> Result<()> {
+    let path = entry_path(url).ok_or_else(|| anyhow!("No cache directory is configured"))?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .context(format!("Failed to create directory: {}", parent.display()))?;
+    }
+    fs::write(&path, format!("{}\n{}", url, body))
+        .context(format!("Failed to write cache entry: {}", path.display()))
+}
+
+fn entry_path(url: &str) -> Option<PathBuf> {
+    let cache_dir = config::current().cache().resolved_dir()?;
+    let host = Url::parse(url)
+        .ok()
+        .and_then(|url| url.host_str().map(str::to_string))
+        .unwrap_or_else(|| "other".to_string());
+    Some(
+        cache_dir
+            .join(paths::PAGES_DIR)
+            .join(host)
+            .join(format!("{:016x}", fnv1a(url.as_bytes()))),
+    )
+}
+
+/// Derived data as stored, with its whole key to tell digest collisions apart
+#[derive(Serialize, Deserialize)]
+struct DerivedEntry<T> {
+    key: DerivedKey,
+    value: T,
+}
+
+/// The data stored for `key`, unless caching is disabled or none is stored.
+pub(crate) fn get_derived<T: DeserializeOwned>(key: &DerivedKey) -> Option<T> {
+    if !config::current().cache().enabled() {
+        return None;
+    }
+    let path = derived_path(key)?;
+    let value = fs::read_to_string(&path)
+        .ok()
+        .and_then(|contents| serde_json::from_str::<DerivedEntry<T>>(&contents).ok())
+        .filter(|entry| entry.key == *key)
+        .map(|entry| entry.value);
+    telemetry::record_cache_lookup("derived", value.is_some());
+    if value.is_some() {
+        debug!("Reusing what was read from {}", path.display());
+    }
+    value
+}
+
+/// Stores the data derived for `key`, logging rather than failing when it can't be written.
+pub(crate) fn put_derived<T: Serialize>(key: &DerivedKey, value: &T) {
+    if !config::current().cache().enabled() {
+        return;
+    }
+    let written = derived_path(key)
+        .ok_or_else(|| anyhow!("No cache directory is configured"))
+        .and_then(|path| {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)
+                    .context(format!("Failed to create directory: {}", parent.display()))?;
+            }
+            let json = serde_json::to_string(&DerivedEntry {
+                key: key.clone(),
+                value,
+            })?;
+            fs::write(&path, json)
+                .context(format!("Failed to write cache entry: {}", path.display()))
+        });
+    if let Err(e) = written {
+        warn!("Failed to cache data derived from {:?}: {:#}", key, e);
+    }
+}
+
+fn derived_path(key: &DerivedKey) -> Option<PathBuf> {
+    let cache_dir = config::current().cache().resolved_dir()?;
+    Some(
+        cache_dir
+            .join(paths::DERIVED_DIR)
+            .join(format!("{}.json", key.digest())),
+    )
+}
+
+/// Cache entries removed by [`purge`]
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+pub(crate) struct Purged {
+    pages: usize,
+    derived: usize,
+    /// Crates whose item listings were removed from the search index
+    indexed_crates: usize,
+}
+
+/// Removes what is cached on disk for `crate_name`, or everything when `None`.
+pub(crate) fn purge(crate_name: Option<&str>) -> Result<Purged> {
+    let mut purged = Purged::default();
+    let Some(cache_dir) = config::current().cache().resolved_dir() else {
+        return Ok(purged);
+    };
+    let matches = |url: &str| crate_name.is_none_or(|name| mentions_crate(url, name));
+
+    for host in children(&cache_dir.join(paths::PAGES_DIR))? {
+        for path in children(&host)? {
+            if first_line(&path).is_some_and(|url| matches(&url)) {
+                remove(&path)?;
+                purged.pages += 1;
+            }
+        }
+    }
+    for path in children(&cache_dir.join(paths::DERIVED_DIR))? {
+        let url = fs::read_to_string(&path).ok().and_then(|contents| {
+            serde_json::from_str::<DerivedEntry<serde::de::IgnoredAny>>(&contents)
+                .ok()
+                .map(|entry| entry.key.url().to_string())
+        });
+        // Unreadable entries are of no use to anyone, so they go whatever crate they were for
+        if url.is_none_or(|url| matches(&url)) {
+            remove(&path)?;
+            purged.derived += 1;
+        }
+    }
+    for registry in children(&cache_dir.join(paths::INDEX_DIR))? {
+        for listing in children(&registry)? {
+            let name = listing.file_name().and_then(|name| name.to_str());
+            let listed = match (crate_name, name) {
+                (None, _) => true,
+                (Some(wanted), Some(name)) => wanted
+                    .replace('-', "_")
+                    .eq_ignore_ascii_case(&name.replace('-', "_")),
+                (Some(_), None) => false,
+            };
+            if listed {
+                fs::remove_dir_all(&listing)
+                    .context(format!("Failed to remove {}", listing.display()))?;
+                purged.indexed_crates += 1;
+            }
+        }
+    }
+    Ok(purged)
+}
+
+/// Entries of directory `dir`, none when it doesn't exist
+fn children(dir: &Path) -> Result<Vec<PathBuf>> {
+    match fs::read_dir(dir) {
+        Ok(entries) => Ok(entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .collect()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(e).context(format!("Failed to read directory: {}", dir.display())),
+    }
+}
+
+/// The first line of the file at `path`, the URL of a cached page
+fn first_line(path: &Path) -> Option<String> {
+    let mut line = String::new();
+    BufReader::new(fs::File::open(path).ok()?)
+        .read_line(&mut line)
+        .ok()?;
+    Some(line.trim_end().to_string())
+}
+
+fn remove(path: &Path) -> Result<()> {
+    fs::remove_file(path).context(format!("Failed to remove cache entry: {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_round_trip() -> Result<()> {
+        let dir = std::env::temp_dir().join(format!("docs-rs-mcp-cache-{}", std::process::id()));
+        let config = Config::from_toml(&format!("[cache]\ndir = {:?}\n", dir))?;
+        let url = "https://docs.rs/demo/0.4.2/demo/struct.Frame.html";
+
+        config::scoped(config.clone(), || {
+            assert_eq!(get(url), None);
+            put(url, "<html>Frame\nsecond line</html>");
+            assert_eq!(get(url).as_deref(), Some("<html>Frame\nsecond line</html>"));
+            assert_eq!(get("https://docs.rs/demo/0.4.2/demo/"), None);
+        });
+
+        let disabled = Config::from_toml(&format!("[cache]\nenabled = false\ndir = {:?}\n", dir))?;
+        config::scoped(disabled, || assert_eq!(get(url), None));
+
+        let expired = Config::from_toml(&format!("[cache]\nttl_secs = 0\ndir = {:?}\n", dir))?;
+        std::thread::sleep(std::time::Duration::from_millis(10));
+        config::scoped(expired, || assert_eq!(get(url), None));
+
+        fs::remove_dir_all(&dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_derived() -> Result<()> {
+        let dir = std::env::temp_dir().join(format!("docs-rs-mcp-derived-{}", std::process::id()));
+        let config = Config::from_toml(&format!("[cache]\ndir = {:?}\n", dir))?;
+        let url = "https://docs.rs/demo/0.4.2/demo/all.html";
+        let key = DerivedKey::new(url, b"<html>", None);
+
+        config::scoped(config, || {
+            assert_eq!(get_derived::<Vec<String>>(&key), None);
+            put_derived(&key, &vec!["codec::Frame".to_string()]);
+            assert_eq!(
+                get_derived::<Vec<String>>(&key),
+                Some(vec!["codec::Frame".to_string()])
+            );
+            assert_eq!(
+                get_derived::<Vec<String>>(&DerivedKey::new(url, b"<html>!", None)),
+                None,
+                "Changed pages are read again"
+            );
+        });
+
+        fs::remove_dir_all(&dir)?;
+        Ok(())
+    }
+    #[test]
+    fn test_purge() -> Result<()> {
+        let dir = std::env::temp_dir().join(format!("docs-rs-mcp-purge-{}", std::process::id()));
+        let config = Config::from_toml(&format!("[cache]\ndir = {:?}\n", dir))?;
+        let tokio_page = "https://docs.rs/tokio/1.43.0/tokio/all.html";
+        let serde_page = "https://docs.rs/serde/1.0.217/serde/all.html";
+        let listing = dir.join(paths::INDEX_DIR).join("crates-io").join("tokio");
+        fs::create_dir_all(&listing)?;
+        fs::write(listing.join("1.43.0.json"), "[]")?;
+
+        config::scoped(config, || -> Result<()> {
+            put(tokio_page, "<html>tokio</html>");
+            put(serde_page, "<html>serde</html>");
+            put_derived(&DerivedKey::new(tokio_page, b"<html>", None), &1);
+
+            assert_eq!(
+                purge(Some("tokio"))?,
+                Purged {
+                    pages: 1,
+                    derived: 1,
+                    indexed_crates: 1,
+                }
+            );
+            assert_eq!(get(tokio_page), None);
+            assert!(get(serde_page).is_some(), "Other crates are kept");
+            assert_eq!(purge(None)?.pages, 1);
+            assert_eq!(get(serde_page), None);
+            Ok(())
+        })?;
+
+        fs::remove_dir_all(&dir)?;
+        Ok(())
+    }
+}
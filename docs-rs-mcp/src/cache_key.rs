@@ -0,0 +1,181 @@
+//! Keys of cached data derived from documentation, such as the item listings of the index.
+//!
+//! Derived data is keyed by the URL it was read from, a hash of the content read, the revision
+//! of the parsers that read it and the target the documentation was built for. A server whose
+//! parsers changed, or asking for another target, never gets structures read by other logic,
+//! and the same content read by the same parsers is never read twice.
+
+use semver::Version;
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+/// Revision of the parsers, to bump whenever a change makes them read something else out of
+/// the same page. Released servers are told apart by their version as well.
+pub(crate) const PARSER_REVISION: u32 = 1;
+
+/// The parsers of this server, e.g. `0.1.0+1`
+pub(crate) fn parser_revision() -> String {
+    format!("{}+{}", env!("CARGO_PKG_VERSION"), PARSER_REVISION)
+}
+
+/// What a piece of derived data was derived from, and by which parsers
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct DerivedKey {
+    /// The page or file read, e.g. `https://docs.rs/tokio/1.43.0/tokio/all.html`
+    url: String,
+    /// Hash of the content read, e.g. `fnv1a:0123456789abcdef`
+    content: String,
+    /// See [`parser_revision`]
+    parser: String,
+    /// Target triple the documentation was built for; `None` for docs.rs' default target
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    target: Option<String>,
+}
+
+impl DerivedKey {
+    /// The key of data the current parsers derive from `content` read at `url`.
+    pub(crate) fn new(url: &str, content: &[u8], target: Option<&str>) -> Self {
+        Self::with_hash(url, fnv1a(content), target)
+    }
+
+    /// Like [`Self::new`], with the content hashed by [`fnv1a`] already.
+    pub(crate) fn with_hash(url: &str, content_hash: u64, target: Option<&str>) -> Self {
+        Self {
+            url: url.to_string(),
+            content: format!("fnv1a:{:016x}", content_hash),
+            parser: parser_revision(),
+            target: target.map(str::to_string),
+        }
+    }
+
+    /// The page or file the data was read from
+    #[cfg(feature = "native")]
+    pub(crate) fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Whether the current parsers derived the data, for `target`
+    #[cfg(feature = "native")]
+    pub(crate) fn is_current(&self, target: Option<&str>) -> bool {
+        self.parser == parser_revision() && self.target.as_deref() == target
+    }
+
+    /// A name for the data in content-addressed storage
+    #[cfg(feature = "native")]
+    pub(crate) fn digest(&self) -> String {
+        let key = [
+            self.url.as_str(),
+            &self.content,
+            &self.parser,
+            self.target.as_deref().unwrap_or_default(),
+        ]
+        .join("\n");
+        format!("{:016x}", fnv1a(key.as_bytes()))
+    }
+}
+
+/// The target a documentation root is built for, from the path docs.rs serves it under:
+/// `/{crate}/{version}/{target}/{crate}/` for other targets than the default one,
+/// `/{crate}/{version}/{crate}/` for that.
+pub(crate) fn target_of(root_url: &str) -> Option<String> {
+    let url = Url::parse(root_url).ok()?;
+    let segments: Vec<&str> = url.path_segments()?.filter(|s| !s.is_empty()).collect();
+    let [.., version, target, _] = segments.as_slice() else {
+        return None;
+    };
+    let is_version = |segment: &str| segment == "latest" || Version::parse(segment).is_ok();
+    (is_version(version) && !is_version(target) && target.contains('-')).then(|| target.to_string())
+}
+
+/// Whether `url` belongs to `crate_name`: one of its path segments is the crate name, written
+/// with dashes or underscores, as in docs.rs pages, registry API paths and sparse index files.
+#[cfg(feature = "native")]
+pub(crate) fn mentions_crate(url: &str, crate_name: &str) -> bool {
+    let normalize = |name: &str| name.to_ascii_lowercase().replace('-', "_");
+    let crate_name = normalize(crate_name);
+    Url::parse(url).ok().is_some_and(|url| {
+        url.path_segments()
+            .is_some_and(|mut segments| segments.any(|segment| normalize(segment) == crate_name))
+    })
+}
+
+/// 64-bit FNV-1a, a hash that stays the same across Rust releases, unlike `DefaultHasher`
+pub(crate) fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_fnv1a() {
+        assert_eq!(fnv1a(b""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(fnv1a(b"a"), 0xaf63_dc4c_8601_ec8c);
+    }
+
+    #[test]
+    fn test_mentions_crate() {
+        assert!(mentions_crate(
+            "https://docs.rs/serde_json/1.0.0/serde_json/",
+            "serde-json"
+        ));
+        assert!(mentions_crate(
+            "https://crates.io/api/v1/crates/serde/1.0.217/readme",
+            "serde"
+        ));
+        assert!(mentions_crate(
+            "https://index.crates.io/se/rd/serde",
+            "serde"
+        ));
+        assert!(!mentions_crate(
+            "https://docs.rs/serde_json/1.0.0/serde_json/",
+            "serde"
+        ));
+        assert!(!mentions_crate("not a url", "serde"));
+    }
+
+    #[test]
+    fn test_target_of() {
+        assert_eq!(target_of("https://docs.rs/tokio/1.43.0/tokio/"), None);
+        assert_eq!(
+            target_of("https://docs.rs/windows/0.59.0/x86_64-pc-windows-msvc/windows/"),
+            Some("x86_64-pc-windows-msvc".to_string())
+        );
+        assert_eq!(
+            target_of("https://docs.rs/demo/latest/i686-unknown-linux-gnu/demo/"),
+            Some("i686-unknown-linux-gnu".to_string())
+        );
+        assert_eq!(
+            target_of("https://docs.rs/serde-json-core/0.6.0-rc.1/serde_json_core/"),
+            None,
+            "Pre-release versions aren't targets"
+        );
+        assert_eq!(target_of("file:///cache/local-docs/demo/doc/demo/"), None);
+    }
+
+    #[test]
+    fn test_is_current() {
+        let key = DerivedKey::new("https://docs.rs/demo/0.1.0/demo/all.html", b"<html>", None);
+        assert!(key.is_current(None));
+        assert!(!key.is_current(Some("x86_64-pc-windows-msvc")));
+
+        let older = DerivedKey {
+            parser: "0.0.1+1".to_string(),
+            ..key.clone()
+        };
+        assert!(
+            !older.is_current(None),
+            "Data read by older parsers is stale"
+        );
+        assert_ne!(older.digest(), key.digest());
+        assert_ne!(
+            DerivedKey::new("https://docs.rs/demo/0.1.0/demo/all.html", b"<html>!", None).digest(),
+            key.digest(),
+            "Other content gets another name"
+        );
+    }
+}
@@ -0,0 +1,251 @@
+//! Startup diagnostics: whether the configured documentation hosts, registry APIs and indexes
+//! answer, for telling a network or proxy problem from a bug before wiring up a client.
+//!
+//! ```text
+//! docs-rs-mcp check [--config path] [--docs-rs-url url] ...
+//! ```
+//!
+//! One request is sent to each: the docs.rs front page, a one-result crate search on the
+//! registry API (what `cargo search` asks for), and the sparse index's `config.json`.
+
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+
+use crate::config::Config;
+use crate::tools::http;
+
+/// A request sent to check that a host answers
+#[derive(Debug, Clone, PartialEq)]
+pub struct Probe {
+    /// Registry the host serves, `crates-io` for crates.io and docs.rs
+    registry: String,
+    /// What the host serves: `docs`, `api` or `index`
+    service: &'static str,
+    url: String,
+    outcome: Outcome,
+}
+
+/// How a [`Probe`] went
+#[derive(Debug, Clone, PartialEq)]
+pub enum Outcome {
+    /// The host answered with a success status
+    Reachable { status: u16, elapsed: Duration },
+    /// The request failed or was answered with an error status
+    Unreachable(String),
+    /// Not sent, as the server is offline
+    Skipped,
+}
+
+impl Probe {
+    pub fn registry(&self) -> &str {
+        &self.registry
+    }
+
+    pub fn service(&self) -> &str {
+        self.service
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    pub fn outcome(&self) -> &Outcome {
+        &self.outcome
+    }
+
+    pub fn is_reachable(&self) -> bool {
+        !matches!(self.outcome, Outcome::Unreachable(_))
+    }
+}
+
+/// Sends one request to each host `config` points at, one at a time.
+pub async fn run(config: &Config) -> Result<Vec<Probe>> {
+    let client = http::shared()?;
+    let mut probes = Vec::new();
+    for (registry, service, url) in targets(config)? {
+        let outcome = if config.offline() {
+            Outcome::Skipped
+        } else {
+            let start = Instant::now();
+            match client.get(&url).await {
+                Ok(response) if response.status().is_success() => Outcome::Reachable {
+                    status: response.status().as_u16(),
+                    elapsed: start.elapsed(),
+                },
+                Ok(response) => Outcome::Unreachable(format!("HTTP {}", response.status())),
+                Err(e) => Outcome::Unreachable(format!("{:#}", e)),
+            }
+        };
+        probes.push(Probe {
+            registry,
+            service,
+            url,
+            outcome,
+        });
+    }
+    Ok(probes)
+}
+
+/// The registry, service and URL of every request to send, crates.io's first
+fn targets(config: &Config) -> Result<Vec<(String, &'static str, String)>> {
+    let mut targets = Vec::new();
+    for name in config.registry_names() {
+        let sources = config.for_registry(name)?.sources().clone();
+        let base = |url: &str| url.trim_end_matches('/').to_string();
+        targets.push((
+            name.to_string(),
+            "docs",
+            format!("{}/", base(sources.docs_rs_url())),
+        ));
+        targets.push((
+            name.to_string(),
+            "api",
+            format!(
+                "{}/api/v1/crates?q=serde&per_page=1",
+                base(sources.crates_io_url())
+            ),
+        ));
+        if let Some(index) = sources.index_url() {
+            targets.push((
+                name.to_string(),
+                "index",
+                format!("{}/config.json", base(index)),
+            ));
+        }
+    }
+    Ok(targets)
+}
+
+/// The outcome of every probe, one per line.
+pub fn describe(probes: &[Probe]) -> String {
+    let width = |column: fn(&Probe) -> &str| {
+        probes
+            .iter()
+            .map(|probe| column(probe).len())
+            .max()
+            .unwrap_or(0)
+    };
+    let (registry_width, service_width) = (width(Probe::registry), width(Probe::service));
+    probes
+        .iter()
+        .map(|probe| {
+            let (verdict, detail) = match &probe.outcome {
+                Outcome::Reachable { status, elapsed } => {
+                    ("ok", format!("{} in {} ms", status, elapsed.as_millis()))
+                }
+                Outcome::Unreachable(error) => ("FAILED", error.clone()),
+                Outcome::Skipped => ("skipped", "offline".to_string()),
+            };
+            format!(
+                "{:7}  {:registry_width$}  {:service_width$}  {}  ({})\n",
+                verdict,
+                probe.registry,
+                probe.service,
+                probe.url,
+                detail,
+                registry_width = registry_width,
+                service_width = service_width
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_targets() -> Result<()> {
+        let config = Config::from_toml(
+            r#"
+            [sources]
+            docs_rs_url = "http://localhost:3000/"
+
+            [[registries]]
+            name = "acme"
+            api_url = "https://registry.acme.internal"
+            docs_url = "https://docs.acme.internal"
+            "#,
+        )?;
+        let urls: Vec<String> = targets(&config)?
+            .into_iter()
+            .map(|(registry, service, url)| format!("{} {} {}", registry, service, url))
+            .collect();
+        assert_eq!(
+            urls,
+            vec![
+                "crates-io docs http://localhost:3000/",
+                "crates-io api https://crates.io/api/v1/crates?q=serde&per_page=1",
+                "crates-io index https://index.crates.io/config.json",
+                "acme docs https://docs.acme.internal/",
+                "acme api https://registry.acme.internal/api/v1/crates?q=serde&per_page=1",
+            ]
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_run() -> Result<()> {
+        use wiremock::matchers::path;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(path("/"))
+            .respond_with(ResponseTemplate::new(200))
+            .mount(&server)
+            .await;
+        Mock::given(path("/api/v1/crates"))
+            .respond_with(ResponseTemplate::new(503))
+            .mount(&server)
+            .await;
+
+        let toml = format!(
+            "[sources]\ndocs_rs_url = \"{0}\"\ncrates_io_url = \"{0}\"\nindex_url = \"\"\n",
+            server.uri()
+        );
+        let config = Config::from_toml(&toml)?;
+        let probes = config::scoped_task(config.clone(), run(&config)).await?;
+        assert_eq!(probes.len(), 2, "Without an index, it isn't checked");
+        assert!(probes[0].is_reachable());
+        assert_eq!(
+            probes[1].outcome(),
+            &Outcome::Unreachable("HTTP 503 Service Unavailable".to_string())
+        );
+
+        let offline = Config::from_toml(&format!("offline = true\n{}", toml))?;
+        let probes = config::scoped_task(offline.clone(), run(&offline)).await?;
+        assert!(probes
+            .iter()
+            .all(|probe| probe.outcome() == &Outcome::Skipped));
+        Ok(())
+    }
+
+    #[test]
+    fn test_describe() {
+        let probes = [
+            Probe {
+                registry: "crates-io".to_string(),
+                service: "docs",
+                url: "https://docs.rs/".to_string(),
+                outcome: Outcome::Reachable {
+                    status: 200,
+                    elapsed: Duration::from_millis(84),
+                },
+            },
+            Probe {
+                registry: "acme".to_string(),
+                service: "index",
+                url: "https://registry.acme.internal/index/config.json".to_string(),
+                outcome: Outcome::Unreachable("HTTP 401 Unauthorized".to_string()),
+            },
+        ];
+        assert_eq!(
+            describe(&probes),
+            "ok       crates-io  docs   https://docs.rs/  (200 in 84 ms)\n\
+             FAILED   acme       index  https://registry.acme.internal/index/config.json  (HTTP 401 Unauthorized)\n"
+        );
+    }
+}
@@ -0,0 +1,465 @@
+//! Lookups from the command line, for using the binary as a docs CLI without an MCP client in
+//! the loop. By default the JSON the matching tool returns is printed; `--pretty` renders it as
+//! colored tables instead, shown through `$PAGER` when writing to a terminal.
+//!
+//! ```text
+//! docs-rs-mcp items --crate tokio [--version 1.43.0] [--pretty]
+//! docs-rs-mcp struct --crate tokio --struct sync::Mutex [--version 1.43.0] [--pretty]
+//! docs-rs-mcp diff --crate tokio --from 1.42.0 [--to 1.43.0] [--pretty]
+//! ```
+
+use std::io::{IsTerminal, Write};
+use std::process::{Command, Stdio};
+
+use anyhow::Result;
+use comfy_table::{presets, Table, TableComponent};
+use owo_colors::{OwoColorize, Stream, Style};
+
+use crate::client::DocsClient;
+use crate::tools::compare_versions::ApiDiff;
+use crate::tools::crate_items::CrateItems;
+use crate::tools::get_struct_docs::StructDocs;
+
+/// Pager used when `$PAGER` isn't set. `-R` keeps the colors and `-F` quits right away when
+/// the output fits on one screen.
+const DEFAULT_PAGER: &str = "less -FRX";
+
+/// Descriptions are cut to their first line and this many characters in tables
+const MAX_SUMMARY_CHARS: usize = 100;
+
+/// What to look up
+#[derive(Debug, Clone, PartialEq, clap::Subcommand)]
+pub enum Lookup {
+    /// Lists the items of a crate
+    #[command(disable_version_flag = true)]
+    Items {
+        #[command(flatten)]
+        options: LookupOptions,
+        /// Version of the crate, latest by default
+        #[arg(long, value_name = "version")]
+        version: Option<String>,
+    },
+    /// Shows the documentation of a struct
+    #[command(disable_version_flag = true)]
+    Struct {
+        #[command(flatten)]
+        options: LookupOptions,
+        /// Path of the struct, e.g. sync::Mutex
+        #[arg(long = "struct", value_name = "path")]
+        name: String,
+        /// Version of the crate, latest by default
+        #[arg(long, value_name = "version")]
+        version: Option<String>,
+    },
+    /// Compares the public API of two versions of a crate
+    Diff {
+        #[command(flatten)]
+        options: LookupOptions,
+        /// Version to compare from
+        #[arg(long, value_name = "version")]
+        from: String,
+        /// Version to compare to, latest by default
+        #[arg(long, value_name = "version", default_value = "latest")]
+        to: String,
+    },
+}
+
+/// The crate a lookup is about and how to show its result
+#[derive(Debug, Clone, PartialEq, clap::Args)]
+pub struct LookupOptions {
+    /// Crate to look up
+    #[arg(long = "crate", value_name = "name")]
+    crate_name: String,
+    /// Render colored tables instead of JSON
+    #[arg(long)]
+    pretty: bool,
+}
+
+impl Lookup {
+    pub fn options(&self) -> &LookupOptions {
+        match self {
+            Self::Items { options, .. }
+            | Self::Struct { options, .. }
+            | Self::Diff { options, .. } => options,
+        }
+    }
+}
+
+impl LookupOptions {
+    pub fn crate_name(&self) -> &str {
+        &self.crate_name
+    }
+
+    pub fn pretty(&self) -> bool {
+        self.pretty
+    }
+}
+
+/// Runs the lookup and writes its result to stdout.
+pub async fn run(lookup: &Lookup) -> Result<()> {
+    let client = DocsClient::new();
+    let options = lookup.options();
+    let crate_name = options.crate_name.as_str();
+    let terminal = std::io::stdout().is_terminal();
+
+    let output = match lookup {
+        Lookup::Items { version, .. } => {
+            let items = client.crate_items(crate_name, version.as_deref()).await?;
+            match options.pretty {
+                true => render_items(&items),
+                false => serde_json::to_string_pretty(&items)?,
+            }
+        }
+        Lookup::Struct { name, version, .. } => {
+            let docs = client
+                .struct_docs(crate_name, name, version.as_deref())
+                .await?;
+            match options.pretty {
+                true => render_struct(&docs),
+                false => serde_json::to_string_pretty(&docs)?,
+            }
+        }
+        Lookup::Diff { from, to, .. } => {
+            let diff = client.compare_versions(crate_name, from, to).await?;
+            match options.pretty {
+                true => render_diff(&diff),
+                false => serde_json::to_string_pretty(&diff)?,
+            }
+        }
+    };
+
+    if options.pretty && terminal {
+        page(&output)
+    } else {
+        println!("{}", output);
+        Ok(())
+    }
+}
+
+/// Shows `output` through `$PAGER`, or prints it when no pager can be started.
+fn page(output: &str) -> Result<()> {
+    let pager = std::env::var("PAGER")
+        .ok()
+        .filter(|pager| !pager.trim().is_empty())
+        .unwrap_or_else(|| DEFAULT_PAGER.to_string());
+    let mut words = pager.split_whitespace();
+    let program = words.next().unwrap_or_default();
+
+    match Command::new(program)
+        .args(words)
+        .stdin(Stdio::piped())
+        .spawn()
+    {
+        Ok(mut child) => {
+            if let Some(mut stdin) = child.stdin.take() {
+                // Quitting the pager before reading everything isn't an error
+                let _ = stdin.write_all(output.as_bytes());
+            }
+            child.wait()?;
+        }
+        Err(_) => println!("{}", output),
+    }
+    Ok(())
+}
+
+/// `text` in `style` when stdout takes colors, i.e. is a terminal and `NO_COLOR` isn't set
+fn paint(style: Style, text: &str) -> String {
+    text.if_supports_color(Stream::Stdout, |text| text.style(style))
+        .to_string()
+}
+
+/// Lays `rows` out in columns under `header`, each as wide as its widest cell
+fn table(header: &[&str], rows: Vec<Vec<String>>) -> String {
+    let bold = Style::new().bold();
+    let mut table = Table::new();
+    table
+        .load_preset(presets::NOTHING)
+        .set_style(TableComponent::HeaderLines, '─')
+        .set_header(header.iter().map(|title| paint(bold, title)))
+        .add_rows(rows);
+    for column in table.column_iter_mut() {
+        column.set_padding((0, 2));
+    }
+    table.trim_fmt()
+}
+
+/// The first line of `text`, cut to [`MAX_SUMMARY_CHARS`]
+fn summary(text: &str) -> String {
+    let first = text
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .unwrap_or_default();
+    match first.char_indices().nth(MAX_SUMMARY_CHARS) {
+        Some((end, _)) => format!("{}…", first[..end].trim_end()),
+        None => first.to_string(),
+    }
+}
+
+fn render_items(items: &CrateItems) -> String {
+    let counts = items
+        .items()
+        .iter()
+        .map(|(section, items)| format!("{} {}", items.len(), section.to_lowercase()))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let mut out = vec![
+        paint(
+            Style::new().bold(),
+            &format!("{} {}", items.crate_name(), items.version()),
+        ),
+        format!("{} items: {}", items.counts().total(), counts),
+    ];
+    if let Some(indexing) = items.indexing() {
+        out.push(paint(Style::new().dimmed(), &indexing.describe()));
+    }
+
+    for (section, section_items) in items.items() {
+        let rows: Vec<Vec<String>> = section_items
+            .iter()
+            .map(|item| {
+                vec![
+                    paint(Style::new().cyan(), item.name()),
+                    paint(Style::new().dimmed(), item.doc_link()),
+                ]
+            })
+            .collect();
+        out.push(String::new());
+        out.push(paint(
+            Style::new().yellow().bold(),
+            &format!("{} ({})", section, section_items.len()),
+        ));
+        out.push(table(&["Item", "Docs"], rows));
+    }
+    out.join("\n")
+}
+
+fn render_struct(docs: &StructDocs) -> String {
+    let mut out = vec![
+        paint(
+            Style::new().bold(),
+            &format!("struct {} ({})", docs.name(), docs.crate_name()),
+        ),
+        paint(Style::new().dimmed(), docs.doc_url()),
+    ];
+    if !docs.required_features().is_empty() {
+        out.push(paint(
+            Style::new().yellow(),
+            &format!("Requires features: {}", docs.required_features().join(", ")),
+        ));
+    }
+    if !docs.description().trim().is_empty() {
+        out.push(String::new());
+        out.push(docs.description().trim().to_string());
+    }
+
+    if !docs.fields().is_empty() {
+        let rows: Vec<Vec<String>> = docs
+            .fields()
+            .iter()
+            .map(|field| {
+                vec![
+                    paint(Style::new().cyan(), field.name()),
+                    paint(Style::new().green(), field.type_name()),
+                    summary(field.description()),
+                ]
+            })
+            .collect();
+        out.push(String::new());
+        out.push(paint(Style::new().yellow().bold(), "Fields"));
+        out.push(table(&["Field", "Type", "Description"], rows));
+    }
+
+    for block in docs.impls() {
+        if block.methods().is_empty() {
+            continue;
+        }
+        out.push(String::new());
+        out.push(paint(Style::new().yellow().bold(), block.header()));
+        for method in block.methods() {
+            out.push(format!(
+                "  {}",
+                paint(Style::new().green(), method.signature())
+            ));
+            let description = summary(method.description());
+            if !description.is_empty() {
+                out.push(format!("      {}", description));
+            }
+        }
+    }
+
+    if !docs.traits().is_empty() {
+        out.push(String::new());
+        out.push(format!(
+            "{} {}",
+            paint(Style::new().yellow().bold(), "Traits:"),
+            docs.traits().join(", ")
+        ));
+    }
+    out.join("\n")
+}
+
+fn render_diff(diff: &ApiDiff) -> String {
+    let mut out = vec![
+        paint(
+            Style::new().bold(),
+            &format!(
+                "{} {} → {}",
+                diff.crate_name(),
+                diff.from_version(),
+                diff.to_version()
+            ),
+        ),
+        format!(
+            "{} added, {} removed, {} changed kind",
+            diff.added().len(),
+            diff.removed().len(),
+            diff.changed().len()
+        ),
+    ];
+
+    let added = Style::new().green();
+    let removed = Style::new().red();
+    let changed = Style::new().yellow();
+    let rows: Vec<Vec<String>> = diff
+        .added()
+        .iter()
+        .map(|item| (added, "+", item.kind().to_string(), item.name()))
+        .chain(
+            diff.removed()
+                .iter()
+                .map(|item| (removed, "-", item.kind().to_string(), item.name())),
+        )
+        .chain(diff.changed().iter().map(|change| {
+            let kind = format!("{} → {}", change.from_kind(), change.to_kind());
+            (changed, "~", kind, change.name())
+        }))
+        .map(|(style, sign, kind, name)| {
+            vec![paint(style, sign), paint(style, &kind), paint(style, name)]
+        })
+        .collect();
+    if !rows.is_empty() {
+        out.push(String::new());
+        out.push(table(&["", "Kind", "Item"], rows));
+    }
+    out.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::args::{Cli, Command};
+    use crate::CrateItemsTool;
+    use pretty_assertions::assert_eq;
+
+    /// Leaves colors out whatever the environment says, e.g. `FORCE_COLOR`
+    fn plain() {
+        owo_colors::set_override(false);
+    }
+
+    #[test]
+    fn test_parse_lookup() -> Result<()> {
+        let cli = Cli::parse_args([
+            "struct",
+            "--crate",
+            "tokio",
+            "--struct=sync::Mutex",
+            "--pretty",
+            "--offline",
+        ])?;
+        let Some(Command::Lookup(lookup)) = cli.command else {
+            panic!("Expected a lookup");
+        };
+        assert_eq!(lookup.options().crate_name(), "tokio");
+        assert!(lookup.options().pretty());
+        assert!(matches!(
+            lookup,
+            Lookup::Struct { ref name, version: None, .. } if name == "sync::Mutex"
+        ));
+        assert_eq!(
+            cli.overrides,
+            Cli::parse_args(["--offline"])?.overrides,
+            "Config flags may follow the command"
+        );
+
+        let cli = Cli::parse_args(["diff", "--crate", "tokio", "--from", "1.42.0"])?;
+        let Some(Command::Lookup(Lookup::Diff { from, to, .. })) = cli.command else {
+            panic!("Expected a diff");
+        };
+        assert_eq!((from.as_str(), to.as_str()), ("1.42.0", "latest"));
+        assert!(Cli::parse_args(["diff", "--crate", "tokio"]).is_err());
+        assert!(Cli::parse_args(["items", "--version", "1.0.0"]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_table() {
+        plain();
+        let rows = vec![
+            vec!["sync::Mutex".to_string(), "a".to_string()],
+            vec!["Runtime".to_string(), "b".to_string()],
+        ];
+        assert_eq!(
+            table(&["Item", "Docs"], rows),
+            format!(
+                "Item         Docs\n{}\nsync::Mutex  a\nRuntime      b",
+                "─".repeat(19)
+            )
+        );
+    }
+
+    #[test]
+    fn test_render_items() -> Result<()> {
+        plain();
+        let html = std::fs::read_to_string("test-data/list-of-all-items-scraper-0.22.0.html")?;
+        let items = CrateItemsTool::new().parse_items(
+            "scraper",
+            "0.22.0",
+            "https://docs.rs/scraper/0.22.0/scraper/",
+            &html,
+        )?;
+        let rendered = render_items(&items);
+
+        let lines: Vec<&str> = rendered.lines().collect();
+        assert_eq!(lines[0], "scraper 0.22.0");
+        assert!(lines[1].starts_with("29 items: "), "{}", lines[1]);
+        assert!(rendered.contains("\nStructs (18)\n"));
+        let html_row = lines
+            .iter()
+            .find(|line| line.starts_with("html::Html "))
+            .expect("Html should be listed");
+        assert!(
+            html_row.ends_with("/scraper/html/struct.Html.html"),
+            "{}",
+            html_row
+        );
+        assert!(!rendered.contains('\u{1b}'), "Plain output has no escapes");
+        Ok(())
+    }
+
+    #[test]
+    fn test_render_diff() -> Result<()> {
+        plain();
+        let diff: ApiDiff = serde_json::from_value(serde_json::json!({
+            "crate_name": "demo",
+            "from_version": "1.0.0",
+            "to_version": "2.0.0",
+            "added": [{ "name": "Client", "kind": "Structs", "doc_link": "" }],
+            "removed": [{ "name": "legacy", "kind": "Functions", "doc_link": "" }],
+            "changed": [{ "name": "Id", "from_kind": "Structs", "to_kind": "Type Aliases" }],
+        }))?;
+
+        assert_eq!(
+            render_diff(&diff),
+            "demo 1.0.0 → 2.0.0\n\
+             1 added, 1 removed, 1 changed kind\n\
+             \n   \
+             Kind                    Item\n\
+             ───────────────────────────────────\n\
+             +  Structs                 Client\n\
+             -  Functions               legacy\n\
+             ~  Structs → Type Aliases  Id"
+        );
+        Ok(())
+    }
+}
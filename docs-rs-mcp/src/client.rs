@@ -0,0 +1,206 @@
+//! An async Rust API over the documentation tools, for embedding them in a program that doesn't
+//! speak MCP. Each method does what the tool of the same name does, using the same code and
+//! the loaded configuration (allow list, registries, HTTP settings), and returns its result as
+//! a typed value instead of JSON text.
+//!
+//! ```no_run
+//! # async fn demo() -> Result<(), docs_rs_mcp::DocsRsMcpError> {
+//! let client = docs_rs_mcp::DocsClient::new();
+//! let docs = client.struct_docs("tokio", "sync::Mutex", None).await?;
+//! let hits = client.search("serde", "Deserializer", None, 5).await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Struct docs and crate items are looked up with async I/O. The other lookups still block
+//! their thread while they wait, so they run on tokio's blocking thread pool; the methods must be
+//! called from within a tokio runtime. Without the `native` feature, e.g. on wasm, only the
+//! async lookups are offered.
+
+use std::future::Future;
+
+use anyhow::Result;
+
+use crate::config;
+use crate::error::DocsRsMcpError;
+use crate::tools::crate_items::CrateItems;
+use crate::tools::get_struct_docs::StructDocs;
+#[cfg(feature = "native")]
+use crate::tools::{
+    compare_versions::ApiDiff, crate_features::CrateFeatures, crate_info::CrateInfo,
+    crate_readme::CrateReadme, module_items::ModuleItems, search_in_crate,
+    search_in_crate::SearchResult, CompareVersionsTool, CrateFeaturesTool, CrateInfoTool,
+    CrateReadmeTool, ModuleItemsTool, SearchInCrateTool,
+};
+use crate::tools::{registry, CrateItemsTool, StructDocsTool};
+
+/// Entry point of the library API. Cheap to create and clone; it holds no connections.
+#[derive(Debug, Clone, Default)]
+pub struct DocsClient {
+    registry: Option<String>,
+}
+
+impl DocsClient {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Looks crates up in the named registry from the configuration instead of picking one
+    /// per crate.
+    pub fn with_registry(mut self, registry: impl Into<String>) -> Self {
+        self.registry = Some(registry.into());
+        self
+    }
+
+    /// Documentation of a struct, as the `get_struct_docs` tool returns it. `struct_name` may
+    /// be qualified with its module path, e.g. `sync::Mutex`.
+    pub async fn struct_docs(
+        &self,
+        crate_name: &str,
+        struct_name: &str,
+        version: Option<&str>,
+    ) -> Result<StructDocs, DocsRsMcpError> {
+        let tool = StructDocsTool::new();
+        self.run_async(
+            crate_name,
+            tool.fetch_docs(crate_name, struct_name, version, 0),
+        )
+        .await
+    }
+
+    /// Every public item of a crate, grouped by kind, as the `crate_items` tool returns it.
+    pub async fn crate_items(
+        &self,
+        crate_name: &str,
+        version: Option<&str>,
+    ) -> Result<CrateItems, DocsRsMcpError> {
+        let tool = CrateItemsTool::new();
+        self.run_async(crate_name, tool.scrape_items(crate_name, version))
+            .await
+    }
+
+    /// The items added, removed and changed in kind between two versions of a crate, as the
+    /// `compare_versions` tool returns them.
+    #[cfg(feature = "native")]
+    pub async fn compare_versions(
+        &self,
+        crate_name: &str,
+        from_version: &str,
+        to_version: &str,
+    ) -> Result<ApiDiff, DocsRsMcpError> {
+        let from_version = from_version.to_string();
+        let to_version = to_version.to_string();
+        self.run(crate_name, move |crate_name| {
+            CompareVersionsTool::new().compare(crate_name, &from_version, &to_version)
+        })
+        .await
+    }
+
+    /// The items of a crate best matching `query`, ranked like docs.rs search.
+    #[cfg(feature = "native")]
+    pub async fn search(
+        &self,
+        crate_name: &str,
+        query: &str,
+        version: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<SearchResult>, DocsRsMcpError> {
+        let query = query.to_string();
+        let version = version.map(str::to_string);
+        self.run(crate_name, move |crate_name| {
+            let items = SearchInCrateTool::new().fetch_index(crate_name, version.as_deref())?;
+            Ok(search_in_crate::search(&items, &query, None, limit)
+                .into_iter()
+                .cloned()
+                .collect())
+        })
+        .await
+    }
+
+    /// The items of one module, e.g. `sync` or `tokio::net`.
+    #[cfg(feature = "native")]
+    pub async fn module_items(
+        &self,
+        crate_name: &str,
+        path: &str,
+        version: Option<&str>,
+    ) -> Result<ModuleItems, DocsRsMcpError> {
+        let path = path.to_string();
+        let version = version.map(str::to_string);
+        self.run(crate_name, move |crate_name| {
+            ModuleItemsTool::new().fetch_module(crate_name, &path, version.as_deref())
+        })
+        .await
+    }
+
+    /// The feature flags of a crate and what each enables.
+    #[cfg(feature = "native")]
+    pub async fn crate_features(
+        &self,
+        crate_name: &str,
+        version: Option<&str>,
+    ) -> Result<CrateFeatures, DocsRsMcpError> {
+        let version = version.unwrap_or("latest").to_string();
+        self.run(crate_name, move |crate_name| {
+            CrateFeaturesTool::new().fetch_features(crate_name, &version)
+        })
+        .await
+    }
+
+    /// The README of a crate.
+    #[cfg(feature = "native")]
+    pub async fn crate_readme(
+        &self,
+        crate_name: &str,
+        version: Option<&str>,
+    ) -> Result<CrateReadme, DocsRsMcpError> {
+        let version = version.map(str::to_string);
+        self.run(crate_name, move |crate_name| {
+            CrateReadmeTool::new().fetch_readme(crate_name, version.as_deref())
+        })
+        .await
+    }
+
+    /// Metadata of the latest release of a crate, as reported by `cargo info`.
+    #[cfg(feature = "native")]
+    pub async fn crate_info(&self, crate_name: &str) -> Result<CrateInfo, DocsRsMcpError> {
+        self.run(crate_name, |crate_name| {
+            CrateInfoTool::new().fetch_crate_info(crate_name)
+        })
+        .await
+    }
+
+    /// Runs a lookup about `crate_name` on the blocking pool, with the checks and registry
+    /// selection the MCP tools apply.
+    #[cfg(feature = "native")]
+    async fn run<T, F>(&self, crate_name: &str, lookup: F) -> Result<T, DocsRsMcpError>
+    where
+        T: Send + 'static,
+        F: FnOnce(&str) -> Result<T> + Send + 'static,
+    {
+        let crate_name = crate_name.to_string();
+        let registry = self.registry.clone();
+        let result = tokio::task::spawn_blocking(move || {
+            config::current().ensure_crate_allowed(&crate_name)?;
+            registry::in_registry(&crate_name, registry.as_deref(), || lookup(&crate_name))
+        })
+        .await
+        .map_err(|e| DocsRsMcpError::Other(format!("The lookup task failed: {}", e)))?;
+        result.map_err(|error| DocsRsMcpError::classify(&error))
+    }
+
+    /// Awaits a lookup about `crate_name` with the checks and registry selection the MCP tools
+    /// apply.
+    async fn run_async<T>(
+        &self,
+        crate_name: &str,
+        lookup: impl Future<Output = Result<T>>,
+    ) -> Result<T, DocsRsMcpError> {
+        let result = async {
+            config::current().ensure_crate_allowed(crate_name)?;
+            registry::in_registry_async(crate_name, self.registry.as_deref(), lookup).await
+        }
+        .await;
+        result.map_err(|error| DocsRsMcpError::classify(&error))
+    }
+}
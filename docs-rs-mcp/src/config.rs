@@ -0,0 +1,1727 @@
+//! Server configuration.
+//!
+//! Settings are layered, each layer overriding the previous one:
+//!
+//! 1. built-in defaults
+//! 2. the config file (`~/.config/docs-rs-mcp/config.toml`, or the path given by `--config` /
+//!    `DOCS_RS_MCP_CONFIG`)
+//! 3. environment variables
+//! 4. command line flags
+//!
+//! The effective configuration is installed process-wide with [`install`] (or [`init`]) and read
+//! by the tools through [`current`]. Because tools look the configuration up on every call,
+//! [`reload`] can swap in a re-read config file without restarting the server or dropping client
+//! sessions.
+
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap};
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::{Arc, OnceLock, RwLock};
+use std::time::Duration;
+
+use anyhow::{anyhow, bail, Context, Result};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, AUTHORIZATION};
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::error::DocsRsMcpError;
+use crate::paths;
+
+/// User agent sent with every request unless configured; crates.io rejects requests without
+/// one and asks for a way to reach the operator
+const USER_AGENT: &str = concat!(
+    env!("CARGO_PKG_NAME"),
+    "/",
+    env!("CARGO_PKG_VERSION"),
+    " (+",
+    env!("CARGO_PKG_REPOSITORY"),
+    ")"
+);
+
+/// Environment variable pointing at an alternative config file
+pub const CONFIG_ENV: &str = "DOCS_RS_MCP_CONFIG";
+
+/// Name of the built-in registry served by `sources.crates_io_url` and `sources.docs_rs_url`
+pub const DEFAULT_REGISTRY: &str = "crates-io";
+
+/// The effective server configuration
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    log_level: String,
+    /// Answer from the cache only, however old the cached copies, without sending requests
+    offline: bool,
+    cache: CacheConfig,
+    sources: SourcesConfig,
+    allow_list: AllowListConfig,
+    http: HttpConfig,
+    admin: AdminConfig,
+    local_docs: LocalDocsConfig,
+    transport: TransportConfig,
+    summary: SummaryConfig,
+    index: IndexConfig,
+    registries: Vec<RegistryConfig>,
+    /// Registry the sources currently point at, see [`Config::for_registry`]
+    #[serde(skip)]
+    active_registry: Option<String>,
+    /// Documentation host of a single tool call, see [`Config::with_docs_url`]
+    #[serde(skip)]
+    docs_url_override: Option<String>,
+}
+
+/// Settings of the documentation cache
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CacheConfig {
+    enabled: bool,
+    ttl_secs: u64,
+    /// How long a URL that answered Not Found is answered so without asking again, 0 to always
+    /// ask
+    not_found_ttl_secs: u64,
+    dir: Option<PathBuf>,
+}
+
+/// Upstream locations and the order in which they are consulted
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SourcesConfig {
+    docs_rs_url: String,
+    crates_io_url: String,
+    /// Base URL of the sparse registry index, empty when the registry has none
+    index_url: String,
+    /// OSV API answering which RustSec advisories concern a crate, empty to not ask
+    osv_url: String,
+    /// GitHub API telling whether a crate's repository is archived, empty to not ask
+    github_api_url: String,
+    priority: Vec<Source>,
+    /// Whether tool calls may point docs.rs lookups elsewhere with `docs_base_url`. Off unless
+    /// enabled, as the server then fetches whatever URL the client names.
+    allow_docs_base_url: bool,
+}
+
+/// An upstream documentation source
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Source {
+    #[serde(rename = "crates.io")]
+    CratesIo,
+    #[serde(rename = "docs.rs")]
+    DocsRs,
+}
+
+impl std::fmt::Display for Source {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::CratesIo => write!(f, "crates.io"),
+            Self::DocsRs => write!(f, "docs.rs"),
+        }
+    }
+}
+
+/// Crates the server is allowed to look up. An empty `allowed` list allows every crate that
+/// isn't explicitly `blocked`. Entries ending in `*` match by prefix.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AllowListConfig {
+    allowed: Vec<String>,
+    blocked: Vec<String>,
+}
+
+/// An additional crates.io-compatible registry, e.g. a private company registry
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RegistryConfig {
+    /// Name used in the `registry` tool parameter, ideally matching the Cargo registry name
+    name: String,
+    /// Base URL of the crates.io-compatible web API
+    api_url: String,
+    /// Base URL of the docs.rs-compatible documentation host
+    docs_url: String,
+    /// Base URL of the sparse index, without the `sparse+` prefix
+    #[serde(default)]
+    index_url: Option<String>,
+    /// Crates always looked up in this registry, without asking the others. Entries ending in
+    /// `*` match by prefix.
+    #[serde(default)]
+    crates: Vec<String>,
+    /// Sent as is in the `Authorization` header to the registry's hosts, as Cargo does. Never
+    /// echoed back.
+    #[serde(default, skip_serializing)]
+    token: Option<String>,
+}
+
+/// Access to the operator tools in the `admin/` namespace
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AdminConfig {
+    enabled: bool,
+    /// Token admin tool calls must pass; never echoed back by the tools
+    #[serde(skip_serializing)]
+    token: Option<String>,
+}
+
+/// Building documentation locally for crates docs.rs doesn't have, such as crates of private
+/// registries or unpublished git dependencies
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LocalDocsConfig {
+    enabled: bool,
+    /// Toolchain passed to cargo as `+toolchain`; rustdoc JSON needs a nightly one
+    toolchain: String,
+    /// Git repositories of crates that aren't published to any registry, by crate name
+    git: BTreeMap<String, String>,
+}
+
+/// Options of the HTTP client used for upstream requests
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct HttpConfig {
+    timeout_secs: u64,
+    proxy: Option<String>,
+    /// Requests sent to a host per second on average, 0 for no limit
+    requests_per_second: f64,
+    /// Requests sent to a host at once before the rate applies
+    burst: u32,
+    /// Replaces the default `docs-rs-mcp/<version> (+<repository>)`
+    user_agent: Option<String>,
+    /// Headers sent with every request. May hold credentials, so never echoed back.
+    #[serde(skip_serializing)]
+    headers: BTreeMap<String, String>,
+    /// Headers sent only to the given host, e.g. to authenticate to an internal mirror
+    #[serde(skip_serializing)]
+    host_headers: BTreeMap<String, BTreeMap<String, String>>,
+}
+
+/// How the server talks to its clients, and the limits of that
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TransportConfig {
+    /// Largest message the client accepts, 0 for no limit. Larger tool responses are split
+    /// into resources the client reads one by one.
+    max_message_bytes: usize,
+    /// How clients reach the server
+    kind: TransportKind,
+    /// Address the HTTP and TCP transports listen on
+    bind: String,
+    /// Socket the Unix socket transport listens on, see [`paths::socket_path`] for the default
+    socket: Option<PathBuf>,
+    /// Bearer token HTTP clients must send; never echoed back
+    #[serde(skip_serializing)]
+    token: Option<String>,
+    /// Most HTTP sessions open at once; `initialize` is refused beyond it
+    max_sessions: usize,
+    /// Seconds after which an HTTP session without messages is ended
+    session_idle_secs: u64,
+}
+
+/// How clients reach the server
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TransportKind {
+    /// One client, which started the server, over stdin and stdout
+    Stdio,
+    /// Any number of clients over MCP's streamable HTTP transport, or the legacy HTTP+SSE one
+    Http,
+    /// Any number of local clients, one JSON-RPC message per line as over stdio, on a TCP port
+    /// of the loopback interface
+    Tcp,
+    /// Any number of local clients, one JSON-RPC message per line as over stdio, on a Unix
+    /// domain socket
+    Unix,
+}
+
+impl FromStr for TransportKind {
+    type Err = anyhow::Error;
+
+    fn from_str(kind: &str) -> Result<Self> {
+        match kind {
+            "stdio" => Ok(Self::Stdio),
+            "http" => Ok(Self::Http),
+            "tcp" => Ok(Self::Tcp),
+            "unix" => Ok(Self::Unix),
+            _ => bail!(
+                "Unknown transport '{}', expected stdio, http, tcp or unix",
+                kind
+            ),
+        }
+    }
+}
+
+impl TransportKind {
+    /// Whether clients other than the one that started the server can connect
+    pub fn is_network(self) -> bool {
+        matches!(self, Self::Http | Self::Tcp)
+    }
+}
+
+/// Shortening descriptions too long to be useful in a tool response
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SummaryConfig {
+    /// Length in characters above which a description is summarized, 0 to never summarize
+    threshold_chars: usize,
+    /// Length the summary is kept under
+    max_chars: usize,
+    /// OpenAI-compatible chat completions endpoint to summarize with, instead of taking the
+    /// first paragraph and the headings
+    endpoint: Option<String>,
+    /// Model requested from the endpoint
+    model: String,
+}
+
+/// Indexing crates too large to list in one pass
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct IndexConfig {
+    /// Size in bytes of the `.crate` file above which the items are listed module by module in
+    /// the background, 0 to always list them in one pass
+    large_crate_bytes: u64,
+    /// Module pages read before progress is saved
+    chunk_pages: usize,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            log_level: "debug".to_string(),
+            offline: false,
+            cache: CacheConfig::default(),
+            sources: SourcesConfig::default(),
+            allow_list: AllowListConfig::default(),
+            http: HttpConfig::default(),
+            admin: AdminConfig::default(),
+            local_docs: LocalDocsConfig::default(),
+            transport: TransportConfig::default(),
+            summary: SummaryConfig::default(),
+            index: IndexConfig::default(),
+            registries: Vec::new(),
+            active_registry: None,
+            docs_url_override: None,
+        }
+    }
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            ttl_secs: 24 * 60 * 60,
+            not_found_ttl_secs: 5 * 60,
+            dir: None,
+        }
+    }
+}
+
+impl Default for SourcesConfig {
+    fn default() -> Self {
+        Self {
+            docs_rs_url: "https://docs.rs".to_string(),
+            crates_io_url: "https://crates.io".to_string(),
+            index_url: "https://index.crates.io".to_string(),
+            osv_url: "https://api.osv.dev".to_string(),
+            github_api_url: "https://api.github.com".to_string(),
+            priority: vec![Source::CratesIo, Source::DocsRs],
+            allow_docs_base_url: false,
+        }
+    }
+}
+
+impl Default for AdminConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            token: None,
+        }
+    }
+}
+
+impl Default for LocalDocsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            toolchain: "nightly".to_string(),
+            git: BTreeMap::new(),
+        }
+    }
+}
+
+impl Default for HttpConfig {
+    fn default() -> Self {
+        Self {
+            timeout_secs: 30,
+            proxy: None,
+            requests_per_second: 5.0,
+            burst: 10,
+            user_agent: None,
+            headers: BTreeMap::new(),
+            host_headers: BTreeMap::new(),
+        }
+    }
+}
+
+impl Default for TransportConfig {
+    fn default() -> Self {
+        Self {
+            max_message_bytes: 1024 * 1024,
+            kind: TransportKind::Stdio,
+            bind: "127.0.0.1:8080".to_string(),
+            socket: None,
+            token: None,
+            max_sessions: 100,
+            session_idle_secs: 30 * 60,
+        }
+    }
+}
+
+impl Default for SummaryConfig {
+    fn default() -> Self {
+        Self {
+            threshold_chars: 16 * 1024,
+            max_chars: 2000,
+            endpoint: None,
+            model: String::new(),
+        }
+    }
+}
+
+impl Default for IndexConfig {
+    fn default() -> Self {
+        Self {
+            large_crate_bytes: 2_000_000,
+            chunk_pages: 20,
+        }
+    }
+}
+
+impl Config {
+    /// Loads the configuration by layering the config file, the process environment and the
+    /// given command line overrides on top of the defaults.
+    pub fn load(overrides: &ConfigOverrides) -> Result<Self> {
+        let env = |key: &str| std::env::var(key).ok();
+
+        let mut config = match explicit_config_path(overrides) {
+            Some(path) => Self::from_file(&path)?,
+            None => match default_config_path() {
+                Some(path) if path.exists() => Self::from_file(&path)?,
+                _ => Self::default(),
+            },
+        };
+        config.apply_env(env)?;
+        config.apply_overrides(overrides);
+        Ok(config)
+    }
+
+    /// Reads a config file, using defaults for every setting it doesn't mention.
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let contents = std::fs::read_to_string(path)
+            .context(format!("Failed to read config file: {}", path.display()))?;
+        Self::from_toml(&contents).context(format!("Invalid config file: {}", path.display()))
+    }
+
+    /// Parses a TOML config document.
+    pub fn from_toml(contents: &str) -> Result<Self> {
+        Ok(toml::from_str(contents)?)
+    }
+
+    /// Defaults with the environment variable layer applied, used when no configuration has
+    /// been installed (e.g. when the tools are used as a library).
+    fn from_env() -> Self {
+        let mut config = Self::default();
+        if let Err(e) = config.apply_env(|key| std::env::var(key).ok()) {
+            tracing::warn!("Ignoring invalid environment configuration: {}", e);
+        }
+        config
+    }
+
+    /// Applies the environment variable layer, reading variables through `lookup`.
+    fn apply_env(&mut self, lookup: impl Fn(&str) -> Option<String>) -> Result<()> {
+        if let Some(url) = lookup("DOCS_RS_URL") {
+            self.sources.docs_rs_url = url;
+        }
+        if let Some(url) = lookup("CRATES_IO_URL") {
+            self.sources.crates_io_url = url;
+        }
+        if let Some(url) = lookup("CRATES_IO_INDEX_URL") {
+            self.sources.index_url = url;
+        }
+        if let Some(url) = lookup("OSV_URL") {
+            self.sources.osv_url = url;
+        }
+        if let Some(url) = lookup("GITHUB_API_URL") {
+            self.sources.github_api_url = url;
+        }
+        if let Some(level) = lookup("DOCS_RS_MCP_LOG_LEVEL") {
+            self.log_level = level;
+        }
+        if let Some(offline) = lookup("DOCS_RS_MCP_OFFLINE") {
+            self.offline = offline
+                .parse()
+                .context("DOCS_RS_MCP_OFFLINE must be true or false")?;
+        }
+        if let Some(dir) = lookup("DOCS_RS_MCP_CACHE_DIR") {
+            self.cache.dir = Some(PathBuf::from(dir));
+        }
+        if let Some(ttl) = lookup("DOCS_RS_MCP_CACHE_TTL_SECS") {
+            self.cache.ttl_secs = ttl
+                .parse()
+                .context("DOCS_RS_MCP_CACHE_TTL_SECS must be a number of seconds")?;
+        }
+        if let Some(ttl) = lookup("DOCS_RS_MCP_CACHE_NOT_FOUND_TTL_SECS") {
+            self.cache.not_found_ttl_secs = ttl
+                .parse()
+                .context("DOCS_RS_MCP_CACHE_NOT_FOUND_TTL_SECS must be a number of seconds")?;
+        }
+        if let Some(timeout) = lookup("DOCS_RS_MCP_HTTP_TIMEOUT_SECS") {
+            self.http.timeout_secs = timeout
+                .parse()
+                .context("DOCS_RS_MCP_HTTP_TIMEOUT_SECS must be a number of seconds")?;
+        }
+        if let Some(rate) = lookup("DOCS_RS_MCP_HTTP_REQUESTS_PER_SECOND") {
+            self.http.requests_per_second = rate
+                .parse()
+                .context("DOCS_RS_MCP_HTTP_REQUESTS_PER_SECOND must be a number")?;
+        }
+        if let Some(bytes) = lookup("DOCS_RS_MCP_MAX_MESSAGE_BYTES") {
+            self.transport.max_message_bytes = bytes
+                .parse()
+                .context("DOCS_RS_MCP_MAX_MESSAGE_BYTES must be a number of bytes")?;
+        }
+        if let Some(user_agent) = lookup("DOCS_RS_MCP_USER_AGENT").filter(|ua| !ua.is_empty()) {
+            self.http.user_agent = Some(user_agent);
+        }
+        if let Some(threshold) = lookup("DOCS_RS_MCP_SUMMARY_THRESHOLD_CHARS") {
+            self.summary.threshold_chars = threshold
+                .parse()
+                .context("DOCS_RS_MCP_SUMMARY_THRESHOLD_CHARS must be a number of characters")?;
+        }
+        if let Some(bytes) = lookup("DOCS_RS_MCP_INDEX_LARGE_CRATE_BYTES") {
+            self.index.large_crate_bytes = bytes
+                .parse()
+                .context("DOCS_RS_MCP_INDEX_LARGE_CRATE_BYTES must be a number of bytes")?;
+        }
+        if let Some(enabled) = lookup("DOCS_RS_MCP_LOCAL_DOCS") {
+            self.local_docs.enabled = enabled
+                .parse()
+                .context("DOCS_RS_MCP_LOCAL_DOCS must be true or false")?;
+        }
+        if let Some(token) = lookup("DOCS_RS_MCP_ADMIN_TOKEN").filter(|t| !t.is_empty()) {
+            self.admin.token = Some(token);
+        }
+        if let Some(kind) = lookup("DOCS_RS_MCP_TRANSPORT") {
+            self.transport.kind = kind.parse()?;
+        }
+        if let Some(bind) = lookup("DOCS_RS_MCP_BIND").filter(|bind| !bind.is_empty()) {
+            self.transport.bind = bind;
+        }
+        if let Some(socket) = lookup("DOCS_RS_MCP_SOCKET").filter(|socket| !socket.is_empty()) {
+            self.transport.socket = Some(PathBuf::from(socket));
+        }
+        if let Some(token) = lookup("DOCS_RS_MCP_TRANSPORT_TOKEN").filter(|t| !t.is_empty()) {
+            self.transport.token = Some(token);
+        }
+        for registry in self.registries.iter_mut().filter(|r| r.token.is_none()) {
+            // The variable Cargo reads the token of the registry from
+            let key = format!(
+                "CARGO_REGISTRIES_{}_TOKEN",
+                registry.name.to_uppercase().replace('-', "_")
+            );
+            registry.token = lookup(&key).filter(|token| !token.is_empty());
+        }
+        if let Some(crates) = lookup("DOCS_RS_MCP_ALLOWED_CRATES") {
+            self.allow_list.allowed = crates
+                .split(',')
+                .map(str::trim)
+                .filter(|c| !c.is_empty())
+                .map(str::to_string)
+                .collect();
+        }
+        Ok(())
+    }
+
+    /// Applies the command line layer.
+    fn apply_overrides(&mut self, overrides: &ConfigOverrides) {
+        if let Some(level) = &overrides.log_level {
+            self.log_level.clone_from(level);
+        }
+        if let Some(url) = &overrides.docs_rs_url {
+            self.sources.docs_rs_url.clone_from(url);
+        }
+        if let Some(url) = &overrides.crates_io_url {
+            self.sources.crates_io_url.clone_from(url);
+        }
+        if let Some(dir) = &overrides.cache_dir {
+            self.cache.dir = Some(dir.clone());
+        }
+        if overrides.no_cache {
+            self.cache.enabled = false;
+        }
+        if overrides.offline {
+            self.offline = true;
+        }
+        if let Some(kind) = overrides.transport {
+            self.transport.kind = kind;
+        }
+        if let Some(bind) = &overrides.bind {
+            self.transport.bind.clone_from(bind);
+        }
+        if let Some(socket) = &overrides.socket {
+            self.transport.socket = Some(socket.clone());
+        }
+    }
+
+    /// Tracing filter directive, e.g. `info` or `docs_rs_mcp=debug`
+    pub fn log_level(&self) -> &str {
+        &self.log_level
+    }
+
+    /// Whether lookups are answered from the cache only. Cached copies are then never stale,
+    /// and anything not cached fails.
+    pub fn offline(&self) -> bool {
+        self.offline
+    }
+
+    /// Whether a cached copy `age` old is too old to be served
+    pub fn is_stale(&self, age: Duration) -> bool {
+        !self.offline && age > self.cache.ttl()
+    }
+
+    pub fn cache(&self) -> &CacheConfig {
+        &self.cache
+    }
+
+    pub fn sources(&self) -> &SourcesConfig {
+        &self.sources
+    }
+
+    pub fn allow_list(&self) -> &AllowListConfig {
+        &self.allow_list
+    }
+
+    pub fn http(&self) -> &HttpConfig {
+        &self.http
+    }
+
+    pub fn admin(&self) -> &AdminConfig {
+        &self.admin
+    }
+
+    /// Returns an error unless an admin tool called with `token` may run. Any client can reach
+    /// a network transport, so admin tools need a token there.
+    pub fn authorize_admin(&self, token: Option<&str>) -> Result<()> {
+        let kind = self.transport.kind();
+        if self.admin.enabled && !self.admin.has_token() && kind.is_network() {
+            bail!(
+                "Admin tools require admin.token to be set when serving over {}",
+                format!("{:?}", kind).to_lowercase()
+            );
+        }
+        self.admin.authorize(token)
+    }
+
+    pub fn local_docs(&self) -> &LocalDocsConfig {
+        &self.local_docs
+    }
+
+    pub fn transport(&self) -> &TransportConfig {
+        &self.transport
+    }
+
+    pub fn summary(&self) -> &SummaryConfig {
+        &self.summary
+    }
+
+    pub fn index(&self) -> &IndexConfig {
+        &self.index
+    }
+
+    /// Additional registries besides crates.io
+    pub fn registries(&self) -> &[RegistryConfig] {
+        &self.registries
+    }
+
+    /// Name of the registry the sources point at.
+    pub fn active_registry(&self) -> &str {
+        self.active_registry.as_deref().unwrap_or(DEFAULT_REGISTRY)
+    }
+
+    /// A copy of this configuration whose sources point at the named registry.
+    pub fn for_registry(&self, name: &str) -> Result<Self> {
+        let mut config = self.clone();
+        if name != DEFAULT_REGISTRY {
+            let registry = self
+                .registries
+                .iter()
+                .find(|registry| registry.name == name)
+                .ok_or_else(|| {
+                    anyhow!(
+                        "Unknown registry {}, expected one of: {}",
+                        name,
+                        self.registry_names().join(", ")
+                    )
+                })?;
+            config.sources.crates_io_url.clone_from(&registry.api_url);
+            config.sources.docs_rs_url.clone_from(&registry.docs_url);
+            config.sources.index_url = registry.index_url.clone().unwrap_or_default();
+        }
+        if let Some(url) = &self.docs_url_override {
+            config.sources.docs_rs_url.clone_from(url);
+        }
+        config.active_registry = Some(name.to_string());
+        Ok(config)
+    }
+
+    /// A copy of this configuration fetching documentation from `url` instead of docs.rs or the
+    /// registry's documentation host, for a tool call asking for a mirror.
+    pub fn with_docs_url(&self, url: &str) -> Result<Self> {
+        if !self.sources.allow_docs_base_url {
+            return Err(DocsRsMcpError::InvalidInput(
+                "docs_base_url is disabled by the server configuration".to_string(),
+            )
+            .into());
+        }
+        let parsed = Url::parse(url).map_err(|e| {
+            DocsRsMcpError::InvalidInput(format!("Invalid docs_base_url {}: {}", url, e))
+        })?;
+        if !matches!(parsed.scheme(), "http" | "https") {
+            return Err(DocsRsMcpError::InvalidInput(format!(
+                "docs_base_url must be an http or https URL, got {}",
+                url
+            ))
+            .into());
+        }
+        let mut config = self.clone();
+        config.sources.docs_rs_url = url.to_string();
+        config.docs_url_override = Some(url.to_string());
+        Ok(config)
+    }
+
+    /// Documentation host a tool call named with `docs_base_url`, if any
+    pub fn docs_url_override(&self) -> Option<&str> {
+        self.docs_url_override.as_deref()
+    }
+
+    /// Registry whose `crates` patterns claim `crate_name`, the first configured one winning.
+    pub fn routed_registry(&self, crate_name: &str) -> Option<&str> {
+        self.registries
+            .iter()
+            .find(|registry| registry.routes(crate_name))
+            .map(RegistryConfig::name)
+    }
+
+    /// Names of every registry, the built-in one first.
+    pub fn registry_names(&self) -> Vec<&str> {
+        std::iter::once(DEFAULT_REGISTRY)
+            .chain(
+                self.registries
+                    .iter()
+                    .map(|registry| registry.name.as_str()),
+            )
+            .collect()
+    }
+
+    /// Returns an error if the allow-list forbids looking up `crate_name`.
+    pub fn ensure_crate_allowed(&self, crate_name: &str) -> Result<()> {
+        if self.allow_list.is_allowed(crate_name) {
+            Ok(())
+        } else {
+            Err(DocsRsMcpError::InvalidInput(format!(
+                "Crate {} is not allowed by the server configuration",
+                crate_name
+            ))
+            .into())
+        }
+    }
+}
+
+impl CacheConfig {
+    pub fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn ttl(&self) -> Duration {
+        Duration::from_secs(self.ttl_secs)
+    }
+
+    /// How long Not Found answers are remembered, `None` when they aren't
+    pub fn not_found_ttl(&self) -> Option<Duration> {
+        (self.enabled && self.not_found_ttl_secs > 0)
+            .then(|| Duration::from_secs(self.not_found_ttl_secs))
+    }
+
+    /// Explicitly configured cache directory, if any
+    pub fn dir(&self) -> Option<&Path> {
+        self.dir.as_deref()
+    }
+
+    /// The configured cache directory, defaulting to the platform's, see [`paths::cache_dir`].
+    pub fn resolved_dir(&self) -> Option<PathBuf> {
+        self.dir.clone().or_else(paths::cache_dir)
+    }
+}
+
+impl SourcesConfig {
+    pub fn docs_rs_url(&self) -> &str {
+        self.docs_rs_url.trim_end_matches('/')
+    }
+
+    pub fn crates_io_url(&self) -> &str {
+        self.crates_io_url.trim_end_matches('/')
+    }
+
+    /// Sparse index of the registry, if it has one
+    pub fn index_url(&self) -> Option<&str> {
+        Some(self.index_url.trim_end_matches('/')).filter(|url| !url.is_empty())
+    }
+
+    /// OSV API to look up RustSec advisories in, unless disabled
+    pub fn osv_url(&self) -> Option<&str> {
+        Some(self.osv_url.trim_end_matches('/')).filter(|url| !url.is_empty())
+    }
+
+    /// GitHub API to look up repositories in, unless disabled
+    pub fn github_api_url(&self) -> Option<&str> {
+        Some(self.github_api_url.trim_end_matches('/')).filter(|url| !url.is_empty())
+    }
+
+    /// Sources in the order they should be tried
+    pub fn priority(&self) -> &[Source] {
+        &self.priority
+    }
+}
+
+/// Whether `crate_name` matches `pattern`, by prefix when it ends in `*`.
+fn matches_crate(pattern: &str, crate_name: &str) -> bool {
+    match pattern.strip_suffix('*') {
+        Some(prefix) => crate_name.starts_with(prefix),
+        None => pattern == crate_name,
+    }
+}
+
+impl AllowListConfig {
+    pub fn is_allowed(&self, crate_name: &str) -> bool {
+        let matches = |pattern: &String| matches_crate(pattern, crate_name);
+
+        !self.blocked.iter().any(matches)
+            && (self.allowed.is_empty() || self.allowed.iter().any(matches))
+    }
+}
+
+impl RegistryConfig {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn api_url(&self) -> &str {
+        self.api_url.trim_end_matches('/')
+    }
+
+    pub fn docs_url(&self) -> &str {
+        self.docs_url.trim_end_matches('/')
+    }
+
+    pub fn index_url(&self) -> Option<&str> {
+        self.index_url
+            .as_deref()
+            .map(|url| url.trim_end_matches('/'))
+    }
+
+    /// Whether `crate_name` is always looked up in this registry
+    pub fn routes(&self, crate_name: &str) -> bool {
+        self.crates
+            .iter()
+            .any(|pattern| matches_crate(pattern, crate_name))
+    }
+
+    /// The `Authorization` header to send to each host of the registry, none without a token
+    pub fn auth_headers(&self) -> Result<HashMap<String, HeaderMap>> {
+        let Some(token) = &self.token else {
+            return Ok(HashMap::new());
+        };
+        let mut value = HeaderValue::from_str(token)
+            .context(format!("Invalid token for registry {}", self.name))?;
+        value.set_sensitive(true);
+        Ok([
+            Some(self.api_url()),
+            Some(self.docs_url()),
+            self.index_url(),
+        ]
+        .into_iter()
+        .flatten()
+        .filter_map(|url| Some(Url::parse(url).ok()?.host_str()?.to_string()))
+        .map(|host| {
+            let mut headers = HeaderMap::new();
+            headers.insert(AUTHORIZATION, value.clone());
+            (host, headers)
+        })
+        .collect())
+    }
+}
+
+impl AdminConfig {
+    pub fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Whether admin tools must be called with a token
+    pub fn has_token(&self) -> bool {
+        self.token.is_some()
+    }
+
+    /// Returns an error unless admin tools are enabled and `token` matches the configured one.
+    pub fn authorize(&self, token: Option<&str>) -> Result<()> {
+        if !self.enabled {
+            bail!("Admin tools are disabled by the server configuration");
+        }
+        match &self.token {
+            Some(expected) if token != Some(expected.as_str()) => {
+                bail!("Admin tools require a valid token")
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+impl LocalDocsConfig {
+    pub fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn toolchain(&self) -> &str {
+        &self.toolchain
+    }
+
+    /// Git repository to build `crate_name` from instead of a registry, if configured
+    pub fn git(&self, crate_name: &str) -> Option<&str> {
+        self.git.get(crate_name).map(String::as_str)
+    }
+}
+
+impl TransportConfig {
+    /// Largest message the client accepts, `None` when unlimited
+    pub fn max_message_bytes(&self) -> Option<usize> {
+        (self.max_message_bytes > 0).then_some(self.max_message_bytes)
+    }
+
+    pub fn kind(&self) -> TransportKind {
+        self.kind
+    }
+
+    pub fn bind(&self) -> &str {
+        &self.bind
+    }
+
+    /// Socket the Unix socket transport listens on
+    pub fn socket(&self) -> PathBuf {
+        self.socket.clone().unwrap_or_else(paths::socket_path)
+    }
+
+    pub fn max_sessions(&self) -> usize {
+        self.max_sessions.max(1)
+    }
+
+    /// How long an HTTP session may go without messages before it is ended
+    pub fn session_idle(&self) -> Duration {
+        Duration::from_secs(self.session_idle_secs)
+    }
+
+    /// Whether HTTP clients must send a bearer token
+    pub fn has_token(&self) -> bool {
+        self.token.is_some()
+    }
+
+    /// Whether an HTTP request with `authorization`, the value of its `Authorization` header,
+    /// may use the server
+    pub fn authorize(&self, authorization: Option<&str>) -> bool {
+        match &self.token {
+            Some(token) => authorization
+                .and_then(|value| value.strip_prefix("Bearer "))
+                .is_some_and(|given| given.trim() == token),
+            None => true,
+        }
+    }
+}
+
+impl SummaryConfig {
+    /// Length above which descriptions are summarized, `None` when they never are
+    pub fn threshold_chars(&self) -> Option<usize> {
+        (self.threshold_chars > 0).then_some(self.threshold_chars)
+    }
+
+    pub fn max_chars(&self) -> usize {
+        self.max_chars
+    }
+
+    pub fn endpoint(&self) -> Option<&str> {
+        self.endpoint
+            .as_deref()
+            .filter(|endpoint| !endpoint.is_empty())
+    }
+
+    pub fn model(&self) -> &str {
+        &self.model
+    }
+}
+
+impl IndexConfig {
+    /// Size above which crates are indexed in the background, `None` when none are
+    pub fn large_crate_bytes(&self) -> Option<u64> {
+        (self.large_crate_bytes > 0).then_some(self.large_crate_bytes)
+    }
+
+    pub fn chunk_pages(&self) -> usize {
+        self.chunk_pages.max(1)
+    }
+}
+
+impl HttpConfig {
+    pub fn timeout(&self) -> Duration {
+        Duration::from_secs(self.timeout_secs)
+    }
+
+    pub fn requests_per_second(&self) -> f64 {
+        self.requests_per_second
+    }
+
+    pub fn burst(&self) -> u32 {
+        self.burst
+    }
+
+    pub fn user_agent(&self) -> &str {
+        self.user_agent.as_deref().unwrap_or(USER_AGENT)
+    }
+
+    /// Headers sent to every host
+    pub fn headers(&self) -> Result<HeaderMap> {
+        header_map(&self.headers)
+    }
+
+    /// Headers to add to requests by host, on top of those sent to every host
+    pub fn host_headers(&self) -> Result<HashMap<String, HeaderMap>> {
+        self.host_headers
+            .iter()
+            .map(|(host, headers)| Ok((host.clone(), header_map(headers)?)))
+            .collect()
+    }
+
+    /// Builds an async HTTP client honoring the timeout and proxy settings. In the browser, the
+    /// fetch API applies its own timeout and proxy, so those settings are ignored. Headers are
+    /// added per request, see [`HttpClient`](crate::tools::http::HttpClient).
+    pub fn async_client(&self) -> Result<reqwest::Client> {
+        let builder = reqwest::Client::builder().user_agent(self.user_agent());
+        #[cfg(not(target_arch = "wasm32"))]
+        let builder = {
+            let mut builder = builder.timeout(self.timeout());
+            if let Some(proxy) = self.proxy()? {
+                builder = builder.proxy(proxy);
+            }
+            builder
+        };
+        builder.build().context("Failed to build HTTP client")
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn proxy(&self) -> Result<Option<reqwest::Proxy>> {
+        self.proxy
+            .as_deref()
+            .map(|proxy| {
+                reqwest::Proxy::all(proxy).context(format!("Invalid proxy URL: {}", proxy))
+            })
+            .transpose()
+    }
+}
+
+/// Parses configured headers, naming the offending one when a name or value is invalid.
+fn header_map(headers: &BTreeMap<String, String>) -> Result<HeaderMap> {
+    headers
+        .iter()
+        .map(|(name, value)| {
+            let name = HeaderName::from_bytes(name.as_bytes())
+                .context(format!("Invalid HTTP header name: {}", name))?;
+            let mut value = HeaderValue::from_str(value)
+                .context(format!("Invalid value for HTTP header {}", name))?;
+            value.set_sensitive(true);
+            Ok((name, value))
+        })
+        .collect()
+}
+
+/// Settings given on the command line, which take precedence over every other layer. They are
+/// accepted before and after a command, see [`Cli`](crate::args::Cli).
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "native", derive(clap::Args))]
+pub struct ConfigOverrides {
+    /// Config file to read instead of the default one
+    #[cfg_attr(
+        feature = "native",
+        arg(long = "config", value_name = "path", global = true)
+    )]
+    config_path: Option<PathBuf>,
+    /// Log level, e.g. info or debug
+    #[cfg_attr(feature = "native", arg(long, value_name = "level", global = true))]
+    log_level: Option<String>,
+    /// Base URL of docs.rs
+    #[cfg_attr(feature = "native", arg(long, value_name = "url", global = true))]
+    docs_rs_url: Option<String>,
+    /// Base URL of the crates.io API
+    #[cfg_attr(feature = "native", arg(long, value_name = "url", global = true))]
+    crates_io_url: Option<String>,
+    /// Directory of the documentation cache
+    #[cfg_attr(feature = "native", arg(long, value_name = "path", global = true))]
+    cache_dir: Option<PathBuf>,
+    /// Fetch everything without using the cache
+    #[cfg_attr(feature = "native", arg(long, global = true))]
+    no_cache: bool,
+    /// Answer from the cache only, however old, without sending requests
+    #[cfg_attr(
+        feature = "native",
+        arg(long, global = true, conflicts_with = "no_cache")
+    )]
+    offline: bool,
+    /// Record the tool calls of the MCP session and the pages they fetched to this file, for
+    /// bug reports
+    #[cfg_attr(
+        feature = "native",
+        arg(long = "record", value_name = "path", global = true)
+    )]
+    record_path: Option<PathBuf>,
+    /// Serve MCP over stdio, the default, http, tcp or unix
+    #[cfg_attr(feature = "native", arg(long, value_name = "kind", global = true))]
+    transport: Option<TransportKind>,
+    /// Address the http and tcp transports listen on, 127.0.0.1:8080 by default
+    #[cfg_attr(feature = "native", arg(long, value_name = "addr", global = true))]
+    bind: Option<String>,
+    /// Socket the unix transport listens on
+    #[cfg_attr(feature = "native", arg(long, value_name = "path", global = true))]
+    socket: Option<PathBuf>,
+}
+
+impl ConfigOverrides {
+    /// Fetches everything without the cache, as `--no-cache` does.
+    pub fn without_cache(mut self) -> Self {
+        self.no_cache = true;
+        self
+    }
+
+    pub fn record_path(&self) -> Option<&Path> {
+        self.record_path.as_deref()
+    }
+}
+
+/// `config.toml` in the platform's config directory, see [`paths::config_dir`].
+pub fn default_config_path() -> Option<PathBuf> {
+    paths::config_dir().map(|dir| dir.join("config.toml"))
+}
+
+/// The config file given by `--config` or `DOCS_RS_MCP_CONFIG`, which must exist
+fn explicit_config_path(overrides: &ConfigOverrides) -> Option<PathBuf> {
+    overrides.config_path.clone().or_else(|| {
+        std::env::var_os(CONFIG_ENV)
+            .filter(|path| !path.is_empty())
+            .map(PathBuf::from)
+    })
+}
+
+/// The config file [`init`] and [`reload`] read, whether or not it exists.
+pub fn config_path() -> Option<PathBuf> {
+    let command_line = overrides().get().cloned().unwrap_or_default();
+    explicit_config_path(&command_line).or_else(default_config_path)
+}
+
+fn global() -> &'static RwLock<Arc<Config>> {
+    static CONFIG: OnceLock<RwLock<Arc<Config>>> = OnceLock::new();
+    CONFIG.get_or_init(|| RwLock::new(Arc::new(Config::from_env())))
+}
+
+/// Command line overrides given to [`init`], re-applied on every [`reload`]
+fn overrides() -> &'static OnceLock<ConfigOverrides> {
+    static OVERRIDES: OnceLock<ConfigOverrides> = OnceLock::new();
+    &OVERRIDES
+}
+
+/// Loads the configuration for the given command line overrides and installs it, remembering
+/// the overrides so that [`reload`] keeps honoring them.
+pub fn init(command_line: ConfigOverrides) -> Result<Arc<Config>> {
+    let config = Config::load(&command_line)?;
+    // Only the first call records its overrides; the command line can't change afterwards
+    let _ = overrides().set(command_line);
+    install(config);
+    Ok(current())
+}
+
+/// Re-reads the config file and environment and installs the result. The previous configuration
+/// stays in effect if the new one fails to load.
+///
+/// The log level is only read at startup, so changing it still requires a restart.
+pub fn reload() -> Result<Arc<Config>> {
+    let command_line = overrides().get().cloned().unwrap_or_default();
+    let config = Config::load(&command_line).context("Failed to reload configuration")?;
+    install(config);
+    tracing::info!("Configuration reloaded");
+    Ok(current())
+}
+
+thread_local! {
+    /// Configuration overriding the global one for the duration of [`scoped`]
+    static SCOPED: RefCell<Option<Arc<Config>>> = const { RefCell::new(None) };
+}
+
+tokio::task_local! {
+    /// Configuration overriding the global one for the duration of [`scoped_task`]
+    static TASK_SCOPED: Arc<Config>;
+}
+
+/// Runs `f` with `config` returned by [`current`] on this thread, e.g. to point every request
+/// of a tool call at another registry. The override covers synchronous code only; use
+/// [`scoped_task`] for a future, which may be polled on any thread.
+pub fn scoped<T>(config: Config, f: impl FnOnce() -> T) -> T {
+    struct Restore(Option<Arc<Config>>);
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            let previous = self.0.take();
+            SCOPED.with(|scoped| *scoped.borrow_mut() = previous);
+        }
+    }
+
+    let previous = SCOPED.with(|scoped| scoped.borrow_mut().replace(Arc::new(config)));
+    let _restore = Restore(previous);
+    f()
+}
+
+/// Runs `future` with `config` returned by [`current`] wherever the future is polled, the
+/// async counterpart of [`scoped`].
+pub async fn scoped_task<F: Future>(config: Config, future: F) -> F::Output {
+    TASK_SCOPED.scope(Arc::new(config), future).await
+}
+
+/// The configuration currently in effect.
+pub fn current() -> Arc<Config> {
+    if let Ok(config) = TASK_SCOPED.try_with(Arc::clone) {
+        return config;
+    }
+    if let Some(config) = SCOPED.with(|scoped| scoped.borrow().clone()) {
+        return config;
+    }
+    let config = match global().read() {
+        Ok(config) => config,
+        Err(poisoned) => poisoned.into_inner(),
+    };
+    Arc::clone(&config)
+}
+
+/// Makes `config` the configuration in effect for the whole process.
+pub fn install(config: Config) {
+    let mut current = match global().write() {
+        Ok(current) => current,
+        Err(poisoned) => poisoned.into_inner(),
+    };
+    *current = Arc::new(config);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::args::Cli;
+    use pretty_assertions::assert_eq;
+    use std::collections::HashMap;
+
+    /// The overrides given by config flags `args`
+    fn command_line(args: &[&str]) -> Result<ConfigOverrides> {
+        Ok(Cli::parse_args(args.iter().copied())?.overrides)
+    }
+
+    #[test]
+    fn test_partial_file_keeps_defaults() -> Result<()> {
+        let config = Config::from_toml(
+            r#"
+            log_level = "info"
+
+            [sources]
+            docs_rs_url = "https://docs.internal.example/"
+            priority = ["docs.rs"]
+
+            [allow_list]
+            blocked = ["failure"]
+            "#,
+        )?;
+
+        assert_eq!(config.log_level(), "info", "File should set the log level");
+        assert_eq!(
+            config.sources().docs_rs_url(),
+            "https://docs.internal.example",
+            "Trailing slash should be trimmed"
+        );
+        assert_eq!(
+            config.sources().crates_io_url(),
+            "https://crates.io",
+            "Unset keys should keep their defaults"
+        );
+        assert_eq!(config.sources().priority(), &[Source::DocsRs]);
+        assert!(config.cache().enabled(), "Cache should default to enabled");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_local_docs() -> Result<()> {
+        let mut config = Config::from_toml(
+            r#"
+            [local_docs.git]
+            acme-utils = "https://git.acme.internal/utils"
+            "#,
+        )?;
+        assert!(
+            !config.local_docs().enabled(),
+            "Local docs should be opt-in"
+        );
+        assert_eq!(config.local_docs().toolchain(), "nightly");
+        assert_eq!(
+            config.local_docs().git("acme-utils"),
+            Some("https://git.acme.internal/utils")
+        );
+
+        config.apply_env(|key| (key == "DOCS_RS_MCP_LOCAL_DOCS").then(|| "true".to_string()))?;
+        assert!(config.local_docs().enabled());
+        Ok(())
+    }
+
+    #[test]
+    fn test_index() -> Result<()> {
+        let mut config = Config::from_toml(
+            r#"
+            [index]
+            chunk_pages = 0
+            "#,
+        )?;
+        assert_eq!(config.index().large_crate_bytes(), Some(2_000_000));
+        assert_eq!(
+            config.index().chunk_pages(),
+            1,
+            "Chunks read at least a page"
+        );
+
+        config.apply_env(|key| {
+            (key == "DOCS_RS_MCP_INDEX_LARGE_CRATE_BYTES").then(|| "0".to_string())
+        })?;
+        assert_eq!(config.index().large_crate_bytes(), None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_http_headers() -> Result<()> {
+        let mut config = Config::from_toml(
+            r#"
+            [http.headers]
+            From = "ops@example.com"
+
+            [http.host_headers."docs.acme.internal"]
+            Authorization = "Bearer secret"
+            "#,
+        )?;
+        assert!(
+            config.http().user_agent().starts_with(concat!(
+                "docs-rs-mcp/",
+                env!("CARGO_PKG_VERSION"),
+                " (+https://"
+            )),
+            "{}",
+            config.http().user_agent()
+        );
+        let host_headers = config.http().host_headers()?;
+        assert_eq!(
+            host_headers["docs.acme.internal"]["authorization"],
+            "Bearer secret"
+        );
+        assert!(
+            !toml::to_string(&config)?.contains("secret"),
+            "Headers may hold credentials"
+        );
+
+        config.apply_env(|key| {
+            (key == "DOCS_RS_MCP_USER_AGENT").then(|| "acme-docs/1.0".to_string())
+        })?;
+        assert_eq!(config.http().user_agent(), "acme-docs/1.0");
+
+        let invalid = Config::from_toml("[http.headers]\n\"Bad Name\" = \"x\"\n")?;
+        let error = invalid.http().headers().unwrap_err();
+        assert!(format!("{:#}", error).contains("Bad Name"), "{:#}", error);
+        Ok(())
+    }
+
+    #[test]
+    fn test_unknown_keys_are_rejected() {
+        let result = Config::from_toml("[cache]\nttl = 10\n");
+        assert!(result.is_err(), "Typos in config keys should be reported");
+    }
+
+    #[test]
+    fn test_layer_precedence() -> Result<()> {
+        let mut config = Config::from_toml("log_level = \"info\"\n[http]\ntimeout_secs = 5\n")?;
+        let env: HashMap<&str, &str> = HashMap::from([
+            ("DOCS_RS_MCP_LOG_LEVEL", "warn"),
+            ("DOCS_RS_MCP_HTTP_TIMEOUT_SECS", "10"),
+            ("DOCS_RS_MCP_HTTP_REQUESTS_PER_SECOND", "0.5"),
+            ("DOCS_RS_URL", "http://localhost:3000"),
+        ]);
+        config.apply_env(|key| env.get(key).map(|v| v.to_string()))?;
+        let overrides = command_line(&["--log-level", "error", "--no-cache"])?;
+        config.apply_overrides(&overrides);
+
+        assert_eq!(config.log_level(), "error", "CLI should override env");
+        assert_eq!(
+            config.http().timeout(),
+            Duration::from_secs(10),
+            "Env should override the file"
+        );
+        assert_eq!(config.http().requests_per_second(), 0.5);
+        assert_eq!(config.sources().docs_rs_url(), "http://localhost:3000");
+        assert!(
+            !config.cache().enabled(),
+            "--no-cache should disable the cache"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid_env_value() {
+        let mut config = Config::default();
+        let result = config
+            .apply_env(|key| (key == "DOCS_RS_MCP_CACHE_TTL_SECS").then(|| "a day".to_string()));
+        assert!(result.is_err(), "Non-numeric TTL should be rejected");
+    }
+
+    #[test]
+    fn test_parse_args() -> Result<()> {
+        let overrides = command_line(&[
+            "--config=/etc/docs-rs-mcp.toml",
+            "--cache-dir",
+            "/tmp/cache",
+            "--record=session.json",
+            "--transport",
+            "http",
+            "--bind=0.0.0.0:9000",
+            "--socket=/run/docs-rs-mcp.sock",
+        ])?;
+        assert_eq!(
+            overrides.config_path,
+            Some(PathBuf::from("/etc/docs-rs-mcp.toml")),
+            "Inline flag values should be supported"
+        );
+        assert_eq!(overrides.cache_dir, Some(PathBuf::from("/tmp/cache")));
+        assert_eq!(overrides.record_path(), Some(Path::new("session.json")));
+        let mut config = Config::default();
+        config.apply_overrides(&overrides);
+        assert_eq!(config.transport().kind(), TransportKind::Http);
+        assert_eq!(config.transport().bind(), "0.0.0.0:9000");
+        assert_eq!(
+            config.transport().socket(),
+            PathBuf::from("/run/docs-rs-mcp.sock")
+        );
+        assert!(command_line(&["--transport=sse"]).is_err());
+        assert!(
+            command_line(&["--offline", "--no-cache"]).is_err(),
+            "Offline lookups need the cache"
+        );
+
+        let unknown = command_line(&["--verbose"]);
+        assert!(unknown.is_err(), "Unknown flags should be rejected");
+
+        let missing = command_line(&["--log-level"]);
+        assert!(missing.is_err(), "Flags without values should be rejected");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_reload_picks_up_file_changes() -> Result<()> {
+        let path = std::env::temp_dir().join(format!(
+            "docs-rs-mcp-reload-test-{}.toml",
+            std::process::id()
+        ));
+        std::fs::write(&path, "[cache]\nttl_secs = 60\n")?;
+        let command_line = ConfigOverrides {
+            config_path: Some(path.clone()),
+            ..ConfigOverrides::default()
+        };
+        assert_eq!(init(command_line)?.cache().ttl(), Duration::from_secs(60));
+
+        std::fs::write(&path, "[cache]\nttl_secs = 120\n")?;
+        assert_eq!(
+            reload()?.cache().ttl(),
+            Duration::from_secs(120),
+            "Reload should pick up the edited file"
+        );
+
+        std::fs::write(&path, "[cache]\nttl_secs = \"forever\"\n")?;
+        assert!(reload().is_err(), "Invalid files should be reported");
+        assert_eq!(
+            current().cache().ttl(),
+            Duration::from_secs(120),
+            "A failed reload should keep the previous configuration"
+        );
+
+        std::fs::remove_file(&path)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_admin_authorization() -> Result<()> {
+        assert!(
+            Config::default().admin().authorize(None).is_ok(),
+            "Admin tools are open when no token is configured"
+        );
+
+        let config = Config::from_toml("[admin]\ntoken = \"s3cret\"\n")?;
+        assert!(config.admin().authorize(Some("s3cret")).is_ok());
+        assert!(config.admin().authorize(Some("guess")).is_err());
+        assert!(config.admin().authorize(None).is_err());
+        assert!(
+            !serde_json::to_string(&config)?.contains("s3cret"),
+            "The token must not be serialized"
+        );
+
+        let disabled = Config::from_toml("[admin]\nenabled = false\n")?;
+        assert!(disabled.admin().authorize(None).is_err());
+
+        assert!(Config::default().authorize_admin(None).is_ok());
+        let http = Config::from_toml("[transport]\nkind = \"http\"\n")?;
+        assert!(
+            http.authorize_admin(None).is_err(),
+            "Admin tools are closed over HTTP until a token is configured"
+        );
+        let tcp = Config::from_toml("[transport]\nkind = \"tcp\"\n[admin]\ntoken = \"s3cret\"\n")?;
+        assert!(tcp.authorize_admin(Some("s3cret")).is_ok());
+        assert!(tcp.authorize_admin(None).is_err());
+        let unix = Config::from_toml("[transport]\nkind = \"unix\"\n")?;
+        assert!(unix.authorize_admin(None).is_ok());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_offline() -> Result<()> {
+        let mut config = Config::from_toml("[cache]\nttl_secs = 60\n")?;
+        assert!(!config.offline());
+        assert!(config.is_stale(Duration::from_secs(61)));
+        assert!(!config.is_stale(Duration::from_secs(59)));
+
+        config.apply_env(|key| (key == "DOCS_RS_MCP_OFFLINE").then(|| "true".to_string()))?;
+        assert!(config.offline());
+        assert!(
+            !config.is_stale(Duration::from_secs(365 * 24 * 60 * 60)),
+            "Nothing is stale when nothing can be fetched again"
+        );
+
+        let mut config = Config::default();
+        config.apply_overrides(&command_line(&["--offline"])?);
+        assert!(config.offline());
+        Ok(())
+    }
+
+    #[test]
+    fn test_transport() -> Result<()> {
+        let transport = Config::default().transport().clone();
+        assert_eq!(transport.kind(), TransportKind::Stdio);
+        assert!(
+            transport.authorize(None),
+            "Open when no token is configured"
+        );
+
+        let mut config = Config::from_toml("[transport]\nkind = \"http\"\n")?;
+        config.apply_env(|key| {
+            (key == "DOCS_RS_MCP_TRANSPORT_TOKEN").then(|| "s3cret".to_string())
+        })?;
+        let transport = config.transport();
+        assert_eq!(transport.kind(), TransportKind::Http);
+        assert_eq!(transport.bind(), "127.0.0.1:8080");
+        assert_eq!(transport.socket(), paths::socket_path());
+        assert_eq!(transport.max_sessions(), 100);
+        assert_eq!(transport.session_idle(), Duration::from_secs(1800));
+        assert!(transport.authorize(Some("Bearer s3cret")));
+        assert!(!transport.authorize(Some("Bearer guess")));
+        assert!(!transport.authorize(Some("s3cret")));
+        assert!(!transport.authorize(None));
+        assert!(
+            !serde_json::to_string(&config)?.contains("s3cret"),
+            "The token must not be serialized"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_registries() -> Result<()> {
+        let config = Config::from_toml(
+            r#"
+            [[registries]]
+            name = "acme"
+            api_url = "https://registry.acme.internal/"
+            docs_url = "https://docs.acme.internal"
+            "#,
+        )?;
+        assert_eq!(config.registry_names(), vec!["crates-io", "acme"]);
+
+        let acme = config.for_registry("acme")?;
+        assert_eq!(acme.active_registry(), "acme");
+        assert_eq!(
+            acme.sources().crates_io_url(),
+            "https://registry.acme.internal"
+        );
+        assert_eq!(acme.sources().docs_rs_url(), "https://docs.acme.internal");
+        assert_eq!(
+            acme.sources().index_url(),
+            None,
+            "Registries without an index don't fall back to the crates.io index"
+        );
+
+        let default = config.for_registry(DEFAULT_REGISTRY)?;
+        assert_eq!(default.sources().docs_rs_url(), "https://docs.rs");
+        assert_eq!(
+            default.sources().index_url(),
+            Some("https://index.crates.io")
+        );
+        assert!(config.for_registry("nope").is_err());
+
+        let scoped_url = scoped(acme, || current().sources().docs_rs_url().to_string());
+        assert_eq!(scoped_url, "https://docs.acme.internal");
+        assert_eq!(
+            current().active_registry(),
+            DEFAULT_REGISTRY,
+            "The override should end with the scope"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_with_docs_url() -> Result<()> {
+        assert!(
+            Config::default()
+                .with_docs_url("http://localhost:3000")
+                .is_err(),
+            "Off unless enabled"
+        );
+        let config = Config::from_toml(
+            r#"
+            [sources]
+            allow_docs_base_url = true
+
+            [[registries]]
+            name = "acme"
+            api_url = "https://registry.acme.internal"
+            docs_url = "https://docs.acme.internal"
+            "#,
+        )?;
+        let mirrored = config.with_docs_url("http://localhost:3000")?;
+        assert_eq!(mirrored.sources().docs_rs_url(), "http://localhost:3000");
+        assert_eq!(
+            mirrored.for_registry("acme")?.sources().docs_rs_url(),
+            "http://localhost:3000",
+            "The call's mirror wins over the registry's host"
+        );
+        assert_eq!(
+            mirrored.for_registry("acme")?.sources().crates_io_url(),
+            "https://registry.acme.internal"
+        );
+        assert!(config.with_docs_url("file:///etc").is_err());
+
+        assert_eq!(mirrored.docs_url_override(), Some("http://localhost:3000"));
+        assert_eq!(config.docs_url_override(), None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_registry_routing_and_tokens() -> Result<()> {
+        let mut config = Config::from_toml(
+            r#"
+            [[registries]]
+            name = "acme-internal"
+            api_url = "https://registry.acme.internal"
+            docs_url = "https://docs.acme.internal"
+            crates = ["acme-*", "billing"]
+
+            [[registries]]
+            name = "mirror"
+            api_url = "https://mirror.acme.internal"
+            docs_url = "https://mirror.acme.internal"
+            token = "configured"
+            "#,
+        )?;
+        assert_eq!(config.routed_registry("acme-utils"), Some("acme-internal"));
+        assert_eq!(config.routed_registry("billing"), Some("acme-internal"));
+        assert_eq!(config.routed_registry("billing-core"), None);
+        assert_eq!(config.routed_registry("serde"), None);
+
+        config.apply_env(|key| {
+            key.starts_with("CARGO_REGISTRIES_")
+                .then(|| format!("from {}", key))
+        })?;
+        let acme = &config.registries()[0];
+        assert_eq!(
+            acme.auth_headers()?["docs.acme.internal"]["authorization"],
+            "from CARGO_REGISTRIES_ACME_INTERNAL_TOKEN"
+        );
+        assert_eq!(
+            config.registries()[1].auth_headers()?["mirror.acme.internal"]["authorization"],
+            "configured",
+            "The config file wins over Cargo's environment"
+        );
+        assert!(
+            !serde_json::to_string(&config)?.contains("configured"),
+            "Tokens must not be serialized"
+        );
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_scoped_task() -> Result<()> {
+        let acme = Config::from_toml(
+            r#"
+            [[registries]]
+            name = "acme"
+            api_url = "https://registry.acme.internal"
+            docs_url = "https://docs.acme.internal"
+            "#,
+        )?
+        .for_registry("acme")?;
+
+        let docs_rs_url = scoped_task(acme, async {
+            // Resume on whichever worker picks the task up next
+            tokio::task::yield_now().await;
+            current().sources().docs_rs_url().to_string()
+        })
+        .await;
+        assert_eq!(docs_rs_url, "https://docs.acme.internal");
+        assert_eq!(current().active_registry(), DEFAULT_REGISTRY);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_allow_list() -> Result<()> {
+        let config = Config::from_toml(
+            "[allow_list]\nallowed = [\"tokio*\", \"serde\"]\nblocked = [\"tokio-core\"]\n",
+        )?;
+
+        assert!(config.ensure_crate_allowed("tokio").is_ok(), "Prefix match");
+        assert!(
+            config.ensure_crate_allowed("tokio-util").is_ok(),
+            "Prefix match"
+        );
+        assert!(config.ensure_crate_allowed("serde").is_ok(), "Exact match");
+        assert!(
+            config.ensure_crate_allowed("serde_json").is_err(),
+            "Exact entries shouldn't match by prefix"
+        );
+        assert!(
+            config.ensure_crate_allowed("tokio-core").is_err(),
+            "Blocked entries win over allowed ones"
+        );
+        assert!(
+            Config::default().ensure_crate_allowed("anything").is_ok(),
+            "Empty allow-list allows everything"
+        );
+
+        Ok(())
+    }
+}
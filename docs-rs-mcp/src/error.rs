@@ -0,0 +1,355 @@
+//! Errors the tools report to library consumers and MCP clients. Tools build on anyhow
+//! internally; failures a caller can act on are raised as a [`DocsRsMcpError`] inside the
+//! chain, and [`DocsRsMcpError::classify`] recovers one from any tool error.
+
+#[cfg(feature = "native")]
+use mcp_sdk::{
+    tools::Tool,
+    types::{CallToolResponse, ToolResponseContent},
+};
+#[cfg(feature = "native")]
+use serde::de::DeserializeOwned;
+#[cfg(feature = "native")]
+use serde_json::json;
+
+use crate::tools::selectors;
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[non_exhaustive]
+pub enum DocsRsMcpError {
+    /// The crate, version, item or page doesn't exist
+    #[error("Not found: {0}")]
+    NotFound(String),
+    /// An item isn't in the crate, with the names closest to the one asked for
+    #[error("Not found: {what}")]
+    ItemNotFound {
+        what: String,
+        did_you_mean: Vec<String>,
+    },
+    /// docs.rs, crates.io or a registry answered with an error status
+    #[error("HTTP {status} from {url}")]
+    UpstreamHttp { url: String, status: u16 },
+    /// A page or API response couldn't be parsed
+    #[error("Failed to parse {0}")]
+    ParseFailure(String),
+    /// A docs page lacks the markup the tool reads, e.g. one rendered by an unknown rustdoc
+    #[error("Unsupported page layout: {0}")]
+    UnsupportedLayout(String),
+    /// A request to docs.rs, crates.io or a registry timed out
+    #[error("Timed out: {0}")]
+    Timeout(String),
+    /// The tool arguments are malformed or not allowed by the server configuration
+    #[error("Invalid input: {0}")]
+    InvalidInput(String),
+    /// Any other failure, with its context chain
+    #[error("{0}")]
+    Other(String),
+}
+
+impl DocsRsMcpError {
+    /// Stable name of the variant, as sent to MCP clients
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::NotFound(_) | Self::ItemNotFound { .. } => "not_found",
+            Self::UpstreamHttp { .. } => "upstream_http",
+            Self::ParseFailure(_) => "parse_failure",
+            Self::UnsupportedLayout(_) => "unsupported_layout",
+            Self::Timeout(_) => "timeout",
+            Self::InvalidInput(_) => "invalid_input",
+            Self::Other(_) => "other",
+        }
+    }
+
+    /// The typed error behind a tool error: one raised by the tool itself, or else one
+    /// inferred from the HTTP, JSON or selector error that caused it.
+    pub fn classify(error: &anyhow::Error) -> Self {
+        if let Some(typed) = error.chain().find_map(|e| e.downcast_ref::<Self>()) {
+            return typed.clone();
+        }
+        let message = format!("{:#}", error);
+        for cause in error.chain() {
+            if let Some(http) = cause.downcast_ref::<reqwest::Error>() {
+                if http.is_timeout() {
+                    return Self::Timeout(message);
+                }
+                if let (Some(status), Some(url)) = (http.status(), http.url()) {
+                    return Self::from_status(url.as_str(), status.as_u16());
+                }
+            }
+            if cause.is::<serde_json::Error>() || cause.is::<selectors::ParseError>() {
+                return Self::ParseFailure(message);
+            }
+        }
+        Self::Other(message)
+    }
+
+    /// Error for an unsuccessful HTTP response; a 404 means what was asked for doesn't exist.
+    pub(crate) fn from_status(url: &str, status: u16) -> Self {
+        if status == 404 {
+            Self::NotFound(url.to_string())
+        } else {
+            Self::UpstreamHttp {
+                url: url.to_string(),
+                status,
+            }
+        }
+    }
+
+    /// The URL whose request failed, when the error comes from one
+    pub fn url(&self) -> Option<&str> {
+        match self {
+            Self::UpstreamHttp { url, .. } => Some(url),
+            Self::NotFound(what) if is_url(what) => Some(what),
+            _ => None,
+        }
+    }
+
+    /// The HTTP status answered for [`Self::url`]
+    pub fn status(&self) -> Option<u16> {
+        match self {
+            Self::UpstreamHttp { status, .. } => Some(*status),
+            Self::NotFound(what) if is_url(what) => Some(404),
+            _ => None,
+        }
+    }
+
+    /// What a client can try next to get past the error
+    pub fn suggestions(&self) -> Vec<&'static str> {
+        match self {
+            Self::NotFound(_) => vec![
+                "Check the spelling of the crate name; crate_info shows whether it exists and its latest version",
+                "Leave out the version to use the latest release",
+                "Find the item's exact name and path with search_in_crate or crate_items",
+            ],
+            Self::ItemNotFound { .. } => vec![
+                "Retry with one of the names in did_you_mean, if any",
+                "Find the item's exact name and path with search_in_crate or crate_items",
+            ],
+            Self::UpstreamHttp { status: 429, .. } => {
+                vec!["Rate limited: wait a little before retrying"]
+            }
+            Self::UpstreamHttp { status: 401 | 403, .. } => vec![
+                "The registry refused the request: set a token for it under [registries] or in CARGO_REGISTRIES_<NAME>_TOKEN",
+            ],
+            Self::UpstreamHttp { status, .. } if *status >= 500 => {
+                vec!["The server is having trouble: retry later"]
+            }
+            Self::UpstreamHttp { .. } => Vec::new(),
+            Self::Timeout(_) => vec![
+                "Retry; if it keeps timing out, raise http.timeout_secs",
+            ],
+            Self::ParseFailure(_) | Self::UnsupportedLayout(_) => vec![
+                "Read the item's source with get_source instead",
+            ],
+            Self::InvalidInput(_) => vec!["Check the arguments against the tool's input schema"],
+            Self::Other(_) => Vec::new(),
+        }
+    }
+
+    /// Names of items close to the one that wasn't found, closest first
+    pub fn did_you_mean(&self) -> &[String] {
+        match self {
+            Self::ItemNotFound { did_you_mean, .. } => did_you_mean,
+            _ => &[],
+        }
+    }
+
+    /// Tool response reporting the error to an MCP client as
+    /// `{"error": {"kind", "message", "url", "status", "suggestions", "did_you_mean"}}`,
+    /// leaving out the fields that don't apply.
+    #[cfg(feature = "native")]
+    pub fn to_response(&self) -> CallToolResponse {
+        let mut error = json!({
+            "kind": self.kind(),
+            "message": self.to_string()
+        });
+        if let Some(url) = self.url() {
+            error["url"] = json!(url);
+        }
+        if let Some(status) = self.status() {
+            error["status"] = json!(status);
+        }
+        let suggestions = self.suggestions();
+        if !suggestions.is_empty() {
+            error["suggestions"] = json!(suggestions);
+        }
+        if !self.did_you_mean().is_empty() {
+            error["did_you_mean"] = json!(self.did_you_mean());
+        }
+        let payload = json!({ "error": error });
+        CallToolResponse {
+            content: vec![ToolResponseContent::Text {
+                text: serde_json::to_string_pretty(&payload).unwrap_or_else(|_| self.to_string()),
+            }],
+            is_error: Some(true),
+            meta: None,
+        }
+    }
+}
+
+/// Whether `what` a [`DocsRsMcpError::NotFound`] names is the URL that answered 404
+fn is_url(what: &str) -> bool {
+    what.starts_with("https://") || what.starts_with("http://")
+}
+
+/// Calling a tool with failures returned as a [`DocsRsMcpError`] to match on, for embedding
+/// the tools in another program.
+#[cfg(feature = "native")]
+pub trait TypedCall {
+    fn call_typed(
+        &self,
+        input: Option<serde_json::Value>,
+    ) -> Result<CallToolResponse, DocsRsMcpError>;
+}
+
+#[cfg(feature = "native")]
+impl<T: Tool + ?Sized> TypedCall for T {
+    fn call_typed(
+        &self,
+        input: Option<serde_json::Value>,
+    ) -> Result<CallToolResponse, DocsRsMcpError> {
+        self.call(input)
+            .map_err(|error| DocsRsMcpError::classify(&error))
+    }
+}
+
+/// Deserializes the arguments of a tool call, reporting malformed ones as invalid input.
+#[cfg(feature = "native")]
+pub(crate) fn parse_args<T: DeserializeOwned>(
+    input: Option<serde_json::Value>,
+) -> Result<T, DocsRsMcpError> {
+    serde_json::from_value(input.unwrap_or_default())
+        .map_err(|e| DocsRsMcpError::InvalidInput(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::{anyhow, Context};
+
+    #[test]
+    fn test_classify() {
+        let raised: anyhow::Result<()> = Err(DocsRsMcpError::from_status(
+            "https://docs.rs/demo/9.9.9/demo/",
+            404,
+        ))
+        .context("Failed to fetch the crate root");
+        assert_eq!(
+            DocsRsMcpError::classify(&raised.unwrap_err()),
+            DocsRsMcpError::NotFound("https://docs.rs/demo/9.9.9/demo/".to_string())
+        );
+
+        let json = serde_json::from_str::<serde_json::Value>("{")
+            .context("Failed to parse the crates.io response")
+            .unwrap_err();
+        assert_eq!(DocsRsMcpError::classify(&json).kind(), "parse_failure");
+
+        let selector =
+            anyhow::Error::new(selectors::parse("[id='it's']", "struct page").unwrap_err());
+        assert_eq!(DocsRsMcpError::classify(&selector).kind(), "parse_failure");
+
+        assert_eq!(
+            DocsRsMcpError::classify(&anyhow!("Something else")),
+            DocsRsMcpError::Other("Something else".to_string())
+        );
+    }
+
+    #[test]
+    fn test_parse_args() {
+        #[derive(Debug, serde::Deserialize)]
+        struct Params {
+            #[allow(dead_code)]
+            crate_name: String,
+        }
+
+        let error = parse_args::<Params>(Some(json!({ "version": "1.0.0" }))).unwrap_err();
+        assert_eq!(error.kind(), "invalid_input");
+        assert!(error.to_string().contains("crate_name"), "{}", error);
+    }
+
+    #[test]
+    fn test_to_response() {
+        let response = DocsRsMcpError::UpstreamHttp {
+            url: "https://crates.io/api/v1/crates/demo".to_string(),
+            status: 503,
+        }
+        .to_response();
+        assert_eq!(response.is_error, Some(true));
+        let ToolResponseContent::Text { text } = &response.content[0] else {
+            panic!("Expected a text response");
+        };
+        let payload: serde_json::Value = serde_json::from_str(text).unwrap();
+        assert_eq!(payload["error"]["kind"], "upstream_http");
+        assert_eq!(
+            payload["error"]["message"],
+            "HTTP 503 from https://crates.io/api/v1/crates/demo"
+        );
+        assert_eq!(
+            payload["error"]["url"],
+            "https://crates.io/api/v1/crates/demo"
+        );
+        assert_eq!(payload["error"]["status"], 503);
+        assert!(payload["error"]["suggestions"][0]
+            .as_str()
+            .is_some_and(|s| s.contains("retry")));
+
+        let ToolResponseContent::Text { text } =
+            &DocsRsMcpError::Other("Something else".to_string())
+                .to_response()
+                .content[0]
+        else {
+            panic!("Expected a text response");
+        };
+        let payload: serde_json::Value = serde_json::from_str(text).unwrap();
+        assert_eq!(
+            payload,
+            json!({ "error": { "kind": "other", "message": "Something else" } }),
+            "Fields that don't apply are left out"
+        );
+    }
+
+    #[test]
+    fn test_not_found_details() {
+        let page = DocsRsMcpError::from_status("https://docs.rs/demo/latest/demo/", 404);
+        assert_eq!(page.url(), Some("https://docs.rs/demo/latest/demo/"));
+        assert_eq!(page.status(), Some(404));
+        assert!(page
+            .suggestions()
+            .iter()
+            .any(|s| s.contains("search_in_crate")));
+
+        let item = DocsRsMcpError::NotFound("struct Imaginary in crate demo".to_string());
+        assert_eq!(item.url(), None);
+        assert_eq!(item.status(), None);
+        assert_eq!(item.suggestions(), page.suggestions());
+    }
+
+    #[test]
+    fn test_did_you_mean() -> anyhow::Result<()> {
+        let error = DocsRsMcpError::ItemNotFound {
+            what: "struct TracerProvider in crate opentelemetry_sdk".to_string(),
+            did_you_mean: vec!["trace::SdkTracerProvider".to_string()],
+        };
+        assert_eq!(error.kind(), "not_found");
+        let ToolResponseContent::Text { text } = &error.to_response().content[0] else {
+            panic!("Expected a text response");
+        };
+        let payload: serde_json::Value = serde_json::from_str(text)?;
+        assert_eq!(
+            payload["error"]["did_you_mean"],
+            json!(["trace::SdkTracerProvider"])
+        );
+        assert_eq!(
+            payload["error"]["message"],
+            "Not found: struct TracerProvider in crate opentelemetry_sdk"
+        );
+
+        let no_matches = DocsRsMcpError::NotFound("https://docs.rs/demo/".to_string());
+        assert!(no_matches.did_you_mean().is_empty());
+        let ToolResponseContent::Text { text } = &no_matches.to_response().content[0] else {
+            panic!("Expected a text response");
+        };
+        assert!(!text.contains("did_you_mean"));
+        Ok(())
+    }
+}
@@ -0,0 +1,308 @@
+//! Offline export of a crate's documentation as a tree of Markdown files, for feeding the same
+//! parsed docs the tools serve into other pipelines (e.g. RAG corpora).
+//!
+//! ```text
+//! docs-rs-mcp export --crate tokio --version 1.43.0 --out docs/ [--kinds structs,traits] [--path-prefix sync::]
+//! ```
+
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use anyhow::{anyhow, bail, Context, Result};
+use scraper::Html;
+use tracing::{info, warn};
+
+use crate::tools::crate_items::{section_key, Item};
+use crate::tools::get_struct_docs::{HtmlFetcher, HttpHtmlFetcher};
+use crate::tools::selectors;
+use crate::tools::{markdown, CrateItemsTool};
+
+/// What to export and where to
+#[derive(Debug, Clone, PartialEq, clap::Args)]
+pub struct ExportOptions {
+    /// Crate to export
+    #[arg(long = "crate", value_name = "name")]
+    crate_name: String,
+    /// Version of the crate, latest by default
+    #[arg(
+        long,
+        value_name = "version",
+        default_value = "latest",
+        hide_default_value = true
+    )]
+    version: String,
+    /// Directory to write the files to
+    #[arg(long = "out", value_name = "dir")]
+    out_dir: PathBuf,
+    /// Comma separated sections to export, e.g. structs,traits
+    #[arg(long, value_name = "kinds", value_delimiter = ',', value_parser = parse_kind)]
+    kinds: Vec<String>,
+    /// Only export items whose path starts with this prefix, e.g. sync::
+    #[arg(long, value_name = "prefix")]
+    path_prefix: Option<String>,
+}
+
+/// A section of `crate_items` as given to `--kinds`, compared lowercase
+fn parse_kind(kind: &str) -> Result<String> {
+    let kind = kind.trim().to_lowercase();
+    if kind.is_empty() {
+        bail!("Empty section name");
+    }
+    Ok(kind)
+}
+
+impl ExportOptions {
+    pub fn crate_name(&self) -> &str {
+        &self.crate_name
+    }
+
+    pub fn version(&self) -> &str {
+        &self.version
+    }
+
+    pub fn out_dir(&self) -> &Path {
+        &self.out_dir
+    }
+
+    fn includes(&self, section: &str, item: &Item) -> bool {
+        let kind_matches =
+            self.kinds.is_empty() || self.kinds.iter().any(|kind| section_key(section) == *kind);
+        let path_matches = self
+            .path_prefix
+            .as_deref()
+            .is_none_or(|prefix| item.name().starts_with(prefix));
+        kind_matches && path_matches
+    }
+}
+
+/// Result of an export run
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExportSummary {
+    /// Directory the files were written to
+    pub root: PathBuf,
+    pub exported: usize,
+    pub failed: usize,
+}
+
+/// Exports the selected items of a crate, one Markdown file per item plus an `index.md`.
+/// Items whose page can't be fetched are logged and skipped.
+pub fn run(options: &ExportOptions) -> Result<ExportSummary> {
+    let items =
+        CrateItemsTool::new().scrape_items_blocking(&options.crate_name, Some(&options.version))?;
+    let fetcher = HttpHtmlFetcher::new();
+    let root = options
+        .out_dir
+        .join(&options.crate_name)
+        .join(&options.version);
+
+    let sections: Vec<(&String, Vec<&Item>)> = items
+        .items()
+        .iter()
+        .map(|(section, items)| {
+            let selected = items
+                .iter()
+                .filter(|item| options.includes(section, item))
+                .collect::<Vec<_>>();
+            (section, selected)
+        })
+        .filter(|(_, items)| !items.is_empty())
+        .collect();
+
+    if sections.is_empty() {
+        bail!(
+            "No items of {} {} match the export filters",
+            options.crate_name,
+            options.version
+        );
+    }
+
+    let mut exported = 0;
+    let mut failed = 0;
+    for item in sections.iter().flat_map(|(_, items)| items) {
+        let result = item_file(item).and_then(|file| {
+            let html = fetcher.fetch_html_blocking(item.doc_link())?;
+            write_file(&root.join(file), &page_to_markdown(&html)?)
+        });
+        match result {
+            Ok(()) => exported += 1,
+            Err(e) => {
+                warn!("Failed to export {}: {:#}", item.name(), e);
+                failed += 1;
+            }
+        }
+    }
+
+    write_file(
+        &root.join("index.md"),
+        &render_index(&options.crate_name, &options.version, &sections),
+    )?;
+    info!(
+        "Exported {} items of {} {} to {}",
+        exported,
+        options.crate_name,
+        options.version,
+        root.display()
+    );
+
+    Ok(ExportSummary {
+        root,
+        exported,
+        failed,
+    })
+}
+
+/// File an item is exported to, mirroring the rustdoc page path: `sync/struct.Mutex.md`. The
+/// path is read from the crate's pages, so one leading out of the export directory is refused.
+fn item_file(item: &Item) -> Result<PathBuf> {
+    let path = item.path().trim_start_matches('/');
+    let file = PathBuf::from(format!("{}.md", path.strip_suffix(".html").unwrap_or(path)));
+    if !file
+        .components()
+        .all(|component| matches!(component, Component::Normal(_)))
+    {
+        bail!(
+            "Not exporting {} to {}, which is outside the export directory",
+            item.name(),
+            file.display()
+        );
+    }
+    Ok(file)
+}
+
+fn page_to_markdown(html: &str) -> Result<String> {
+    let document = Html::parse_document(html);
+    let selector = selectors::parse("#main-content", "item page")?;
+    document
+        .select(&selector)
+        .next()
+        .map(markdown::element_to_markdown)
+        .ok_or_else(|| anyhow!("Could not find the documentation content"))
+}
+
+fn render_index(crate_name: &str, version: &str, sections: &[(&String, Vec<&Item>)]) -> String {
+    let mut index = format!("# {} {}\n", crate_name, version);
+    for (section, items) in sections {
+        index.push_str(&format!("\n## {}\n\n", section));
+        // Items that weren't exported aren't linked
+        for (item, file) in items
+            .iter()
+            .filter_map(|item| Some((item, item_file(item).ok()?)))
+        {
+            index.push_str(&format!("- [{}]({})\n", item.name(), file.display()));
+        }
+    }
+    index
+}
+
+fn write_file(path: &Path, contents: &str) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .context(format!("Failed to create directory: {}", parent.display()))?;
+    }
+    fs::write(path, contents).context(format!("Failed to write file: {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::args::{Cli, Command};
+    use pretty_assertions::assert_eq;
+
+    fn parse(args: &[&str]) -> Result<ExportOptions> {
+        match Cli::parse_args(std::iter::once("export").chain(args.iter().copied()))?.command {
+            Some(Command::Export(options)) => Ok(options),
+            command => panic!("Expected export, got {:?}", command),
+        }
+    }
+
+    #[test]
+    fn test_parse_export_args() -> Result<()> {
+        let options = parse(&[
+            "--crate",
+            "tokio",
+            "--version=1.43.0",
+            "--log-level",
+            "info",
+            "--out",
+            "docs",
+            "--kinds",
+            "Structs, traits",
+        ])?;
+
+        assert_eq!(options.crate_name(), "tokio");
+        assert_eq!(options.version(), "1.43.0");
+        assert_eq!(options.out_dir(), Path::new("docs"));
+        assert_eq!(options.kinds, vec!["structs", "traits"]);
+        assert_eq!(
+            parse(&["--crate", "tokio", "--out", "docs"])?.version(),
+            "latest"
+        );
+        assert!(parse(&["--crate", "tokio"]).is_err(), "--out is required");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_filters_and_paths() -> Result<()> {
+        let html = std::fs::read_to_string("test-data/list-of-all-items-tokio-1.43.0.html")?;
+        let items = CrateItemsTool::new().parse_items(
+            "tokio",
+            "1.43.0",
+            "https://docs.rs/tokio/1.43.0/tokio/",
+            &html,
+        )?;
+        let options = parse(&[
+            "--crate",
+            "tokio",
+            "--out",
+            "docs",
+            "--kinds",
+            "structs",
+            "--path-prefix",
+            "sync::",
+        ])?;
+
+        let selected: Vec<&Item> = items
+            .items()
+            .iter()
+            .flat_map(|(section, items)| {
+                items.iter().filter(|item| options.includes(section, item))
+            })
+            .collect();
+
+        assert!(!selected.is_empty(), "tokio has structs in sync");
+        assert!(selected
+            .iter()
+            .all(|item| item.name().starts_with("sync::")));
+        let mutex = selected
+            .iter()
+            .find(|item| item.name() == "sync::Mutex")
+            .expect("sync::Mutex should be selected");
+        assert_eq!(item_file(mutex)?, PathBuf::from("sync/struct.Mutex.md"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_item_file_stays_in_the_export() -> Result<()> {
+        let item = |path: &str| -> Result<Item> {
+            Ok(serde_json::from_value(serde_json::json!({
+                "name": "Evil",
+                "path": path,
+                "doc_link": "https://docs.rs/evil/1.0.0/evil/struct.Evil.html",
+            }))?)
+        };
+        assert_eq!(
+            item_file(&item("/sync/struct.Mutex.html")?)?,
+            PathBuf::from("sync/struct.Mutex.md")
+        );
+        for path in [
+            "../../.bashrc.html",
+            "sync/../../../etc/cron.d/evil.html",
+            "./struct.Evil.html",
+        ] {
+            assert!(item_file(&item(path)?).is_err(), "{}", path);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,469 @@
+//! Pre-built item index for warming a server ahead of time.
+//!
+//! ```text
+//! docs-rs-mcp index --lockfile Cargo.lock [--concurrency 4] [serde@1.0.217 tokio ...]
+//! ```
+//!
+//! Item listings are stored under `<cache dir>/index/<registry>/<crate>/<version>.json` and
+//! served by `crate_items` (and everything built on it) while they are younger than the cache
+//! TTL. Crates indexed in the background keep their progress next to it, in
+//! `<version>.progress.json`, until the listing is complete.
+//!
+//! Entries record what the listing was read from and by which parsers, see
+//! [`DerivedKey`]; those read by other parsers than the server's, or for another target than
+//! docs.rs' default one, are ignored.
+
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+use crate::cache_key::DerivedKey;
+use crate::config::Config;
+use crate::tools::crate_items::CrateItems;
+use crate::tools::CrateItemsTool;
+use crate::{background_index, config, paths, telemetry, validate};
+
+/// A crate to index, written `name@version` or just `name` for the latest version
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct CrateSpec {
+    name: String,
+    version: String,
+}
+
+impl FromStr for CrateSpec {
+    type Err = anyhow::Error;
+
+    fn from_str(spec: &str) -> Result<Self> {
+        let (name, version) = spec.split_once('@').unwrap_or((spec, "latest"));
+        if name.is_empty() || version.is_empty() {
+            return Err(anyhow!(
+                "Invalid crate spec '{}', expected name@version",
+                spec
+            ));
+        }
+        Ok(Self {
+            name: name.to_string(),
+            version: version.to_string(),
+        })
+    }
+}
+
+impl CrateSpec {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl std::fmt::Display for CrateSpec {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}@{}", self.name, self.version)
+    }
+}
+
+/// Crates fetched at the same time unless `--concurrency` says otherwise
+const DEFAULT_CONCURRENCY: usize = 4;
+
+/// What to index
+#[derive(Debug, Clone, PartialEq, clap::Args)]
+pub struct IndexOptions {
+    /// Cargo.lock whose registry dependencies are indexed
+    #[arg(long, value_name = "path", required_unless_present = "crates")]
+    lockfile: Option<PathBuf>,
+    /// Crates to index, as name@version or just name for the latest version
+    #[arg(value_name = "name@version", value_parser = parse_spec)]
+    crates: Vec<CrateSpec>,
+    /// Crates fetched at once
+    #[arg(long, value_name = "n", default_value_t = DEFAULT_CONCURRENCY, value_parser = parse_concurrency)]
+    concurrency: usize,
+}
+
+fn parse_spec(spec: &str) -> Result<CrateSpec> {
+    spec.parse()
+}
+
+fn parse_concurrency(n: &str) -> Result<usize> {
+    match n.parse() {
+        Ok(n) if n > 0 => Ok(n),
+        _ => Err(anyhow!("--concurrency must be a positive number")),
+    }
+}
+
+impl IndexOptions {
+    /// Indexes `crates`, the default number at a time.
+    pub fn for_crates(crates: Vec<CrateSpec>) -> Self {
+        Self {
+            lockfile: None,
+            crates,
+            concurrency: DEFAULT_CONCURRENCY,
+        }
+    }
+
+    /// Every crate to index: the lockfile's registry packages plus the listed crates.
+    pub fn crates(&self) -> Result<Vec<CrateSpec>> {
+        let mut crates: BTreeSet<CrateSpec> = self.crates.iter().cloned().collect();
+        if let Some(lockfile) = &self.lockfile {
+            let contents = fs::read_to_string(lockfile)
+                .context(format!("Failed to read lockfile: {}", lockfile.display()))?;
+            crates.extend(parse_lockfile(&contents)?);
+        }
+        Ok(crates.into_iter().collect())
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct Lockfile {
+    #[serde(default)]
+    package: Vec<LockedPackage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LockedPackage {
+    name: String,
+    version: String,
+    source: Option<String>,
+}
+
+/// Registry packages of a `Cargo.lock`; path and git dependencies have no docs.rs pages.
+fn parse_lockfile(contents: &str) -> Result<Vec<CrateSpec>> {
+    let lockfile: Lockfile = toml::from_str(contents).context("Invalid Cargo.lock")?;
+    Ok(lockfile
+        .package
+        .into_iter()
+        .filter(|package| {
+            package
+                .source
+                .as_deref()
+                .is_some_and(|source| source.starts_with("registry+"))
+        })
+        .map(|package| CrateSpec {
+            name: package.name,
+            version: package.version,
+        })
+        .collect())
+}
+
+/// Result of an index run
+#[derive(Debug, Clone, PartialEq)]
+pub struct IndexSummary {
+    pub indexed: usize,
+    pub failed: Vec<String>,
+}
+
+/// A crate done with, handed to the progress callback of [`run`]
+#[derive(Debug)]
+pub struct IndexProgress<'a> {
+    /// How many crates are done, this one included
+    pub position: usize,
+    pub total: usize,
+    pub spec: &'a CrateSpec,
+    /// Why the crate couldn't be indexed
+    pub error: Option<&'a anyhow::Error>,
+}
+
+/// Fetches and stores the item listings of every crate under `config`, `concurrency` at a
+/// time, calling `progress` as each one is done.
+pub fn run(
+    config: &Config,
+    options: &IndexOptions,
+    progress: impl Fn(IndexProgress) + Sync,
+) -> Result<IndexSummary> {
+    let crates = options.crates()?;
+    let total = crates.len();
+    let next = AtomicUsize::new(0);
+    let done = AtomicUsize::new(0);
+    let failed = Mutex::new(Vec::new());
+
+    std::thread::scope(|scope| {
+        for _ in 0..options.concurrency.min(total) {
+            // The config is scoped per thread, so each worker has to scope it again
+            scope.spawn(|| {
+                config::scoped(config.clone(), || {
+                    let tool = CrateItemsTool::new();
+                    while let Some(spec) = crates.get(next.fetch_add(1, Ordering::SeqCst)) {
+                        let result = tool
+                            .scrape_items_blocking(&spec.name, Some(&spec.version))
+                            .and_then(|items| match items.indexing() {
+                                // Too large for one pass: wait for the background crawl to finish
+                                Some(_) => background_index::finish_blocking(&items).map(|_| ()),
+                                None => store(&items),
+                            });
+                        let position = done.fetch_add(1, Ordering::SeqCst) + 1;
+                        match &result {
+                            Ok(()) => debug!("Indexed {}", spec),
+                            Err(e) => debug!("Failed to index {}: {:#}", spec, e),
+                        }
+                        progress(IndexProgress {
+                            position,
+                            total,
+                            spec,
+                            error: result.as_ref().err(),
+                        });
+                        if result.is_err() {
+                            let mut failed = match failed.lock() {
+                                Ok(failed) => failed,
+                                Err(poisoned) => poisoned.into_inner(),
+                            };
+                            failed.push(spec.to_string());
+                        }
+                    }
+                })
+            });
+        }
+    });
+
+    let failed = failed.into_inner().unwrap_or_else(|e| e.into_inner());
+    Ok(IndexSummary {
+        indexed: total - failed.len(),
+        failed,
+    })
+}
+
+/// Where the listing of `crate_name` at `version` is kept, refusing names and versions that
+/// would lead out of the index.
+fn entry_path(
+    cache_dir: &Path,
+    registry: &str,
+    crate_name: &str,
+    version: &str,
+) -> Result<PathBuf> {
+    validate::crate_name(crate_name)?;
+    validate::version(version)?;
+    Ok(cache_dir
+        .join(paths::INDEX_DIR)
+        .join(registry)
+        .join(crate_name)
+        .join(format!("{}.json", version)))
+}
+
+/// Where the progress of indexing a crate in the background is kept.
+pub(crate) fn progress_path(
+    cache_dir: &Path,
+    registry: &str,
+    crate_name: &str,
+    version: &str,
+) -> Result<PathBuf> {
+    Ok(entry_path(cache_dir, registry, crate_name, version)?.with_extension("progress.json"))
+}
+
+/// An item listing as stored, with what it was read from
+#[derive(Serialize, Deserialize)]
+struct Entry<Items> {
+    key: DerivedKey,
+    items: Items,
+}
+
+/// Stores an item listing in the index.
+pub(crate) fn store(items: &CrateItems) -> Result<()> {
+    let key = items.source().ok_or_else(|| {
+        anyhow!(
+            "The listing of {} {} doesn't tell what it was read from",
+            items.crate_name(),
+            items.version()
+        )
+    })?;
+    let config = config::current();
+    let Some(cache_dir) = config.cache().resolved_dir() else {
+        return Err(anyhow!("No cache directory is configured"));
+    };
+    let path = entry_path(
+        &cache_dir,
+        config.active_registry(),
+        items.crate_name(),
+        items.version(),
+    )?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .context(format!("Failed to create directory: {}", parent.display()))?;
+    }
+    fs::write(
+        &path,
+        serde_json::to_string(&Entry {
+            key: key.clone(),
+            items,
+        })?,
+    )
+    .context(format!("Failed to write index entry: {}", path.display()))
+}
+
+/// Looks up an item listing in the index, ignoring entries older than the cache TTL.
+pub(crate) fn load(crate_name: &str, version: &str) -> Option<CrateItems> {
+    if !config::current().cache().enabled() {
+        return None;
+    }
+    let _span = tracing::info_span!("index_load", crate_name, version).entered();
+    let items = lookup(crate_name, version);
+    telemetry::record_cache_lookup("index", items.is_some());
+    items
+}
+
+fn lookup(crate_name: &str, version: &str) -> Option<CrateItems> {
+    let config = config::current();
+    let path = entry_path(
+        &config.cache().resolved_dir()?,
+        config.active_registry(),
+        crate_name,
+        version,
+    )
+    .ok()?;
+
+    let modified = fs::metadata(&path).and_then(|m| m.modified()).ok()?;
+    let age = SystemTime::now()
+        .duration_since(modified)
+        .unwrap_or_default();
+    if config.is_stale(age) {
+        debug!("Index entry {} is stale", path.display());
+        return None;
+    }
+
+    let contents = fs::read_to_string(&path).ok()?;
+    match serde_json::from_str::<Entry<CrateItems>>(&contents) {
+        Ok(entry) if entry.key.is_current(None) => {
+            debug!("Serving {}@{} from the index", crate_name, version);
+            Some(entry.items.with_source(entry.key))
+        }
+        Ok(_) => {
+            debug!(
+                "Index entry {} was read by other parsers or for another target",
+                path.display()
+            );
+            None
+        }
+        // Entries written before they were keyed are read again as well
+        Err(e) => {
+            debug!("Ignoring index entry {}: {}", path.display(), e);
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::args::{Cli, Command};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_parse_lockfile() -> Result<()> {
+        let lockfile = r#"
+version = 4
+
+[[package]]
+name = "anyhow"
+version = "1.0.95"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+checksum = "34ac096ce696dc2fcabef30516bb13c0a68a11d30131d3df6f04711467681b04"
+
+[[package]]
+name = "docs-rs-mcp"
+version = "0.1.0"
+dependencies = ["anyhow"]
+
+[[package]]
+name = "mcp-sdk"
+version = "0.0.3"
+source = "git+https://github.com/AntigmaLabs/mcp-sdk#abc"
+"#;
+        assert_eq!(
+            parse_lockfile(lockfile)?,
+            vec![CrateSpec {
+                name: "anyhow".to_string(),
+                version: "1.0.95".to_string(),
+            }],
+            "Only registry packages should be indexed"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_index_args() -> Result<()> {
+        let parse = |args: &[&str]| -> Result<IndexOptions> {
+            match Cli::parse_args(std::iter::once("index").chain(args.iter().copied()))?.command {
+                Some(Command::Index(options)) => Ok(options),
+                command => panic!("Expected index, got {:?}", command),
+            }
+        };
+        let options = parse(&[
+            "serde@1.0.217",
+            "--docs-rs-url",
+            "http://localhost:3000",
+            "tokio",
+            "--concurrency=8",
+        ])?;
+
+        assert_eq!(options.concurrency, 8);
+        assert_eq!(
+            options.crates()?,
+            vec!["serde@1.0.217".parse()?, "tokio@latest".parse()?]
+        );
+
+        assert!(parse(&[]).is_err(), "Something to index is required");
+        assert!(parse(&["tokio", "--concurrency=0"]).is_err());
+        assert!(parse(&["@1.0"]).is_err());
+        assert!("@1.0".parse::<CrateSpec>().is_err());
+
+        Ok(())
+    }
+    #[test]
+    fn test_entry_path() -> Result<()> {
+        let cache_dir = Path::new("/cache");
+        assert_eq!(
+            entry_path(cache_dir, "crates-io", "serde", "1.0.217")?,
+            Path::new("/cache/index/crates-io/serde/1.0.217.json")
+        );
+        assert_eq!(
+            progress_path(cache_dir, "crates-io", "serde", "latest")?,
+            Path::new("/cache/index/crates-io/serde/latest.progress.json")
+        );
+        assert!(entry_path(cache_dir, "crates-io", "../../etc", "1.0.0").is_err());
+        assert!(entry_path(cache_dir, "crates-io", "/etc", "1.0.0").is_err());
+        assert!(progress_path(cache_dir, "crates-io", "serde", "../../../passwd").is_err());
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_run_scopes_config_in_workers() -> Result<()> {
+        use wiremock::{MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        wiremock::Mock::given(wiremock::matchers::any())
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&server)
+            .await;
+        let config = Config::from_toml(&format!(
+            "[sources]\ndocs_rs_url = \"{}\"\n[cache]\nenabled = false\n",
+            server.uri()
+        ))?;
+        let options = IndexOptions {
+            concurrency: 2,
+            ..IndexOptions::for_crates(vec![
+                "scoped-worker-a@1.0.0".parse()?,
+                "scoped-worker-b@1.0.0".parse()?,
+            ])
+        };
+
+        let summary = tokio::task::spawn_blocking(move || run(&config, &options, |_| ())).await??;
+        assert_eq!(summary.failed.len(), 2);
+        let requested: BTreeSet<String> = server
+            .received_requests()
+            .await
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|request| request.url.path_segments()?.nth(1).map(str::to_string))
+            .collect();
+        assert!(
+            requested.contains("scoped-worker-a") && requested.contains("scoped-worker-b"),
+            "Every worker should fetch from the configured docs.rs, got {:?}",
+            requested
+        );
+        Ok(())
+    }
+}
@@ -1,7 +1,57 @@
+//! Documentation lookups for Rust crates on docs.rs and crates.io, served to AI agents as an
+//! MCP server. [`DocsClient`] offers the same lookups as an async Rust API, with
+//! [`DocsRsMcpError`] to match failures on; the tools themselves are in [`tools`].
+
+#[cfg(feature = "native")]
+pub mod args;
+#[cfg(feature = "native")]
+pub mod background_index;
+#[cfg(feature = "native")]
+mod cache;
+pub(crate) mod cache_key;
+#[cfg(feature = "native")]
+pub mod check;
+#[cfg(feature = "native")]
+pub mod cli;
+pub mod client;
+pub mod config;
+pub mod error;
+#[cfg(feature = "native")]
+pub mod export;
+#[cfg(feature = "native")]
+pub mod index;
+pub mod paths;
+pub(crate) mod resources;
+#[cfg(feature = "native")]
+pub mod server;
+#[cfg(feature = "native")]
+pub mod session;
+pub mod telemetry;
 pub mod tools;
+#[cfg(feature = "native")]
+pub mod transport;
+#[cfg(feature = "native")]
+pub mod usage;
+#[cfg(feature = "native")]
+pub(crate) mod validate;
+
+pub use tools::{CrateItemsTool, StructDocsTool};
+
+#[cfg(feature = "native")]
+pub use tools::{
+    AttributeMacroDocsTool, BrowseSourceTool, CapabilitiesTool, CompareVersionsTool,
+    CrateCategoriesTool, CrateChangelogTool, CrateFeaturesTool, CrateInfoTool, CrateLicenseTool,
+    CrateOwnersTool, CrateReadmeTool, DependencySnippetTool, DependencyTreeTool,
+    DeprecatedItemsTool, DeriveMacroDocsTool, ErrorTypesTool, FindUsagesInApiTool, GetSourceTool,
+    ModuleItemsTool, ReloadConfigTool, SearchInCrateTool, StatsTool, SuggestImportsTool,
+    SupportedTargetsTool, TraitImplementorsTool, TraitImplsTool,
+};
 
-pub use tools::{CrateInfoTool, CrateItemsTool, StructDocsTool};
+pub use client::DocsClient;
+pub use error::DocsRsMcpError;
+#[cfg(feature = "native")]
+pub use error::TypedCall;
 
 // Re-export test components
 #[cfg(test)]
-pub use tools::get_struct_docs::TestHtmlFetcher;
+pub use tools::get_struct_docs::{StdDocsFetcher, TestHtmlFetcher};
@@ -1,46 +1,173 @@
 use anyhow::Result;
-use mcp_sdk::{
-    server::Server,
-    tools::{Tool, Tools},
-    transport::ServerStdioTransport,
-    types::{ListRequest, ResourcesListResponse, ServerCapabilities},
-};
-use serde_json::json;
-
-mod tools;
-use tools::{CrateInfoTool, CrateItemsTool, StructDocsTool};
+use clap::Parser;
+use docs_rs_mcp::args::{Cli, Command};
+use docs_rs_mcp::background_index;
+use docs_rs_mcp::check;
+use docs_rs_mcp::cli;
+use docs_rs_mcp::config::{self, TransportKind};
+use docs_rs_mcp::export;
+use docs_rs_mcp::index;
+use docs_rs_mcp::paths;
+use docs_rs_mcp::server;
+use docs_rs_mcp::session;
+use docs_rs_mcp::tools::http;
+use docs_rs_mcp::transport::{self, socket, StdioTransport};
+use docs_rs_mcp::usage;
+use tracing::{error, info, warn};
+use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter, Layer};
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    tracing_subscriber::fmt()
-        .with_max_level(tracing::Level::DEBUG)
+    let Cli {
+        mut overrides,
+        command,
+    } = Cli::parse();
+    if matches!(command, Some(Command::Replay { .. })) {
+        // Pages come from the recording, never from the cache
+        overrides = overrides.without_cache();
+    }
+    let record_path = overrides.record_path().map(|path| path.to_path_buf());
+    if record_path.is_some() && command.is_some() {
+        anyhow::bail!("--record records MCP sessions, it can't be given with a command");
+    }
+    let config = config::init(overrides)?;
+    // Every tool sends its requests through this client; building it up front reports a bad
+    // proxy setting at startup rather than on the first tool call
+    http::shared()?;
+
+    let logs = tracing_subscriber::fmt::layer()
         // needs to be stderr due to stdio transport
         .with_writer(std::io::stderr)
-        .init();
-
-    let tools = tool_set();
-    let server = Server::builder(ServerStdioTransport)
-        .capabilities(ServerCapabilities {
-            tools: Some(json!({
-                "crate_info": CrateInfoTool::new().as_definition(),
-                "crate_items": CrateItemsTool::new().as_definition(),
-                "get_struct_docs": StructDocsTool::new().as_definition(),
-            })),
-            ..Default::default()
-        })
-        .tools(tools)
-        .request_handler("resources/list", |_req: ListRequest| {
-            Ok(ResourcesListResponse {
-                resources: vec![],
-                next_cursor: None,
-                meta: None,
+        .with_filter(EnvFilter::new(config.log_level()));
+    let subscriber = tracing_subscriber::registry().with(logs);
+    // Kept until main returns, which flushes what is left to export
+    #[cfg(feature = "otel")]
+    let telemetry = tokio::task::spawn_blocking(docs_rs_mcp::telemetry::init).await??;
+    #[cfg(feature = "otel")]
+    let subscriber = subscriber.with(telemetry.as_ref().map(|telemetry| telemetry.layer()));
+    subscriber.init();
+
+    match command {
+        Some(Command::Export(options)) => {
+            let summary = tokio::task::spawn_blocking(move || export::run(&options)).await??;
+            println!(
+                "Exported {} items to {} ({} failed)",
+                summary.exported,
+                summary.root.display(),
+                summary.failed
+            );
+            return Ok(());
+        }
+        Some(Command::Index(options)) => {
+            let summary = tokio::task::spawn_blocking(move || {
+                index::run(&config::current(), &options, |progress| {
+                    match progress.error {
+                        None => eprintln!(
+                            "[{}/{}] {}",
+                            progress.position, progress.total, progress.spec
+                        ),
+                        Some(e) => eprintln!(
+                            "[{}/{}] {} failed: {:#}",
+                            progress.position, progress.total, progress.spec, e
+                        ),
+                    }
+                })
             })
-        })
-        .build();
+            .await??;
+            println!(
+                "Indexed {} crates ({} failed)",
+                summary.indexed,
+                summary.failed.len()
+            );
+            return Ok(());
+        }
+        Some(Command::Replay { session }) => {
+            let summary = tokio::task::spawn_blocking(move || session::replay(&session)).await??;
+            for difference in &summary.differences {
+                println!("{}\n", difference.describe());
+            }
+            println!(
+                "Replayed {} calls recorded by version {}: {} responses differ",
+                summary.replayed,
+                summary.server_version,
+                summary.differences.len()
+            );
+            return Ok(());
+        }
+        Some(Command::Lookup(lookup)) => {
+            cli::run(&lookup).await?;
+            return Ok(());
+        }
+        Some(Command::Completions { shell }) => {
+            print!("{}", usage::completions(shell));
+            return Ok(());
+        }
+        Some(Command::Man { out }) => {
+            return match out {
+                Some(dir) => usage::write_man_pages(&dir),
+                None => usage::man_page(&mut std::io::stdout()),
+            };
+        }
+        Some(Command::Paths) => {
+            print!(
+                "{}",
+                paths::describe(config::config_path().as_ref(), &config)
+            );
+            return Ok(());
+        }
+        Some(Command::Check) => {
+            print!(
+                "{}\n{}\n",
+                paths::describe(config::config_path().as_ref(), &config),
+                toml::to_string(&*config)?
+            );
+            let probes = check::run(&config).await?;
+            print!("{}", check::describe(&probes));
+            let failed = probes.iter().filter(|probe| !probe.is_reachable()).count();
+            if failed > 0 {
+                anyhow::bail!("{} of {} hosts didn't answer", failed, probes.len());
+            }
+            return Ok(());
+        }
+        None => {}
+    }
+
+    #[cfg(unix)]
+    reload_config_on_sighup()?;
+    background_index::resume();
+    if let Some(path) = &record_path {
+        session::record_to(path)?;
+        info!("Recording the session to {}", path.display());
+    }
+
+    let admin_enabled = config.admin().enabled();
+    if admin_enabled && config.transport().kind().is_network() && !config.admin().has_token() {
+        warn!("Admin tools will refuse every call until admin.token is set");
+    }
+    match config.transport().kind() {
+        TransportKind::Stdio => {}
+        TransportKind::Http => {
+            let listener = socket::tcp_listener(config.transport().bind())?;
+            return transport::http::serve(listener, admin_enabled).await;
+        }
+        TransportKind::Tcp => {
+            let listener = socket::tcp_listener(config.transport().bind())?;
+            return socket::serve_tcp(listener, admin_enabled).await;
+        }
+        #[cfg(unix)]
+        TransportKind::Unix => {
+            let listener = socket::unix_listener(&config.transport().socket())?;
+            return socket::serve_unix(listener, admin_enabled).await;
+        }
+        #[cfg(not(unix))]
+        TransportKind::Unix => anyhow::bail!("The unix transport needs macOS or Linux"),
+    }
+
+    let server = server::build(StdioTransport::new(), admin_enabled);
 
     let server_handle = {
         let server = server;
-        tokio::spawn(async move { server.listen().await })
+        tokio::spawn(async move { server::listen(&server).await })
     };
 
     server_handle
@@ -49,11 +176,19 @@ async fn main() -> Result<()> {
     Ok(())
 }
 
-//NOTE: Must be updated if a tool is added!
-fn tool_set() -> Tools {
-    let mut tools = Tools::default();
-    tools.add_tool(CrateInfoTool::new());
-    tools.add_tool(CrateItemsTool::new());
-    tools.add_tool(StructDocsTool::new());
-    tools
+/// Reloads the config file whenever the process receives `SIGHUP`, keeping client sessions alive.
+#[cfg(unix)]
+fn reload_config_on_sighup() -> Result<()> {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    let mut hangups = signal(SignalKind::hangup())?;
+    tokio::spawn(async move {
+        while hangups.recv().await.is_some() {
+            info!("Received SIGHUP, reloading configuration");
+            if let Err(e) = config::reload() {
+                error!("{:#}", e);
+            }
+        }
+    });
+    Ok(())
 }
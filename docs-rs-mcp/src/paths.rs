@@ -0,0 +1,117 @@
+//! Where the server keeps its files: the platform's conventional directories, as
+//! [`directories::ProjectDirs`] finds them, which on Linux honors `XDG_CONFIG_HOME` and
+//! `XDG_CACHE_HOME`:
+//!
+//! | | Config | Cache |
+//! | --- | --- | --- |
+//! | Linux and BSDs | `~/.config/docs-rs-mcp` | `~/.cache/docs-rs-mcp` |
+//! | macOS | `~/Library/Application Support/docs-rs-mcp` | `~/Library/Caches/docs-rs-mcp` |
+//! | Windows | `%APPDATA%\docs-rs-mcp\config` | `%LOCALAPPDATA%\docs-rs-mcp\cache` |
+//!
+//! `--config` / `DOCS_RS_MCP_CONFIG` and `--cache-dir` / `DOCS_RS_MCP_CACHE_DIR` take precedence
+//! over these.
+
+use std::path::PathBuf;
+
+use directories::ProjectDirs;
+
+use crate::config::{Config, TransportKind};
+
+const APP: &str = "docs-rs-mcp";
+
+/// Cached documentation pages, under the cache directory
+pub(crate) const PAGES_DIR: &str = "pages";
+/// The item index, under the cache directory
+pub(crate) const INDEX_DIR: &str = "index";
+/// Data parsed out of documentation pages, under the cache directory
+pub(crate) const DERIVED_DIR: &str = "derived";
+/// Locally built documentation, under the cache directory
+pub(crate) const LOCAL_DOCS_DIR: &str = "local-docs";
+
+fn project_dirs() -> Option<ProjectDirs> {
+    ProjectDirs::from("", "", APP)
+}
+
+/// Directory of the default config file
+pub fn config_dir() -> Option<PathBuf> {
+    project_dirs().map(|dirs| dirs.config_dir().to_path_buf())
+}
+
+/// Cache directory used unless one is configured
+pub fn cache_dir() -> Option<PathBuf> {
+    project_dirs().map(|dirs| dirs.cache_dir().to_path_buf())
+}
+
+/// Default socket of the Unix socket transport: `docs-rs-mcp.sock` in `XDG_RUNTIME_DIR`, or in
+/// the temporary directory without one
+pub fn socket_path() -> PathBuf {
+    std::env::var_os("XDG_RUNTIME_DIR")
+        .map(PathBuf::from)
+        .filter(|dir| dir.is_absolute())
+        .unwrap_or_else(std::env::temp_dir)
+        .join(format!("{}.sock", APP))
+}
+
+/// Lists where the config file, cache and index live for `config`, loaded from `config_file`,
+/// one `name  path` line each, as printed by the `paths` command.
+pub fn describe(config_file: Option<&PathBuf>, config: &Config) -> String {
+    let show = |path: Option<PathBuf>| match path {
+        Some(path) => path.display().to_string(),
+        None => "(none)".to_string(),
+    };
+    let config_line = match config_file {
+        Some(path) if path.exists() => path.display().to_string(),
+        Some(path) => format!("{} (not found, using defaults)", path.display()),
+        None => "(none)".to_string(),
+    };
+    let cache = config.cache().resolved_dir();
+    let cache_line = match config.cache().enabled() {
+        true => show(cache.clone()),
+        false => format!("{} (disabled)", show(cache.clone())),
+    };
+    let under_cache = |dir: &str| show(cache.as_ref().map(|cache| cache.join(dir)));
+
+    let mut rows = vec![
+        ("config", config_line),
+        ("cache", cache_line),
+        (PAGES_DIR, under_cache(PAGES_DIR)),
+        (INDEX_DIR, under_cache(INDEX_DIR)),
+        (DERIVED_DIR, under_cache(DERIVED_DIR)),
+        (LOCAL_DOCS_DIR, under_cache(LOCAL_DOCS_DIR)),
+    ];
+    if config.transport().kind() == TransportKind::Unix {
+        rows.push(("socket", show(Some(config.transport().socket()))));
+    }
+    let width = rows.iter().map(|(name, _)| name.len()).max().unwrap_or(0);
+    rows.iter()
+        .map(|(name, path)| format!("{:width$}  {}\n", name, path, width = width))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_describe() -> anyhow::Result<()> {
+        let config = Config::from_toml("[cache]\nenabled = false\ndir = \"/var/cache/docs\"\n")?;
+        let config_file = PathBuf::from("/nonexistent/docs-rs-mcp/config.toml");
+        assert_eq!(
+            describe(Some(&config_file), &config),
+            "config      /nonexistent/docs-rs-mcp/config.toml (not found, using defaults)\n\
+             cache       /var/cache/docs (disabled)\n\
+             pages       /var/cache/docs/pages\n\
+             index       /var/cache/docs/index\n\
+             derived     /var/cache/docs/derived\n\
+             local-docs  /var/cache/docs/local-docs\n"
+        );
+
+        let unix = Config::from_toml(
+            "[cache]\ndir = \"/var/cache/docs\"\n\
+             [transport]\nkind = \"unix\"\nsocket = \"/run/docs-rs-mcp.sock\"\n",
+        )?;
+        assert!(describe(None, &unix).ends_with("socket      /run/docs-rs-mcp.sock\n"));
+        Ok(())
+    }
+}
@@ -0,0 +1,180 @@
+//! Text kept in memory for the client to fetch with `resources/read`: the parts of tool
+//! responses too large to send at once, and the full text of summarized descriptions. Only
+//! the most recent entries of each kind are kept, so memory stays bounded on long sessions.
+//!
+//! Without the `native` feature there is no server to serve them from, only summaries keeping
+//! their full text.
+#![cfg_attr(not(feature = "native"), allow(dead_code))]
+
+use std::collections::VecDeque;
+use std::sync::{Mutex, MutexGuard, OnceLock};
+
+const SCHEME: &str = "docs-rs-mcp://";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Kind {
+    /// A tool response split into parts, read in order
+    ResponseParts,
+    /// The full text a tool response only summarizes
+    FullText,
+}
+
+impl Kind {
+    const ALL: [Kind; 2] = [Kind::ResponseParts, Kind::FullText];
+
+    fn path(self) -> &'static str {
+        match self {
+            Kind::ResponseParts => "responses",
+            Kind::FullText => "full-text",
+        }
+    }
+
+    /// Entries kept before the oldest is dropped
+    fn kept(self) -> usize {
+        match self {
+            Kind::ResponseParts => 16,
+            Kind::FullText => 64,
+        }
+    }
+
+    fn index(self) -> usize {
+        match self {
+            Kind::ResponseParts => 0,
+            Kind::FullText => 1,
+        }
+    }
+}
+
+struct Entry {
+    id: u64,
+    title: String,
+    texts: Vec<String>,
+}
+
+#[derive(Default)]
+struct Kept {
+    next_id: u64,
+    entries: VecDeque<Entry>,
+}
+
+fn kept(kind: Kind) -> MutexGuard<'static, Kept> {
+    static KEPT: OnceLock<[Mutex<Kept>; 2]> = OnceLock::new();
+    let kept = &KEPT.get_or_init(Default::default)[kind.index()];
+    match kept.lock() {
+        Ok(kept) => kept,
+        Err(poisoned) => poisoned.into_inner(),
+    }
+}
+
+/// URI of text `part` (1-based) of entry `id`. Full texts have a single part, so the part is
+/// left out of their URIs.
+fn uri(kind: Kind, id: u64, part: usize) -> String {
+    match kind {
+        Kind::ResponseParts => format!("{}{}/{}/{}", SCHEME, kind.path(), id, part),
+        Kind::FullText => format!("{}{}/{}", SCHEME, kind.path(), id),
+    }
+}
+
+impl Kept {
+    fn keep(&mut self, kind: Kind, title: String, texts: Vec<String>) -> Vec<String> {
+        self.next_id += 1;
+        let id = self.next_id;
+        if self.entries.len() == kind.kept() {
+            self.entries.pop_front();
+        }
+        let uris = (1..=texts.len()).map(|part| uri(kind, id, part)).collect();
+        self.entries.push_back(Entry { id, title, texts });
+        uris
+    }
+
+    fn read(&self, id: u64, part: usize) -> Option<String> {
+        let entry = self.entries.iter().find(|entry| entry.id == id)?;
+        entry.texts.get(part.checked_sub(1)?).cloned()
+    }
+}
+
+/// Keeps `texts` under `title`, returning the URI of each in order.
+pub(crate) fn keep(kind: Kind, title: String, texts: Vec<String>) -> Vec<String> {
+    kept(kind).keep(kind, title, texts)
+}
+
+/// The text at `uri`, if it is still kept
+pub(crate) fn read(uri: &str) -> Option<String> {
+    let rest = uri.strip_prefix(SCHEME)?;
+    let (path, rest) = rest.split_once('/')?;
+    let kind = Kind::ALL.into_iter().find(|kind| kind.path() == path)?;
+    let (id, part) = match rest.split_once('/') {
+        Some((id, part)) => (id, part.parse().ok()?),
+        None => (rest, 1),
+    };
+    kept(kind).read(id.parse().ok()?, part)
+}
+
+/// URI and title of every text still kept
+pub(crate) fn list() -> Vec<(String, String)> {
+    Kind::ALL
+        .into_iter()
+        .flat_map(|kind| {
+            kept(kind)
+                .entries
+                .iter()
+                .flat_map(|entry| {
+                    let count = entry.texts.len();
+                    (1..=count).map(move |part| {
+                        let title = match count {
+                            1 => entry.title.clone(),
+                            _ => format!("{} (part {} of {})", entry.title, part, count),
+                        };
+                        (uri(kind, entry.id, part), title)
+                    })
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_keep_and_read() {
+        let uris = keep(
+            Kind::ResponseParts,
+            "Response".to_string(),
+            vec!["one".to_string(), "two".to_string()],
+        );
+        assert_eq!(uris.len(), 2);
+        assert!(uris[0].starts_with("docs-rs-mcp://responses/"));
+        assert_eq!(read(&uris[1]).as_deref(), Some("two"));
+
+        let [full] = &keep(Kind::FullText, "Docs".to_string(), vec!["full".to_string()])[..] else {
+            panic!("Expected one URI");
+        };
+        assert!(
+            full.strip_prefix("docs-rs-mcp://full-text/")
+                .is_some_and(|id| !id.contains('/')),
+            "Full texts have no part: {}",
+            full
+        );
+        assert_eq!(read(full).as_deref(), Some("full"));
+        assert!(list()
+            .iter()
+            .any(|(uri, title)| uri == full && title == "Docs"));
+
+        assert_eq!(read("docs-rs-mcp://full-text/0"), None);
+        assert_eq!(read("docs-rs-mcp://unknown/1"), None);
+    }
+
+    #[test]
+    fn test_oldest_entries_are_dropped() {
+        let mut kept = Kept::default();
+        kept.keep(Kind::FullText, "First".to_string(), vec!["a".to_string()]);
+        for _ in 0..Kind::FullText.kept() {
+            kept.keep(Kind::FullText, "Later".to_string(), vec!["b".to_string()]);
+        }
+        assert_eq!(kept.read(1, 1), None);
+        assert_eq!(kept.read(2, 1).as_deref(), Some("b"));
+    }
+}
@@ -0,0 +1,329 @@
+//! The MCP server: every tool plus the protocol handlers, over any transport. The binary serves
+//! it on stdio; the protocol tests drive it through an in-memory transport.
+
+use std::time::Instant;
+
+use anyhow::Result;
+use mcp_sdk::{
+    server::Server,
+    tools::{Tool, Tools},
+    transport::Transport,
+    types::{
+        CallToolResponse, ListRequest, Resource, ResourceCapabilities, ResourcesListResponse,
+        ServerCapabilities,
+    },
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use url::Url;
+
+use crate::error::DocsRsMcpError;
+use crate::tools::admin::stats;
+use crate::tools::registry;
+use crate::tools::{
+    AttributeMacroDocsTool, BrowseSourceTool, CachePurgeTool, CapabilitiesTool,
+    CompareVersionsTool, CrateCategoriesTool, CrateChangelogTool, CrateFeaturesTool, CrateInfoTool,
+    CrateItemsTool, CrateLicenseTool, CrateOwnersTool, CrateReadmeTool, DependencySnippetTool,
+    DependencyTreeTool, DeprecatedItemsTool, DeriveMacroDocsTool, ErrorTypesTool,
+    FindUsagesInApiTool, GetSourceTool, ModuleItemsTool, PrefetchTool, ReloadConfigTool,
+    SearchInCrateTool, StatsTool, StructDocsTool, SuggestImportsTool, SupportedTargetsTool,
+    TraitImplementorsTool, TraitImplsTool, TraitMatrixTool,
+};
+use crate::transport::Closed;
+use crate::{resources, session, telemetry};
+
+/// Builds the server on `transport`, with the `admin/*` tools when `admin_enabled`.
+pub fn build<T: Transport>(transport: T, admin_enabled: bool) -> Server<T> {
+    let tools = tool_set(admin_enabled);
+    Server::builder(transport)
+        .capabilities(ServerCapabilities {
+            tools: Some(tool_definitions(&tools)),
+            // Kept texts come and go with responses, nobody is told
+            resources: Some(ResourceCapabilities {
+                subscribe: Some(false),
+                list_changed: Some(false),
+            }),
+            ..Default::default()
+        })
+        .tools(tools)
+        .request_handler("resources/list", |_req: ListRequest| {
+            Ok(ResourcesListResponse {
+                resources: kept_resources()?,
+                next_cursor: None,
+                meta: None,
+            })
+        })
+        .request_handler("resources/read", read_resource)
+        .build()
+}
+
+/// Serves the client on the other end of `server`'s transport until it hangs up, which the SDK
+/// reports as an error like any other.
+pub async fn listen<T: Transport>(server: &Server<T>) -> anyhow::Result<()> {
+    match server.listen().await {
+        Err(e) if e.is::<Closed>() => Ok(()),
+        result => result,
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ReadResourceRequest {
+    uri: String,
+}
+
+#[derive(Debug, Serialize)]
+struct ReadResourceResponse {
+    contents: Vec<TextResourceContents>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TextResourceContents {
+    uri: String,
+    mime_type: String,
+    text: String,
+}
+
+/// The parts of oversized tool responses and the full texts of summarized descriptions, the
+/// only resources the server has
+fn kept_resources() -> anyhow::Result<Vec<Resource>> {
+    resources::list()
+        .into_iter()
+        .map(|(uri, name)| {
+            Ok(Resource {
+                uri: Url::parse(&uri)?,
+                name,
+                description: None,
+                mime_type: Some("text/plain".to_string()),
+            })
+        })
+        .collect()
+}
+
+fn read_resource(req: ReadResourceRequest) -> anyhow::Result<ReadResourceResponse> {
+    let text = resources::read(&req.uri).ok_or_else(|| {
+        anyhow::anyhow!(
+            "Unknown resource {}; texts are only kept for recent responses",
+            req.uri
+        )
+    })?;
+    Ok(ReadResourceResponse {
+        contents: vec![TextResourceContents {
+            uri: req.uri,
+            mime_type: "text/plain".to_string(),
+            text,
+        }],
+    })
+}
+
+/// Every tool the server offers, each counted by `admin/stats`.
+pub fn tool_set(admin_enabled: bool) -> Tools {
+    let mut tools = Tools::default();
+    tools.add_tool(Tracked::crate_lookup(AttributeMacroDocsTool::new()));
+    tools.add_tool(Tracked::crate_lookup(BrowseSourceTool::new()));
+    tools.add_tool(Tracked::crate_lookup(CompareVersionsTool::new()));
+    tools.add_tool(Tracked::crate_lookup(CrateCategoriesTool::new()));
+    tools.add_tool(Tracked::crate_lookup(CrateChangelogTool::new()));
+    tools.add_tool(Tracked::crate_lookup(CrateFeaturesTool::new()));
+    tools.add_tool(Tracked::crate_lookup(CrateInfoTool::new()));
+    tools.add_tool(Tracked::crate_lookup(CrateItemsTool::new()));
+    tools.add_tool(Tracked::crate_lookup(CrateLicenseTool::new()));
+    tools.add_tool(Tracked::crate_lookup(CrateOwnersTool::new()));
+    tools.add_tool(Tracked::crate_lookup(CrateReadmeTool::new()));
+    tools.add_tool(Tracked::crate_lookup(DependencySnippetTool::new()));
+    tools.add_tool(Tracked::crate_lookup(DependencyTreeTool::new()));
+    tools.add_tool(Tracked::crate_lookup(DeprecatedItemsTool::new()));
+    tools.add_tool(Tracked::crate_lookup(DeriveMacroDocsTool::new()));
+    tools.add_tool(Tracked::crate_lookup(ErrorTypesTool::new()));
+    tools.add_tool(Tracked::crate_lookup(FindUsagesInApiTool::new()));
+    tools.add_tool(Tracked::crate_lookup(GetSourceTool::new()));
+    tools.add_tool(Tracked::crate_lookup(StructDocsTool::new()));
+    tools.add_tool(Tracked::crate_lookup(ModuleItemsTool::new()));
+    tools.add_tool(Tracked::crate_lookup(SearchInCrateTool::new()));
+    tools.add_tool(Tracked::crate_lookup(SuggestImportsTool::new()));
+    tools.add_tool(Tracked::crate_lookup(SupportedTargetsTool::new()));
+    tools.add_tool(Tracked::crate_lookup(TraitImplementorsTool::new()));
+    tools.add_tool(Tracked::crate_lookup(TraitImplsTool::new()));
+    tools.add_tool(Tracked::new(TraitMatrixTool::new()));
+    if admin_enabled {
+        tools.add_tool(Tracked::new(CachePurgeTool::new()));
+        tools.add_tool(Tracked::new(PrefetchTool::new()));
+        tools.add_tool(Tracked::new(ReloadConfigTool::new()));
+        tools.add_tool(Tracked::new(StatsTool::new()));
+    }
+    // Last, so it describes every other tool
+    let catalog = CapabilitiesTool::new(tools.list_tools());
+    tools.add_tool(Tracked::new(catalog));
+    tools
+}
+
+/// Wraps a tool so that its calls and failures show up in `admin/stats`, and its failures
+/// reach MCP clients as a structured [`DocsRsMcpError`] payload instead of a bare message.
+/// When the session is being recorded, calls are recorded here too, see [`session`].
+pub struct Tracked<T> {
+    inner: T,
+    /// Whether the tool looks crates up, and so takes a `docs_base_url` argument
+    crate_lookup: bool,
+}
+
+impl<T: Tool> Tracked<T> {
+    pub fn new(inner: T) -> Self {
+        // Uptime is measured from when the tool set is built
+        stats::started_at();
+        Self {
+            inner,
+            crate_lookup: false,
+        }
+    }
+
+    /// Wraps a tool looking crates up, which also takes a `docs_base_url` argument to fetch
+    /// documentation from another host for the call.
+    pub fn crate_lookup(inner: T) -> Self {
+        Self {
+            crate_lookup: true,
+            ..Self::new(inner)
+        }
+    }
+}
+
+impl<T: Tool> Tool for Tracked<T> {
+    fn name(&self) -> String {
+        self.inner.name()
+    }
+
+    fn description(&self) -> String {
+        self.inner.description()
+    }
+
+    fn input_schema(&self) -> Value {
+        let schema = self.inner.input_schema();
+        if self.crate_lookup {
+            registry::with_docs_base_url_schema(schema)
+        } else {
+            schema
+        }
+    }
+
+    fn call(&self, input: Option<Value>) -> Result<CallToolResponse> {
+        let name = self.inner.name();
+        let _span = tracing::info_span!("tool_call", tool = %name).entered();
+        let start = Instant::now();
+        session::begin_call(&name, input.as_ref());
+        let result = if self.crate_lookup {
+            registry::with_docs_base_url(input, |input| self.inner.call(input))
+        } else {
+            self.inner.call(input)
+        };
+        let failed = match &result {
+            Ok(response) => response.is_error == Some(true),
+            Err(_) => true,
+        };
+        stats::record_call(&name, !failed);
+        telemetry::record_tool_call(&name, start.elapsed(), !failed);
+        let response =
+            result.unwrap_or_else(|error| DocsRsMcpError::classify(&error).to_response());
+        session::end_call(&response);
+        Ok(response)
+    }
+}
+
+/// The `tools` capability, keyed by tool name. Derived from the tool set so the advertised
+/// tools can't drift from the callable ones.
+fn tool_definitions(tools: &Tools) -> Value {
+    let definitions: Map<String, Value> = tools
+        .list_tools()
+        .into_iter()
+        .map(|definition| (definition.name.clone(), serde_json::json!(definition)))
+        .collect();
+    Value::Object(definitions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::anyhow;
+    use mcp_sdk::types::ToolResponseContent;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    /// Fails when called without input, counted under its name
+    struct FlakyTool(&'static str);
+
+    impl Tool for FlakyTool {
+        fn name(&self) -> String {
+            self.0.to_string()
+        }
+
+        fn description(&self) -> String {
+            String::new()
+        }
+
+        fn input_schema(&self) -> Value {
+            json!({ "type": "object", "properties": { "registry": {} } })
+        }
+
+        fn call(&self, input: Option<Value>) -> Result<CallToolResponse> {
+            match input {
+                Some(_) => Ok(CallToolResponse {
+                    content: vec![],
+                    is_error: None,
+                    meta: None,
+                }),
+                None => Err(anyhow!("no input")),
+            }
+        }
+    }
+
+    #[test]
+    fn test_tracked_counts_calls_and_errors() {
+        let tool = Tracked::new(FlakyTool("stats_test_flaky"));
+        assert!(tool.call(Some(json!({}))).is_ok());
+        let failure = tool
+            .call(None)
+            .expect("Failures are reported in the response");
+        assert_eq!(failure.is_error, Some(true));
+        let ToolResponseContent::Text { text } = &failure.content[0] else {
+            panic!("Expected a text response");
+        };
+        let payload: Value = serde_json::from_str(text).unwrap();
+        assert_eq!(payload["error"]["kind"], "other");
+        assert_eq!(payload["error"]["message"], "no input");
+
+        let stats = stats::snapshot();
+        let flaky = stats
+            .tools()
+            .iter()
+            .find(|t| t.name() == "stats_test_flaky")
+            .expect("Tracked tool should be counted");
+        assert_eq!(flaky.calls(), 2);
+        assert_eq!(flaky.errors(), 1);
+    }
+
+    #[test]
+    fn test_docs_base_url_only_for_crate_lookups() {
+        let input = json!({ "docs_base_url": "https://mirror.example" });
+        let lookup = Tracked::crate_lookup(FlakyTool("stats_test_lookup"));
+        assert!(lookup.input_schema()["properties"]["docs_base_url"].is_object());
+        let refused = lookup.call(Some(input.clone())).unwrap();
+        assert_eq!(refused.is_error, Some(true), "Off unless enabled");
+
+        let other = Tracked::new(FlakyTool("stats_test_other"));
+        assert!(other.input_schema()["properties"]
+            .get("docs_base_url")
+            .is_none());
+        let ignored = other.call(Some(input)).unwrap();
+        assert_eq!(ignored.is_error, None);
+
+        for tool in tool_set(true).list_tools() {
+            let takes_url = tool.input_schema["properties"]
+                .get("docs_base_url")
+                .is_some();
+            assert_eq!(
+                takes_url,
+                tool.input_schema["properties"].get("registry").is_some(),
+                "{}",
+                tool.name
+            );
+        }
+    }
+}
@@ -0,0 +1,578 @@
+//! Record and replay of server sessions, for bug reports. Scraping failures depend on the page
+//! docs.rs served for a crate and version at a given time, which a maintainer can't fetch again
+//! later. With `--record session.json` the server writes every tool call to the file, along with
+//! its response and each page fetched to answer it, including those served from the cache;
+//! `docs-rs-mcp replay session.json` runs the calls again against the recorded pages instead of
+//! the network and reports the responses that came out differently.
+//!
+//! Request headers, which may carry registry tokens, are not recorded, and neither are calls of
+//! the `admin/` tools, whose arguments may hold the admin token. Fetched pages are, so a session
+//! of lookups in a private registry holds its documentation.
+
+use std::collections::{HashMap, VecDeque};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard, OnceLock};
+
+use anyhow::{anyhow, Context, Result};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use mcp_sdk::types::{CallToolRequest, CallToolResponse};
+use reqwest::ResponseBuilderExt;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tracing::warn;
+use url::Url;
+
+use crate::tools::admin::ADMIN_PREFIX;
+
+/// The tool calls of a session, as written by `--record`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Session {
+    /// Version of the server, and so of the parsers, that answered the calls
+    server_version: String,
+    exchanges: Vec<Exchange>,
+}
+
+impl Session {
+    fn new() -> Self {
+        Self {
+            server_version: env!("CARGO_PKG_VERSION").to_string(),
+            exchanges: Vec::new(),
+        }
+    }
+
+    pub fn server_version(&self) -> &str {
+        &self.server_version
+    }
+
+    pub fn exchanges(&self) -> &[Exchange] {
+        &self.exchanges
+    }
+}
+
+/// A tool call, its response and the pages fetched to answer it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Exchange {
+    tool: String,
+    #[serde(default)]
+    arguments: Value,
+    /// The response as the client got it, failures included
+    #[serde(default)]
+    response: Value,
+    #[serde(default)]
+    fetches: Vec<Fetch>,
+}
+
+impl Exchange {
+    pub fn tool(&self) -> &str {
+        &self.tool
+    }
+
+    pub fn fetches(&self) -> &[Fetch] {
+        &self.fetches
+    }
+}
+
+/// Where the body of a fetch came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CacheStatus {
+    /// Fetched over the network
+    Miss,
+    /// Served from the disk cache of pages
+    Hit,
+    /// Answered Not Found without asking, as the URL did recently
+    NotFound,
+}
+
+/// A request sent while answering a call, and what it got
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Fetch {
+    method: String,
+    url: String,
+    /// The URL that answered after redirects, when it isn't `url`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    final_url: Option<String>,
+    cache: CacheStatus,
+    /// The HTTP status; `None` for pages from the cache and requests that failed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    status: Option<u16>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    body: Option<Body>,
+    /// Why the request failed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+/// A response body: as text when it is UTF-8, otherwise in base64, e.g. gzipped rustdoc JSON
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Body {
+    Text(String),
+    Base64(String),
+}
+
+impl Body {
+    fn new(bytes: &[u8]) -> Self {
+        match std::str::from_utf8(bytes) {
+            Ok(text) => Self::Text(text.to_string()),
+            Err(_) => Self::Base64(STANDARD.encode(bytes)),
+        }
+    }
+
+    fn bytes(&self) -> Result<Vec<u8>> {
+        match self {
+            Self::Text(text) => Ok(text.as_bytes().to_vec()),
+            Self::Base64(encoded) => STANDARD
+                .decode(encoded)
+                .context("Failed to decode a recorded body"),
+        }
+    }
+}
+
+impl Fetch {
+    /// A response received over the network
+    pub(crate) fn received(
+        method: &str,
+        url: &str,
+        final_url: &Url,
+        status: u16,
+        body: &[u8],
+    ) -> Self {
+        Self {
+            method: method.to_string(),
+            url: url.to_string(),
+            final_url: (final_url.as_str() != url).then(|| final_url.to_string()),
+            cache: CacheStatus::Miss,
+            status: Some(status),
+            body: Some(Body::new(body)),
+            error: None,
+        }
+    }
+
+    /// A request that got no response, or whose body couldn't be read
+    pub(crate) fn failed(method: &str, url: &str, error: &impl std::fmt::Display) -> Self {
+        Self {
+            method: method.to_string(),
+            url: url.to_string(),
+            final_url: None,
+            cache: CacheStatus::Miss,
+            status: None,
+            body: None,
+            error: Some(error.to_string()),
+        }
+    }
+
+    /// A page served from the disk cache
+    #[cfg(feature = "native")]
+    pub(crate) fn cached(url: &str, body: &str) -> Self {
+        Self {
+            method: "GET".to_string(),
+            url: url.to_string(),
+            final_url: None,
+            cache: CacheStatus::Hit,
+            status: None,
+            body: Some(Body::Text(body.to_string())),
+            error: None,
+        }
+    }
+
+    /// A URL answered Not Found without asking
+    pub(crate) fn known_missing(method: &str, url: &str) -> Self {
+        Self {
+            method: method.to_string(),
+            url: url.to_string(),
+            final_url: None,
+            cache: CacheStatus::NotFound,
+            status: Some(404),
+            body: None,
+            error: None,
+        }
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    pub fn cache(&self) -> CacheStatus {
+        self.cache
+    }
+
+    pub fn status(&self) -> Option<u16> {
+        self.status
+    }
+
+    /// The response again, or the error of a failed request. Pages from the cache answer
+    /// `200 OK`.
+    pub(crate) fn to_response(&self) -> Result<http::Response<Vec<u8>>> {
+        if let Some(error) = &self.error {
+            return Err(anyhow!("{}", error));
+        }
+        let url = Url::parse(self.final_url.as_deref().unwrap_or(&self.url))
+            .context(format!("Invalid recorded URL: {}", self.url))?;
+        let body = match &self.body {
+            Some(body) => body.bytes()?,
+            None => Vec::new(),
+        };
+        Ok(http::Response::builder()
+            .status(self.status.unwrap_or(200))
+            .url(url)
+            .body(body)?)
+    }
+}
+
+/// Writes the session to its file as calls complete
+struct Recorder {
+    path: PathBuf,
+    session: Session,
+    /// The call being answered
+    current: Option<Exchange>,
+}
+
+impl Recorder {
+    fn new(path: &Path) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            session: Session::new(),
+            current: None,
+        }
+    }
+
+    fn begin(&mut self, tool: &str, arguments: Option<&Value>) {
+        self.current = (!tool.starts_with(ADMIN_PREFIX)).then(|| Exchange {
+            tool: tool.to_string(),
+            arguments: arguments.cloned().unwrap_or_default(),
+            response: Value::Null,
+            fetches: Vec::new(),
+        });
+    }
+
+    /// Adds `fetch` to the call being answered; requests made outside a call aren't recorded.
+    fn fetch(&mut self, fetch: Fetch) {
+        if let Some(current) = &mut self.current {
+            current.fetches.push(fetch);
+        }
+    }
+
+    /// Adds the call being answered to the session and saves it, so what was recorded until
+    /// then survives the server being killed.
+    fn end(&mut self, response: &CallToolResponse) -> Result<()> {
+        let Some(mut exchange) = self.current.take() else {
+            return Ok(());
+        };
+        exchange.response = serde_json::to_value(response)?;
+        self.session.exchanges.push(exchange);
+        self.save()
+    }
+
+    fn save(&self) -> Result<()> {
+        let json = serde_json::to_string_pretty(&self.session)?;
+        fs::write(&self.path, json)
+            .context(format!("Failed to write session: {}", self.path.display()))
+    }
+}
+
+fn recorder() -> MutexGuard<'static, Option<Recorder>> {
+    static RECORDER: OnceLock<Mutex<Option<Recorder>>> = OnceLock::new();
+    match RECORDER.get_or_init(|| Mutex::new(None)).lock() {
+        Ok(recorder) => recorder,
+        Err(poisoned) => poisoned.into_inner(),
+    }
+}
+
+/// Records the tool calls from now on to `path`, which is written right away so a path that
+/// can't be written is reported at startup.
+pub fn record_to(path: &Path) -> Result<()> {
+    let recording = Recorder::new(path);
+    recording.save()?;
+    *recorder() = Some(recording);
+    Ok(())
+}
+
+pub(crate) fn is_recording() -> bool {
+    recorder().is_some()
+}
+
+/// Starts recording a call of `tool`, when a session is being recorded.
+pub(crate) fn begin_call(tool: &str, arguments: Option<&Value>) {
+    if let Some(recorder) = recorder().as_mut() {
+        recorder.begin(tool, arguments);
+    }
+}
+
+pub(crate) fn record_fetch(fetch: Fetch) {
+    if let Some(recorder) = recorder().as_mut() {
+        recorder.fetch(fetch);
+    }
+}
+
+/// Records the response of the call begun last, logging rather than failing the call when the
+/// session can't be written.
+pub(crate) fn end_call(response: &CallToolResponse) {
+    if let Some(recorder) = recorder().as_mut() {
+        if let Err(e) = recorder.end(response) {
+            warn!("{:#}", e);
+        }
+    }
+}
+
+/// The recorded fetches of a session, served in place of the network
+struct Replay {
+    fetches: HashMap<(String, String), VecDeque<Fetch>>,
+}
+
+impl Replay {
+    #[cfg(feature = "native")]
+    fn new(session: &Session) -> Self {
+        let mut fetches: HashMap<(String, String), VecDeque<Fetch>> = HashMap::new();
+        for fetch in session.exchanges.iter().flat_map(|e| &e.fetches) {
+            fetches
+                .entry((fetch.method.clone(), fetch.url.clone()))
+                .or_default()
+                .push_back(fetch.clone());
+        }
+        Self { fetches }
+    }
+
+    /// The next recorded answer to `url`. A URL fetched more than once, e.g. retried after an
+    /// error, gets its answers in the recorded order, then the last one again.
+    fn next(&mut self, method: &str, url: &str) -> Result<http::Response<Vec<u8>>> {
+        let answers = self
+            .fetches
+            .get_mut(&(method.to_string(), url.to_string()))
+            .ok_or_else(|| anyhow!("{} {} wasn't fetched in the recorded session", method, url))?;
+        let fetch = match answers.len() {
+            1 => answers.front().cloned(),
+            _ => answers.pop_front(),
+        };
+        fetch
+            .ok_or_else(|| anyhow!("{} {} wasn't fetched in the recorded session", method, url))?
+            .to_response()
+    }
+}
+
+fn replay_state() -> MutexGuard<'static, Option<Replay>> {
+    static REPLAY: OnceLock<Mutex<Option<Replay>>> = OnceLock::new();
+    match REPLAY.get_or_init(|| Mutex::new(None)).lock() {
+        Ok(replay) => replay,
+        Err(poisoned) => poisoned.into_inner(),
+    }
+}
+
+/// The recorded answer to `url` while a session is being replayed, `None` otherwise.
+pub(crate) fn replayed(method: &str, url: &str) -> Option<Result<http::Response<Vec<u8>>>> {
+    replay_state()
+        .as_mut()
+        .map(|replay| replay.next(method, url))
+}
+
+/// Reads a session written by `--record`.
+pub fn load(path: &Path) -> Result<Session> {
+    let json =
+        fs::read_to_string(path).context(format!("Failed to read session: {}", path.display()))?;
+    serde_json::from_str(&json).context(format!("Failed to parse session: {}", path.display()))
+}
+
+/// A call whose response came out differently in the replay
+#[derive(Debug, Clone, PartialEq)]
+pub struct Difference {
+    /// Position of the call in the session, from 1
+    pub call: usize,
+    pub tool: String,
+    pub recorded: Value,
+    pub replayed: Value,
+}
+
+impl Difference {
+    /// The call and the first line of its response that differs, e.g.
+    /// "call 2 (get_struct_docs), line 7:\n- recorded\n+ replayed"
+    pub fn describe(&self) -> String {
+        let recorded = response_text(&self.recorded);
+        let replayed = response_text(&self.replayed);
+        let mut recorded_lines = recorded.lines();
+        let mut replayed_lines = replayed.lines();
+        let mut line = 1;
+        loop {
+            match (recorded_lines.next(), replayed_lines.next()) {
+                (Some(a), Some(b)) if a == b => line += 1,
+                (a, b) => {
+                    return format!(
+                        "call {} ({}), line {}:\n- {}\n+ {}",
+                        self.call,
+                        self.tool,
+                        line,
+                        a.unwrap_or("<end>"),
+                        b.unwrap_or("<end>")
+                    )
+                }
+            }
+        }
+    }
+}
+
+/// The text contents of a response, or the response as JSON when it has none
+fn response_text(response: &Value) -> String {
+    let texts: Vec<&str> = response["content"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|content| content["text"].as_str())
+        .collect();
+    match texts.is_empty() {
+        true => serde_json::to_string_pretty(response).unwrap_or_default(),
+        false => texts.join("\n"),
+    }
+}
+
+/// The outcome of replaying a session
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReplaySummary {
+    pub server_version: String,
+    pub replayed: usize,
+    pub differences: Vec<Difference>,
+}
+
+/// Runs the calls of the session in `path` again, answering their requests with the recorded
+/// responses, and compares what the tools answer now with what they answered then.
+#[cfg(feature = "native")]
+pub fn replay(path: &Path) -> Result<ReplaySummary> {
+    let session = load(path)?;
+    *replay_state() = Some(Replay::new(&session));
+    let tools = crate::server::tool_set(true);
+
+    let mut differences = Vec::new();
+    for (index, exchange) in session.exchanges.iter().enumerate() {
+        let request = CallToolRequest {
+            name: exchange.tool.clone(),
+            arguments: (!exchange.arguments.is_null()).then(|| exchange.arguments.clone()),
+            meta: None,
+        };
+        // Failures come back as responses too, as the client got them
+        let replayed = serde_json::to_value(tools.call_tool(request))?;
+        if replayed != exchange.response {
+            differences.push(Difference {
+                call: index + 1,
+                tool: exchange.tool.clone(),
+                recorded: exchange.response.clone(),
+                replayed,
+            });
+        }
+    }
+    *replay_state() = None;
+    Ok(ReplaySummary {
+        server_version: session.server_version,
+        replayed: session.exchanges.len(),
+        differences,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mcp_sdk::types::ToolResponseContent;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    fn text_response(text: &str) -> CallToolResponse {
+        CallToolResponse {
+            content: vec![ToolResponseContent::Text {
+                text: text.to_string(),
+            }],
+            is_error: None,
+            meta: None,
+        }
+    }
+
+    #[test]
+    fn test_fetch_to_response() -> Result<()> {
+        let redirected = Url::parse("https://docs.rs/tokio/1.43.0/tokio/")?;
+        let fetch = Fetch::received("GET", "https://docs.rs/tokio", &redirected, 200, b"<html>");
+        let response = reqwest::Response::from(fetch.to_response()?);
+        assert_eq!(response.url(), &redirected, "Redirects are replayed");
+        assert_eq!(response.status(), 200);
+
+        let gzipped = Fetch::received("GET", redirected.as_str(), &redirected, 200, &[0x1f, 0x8b]);
+        assert!(matches!(gzipped.body, Some(Body::Base64(_))));
+        assert_eq!(gzipped.final_url, None);
+        assert_eq!(gzipped.to_response()?.into_body(), [0x1f, 0x8b]);
+
+        assert_eq!(
+            Fetch::cached("https://docs.rs/tokio", "<html>")
+                .to_response()?
+                .status(),
+            200
+        );
+        let failed = Fetch::failed("GET", "https://docs.rs/tokio", &"operation timed out");
+        assert!(failed.to_response().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_recorder() -> Result<()> {
+        let path = std::env::temp_dir().join(format!(
+            "docs-rs-mcp-session-test-{}.json",
+            std::process::id()
+        ));
+        let mut recorder = Recorder::new(&path);
+        recorder.fetch(Fetch::cached("https://docs.rs/outside", ""));
+        recorder.begin("crate_items", Some(&json!({ "crate_name": "tokio" })));
+        recorder.fetch(Fetch::cached(
+            "https://docs.rs/tokio/latest/tokio/all.html",
+            "<html>",
+        ));
+        recorder.end(&text_response("[]"))?;
+        recorder.begin("admin/stats", Some(&json!({ "token": "secret" })));
+        recorder.fetch(Fetch::known_missing("GET", "https://docs.rs/admin"));
+        recorder.end(&text_response("{}"))?;
+
+        let session = load(&path)?;
+        fs::remove_file(&path)?;
+        assert_eq!(session.server_version(), env!("CARGO_PKG_VERSION"));
+        assert_eq!(session.exchanges().len(), 1, "Admin calls aren't recorded");
+        let exchange = &session.exchanges()[0];
+        assert_eq!(exchange.tool(), "crate_items");
+        assert_eq!(exchange.response["content"][0]["text"], "[]");
+        let urls: Vec<&str> = exchange.fetches().iter().map(Fetch::url).collect();
+        assert_eq!(urls, ["https://docs.rs/tokio/latest/tokio/all.html"]);
+        assert_eq!(exchange.fetches()[0].cache(), CacheStatus::Hit);
+        Ok(())
+    }
+
+    #[test]
+    fn test_replay_order() -> Result<()> {
+        let url = Url::parse("https://crates.io/api/v1/crates/tokio")?;
+        let mut session = Session::new();
+        session.exchanges.push(Exchange {
+            tool: "crate_info".to_string(),
+            arguments: json!({ "crate_name": "tokio" }),
+            response: Value::Null,
+            fetches: vec![
+                Fetch::received("GET", url.as_str(), &url, 503, b""),
+                Fetch::received("GET", url.as_str(), &url, 200, b"{}"),
+            ],
+        });
+        let mut replay = Replay::new(&session);
+        let statuses: Vec<u16> = (0..3)
+            .map(|_| Ok(replay.next("GET", url.as_str())?.status().as_u16()))
+            .collect::<Result<_>>()?;
+        assert_eq!(statuses, [503, 200, 200]);
+        assert!(replay.next("GET", "https://docs.rs/tokio").is_err());
+        assert!(replay.next("POST", url.as_str()).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_describe() -> Result<()> {
+        let difference = Difference {
+            call: 2,
+            tool: "get_struct_docs".to_string(),
+            recorded: serde_json::to_value(text_response("# Mutex\nfields: 1\n"))?,
+            replayed: serde_json::to_value(text_response("# Mutex\nfields: 0\n"))?,
+        };
+        assert_eq!(
+            difference.describe(),
+            "call 2 (get_struct_docs), line 2:\n- fields: 1\n+ fields: 0"
+        );
+        Ok(())
+    }
+}
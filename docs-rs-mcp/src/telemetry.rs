@@ -0,0 +1,259 @@
+//! Opt-in OpenTelemetry export of traces and metrics, built with the `otel` feature. Export only
+//! starts when an OTLP endpoint is configured through the standard environment variables:
+//!
+//! - `OTEL_EXPORTER_OTLP_ENDPOINT`, or the per-signal `OTEL_EXPORTER_OTLP_TRACES_ENDPOINT` and
+//!   `OTEL_EXPORTER_OTLP_METRICS_ENDPOINT`, e.g. `http://localhost:4318`. Data is sent as
+//!   HTTP/protobuf; `OTEL_EXPORTER_OTLP_HEADERS` and `OTEL_EXPORTER_OTLP_TIMEOUT` apply too.
+//! - `OTEL_SERVICE_NAME` and `OTEL_RESOURCE_ATTRIBUTES` describe the service, which is named
+//!   `docs-rs-mcp` by default.
+//! - `OTEL_SDK_DISABLED=true` turns export off even with an endpoint set.
+//!
+//! Tool calls, upstream fetches and cache lookups run in `tracing` spans, which become the
+//! exported traces, and are counted by the `record_*` functions, which do nothing without the
+//! feature.
+
+use std::time::Duration;
+
+/// Counts a finished tool call and its duration.
+#[cfg(feature = "native")]
+pub(crate) fn record_tool_call(tool: &str, elapsed: Duration, ok: bool) {
+    #[cfg(feature = "otel")]
+    otel::instruments().record_tool_call(tool, elapsed, ok);
+    #[cfg(not(feature = "otel"))]
+    let _ = (tool, elapsed, ok);
+}
+
+/// Counts an upstream request by host and status, `None` when no response arrived.
+pub(crate) fn record_fetch(url: &str, status: Option<u16>, elapsed: Duration) {
+    #[cfg(feature = "otel")]
+    otel::instruments().record_fetch(url, status, elapsed);
+    #[cfg(not(feature = "otel"))]
+    let _ = (url, status, elapsed);
+}
+
+/// Counts a lookup in one of the caches, e.g. `pages`.
+pub(crate) fn record_cache_lookup(cache: &'static str, hit: bool) {
+    #[cfg(feature = "otel")]
+    otel::instruments().record_cache_lookup(cache, hit);
+    #[cfg(not(feature = "otel"))]
+    let _ = (cache, hit);
+}
+
+#[cfg(feature = "otel")]
+pub use otel::{init, Telemetry};
+
+#[cfg(feature = "otel")]
+mod otel {
+    use std::sync::OnceLock;
+    use std::time::Duration;
+
+    use anyhow::{Context, Result};
+    use opentelemetry::metrics::{Counter, Histogram};
+    use opentelemetry::trace::TracerProvider;
+    use opentelemetry::{global, Key, KeyValue};
+    use opentelemetry_sdk::metrics::{PeriodicReader, SdkMeterProvider};
+    use opentelemetry_sdk::trace::SdkTracerProvider;
+    use opentelemetry_sdk::Resource;
+    use tracing::Subscriber;
+    use tracing_subscriber::filter::LevelFilter;
+    use tracing_subscriber::registry::LookupSpan;
+    use tracing_subscriber::Layer;
+    use url::Url;
+
+    const NAME: &str = env!("CARGO_PKG_NAME");
+
+    /// Variables naming an OTLP endpoint, any of which turns export on
+    const ENDPOINT_VARS: [&str; 3] = [
+        "OTEL_EXPORTER_OTLP_ENDPOINT",
+        "OTEL_EXPORTER_OTLP_TRACES_ENDPOINT",
+        "OTEL_EXPORTER_OTLP_METRICS_ENDPOINT",
+    ];
+
+    /// The exporters in use; dropping it flushes and shuts them down.
+    pub struct Telemetry {
+        tracer_provider: SdkTracerProvider,
+        meter_provider: SdkMeterProvider,
+    }
+
+    /// Starts exporting if the environment configures an endpoint. The exporters use a
+    /// blocking HTTP client, so call this outside of async code, e.g. in `spawn_blocking`.
+    pub fn init() -> Result<Option<Telemetry>> {
+        if !enabled(|key| std::env::var(key).ok()) {
+            return Ok(None);
+        }
+
+        let resource = Resource::builder().build();
+        let named = resource
+            .get(&Key::new("service.name"))
+            .is_some_and(|name| !name.as_str().starts_with("unknown_service"));
+        let resource = match named {
+            true => resource,
+            false => Resource::builder().with_service_name(NAME).build(),
+        };
+
+        let span_exporter = opentelemetry_otlp::SpanExporter::builder()
+            .with_http()
+            .build()
+            .context("Failed to build the OTLP span exporter")?;
+        let tracer_provider = SdkTracerProvider::builder()
+            .with_batch_exporter(span_exporter)
+            .with_resource(resource.clone())
+            .build();
+
+        let metric_exporter = opentelemetry_otlp::MetricExporter::builder()
+            .with_http()
+            .build()
+            .context("Failed to build the OTLP metric exporter")?;
+        let meter_provider = SdkMeterProvider::builder()
+            .with_reader(PeriodicReader::builder(metric_exporter).build())
+            .with_resource(resource)
+            .build();
+        global::set_meter_provider(meter_provider.clone());
+
+        Ok(Some(Telemetry {
+            tracer_provider,
+            meter_provider,
+        }))
+    }
+
+    fn enabled(lookup: impl Fn(&str) -> Option<String>) -> bool {
+        if lookup("OTEL_SDK_DISABLED").is_some_and(|disabled| disabled.trim() == "true") {
+            return false;
+        }
+        ENDPOINT_VARS
+            .iter()
+            .any(|key| lookup(key).is_some_and(|endpoint| !endpoint.trim().is_empty()))
+    }
+
+    impl Telemetry {
+        /// Exports the spans of `info` level and above as traces, independently of the log
+        /// level.
+        pub fn layer<S>(&self) -> impl Layer<S>
+        where
+            S: Subscriber + for<'span> LookupSpan<'span>,
+        {
+            tracing_opentelemetry::layer()
+                .with_tracer(self.tracer_provider.tracer(NAME))
+                .with_filter(LevelFilter::INFO)
+        }
+    }
+
+    impl Drop for Telemetry {
+        fn drop(&mut self) {
+            if let Err(e) = self.tracer_provider.shutdown() {
+                eprintln!("Failed to flush traces: {}", e);
+            }
+            if let Err(e) = self.meter_provider.shutdown() {
+                eprintln!("Failed to flush metrics: {}", e);
+            }
+        }
+    }
+
+    pub(super) struct Instruments {
+        tool_calls: Counter<u64>,
+        tool_duration: Histogram<f64>,
+        fetches: Counter<u64>,
+        fetch_duration: Histogram<f64>,
+        cache_lookups: Counter<u64>,
+    }
+
+    /// The instruments, created from the global meter provider on first use, which is after
+    /// [`init`] installed it.
+    pub(super) fn instruments() -> &'static Instruments {
+        static INSTRUMENTS: OnceLock<Instruments> = OnceLock::new();
+        INSTRUMENTS.get_or_init(|| {
+            let meter = global::meter(NAME);
+            Instruments {
+                tool_calls: meter
+                    .u64_counter("docs_rs_mcp.tool.calls")
+                    .with_description("Tool calls by tool and outcome")
+                    .build(),
+                tool_duration: meter
+                    .f64_histogram("docs_rs_mcp.tool.duration")
+                    .with_unit("s")
+                    .with_description("Time taken by tool calls")
+                    .build(),
+                fetches: meter
+                    .u64_counter("docs_rs_mcp.http.requests")
+                    .with_description("Upstream requests by host and status")
+                    .build(),
+                fetch_duration: meter
+                    .f64_histogram("docs_rs_mcp.http.duration")
+                    .with_unit("s")
+                    .with_description("Time taken by upstream requests")
+                    .build(),
+                cache_lookups: meter
+                    .u64_counter("docs_rs_mcp.cache.lookups")
+                    .with_description("Cache lookups by cache and result")
+                    .build(),
+            }
+        })
+    }
+
+    impl Instruments {
+        pub(super) fn record_tool_call(&self, tool: &str, elapsed: Duration, ok: bool) {
+            let attributes = [
+                KeyValue::new("tool", tool.to_string()),
+                KeyValue::new("outcome", if ok { "ok" } else { "error" }),
+            ];
+            self.tool_calls.add(1, &attributes);
+            self.tool_duration
+                .record(elapsed.as_secs_f64(), &attributes[..1]);
+        }
+
+        pub(super) fn record_fetch(&self, url: &str, status: Option<u16>, elapsed: Duration) {
+            let host = Url::parse(url)
+                .ok()
+                .and_then(|url| url.host_str().map(str::to_string))
+                .unwrap_or_default();
+            let status = status.map(i64::from).unwrap_or(0);
+            self.fetches.add(
+                1,
+                &[
+                    KeyValue::new("host", host.clone()),
+                    KeyValue::new("status", status),
+                ],
+            );
+            self.fetch_duration
+                .record(elapsed.as_secs_f64(), &[KeyValue::new("host", host)]);
+        }
+
+        pub(super) fn record_cache_lookup(&self, cache: &'static str, hit: bool) {
+            self.cache_lookups.add(
+                1,
+                &[
+                    KeyValue::new("cache", cache),
+                    KeyValue::new("result", if hit { "hit" } else { "miss" }),
+                ],
+            );
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use std::collections::HashMap;
+
+        fn enabled_with(vars: &[(&str, &str)]) -> bool {
+            let vars: HashMap<&str, &str> = vars.iter().copied().collect();
+            enabled(|key| vars.get(key).map(|value| value.to_string()))
+        }
+
+        #[test]
+        fn test_enabled() {
+            assert!(!enabled_with(&[]), "Export is opt-in");
+            assert!(enabled_with(&[(
+                "OTEL_EXPORTER_OTLP_ENDPOINT",
+                "http://localhost:4318"
+            )]));
+            assert!(enabled_with(&[(
+                "OTEL_EXPORTER_OTLP_TRACES_ENDPOINT",
+                "http://localhost:4318/v1/traces"
+            )]));
+            assert!(!enabled_with(&[
+                ("OTEL_EXPORTER_OTLP_ENDPOINT", "http://localhost:4318"),
+                ("OTEL_SDK_DISABLED", "true"),
+            ]));
+        }
+    }
+}
@@ -0,0 +1,128 @@
+use anyhow::Result;
+use mcp_sdk::{
+    tools::Tool,
+    types::{CallToolResponse, ToolResponseContent},
+};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use super::{authorize, ADMIN_PREFIX};
+use crate::cache::{self, Purged};
+use crate::cache_key::mentions_crate;
+use crate::error;
+use crate::tools::{all_items_cache, http};
+
+/// Everything [`CachePurgeTool`] dropped
+#[derive(Debug, Serialize)]
+pub struct CachePurge {
+    /// The crate purged, `None` when the whole cache was
+    crate_name: Option<String>,
+    /// Entries removed from the disk cache
+    disk: Purged,
+    /// `all.html` pages dropped from memory
+    item_listings: usize,
+    /// URLs no longer remembered as Not Found
+    not_found: usize,
+}
+
+#[derive(Debug, Deserialize)]
+struct CachePurgeParams {
+    crate_name: Option<String>,
+}
+
+/// Drops what is cached for a crate, or for every crate, on disk and in memory.
+pub struct CachePurgeTool;
+
+impl CachePurgeTool {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for CachePurgeTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Tool for CachePurgeTool {
+    fn name(&self) -> String {
+        format!("{}cache_purge", ADMIN_PREFIX)
+    }
+
+    fn description(&self) -> String {
+        "Drop the cached pages, parsed data, search index entries and remembered Not Found \
+        answers of a crate, or of every crate when none is given, so the next lookup fetches \
+        them again. Use it after a crate was republished or its docs were rebuilt."
+            .to_string()
+    }
+
+    fn input_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "token": {
+                    "type": "string",
+                    "description": "Admin token, required when the server is configured with one"
+                },
+                "crate_name": {
+                    "type": "string",
+                    "description": "Crate to purge (defaults to the whole cache)"
+                }
+            }
+        })
+    }
+
+    fn call(&self, input: Option<serde_json::Value>) -> Result<CallToolResponse> {
+        authorize(input.as_ref())?;
+        let args: CachePurgeParams = error::parse_args(input)?;
+        let crate_name = args.crate_name.as_deref();
+        let matches = |url: &str| crate_name.is_none_or(|name| mentions_crate(url, name));
+
+        let purge = CachePurge {
+            disk: cache::purge(crate_name)?,
+            item_listings: all_items_cache::forget(matches),
+            not_found: http::shared()?.forget_not_found(matches),
+            crate_name: args.crate_name,
+        };
+
+        Ok(CallToolResponse {
+            content: vec![ToolResponseContent::Text {
+                text: serde_json::to_string_pretty(&purge)?,
+            }],
+            is_error: None,
+            meta: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{self, Config};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_purge_crate() -> Result<()> {
+        let dir =
+            std::env::temp_dir().join(format!("docs-rs-mcp-admin-purge-{}", std::process::id()));
+        let config = Config::from_toml(&format!("[cache]\ndir = {:?}\n", dir))?;
+        let page = "https://docs.rs/demo/0.4.2/demo/struct.Frame.html";
+
+        let response = config::scoped(config, || {
+            cache::put(page, "<html>Frame</html>");
+            let response = CachePurgeTool::new().call(Some(json!({ "crate_name": "demo" })))?;
+            assert_eq!(cache::get(page), None);
+            Ok::<_, anyhow::Error>(response)
+        })?;
+        let ToolResponseContent::Text { text } = &response.content[0] else {
+            panic!("Expected a text response");
+        };
+        let purge: serde_json::Value = serde_json::from_str(text)?;
+        assert_eq!(purge["crate_name"], "demo");
+        assert_eq!(purge["disk"]["pages"], 1);
+
+        std::fs::remove_dir_all(&dir)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,45 @@
+//! Operator tools, exposed under the `admin/` prefix. They can be disabled or protected by a
+//! token (`[admin]` in the config file) independently of the documentation tools.
+
+#[cfg(feature = "native")]
+pub mod cache_purge;
+#[cfg(feature = "native")]
+pub mod prefetch;
+pub mod reload_config;
+pub mod stats;
+
+#[cfg(feature = "native")]
+pub use cache_purge::CachePurgeTool;
+#[cfg(feature = "native")]
+pub use prefetch::PrefetchTool;
+pub use reload_config::ReloadConfigTool;
+pub use stats::StatsTool;
+
+use anyhow::Result;
+use serde_json::json;
+
+use crate::config;
+
+/// Prefix of every admin tool name
+pub const ADMIN_PREFIX: &str = "admin/";
+
+/// Checks the `token` argument of an admin tool call against the server configuration.
+fn authorize(input: Option<&serde_json::Value>) -> Result<()> {
+    let token = input
+        .and_then(|input| input.get("token"))
+        .and_then(serde_json::Value::as_str);
+    config::current().authorize_admin(token)
+}
+
+/// Input schema shared by admin tools that take no arguments besides the token.
+fn token_only_schema() -> serde_json::Value {
+    json!({
+        "type": "object",
+        "properties": {
+            "token": {
+                "type": "string",
+                "description": "Admin token, required when the server is configured with one"
+            }
+        }
+    })
+}
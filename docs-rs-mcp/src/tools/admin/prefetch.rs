@@ -0,0 +1,153 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use anyhow::{bail, Result};
+use mcp_sdk::{
+    tools::Tool,
+    types::{CallToolResponse, ToolResponseContent},
+};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tracing::{info, warn};
+
+use super::{authorize, ADMIN_PREFIX};
+use crate::config;
+use crate::error;
+use crate::index::{self, CrateSpec, IndexOptions, IndexProgress};
+
+/// Whether a prefetch is running; one at a time is enough to warm the cache
+static RUNNING: AtomicBool = AtomicBool::new(false);
+
+/// What [`PrefetchTool`] started
+#[derive(Debug, Serialize)]
+pub struct Prefetch {
+    /// Crates being indexed, as `name@version`
+    crates: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PrefetchParams {
+    crates: Vec<String>,
+}
+
+/// Indexes crates in the background, as `docs-rs-mcp index` does, so lookups in them are
+/// answered from the cache once a client asks.
+pub struct PrefetchTool;
+
+impl PrefetchTool {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for PrefetchTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Tool for PrefetchTool {
+    fn name(&self) -> String {
+        format!("{}prefetch", ADMIN_PREFIX)
+    }
+
+    fn description(&self) -> String {
+        "Fetch and cache the documentation and item listings of crates in the background, \
+        so later lookups in them don't wait on docs.rs. Returns as soon as the work is \
+        started; only one prefetch runs at a time."
+            .to_string()
+    }
+
+    fn input_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "token": {
+                    "type": "string",
+                    "description": "Admin token, required when the server is configured with one"
+                },
+                "crates": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Crates to prefetch, as name@version or just name for the latest version"
+                }
+            },
+            "required": ["crates"]
+        })
+    }
+
+    fn call(&self, input: Option<serde_json::Value>) -> Result<CallToolResponse> {
+        authorize(input.as_ref())?;
+        let args: PrefetchParams = error::parse_args(input)?;
+        let crates = args
+            .crates
+            .iter()
+            .map(|spec| spec.parse())
+            .collect::<Result<Vec<CrateSpec>>>()?;
+        if crates.is_empty() {
+            bail!("No crates to prefetch");
+        }
+        let config = config::current();
+        for spec in &crates {
+            config.ensure_crate_allowed(spec.name())?;
+        }
+        if RUNNING
+            .compare_exchange(false, true, Ordering::AcqRel, Ordering::Acquire)
+            .is_err()
+        {
+            bail!("A prefetch is already running");
+        }
+
+        let prefetch = Prefetch {
+            crates: crates.iter().map(CrateSpec::to_string).collect(),
+        };
+        let options = IndexOptions::for_crates(crates);
+        let config = (*config).clone();
+        std::thread::spawn(move || {
+            let result = index::run(&config, &options, log_progress);
+            RUNNING.store(false, Ordering::Release);
+            match result {
+                Ok(summary) => info!(
+                    "Prefetched {} crates, {} failed",
+                    summary.indexed,
+                    summary.failed.len()
+                ),
+                Err(e) => warn!("Prefetch failed: {:#}", e),
+            }
+        });
+
+        Ok(CallToolResponse {
+            content: vec![ToolResponseContent::Text {
+                text: serde_json::to_string_pretty(&prefetch)?,
+            }],
+            is_error: None,
+            meta: None,
+        })
+    }
+}
+
+fn log_progress(progress: IndexProgress) {
+    match progress.error {
+        None => info!(
+            "Prefetched {} ({}/{})",
+            progress.spec, progress.position, progress.total
+        ),
+        Some(e) => warn!("Failed to prefetch {}: {:#}", progress.spec, e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rejects_bad_crates() {
+        let tool = PrefetchTool::new();
+        assert!(tool.call(Some(json!({ "crates": [] }))).is_err());
+        assert!(tool.call(Some(json!({ "crates": ["@1.0.0"] }))).is_err());
+        assert!(tool.call(None).is_err(), "crates is required");
+        assert!(
+            !RUNNING.load(Ordering::Acquire),
+            "Rejected calls don't start a prefetch"
+        );
+    }
+}
@@ -0,0 +1,54 @@
+use anyhow::Result;
+use mcp_sdk::{
+    tools::Tool,
+    types::{CallToolResponse, ToolResponseContent},
+};
+
+use super::{authorize, token_only_schema, ADMIN_PREFIX};
+use crate::config;
+use crate::tools::runtime;
+
+/// Re-reads the server configuration, the tool equivalent of sending the server `SIGHUP`.
+pub struct ReloadConfigTool;
+
+impl ReloadConfigTool {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for ReloadConfigTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Tool for ReloadConfigTool {
+    fn name(&self) -> String {
+        format!("{}reload_config", ADMIN_PREFIX)
+    }
+
+    fn description(&self) -> String {
+        "Reload the server configuration file and environment without restarting, \
+        so cache TTLs, allow-lists and source priorities can be changed on a running \
+        server. Returns the configuration now in effect."
+            .to_string()
+    }
+
+    fn input_schema(&self) -> serde_json::Value {
+        token_only_schema()
+    }
+
+    fn call(&self, input: Option<serde_json::Value>) -> Result<CallToolResponse> {
+        authorize(input.as_ref())?;
+        let config = runtime::blocking(config::reload)?;
+
+        Ok(CallToolResponse {
+            content: vec![ToolResponseContent::Text {
+                text: serde_json::to_string_pretty(&*config)?,
+            }],
+            is_error: None,
+            meta: None,
+        })
+    }
+}
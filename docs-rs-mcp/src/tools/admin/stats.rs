@@ -0,0 +1,130 @@
+use std::collections::BTreeMap;
+use std::sync::{Mutex, OnceLock};
+use std::time::Instant;
+
+use anyhow::Result;
+use mcp_sdk::{
+    tools::Tool,
+    types::{CallToolResponse, ToolResponseContent},
+};
+use serde::{Deserialize, Serialize};
+
+use super::{authorize, token_only_schema, ADMIN_PREFIX};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ServerStats {
+    uptime_secs: u64,
+    tools: Vec<ToolStats>,
+}
+
+impl ServerStats {
+    pub fn uptime_secs(&self) -> u64 {
+        self.uptime_secs
+    }
+
+    pub fn tools(&self) -> &[ToolStats] {
+        &self.tools
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ToolStats {
+    name: String,
+    calls: u64,
+    errors: u64,
+}
+
+impl ToolStats {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn calls(&self) -> u64 {
+        self.calls
+    }
+
+    pub fn errors(&self) -> u64 {
+        self.errors
+    }
+}
+
+/// When the server started, which is when the tool set was first built
+pub(crate) fn started_at() -> &'static Instant {
+    static STARTED_AT: OnceLock<Instant> = OnceLock::new();
+    STARTED_AT.get_or_init(Instant::now)
+}
+
+fn counters() -> &'static Mutex<BTreeMap<String, ToolStats>> {
+    static COUNTERS: OnceLock<Mutex<BTreeMap<String, ToolStats>>> = OnceLock::new();
+    COUNTERS.get_or_init(|| Mutex::new(BTreeMap::new()))
+}
+
+/// Counts a call of the tool `name`.
+pub(crate) fn record_call(name: &str, succeeded: bool) {
+    let mut counters = match counters().lock() {
+        Ok(counters) => counters,
+        Err(poisoned) => poisoned.into_inner(),
+    };
+    let stats = counters
+        .entry(name.to_string())
+        .or_insert_with(|| ToolStats {
+            name: name.to_string(),
+            ..ToolStats::default()
+        });
+    stats.calls += 1;
+    if !succeeded {
+        stats.errors += 1;
+    }
+}
+
+/// Snapshot of the call counters of every [`Tracked`](crate::server::Tracked) tool.
+pub fn snapshot() -> ServerStats {
+    let counters = match counters().lock() {
+        Ok(counters) => counters,
+        Err(poisoned) => poisoned.into_inner(),
+    };
+    ServerStats {
+        uptime_secs: started_at().elapsed().as_secs(),
+        tools: counters.values().cloned().collect(),
+    }
+}
+
+pub struct StatsTool;
+
+impl StatsTool {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for StatsTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Tool for StatsTool {
+    fn name(&self) -> String {
+        format!("{}stats", ADMIN_PREFIX)
+    }
+
+    fn description(&self) -> String {
+        "Get server statistics: uptime and the number of calls and failures per tool.".to_string()
+    }
+
+    fn input_schema(&self) -> serde_json::Value {
+        token_only_schema()
+    }
+
+    fn call(&self, input: Option<serde_json::Value>) -> Result<CallToolResponse> {
+        authorize(input.as_ref())?;
+
+        Ok(CallToolResponse {
+            content: vec![ToolResponseContent::Text {
+                text: serde_json::to_string_pretty(&snapshot())?,
+            }],
+            is_error: None,
+            meta: None,
+        })
+    }
+}
@@ -0,0 +1,197 @@
+//! In-memory cache of the `all.html` pages fetched during a session, keyed by URL and so by
+//! registry, crate and version. Looking several items up in the same crate fetches and parses
+//! its listing once; the least recently used pages are dropped beyond [`CAPACITY`].
+
+use std::collections::VecDeque;
+use std::future::Future;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use scraper::Html;
+
+use super::selectors;
+use crate::{config, telemetry};
+
+/// Pages kept at most
+const CAPACITY: usize = 32;
+
+/// Links to struct pages, matched by either docs.rs layout
+const STRUCT_LINK_SELECTORS: [&str; 2] = [
+    "h3#structs + ul.all-items > li > a",
+    "div[id='structs'] > div.item-table > div.item-row > a",
+];
+
+/// A fetched `all.html` page, parsed on first use
+#[derive(Debug)]
+pub(crate) struct AllItemsPage {
+    html: String,
+    struct_links: OnceLock<Vec<StructLink>>,
+}
+
+/// A link of the page's struct section
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct StructLink {
+    /// Path of the struct as listed, e.g. `sync::Mutex`
+    pub(crate) text: String,
+    pub(crate) href: String,
+}
+
+impl AllItemsPage {
+    fn new(html: String) -> Self {
+        Self {
+            html,
+            struct_links: OnceLock::new(),
+        }
+    }
+
+    pub(crate) fn html(&self) -> &str {
+        &self.html
+    }
+
+    /// The struct links of the page, in page order
+    pub(crate) fn struct_links(&self) -> Result<&[StructLink]> {
+        if let Some(links) = self.struct_links.get() {
+            return Ok(links);
+        }
+        let document = Html::parse_document(&self.html);
+        let mut links = Vec::new();
+        for selector in STRUCT_LINK_SELECTORS {
+            let selector = selectors::parse(selector, "all items page")?;
+            links.extend(document.select(&selector).map(|element| StructLink {
+                text: element.text().collect(),
+                href: element.value().attr("href").unwrap_or_default().to_string(),
+            }));
+        }
+        Ok(self.struct_links.get_or_init(|| links))
+    }
+}
+
+/// Pages by URL, most recently used first
+#[derive(Debug)]
+struct Lru {
+    entries: VecDeque<(String, Instant, Arc<AllItemsPage>)>,
+    capacity: usize,
+}
+
+impl Lru {
+    fn new(capacity: usize) -> Self {
+        Self {
+            entries: VecDeque::new(),
+            capacity,
+        }
+    }
+
+    fn get(&mut self, url: &str, ttl: Duration) -> Option<Arc<AllItemsPage>> {
+        let position = self.entries.iter().position(|(key, _, _)| key == url)?;
+        let entry = self.entries.remove(position)?;
+        if entry.1.elapsed() > ttl {
+            return None;
+        }
+        let page = Arc::clone(&entry.2);
+        self.entries.push_front(entry);
+        Some(page)
+    }
+
+    fn insert(&mut self, url: &str, page: Arc<AllItemsPage>) {
+        self.entries.retain(|(key, _, _)| key != url);
+        self.entries
+            .push_front((url.to_string(), Instant::now(), page));
+        self.entries.truncate(self.capacity);
+    }
+
+    #[cfg(feature = "native")]
+    fn remove_matching(&mut self, matches: impl Fn(&str) -> bool) -> usize {
+        let before = self.entries.len();
+        self.entries.retain(|(key, _, _)| !matches(key));
+        before - self.entries.len()
+    }
+}
+
+fn pages() -> &'static Mutex<Lru> {
+    static PAGES: OnceLock<Mutex<Lru>> = OnceLock::new();
+    PAGES.get_or_init(|| Mutex::new(Lru::new(CAPACITY)))
+}
+
+/// Drops the pages whose URL `matches`, returning how many were cached.
+#[cfg(feature = "native")]
+pub(crate) fn forget(matches: impl Fn(&str) -> bool) -> usize {
+    match pages().lock() {
+        Ok(mut pages) => pages.remove_matching(matches),
+        Err(poisoned) => poisoned.into_inner().remove_matching(matches),
+    }
+}
+
+/// The page at `url`, fetched by awaiting `fetch` unless a copy younger than the cache TTL is
+/// cached.
+pub(crate) async fn get_or_fetch(
+    url: &str,
+    fetch: impl Future<Output = Result<String>>,
+) -> Result<Arc<AllItemsPage>> {
+    let config = config::current();
+    if !config.cache().enabled() {
+        return Ok(Arc::new(AllItemsPage::new(fetch.await?)));
+    }
+    if let Some(page) = pages()
+        .lock()
+        .ok()
+        .and_then(|mut pages| pages.get(url, config.cache().ttl()))
+    {
+        telemetry::record_cache_lookup("all_items", true);
+        return Ok(page);
+    }
+    telemetry::record_cache_lookup("all_items", false);
+
+    let page = Arc::new(AllItemsPage::new(fetch.await?));
+    if let Ok(mut pages) = pages().lock() {
+        pages.insert(url, Arc::clone(&page));
+    }
+    Ok(page)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::anyhow;
+    use pretty_assertions::assert_eq;
+
+    #[tokio::test]
+    async fn test_fetches_once() -> Result<()> {
+        let url = "https://docs.rs/demo/0.1.0/demo/all.html";
+        let html = r#"<h3 id="structs">Structs</h3>
+            <ul class="all-items"><li><a href="codec/struct.Frame.html">codec::Frame</a></li></ul>"#;
+
+        let page = get_or_fetch(url, async { Ok(html.to_string()) }).await?;
+        let cached = get_or_fetch(url, async {
+            Err(anyhow!("Should be served from the cache"))
+        })
+        .await?;
+        assert!(Arc::ptr_eq(&page, &cached));
+        assert_eq!(
+            cached.struct_links()?,
+            [StructLink {
+                text: "codec::Frame".to_string(),
+                href: "codec/struct.Frame.html".to_string(),
+            }]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_evicts_least_recently_used() {
+        let mut lru = Lru::new(2);
+        let ttl = Duration::from_secs(60);
+        for url in ["a", "b"] {
+            lru.insert(url, Arc::new(AllItemsPage::new(String::new())));
+        }
+        // Using the oldest page keeps it, so the other one is dropped instead
+        assert!(lru.get("a", ttl).is_some());
+        lru.insert("c", Arc::new(AllItemsPage::new(String::new())));
+        assert!(lru.get("a", ttl).is_some());
+        assert!(lru.get("b", ttl).is_none());
+        assert!(
+            lru.get("c", Duration::ZERO).is_none(),
+            "Stale pages should be dropped"
+        );
+    }
+}
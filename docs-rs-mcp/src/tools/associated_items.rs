@@ -0,0 +1,332 @@
+//! Reading associated constants and types, such as `pub const MAX: u32 = 4_294_967_295u32` or
+//! `type Err = ParseIntError`, from the impl blocks of an item page
+
+use anyhow::Result;
+use scraper::{CaseSensitivity, ElementRef, Html};
+use serde::{Deserialize, Serialize};
+
+use super::get_struct_docs::{code_header_text, impl_trait_name};
+use super::selectors;
+
+/// Impl lists whose associated items belong to the type. Blanket impls are left out: their
+/// items, such as `TryFrom::Error`, are the same for every type.
+const IMPL_LISTS: [&str; 2] = ["implementations-list", "trait-implementations-list"];
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AssociatedConst {
+    name: String,
+    type_name: String,
+    /// The value, when rustdoc shows it
+    default: Option<String>,
+    /// The trait whose impl defines the constant; `None` for inherent constants
+    trait_name: Option<String>,
+    docs: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AssociatedType {
+    name: String,
+    /// Parameters of a generic associated type, e.g. `<'a>` for `type Item<'a>`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    generics: Option<String>,
+    /// Bounds the type must meet, as declared in a trait
+    bounds: Option<String>,
+    /// The type assigned by an impl, or a trait's default
+    type_name: Option<String>,
+    /// The where clause of a generic associated type, e.g. `Self: 'a`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    where_clause: Option<String>,
+    trait_name: Option<String>,
+    docs: Option<String>,
+}
+
+/// Associated constants and types the inherent and trait impls of an item page define.
+pub(crate) fn parse_impl_items(
+    document: &Html,
+) -> Result<(Vec<AssociatedConst>, Vec<AssociatedType>)> {
+    let block_selector = selectors::parse(
+        &IMPL_LISTS
+            .iter()
+            .map(|list_id| format!("#{list_id} > details.implementors-toggle"))
+            .collect::<Vec<_>>()
+            .join(", "),
+        "item page",
+    )?;
+    let header_selector = selectors::parse("summary h3.code-header", "item page")?;
+    let item_selector = selectors::parse(
+        ".impl-items section.associatedconstant, .impl-items section.associatedtype",
+        "item page",
+    )?;
+
+    let mut constants = Vec::new();
+    let mut types = Vec::new();
+    for block in document.select(&block_selector) {
+        let trait_name = block
+            .select(&header_selector)
+            .next()
+            .and_then(|header| impl_trait_name(&code_header_text(header)));
+        for section in block.select(&item_selector) {
+            let is_type = section
+                .value()
+                .has_class("associatedtype", CaseSensitivity::CaseSensitive);
+            if is_type {
+                types.extend(parse_type(section, trait_name.as_deref()));
+            } else {
+                constants.extend(parse_const(section, trait_name.as_deref()));
+            }
+        }
+    }
+    Ok((constants, types))
+}
+
+/// Reads a `section.associatedconstant`, whose header reads `const NAME: Type = value`.
+pub(crate) fn parse_const(
+    section: ElementRef,
+    trait_name: Option<&str>,
+) -> Option<AssociatedConst> {
+    let header = item_header(section)?;
+    let (_, rest) = header.split_once("const ")?;
+    let (declaration, default) = split_top_level(rest, " = ");
+    let (name, type_name) = declaration.split_once(':')?;
+
+    Some(AssociatedConst {
+        name: name.trim().to_string(),
+        type_name: type_name.trim().to_string(),
+        default: default.map(|value| value.trim().to_string()),
+        trait_name: trait_name.map(str::to_string),
+        docs: item_docs(section),
+    })
+}
+
+/// Reads a `section.associatedtype`, whose header reads `type Name: Bounds = Type`, or
+/// `type Name<'a>: Bounds = Type where Self: 'a` for a generic associated type.
+pub(crate) fn parse_type(section: ElementRef, trait_name: Option<&str>) -> Option<AssociatedType> {
+    let header = item_header(section)?;
+    let (_, rest) = header.split_once("type ")?;
+    let (rest, where_clause) = split_where_clause(rest.trim().trim_end_matches(';'));
+    let (declaration, type_name) = split_top_level(rest, " = ");
+    let (name, bounds) = match split_top_level(declaration, ": ") {
+        (name, Some(bounds)) => (name, Some(bounds.trim().to_string())),
+        (name, None) => (name, None),
+    };
+    let (name, generics) = match name.trim().split_once('<') {
+        Some((name, generics)) => (name, Some(format!("<{}", generics))),
+        None => (name, None),
+    };
+
+    Some(AssociatedType {
+        name: name.trim().to_string(),
+        generics,
+        bounds,
+        type_name: type_name.map(|ty| ty.trim().to_string()),
+        where_clause: where_clause.map(|clause| clause.trim_end_matches(',').to_string()),
+        trait_name: trait_name.map(str::to_string),
+        docs: item_docs(section),
+    })
+}
+
+fn item_header(section: ElementRef) -> Option<String> {
+    let selector = selectors::parse(".code-header", "item page").ok()?;
+    section.select(&selector).next().map(code_header_text)
+}
+
+/// Documented items are wrapped in a `details` toggle, with the docs following the summary
+/// that holds the item's section.
+fn item_docs(section: ElementRef) -> Option<String> {
+    let summary = section.parent().and_then(ElementRef::wrap)?;
+    let toggle = summary.parent().and_then(ElementRef::wrap)?;
+    if summary.value().name() != "summary" || toggle.value().name() != "details" {
+        return None;
+    }
+    toggle
+        .children()
+        .filter_map(ElementRef::wrap)
+        .find(|child| {
+            child
+                .value()
+                .has_class("docblock", CaseSensitivity::CaseSensitive)
+        })
+        .map(|docblock| docblock.text().collect::<String>().trim().to_string())
+        .filter(|docs| !docs.is_empty())
+}
+
+/// Splits off a `where` clause outside of brackets: `&'a mut [T] where Self: 'a` gives
+/// `("&'a mut [T]", Some("Self: 'a"))`. Headers read without the space rustdoc leaves out
+/// before the clause, as in `Timeout<F>where`, are split too.
+pub(crate) fn split_where_clause(text: &str) -> (&str, Option<&str>) {
+    let mut depth = 0usize;
+    let mut previous = ' ';
+    for (i, c) in text.char_indices() {
+        match c {
+            '<' | '(' | '[' => depth += 1,
+            '>' if previous != '-' => depth = depth.saturating_sub(1),
+            ')' | ']' => depth = depth.saturating_sub(1),
+            _ if depth == 0
+                && i > 0
+                && !(previous.is_alphanumeric() || previous == '_')
+                && text[i..].starts_with("where")
+                && !text[i + "where".len()..]
+                    .starts_with(|c: char| c.is_alphanumeric() || c == '_') =>
+            {
+                let clause = text[i + "where".len()..].trim();
+                return (text[..i].trim_end(), (!clause.is_empty()).then_some(clause));
+            }
+            _ => {}
+        }
+        previous = c;
+    }
+    (text, None)
+}
+
+/// Splits at the first `separator` outside of brackets, so `Box<dyn Fn() -> u8>` or
+/// `Iterator<Item = u8>` aren't cut apart.
+pub(crate) fn split_top_level<'a>(text: &'a str, separator: &str) -> (&'a str, Option<&'a str>) {
+    let mut depth = 0usize;
+    let mut previous = ' ';
+    for (i, c) in text.char_indices() {
+        match c {
+            '<' | '(' | '[' => depth += 1,
+            '>' if previous != '-' => depth = depth.saturating_sub(1),
+            ')' | ']' => depth = depth.saturating_sub(1),
+            _ if depth == 0 && text[i..].starts_with(separator) => {
+                return (&text[..i], Some(&text[i + separator.len()..]));
+            }
+            _ => {}
+        }
+        previous = c;
+    }
+    (text, None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::fs;
+
+    #[test]
+    fn test_parse_impl_items() -> Result<()> {
+        let html = fs::read_to_string("test-data/associated_items/struct-page.html")?;
+        let (constants, types) = parse_impl_items(&Html::parse_document(&html))?;
+
+        assert_eq!(
+            constants,
+            vec![
+                AssociatedConst {
+                    name: "BLACK".to_string(),
+                    type_name: "Rgb".to_string(),
+                    default: None,
+                    trait_name: None,
+                    docs: Some("Pure black.".to_string()),
+                },
+                AssociatedConst {
+                    name: "CHANNELS".to_string(),
+                    type_name: "usize".to_string(),
+                    default: Some("3usize".to_string()),
+                    trait_name: None,
+                    docs: None,
+                },
+                AssociatedConst {
+                    name: "DEPTH".to_string(),
+                    type_name: "u32".to_string(),
+                    default: Some("8u32".to_string()),
+                    trait_name: Some("Pixel".to_string()),
+                    docs: Some("Bits per channel.".to_string()),
+                },
+            ]
+        );
+        assert_eq!(
+            types,
+            vec![
+                AssociatedType {
+                    name: "Err".to_string(),
+                    generics: None,
+                    bounds: None,
+                    type_name: Some("ParseRgbError".to_string()),
+                    where_clause: None,
+                    trait_name: Some("FromStr".to_string()),
+                    docs: Some(
+                        "The associated error which can be returned from parsing.".to_string()
+                    ),
+                },
+                AssociatedType {
+                    name: "Channels".to_string(),
+                    generics: None,
+                    bounds: None,
+                    type_name: Some("Box<dyn Iterator<Item = u8>>".to_string()),
+                    where_clause: None,
+                    trait_name: Some("Pixel".to_string()),
+                    docs: None,
+                },
+            ],
+            "Blanket impls such as TryFrom are left out"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_generic_associated_types() -> Result<()> {
+        let html = fs::read_to_string("test-data/trait_impls/lending-iterator-impls.html")?;
+        let (constants, types) = parse_impl_items(&Html::parse_document(&html))?;
+
+        assert!(constants.is_empty());
+        assert_eq!(
+            types,
+            vec![
+                AssociatedType {
+                    name: "Item".to_string(),
+                    generics: Some("<'a>".to_string()),
+                    bounds: None,
+                    type_name: Some("&'a mut [T]".to_string()),
+                    where_clause: Some("Self: 'a".to_string()),
+                    trait_name: Some("LendingIterator".to_string()),
+                    docs: Some("The type of the elements lent by the iterator.".to_string()),
+                },
+                AssociatedType {
+                    name: "Window".to_string(),
+                    generics: Some("<'a, const N: usize>".to_string()),
+                    bounds: None,
+                    type_name: Some("Window<'a, T, N>".to_string()),
+                    where_clause: Some("Self: 'a, T: 'a".to_string()),
+                    trait_name: Some("Windows".to_string()),
+                    docs: Some("A window of N elements.".to_string()),
+                },
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_split_where_clause() {
+        assert_eq!(
+            split_where_clause("Item<'a>: Debug where Self: 'a"),
+            ("Item<'a>: Debug", Some("Self: 'a"))
+        );
+        assert_eq!(
+            split_where_clause("Timeout<F>where F: Future,"),
+            ("Timeout<F>", Some("F: Future,"))
+        );
+        assert_eq!(
+            split_where_clause("Box<dyn Fn() -> u8 + Send> where"),
+            ("Box<dyn Fn() -> u8 + Send>", None)
+        );
+        assert_eq!(split_where_clause("Somewhere"), ("Somewhere", None));
+        assert_eq!(
+            split_where_clause("Item = Wherever"),
+            ("Item = Wherever", None)
+        );
+    }
+
+    #[test]
+    fn test_split_top_level() {
+        assert_eq!(
+            split_top_level("Item: Iterator<Item = u8> = Empty", " = "),
+            ("Item: Iterator<Item = u8>", Some("Empty"))
+        );
+        assert_eq!(
+            split_top_level("F: Fn() -> u8", ": "),
+            ("F", Some("Fn() -> u8"))
+        );
+        assert_eq!(split_top_level("Output", " = "), ("Output", None));
+    }
+}
@@ -0,0 +1,370 @@
+use anyhow::{anyhow, Result};
+use mcp_sdk::{
+    tools::Tool,
+    types::{CallToolResponse, ToolResponseContent},
+};
+use scraper::{ElementRef, Html};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tracing::debug;
+
+use super::derive_macro_docs::{attribute_name, parse_attribute_docs, text_of, AttributeDoc};
+use super::example_dependencies::{self, ExampleDependency};
+use super::get_struct_docs::{HtmlFetcher, HttpHtmlFetcher};
+use super::{markdown, registry, runtime, selectors, summary, CrateItemsTool};
+use crate::{config, error};
+
+/// Documentation of an attribute macro, from its `attr.*.html` page
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AttributeMacroDocs {
+    crate_name: String,
+    version: String,
+    name: String,
+    doc_link: String,
+    /// Arguments the docs pass to the attribute, e.g. `flavor` for
+    /// `#[tokio::main(flavor = "current_thread")]`
+    arguments: Vec<String>,
+    /// Invocations of the attribute the docs explain
+    attributes: Vec<AttributeDoc>,
+    examples: Vec<Example>,
+    /// Crates the examples use besides this one, to add to Cargo.toml before they compile
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    example_dependencies: Vec<ExampleDependency>,
+    /// The macro's documentation as Markdown
+    docs: String,
+    /// URI of the full documentation, a resource, when `docs` is only a summary
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    full_docs: Option<String>,
+}
+
+impl AttributeMacroDocs {
+    pub fn arguments(&self) -> &[String] {
+        &self.arguments
+    }
+
+    pub fn examples(&self) -> &[Example] {
+        &self.examples
+    }
+
+    pub fn example_dependencies(&self) -> &[ExampleDependency] {
+        &self.example_dependencies
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Example {
+    /// Heading the example appears under
+    section: Option<String>,
+    code: String,
+}
+
+impl Example {
+    pub fn section(&self) -> Option<&str> {
+        self.section.as_deref()
+    }
+
+    pub fn code(&self) -> &str {
+        &self.code
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct AttributeMacroDocsParams {
+    crate_name: String,
+    attribute_name: String,
+    version: Option<String>,
+    registry: Option<String>,
+}
+
+pub struct AttributeMacroDocsTool {
+    html_fetcher: Box<dyn HtmlFetcher>,
+}
+
+impl AttributeMacroDocsTool {
+    pub fn new() -> Self {
+        Self {
+            html_fetcher: Box::new(HttpHtmlFetcher::new()),
+        }
+    }
+
+    fn fetch_docs(
+        &self,
+        crate_name: &str,
+        attribute_name: &str,
+        version: Option<&str>,
+    ) -> Result<AttributeMacroDocs> {
+        let items = CrateItemsTool::new().scrape_items_blocking(crate_name, version)?;
+        let attributes = items
+            .items()
+            .get("Attributes")
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        let crate_ident = crate_name.replace('-', "_");
+        // Accept the name as written at the use site, e.g. `#[tokio::main]` or `tokio::main`
+        let name = attribute_name
+            .trim_start_matches("#[")
+            .trim_end_matches(']')
+            .trim_start_matches(&format!("{}::", crate_ident));
+        let suffix = format!("::{}", name);
+        let item = attributes
+            .iter()
+            .find(|item| item.name() == name || item.name().ends_with(&suffix))
+            .ok_or_else(|| {
+                anyhow!(
+                    "Crate {} {} has no attribute macro {}; its attribute macros are: {}",
+                    crate_name,
+                    items.version(),
+                    attribute_name,
+                    attributes
+                        .iter()
+                        .map(|item| item.name())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )
+            })?;
+        debug!("Found attribute {} at {}", item.name(), item.doc_link());
+
+        let html = self.html_fetcher.fetch_html_blocking(item.doc_link())?;
+        let (attributes, examples, docs) = parse_attribute_page(&html, name)?;
+
+        Ok(AttributeMacroDocs {
+            crate_name: items.crate_name().to_string(),
+            version: items.version().to_string(),
+            name: item.name().to_string(),
+            doc_link: item.doc_link().to_string(),
+            arguments: arguments(&attributes, &examples, name),
+            example_dependencies: example_dependencies::for_examples(
+                crate_name,
+                items.version(),
+                &crate_ident,
+                examples.iter().map(Example::code),
+            ),
+            attributes,
+            examples,
+            docs,
+            full_docs: None,
+        })
+    }
+}
+
+impl Default for AttributeMacroDocsTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn parse_attribute_page(
+    html: &str,
+    name: &str,
+) -> Result<(Vec<AttributeDoc>, Vec<Example>, String)> {
+    let document = Html::parse_document(html);
+    let docblock_selector = selectors::parse(
+        "#main-content .toggle.top-doc .docblock",
+        "attribute macro page",
+    )?;
+
+    let Some(docblock) = document.select(&docblock_selector).next() else {
+        return Ok((Vec::new(), Vec::new(), String::new()));
+    };
+    Ok((
+        parse_attribute_docs(docblock, &[name.to_string()]),
+        parse_examples(docblock),
+        markdown::element_to_markdown(docblock),
+    ))
+}
+
+/// Rust code blocks of the docblock, with the heading they appear under
+pub(crate) fn parse_examples(docblock: ElementRef) -> Vec<Example> {
+    let mut examples = Vec::new();
+    let mut section = None;
+
+    for node in docblock.descendants() {
+        let Some(element) = ElementRef::wrap(node) else {
+            continue;
+        };
+        match element.value().name() {
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => section = Some(text_of(element)),
+            "pre" if element.value().classes().any(|class| class == "rust") => {
+                examples.push(Example {
+                    section: section.clone(),
+                    code: element.text().collect::<String>().trim_end().to_string(),
+                })
+            }
+            _ => {}
+        }
+    }
+    examples
+}
+
+/// Names of the arguments the attribute is invoked with across the docs and examples, in order
+/// of first use: `#[demo::main(flavor = "current_thread", start_paused)]` passes `flavor` and
+/// `start_paused`.
+fn arguments(attributes: &[AttributeDoc], examples: &[Example], name: &str) -> Vec<String> {
+    let invocations = attributes
+        .iter()
+        .map(|doc| doc.attribute().to_string())
+        .chain(
+            examples
+                .iter()
+                .flat_map(|example| example.code.lines().map(|line| line.trim().to_string())),
+        )
+        .filter(|line| attribute_name(line).is_some_and(|invoked| invoked == name));
+
+    let mut arguments: Vec<String> = Vec::new();
+    for invocation in invocations {
+        let Some(open) = invocation.find('(') else {
+            continue;
+        };
+        let Some(close) = invocation.rfind(')') else {
+            continue;
+        };
+        for argument in split_arguments(&invocation[open + 1..close.max(open + 1)]) {
+            let argument = argument
+                .split(['=', '('])
+                .next()
+                .unwrap_or_default()
+                .trim()
+                .to_string();
+            let is_name = !argument.is_empty()
+                && argument
+                    .chars()
+                    .all(|c| c.is_alphanumeric() || c == '_' || c == ':');
+            if is_name && !arguments.contains(&argument) {
+                arguments.push(argument);
+            }
+        }
+    }
+    arguments
+}
+
+/// Splits attribute arguments at commas outside of brackets and string literals.
+fn split_arguments(arguments: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0usize;
+    let mut in_string = false;
+    let mut start = 0;
+    for (i, c) in arguments.char_indices() {
+        match c {
+            '"' => in_string = !in_string,
+            '(' | '[' | '{' if !in_string => depth += 1,
+            ')' | ']' | '}' if !in_string => depth = depth.saturating_sub(1),
+            ',' if !in_string && depth == 0 => {
+                parts.push(&arguments[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(&arguments[start..]);
+    parts
+}
+
+impl Tool for AttributeMacroDocsTool {
+    fn name(&self) -> String {
+        "attribute_macro_docs".to_string()
+    }
+
+    fn description(&self) -> String {
+        "Get the documentation of an attribute macro, e.g. #[tokio::main], #[async_trait] or \
+        #[tracing::instrument]. Returns the arguments the docs pass to the attribute, the \
+        invocations they explain, the code examples along with the other crates they use as \
+        example_dependencies, and the full docs as Markdown."
+            .to_string()
+    }
+
+    fn input_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "crate_name": {
+                    "type": "string",
+                    "description": "Name of the crate exporting the attribute macro, e.g. tokio or async-trait"
+                },
+                "attribute_name": {
+                    "type": "string",
+                    "description": "Name of the attribute macro, e.g. main, async_trait or instrument"
+                },
+                "version": {
+                    "type": "string",
+                    "description": "Optional version of the crate. Defaults to latest if not specified"
+                },
+                "registry": registry::registry_schema()
+            },
+            "required": ["crate_name", "attribute_name"]
+        })
+    }
+
+    fn call(&self, input: Option<serde_json::Value>) -> Result<CallToolResponse> {
+        let args: AttributeMacroDocsParams = error::parse_args(input)?;
+        config::current().ensure_crate_allowed(&args.crate_name)?;
+        let mut docs = runtime::blocking(|| {
+            registry::in_registry(&args.crate_name, args.registry.as_deref(), || {
+                self.fetch_docs(
+                    &args.crate_name,
+                    &args.attribute_name,
+                    args.version.as_deref(),
+                )
+            })
+        })?;
+
+        docs.full_docs = summary::shorten(
+            &format!("Docs of {}::{}", docs.crate_name, docs.name),
+            &mut docs.docs,
+        );
+
+        Ok(CallToolResponse {
+            content: vec![ToolResponseContent::Text {
+                text: serde_json::to_string_pretty(&docs)?,
+            }],
+            is_error: None,
+            meta: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::fs;
+
+    #[test]
+    fn test_parse_attribute_page() -> Result<()> {
+        let html = fs::read_to_string("test-data/attribute_macro_docs/attr-page.html")?;
+        let (attributes, examples, docs) = parse_attribute_page(&html, "main")?;
+
+        let documented: Vec<&str> = attributes.iter().map(|doc| doc.attribute()).collect();
+        assert_eq!(
+            documented,
+            vec![
+                "#[demo::main(flavor = \"current_thread\")]",
+                "#[demo::main(workers = N)]"
+            ]
+        );
+
+        let sections: Vec<Option<&str>> = examples
+            .iter()
+            .map(|example| example.section.as_deref())
+            .collect();
+        assert_eq!(sections, vec![None, Some("Multi-threaded runtime")]);
+        assert!(examples[1]
+            .code()
+            .contains("#[demo::main(workers = 4, start_paused)]"));
+
+        assert_eq!(
+            arguments(&attributes, &examples, "main"),
+            vec!["flavor", "workers", "start_paused"],
+            "Arguments are named once, in order of first use"
+        );
+        assert!(docs.starts_with("Marks an async function to be run by the demo runtime."));
+        Ok(())
+    }
+
+    #[test]
+    fn test_split_arguments() {
+        assert_eq!(
+            split_arguments(r#"flavor = "a, b", fields(x, y), skip"#),
+            vec![r#"flavor = "a, b""#, " fields(x, y)", " skip"]
+        );
+    }
+}
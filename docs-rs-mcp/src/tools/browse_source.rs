@@ -0,0 +1,269 @@
+use anyhow::{anyhow, Result};
+use mcp_sdk::{
+    tools::Tool,
+    types::{CallToolResponse, ToolResponseContent},
+};
+use scraper::{Html, Node};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tracing::debug;
+
+use super::get_struct_docs::{HtmlFetcher, HttpHtmlFetcher};
+use super::{registry, runtime, selectors};
+use crate::config;
+use crate::error::{self, DocsRsMcpError};
+
+/// A directory listing or a single file of a crate's source, as shown by the docs.rs source
+/// browser
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SourceView {
+    Directory {
+        crate_name: String,
+        version: String,
+        path: String,
+        entries: Vec<SourceEntry>,
+    },
+    File {
+        crate_name: String,
+        version: String,
+        path: String,
+        contents: String,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SourceEntry {
+    name: String,
+    is_dir: bool,
+}
+
+impl SourceEntry {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn is_dir(&self) -> bool {
+        self.is_dir
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct BrowseSourceParams {
+    crate_name: String,
+    path: Option<String>,
+    version: Option<String>,
+    registry: Option<String>,
+}
+
+pub struct BrowseSourceTool {
+    html_fetcher: Box<dyn HtmlFetcher>,
+}
+
+impl BrowseSourceTool {
+    pub fn new() -> Self {
+        Self {
+            html_fetcher: Box::new(HttpHtmlFetcher::new()),
+        }
+    }
+
+    pub(crate) fn browse(
+        &self,
+        crate_name: &str,
+        path: &str,
+        version: Option<&str>,
+    ) -> Result<SourceView> {
+        let version = version.unwrap_or("latest");
+        let path = path.trim_start_matches('/');
+        let url = format!(
+            "{}/crate/{}/{}/source/{}",
+            config::current().sources().docs_rs_url(),
+            crate_name,
+            version,
+            path
+        );
+        debug!("Fetching source view: {}", url);
+        let html = self.html_fetcher.fetch_html_blocking(&url)?;
+        let document = Html::parse_document(&html);
+
+        // Directories are requested with a trailing slash; the crate root is one too
+        if path.is_empty() || path.ends_with('/') {
+            Ok(SourceView::Directory {
+                crate_name: crate_name.to_string(),
+                version: version.to_string(),
+                path: path.to_string(),
+                entries: parse_entries(&document)?,
+            })
+        } else {
+            Ok(SourceView::File {
+                crate_name: crate_name.to_string(),
+                version: version.to_string(),
+                path: path.to_string(),
+                contents: parse_file_contents(&document)?,
+            })
+        }
+    }
+}
+
+impl Default for BrowseSourceTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Lists the files and directories in the side menu of a source view page.
+fn parse_entries(document: &Html) -> Result<Vec<SourceEntry>> {
+    let selector = selectors::parse("#side-menu a.pure-menu-link", "source view page")?;
+
+    let entries: Vec<SourceEntry> = document
+        .select(&selector)
+        .filter_map(|link| {
+            let href = link.value().attr("href")?;
+            let name = link.text().collect::<String>().trim().to_string();
+            // Skip the link to the parent directory
+            if name.is_empty() || name == ".." || href == "../" {
+                return None;
+            }
+            Some(SourceEntry {
+                name,
+                is_dir: href.ends_with('/'),
+            })
+        })
+        .collect();
+
+    if entries.is_empty() {
+        return Err(DocsRsMcpError::UnsupportedLayout(
+            "no file listing on the source page".to_string(),
+        )
+        .into());
+    }
+    Ok(entries)
+}
+
+/// Extracts the text of a rendered source file. Line numbers are rendered in a separate `pre`,
+/// so the code is the longest `pre` that isn't marked `data-nosnippet` or `#line-numbers`.
+fn parse_file_contents(document: &Html) -> Result<String> {
+    let selector = selectors::parse("pre", "source view page")?;
+
+    document
+        .select(&selector)
+        .filter(|pre| pre.value().id() != Some("line-numbers"))
+        .filter(|pre| {
+            !pre.ancestors().any(|ancestor| {
+                ancestor
+                    .value()
+                    .as_element()
+                    .is_some_and(|element| element.attr("data-nosnippet").is_some())
+            })
+        })
+        .map(|pre| {
+            pre.descendants()
+                .filter_map(|node| match node.value() {
+                    Node::Text(text) => Some(&**text),
+                    _ => None,
+                })
+                .collect::<String>()
+        })
+        .max_by_key(String::len)
+        .ok_or_else(|| {
+            anyhow!("Could not find the file contents on the source page; is the path a directory?")
+        })
+}
+
+impl Tool for BrowseSourceTool {
+    fn name(&self) -> String {
+        "browse_source".to_string()
+    }
+
+    fn description(&self) -> String {
+        "Browse a crate's source code as published on docs.rs. A path ending in / (or no \
+        path) lists the files and directories in it; any other path returns the contents of \
+        that file. Lighter than downloading the whole crate when only a few files are needed."
+            .to_string()
+    }
+
+    fn input_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "crate_name": {
+                    "type": "string",
+                    "description": "Name of the crate to browse"
+                },
+                "path": {
+                    "type": "string",
+                    "description": "Path within the crate package, e.g. src/ to list a directory or src/lib.rs to read a file. Defaults to the package root"
+                },
+                "version": {
+                    "type": "string",
+                    "description": "Optional version of the crate (defaults to latest)"
+                },
+                "registry": registry::registry_schema()
+            },
+            "required": ["crate_name"]
+        })
+    }
+
+    fn call(&self, input: Option<serde_json::Value>) -> Result<CallToolResponse> {
+        let args: BrowseSourceParams = error::parse_args(input)?;
+        config::current().ensure_crate_allowed(&args.crate_name)?;
+        let view = runtime::blocking(|| {
+            registry::in_registry(&args.crate_name, args.registry.as_deref(), || {
+                self.browse(
+                    &args.crate_name,
+                    args.path.as_deref().unwrap_or_default(),
+                    args.version.as_deref(),
+                )
+            })
+        })?;
+
+        Ok(CallToolResponse {
+            content: vec![ToolResponseContent::Text {
+                text: serde_json::to_string_pretty(&view)?,
+            }],
+            is_error: None,
+            meta: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::fs;
+
+    fn load(file: &str) -> Html {
+        let html = fs::read_to_string(format!("test-data/browse_source/{}", file))
+            .expect("Should be able to read test HTML file");
+        Html::parse_document(&html)
+    }
+
+    #[test]
+    fn test_parse_entries() -> Result<()> {
+        let entries = parse_entries(&load("source-dir.html"))?;
+        let listed: Vec<(&str, bool)> = entries.iter().map(|e| (e.name(), e.is_dir())).collect();
+
+        assert_eq!(
+            listed,
+            vec![
+                ("benches", true),
+                ("src", true),
+                ("Cargo.toml", false),
+                ("README.md", false),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_file_contents() -> Result<()> {
+        let contents = parse_file_contents(&load("source-file.html"))?;
+
+        assert_eq!(
+            contents,
+            "//! Demo crate\n\npub mod trace;\n\npub fn init() -> bool {\n    true\n}\n"
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,432 @@
+//! A catalog of the server's tools in one call: their parameters, the formats they answer in,
+//! the payload failures are reported with, and an example call of each. It is built from the
+//! definitions of the tool set, so it can't drift from what `tools/list` offers, and helps
+//! clients whose `tools/list` support is limited, or someone debugging the server by hand.
+
+use anyhow::Result;
+use mcp_sdk::{
+    tools::Tool,
+    types::{CallToolResponse, ToolDefinition, ToolResponseContent},
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use super::near_matches;
+use crate::error::{self, DocsRsMcpError};
+
+/// Name the catalog tool is offered under
+const TOOL_NAME: &str = "list_capabilities";
+
+/// Everything the server offers
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Catalog {
+    server: String,
+    version: String,
+    tools: Vec<ToolEntry>,
+    /// Schema of the JSON failures are reported with, as the text of a result with `isError`
+    error_schema: Value,
+}
+
+impl Catalog {
+    pub fn tools(&self) -> &[ToolEntry] {
+        &self.tools
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolEntry {
+    name: String,
+    description: String,
+    parameters: Vec<Parameter>,
+    output: Output,
+    /// A `tools/call` request for the tool
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    example: Option<Value>,
+}
+
+impl ToolEntry {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn parameters(&self) -> &[Parameter] {
+        &self.parameters
+    }
+
+    pub fn example(&self) -> Option<&Value> {
+        self.example.as_ref()
+    }
+}
+
+/// A property of a tool's input schema
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Parameter {
+    name: String,
+    /// JSON type, e.g. `string`, or `array of string`
+    #[serde(rename = "type")]
+    kind: String,
+    required: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    /// The values the parameter takes, when they are listed
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    values: Vec<String>,
+}
+
+impl Parameter {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn required(&self) -> bool {
+        self.required
+    }
+}
+
+/// How a tool answers: the text of its result, in one of `formats` when it takes a `format`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Output {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    formats: Vec<String>,
+    /// Whether the tool can answer in chunks of JSON lines instead
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    chunked: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct CapabilitiesParams {
+    tool: Option<String>,
+}
+
+/// The `list_capabilities` tool, describing the tools it is built with and itself
+pub struct CapabilitiesTool {
+    catalog: Catalog,
+}
+
+impl CapabilitiesTool {
+    /// Builds the catalog of the tools `definitions` describes, e.g. those of the server's
+    /// tool set.
+    pub fn new(definitions: Vec<ToolDefinition>) -> Self {
+        let mut tools: Vec<ToolEntry> = definitions
+            .iter()
+            .filter(|definition| definition.name != TOOL_NAME)
+            .map(tool_entry)
+            .collect();
+        tools.push(tool_entry(&ToolDefinition {
+            name: TOOL_NAME.to_string(),
+            description: Some(description()),
+            input_schema: input_schema(),
+        }));
+        tools.sort_by(|a, b| a.name.cmp(&b.name));
+
+        Self {
+            catalog: Catalog {
+                server: env!("CARGO_PKG_NAME").to_string(),
+                version: env!("CARGO_PKG_VERSION").to_string(),
+                tools,
+                error_schema: error_schema(),
+            },
+        }
+    }
+
+    pub fn catalog(&self) -> &Catalog {
+        &self.catalog
+    }
+
+    /// The catalog, or only the entry of `tool` when one is named
+    fn describe(&self, tool: Option<&str>) -> Result<Catalog, DocsRsMcpError> {
+        let Some(name) = tool else {
+            return Ok(self.catalog.clone());
+        };
+        let names = self.catalog.tools.iter().map(|entry| entry.name.as_str());
+        let Some(entry) = self.catalog.tools.iter().find(|entry| entry.name == name) else {
+            return Err(DocsRsMcpError::ItemNotFound {
+                what: format!("tool {}", name),
+                did_you_mean: near_matches::nearest(name, names)
+                    .into_iter()
+                    .map(str::to_string)
+                    .collect(),
+            });
+        };
+        Ok(Catalog {
+            tools: vec![entry.clone()],
+            ..self.catalog.clone()
+        })
+    }
+}
+
+fn description() -> String {
+    "List every tool of the server with its parameters, the formats it answers in and an \
+    example call, along with the schema of the error payload failed calls return. Name a tool \
+    to describe only that one."
+        .to_string()
+}
+
+fn input_schema() -> Value {
+    json!({
+        "type": "object",
+        "properties": {
+            "tool": {
+                "type": "string",
+                "description": "Optional name of the tool to describe, e.g. get_struct_docs. Defaults to every tool"
+            }
+        }
+    })
+}
+
+fn tool_entry(definition: &ToolDefinition) -> ToolEntry {
+    let schema = &definition.input_schema;
+    let required: Vec<&str> = schema["required"]
+        .as_array()
+        .map(|names| names.iter().filter_map(Value::as_str).collect())
+        .unwrap_or_default();
+    let properties = schema["properties"].as_object();
+    let mut parameters: Vec<Parameter> = properties
+        .into_iter()
+        .flatten()
+        .map(|(name, property)| Parameter {
+            name: name.clone(),
+            kind: json_type(property),
+            required: required.contains(&name.as_str()),
+            description: property["description"].as_str().map(str::to_string),
+            values: strings(&property["enum"]),
+        })
+        .collect();
+    // Required parameters first, each group in the order of the schema
+    parameters.sort_by_key(|parameter| !parameter.required);
+
+    let has = |name: &str| properties.is_some_and(|properties| properties.contains_key(name));
+    let output = Output {
+        formats: strings(&schema["properties"]["format"]["enum"]),
+        chunked: has("chunked"),
+    };
+    ToolEntry {
+        name: definition.name.clone(),
+        description: definition.description.clone().unwrap_or_default(),
+        parameters,
+        output,
+        example: example_arguments(&definition.name).map(|arguments| {
+            json!({
+                "method": "tools/call",
+                "params": { "name": definition.name, "arguments": arguments }
+            })
+        }),
+    }
+}
+
+/// `string`, or `array of string` for a list
+fn json_type(property: &Value) -> String {
+    let kind = property["type"].as_str().unwrap_or("any");
+    match property["items"]["type"].as_str() {
+        Some(item) if kind == "array" => format!("array of {}", item),
+        _ => kind.to_string(),
+    }
+}
+
+fn strings(values: &Value) -> Vec<String> {
+    values
+        .as_array()
+        .map(|values| {
+            values
+                .iter()
+                .filter_map(Value::as_str)
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Arguments of a typical call of each tool
+fn example_arguments(tool: &str) -> Option<Value> {
+    Some(match tool {
+        "admin/cache_purge" => json!({ "crate_name": "tokio" }),
+        "admin/prefetch" => json!({ "crates": ["tokio@1.43.0", "serde"] }),
+        "admin/reload_config" | "admin/stats" => json!({}),
+        "attribute_macro_docs" => json!({ "crate_name": "tokio", "attribute_name": "main" }),
+        "browse_source" => json!({ "crate_name": "bytes" }),
+        "compare_versions" => json!({
+            "crate_name": "tokio",
+            "from_version": "1.35.0",
+            "to_version": "1.43.0"
+        }),
+        "crate_categories" => json!({ "category": "asynchronous" }),
+        "crate_changelog" => json!({ "crate_name": "tokio" }),
+        "crate_features" => json!({ "crate_name": "tokio" }),
+        "crate_info" => json!({ "crate_name": "serde" }),
+        "crate_items" => json!({ "crate_name": "tokio", "kinds": ["structs"], "module": "sync" }),
+        "crate_license" => json!({ "crate_name": "ring" }),
+        "crate_owners" => json!({ "crate_name": "serde" }),
+        "crate_readme" => json!({ "crate_name": "reqwest" }),
+        "dependency_snippet" => json!({ "task": "send an HTTP GET request" }),
+        "dependency_tree" => json!({ "crate_name": "reqwest" }),
+        "deprecated_items" => json!({ "crate_name": "chrono" }),
+        "derive_macro_docs" => json!({ "crate_name": "thiserror", "derive_name": "Error" }),
+        "error_types" => json!({ "crate_name": "reqwest" }),
+        "find_usages_in_api" => json!({ "crate_name": "hyper", "type_name": "Bytes" }),
+        "get_source" => json!({ "crate_name": "tokio", "item_path": "sync::Mutex" }),
+        "get_struct_docs" => json!({
+            "crate_name": "tokio",
+            "struct_name": "sync::Mutex",
+            "format": "markdown"
+        }),
+        TOOL_NAME => json!({ "tool": "get_struct_docs" }),
+        "module_items" => json!({ "crate_name": "tokio", "path": "sync::mpsc" }),
+        "search_in_crate" => json!({ "crate_name": "tokio", "query": "spawn" }),
+        "suggest_imports" => json!({ "crate_name": "tokio", "item": "Mutex" }),
+        "supported_targets" => json!({ "crate_name": "tokio" }),
+        "trait_implementors" => json!({ "crate_name": "tokio", "trait_path": "io::AsyncRead" }),
+        "trait_impls" => json!({ "crate_name": "bytes", "type_path": "Bytes" }),
+        "trait_matrix" => json!({
+            "types": ["bytes::Bytes", "std::vec::Vec"],
+            "traits": ["Clone", "Send"]
+        }),
+        _ => return None,
+    })
+}
+
+/// Schema of [`DocsRsMcpError::to_response`]'s payload
+fn error_schema() -> Value {
+    json!({
+        "type": "object",
+        "required": ["error"],
+        "properties": {
+            "error": {
+                "type": "object",
+                "required": ["kind", "message"],
+                "properties": {
+                    "kind": {
+                        "type": "string",
+                        "enum": [
+                            "not_found",
+                            "upstream_http",
+                            "parse_failure",
+                            "unsupported_layout",
+                            "timeout",
+                            "invalid_input",
+                            "other"
+                        ]
+                    },
+                    "message": { "type": "string" },
+                    "url": { "type": "string", "description": "The URL whose request failed" },
+                    "status": { "type": "integer", "description": "The HTTP status it answered" },
+                    "suggestions": {
+                        "type": "array",
+                        "items": { "type": "string" },
+                        "description": "What to try next"
+                    },
+                    "did_you_mean": {
+                        "type": "array",
+                        "items": { "type": "string" },
+                        "description": "Names close to the one that wasn't found"
+                    }
+                }
+            }
+        }
+    })
+}
+
+impl Tool for CapabilitiesTool {
+    fn name(&self) -> String {
+        TOOL_NAME.to_string()
+    }
+
+    fn description(&self) -> String {
+        description()
+    }
+
+    fn input_schema(&self) -> Value {
+        input_schema()
+    }
+
+    fn call(&self, input: Option<Value>) -> Result<CallToolResponse> {
+        let args: CapabilitiesParams = error::parse_args(input.or(Some(json!({}))))?;
+        let catalog = self.describe(args.tool.as_deref())?;
+        Ok(CallToolResponse {
+            content: vec![ToolResponseContent::Text {
+                text: serde_json::to_string_pretty(&catalog)?,
+            }],
+            is_error: None,
+            meta: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn definition(name: &str, input_schema: Value) -> ToolDefinition {
+        ToolDefinition {
+            name: name.to_string(),
+            description: Some(format!("The {} tool", name)),
+            input_schema,
+        }
+    }
+
+    #[test]
+    fn test_tool_entry() {
+        let entry = tool_entry(&definition(
+            "crate_items",
+            json!({
+                "type": "object",
+                "required": ["crate_name"],
+                "properties": {
+                    "chunked": { "type": "boolean" },
+                    "crate_name": { "type": "string", "description": "Name of the crate" },
+                    "format": { "type": "string", "enum": ["json", "markdown"] },
+                    "kinds": { "type": "array", "items": { "type": "string" } }
+                }
+            }),
+        ));
+
+        let parameters: Vec<(&str, &str, bool)> = entry
+            .parameters()
+            .iter()
+            .map(|p| (p.name(), p.kind.as_str(), p.required()))
+            .collect();
+        assert_eq!(
+            parameters,
+            [
+                ("crate_name", "string", true),
+                ("chunked", "boolean", false),
+                ("format", "string", false),
+                ("kinds", "array of string", false),
+            ],
+            "Required parameters come first"
+        );
+        assert_eq!(entry.parameters[2].values, ["json", "markdown"]);
+        assert_eq!(
+            entry.output,
+            Output {
+                formats: vec!["json".to_string(), "markdown".to_string()],
+                chunked: true,
+            }
+        );
+        assert_eq!(
+            entry
+                .example()
+                .map(|example| &example["params"]["arguments"]["crate_name"]),
+            Some(&json!("tokio"))
+        );
+    }
+
+    #[test]
+    fn test_describe() -> Result<()> {
+        let tool = CapabilitiesTool::new(vec![
+            definition("crate_info", json!({ "type": "object" })),
+            definition("crate_items", json!({ "type": "object" })),
+        ]);
+        let names: Vec<&str> = tool.catalog().tools().iter().map(ToolEntry::name).collect();
+        assert_eq!(
+            names,
+            ["crate_info", "crate_items", "list_capabilities"],
+            "The catalog describes itself too"
+        );
+
+        assert_eq!(tool.describe(Some("crate_info"))?.tools().len(), 1);
+        let error = tool.describe(Some("crate_inf")).expect_err("unknown tool");
+        assert_eq!(error.did_you_mean(), ["crate_info"]);
+        Ok(())
+    }
+}
@@ -0,0 +1,99 @@
+//! Responses split over several text contents, for listings too large to take in as one JSON
+//! document. The first content is a JSON header describing the listing and every following
+//! one a run of items as JSON Lines, so a client can use each chunk on its own instead of
+//! parsing the whole listing first.
+
+use anyhow::Result;
+use mcp_sdk::types::{CallToolResponse, ToolResponseContent};
+use serde::Serialize;
+
+/// Size a chunk of items is kept under, unless a single item is larger
+pub(crate) const DEFAULT_CHUNK_BYTES: usize = 64 * 1024;
+
+/// Builds a chunked response item by item, serializing each as it is pushed.
+pub(crate) struct ChunkWriter {
+    header: String,
+    chunks: Vec<String>,
+    current: String,
+    max_bytes: usize,
+}
+
+impl ChunkWriter {
+    pub(crate) fn new(header: &impl Serialize, max_bytes: usize) -> Result<Self> {
+        Ok(Self {
+            header: serde_json::to_string_pretty(header)?,
+            chunks: Vec::new(),
+            current: String::new(),
+            max_bytes,
+        })
+    }
+
+    pub(crate) fn push(&mut self, item: &impl Serialize) -> Result<()> {
+        let line = serde_json::to_string(item)?;
+        if !self.current.is_empty() && self.current.len() + line.len() + 1 > self.max_bytes {
+            self.chunks.push(std::mem::take(&mut self.current));
+        }
+        self.current.push_str(&line);
+        self.current.push('\n');
+        Ok(())
+    }
+
+    pub(crate) fn into_response(mut self) -> CallToolResponse {
+        if !self.current.is_empty() {
+            self.chunks.push(self.current);
+        }
+        CallToolResponse {
+            content: std::iter::once(self.header)
+                .chain(self.chunks)
+                .map(|text| ToolResponseContent::Text { text })
+                .collect(),
+            is_error: None,
+            meta: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    fn texts(response: &CallToolResponse) -> Vec<&str> {
+        response
+            .content
+            .iter()
+            .map(|content| match content {
+                ToolResponseContent::Text { text } => text.as_str(),
+                _ => panic!("Expected text contents"),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_chunks() -> Result<()> {
+        let mut writer = ChunkWriter::new(&json!({ "total_items": 3 }), 30)?;
+        for name in ["alpha", "beta", "a_rather_long_item_name_over_the_limit"] {
+            writer.push(&json!({ "name": name }))?;
+        }
+        let response = writer.into_response();
+
+        assert_eq!(
+            texts(&response),
+            [
+                "{\n  \"total_items\": 3\n}",
+                "{\"name\":\"alpha\"}\n",
+                "{\"name\":\"beta\"}\n",
+                "{\"name\":\"a_rather_long_item_name_over_the_limit\"}\n",
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_no_items() -> Result<()> {
+        let response = ChunkWriter::new(&json!({}), DEFAULT_CHUNK_BYTES)?.into_response();
+        assert_eq!(texts(&response), ["{}"]);
+        Ok(())
+    }
+}
@@ -0,0 +1,303 @@
+use std::collections::BTreeMap;
+
+use anyhow::Result;
+use mcp_sdk::{
+    tools::Tool,
+    types::{CallToolResponse, ToolResponseContent},
+};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use super::crate_items::CrateItems;
+use super::{registry, runtime, CrateItemsTool};
+use crate::{config, error};
+
+/// Public API differences between two versions of a crate, based on their `all.html` listings
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ApiDiff {
+    crate_name: String,
+    from_version: String,
+    to_version: String,
+    added: Vec<DiffItem>,
+    removed: Vec<DiffItem>,
+    /// Items that kept their path but changed kind, e.g. a struct that became a type alias
+    changed: Vec<KindChange>,
+}
+
+impl ApiDiff {
+    pub fn crate_name(&self) -> &str {
+        &self.crate_name
+    }
+
+    pub fn from_version(&self) -> &str {
+        &self.from_version
+    }
+
+    pub fn to_version(&self) -> &str {
+        &self.to_version
+    }
+
+    pub fn added(&self) -> &[DiffItem] {
+        &self.added
+    }
+
+    pub fn removed(&self) -> &[DiffItem] {
+        &self.removed
+    }
+
+    pub fn changed(&self) -> &[KindChange] {
+        &self.changed
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DiffItem {
+    /// Module path of the item, e.g. `sync::Mutex`
+    name: String,
+    /// Item category as listed by `crate_items`, e.g. `Structs`
+    kind: String,
+    doc_link: String,
+}
+
+impl DiffItem {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn kind(&self) -> &str {
+        &self.kind
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct KindChange {
+    name: String,
+    from_kind: String,
+    to_kind: String,
+}
+
+impl KindChange {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn from_kind(&self) -> &str {
+        &self.from_kind
+    }
+
+    pub fn to_kind(&self) -> &str {
+        &self.to_kind
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CompareVersionsParams {
+    crate_name: String,
+    from_version: String,
+    to_version: String,
+    registry: Option<String>,
+}
+
+pub struct CompareVersionsTool;
+
+impl CompareVersionsTool {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub(crate) fn compare(
+        &self,
+        crate_name: &str,
+        from_version: &str,
+        to_version: &str,
+    ) -> Result<ApiDiff> {
+        let items = CrateItemsTool::new();
+        let old = items.scrape_items_blocking(crate_name, Some(from_version))?;
+        let new = items.scrape_items_blocking(crate_name, Some(to_version))?;
+        Ok(diff_items(&old, &new))
+    }
+}
+
+impl Default for CompareVersionsTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Indexes the items of a listing by name. Names are unique per kind, so macros and functions
+/// sharing a name keep separate entries.
+fn index_items(items: &CrateItems) -> BTreeMap<(String, String), DiffItem> {
+    items
+        .items()
+        .iter()
+        .flat_map(|(kind, items)| {
+            items.iter().map(move |item| {
+                (
+                    (item.name().to_string(), kind.clone()),
+                    DiffItem {
+                        name: item.name().to_string(),
+                        kind: kind.clone(),
+                        doc_link: item.doc_link().to_string(),
+                    },
+                )
+            })
+        })
+        .collect()
+}
+
+fn diff_items(old: &CrateItems, new: &CrateItems) -> ApiDiff {
+    let old_items = index_items(old);
+    let new_items = index_items(new);
+
+    let mut added: Vec<DiffItem> = new_items
+        .iter()
+        .filter(|(key, _)| !old_items.contains_key(*key))
+        .map(|(_, item)| item.clone())
+        .collect();
+    let mut removed: Vec<DiffItem> = old_items
+        .iter()
+        .filter(|(key, _)| !new_items.contains_key(*key))
+        .map(|(_, item)| item.clone())
+        .collect();
+
+    // An item that disappears under one kind and appears under another changed kind
+    let mut changed = Vec::new();
+    removed.retain(|old_item| {
+        let Some(index) = added.iter().position(|item| item.name == old_item.name) else {
+            return true;
+        };
+        let new_item = added.remove(index);
+        changed.push(KindChange {
+            name: old_item.name.clone(),
+            from_kind: old_item.kind.clone(),
+            to_kind: new_item.kind,
+        });
+        false
+    });
+
+    ApiDiff {
+        crate_name: new.crate_name().to_string(),
+        from_version: old.version().to_string(),
+        to_version: new.version().to_string(),
+        added,
+        removed,
+        changed,
+    }
+}
+
+impl Tool for CompareVersionsTool {
+    fn name(&self) -> String {
+        "compare_versions".to_string()
+    }
+
+    fn description(&self) -> String {
+        "Compare the public API of two versions of a crate. Returns the items \
+        added, removed, and changed in kind between the versions, based on the \
+        docs.rs item listings. Useful for answering what changed when upgrading."
+            .to_string()
+    }
+
+    fn input_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "crate_name": {
+                    "type": "string",
+                    "description": "Name of the crate to compare"
+                },
+                "from_version": {
+                    "type": "string",
+                    "description": "The older version, e.g. 1.35.0"
+                },
+                "to_version": {
+                    "type": "string",
+                    "description": "The newer version, e.g. 1.43.0 or latest"
+                },
+                "registry": registry::registry_schema()
+            },
+            "required": ["crate_name", "from_version", "to_version"]
+        })
+    }
+
+    fn call(&self, input: Option<serde_json::Value>) -> Result<CallToolResponse> {
+        let args: CompareVersionsParams = error::parse_args(input)?;
+        config::current().ensure_crate_allowed(&args.crate_name)?;
+        let diff = runtime::blocking(|| {
+            registry::in_registry(&args.crate_name, args.registry.as_deref(), || {
+                self.compare(&args.crate_name, &args.from_version, &args.to_version)
+            })
+        })?;
+
+        Ok(CallToolResponse {
+            content: vec![ToolResponseContent::Text {
+                text: serde_json::to_string_pretty(&diff)?,
+            }],
+            is_error: None,
+            meta: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn listing(version: &str, sections: &[(&str, &[&str])]) -> CrateItems {
+        let html: String = sections
+            .iter()
+            .map(|(section, items)| {
+                let links: String = items
+                    .iter()
+                    .map(|name| format!(r#"<li><a href="{}.html">{}</a></li>"#, name, name))
+                    .collect();
+                format!(
+                    r#"<h3 id="{}">x</h3><ul class="all-items">{}</ul>"#,
+                    section, links
+                )
+            })
+            .collect();
+        let root_url = format!("https://docs.rs/demo/{}/demo/", version);
+        CrateItemsTool::new()
+            .parse_items("demo", version, &root_url, &html)
+            .expect("The listing should parse")
+    }
+
+    #[test]
+    fn test_diff_items() {
+        let old = listing(
+            "1.0.0",
+            &[
+                ("structs", &["Client", "Request", "Legacy"]),
+                ("functions", &["get"]),
+            ],
+        );
+        let new = listing(
+            "2.0.0",
+            &[
+                ("structs", &["Client", "Response"]),
+                ("types", &["Request"]),
+                ("functions", &["get", "post"]),
+            ],
+        );
+
+        let diff = diff_items(&old, &new);
+        let names = |items: &[DiffItem]| {
+            items
+                .iter()
+                .map(|i| i.name().to_string())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(names(diff.added()), vec!["Response", "post"]);
+        assert_eq!(names(diff.removed()), vec!["Legacy"]);
+        assert_eq!(
+            diff.changed(),
+            &[KindChange {
+                name: "Request".to_string(),
+                from_kind: "Structs".to_string(),
+                to_kind: "Type Aliases".to_string(),
+            }]
+        );
+    }
+}
@@ -0,0 +1,333 @@
+use anyhow::{anyhow, Context, Result};
+use mcp_sdk::{
+    tools::Tool,
+    types::{CallToolResponse, ToolResponseContent},
+};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tracing::debug;
+
+use super::{crates_io, registry, runtime};
+use crate::{config, error};
+
+const DEFAULT_LIMIT: usize = 10;
+/// Largest page crates.io serves
+const MAX_LIMIT: usize = 100;
+
+/// The categories and keywords a crate is published under
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CrateCategories {
+    crate_name: String,
+    categories: Vec<Category>,
+    keywords: Vec<String>,
+}
+
+impl CrateCategories {
+    pub fn categories(&self) -> &[Category] {
+        &self.categories
+    }
+
+    pub fn keywords(&self) -> &[String] {
+        &self.keywords
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Category {
+    /// Identifier to list the category's crates by, e.g. `network-programming`
+    slug: String,
+    name: String,
+    description: String,
+}
+
+impl Category {
+    pub fn slug(&self) -> &str {
+        &self.slug
+    }
+}
+
+/// The most downloaded crates of a category
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CategoryCrates {
+    category: String,
+    /// Number of crates in the category
+    total: u64,
+    crates: Vec<PopularCrate>,
+}
+
+impl CategoryCrates {
+    pub fn crates(&self) -> &[PopularCrate] {
+        &self.crates
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PopularCrate {
+    name: String,
+    description: Option<String>,
+    /// Highest stable version, or the highest pre-release for crates without one
+    version: String,
+    downloads: u64,
+    /// Downloads in the last 90 days
+    recent_downloads: Option<u64>,
+    repository: Option<String>,
+}
+
+impl PopularCrate {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+/// Response body of the crates.io `/api/v1/crates?category={slug}` endpoint
+#[derive(Debug, Deserialize)]
+struct CratesResponse {
+    crates: Vec<ApiListedCrate>,
+    meta: ApiMeta,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiListedCrate {
+    name: String,
+    description: Option<String>,
+    max_version: String,
+    max_stable_version: Option<String>,
+    downloads: u64,
+    recent_downloads: Option<u64>,
+    repository: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiMeta {
+    total: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CrateCategoriesParams {
+    crate_name: Option<String>,
+    category: Option<String>,
+    sort: Option<String>,
+    limit: Option<usize>,
+    registry: Option<String>,
+}
+
+pub struct CrateCategoriesTool;
+
+impl CrateCategoriesTool {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn parse_categories(&self, crate_name: &str, body: &str) -> Result<CrateCategories> {
+        let response = crates_io::parse_crate(body)?;
+
+        Ok(CrateCategories {
+            crate_name: crate_name.to_string(),
+            categories: response
+                .categories
+                .into_iter()
+                .map(|category| Category {
+                    slug: category.slug,
+                    name: category.category,
+                    description: category.description,
+                })
+                .collect(),
+            keywords: response.krate.keywords,
+        })
+    }
+
+    fn parse_category_crates(&self, category: &str, body: &str) -> Result<CategoryCrates> {
+        let response: CratesResponse =
+            serde_json::from_str(body).context("Failed to parse crates.io crates response")?;
+
+        Ok(CategoryCrates {
+            category: category.to_string(),
+            total: response.meta.total,
+            crates: response
+                .crates
+                .into_iter()
+                .map(|listed| PopularCrate {
+                    name: listed.name,
+                    description: listed
+                        .description
+                        .map(|description| description.trim().replace('\n', " ")),
+                    version: listed.max_stable_version.unwrap_or(listed.max_version),
+                    downloads: listed.downloads,
+                    recent_downloads: listed.recent_downloads,
+                    repository: listed.repository,
+                })
+                .collect(),
+        })
+    }
+
+    fn fetch_categories(&self, crate_name: &str) -> Result<CrateCategories> {
+        let url = format!(
+            "{}/api/v1/crates/{}",
+            crates_io::crates_io_url(),
+            crate_name
+        );
+        debug!("Fetching crate categories from URL: {}", url);
+
+        let body = crates_io::fetch_blocking(&url)?;
+        self.parse_categories(crate_name, &body)
+    }
+
+    fn fetch_category_crates(
+        &self,
+        category: &str,
+        sort: &str,
+        limit: usize,
+    ) -> Result<CategoryCrates> {
+        let url = format!(
+            "{}/api/v1/crates?category={}&sort={}&per_page={}",
+            crates_io::crates_io_url(),
+            category,
+            sort,
+            limit.clamp(1, MAX_LIMIT)
+        );
+        debug!("Fetching category crates from URL: {}", url);
+
+        let body = crates_io::fetch_blocking(&url)?;
+        self.parse_category_crates(category, &body)
+    }
+}
+
+impl Default for CrateCategoriesTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Tool for CrateCategoriesTool {
+    fn name(&self) -> String {
+        "crate_categories".to_string()
+    }
+
+    fn description(&self) -> String {
+        "Get the crates.io categories and keywords of a crate, or, given a category slug \
+        (e.g. asynchronous, parser-implementations), list the most downloaded crates in that \
+        category. Useful for finding alternatives to a crate or the established crates for \
+        a task."
+            .to_string()
+    }
+
+    fn input_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "crate_name": {
+                    "type": "string",
+                    "description": "Name of the crate to get categories and keywords for"
+                },
+                "category": {
+                    "type": "string",
+                    "description": "Category slug to list popular crates of, e.g. asynchronous. Used instead of crate_name"
+                },
+                "sort": {
+                    "type": "string",
+                    "enum": ["downloads", "recent-downloads"],
+                    "description": "Order of the category's crates: all-time or recent downloads (defaults to downloads)"
+                },
+                "limit": {
+                    "type": "integer",
+                    "description": "Maximum number of crates to list for a category (defaults to 10, at most 100)"
+                },
+                "registry": registry::registry_schema()
+            }
+        })
+    }
+
+    fn call(&self, input: Option<serde_json::Value>) -> Result<CallToolResponse> {
+        let args: CrateCategoriesParams = error::parse_args(input)?;
+        let text = match (&args.crate_name, &args.category) {
+            (Some(crate_name), None) => {
+                config::current().ensure_crate_allowed(crate_name)?;
+                let categories = runtime::blocking(|| {
+                    registry::in_registry(crate_name, args.registry.as_deref(), || {
+                        self.fetch_categories(crate_name)
+                    })
+                })?;
+                serde_json::to_string_pretty(&categories)?
+            }
+            (None, Some(category)) => {
+                let sort = args.sort.as_deref().unwrap_or("downloads");
+                if !matches!(sort, "downloads" | "recent-downloads") {
+                    return Err(anyhow!(
+                        "Unknown sort order {}; expected downloads or recent-downloads",
+                        sort
+                    ));
+                }
+                let crates = runtime::blocking(|| {
+                    registry::in_named_registry(args.registry.as_deref(), || {
+                        self.fetch_category_crates(
+                            category,
+                            sort,
+                            args.limit.unwrap_or(DEFAULT_LIMIT),
+                        )
+                    })
+                })?;
+                serde_json::to_string_pretty(&crates)?
+            }
+            _ => return Err(anyhow!("Pass either crate_name or category")),
+        };
+
+        Ok(CallToolResponse {
+            content: vec![ToolResponseContent::Text { text }],
+            is_error: None,
+            meta: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::fs;
+
+    #[test]
+    fn test_parse_categories() -> Result<()> {
+        let body = fs::read_to_string("test-data/crate_categories/tokio-crate.json")?;
+        let categories = CrateCategoriesTool::new().parse_categories("tokio", &body)?;
+
+        let slugs: Vec<&str> = categories.categories().iter().map(Category::slug).collect();
+        assert_eq!(slugs, vec!["asynchronous", "network-programming"]);
+        assert_eq!(categories.categories()[0].name, "Asynchronous");
+        assert_eq!(
+            categories.keywords(),
+            &["io", "async", "non-blocking", "futures"]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_category_crates() -> Result<()> {
+        let body = fs::read_to_string("test-data/crate_categories/asynchronous-crates.json")?;
+        let crates = CrateCategoriesTool::new().parse_category_crates("asynchronous", &body)?;
+
+        assert_eq!(crates.total, 4866);
+        let names: Vec<&str> = crates.crates().iter().map(PopularCrate::name).collect();
+        assert_eq!(names, vec!["tokio", "futures", "demo-async"]);
+        assert_eq!(
+            crates.crates()[0].description.as_deref(),
+            Some("An event-driven, non-blocking I/O platform for writing asynchronous I/O backed applications.")
+        );
+        assert_eq!(
+            crates.crates()[2].version,
+            "0.1.0-alpha.1",
+            "Crates without stable releases show their newest pre-release"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_invalid_body() {
+        let tool = CrateCategoriesTool::new();
+        assert!(tool
+            .parse_categories("tokio", "<html>not json</html>")
+            .is_err());
+        assert!(tool
+            .parse_category_crates("asynchronous", "<html>not json</html>")
+            .is_err());
+    }
+}
@@ -0,0 +1,486 @@
+use anyhow::{anyhow, Context, Result};
+use mcp_sdk::{
+    tools::Tool,
+    types::{CallToolResponse, ToolResponseContent},
+};
+use semver::Version;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tracing::{debug, warn};
+use url::Url;
+
+use super::{crates_io, dates, http, registry, runtime};
+use crate::{config, error};
+
+/// File names commonly used for changelogs, in the order they are tried
+const CHANGELOG_FILES: &[&str] = &["CHANGELOG.md", "RELEASES.md", "CHANGES.md"];
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CrateChangelog {
+    crate_name: String,
+    repository: String,
+    /// URL of the changelog file that was found
+    url: String,
+    entries: Vec<ChangelogEntry>,
+}
+
+impl CrateChangelog {
+    pub fn crate_name(&self) -> &str {
+        &self.crate_name
+    }
+
+    pub fn repository(&self) -> &str {
+        &self.repository
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    pub fn entries(&self) -> &[ChangelogEntry] {
+        &self.entries
+    }
+}
+
+/// The notes of a single release
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChangelogEntry {
+    version: String,
+    /// The full heading text, which usually includes the release date
+    heading: String,
+    /// The release date the heading gives, as an RFC 3339 date, e.g. `2025-01-08`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    date: Option<String>,
+    /// The Markdown body of the entry, without the heading
+    body: String,
+}
+
+impl ChangelogEntry {
+    pub fn version(&self) -> &str {
+        &self.version
+    }
+
+    pub fn heading(&self) -> &str {
+        &self.heading
+    }
+
+    pub fn date(&self) -> Option<&str> {
+        self.date.as_deref()
+    }
+
+    pub fn body(&self) -> &str {
+        &self.body
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CrateChangelogParams {
+    crate_name: String,
+    from_version: Option<String>,
+    to_version: Option<String>,
+    registry: Option<String>,
+}
+
+pub struct CrateChangelogTool;
+
+impl CrateChangelogTool {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn fetch_changelog(
+        &self,
+        crate_name: &str,
+        from_version: Option<&str>,
+        to_version: Option<&str>,
+    ) -> Result<CrateChangelog> {
+        let from = from_version.map(parse_version).transpose()?;
+        let to = to_version.map(parse_version).transpose()?;
+
+        let repository = crates_io::repository(crate_name)?
+            .ok_or_else(|| anyhow!("{} does not declare a repository", crate_name))?;
+
+        let mut last_error = None;
+        for url in raw_file_urls(&repository, crate_name, CHANGELOG_FILES)? {
+            match self.fetch_file(&url) {
+                Ok(markdown) => {
+                    let entries = parse_changelog(&markdown)
+                        .into_iter()
+                        .filter(|entry| entry_in_range(entry, from.as_ref(), to.as_ref()))
+                        .collect();
+                    return Ok(CrateChangelog {
+                        crate_name: crate_name.to_string(),
+                        repository,
+                        url,
+                        entries,
+                    });
+                }
+                Err(e) => {
+                    debug!("No changelog at {}: {}", url, e);
+                    last_error = Some(e);
+                }
+            }
+        }
+
+        warn!(
+            "Could not find a changelog for {} in {}",
+            crate_name, repository
+        );
+        Err(anyhow!(
+            "Could not find a changelog ({}) in {}: {}",
+            CHANGELOG_FILES.join(", "),
+            repository,
+            last_error.map(|e| e.to_string()).unwrap_or_default()
+        ))
+    }
+
+    fn fetch_file(&self, url: &str) -> Result<String> {
+        runtime::block_on(async {
+            let response = http::shared()?
+                .get(url)
+                .await
+                .context(format!("Failed to fetch URL: {}", url))?;
+            if !response.status().is_success() {
+                return Err(anyhow!(
+                    "Failed to fetch changelog: {} - {}",
+                    response.status(),
+                    url
+                ));
+            }
+
+            response
+                .text()
+                .await
+                .context(format!("Failed to get text from response for URL: {}", url))
+        })
+    }
+}
+
+impl Default for CrateChangelogTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Builds the raw URLs `files` may live at in a repository, for the hosts we know how to read
+/// from. Workspaces often keep one changelog or license per crate, so the crate's own directory
+/// is tried before the repository root.
+pub(crate) fn raw_file_urls(
+    repository: &str,
+    crate_name: &str,
+    files: &[&str],
+) -> Result<Vec<String>> {
+    let url = Url::parse(repository.trim_end_matches('/'))
+        .context(format!("Invalid repository URL: {}", repository))?;
+    let segments: Vec<&str> = url
+        .path_segments()
+        .map(|segments| segments.filter(|s| !s.is_empty()).collect())
+        .unwrap_or_default();
+    let (owner, repo) = match segments.as_slice() {
+        [owner, repo, ..] => (*owner, repo.trim_end_matches(".git")),
+        _ => return Err(anyhow!("Unsupported repository URL: {}", repository)),
+    };
+
+    // Links such as `https://github.com/owner/repo/tree/master/crates/foo` point at the crate
+    let subdirectory = match segments.as_slice() {
+        [_, _, "tree", _, rest @ ..] | [_, _, "-", "tree", _, rest @ ..] if !rest.is_empty() => {
+            Some(rest.join("/"))
+        }
+        _ => None,
+    };
+
+    let raw_base = match url.host_str() {
+        Some("github.com") => format!("https://raw.githubusercontent.com/{}/{}/HEAD", owner, repo),
+        Some("gitlab.com") => format!("https://gitlab.com/{}/{}/-/raw/HEAD", owner, repo),
+        _ => return Err(anyhow!("Unsupported repository host: {}", repository)),
+    };
+
+    let mut directories = Vec::new();
+    directories.extend(subdirectory);
+    directories.push(crate_name.to_string());
+    directories.push(String::new());
+    directories.dedup();
+
+    Ok(directories
+        .iter()
+        .flat_map(|directory| {
+            let raw_base = &raw_base;
+            files.iter().map(move |file| {
+                if directory.is_empty() {
+                    format!("{}/{}", raw_base, file)
+                } else {
+                    format!("{}/{}/{}", raw_base, directory, file)
+                }
+            })
+        })
+        .collect())
+}
+
+/// Splits a Markdown changelog into one entry per heading that names a version.
+fn parse_changelog(markdown: &str) -> Vec<ChangelogEntry> {
+    let mut entries = Vec::new();
+    // The open entry, its heading level and its body lines
+    let mut current: Option<(ChangelogEntry, usize, Vec<&str>)> = None;
+    let mut in_code_block = false;
+
+    let mut close = |current: &mut Option<(ChangelogEntry, usize, Vec<&str>)>| {
+        if let Some((mut entry, _, lines)) = current.take() {
+            entry.body = lines.join("\n").trim().to_string();
+            entries.push(entry);
+        }
+    };
+
+    for line in markdown.lines() {
+        if line.trim_start().starts_with("```") {
+            in_code_block = !in_code_block;
+        }
+        let heading = (!in_code_block).then(|| parse_heading(line)).flatten();
+
+        match heading {
+            Some((level, text)) => {
+                if let Some(version) = heading_version(text) {
+                    close(&mut current);
+                    current = Some((
+                        ChangelogEntry {
+                            version: version.to_string(),
+                            heading: text.to_string(),
+                            date: dates::find_date(text),
+                            body: String::new(),
+                        },
+                        level,
+                        Vec::new(),
+                    ));
+                    continue;
+                }
+                // A shallower heading that isn't a release ends the release section
+                if matches!(&current, Some((_, entry_level, _)) if level < *entry_level) {
+                    close(&mut current);
+                    continue;
+                }
+                if let Some((_, _, lines)) = current.as_mut() {
+                    lines.push(line);
+                }
+            }
+            None => {
+                if let Some((_, _, lines)) = current.as_mut() {
+                    lines.push(line);
+                }
+            }
+        }
+    }
+    close(&mut current);
+
+    entries
+}
+
+/// Returns the level and text of an ATX heading (`## 1.0.0`).
+fn parse_heading(line: &str) -> Option<(usize, &str)> {
+    let level = line.chars().take_while(|c| *c == '#').count();
+    if level == 0 || level > 6 {
+        return None;
+    }
+    let text = &line[level..];
+    text.starts_with(' ')
+        .then(|| (level, text.trim().trim_end_matches('#').trim()))
+}
+
+/// Finds the version a changelog heading refers to, e.g. `1.43.0` in `1.43.0 (Jan 8th, 2025)`,
+/// `[0.3.1] - 2024-01-01`, `v0.2.0` or `tokio-util-0.7.13`.
+fn heading_version(heading: &str) -> Option<Version> {
+    heading
+        .split(|c: char| c.is_whitespace() || matches!(c, '[' | ']' | '(' | ')' | ',' | ':'))
+        .filter(|word| !word.is_empty())
+        .find_map(|word| {
+            let word = match word.rfind(['-', '@']) {
+                // `name-1.2.3`, but not the pre-release in `1.2.3-alpha.1`
+                Some(index) if !word[..index].contains('.') => &word[index + 1..],
+                _ => word,
+            };
+            Version::parse(word.trim_start_matches('v')).ok()
+        })
+}
+
+/// Parses a user supplied version, accepting `v` prefixes and partial versions like `1.2`.
+fn parse_version(version: &str) -> Result<Version> {
+    let version = version.trim().trim_start_matches('v');
+    let padded = match version.split('.').count() {
+        1 => format!("{}.0.0", version),
+        2 => format!("{}.0", version),
+        _ => version.to_string(),
+    };
+    Version::parse(&padded).map_err(|e| anyhow!("Invalid version '{}': {}", version, e))
+}
+
+/// Whether an entry is newer than `from` (exclusive) and not newer than `to` (inclusive), which
+/// selects exactly the changes picked up when upgrading from `from` to `to`.
+fn entry_in_range(entry: &ChangelogEntry, from: Option<&Version>, to: Option<&Version>) -> bool {
+    let Ok(version) = Version::parse(&entry.version) else {
+        return false;
+    };
+    from.is_none_or(|from| version > *from) && to.is_none_or(|to| version <= *to)
+}
+
+impl Tool for CrateChangelogTool {
+    fn name(&self) -> String {
+        "crate_changelog".to_string()
+    }
+
+    fn description(&self) -> String {
+        "Get the changelog of a crate, located through its repository link \
+        (CHANGELOG.md, RELEASES.md or CHANGES.md). Optionally filter to the \
+        releases between two versions, which is useful when upgrading a dependency. Entries \
+        give the release date of their heading as an RFC 3339 date when it has one."
+            .to_string()
+    }
+
+    fn input_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "crate_name": {
+                    "type": "string",
+                    "description": "Name of the crate to get the changelog for"
+                },
+                "from_version": {
+                    "type": "string",
+                    "description": "Optional version being upgraded from; only newer releases are returned"
+                },
+                "to_version": {
+                    "type": "string",
+                    "description": "Optional version being upgraded to; newer releases are omitted"
+                },
+                "registry": registry::registry_schema()
+            },
+            "required": ["crate_name"]
+        })
+    }
+
+    fn call(&self, input: Option<serde_json::Value>) -> Result<CallToolResponse> {
+        let args: CrateChangelogParams = error::parse_args(input)?;
+        config::current().ensure_crate_allowed(&args.crate_name)?;
+        let changelog = runtime::blocking(|| {
+            registry::in_registry(&args.crate_name, args.registry.as_deref(), || {
+                self.fetch_changelog(
+                    &args.crate_name,
+                    args.from_version.as_deref(),
+                    args.to_version.as_deref(),
+                )
+            })
+        })?;
+
+        Ok(CallToolResponse {
+            content: vec![ToolResponseContent::Text {
+                text: serde_json::to_string_pretty(&changelog)?,
+            }],
+            is_error: None,
+            meta: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn tokio_changelog() -> Result<String> {
+        std::fs::read_to_string("test-data/crate_changelog/tokio-CHANGELOG.md")
+            .map_err(|e| anyhow!("Failed to read test file: {}", e))
+    }
+
+    #[test]
+    fn test_parse_changelog() -> Result<()> {
+        let entries = parse_changelog(&tokio_changelog()?);
+
+        let versions: Vec<&str> = entries.iter().map(|e| e.version()).collect();
+        assert_eq!(
+            versions,
+            vec!["1.43.0", "1.42.0", "1.41.1", "1.41.0"],
+            "Headings inside code blocks should not start an entry"
+        );
+        assert_eq!(entries[0].heading(), "1.43.0 (Jan 8th, 2025)");
+        assert_eq!(entries[0].date(), Some("2025-01-08"));
+        assert_eq!(entries[3].date(), Some("2024-10-22"));
+        assert!(
+            entries[0].body().starts_with("### Added"),
+            "Sub-headings should stay part of the entry body"
+        );
+        assert!(
+            entries[1].body().contains("# 0.9.9 inside a code block"),
+            "Code blocks should stay part of the entry body"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_filter_between_versions() -> Result<()> {
+        let from = parse_version("1.41.0")?;
+        let to = parse_version("v1.42")?;
+        let versions: Vec<String> = parse_changelog(&tokio_changelog()?)
+            .into_iter()
+            .filter(|entry| entry_in_range(entry, Some(&from), Some(&to)))
+            .map(|entry| entry.version)
+            .collect();
+
+        assert_eq!(
+            versions,
+            vec!["1.42.0", "1.41.1"],
+            "The lower bound should be exclusive and the upper bound inclusive"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_heading_version() {
+        let cases = [
+            ("1.43.0 (Jan 8th, 2025)", Some("1.43.0")),
+            ("[0.3.1] - 2024-01-01", Some("0.3.1")),
+            ("v0.2.0", Some("0.2.0")),
+            ("tokio-util-0.7.13", Some("0.7.13")),
+            ("2.0.0-rc.1", Some("2.0.0-rc.1")),
+            ("Unreleased", None),
+        ];
+        for (heading, expected) in cases {
+            assert_eq!(
+                heading_version(heading).map(|v| v.to_string()).as_deref(),
+                expected,
+                "Unexpected version for heading {:?}",
+                heading
+            );
+        }
+    }
+
+    #[test]
+    fn test_raw_file_urls() -> Result<()> {
+        let urls = raw_file_urls(
+            "https://github.com/tokio-rs/tokio",
+            "tokio",
+            CHANGELOG_FILES,
+        )?;
+        assert_eq!(
+            urls[0], "https://raw.githubusercontent.com/tokio-rs/tokio/HEAD/tokio/CHANGELOG.md",
+            "The crate directory should be tried first"
+        );
+        assert_eq!(
+            urls.last().map(String::as_str),
+            Some("https://raw.githubusercontent.com/tokio-rs/tokio/HEAD/CHANGES.md")
+        );
+
+        let urls = raw_file_urls(
+            "https://github.com/rust-lang/cargo/tree/master/crates/cargo-util",
+            "cargo-util",
+            CHANGELOG_FILES,
+        )?;
+        assert_eq!(
+            urls[0],
+            "https://raw.githubusercontent.com/rust-lang/cargo/HEAD/crates/cargo-util/CHANGELOG.md",
+            "Repository links into a subdirectory should be honoured"
+        );
+
+        assert!(raw_file_urls("https://example.com/foo/bar", "bar", CHANGELOG_FILES).is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,510 @@
+use std::collections::{BTreeSet, HashMap};
+
+use anyhow::{anyhow, Context, Result};
+use mcp_sdk::{
+    tools::Tool,
+    types::{CallToolResponse, ToolResponseContent},
+};
+use scraper::{ElementRef, Html};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tracing::debug;
+
+use super::{http, registry, runtime, selectors};
+use crate::{config, error};
+
+/// Text docs.rs shows for features that don't enable anything
+const NO_ADDITIONAL_FEATURES: &str = "This feature flag does not enable additional features.";
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CrateFeatures {
+    crate_name: String,
+    version: String,
+    /// Features enabled when depending on the crate without `default-features = false`
+    default_features: Vec<String>,
+    features: Vec<FeatureNode>,
+}
+
+impl CrateFeatures {
+    pub fn crate_name(&self) -> &str {
+        &self.crate_name
+    }
+
+    pub fn version(&self) -> &str {
+        &self.version
+    }
+
+    pub fn default_features(&self) -> &[String] {
+        &self.default_features
+    }
+
+    pub fn features(&self) -> &[FeatureNode] {
+        &self.features
+    }
+
+    pub fn feature(&self, name: &str) -> Option<&FeatureNode> {
+        self.features.iter().find(|feature| feature.name == name)
+    }
+}
+
+/// A feature flag and its edges in the feature graph
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FeatureNode {
+    name: String,
+    /// Entries of the feature as declared: other features, `dep:` optional dependencies and
+    /// `crate/feature` dependency features
+    enables: Vec<String>,
+    /// Every entry enabled transitively when this feature is turned on
+    closure: Vec<String>,
+    /// Items whose availability depends on this feature
+    affects: Vec<String>,
+    /// Whether the feature is an implicit feature of an optional dependency
+    optional_dependency: bool,
+    enabled_by_default: bool,
+    /// Explanation from the crate root docs, for crates documenting their features with
+    /// `document-features`
+    description: Option<String>,
+}
+
+impl FeatureNode {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn enables(&self) -> &[String] {
+        &self.enables
+    }
+
+    pub fn closure(&self) -> &[String] {
+        &self.closure
+    }
+
+    pub fn affects(&self) -> &[String] {
+        &self.affects
+    }
+
+    pub fn optional_dependency(&self) -> bool {
+        self.optional_dependency
+    }
+
+    pub fn enabled_by_default(&self) -> bool {
+        self.enabled_by_default
+    }
+
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CrateFeaturesParams {
+    crate_name: String,
+    version: Option<String>,
+    registry: Option<String>,
+}
+
+pub struct CrateFeaturesTool;
+
+impl CrateFeaturesTool {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn get_docs_rs_url() -> String {
+        config::current().sources().docs_rs_url().to_string()
+    }
+
+    pub(crate) fn fetch_features(&self, crate_name: &str, version: &str) -> Result<CrateFeatures> {
+        let url = format!(
+            "{}/crate/{}/{}/features",
+            Self::get_docs_rs_url(),
+            crate_name,
+            version
+        );
+        debug!("Fetching features page: {}", url);
+        let html = runtime::block_on(async {
+            let response = http::shared()?
+                .get(&url)
+                .await
+                .context(format!("Failed to fetch URL: {}", url))?;
+            if !response.status().is_success() {
+                return Err(anyhow!(
+                    "Failed to fetch features page: {} - {}",
+                    response.status(),
+                    url
+                ));
+            }
+            Ok(response.text().await?)
+        })?;
+
+        let mut features = self.parse_features_page(crate_name, version, &html)?;
+        match self.fetch_feature_docs(crate_name, version) {
+            Ok(descriptions) => {
+                for feature in &mut features.features {
+                    feature.description = descriptions.get(&feature.name).cloned();
+                }
+            }
+            Err(e) => debug!("No feature documentation for {}: {:#}", crate_name, e),
+        }
+        Ok(features)
+    }
+
+    /// Reads the feature descriptions from the crate root docs.
+    fn fetch_feature_docs(
+        &self,
+        crate_name: &str,
+        version: &str,
+    ) -> Result<HashMap<String, String>> {
+        let url = format!(
+            "{}/{}/{}/{}/index.html",
+            Self::get_docs_rs_url(),
+            crate_name,
+            version,
+            crate_name.replace('-', "_")
+        );
+        debug!("Fetching crate root docs: {}", url);
+        let html = runtime::block_on(async {
+            let response = http::shared()?
+                .get(&url)
+                .await
+                .context(format!("Failed to fetch URL: {}", url))?;
+            if !response.status().is_success() {
+                return Err(anyhow!(
+                    "Failed to fetch crate docs: {} - {}",
+                    response.status(),
+                    url
+                ));
+            }
+            Ok(response.text().await?)
+        })?;
+
+        parse_feature_docs(&html)
+    }
+
+    fn parse_features_page(
+        &self,
+        crate_name: &str,
+        version: &str,
+        html: &str,
+    ) -> Result<CrateFeatures> {
+        let document = Html::parse_document(html);
+        let heading_selector = selectors::parse("#main h3[id]", "features page")?;
+        let entry_selector = selectors::parse("li", "features page")?;
+        let affects_selector = selectors::parse("code", "features page")?;
+
+        let mut declared = Vec::new();
+        for heading in document.select(&heading_selector) {
+            let name = heading.text().collect::<String>().trim().to_string();
+            let mut enables = Vec::new();
+            let mut affects = Vec::new();
+            let mut optional_dependency = false;
+
+            let body = heading
+                .next_siblings()
+                .filter_map(ElementRef::wrap)
+                .take_while(|el| el.value().name() != "h3");
+            for element in body {
+                match element.value().name() {
+                    "ul" => enables.extend(
+                        element
+                            .select(&entry_selector)
+                            .map(|li| li.text().collect::<String>().trim().to_string())
+                            .filter(|entry| !entry.is_empty()),
+                    ),
+                    "p" => {
+                        let text = element.text().collect::<String>();
+                        let text = text.trim();
+                        if text.starts_with("Affects") {
+                            affects.extend(
+                                element
+                                    .select(&affects_selector)
+                                    .map(|code| code.text().collect::<String>().trim().to_string()),
+                            );
+                        } else if text.starts_with("Optional dependency") {
+                            optional_dependency = true;
+                        } else if text != NO_ADDITIONAL_FEATURES {
+                            debug!("Ignoring unknown paragraph for feature {}: {}", name, text);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+
+            declared.push((name, enables, affects, optional_dependency));
+        }
+
+        if declared.is_empty() {
+            return Err(anyhow!(
+                "No feature flags found on the docs.rs features page of {} {}",
+                crate_name,
+                version
+            ));
+        }
+
+        let graph: HashMap<&str, &[String]> = declared
+            .iter()
+            .map(|(name, enables, _, _)| (name.as_str(), enables.as_slice()))
+            .collect();
+        let default_features = feature_closure(&graph, "default")
+            .into_iter()
+            .filter(|entry| graph.contains_key(entry.as_str()))
+            .collect::<Vec<_>>();
+
+        let features = declared
+            .iter()
+            .map(
+                |(name, enables, affects, optional_dependency)| FeatureNode {
+                    name: name.clone(),
+                    enables: enables.clone(),
+                    closure: feature_closure(&graph, name).into_iter().collect(),
+                    affects: affects.clone(),
+                    optional_dependency: *optional_dependency,
+                    enabled_by_default: name == "default" || default_features.contains(name),
+                    description: None,
+                },
+            )
+            .collect();
+
+        Ok(CrateFeatures {
+            crate_name: crate_name.to_string(),
+            version: version.to_string(),
+            default_features,
+            features,
+        })
+    }
+}
+
+impl Default for CrateFeaturesTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Extracts the feature list `document-features` generates in the crate root docs. Each
+/// feature is a list item of the form `<strong><code>name</code></strong> <em>(enabled by
+/// default)</em> — description`.
+fn parse_feature_docs(html: &str) -> Result<HashMap<String, String>> {
+    let document = Html::parse_document(html);
+    let item_selector = selectors::parse(".toggle.top-doc .docblock li", "features page")?;
+
+    let mut descriptions = HashMap::new();
+    for item in document.select(&item_selector) {
+        let Some(label) = item
+            .children()
+            .find(|child| !child.value().as_text().is_some_and(|t| t.trim().is_empty()))
+            .and_then(ElementRef::wrap)
+            .filter(|el| el.value().name() == "strong")
+            .and_then(|strong| strong.first_child().and_then(ElementRef::wrap))
+            .filter(|el| el.value().name() == "code")
+        else {
+            continue;
+        };
+        let name = label.text().collect::<String>().trim().to_string();
+
+        // Nested lists belong to their own items
+        let text: String = item
+            .children()
+            .filter(|child| {
+                ElementRef::wrap(*child).is_none_or(|el| !matches!(el.value().name(), "ul" | "ol"))
+            })
+            .flat_map(|child| match ElementRef::wrap(child) {
+                Some(el) => el.text().collect::<Vec<_>>(),
+                None => child
+                    .value()
+                    .as_text()
+                    .map(|t| vec![&**t])
+                    .unwrap_or_default(),
+            })
+            .collect();
+        let description = text
+            .trim_start()
+            .strip_prefix(name.as_str())
+            .unwrap_or(&text)
+            .trim_start()
+            .trim_start_matches("(enabled by default)")
+            .trim_start()
+            .trim_start_matches(['—', '-'])
+            .trim()
+            .to_string();
+
+        if !name.is_empty() && !description.is_empty() {
+            descriptions.insert(name, description);
+        }
+    }
+
+    if descriptions.is_empty() {
+        return Err(anyhow!("The crate docs have no document-features list"));
+    }
+    Ok(descriptions)
+}
+
+/// Every entry transitively enabled by `feature`, following edges to other features of the same
+/// crate. Entries that point outside the crate (`dep:x`, `x/feature`, `x?/feature`) are included
+/// but not followed.
+fn feature_closure(graph: &HashMap<&str, &[String]>, feature: &str) -> BTreeSet<String> {
+    let mut closure = BTreeSet::new();
+    let mut pending: Vec<&str> = graph.get(feature).map_or(Vec::new(), |enables| {
+        enables.iter().map(String::as_str).collect()
+    });
+
+    while let Some(entry) = pending.pop() {
+        if entry == feature || !closure.insert(entry.to_string()) {
+            continue;
+        }
+        if let Some(enables) = graph.get(entry) {
+            pending.extend(enables.iter().map(String::as_str));
+        }
+    }
+
+    closure
+}
+
+impl Tool for CrateFeaturesTool {
+    fn name(&self) -> String {
+        "crate_features".to_string()
+    }
+
+    fn description(&self) -> String {
+        "Get the feature flags of a crate from its docs.rs features page as a \
+        feature graph: what each feature enables directly and transitively, which \
+        features are on by default, which are optional dependencies and which items \
+        each feature affects. Includes per-feature descriptions when the crate documents \
+        its features with document-features."
+            .to_string()
+    }
+
+    fn input_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "crate_name": {
+                    "type": "string",
+                    "description": "Name of the crate to get the feature flags of"
+                },
+                "version": {
+                    "type": "string",
+                    "description": "Optional version of the crate (defaults to latest)"
+                },
+                "registry": registry::registry_schema()
+            },
+            "required": ["crate_name"]
+        })
+    }
+
+    fn call(&self, input: Option<serde_json::Value>) -> Result<CallToolResponse> {
+        let args: CrateFeaturesParams = error::parse_args(input)?;
+        config::current().ensure_crate_allowed(&args.crate_name)?;
+        let version = args.version.as_deref().unwrap_or("latest");
+        let features = runtime::blocking(|| {
+            registry::in_registry(&args.crate_name, args.registry.as_deref(), || {
+                self.fetch_features(&args.crate_name, version)
+            })
+        })?;
+
+        Ok(CallToolResponse {
+            content: vec![ToolResponseContent::Text {
+                text: serde_json::to_string_pretty(&features)?,
+            }],
+            is_error: None,
+            meta: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn tokio_features() -> Result<CrateFeatures> {
+        let html = std::fs::read_to_string("test-data/crate_features/tokio-1.43.0-features.html")
+            .map_err(|e| anyhow!("Failed to read test file: {}", e))?;
+        CrateFeaturesTool::new().parse_features_page("tokio", "1.43.0", &html)
+    }
+
+    #[test]
+    fn test_parse_features_page() -> Result<()> {
+        let features = tokio_features()?;
+
+        let names: Vec<&str> = features.features().iter().map(|f| f.name()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "default",
+                "full",
+                "net",
+                "io-util",
+                "rt-multi-thread",
+                "rt",
+                "bytes",
+                "tracing"
+            ]
+        );
+        assert!(
+            features.default_features().is_empty(),
+            "tokio enables no features by default"
+        );
+
+        let net = features.feature("net").expect("net feature should exist");
+        assert_eq!(net.enables(), &["libc", "mio/os-poll", "mio/net"]);
+        assert_eq!(net.affects(), &["tokio::net", "net::TcpStream"]);
+
+        let tracing = features
+            .feature("tracing")
+            .expect("tracing feature should exist");
+        assert!(
+            tracing.optional_dependency(),
+            "Implicit features of optional dependencies should be marked"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_feature_docs() -> Result<()> {
+        let html = r#"<details class="toggle top-doc" open><div class="docblock">
+            <h2 id="feature-flags">Feature flags</h2>
+            <ul>
+            <li><strong><code>std</code></strong> <em>(enabled by default)</em> — Use the standard library.</li>
+            <li><strong><code>serde</code></strong> — Implements <code>Serialize</code> for all types.
+                <ul><li>Nested notes aren't part of the description.</li></ul></li>
+            <li>A regular list item about <code>std</code>.</li>
+            </ul></div></details>"#;
+        let descriptions = parse_feature_docs(html)?;
+
+        assert_eq!(descriptions.len(), 2);
+        assert_eq!(descriptions["std"], "Use the standard library.");
+        assert_eq!(descriptions["serde"], "Implements Serialize for all types.");
+
+        assert!(
+            parse_feature_docs("<div class=\"docblock\"><ul><li>Plain</li></ul></div>").is_err(),
+            "Crates without document-features have no descriptions"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_feature_closure() -> Result<()> {
+        let features = tokio_features()?;
+        let full = features.feature("full").expect("full feature should exist");
+
+        assert_eq!(
+            full.closure(),
+            &[
+                "bytes",
+                "dep:bytes",
+                "io-util",
+                "libc",
+                "mio/net",
+                "mio/os-poll",
+                "net",
+                "rt",
+                "rt-multi-thread"
+            ],
+            "The closure should follow features transitively"
+        );
+
+        Ok(())
+    }
+}
@@ -7,11 +7,20 @@ use serde::{Deserialize, Serialize};
 use serde_json::json;
 use std::process::Command;
 
+use super::maintenance::{self, MaintenanceWarning};
+use super::registry;
+use crate::config::{self, DEFAULT_REGISTRY};
+use crate::error;
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CrateInfo {
     name: String,
     description: String,
     version: String,
+    /// Reasons not to use the crate in new code, such as a RustSec advisory declaring it
+    /// unmaintained
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    maintenance_warnings: Vec<MaintenanceWarning>,
     license: Option<String>,
     rust_version: Option<String>,
     documentation: Option<String>,
@@ -21,6 +30,24 @@ pub struct CrateInfo {
     features: Vec<Feature>,
 }
 
+impl CrateInfo {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn version(&self) -> &str {
+        &self.version
+    }
+
+    pub fn repository(&self) -> Option<&str> {
+        self.repository.as_deref()
+    }
+
+    pub fn maintenance_warnings(&self) -> &[MaintenanceWarning] {
+        &self.maintenance_warnings
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Feature {
     name: String,
@@ -31,6 +58,7 @@ pub struct Feature {
 #[derive(Debug, Serialize, Deserialize)]
 struct CrateNameParam {
     crate_name: String,
+    registry: Option<String>,
 }
 
 pub struct CrateInfoTool;
@@ -40,6 +68,22 @@ impl CrateInfoTool {
         Self
     }
 
+    /// Runs `cargo info` for a crate and parses its output, adding the signs that the crate is
+    /// unmaintained.
+    pub(crate) fn fetch_crate_info(&self, crate_name: &str) -> Result<CrateInfo> {
+        let output = self.run_cargo_info(crate_name)?;
+        let mut info = self.parse_cargo_info_output(&output)?;
+        // `cargo info` may follow the version with where it was found, e.g. `(latest 1.0.0)`
+        let version = info.version.split_whitespace().next().unwrap_or_default();
+        info.maintenance_warnings = maintenance::check(
+            &info.name,
+            version,
+            &info.description,
+            info.repository.as_deref(),
+        );
+        Ok(info)
+    }
+
     fn parse_cargo_info_output(&self, output: &str) -> Result<CrateInfo> {
         let mut lines = output.lines();
 
@@ -60,6 +104,7 @@ impl CrateInfoTool {
             name,
             description,
             version: String::new(),
+            maintenance_warnings: Vec::new(),
             license: None,
             rust_version: None,
             documentation: None,
@@ -138,12 +183,17 @@ impl CrateInfoTool {
                 .unwrap_or_default(),
         ];
 
+        // Cargo resolves the registry name through its own configuration
+        let registry = config::current().active_registry().to_string();
+
         let mut last_error = None;
         for cargo_path in cargo_paths {
-            let result = Command::new(&cargo_path)
-                .arg("info")
-                .arg(crate_name)
-                .output();
+            let mut command = Command::new(&cargo_path);
+            command.arg("info").arg(crate_name);
+            if registry != DEFAULT_REGISTRY {
+                command.arg("--registry").arg(&registry);
+            }
+            let result = command.output();
 
             match result {
                 Ok(output) if output.status.success() => {
@@ -182,7 +232,9 @@ impl Tool for CrateInfoTool {
     fn description(&self) -> String {
         "Get detailed information about a Rust crate using cargo-info. \
         Returns strongly typed information including version, license, \
-        documentation links, and feature flags."
+        documentation links, and feature flags. Crates that are unmaintained or deprecated, \
+        per a RustSec advisory, an archived repository or their description, come with \
+        maintenance_warnings: don't recommend them for new code."
             .to_string()
     }
 
@@ -193,19 +245,21 @@ impl Tool for CrateInfoTool {
                 "crate_name": {
                     "type": "string",
                     "description": "Name of the crate to get information about"
-                }
+                },
+                "registry": registry::registry_schema()
             },
             "required": ["crate_name"]
         })
     }
 
     fn call(&self, input: Option<serde_json::Value>) -> Result<CallToolResponse> {
-        let args: CrateNameParam = serde_json::from_value(input.unwrap_or_default())?;
+        let args: CrateNameParam = error::parse_args(input)?;
+        config::current().ensure_crate_allowed(&args.crate_name)?;
 
         // Try to find cargo-info in multiple ways
-        let output = self.run_cargo_info(&args.crate_name)?;
-
-        let crate_info = self.parse_cargo_info_output(&output)?;
+        let crate_info = registry::in_registry(&args.crate_name, args.registry.as_deref(), || {
+            self.fetch_crate_info(&args.crate_name)
+        })?;
 
         Ok(CallToolResponse {
             content: vec![ToolResponseContent::Text {
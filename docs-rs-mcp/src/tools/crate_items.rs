@@ -1,23 +1,116 @@
-use anyhow::Result;
-use mcp_sdk::{
-    tools::Tool,
-    types::{CallToolResponse, ToolResponseContent},
-};
-use reqwest::blocking::Client;
-use scraper::{Html, Selector};
+use anyhow::{anyhow, Context, Result};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+#[cfg(feature = "native")]
+use mcp_sdk::{tools::Tool, types::CallToolResponse};
+use scraper::Html;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
-use std::collections::HashMap;
+use std::collections::BTreeMap;
+
+#[cfg(feature = "native")]
+use super::chunked::{ChunkWriter, DEFAULT_CHUNK_BYTES};
+use super::default_version::{self, Channel, ResolvedVersion};
+use super::docs_build::{self, DocsBuild, PageBuild};
+#[cfg(feature = "native")]
+use super::local_docs;
+use super::output_format::{self, Format};
+#[cfg(feature = "native")]
+use super::runtime;
+use super::{all_items_cache, docs_root, http, near_matches, registry, rustdoc_json, selectors};
+use crate::cache_key::{self, DerivedKey};
+use crate::config;
+use crate::error::{self, DocsRsMcpError};
+#[cfg(feature = "native")]
+use crate::{background_index, cache, index};
+
+/// Items per page when a cursor is given without a limit
+const DEFAULT_PAGE_SIZE: usize = 500;
+
+/// Ids of the sections of the all items page that are listed, as accepted by `kinds`
+const SECTION_KEYS: [&str; 8] = [
+    "macros",
+    "structs",
+    "enums",
+    "traits",
+    "functions",
+    "types",
+    "attributes",
+    "derives",
+];
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CrateItems {
     crate_name: String,
     version: String,
-    items: HashMap<String, Vec<Item>>,
+    /// Items per section and in total, of the whole listing even when only a page of it is
+    /// listed
+    #[serde(default)]
+    counts: ItemCounts,
+    /// Items by section, sections and the items in them sorted by name so listings of the
+    /// same docs serialize identically
+    items: BTreeMap<String, Vec<Item>>,
+    /// How `latest` or a version requirement was resolved to `version`, as docs.rs may default
+    /// to a pre-release or an older release than the highest stable one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    resolved_version: Option<ResolvedVersion>,
+    /// When docs.rs built the listed documentation and whether a newer release is out
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    docs_build: Option<DocsBuild>,
+    /// Sections no item was found for, telling a crate without such items apart from markup
+    /// the selectors don't match
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    debug: Vec<SectionDiagnostic>,
+    /// Items across all sections when only a page of them is listed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    total_items: Option<usize>,
+    /// Cursor to pass for the page after this one, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    next_cursor: Option<String>,
+    /// How far indexing of a crate too large to list in one pass has got, while the listing
+    /// holds only the modules read so far
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    indexing: Option<IndexingProgress>,
+    /// What the listing was read from, and by which parsers, for caching it
+    #[serde(skip)]
+    #[cfg_attr(not(feature = "native"), allow(dead_code))]
+    source: Option<DerivedKey>,
+}
+
+/// Modules read and left to read of a crate being indexed in the background
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IndexingProgress {
+    modules_read: usize,
+    modules_pending: usize,
+}
+
+impl IndexingProgress {
+    #[cfg(feature = "native")]
+    pub(crate) fn new(modules_read: usize, modules_pending: usize) -> Self {
+        Self {
+            modules_read,
+            modules_pending,
+        }
+    }
+
+    pub fn modules_read(&self) -> usize {
+        self.modules_read
+    }
+
+    pub fn modules_pending(&self) -> usize {
+        self.modules_pending
+    }
+
+    /// e.g. "Indexing in progress: 40 modules read, 12 to go; later calls list more items."
+    pub(crate) fn describe(&self) -> String {
+        format!(
+            "Indexing in progress: {} modules read, {} to go; later calls list more items.",
+            self.modules_read, self.modules_pending
+        )
+    }
 }
 
 impl CrateItems {
-    pub fn items(&self) -> &HashMap<String, Vec<Item>> {
+    pub fn items(&self) -> &BTreeMap<String, Vec<Item>> {
         &self.items
     }
 
@@ -28,6 +121,390 @@ impl CrateItems {
     pub fn version(&self) -> &str {
         &self.version
     }
+
+    pub fn docs_build(&self) -> Option<&DocsBuild> {
+        self.docs_build.as_ref()
+    }
+
+    pub fn counts(&self) -> &ItemCounts {
+        &self.counts
+    }
+
+    pub fn debug(&self) -> &[SectionDiagnostic] {
+        &self.debug
+    }
+
+    pub fn total_items(&self) -> Option<usize> {
+        self.total_items
+    }
+
+    pub fn next_cursor(&self) -> Option<&str> {
+        self.next_cursor.as_deref()
+    }
+
+    /// How far indexing has got when the listing is still partial
+    pub fn indexing(&self) -> Option<&IndexingProgress> {
+        self.indexing.as_ref()
+    }
+
+    #[cfg(feature = "native")]
+    pub(crate) fn source(&self) -> Option<&DerivedKey> {
+        self.source.as_ref()
+    }
+
+    /// The listing read from `source`, as cached listings don't carry their key.
+    #[cfg(feature = "native")]
+    pub(crate) fn with_source(self, source: DerivedKey) -> Self {
+        Self {
+            source: Some(source),
+            ..self
+        }
+    }
+
+    /// The listing as Markdown: a heading per section with a link per item.
+    pub fn to_markdown(&self) -> String {
+        let mut about = vec![format!(
+            "{} items: {}",
+            self.counts.total(),
+            self.counts
+                .sections
+                .iter()
+                .map(|(section, count)| format!("{} {}", count, section.to_lowercase()))
+                .collect::<Vec<_>>()
+                .join(", ")
+        )];
+        if let Some(build) = &self.docs_build {
+            about.push(build.describe());
+        }
+        if let Some(indexing) = &self.indexing {
+            about.push(indexing.describe());
+        }
+        let mut blocks = vec![
+            format!("# Items of `{}` {}", self.crate_name, self.version),
+            about.join("\n"),
+        ];
+        for (section, items) in &self.items {
+            blocks.push(format!("## {}", section));
+            blocks.push(
+                items
+                    .iter()
+                    .map(|item| format!("- [`{}`]({})", item.name, item.doc_link))
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            );
+        }
+        if let Some(cursor) = &self.next_cursor {
+            blocks.push(format!(
+                "More items follow: pass cursor `{}` for the next page.",
+                cursor
+            ));
+        }
+        blocks.join("\n\n")
+    }
+
+    /// The listing as a digest, one `path | kind` line per item, as the all items page has no
+    /// signatures or summaries.
+    pub fn to_digest(&self) -> String {
+        let mut lines: Vec<String> = self
+            .items
+            .values()
+            .flatten()
+            .map(|item| {
+                output_format::digest_line(
+                    &format!("{}::{}", self.crate_name, item.name),
+                    item.kind(),
+                    "",
+                    "",
+                )
+            })
+            .collect();
+        if let Some(cursor) = &self.next_cursor {
+            lines.push(format!("# More items follow: pass cursor {}", cursor));
+        }
+        if let Some(indexing) = &self.indexing {
+            lines.push(format!("# {}", indexing.describe()));
+        }
+        lines.join("\n")
+    }
+
+    /// Keeps the sections whose id is one of `kinds`, e.g. `structs` or `types`, counting the
+    /// items of those only.
+    pub fn retain_kinds(&mut self, kinds: &[String]) -> Result<(), DocsRsMcpError> {
+        if let Some(unknown) = kinds
+            .iter()
+            .find(|kind| !SECTION_KEYS.contains(&kind.as_str()))
+        {
+            return Err(DocsRsMcpError::InvalidInput(format!(
+                "Unknown item kind '{}', expected some of: {}",
+                unknown,
+                SECTION_KEYS.join(", ")
+            )));
+        }
+        self.items
+            .retain(|section, _| kinds.contains(&section_key(section)));
+        self.debug
+            .retain(|diagnostic| kinds.contains(&section_key(diagnostic.section())));
+        self.counts = ItemCounts::new(&self.items);
+        Ok(())
+    }
+
+    /// Keeps the items under `module`, e.g. `tokio::sync` or `sync`, including those of its
+    /// submodules, counting those only.
+    pub fn retain_module(&mut self, module: &str) {
+        let module = module.trim().trim_matches(':');
+        let crate_path = self.crate_name.replace('-', "_");
+        let module = module
+            .strip_prefix(crate_path.as_str())
+            .or_else(|| module.strip_prefix(self.crate_name.as_str()))
+            .filter(|rest| rest.is_empty() || rest.starts_with("::"))
+            .unwrap_or(module)
+            .trim_start_matches(':');
+        if module.is_empty() {
+            return;
+        }
+        let prefix = format!("{}::", module);
+        for items in self.items.values_mut() {
+            items.retain(|item| item.name.starts_with(&prefix));
+        }
+        self.items.retain(|_, items| !items.is_empty());
+        self.counts = ItemCounts::new(&self.items);
+    }
+
+    /// Keeps the page of at most `limit` items following `cursor`, the `next_cursor` of the
+    /// previous page. Pages follow a stable order, sections and the items in them sorted by
+    /// name, and a cursor points past the last item listed rather than at an offset, so paging
+    /// doesn't skip or repeat items around ones added in between.
+    pub fn into_page(mut self, limit: usize, cursor: Option<&Cursor>) -> CrateItems {
+        let limit = limit.max(1);
+        let mut entries: Vec<(String, Item)> = std::mem::take(&mut self.items)
+            .into_iter()
+            .flat_map(|(section, items)| items.into_iter().map(move |item| (section.clone(), item)))
+            .collect();
+        entries.sort_by(|a, b| (&a.0, &a.1.name).cmp(&(&b.0, &b.1.name)));
+        let total = entries.len();
+
+        let mut page: Vec<(String, Item)> = entries
+            .into_iter()
+            .filter(|(section, item)| {
+                cursor.is_none_or(|cursor| {
+                    (section.as_str(), item.name.as_str())
+                        > (cursor.section.as_str(), cursor.name.as_str())
+                })
+            })
+            .take(limit + 1)
+            .collect();
+        let more = page.len() > limit;
+        page.truncate(limit);
+
+        self.next_cursor = page.last().filter(|_| more).map(|(section, item)| {
+            Cursor {
+                version: self.version.clone(),
+                section: section.clone(),
+                name: item.name.clone(),
+            }
+            .encode()
+        });
+        for (section, item) in page {
+            self.items.entry(section).or_default().push(item);
+        }
+        self.total_items = Some(total);
+        self
+    }
+
+    /// The listing as a chunked response: a header with the counts of the sections, then the
+    /// items sorted by section and name, one JSON object per line with its section.
+    #[cfg(feature = "native")]
+    pub(crate) fn into_chunked_response(self, max_bytes: usize) -> Result<CallToolResponse> {
+        let header = ChunkedHeader {
+            crate_name: &self.crate_name,
+            version: &self.version,
+            resolved_version: self.resolved_version.as_ref(),
+            counts: &self.counts,
+            sections: self
+                .items
+                .iter()
+                .map(|(section, items)| (section.as_str(), items.len()))
+                .collect(),
+            total_items: self.total_items,
+            next_cursor: self.next_cursor.as_deref(),
+            debug: &self.debug,
+            indexing: self.indexing.as_ref(),
+        };
+        let mut writer = ChunkWriter::new(&header, max_bytes)?;
+
+        for (section, items) in &self.items {
+            for item in items {
+                writer.push(&ChunkedItem { section, item })?;
+            }
+        }
+        Ok(writer.into_response())
+    }
+
+    /// Finds an item by its full path (`sync::Mutex`), falling back to a unique match on the
+    /// last path segments (`Mutex`). Returns the item's section along with it.
+    pub fn find(&self, item_path: &str) -> Result<(&str, &Item)> {
+        let candidates = || {
+            self.items
+                .iter()
+                .flat_map(|(kind, items)| items.iter().map(move |item| (kind.as_str(), item)))
+        };
+
+        if let Some(found) = candidates().find(|(_, item)| item.name() == item_path) {
+            return Ok(found);
+        }
+
+        let suffix = format!("::{}", item_path);
+        let matches: Vec<_> = candidates()
+            .filter(|(_, item)| item.name().ends_with(&suffix))
+            .collect();
+        match matches.as_slice() {
+            [found] => Ok(*found),
+            [] => Err(DocsRsMcpError::ItemNotFound {
+                what: format!(
+                    "item {} in crate {} {}",
+                    item_path, self.crate_name, self.version
+                ),
+                did_you_mean: near_matches::nearest(
+                    item_path,
+                    candidates().map(|(_, item)| item.name()),
+                )
+                .into_iter()
+                .map(str::to_string)
+                .collect(),
+            }
+            .into()),
+            _ => Err(anyhow!(
+                "Item {} is ambiguous in crate {}; use one of: {}",
+                item_path,
+                self.crate_name,
+                matches
+                    .iter()
+                    .map(|(_, item)| item.name())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )),
+        }
+    }
+}
+
+/// First content of a chunked listing
+#[derive(Serialize)]
+struct ChunkedHeader<'a> {
+    crate_name: &'a str,
+    version: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    resolved_version: Option<&'a ResolvedVersion>,
+    counts: &'a ItemCounts,
+    /// Items listed per section
+    sections: BTreeMap<&'a str, usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    total_items: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    next_cursor: Option<&'a str>,
+    #[serde(skip_serializing_if = "<[_]>::is_empty")]
+    debug: &'a [SectionDiagnostic],
+    #[serde(skip_serializing_if = "Option::is_none")]
+    indexing: Option<&'a IndexingProgress>,
+}
+
+/// An item of a chunked listing
+#[derive(Serialize)]
+struct ChunkedItem<'a> {
+    section: &'a str,
+    #[serde(flatten)]
+    item: &'a Item,
+}
+
+/// Number of items per section and in total
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ItemCounts {
+    sections: BTreeMap<String, usize>,
+    total: usize,
+}
+
+impl ItemCounts {
+    fn new(items: &BTreeMap<String, Vec<Item>>) -> Self {
+        let sections: BTreeMap<String, usize> = items
+            .iter()
+            .map(|(section, items)| (section.clone(), items.len()))
+            .collect();
+        Self {
+            total: sections.values().sum(),
+            sections,
+        }
+    }
+
+    /// Items in `section`, e.g. `Structs`
+    pub fn section(&self, section: &str) -> usize {
+        self.sections.get(section).copied().unwrap_or_default()
+    }
+
+    pub fn total(&self) -> usize {
+        self.total
+    }
+}
+
+/// How a section of `all.html` was looked up when it yielded no items
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SectionDiagnostic {
+    section: String,
+    selector_used: String,
+    /// Links the selector matched, including any skipped for lacking a name or target
+    matched: usize,
+    /// Whether the page has the section at all; if it does, its markup wasn't understood
+    heading_found: bool,
+}
+
+impl SectionDiagnostic {
+    pub fn section(&self) -> &str {
+        &self.section
+    }
+
+    pub fn heading_found(&self) -> bool {
+        self.heading_found
+    }
+}
+
+/// Where a page of items ended: the last item listed and the version it was listed from, so
+/// the following pages come from the same release even while `latest` moves
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cursor {
+    version: String,
+    section: String,
+    name: String,
+}
+
+impl Cursor {
+    pub fn version(&self) -> &str {
+        &self.version
+    }
+
+    fn encode(&self) -> String {
+        URL_SAFE_NO_PAD.encode(format!("{}\n{}\n{}", self.version, self.section, self.name))
+    }
+
+    pub fn decode(cursor: &str) -> Result<Self> {
+        let decoded = URL_SAFE_NO_PAD
+            .decode(cursor.trim())
+            .ok()
+            .and_then(|bytes| String::from_utf8(bytes).ok())
+            .context(format!(
+                "Invalid cursor {}; pass the next_cursor of a page",
+                cursor
+            ))?;
+        let mut parts = decoded.splitn(3, '\n');
+        match (parts.next(), parts.next(), parts.next()) {
+            (Some(version), Some(section), Some(name)) => Ok(Self {
+                version: version.to_string(),
+                section: section.to_string(),
+                name: name.to_string(),
+            }),
+            _ => Err(anyhow!(
+                "Invalid cursor {}; pass the next_cursor of a page",
+                cursor
+            )),
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -49,12 +526,29 @@ impl Item {
     pub fn doc_link(&self) -> &str {
         &self.doc_link
     }
+
+    /// The rustdoc kind the page is named after, e.g. `struct` for `sync/struct.Mutex.html`
+    pub fn kind(&self) -> &str {
+        let file = self.path.rsplit('/').next().unwrap_or(&self.path);
+        file.split_once('.').map_or("", |(kind, _)| kind)
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 struct CrateNameParam {
     crate_name: String,
     version: Option<String>,
+    channel: Option<Channel>,
+    limit: Option<usize>,
+    cursor: Option<String>,
+    #[serde(default)]
+    chunked: bool,
+    #[serde(default)]
+    kinds: Vec<String>,
+    module: Option<String>,
+    #[serde(default)]
+    format: Format,
+    registry: Option<String>,
 }
 
 pub struct CrateItemsTool;
@@ -64,46 +558,129 @@ impl CrateItemsTool {
         Self
     }
 
-    fn get_docs_rs_url() -> String {
-        std::env::var("DOCS_RS_URL").unwrap_or_else(|_| "https://docs.rs".to_string())
+    pub(crate) async fn scrape_items(
+        &self,
+        crate_name: &str,
+        version: Option<&str>,
+    ) -> Result<CrateItems> {
+        self.scrape_items_on_channel(crate_name, version, None)
+            .await
     }
 
-    fn scrape_items(&self, crate_name: &str, version: Option<&str>) -> Result<CrateItems> {
-        let client = Client::new();
-        let version = version.unwrap_or("latest");
-        let base_url = Self::get_docs_rs_url();
-        let url = format!(
-            "{}/{}/{}/{}/all.html",
-            base_url, crate_name, version, crate_name
-        );
+    /// Like [`Self::scrape_items`], blocking until the listing is read, for tools that do their
+    /// lookups synchronously.
+    #[cfg(feature = "native")]
+    pub(crate) fn scrape_items_blocking(
+        &self,
+        crate_name: &str,
+        version: Option<&str>,
+    ) -> Result<CrateItems> {
+        runtime::block_on(self.scrape_items(crate_name, version))
+    }
 
-        let response = client.get(&url).send()?;
-        if !response.status().is_success() {
-            return Err(anyhow::anyhow!(
-                "Failed to fetch docs.rs page: {} - {}",
-                response.status(),
-                url
-            ));
+    /// Like [`Self::scrape_items`], documenting the highest release on `channel` instead of the
+    /// docs.rs default for `latest` when a channel is given.
+    pub(crate) async fn scrape_items_on_channel(
+        &self,
+        crate_name: &str,
+        version: Option<&str>,
+        channel: Option<Channel>,
+    ) -> Result<CrateItems> {
+        let resolved_version = default_version::resolve(crate_name, version, channel).await?;
+        let version = resolved_version
+            .as_ref()
+            .map_or(version.unwrap_or("latest"), ResolvedVersion::version)
+            .to_string();
+        // "latest" moves, so only concrete versions are served from the index
+        #[cfg(feature = "native")]
+        if version != "latest" {
+            if let Some(items) = index::load(crate_name, &version) {
+                // Listings indexed before counts were recorded have none
+                return Ok(CrateItems {
+                    resolved_version,
+                    counts: ItemCounts::new(&items.items),
+                    ..items
+                });
+            }
+        }
+        // Crates too large to list in one pass are read module by module in the background
+        #[cfg(feature = "native")]
+        if let Some(items) = background_index::listing(crate_name, &version).await {
+            return Ok(CrateItems {
+                resolved_version,
+                ..items
+            });
         }
 
-        let html = response.text()?;
-        let document = Html::parse_document(&html);
+        let root_url = docs_root::crate_root(crate_name, &version).await;
+        if let Some(json) = rustdoc_json::fetch_or_fallback(crate_name, &version).await {
+            return Ok(CrateItems {
+                resolved_version,
+                ..items_from_json(crate_name, &version, &root_url, &json)
+            });
+        }
 
-        // Initialize our categorized items
-        let mut items: HashMap<String, Vec<Item>> = HashMap::new();
+        let url = format!("{}all.html", root_url);
+        let page = match all_items_cache::get_or_fetch(&url, fetch_all_items(&url)).await {
+            Ok(page) => page,
+            #[cfg(feature = "native")]
+            Err(e)
+                if matches!(DocsRsMcpError::classify(&e), DocsRsMcpError::NotFound(_))
+                    && config::current().local_docs().enabled() =>
+            {
+                // Building runs cargo, so it gets a thread of its own with the configuration
+                let config = config::current().as_ref().clone();
+                let (name, release) = (crate_name.to_string(), version.clone());
+                let docs = tokio::task::spawn_blocking(move || {
+                    config::scoped(config, || local_docs::build(&name, &release))
+                })
+                .await?
+                .context(format!(
+                    "{} {} isn't on docs.rs and couldn't be documented locally",
+                    crate_name, version
+                ))?;
+                return Ok(CrateItems {
+                    resolved_version,
+                    ..items_from_json(crate_name, &version, docs.root_url(), docs.json())
+                });
+            }
+            Err(e) => return Err(e),
+        };
 
-        // The sections we want to extract
-        let sections = [
-            "macros",
-            "structs",
-            "enums",
-            "traits",
-            "functions",
-            "types",
-            "attributes",
-        ];
+        // Listings read from the same page by the same parsers are reused rather than read again
+        #[cfg(feature = "native")]
+        let key = all_items_key(&root_url, page.html());
+        #[cfg(feature = "native")]
+        if let Some(items) = cache::get_derived::<CrateItems>(&key) {
+            return Ok(CrateItems {
+                resolved_version,
+                ..items.with_source(key)
+            });
+        }
+        let items = self.parse_items(crate_name, &version, &root_url, page.html())?;
+        #[cfg(feature = "native")]
+        cache::put_derived(&key, &items);
+        Ok(CrateItems {
+            resolved_version,
+            ..items
+        })
+    }
+
+    /// Extracts the categorized items from the HTML of a crate's `all.html` page.
+    pub(crate) fn parse_items(
+        &self,
+        crate_name: &str,
+        version: &str,
+        root_url: &str,
+        html: &str,
+    ) -> Result<CrateItems> {
+        let document = Html::parse_document(html);
+
+        // Initialize our categorized items
+        let mut items = BTreeMap::new();
+        let mut debug = Vec::new();
 
-        for section in sections {
+        for section in SECTION_KEYS {
             // Each section has an h3 with the section ID and a following ul.all-items
             let section_name = match section {
                 "types" => "Type Aliases".to_string(),
@@ -122,9 +699,11 @@ impl CrateItemsTool {
             ];
 
             let mut section_items = Vec::new();
+            let mut matched = 0;
             for selector in &selectors {
-                let link_selector = Selector::parse(selector).unwrap();
+                let link_selector = selectors::parse(selector, "all items page")?;
                 for link in document.select(&link_selector) {
+                    matched += 1;
                     let name = link.text().collect::<String>().trim().to_string();
                     let path = link
                         .value()
@@ -135,14 +714,7 @@ impl CrateItemsTool {
                     let doc_link = if path.starts_with("http") {
                         path.clone()
                     } else {
-                        format!(
-                            "{}/{}/{}/{}/{}",
-                            base_url,
-                            crate_name,
-                            version,
-                            crate_name,
-                            path.trim_start_matches('/')
-                        )
+                        format!("{}{}", root_url, path.trim_start_matches('/'))
                     };
 
                     if !name.is_empty() && !path.is_empty() {
@@ -155,25 +727,212 @@ impl CrateItemsTool {
                 }
             }
 
-            if !section_items.is_empty() {
+            if section_items.is_empty() {
+                let heading_selector =
+                    selectors::parse(&format!("[id='{}']", section), "all items page")?;
+                debug.push(SectionDiagnostic {
+                    selector_used: selectors.join(", "),
+                    matched,
+                    heading_found: document.select(&heading_selector).next().is_some(),
+                    section: section_name,
+                });
+            } else {
+                section_items.sort_by(|a, b| a.name.cmp(&b.name));
                 items.insert(section_name, section_items);
             }
         }
 
         Ok(CrateItems {
             crate_name: crate_name.to_string(),
-            version: version.to_string(),
+            version: docs_build::from_page(&document).served_version(version),
+            counts: ItemCounts::new(&items),
             items,
+            resolved_version: None,
+            docs_build: None,
+            debug,
+            total_items: None,
+            next_cursor: None,
+            indexing: None,
+            source: Some(all_items_key(root_url, html)),
         })
     }
 }
 
+/// The key of the listing read from the `all.html` page below `root_url`
+fn all_items_key(root_url: &str, html: &str) -> DerivedKey {
+    DerivedKey::new(
+        &format!("{}all.html", root_url),
+        html.as_bytes(),
+        cache_key::target_of(root_url).as_deref(),
+    )
+}
+
+/// Fetches an `all.html` page, reporting an unsuccessful response as a typed error.
+async fn fetch_all_items(url: &str) -> Result<String> {
+    let response = http::shared()?.get(url).await?;
+    let status = response.status();
+    if !status.is_success() {
+        return Err(
+            anyhow::Error::new(DocsRsMcpError::from_status(url, status.as_u16())).context(format!(
+                "Failed to fetch docs.rs page: {} - {}",
+                status, url
+            )),
+        );
+    }
+    Ok(response.text().await?)
+}
+
+/// Lowercase section id as used by `kinds` and `--kinds`, e.g. `Type Aliases` -> `types`
+pub(crate) fn section_key(section: &str) -> String {
+    match section {
+        "Type Aliases" => "types".to_string(),
+        section => section.to_lowercase(),
+    }
+}
+
+/// Section of the all items page listing items of a rustdoc JSON `kind`, for the kinds
+/// [`CrateItemsTool::parse_items`] reads from the page
+fn json_section(kind: &str) -> Option<&'static str> {
+    let section = match kind {
+        "macro" => "Macros",
+        "struct" => "Structs",
+        "enum" => "Enums",
+        "trait" => "Traits",
+        "function" => "Functions",
+        "type_alias" | "typedef" => "Type Aliases",
+        "proc_attribute" => "Attributes",
+        "proc_derive" => "Derives",
+        _ => return None,
+    };
+    Some(section)
+}
+
+/// Section of the all items page listing the pages named after a rustdoc `kind`, e.g. `fn` for
+/// `fn.spawn.html`
+#[cfg(feature = "native")]
+fn page_section(kind: &str) -> Option<&'static str> {
+    let section = match kind {
+        "macro" => "Macros",
+        "struct" => "Structs",
+        "enum" => "Enums",
+        "trait" => "Traits",
+        "fn" => "Functions",
+        "type" => "Type Aliases",
+        "attr" => "Attributes",
+        "derive" => "Derives",
+        _ => return None,
+    };
+    Some(section)
+}
+
+/// Builds the listing [`CrateItemsTool::parse_items`] would read from the all items page from
+/// the pages of the items found so far, relative to `root_url`, e.g. `sync/struct.Mutex.html`.
+#[cfg(feature = "native")]
+pub(crate) fn from_pages<'a>(
+    crate_name: &str,
+    version: &str,
+    root_url: &str,
+    pages: impl IntoIterator<Item = &'a String>,
+    indexing: Option<IndexingProgress>,
+) -> CrateItems {
+    let mut items: BTreeMap<String, Vec<Item>> = BTreeMap::new();
+    for path in pages {
+        let (module, file) = path.rsplit_once('/').unwrap_or(("", path));
+        let Some((section, name)) = file
+            .strip_suffix(".html")
+            .and_then(|file| file.split_once('.'))
+            .and_then(|(kind, name)| Some((page_section(kind)?, name)))
+        else {
+            continue;
+        };
+        let name = match module {
+            "" => name.to_string(),
+            module => format!("{}::{}", module.replace('/', "::"), name),
+        };
+        items.entry(section.to_string()).or_default().push(Item {
+            name,
+            path: path.clone(),
+            doc_link: format!("{}{}", root_url, path),
+        });
+    }
+    for section_items in items.values_mut() {
+        section_items.sort_by(|a, b| a.name.cmp(&b.name));
+    }
+    let listed: Vec<&str> = items
+        .values()
+        .flatten()
+        .map(|item| item.path.as_str())
+        .collect();
+
+    CrateItems {
+        crate_name: crate_name.to_string(),
+        version: version.to_string(),
+        counts: ItemCounts::new(&items),
+        source: Some(DerivedKey::new(
+            root_url,
+            listed.join("\n").as_bytes(),
+            cache_key::target_of(root_url).as_deref(),
+        )),
+        items,
+        resolved_version: None,
+        docs_build: None,
+        debug: Vec::new(),
+        total_items: None,
+        next_cursor: None,
+        indexing,
+    }
+}
+
+/// Builds the listing [`CrateItemsTool::parse_items`] would read from the all items page from
+/// a crate's rustdoc JSON instead.
+fn items_from_json(
+    crate_name: &str,
+    version: &str,
+    root_url: &str,
+    json: &rustdoc_json::Crate,
+) -> CrateItems {
+    let mut items: BTreeMap<String, Vec<Item>> = BTreeMap::new();
+    for item in json.local_items() {
+        let (Some(section), Some(path)) = (json_section(item.kind()), item.page()) else {
+            continue;
+        };
+        items.entry(section.to_string()).or_default().push(Item {
+            name: item.name(),
+            doc_link: format!("{}{}", root_url, path),
+            path,
+        });
+    }
+    for section_items in items.values_mut() {
+        section_items.sort_by(|a, b| a.name.cmp(&b.name));
+    }
+
+    CrateItems {
+        crate_name: crate_name.to_string(),
+        // docs.rs serves `latest` without redirecting, so the JSON tells the release
+        version: json
+            .crate_version()
+            .filter(|_| version == "latest")
+            .unwrap_or(version)
+            .to_string(),
+        counts: ItemCounts::new(&items),
+        items,
+        resolved_version: None,
+        docs_build: None,
+        debug: Vec::new(),
+        total_items: None,
+        next_cursor: None,
+        indexing: None,
+        source: Some(json.key()),
+    }
+}
+
 impl Default for CrateItemsTool {
     fn default() -> Self {
         Self::new()
     }
 }
 
+#[cfg(feature = "native")]
 impl Tool for CrateItemsTool {
     fn name(&self) -> String {
         "crate_items".to_string()
@@ -182,7 +941,20 @@ impl Tool for CrateItemsTool {
     fn description(&self) -> String {
         "Get a list of all items (structs, traits, enums, etc.) exposed by a crate \
         by scraping its docs.rs documentation. Returns categorized items with their \
-        documentation links."
+        documentation links, and counts of the items per section and in total, which cover the \
+        whole crate when only a page of items is listed. Sections that yielded no items are listed under debug with the \
+        selector used and whether the page has the section at all. When no version or a \
+        semver requirement such as ^1.0 is given, resolved_version tells which release it resolved to and the highest stable release \
+        if a different one was documented, and docs_build when docs.rs built the documentation and \
+        the latest release when a newer one is out; when docs.rs' latest still documents an older \
+        release, latest_release_docs tells whether the newer one's docs are pending or failed to build. Pass a channel to pick stable releases, pre-releases \
+        or either instead of the release docs.rs serves. For large crates, pass a limit to get a \
+        page of items, sorted by section and name, and pass the returned next_cursor to get the \
+        next page, pass kinds or module to list only some kinds of items or the items under a \
+        module, or pass chunked to get the items as JSON Lines split over several contents \
+        after a header with the section counts. Very large crates are indexed module by module \
+        in the background: until that is done the listing holds the items found so far and \
+        indexing tells how many modules are left, so call again later for the rest."
             .to_string()
     }
 
@@ -196,34 +968,100 @@ impl Tool for CrateItemsTool {
                 },
                 "version": {
                     "type": "string",
-                    "description": "Optional version of the crate (defaults to latest)"
-                }
+                    "description": "Optional version of the crate, or a semver requirement such as ^1.0 or >=0.28, <0.30 resolved to the highest matching release (defaults to latest)"
+                },
+                "channel": {
+                    "type": "string",
+                    "enum": ["stable", "prerelease", "any"],
+                    "description": "When no version is given, document the highest stable release, the highest pre-release (beta, release candidate) or the highest release of either kind, instead of the release docs.rs serves as latest"
+                },
+                "limit": {
+                    "type": "integer",
+                    "description": "Maximum number of items to return, paging through the rest (defaults to all items, or 500 when a cursor is given)"
+                },
+                "cursor": {
+                    "type": "string",
+                    "description": "next_cursor of the previous page, to get the items after it from the same version"
+                },
+                "chunked": {
+                    "type": "boolean",
+                    "description": "Return a header with the section counts followed by the items as JSON Lines (one object per item, with its section) over several contents, so large listings can be read chunk by chunk (defaults to false)"
+                },
+                "kinds": {
+                    "type": "array",
+                    "items": {"type": "string", "enum": SECTION_KEYS},
+                    "description": "Only list items of these kinds, e.g. [\"structs\", \"traits\"] (defaults to all kinds)"
+                },
+                "module": {
+                    "type": "string",
+                    "description": "Only list items under this module and its submodules, e.g. tokio::sync or sync (defaults to the whole crate)"
+                },
+                "format": output_format::format_schema(),
+                "registry": registry::registry_schema()
             },
             "required": ["crate_name"]
         })
     }
 
     fn call(&self, input: Option<serde_json::Value>) -> Result<CallToolResponse> {
-        let args: CrateNameParam = serde_json::from_value(input.unwrap_or_default())?;
-        let items = self.scrape_items(&args.crate_name, args.version.as_deref())?;
-
-        Ok(CallToolResponse {
-            content: vec![ToolResponseContent::Text {
-                text: serde_json::to_string_pretty(&items)?,
-            }],
-            is_error: None,
-            meta: None,
-        })
+        let args: CrateNameParam = error::parse_args(input)?;
+        config::current().ensure_crate_allowed(&args.crate_name)?;
+        let cursor = args.cursor.as_deref().map(Cursor::decode).transpose()?;
+        // Later pages are listed from the version the first one was
+        let version = cursor
+            .as_ref()
+            .map(Cursor::version)
+            .or(args.version.as_deref());
+        let mut items = runtime::block_on(registry::in_registry_async(
+            &args.crate_name,
+            args.registry.as_deref(),
+            async {
+                let mut items = self
+                    .scrape_items_on_channel(&args.crate_name, version, args.channel)
+                    .await?;
+                // The listing names the release `latest` documented
+                let requested = match version {
+                    None | Some("latest") if items.resolved_version.is_none() => "latest",
+                    _ => items.version.as_str(),
+                };
+                items.docs_build = docs_build::fetch(
+                    &args.crate_name,
+                    requested,
+                    PageBuild::of_release(&items.version),
+                )
+                .await;
+                anyhow::Ok(items)
+            },
+        ))?;
+        if !args.kinds.is_empty() {
+            let kinds: Vec<String> = args.kinds.iter().map(|kind| kind.to_lowercase()).collect();
+            items.retain_kinds(&kinds)?;
+        }
+        if let Some(module) = &args.module {
+            items.retain_module(module);
+        }
+        if args.limit.is_some() || cursor.is_some() {
+            items = items.into_page(args.limit.unwrap_or(DEFAULT_PAGE_SIZE), cursor.as_ref());
+        }
+        if args.chunked {
+            return items.into_chunked_response(DEFAULT_CHUNK_BYTES);
+        }
+
+        args.format
+            .response(&items, CrateItems::to_markdown, CrateItems::to_digest)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use mcp_sdk::types::ToolResponseContent;
     use pretty_assertions::assert_eq;
-    use scraper::Html;
+    use scraper::{Html, Selector};
     use std::fs;
 
+    const SCRAPER_ROOT: &str = "https://docs.rs/scraper/0.22.0/scraper/";
+
     fn load_scraper_test_html() -> String {
         fs::read_to_string("test-data/list-of-all-items-scraper-0.22.0.html")
             .expect("Should be able to read test HTML file")
@@ -325,7 +1163,7 @@ mod tests {
     }
 
     #[test]
-    fn test_item_counts() {
+    fn test_item_counts() -> Result<()> {
         let html = load_scraper_test_html();
         let document = Html::parse_document(&html);
 
@@ -343,6 +1181,22 @@ mod tests {
                 section
             );
         }
+
+        let listing =
+            CrateItemsTool::new().parse_items("scraper", "0.22.0", SCRAPER_ROOT, &html)?;
+        let counts = listing.counts();
+        assert_eq!(counts.section("Structs"), 18);
+        assert_eq!(counts.section("Type Aliases"), 3);
+        assert_eq!(counts.section("Macros"), 0);
+        assert_eq!(counts.total(), 29);
+
+        let latest = CrateItemsTool::new().parse_items("scraper", "latest", SCRAPER_ROOT, &html)?;
+        assert_eq!(
+            latest.version(),
+            "0.22.0",
+            "latest is reported as the release the page documents"
+        );
+        Ok(())
     }
 
     #[test]
@@ -378,6 +1232,324 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_section_diagnostics() {
+        let html = load_scraper_test_html();
+        let items = CrateItemsTool::new()
+            .parse_items("scraper", "0.22.0", SCRAPER_ROOT, &html)
+            .unwrap();
+        let empty: Vec<(&str, bool)> = items
+            .debug()
+            .iter()
+            .map(|d| (d.section(), d.heading_found()))
+            .collect();
+        assert_eq!(
+            empty,
+            vec![
+                ("Macros", false),
+                ("Functions", false),
+                ("Attributes", false),
+                ("Derives", false)
+            ],
+            "scraper has no macros, functions, attributes or derives"
+        );
+
+        // Markup the selectors don't know, such as a section rendered as a table
+        let html = r#"<h3 id="traits">Traits</h3><table class="all-items"><tr><td><a href="trait.Element.html">Element</a></td></tr></table>"#;
+        let items = CrateItemsTool::new()
+            .parse_items("scraper", "0.22.0", SCRAPER_ROOT, html)
+            .unwrap();
+        let traits = items
+            .debug()
+            .iter()
+            .find(|d| d.section() == "Traits")
+            .expect("Traits should be reported");
+        assert!(traits.heading_found(), "The traits section exists");
+        assert_eq!(traits.matched, 0);
+        assert_eq!(
+            traits.selector_used,
+            "h3#traits + ul.all-items > li > a, div[id='traits'] > div.item-table > div.item-row > a"
+        );
+    }
+
+    #[test]
+    fn test_into_chunked_response() -> Result<()> {
+        let items = CrateItemsTool::new().parse_items(
+            "tokio",
+            "1.43.0",
+            "https://docs.rs/tokio/1.43.0/tokio/",
+            &load_tokio_test_html(),
+        )?;
+        let total: usize = items.items().values().map(Vec::len).sum();
+        let response = items.into_chunked_response(4096)?;
+
+        let texts: Vec<&str> = response
+            .content
+            .iter()
+            .map(|content| match content {
+                ToolResponseContent::Text { text } => text.as_str(),
+                _ => panic!("Expected text contents"),
+            })
+            .collect();
+        assert!(texts.len() > 2, "The listing should span several chunks");
+        let header: serde_json::Value = serde_json::from_str(texts[0])?;
+        assert_eq!(header["crate_name"], "tokio");
+
+        let lines: Vec<serde_json::Value> = texts[1..]
+            .iter()
+            .flat_map(|chunk| chunk.lines())
+            .map(serde_json::from_str)
+            .collect::<serde_json::Result<_>>()?;
+        assert_eq!(lines.len(), total);
+        let structs = lines
+            .iter()
+            .filter(|line| line["section"] == "Structs")
+            .count();
+        assert_eq!(header["sections"]["Structs"], json!(structs));
+        assert!(lines[0]["doc_link"].is_string());
+        Ok(())
+    }
+
+    #[test]
+    fn test_stable_order() -> Result<()> {
+        let listing = || {
+            CrateItemsTool::new().parse_items(
+                "tokio",
+                "1.43.0",
+                "https://docs.rs/tokio/1.43.0/tokio/",
+                &load_tokio_test_html(),
+            )
+        };
+        let items = listing()?;
+
+        let sections: Vec<&String> = items.items().keys().collect();
+        assert!(sections.is_sorted(), "Sections should be sorted");
+        for (section, section_items) in items.items() {
+            assert!(
+                section_items.is_sorted_by(|a, b| a.name() <= b.name()),
+                "{} should be sorted by path",
+                section
+            );
+        }
+        assert_eq!(
+            serde_json::to_string(&items)?,
+            serde_json::to_string(&listing()?)?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_items_from_json() -> Result<()> {
+        let json = rustdoc_json::parse(&fs::read_to_string("test-data/rustdoc_json/demo.json")?)?;
+        let items = items_from_json("demo", "0.4.2", "https://docs.rs/demo/0.4.2/demo/", &json);
+
+        let mut sections: Vec<&str> = items.items().keys().map(String::as_str).collect();
+        sections.sort();
+        assert_eq!(
+            sections,
+            [
+                "Derives",
+                "Enums",
+                "Functions",
+                "Structs",
+                "Traits",
+                "Type Aliases"
+            ]
+        );
+        let (section, frame) = items.find("codec::Frame")?;
+        assert_eq!(section, "Structs");
+        assert_eq!(frame.path(), "codec/struct.Frame.html");
+        assert_eq!(
+            frame.doc_link(),
+            "https://docs.rs/demo/0.4.2/demo/codec/struct.Frame.html"
+        );
+        assert!(items.debug().is_empty());
+
+        let latest = items_from_json("demo", "latest", "https://docs.rs/demo/latest/demo/", &json);
+        assert_eq!(latest.version(), "0.4.2");
+        Ok(())
+    }
+
+    #[test]
+    fn test_find_suggests_near_names() -> Result<()> {
+        let items = CrateItemsTool::new().parse_items(
+            "tokio",
+            "1.43.0",
+            "https://docs.rs/tokio/1.43.0/tokio/",
+            &load_tokio_test_html(),
+        )?;
+        let error = items.find("MutexGaurd").expect_err("misspelled");
+        let error = DocsRsMcpError::classify(&error);
+        assert_eq!(error.kind(), "not_found");
+        assert_eq!(
+            error.did_you_mean().first().map(String::as_str),
+            Some("sync::MutexGuard"),
+            "{:?}",
+            error
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_into_page() -> Result<()> {
+        let html = load_scraper_test_html();
+        let listing =
+            || CrateItemsTool::new().parse_items("scraper", "0.22.0", SCRAPER_ROOT, &html);
+
+        let mut seen = Vec::new();
+        let mut cursor = None;
+        loop {
+            let page = listing()?.into_page(10, cursor.as_ref());
+            assert_eq!(page.total_items(), Some(29));
+            assert_eq!(
+                page.counts().section("Structs"),
+                18,
+                "Counts cover the whole listing"
+            );
+            let mut sections: Vec<_> = page.items().iter().collect();
+            sections.sort_by(|a, b| a.0.cmp(b.0));
+            for (section, items) in sections {
+                seen.extend(
+                    items
+                        .iter()
+                        .map(|item| (section.clone(), item.name().to_string())),
+                );
+            }
+            match page.next_cursor() {
+                Some(next) => cursor = Some(Cursor::decode(next)?),
+                None => break,
+            }
+        }
+
+        // Every item exactly once, sections and the items in them in name order
+        let mut expected = seen.clone();
+        expected.sort();
+        expected.dedup();
+        assert_eq!(seen, expected);
+        assert_eq!(seen.len(), 29);
+        assert_eq!(
+            seen[0],
+            ("Enums".to_string(), "CaseSensitivity".to_string())
+        );
+
+        let first = listing()?.into_page(10, None);
+        let cursor = Cursor::decode(first.next_cursor().expect("There are more pages"))?;
+        assert_eq!(cursor.version(), "0.22.0");
+        assert!(Cursor::decode("not a cursor!").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_to_markdown() -> Result<()> {
+        let items = CrateItemsTool::new().parse_items(
+            "tokio",
+            "1.43.0",
+            "https://docs.rs/tokio/1.43.0/tokio/",
+            &load_tokio_test_html(),
+        )?;
+        let markdown = items.into_page(2, None).to_markdown();
+        assert!(
+            markdown.starts_with("# Items of `tokio` 1.43.0\n\n"),
+            "{}",
+            markdown
+        );
+        assert!(
+            markdown.contains("\n\n## Attributes\n\n- [`"),
+            "{}",
+            markdown
+        );
+        assert!(markdown.contains("](https://docs.rs/tokio/1.43.0/tokio/"));
+        assert!(markdown.ends_with("for the next page."), "{}", markdown);
+        Ok(())
+    }
+
+    #[test]
+    fn test_retain_kinds() -> Result<()> {
+        let mut items = CrateItemsTool::new().parse_items(
+            "tokio",
+            "1.43.0",
+            "https://docs.rs/tokio/1.43.0/tokio/",
+            &load_tokio_test_html(),
+        )?;
+        let structs = items.counts().section("Structs");
+        let traits = items.counts().section("Traits");
+        items.retain_kinds(&["structs".to_string(), "traits".to_string()])?;
+        assert_eq!(
+            items.items().keys().collect::<Vec<_>>(),
+            ["Structs", "Traits"]
+        );
+        assert_eq!(items.counts().total(), structs + traits);
+
+        let error = items
+            .retain_kinds(&["struct".to_string()])
+            .expect_err("kinds are section ids");
+        assert!(matches!(error, DocsRsMcpError::InvalidInput(_)));
+        assert!(error.to_string().contains("structs, enums"), "{}", error);
+        Ok(())
+    }
+
+    #[test]
+    fn test_retain_module() -> Result<()> {
+        let parse = || {
+            CrateItemsTool::new().parse_items(
+                "tokio",
+                "1.43.0",
+                "https://docs.rs/tokio/1.43.0/tokio/",
+                &load_tokio_test_html(),
+            )
+        };
+        let mut items = parse()?;
+        items.retain_module("tokio::sync");
+        let names: Vec<&str> = items.items().values().flatten().map(Item::name).collect();
+        assert!(names.contains(&"sync::Mutex"), "{:?}", names);
+        assert!(
+            names.contains(&"sync::mpsc::Sender"),
+            "Submodules are included"
+        );
+        assert!(names.iter().all(|name| name.starts_with("sync::")));
+        assert_eq!(items.counts().total(), names.len());
+
+        let mut unqualified = parse()?;
+        unqualified.retain_module("sync::");
+        assert_eq!(unqualified.counts(), items.counts());
+
+        let mut whole = parse()?;
+        let total = whole.counts().total();
+        whole.retain_module("tokio");
+        assert_eq!(
+            whole.counts().total(),
+            total,
+            "The crate root keeps everything"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_to_digest() -> Result<()> {
+        let items = CrateItemsTool::new().parse_items(
+            "tokio",
+            "1.43.0",
+            "https://docs.rs/tokio/1.43.0/tokio/",
+            &load_tokio_test_html(),
+        )?;
+        let digest = items.into_page(3, None).to_digest();
+        let lines: Vec<&str> = digest.lines().collect();
+        assert_eq!(
+            lines[..3],
+            [
+                "tokio::main | attr",
+                "tokio::test | attr",
+                "tokio::doc::NotDefinedHere | enum"
+            ]
+        );
+        assert!(
+            lines[3].starts_with("# More items follow: pass cursor "),
+            "{}",
+            digest
+        );
+        Ok(())
+    }
+
     #[test]
     fn test_tokio_items() {
         let html = load_tokio_test_html();
@@ -0,0 +1,800 @@
+use anyhow::{anyhow, Context, Result};
+use mcp_sdk::{
+    tools::Tool,
+    types::{CallToolResponse, ToolResponseContent},
+};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tracing::{debug, warn};
+
+use super::browse_source::SourceView;
+use super::crate_changelog::raw_file_urls;
+use super::get_struct_docs::{HtmlFetcher, HttpHtmlFetcher};
+use super::{crates_io, registry, runtime, BrowseSourceTool};
+use crate::{config, error};
+
+/// Names license files start with, compared case-insensitively
+const LICENSE_FILE_PREFIXES: &[&str] = &[
+    "LICENSE",
+    "LICENCE",
+    "COPYING",
+    "COPYRIGHT",
+    "UNLICENSE",
+    "NOTICE",
+];
+
+/// Files tried in the repository when the published package has no license file
+const REPOSITORY_LICENSE_FILES: &[&str] = &[
+    "LICENSE",
+    "LICENSE-MIT",
+    "LICENSE-APACHE",
+    "LICENSE.md",
+    "LICENSE.txt",
+    "COPYING",
+    "UNLICENSE",
+];
+
+/// License files read at most, so crates shipping one per dependency don't flood the response
+const MAX_FILES: usize = 6;
+
+/// Phrases that identify a license text, in the order they are checked: the LGPL before the
+/// GPL it mentions, and the 3-clause BSD license before the 2-clause one it extends.
+const LICENSE_MARKERS: &[(&str, &str)] = &[
+    ("Apache-2.0", "apache license version 2.0"),
+    ("MIT", "permission is hereby granted, free of charge"),
+    ("LGPL", "gnu lesser general public license"),
+    ("GPL", "gnu general public license"),
+    ("MPL-2.0", "mozilla public license version 2.0"),
+    ("BSD-3-Clause", "neither the name of"),
+    (
+        "BSD-2-Clause",
+        "redistribution and use in source and binary forms",
+    ),
+    (
+        "ISC",
+        "permission to use, copy, modify, and/or distribute this software for any purpose",
+    ),
+    (
+        "Unlicense",
+        "this is free and unencumbered software released into the public domain",
+    ),
+    (
+        "Zlib",
+        "this software is provided 'as-is', without any express or implied",
+    ),
+    ("BSL-1.0", "boost software license - version 1.0"),
+    ("CC0-1.0", "cc0 1.0 universal"),
+];
+
+/// The license a crate version is published under, with the license texts it ships
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CrateLicense {
+    crate_name: String,
+    version: String,
+    /// The manifest's `license` field; missing when the crate only points at a `license-file`
+    expression: Option<LicenseExpression>,
+    repository: Option<String>,
+    files: Vec<LicenseFile>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    notes: Vec<String>,
+}
+
+impl CrateLicense {
+    pub fn expression(&self) -> Option<&LicenseExpression> {
+        self.expression.as_ref()
+    }
+
+    pub fn files(&self) -> &[LicenseFile] {
+        &self.files
+    }
+}
+
+/// An SPDX license expression taken apart
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LicenseExpression {
+    expression: String,
+    /// License identifiers the expression names, e.g. `MIT` and `Apache-2.0`
+    licenses: Vec<String>,
+    /// Exceptions granted with `WITH`, e.g. `LLVM-exception`
+    exceptions: Vec<String>,
+    /// Ways to comply: the terms of every license in any one option apply.
+    /// `MIT OR Apache-2.0` gives two options, `MIT AND Apache-2.0` one with both.
+    options: Vec<Vec<String>>,
+}
+
+impl LicenseExpression {
+    pub fn licenses(&self) -> &[String] {
+        &self.licenses
+    }
+
+    pub fn exceptions(&self) -> &[String] {
+        &self.exceptions
+    }
+
+    pub fn options(&self) -> &[Vec<String>] {
+        &self.options
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LicenseFile {
+    path: String,
+    /// `package` for files published with the crate, `repository` for files read from its
+    /// repository instead
+    source: String,
+    url: String,
+    /// License the file holds, recognised by its name or text
+    license: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    text: Option<String>,
+}
+
+impl LicenseFile {
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    pub fn license(&self) -> Option<&str> {
+        self.license.as_deref()
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CrateLicenseParams {
+    crate_name: String,
+    version: Option<String>,
+    include_text: Option<bool>,
+    registry: Option<String>,
+}
+
+pub struct CrateLicenseTool {
+    html_fetcher: Box<dyn HtmlFetcher>,
+}
+
+impl CrateLicenseTool {
+    pub fn new() -> Self {
+        Self {
+            html_fetcher: Box::new(HttpHtmlFetcher::new()),
+        }
+    }
+
+    fn fetch_license(
+        &self,
+        crate_name: &str,
+        version: Option<&str>,
+        include_text: bool,
+    ) -> Result<CrateLicense> {
+        let url = format!(
+            "{}/api/v1/crates/{}",
+            crates_io::crates_io_url(),
+            crate_name
+        );
+        debug!("Fetching crate license from URL: {}", url);
+        let (version, license, repository) =
+            parse_crate_response(&crates_io::fetch_blocking(&url)?, version)?;
+
+        let mut notes = Vec::new();
+        let expression = license.as_deref().map(parse_expression).transpose()?;
+        if expression.is_none() {
+            notes.push(format!(
+                "{} {} declares no SPDX license expression; its license files are the terms",
+                crate_name, version
+            ));
+        }
+
+        let mut files = self.package_files(crate_name, &version);
+        if files.is_empty() {
+            match repository.as_deref() {
+                Some(repository) => files = self.repository_files(repository, crate_name),
+                None => debug!(
+                    "{} declares no repository to read licenses from",
+                    crate_name
+                ),
+            }
+        }
+
+        let licenses = expression
+            .as_ref()
+            .map(|expression| expression.licenses.as_slice())
+            .unwrap_or_default();
+        for file in &mut files {
+            let text = file.text.as_deref().unwrap_or_default();
+            file.license = file_license(&file.path, text, licenses);
+            if !include_text {
+                file.text = None;
+            }
+        }
+        notes.extend(license_notes(licenses, &files));
+
+        Ok(CrateLicense {
+            crate_name: crate_name.to_string(),
+            version,
+            expression,
+            repository,
+            files,
+            notes,
+        })
+    }
+
+    /// License files published with the crate, read through the docs.rs source browser
+    fn package_files(&self, crate_name: &str, version: &str) -> Vec<LicenseFile> {
+        let browse_source = BrowseSourceTool::new();
+        let entries = match browse_source.browse(crate_name, "", Some(version)) {
+            Ok(SourceView::Directory { entries, .. }) => entries,
+            Ok(SourceView::File { .. }) => Vec::new(),
+            Err(e) => {
+                warn!("Failed to list the package of {}: {:#}", crate_name, e);
+                Vec::new()
+            }
+        };
+
+        entries
+            .iter()
+            .filter(|entry| !entry.is_dir() && is_license_file(entry.name()))
+            .take(MAX_FILES)
+            .filter_map(|entry| {
+                match browse_source.browse(crate_name, entry.name(), Some(version)) {
+                    Ok(SourceView::File { contents, .. }) => Some(LicenseFile {
+                        path: entry.name().to_string(),
+                        source: "package".to_string(),
+                        url: format!(
+                            "{}/crate/{}/{}/source/{}",
+                            config::current().sources().docs_rs_url(),
+                            crate_name,
+                            version,
+                            entry.name()
+                        ),
+                        license: None,
+                        text: Some(contents),
+                    }),
+                    Ok(SourceView::Directory { .. }) => None,
+                    Err(e) => {
+                        warn!("Failed to read {} of {}: {:#}", entry.name(), crate_name, e);
+                        None
+                    }
+                }
+            })
+            .collect()
+    }
+
+    /// License files at the head of the repository, from the first directory that has any
+    fn repository_files(&self, repository: &str, crate_name: &str) -> Vec<LicenseFile> {
+        let urls = match raw_file_urls(repository, crate_name, REPOSITORY_LICENSE_FILES) {
+            Ok(urls) => urls,
+            Err(e) => {
+                debug!("Can't read licenses from {}: {:#}", repository, e);
+                return Vec::new();
+            }
+        };
+
+        let mut files: Vec<LicenseFile> = Vec::new();
+        for url in urls {
+            let Some((directory, name)) = url.rsplit_once('/') else {
+                continue;
+            };
+            if files
+                .first()
+                .is_some_and(|file| !file.url.starts_with(&format!("{}/", directory)))
+            {
+                break;
+            }
+            match self.html_fetcher.fetch_html_blocking(&url) {
+                Ok(text) => files.push(LicenseFile {
+                    path: name.to_string(),
+                    source: "repository".to_string(),
+                    url: url.clone(),
+                    license: None,
+                    text: Some(text),
+                }),
+                Err(e) => debug!("No license at {}: {}", url, e),
+            }
+        }
+        files
+    }
+}
+
+impl Default for CrateLicenseTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The version asked for, or the latest stable one, with its license and the crate's
+/// repository
+fn parse_crate_response(
+    body: &str,
+    version: Option<&str>,
+) -> Result<(String, Option<String>, Option<String>)> {
+    let response = crates_io::parse_crate(body)?;
+    let repository = response.krate.repository.clone();
+    let version = match version {
+        Some(version) if version != "latest" => version.to_string(),
+        _ => response.krate.latest_version(),
+    };
+    let published = response
+        .versions
+        .into_iter()
+        .find(|published| published.num == version)
+        .ok_or_else(|| anyhow!("Version {} is not published", version))?;
+    Ok((version, published.license, repository))
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Open,
+    Close,
+    And,
+    Or,
+    With,
+    Id(String),
+}
+
+fn tokenize(expression: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut word = String::new();
+    let flush = |word: &mut String, tokens: &mut Vec<Token>| {
+        if word.is_empty() {
+            return;
+        }
+        tokens.push(match word.to_uppercase().as_str() {
+            "AND" => Token::And,
+            "OR" => Token::Or,
+            "WITH" => Token::With,
+            _ => Token::Id(word.clone()),
+        });
+        word.clear();
+    };
+
+    for c in expression.chars() {
+        match c {
+            '(' | ')' | '/' => {
+                flush(&mut word, &mut tokens);
+                tokens.push(match c {
+                    '(' => Token::Open,
+                    ')' => Token::Close,
+                    // `MIT/Apache-2.0` predates SPDX expressions and means either
+                    _ => Token::Or,
+                });
+            }
+            c if c.is_whitespace() => flush(&mut word, &mut tokens),
+            c => word.push(c),
+        }
+    }
+    flush(&mut word, &mut tokens);
+    tokens
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Expression {
+    /// A license, with the exception granted to it if any, e.g. `Apache-2.0 WITH LLVM-exception`
+    License(String),
+    All(Vec<Expression>),
+    Any(Vec<Expression>),
+}
+
+/// Recursive descent over the tokens, `WITH` binding tighter than `AND` and `AND` tighter
+/// than `OR`
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+    licenses: Vec<String>,
+    exceptions: Vec<String>,
+}
+
+impl Parser {
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn any(&mut self) -> Result<Expression> {
+        let mut alternatives = vec![self.all()?];
+        while self.peek() == Some(&Token::Or) {
+            self.position += 1;
+            alternatives.push(self.all()?);
+        }
+        Ok(collapse(alternatives, Expression::Any))
+    }
+
+    fn all(&mut self) -> Result<Expression> {
+        let mut terms = vec![self.term()?];
+        while self.peek() == Some(&Token::And) {
+            self.position += 1;
+            terms.push(self.term()?);
+        }
+        Ok(collapse(terms, Expression::All))
+    }
+
+    fn term(&mut self) -> Result<Expression> {
+        match self.next() {
+            Some(Token::Open) => {
+                let expression = self.any()?;
+                match self.next() {
+                    Some(Token::Close) => Ok(expression),
+                    _ => Err(anyhow!("Unbalanced parenthesis")),
+                }
+            }
+            Some(Token::Id(license)) => {
+                if !self.licenses.contains(&license) {
+                    self.licenses.push(license.clone());
+                }
+                if self.peek() != Some(&Token::With) {
+                    return Ok(Expression::License(license));
+                }
+                self.position += 1;
+                match self.next() {
+                    Some(Token::Id(exception)) => {
+                        let term = format!("{} WITH {}", license, exception);
+                        if !self.exceptions.contains(&exception) {
+                            self.exceptions.push(exception);
+                        }
+                        Ok(Expression::License(term))
+                    }
+                    _ => Err(anyhow!("WITH must be followed by an exception")),
+                }
+            }
+            Some(token) => Err(anyhow!("Unexpected {:?}", token)),
+            None => Err(anyhow!("Unexpected end")),
+        }
+    }
+}
+
+fn collapse(
+    mut expressions: Vec<Expression>,
+    wrap: fn(Vec<Expression>) -> Expression,
+) -> Expression {
+    if expressions.len() == 1 {
+        expressions.remove(0)
+    } else {
+        wrap(expressions)
+    }
+}
+
+/// Parses an SPDX license expression such as `(MIT OR Apache-2.0) AND Unicode-3.0`. The
+/// legacy `MIT/Apache-2.0` form reads as `MIT OR Apache-2.0`.
+fn parse_expression(expression: &str) -> Result<LicenseExpression> {
+    let mut parser = Parser {
+        tokens: tokenize(expression),
+        position: 0,
+        licenses: Vec::new(),
+        exceptions: Vec::new(),
+    };
+    let parsed = parser
+        .any()
+        .and_then(|parsed| match parser.peek() {
+            None => Ok(parsed),
+            Some(token) => Err(anyhow!("Unexpected {:?}", token)),
+        })
+        .context(format!("Invalid license expression {}", expression))?;
+
+    Ok(LicenseExpression {
+        expression: expression.trim().to_string(),
+        licenses: parser.licenses,
+        exceptions: parser.exceptions,
+        options: options(&parsed),
+    })
+}
+
+/// The expression in disjunctive normal form: a list of options, each a list of licenses that
+/// all apply
+fn options(expression: &Expression) -> Vec<Vec<String>> {
+    let mut options: Vec<Vec<String>> = match expression {
+        Expression::License(license) => vec![vec![license.clone()]],
+        Expression::Any(alternatives) => alternatives.iter().flat_map(options).collect(),
+        Expression::All(terms) => terms.iter().fold(vec![Vec::new()], |combined, term| {
+            let term_options = options(term);
+            combined
+                .iter()
+                .flat_map(|left| {
+                    term_options.iter().map(move |right| {
+                        let mut option = left.clone();
+                        for license in right {
+                            if !option.contains(license) {
+                                option.push(license.clone());
+                            }
+                        }
+                        option
+                    })
+                })
+                .collect()
+        }),
+    };
+    let mut unique: Vec<Vec<String>> = Vec::new();
+    for option in options.drain(..) {
+        if !unique.contains(&option) {
+            unique.push(option);
+        }
+    }
+    unique
+}
+
+fn is_license_file(name: &str) -> bool {
+    let name = name.to_uppercase();
+    LICENSE_FILE_PREFIXES
+        .iter()
+        .any(|prefix| name.starts_with(prefix))
+}
+
+/// The license a file holds: the expression's license its name points at, as in
+/// `LICENSE-APACHE`, otherwise the license its text reads as
+fn file_license(path: &str, text: &str, licenses: &[String]) -> Option<String> {
+    let name = path.to_lowercase();
+    let name = ["license", "licence", "copying"]
+        .iter()
+        .find_map(|prefix| name.strip_prefix(prefix))
+        .unwrap_or(&name)
+        .trim_start_matches(['-', '_', '.']);
+    let name = [".md", ".txt", ".markdown"]
+        .iter()
+        .find_map(|extension| name.strip_suffix(extension))
+        .unwrap_or(name);
+    if !name.is_empty() {
+        let by_name = licenses.iter().find(|license| {
+            let family = license.split('-').next().unwrap_or_default().to_lowercase();
+            name.starts_with(&family)
+        });
+        if by_name.is_some() {
+            return by_name.cloned();
+        }
+    }
+
+    let detected = detect_license(text)?;
+    licenses
+        .iter()
+        .find(|license| license.starts_with(detected))
+        .cloned()
+        .or_else(|| Some(detected.to_string()))
+}
+
+/// Recognises a license by a phrase of its text
+fn detect_license(text: &str) -> Option<&'static str> {
+    let text = text
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase();
+    LICENSE_MARKERS
+        .iter()
+        .find(|(_, marker)| text.contains(marker))
+        .map(|(license, _)| *license)
+}
+
+/// Licenses of the expression no file holds, and files holding licenses it doesn't name
+fn license_notes(licenses: &[String], files: &[LicenseFile]) -> Vec<String> {
+    let mut notes = Vec::new();
+    if files.is_empty() {
+        notes.push("No license file was found in the package or its repository".to_string());
+        return notes;
+    }
+    let missing: Vec<&str> = licenses
+        .iter()
+        .filter(|license| {
+            !files
+                .iter()
+                .any(|file| file.license.as_ref() == Some(*license))
+        })
+        .map(String::as_str)
+        .collect();
+    if !missing.is_empty() {
+        notes.push(format!("No license file holds {}", missing.join(", ")));
+    }
+    for file in files {
+        if let Some(license) = &file.license {
+            if !licenses.is_empty() && !licenses.contains(license) {
+                notes.push(format!(
+                    "{} holds {}, which the license expression doesn't name",
+                    file.path, license
+                ));
+            }
+        }
+    }
+    notes
+}
+
+impl Tool for CrateLicenseTool {
+    fn name(&self) -> String {
+        "crate_license".to_string()
+    }
+
+    fn description(&self) -> String {
+        "Get the license of a crate version: its SPDX expression taken apart into licenses, \
+        exceptions and the options to comply with (MIT OR Apache-2.0 gives two), plus the \
+        text of the license files published with the crate, or found in its repository when \
+        the package has none. Notes licenses without a file and files the expression doesn't \
+        name."
+            .to_string()
+    }
+
+    fn input_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "crate_name": {
+                    "type": "string",
+                    "description": "Name of the crate to get the license of"
+                },
+                "version": {
+                    "type": "string",
+                    "description": "Optional version of the crate (defaults to latest)"
+                },
+                "include_text": {
+                    "type": "boolean",
+                    "description": "Whether to include the text of the license files (defaults to true)"
+                },
+                "registry": registry::registry_schema()
+            },
+            "required": ["crate_name"]
+        })
+    }
+
+    fn call(&self, input: Option<serde_json::Value>) -> Result<CallToolResponse> {
+        let args: CrateLicenseParams = error::parse_args(input)?;
+        config::current().ensure_crate_allowed(&args.crate_name)?;
+        let license = runtime::blocking(|| {
+            registry::in_registry(&args.crate_name, args.registry.as_deref(), || {
+                self.fetch_license(
+                    &args.crate_name,
+                    args.version.as_deref(),
+                    args.include_text.unwrap_or(true),
+                )
+            })
+        })?;
+
+        Ok(CallToolResponse {
+            content: vec![ToolResponseContent::Text {
+                text: serde_json::to_string_pretty(&license)?,
+            }],
+            is_error: None,
+            meta: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::fs;
+
+    fn strings(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_expression() -> Result<()> {
+        let expression = parse_expression("MIT OR Apache-2.0")?;
+        assert_eq!(expression.licenses(), strings(&["MIT", "Apache-2.0"]));
+        assert_eq!(
+            expression.options(),
+            &[strings(&["MIT"]), strings(&["Apache-2.0"])]
+        );
+
+        let expression = parse_expression("(MIT OR Apache-2.0) AND Unicode-3.0")?;
+        assert_eq!(
+            expression.options(),
+            &[
+                strings(&["MIT", "Unicode-3.0"]),
+                strings(&["Apache-2.0", "Unicode-3.0"])
+            ]
+        );
+
+        let expression = parse_expression("Apache-2.0 WITH LLVM-exception OR Apache-2.0 OR MIT")?;
+        assert_eq!(expression.licenses(), strings(&["Apache-2.0", "MIT"]));
+        assert_eq!(expression.exceptions(), strings(&["LLVM-exception"]));
+        assert_eq!(
+            expression.options()[0],
+            strings(&["Apache-2.0 WITH LLVM-exception"])
+        );
+
+        let expression = parse_expression("MIT/Apache-2.0")?;
+        assert_eq!(
+            expression.options(),
+            &[strings(&["MIT"]), strings(&["Apache-2.0"])],
+            "The legacy slash means OR"
+        );
+
+        assert_eq!(
+            parse_expression("MIT and BSD-3-Clause")?.options(),
+            &[strings(&["MIT", "BSD-3-Clause"])],
+            "Operators are read case-insensitively"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_invalid_expression() {
+        for expression in [
+            "(MIT OR Apache-2.0",
+            "MIT OR",
+            "MIT WITH",
+            "MIT Apache-2.0",
+            "",
+        ] {
+            assert!(
+                parse_expression(expression).is_err(),
+                "{:?} should not parse",
+                expression
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_crate_response() -> Result<()> {
+        let body = fs::read_to_string("test-data/crate_license/demo-crate.json")?;
+
+        let (version, license, repository) = parse_crate_response(&body, None)?;
+        assert_eq!(version, "1.1.0", "The pre-release isn't the latest version");
+        assert_eq!(license.as_deref(), Some("MIT OR Apache-2.0"));
+        assert_eq!(
+            repository.as_deref(),
+            Some("https://github.com/demo-rs/demo")
+        );
+
+        let (_, license, _) = parse_crate_response(&body, Some("0.9.0"))?;
+        assert_eq!(license, None, "0.9.0 only set license-file");
+
+        assert!(parse_crate_response(&body, Some("3.0.0")).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_file_license() -> Result<()> {
+        let mit = fs::read_to_string("test-data/crate_license/LICENSE-MIT")?;
+        let licenses = strings(&["MIT", "Apache-2.0"]);
+
+        assert!(is_license_file("LICENSE-APACHE"));
+        assert!(is_license_file("unlicense"));
+        assert!(!is_license_file("Cargo.toml"));
+
+        assert_eq!(
+            file_license("LICENSE-APACHE", "", &licenses).as_deref(),
+            Some("Apache-2.0")
+        );
+        assert_eq!(
+            file_license("LICENSE", &mit, &licenses).as_deref(),
+            Some("MIT"),
+            "Files without a license in their name are recognised by their text"
+        );
+        assert_eq!(
+            file_license("COPYING", &mit, &strings(&["Apache-2.0"])).as_deref(),
+            Some("MIT"),
+            "Licenses the expression doesn't name are still reported"
+        );
+        assert_eq!(
+            file_license("NOTICE", "Copyright 2025 Demo", &licenses),
+            None
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_license_notes() {
+        let file = |path: &str, license: Option<&str>| LicenseFile {
+            path: path.to_string(),
+            source: "package".to_string(),
+            url: String::new(),
+            license: license.map(str::to_string),
+            text: None,
+        };
+
+        assert_eq!(
+            license_notes(
+                &strings(&["MIT", "Apache-2.0"]),
+                &[file("LICENSE", Some("MIT")), file("COPYING", Some("GPL"))]
+            ),
+            vec![
+                "No license file holds Apache-2.0",
+                "COPYING holds GPL, which the license expression doesn't name",
+            ]
+        );
+        assert_eq!(
+            license_notes(&strings(&["MIT"]), &[]),
+            vec!["No license file was found in the package or its repository"]
+        );
+    }
+}
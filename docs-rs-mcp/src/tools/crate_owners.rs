@@ -0,0 +1,201 @@
+use anyhow::{Context, Result};
+use mcp_sdk::{
+    tools::Tool,
+    types::{CallToolResponse, ToolResponseContent},
+};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tracing::debug;
+
+use super::{crates_io, registry, runtime};
+use crate::{config, error};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CrateOwners {
+    crate_name: String,
+    users: Vec<Owner>,
+    teams: Vec<Owner>,
+}
+
+impl CrateOwners {
+    pub fn crate_name(&self) -> &str {
+        &self.crate_name
+    }
+
+    pub fn users(&self) -> &[Owner] {
+        &self.users
+    }
+
+    pub fn teams(&self) -> &[Owner] {
+        &self.teams
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Owner {
+    login: String,
+    name: Option<String>,
+    url: Option<String>,
+}
+
+impl Owner {
+    pub fn login(&self) -> &str {
+        &self.login
+    }
+
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    pub fn url(&self) -> Option<&str> {
+        self.url.as_deref()
+    }
+}
+
+/// Response body of the crates.io `/api/v1/crates/{name}/owners` endpoint
+#[derive(Debug, Deserialize)]
+struct OwnersResponse {
+    users: Vec<ApiOwner>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiOwner {
+    login: String,
+    kind: String,
+    name: Option<String>,
+    url: Option<String>,
+}
+
+impl From<ApiOwner> for Owner {
+    fn from(owner: ApiOwner) -> Self {
+        Self {
+            login: owner.login,
+            name: owner.name,
+            url: owner.url,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CrateNameParam {
+    crate_name: String,
+    registry: Option<String>,
+}
+
+pub struct CrateOwnersTool;
+
+impl CrateOwnersTool {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn parse_owners(&self, crate_name: &str, body: &str) -> Result<CrateOwners> {
+        let response: OwnersResponse =
+            serde_json::from_str(body).context("Failed to parse crates.io owners response")?;
+
+        let (teams, users): (Vec<ApiOwner>, Vec<ApiOwner>) = response
+            .users
+            .into_iter()
+            .partition(|owner| owner.kind == "team");
+
+        Ok(CrateOwners {
+            crate_name: crate_name.to_string(),
+            users: users.into_iter().map(Owner::from).collect(),
+            teams: teams.into_iter().map(Owner::from).collect(),
+        })
+    }
+
+    fn fetch_owners(&self, crate_name: &str) -> Result<CrateOwners> {
+        let url = format!(
+            "{}/api/v1/crates/{}/owners",
+            crates_io::crates_io_url(),
+            crate_name
+        );
+        debug!("Fetching crate owners from URL: {}", url);
+
+        let body = crates_io::fetch_blocking(&url)?;
+        self.parse_owners(crate_name, &body)
+    }
+}
+
+impl Default for CrateOwnersTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Tool for CrateOwnersTool {
+    fn name(&self) -> String {
+        "crate_owners".to_string()
+    }
+
+    fn description(&self) -> String {
+        "Get the owners of a crate from crates.io, split into individual users \
+        and teams (e.g. `github:tokio-rs:core`). Useful for judging who maintains a crate."
+            .to_string()
+    }
+
+    fn input_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "crate_name": {
+                    "type": "string",
+                    "description": "Name of the crate to get owners for"
+                },
+                "registry": registry::registry_schema()
+            },
+            "required": ["crate_name"]
+        })
+    }
+
+    fn call(&self, input: Option<serde_json::Value>) -> Result<CallToolResponse> {
+        let args: CrateNameParam = error::parse_args(input)?;
+        config::current().ensure_crate_allowed(&args.crate_name)?;
+        let owners = runtime::blocking(|| {
+            registry::in_registry(&args.crate_name, args.registry.as_deref(), || {
+                self.fetch_owners(&args.crate_name)
+            })
+        })?;
+
+        Ok(CallToolResponse {
+            content: vec![ToolResponseContent::Text {
+                text: serde_json::to_string_pretty(&owners)?,
+            }],
+            is_error: None,
+            meta: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::fs;
+
+    #[test]
+    fn test_parse_owners() -> Result<()> {
+        let body = fs::read_to_string("test-data/crate_owners/tokio-owners.json")?;
+        let owners = CrateOwnersTool::new().parse_owners("tokio", &body)?;
+
+        assert_eq!(owners.crate_name(), "tokio", "Wrong crate name");
+        let logins: Vec<&str> = owners.users().iter().map(Owner::login).collect();
+        assert_eq!(logins, vec!["carllerche", "Darksonn"], "Wrong user owners");
+        assert_eq!(owners.teams().len(), 1, "Should find exactly one team");
+        assert_eq!(
+            owners.teams()[0].login(),
+            "github:tokio-rs:core",
+            "Wrong team login"
+        );
+        assert_eq!(owners.teams()[0].name(), Some("Core"), "Wrong team name");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_owners_invalid_body() {
+        let result = CrateOwnersTool::new().parse_owners("tokio", "<html>not json</html>");
+        assert!(result.is_err(), "Non-JSON body should be rejected");
+    }
+}
@@ -0,0 +1,249 @@
+use anyhow::{anyhow, Context, Result};
+use mcp_sdk::{
+    tools::Tool,
+    types::{CallToolResponse, ToolResponseContent},
+};
+use scraper::Html;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tracing::{debug, warn};
+
+use super::{crates_io, http, markdown, registry, runtime, selectors, summary};
+use crate::config::{self, Source};
+use crate::error;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CrateReadme {
+    crate_name: String,
+    version: String,
+    /// Where the README was fetched from (`crates.io` or `docs.rs`)
+    source: String,
+    markdown: String,
+    /// URI of the full README, a resource, when `markdown` is only a summary
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    full_markdown: Option<String>,
+}
+
+impl CrateReadme {
+    pub fn crate_name(&self) -> &str {
+        &self.crate_name
+    }
+
+    pub fn version(&self) -> &str {
+        &self.version
+    }
+
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    pub fn markdown(&self) -> &str {
+        &self.markdown
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CrateReadmeParams {
+    crate_name: String,
+    version: Option<String>,
+    registry: Option<String>,
+}
+
+pub struct CrateReadmeTool;
+
+impl CrateReadmeTool {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn get_docs_rs_url() -> String {
+        config::current().sources().docs_rs_url().to_string()
+    }
+
+    /// Fetches the README from the configured sources in priority order, returning the first
+    /// one that succeeds.
+    pub(crate) fn fetch_readme(
+        &self,
+        crate_name: &str,
+        version: Option<&str>,
+    ) -> Result<CrateReadme> {
+        let version = match version {
+            Some(version) => version.to_string(),
+            None => crates_io::latest_version_blocking(crate_name).unwrap_or_else(|e| {
+                warn!(
+                    "Failed to resolve the latest version of {}: {}",
+                    crate_name, e
+                );
+                "latest".to_string()
+            }),
+        };
+
+        let mut last_error = None;
+        for source in config::current().sources().priority() {
+            let result = match source {
+                Source::CratesIo => self.fetch_from_crates_io(crate_name, &version),
+                Source::DocsRs => self.fetch_from_docs_rs(crate_name, &version),
+            };
+            match result {
+                Ok(markdown) => {
+                    return Ok(CrateReadme {
+                        crate_name: crate_name.to_string(),
+                        version,
+                        source: source.to_string(),
+                        markdown,
+                        full_markdown: None,
+                    });
+                }
+                Err(e) => {
+                    warn!("Failed to fetch README from {}: {}", source, e);
+                    last_error = Some(e);
+                }
+            }
+        }
+
+        Err(last_error.unwrap_or_else(|| anyhow!("No README sources are configured")))
+    }
+
+    fn fetch_from_crates_io(&self, crate_name: &str, version: &str) -> Result<String> {
+        let url = format!(
+            "{}/api/v1/crates/{}/{}/readme",
+            crates_io::crates_io_url(),
+            crate_name,
+            version
+        );
+        let html = crates_io::fetch_blocking(&url)?;
+        if html.trim().is_empty() {
+            return Err(anyhow!(
+                "crates.io returned an empty README for {}",
+                crate_name
+            ));
+        }
+        Ok(markdown::html_to_markdown(&html))
+    }
+
+    fn fetch_from_docs_rs(&self, crate_name: &str, version: &str) -> Result<String> {
+        let url = format!(
+            "{}/crate/{}/{}",
+            Self::get_docs_rs_url(),
+            crate_name,
+            version
+        );
+        debug!("Fetching README from docs.rs crate page: {}", url);
+        let html = runtime::block_on(async {
+            let response = http::shared()?
+                .get(&url)
+                .await
+                .context(format!("Failed to fetch URL: {}", url))?;
+            if !response.status().is_success() {
+                return Err(anyhow!(
+                    "Failed to fetch docs.rs page: {} - {}",
+                    response.status(),
+                    url
+                ));
+            }
+            Ok(response.text().await?)
+        })?;
+
+        self.parse_docs_rs_readme(&html)
+    }
+
+    /// Extracts the README rendered on a docs.rs crate page.
+    fn parse_docs_rs_readme(&self, html: &str) -> Result<String> {
+        let document = Html::parse_document(html);
+        let readme_selector = selectors::parse("#main", "crate page")?;
+
+        document
+            .select(&readme_selector)
+            .next()
+            .map(markdown::element_to_markdown)
+            .filter(|readme| !readme.is_empty())
+            .ok_or_else(|| anyhow!("Could not find a README on the docs.rs crate page"))
+    }
+}
+
+impl Default for CrateReadmeTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Tool for CrateReadmeTool {
+    fn name(&self) -> String {
+        "crate_readme".to_string()
+    }
+
+    fn description(&self) -> String {
+        "Get the README of a crate converted to Markdown, as rendered on crates.io \
+        or the docs.rs crate page. READMEs usually contain the quickstart \
+        examples that item-level documentation lacks."
+            .to_string()
+    }
+
+    fn input_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "crate_name": {
+                    "type": "string",
+                    "description": "Name of the crate to get the README for"
+                },
+                "version": {
+                    "type": "string",
+                    "description": "Optional version of the crate (defaults to the latest stable release)"
+                },
+                "registry": registry::registry_schema()
+            },
+            "required": ["crate_name"]
+        })
+    }
+
+    fn call(&self, input: Option<serde_json::Value>) -> Result<CallToolResponse> {
+        let args: CrateReadmeParams = error::parse_args(input)?;
+        config::current().ensure_crate_allowed(&args.crate_name)?;
+        let mut readme = runtime::blocking(|| {
+            registry::in_registry(&args.crate_name, args.registry.as_deref(), || {
+                self.fetch_readme(&args.crate_name, args.version.as_deref())
+            })
+        })?;
+        readme.full_markdown = summary::shorten(
+            &format!("README of {} {}", readme.crate_name, readme.version),
+            &mut readme.markdown,
+        );
+
+        Ok(CallToolResponse {
+            content: vec![ToolResponseContent::Text {
+                text: serde_json::to_string_pretty(&readme)?,
+            }],
+            is_error: None,
+            meta: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_docs_rs_readme() -> Result<()> {
+        let html = r#"<html><body><div class="package-details" id="main">
+            <h1>serde</h1><p>Serde is a framework for <em>ser</em>ializing and <em>de</em>serializing.</p>
+            <pre><code class="language-rust">#[derive(Serialize)]
+struct Point { x: i32 }</code></pre></div></body></html>"#;
+
+        let readme = CrateReadmeTool::new().parse_docs_rs_readme(html)?;
+        assert!(readme.starts_with("# serde"), "Heading should be kept");
+        assert!(
+            readme.contains("```rust\n#[derive(Serialize)]"),
+            "Code examples should become fenced code blocks"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_docs_rs_readme_missing() {
+        let result = CrateReadmeTool::new().parse_docs_rs_readme("<html><body></body></html>");
+        assert!(result.is_err(), "Pages without a README should be an error");
+    }
+}
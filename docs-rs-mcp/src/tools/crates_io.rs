@@ -0,0 +1,266 @@
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use tracing::debug;
+
+use super::http;
+#[cfg(feature = "native")]
+use super::runtime;
+use crate::config;
+use crate::error::DocsRsMcpError;
+
+/// Gets the crates.io URL from the server configuration.
+pub(crate) fn crates_io_url() -> String {
+    config::current().sources().crates_io_url().to_string()
+}
+
+/// Fetches a crates.io API URL and returns the response body.
+pub(crate) async fn fetch(url: &str) -> Result<String> {
+    debug!("Fetching crates.io URL: {}", url);
+    let response = http::shared()?
+        .get(url)
+        .await
+        .context(format!("Failed to fetch URL: {}", url))?;
+    let status = response.status();
+    if !status.is_success() {
+        return Err(
+            anyhow::Error::new(DocsRsMcpError::from_status(url, status.as_u16())).context(format!(
+                "Failed to fetch crates.io URL: {} - {}",
+                status, url
+            )),
+        );
+    }
+
+    response
+        .text()
+        .await
+        .context(format!("Failed to get text from response for URL: {}", url))
+}
+
+/// Like [`fetch`], blocking until the response arrives, for tools that do their lookups
+/// synchronously.
+#[cfg(feature = "native")]
+pub(crate) fn fetch_blocking(url: &str) -> Result<String> {
+    runtime::block_on(fetch(url))
+}
+
+/// Response body of the crates.io `/api/v1/crates/{name}` endpoint, reduced to what we use
+#[derive(Debug, Deserialize)]
+pub(crate) struct CrateResponse {
+    #[serde(rename = "crate")]
+    pub(crate) krate: CrateMetadata,
+    #[cfg(feature = "native")]
+    /// The most recent releases
+    #[serde(default)]
+    pub(crate) versions: Vec<ReleaseMetadata>,
+    /// The categories the crate is published under
+    #[cfg(feature = "native")]
+    #[serde(default)]
+    pub(crate) categories: Vec<CategoryMetadata>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct CrateMetadata {
+    max_version: String,
+    max_stable_version: Option<String>,
+    #[serde(default)]
+    pub(crate) repository: Option<String>,
+    #[cfg(feature = "native")]
+    #[serde(default)]
+    pub(crate) keywords: Vec<String>,
+}
+
+impl CrateMetadata {
+    /// The latest stable version, or the latest pre-release for crates without one
+    pub(crate) fn latest_version(self) -> String {
+        self.max_stable_version.unwrap_or(self.max_version)
+    }
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Deserialize)]
+pub(crate) struct CategoryMetadata {
+    /// Identifier to list the category's crates by, e.g. `network-programming`
+    pub(crate) slug: String,
+    /// Display name, e.g. `Network programming`
+    pub(crate) category: String,
+    pub(crate) description: String,
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Deserialize)]
+pub(crate) struct ReleaseMetadata {
+    pub(crate) num: String,
+    /// Size of the `.crate` file in bytes
+    #[serde(default)]
+    crate_size: Option<u64>,
+    /// SPDX license expression
+    #[serde(default)]
+    pub(crate) license: Option<String>,
+}
+
+/// Resolves the latest stable version of a crate, falling back to the latest pre-release.
+pub(crate) async fn latest_version(crate_name: &str) -> Result<String> {
+    let body = fetch(&format!("{}/api/v1/crates/{}", crates_io_url(), crate_name)).await?;
+    parse_latest_version(&body)
+}
+
+/// Like [`latest_version`], blocking until the response arrives.
+#[cfg(feature = "native")]
+pub(crate) fn latest_version_blocking(crate_name: &str) -> Result<String> {
+    runtime::block_on(latest_version(crate_name))
+}
+
+#[cfg(feature = "native")]
+/// The release `version` stands for, `latest` resolved as by [`latest_version`], and the size of
+/// its `.crate` file when crates.io lists it.
+pub(crate) async fn release_size(crate_name: &str, version: &str) -> Result<(String, Option<u64>)> {
+    let body = fetch(&format!("{}/api/v1/crates/{}", crates_io_url(), crate_name)).await?;
+    parse_release_size(&body, version)
+}
+
+/// The source repository a crate declares, if any.
+#[cfg(feature = "native")]
+pub(crate) fn repository(crate_name: &str) -> Result<Option<String>> {
+    let body = fetch_blocking(&format!("{}/api/v1/crates/{}", crates_io_url(), crate_name))?;
+    parse_repository(&body)
+}
+
+/// Response body of the crates.io `/api/v1/crates/{name}/versions` endpoint
+#[derive(Debug, Deserialize)]
+struct VersionsResponse {
+    versions: Vec<VersionMetadata>,
+}
+
+#[derive(Debug, Deserialize)]
+struct VersionMetadata {
+    num: String,
+    yanked: bool,
+}
+
+/// Lists the published versions of a crate that aren't yanked.
+pub(crate) async fn published_versions(crate_name: &str) -> Result<Vec<String>> {
+    let body = fetch(&format!(
+        "{}/api/v1/crates/{}/versions",
+        crates_io_url(),
+        crate_name
+    ))
+    .await?;
+    parse_published_versions(&body)
+}
+
+/// Parses a response body of the `/api/v1/crates/{name}` endpoint.
+pub(crate) fn parse_crate(body: &str) -> Result<CrateResponse> {
+    serde_json::from_str(body).context("Failed to parse crates.io crate response")
+}
+
+fn parse_latest_version(body: &str) -> Result<String> {
+    Ok(parse_crate(body)?.krate.latest_version())
+}
+
+#[cfg(feature = "native")]
+fn parse_release_size(body: &str, version: &str) -> Result<(String, Option<u64>)> {
+    let response = parse_crate(body)?;
+    let version = match version {
+        "latest" => response.krate.latest_version(),
+        version => version.to_string(),
+    };
+    let size = response
+        .versions
+        .iter()
+        .find(|release| release.num == version)
+        .and_then(|release| release.crate_size);
+    Ok((version, size))
+}
+
+#[cfg(feature = "native")]
+fn parse_repository(body: &str) -> Result<Option<String>> {
+    let response = parse_crate(body)?;
+    Ok(response.krate.repository.filter(|url| !url.is_empty()))
+}
+
+fn parse_published_versions(body: &str) -> Result<Vec<String>> {
+    let response: VersionsResponse =
+        serde_json::from_str(body).context("Failed to parse crates.io versions response")?;
+    Ok(response
+        .versions
+        .into_iter()
+        .filter(|version| !version.yanked)
+        .map(|version| version.num)
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_parse_latest_version() -> Result<()> {
+        let stable = r#"{"crate": {"max_version": "0.15.0-rc.3", "max_stable_version": "0.14.2"}}"#;
+        assert_eq!(
+            parse_latest_version(stable)?,
+            "0.14.2",
+            "Should prefer stable"
+        );
+
+        let prerelease_only =
+            r#"{"crate": {"max_version": "0.1.0-alpha.1", "max_stable_version": null}}"#;
+        assert_eq!(
+            parse_latest_version(prerelease_only)?,
+            "0.1.0-alpha.1",
+            "Should fall back to the newest pre-release"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_release_size() -> Result<()> {
+        let body = r#"{"crate": {"max_version": "0.60.0-rc.1", "max_stable_version": "0.59.0"},
+            "versions": [
+                {"num": "0.60.0-rc.1", "crate_size": 12500000},
+                {"num": "0.59.0", "crate_size": 11800000},
+                {"num": "0.1.0"}
+            ]}"#;
+        assert_eq!(
+            parse_release_size(body, "latest")?,
+            ("0.59.0".to_string(), Some(11_800_000))
+        );
+        assert_eq!(parse_release_size(body, "0.1.0")?.1, None);
+        assert_eq!(
+            parse_release_size(body, "0.2.0")?.1,
+            None,
+            "Older releases aren't listed"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_repository() -> Result<()> {
+        let body = r#"{"crate": {"max_version": "1.0.0", "max_stable_version": "1.0.0",
+            "repository": "https://github.com/acme/demo"}}"#;
+        assert_eq!(
+            parse_repository(body)?.as_deref(),
+            Some("https://github.com/acme/demo")
+        );
+
+        let undeclared = r#"{"crate": {"max_version": "1.0.0", "max_stable_version": "1.0.0"}}"#;
+        assert_eq!(parse_repository(undeclared)?, None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_published_versions() -> Result<()> {
+        let body = r#"{"versions": [
+            {"num": "0.15.0-rc.3", "yanked": false},
+            {"num": "0.14.3", "yanked": true},
+            {"num": "0.14.2", "yanked": false}
+        ], "meta": {"total": 3}}"#;
+        assert_eq!(
+            parse_published_versions(body)?,
+            vec!["0.15.0-rc.3", "0.14.2"],
+            "Yanked versions are left out"
+        );
+        Ok(())
+    }
+}
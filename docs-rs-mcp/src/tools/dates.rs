@@ -0,0 +1,175 @@
+//! Dates and times in machine format. Changelogs write release dates the way their authors
+//! like, e.g. `Jan 8th, 2025`, `8 January 2025` or `2025/01/08`, and services differ in the
+//! precision of their timestamps. Responses give them as RFC 3339 instead, so they can be
+//! compared, as text even, without knowing where they came from.
+
+const MONTHS: [&str; 12] = [
+    "january",
+    "february",
+    "march",
+    "april",
+    "may",
+    "june",
+    "july",
+    "august",
+    "september",
+    "october",
+    "november",
+    "december",
+];
+
+/// The first date written in `text`, as an RFC 3339 full-date, e.g. `2025-01-08` in
+/// `1.43.0 (Jan 8th, 2025)`. Takes ISO dates with `-` or `/`, and month names before or
+/// after the day.
+pub(crate) fn find_date(text: &str) -> Option<String> {
+    let words: Vec<&str> = text
+        .split(|c: char| c.is_whitespace() || matches!(c, '(' | ')' | '[' | ']' | ',' | '_'))
+        .filter(|word| !word.is_empty())
+        .collect();
+    (0..words.len()).find_map(|i| {
+        let ahead = |offset: usize| words.get(i + offset).copied().unwrap_or_default();
+        iso_date(words[i])
+            // `Jan 8th 2025`
+            .or_else(|| full_date(year(ahead(2))?, month(words[i])?, day(ahead(1))?))
+            // `8 January 2025`
+            .or_else(|| full_date(year(ahead(2))?, month(ahead(1))?, day(words[i])?))
+    })
+}
+
+/// `text` as an RFC 3339 timestamp to the second, e.g. `2024-01-05T10:20:30Z` for
+/// `2024-01-05T10:20:30.583811Z` or `2024-01-05 10:20:30 UTC`. A time without an offset is
+/// taken to be UTC.
+pub(crate) fn timestamp(text: &str) -> Option<String> {
+    let text = text.trim();
+    let date = iso_date(text.get(..10)?)?;
+    let rest = text[10..].strip_prefix(['T', 't', ' '])?;
+    let time = rest.get(..8)?;
+    let mut parts = time.split(':').map(|part| part.parse::<u32>().ok());
+    let (hours, minutes, seconds) = (parts.next()??, parts.next()??, parts.next()??);
+    if hours > 23 || minutes > 59 || seconds > 60 {
+        return None;
+    }
+
+    // Fractions of a second are dropped
+    let offset = rest[8..].trim_start_matches(|c: char| c == '.' || c.is_ascii_digit());
+    let offset = match offset.trim() {
+        "" | "Z" | "z" | "UTC" | "+00:00" | "-00:00" => "Z".to_string(),
+        offset => {
+            let sign = offset.chars().next().filter(|c| matches!(c, '+' | '-'))?;
+            let hhmm = offset[1..].replace(':', "");
+            if hhmm.len() != 4 || !hhmm.chars().all(|c| c.is_ascii_digit()) {
+                return None;
+            }
+            format!("{}{}:{}", sign, &hhmm[..2], &hhmm[2..])
+        }
+    };
+    Some(format!(
+        "{}T{:02}:{:02}:{:02}{}",
+        date, hours, minutes, seconds, offset
+    ))
+}
+
+/// `2025-01-08` or `2025/01/08`, which may be followed by a time
+fn iso_date(word: &str) -> Option<String> {
+    let date = word.get(..10)?;
+    let separator = date.chars().nth(4)?;
+    if !matches!(separator, '-' | '/') || date.chars().nth(7) != Some(separator) {
+        return None;
+    }
+    let mut parts = date.split(separator);
+    let (year, month, day) = (parts.next()?, parts.next()?, parts.next()?);
+    if year.len() != 4 || month.len() != 2 || day.len() != 2 {
+        return None;
+    }
+    full_date(year.parse().ok()?, month.parse().ok()?, day.parse().ok()?)
+}
+
+fn full_date(year: u32, month: u32, day: u32) -> Option<String> {
+    let leap = year.is_multiple_of(4) && (!year.is_multiple_of(100) || year.is_multiple_of(400));
+    let days = match month {
+        2 if leap => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        1..=12 => 31,
+        _ => return None,
+    };
+    (1..=days)
+        .contains(&day)
+        .then(|| format!("{:04}-{:02}-{:02}", year, month, day))
+}
+
+/// A four digit year
+fn year(word: &str) -> Option<u32> {
+    let word = word.trim_end_matches('.');
+    (word.len() == 4 && word.chars().all(|c| c.is_ascii_digit()))
+        .then(|| word.parse().ok())
+        .flatten()
+}
+
+/// A month name or its abbreviation, e.g. `Jan`, `Sept.` or `January`
+fn month(word: &str) -> Option<u32> {
+    let word = word.trim_end_matches('.').to_lowercase();
+    if word.len() < 3 || !word.chars().all(|c| c.is_ascii_alphabetic()) {
+        return None;
+    }
+    MONTHS
+        .iter()
+        .position(|name| name.starts_with(&word))
+        .map(|index| index as u32 + 1)
+}
+
+/// A day of the month, optionally with its ordinal suffix, e.g. `8th`
+fn day(word: &str) -> Option<u32> {
+    let digits = word
+        .trim_end_matches('.')
+        .trim_end_matches(|c: char| c.is_ascii_alphabetic());
+    if digits.is_empty() || digits.len() > 2 || !digits.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    digits.parse().ok().filter(|day| (1..=31).contains(day))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_find_date() {
+        let cases = [
+            ("1.43.0 (Jan 8th, 2025)", Some("2025-01-08")),
+            ("[0.3.1] - 2024-01-01", Some("2024-01-01")),
+            ("v2.0.0 - 2023/12/31", Some("2023-12-31")),
+            ("0.7.13 — 22 October 2024", Some("2024-10-22")),
+            ("1.2.0 (Sept. 3, 2024)", Some("2024-09-03")),
+            ("1.0.0 (Feb 29th, 2024)", Some("2024-02-29")),
+            ("1.0.0 (Feb 29th, 2023)", None),
+            ("1.41.1", None),
+            ("Unreleased", None),
+            ("2024.10.22", None),
+        ];
+        for (text, expected) in cases {
+            assert_eq!(find_date(text).as_deref(), expected, "{:?}", text);
+        }
+    }
+
+    #[test]
+    fn test_timestamp() {
+        let cases = [
+            ("2023-08-22T14:17:29.583811Z", Some("2023-08-22T14:17:29Z")),
+            ("2023-08-22 14:17:29 UTC", Some("2023-08-22T14:17:29Z")),
+            ("2023-08-22T14:17:29", Some("2023-08-22T14:17:29Z")),
+            ("2023-08-22T14:17:29+00:00", Some("2023-08-22T14:17:29Z")),
+            (
+                "2023-08-22T16:17:29.5+0200",
+                Some("2023-08-22T16:17:29+02:00"),
+            ),
+            ("2023-08-22", None),
+            ("Aug 22, 2023 14:17", None),
+            ("2023-08-22T25:17:29Z", None),
+        ];
+        for (text, expected) in cases {
+            assert_eq!(timestamp(text).as_deref(), expected, "{:?}", text);
+        }
+    }
+}
@@ -0,0 +1,392 @@
+//! Resolving `latest` to a concrete release. docs.rs defaults to the newest release that built,
+//! which is a pre-release while a major version is in beta, or an older release when the newest
+//! failed to build, so it doesn't necessarily match what `cargo add` installs. A [`Channel`]
+//! picks the release from crates.io instead, and the highest stable release is offered
+//! alongside a pre-release.
+//!
+//! A semver requirement such as `^1.0` or `>=0.28, <0.30` is resolved too, to the highest
+//! release in the registry index matching it, as Cargo would pick.
+
+use anyhow::{anyhow, Context, Result};
+use semver::{Version, VersionReq};
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+use super::sparse_index::{self, IndexVersion};
+use super::{crates_io, http};
+use crate::config;
+use crate::error::DocsRsMcpError;
+
+/// Releases `latest` may resolve to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Channel {
+    /// The highest stable release, as `cargo add` picks
+    Stable,
+    /// The highest pre-release, such as a beta or release candidate
+    Prerelease,
+    /// The highest release, stable or not
+    Any,
+}
+
+/// How `latest` or a version requirement was resolved
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ResolvedVersion {
+    /// The version asked for
+    requested: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    channel: Option<Channel>,
+    /// The release docs.rs serves for `latest`, when docs.rs could tell
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    docs_rs_default: Option<String>,
+    /// The release documented
+    version: String,
+    /// Whether the documented release is a pre-release
+    prerelease: bool,
+    /// Highest stable release, when it isn't the documented release
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    latest_stable: Option<String>,
+}
+
+impl ResolvedVersion {
+    pub fn requested(&self) -> &str {
+        &self.requested
+    }
+
+    pub fn version(&self) -> &str {
+        &self.version
+    }
+}
+
+/// Resolves `latest` (or no version) to a concrete release: the docs.rs default, or the highest
+/// release on `channel` when one is given. Returns `None` for concrete versions, and when
+/// docs.rs can't tell its default without a channel, in which case `latest` is left to docs.rs.
+/// Version requirements are resolved with [`resolve_requirement`].
+pub(crate) async fn resolve(
+    crate_name: &str,
+    requested: Option<&str>,
+    channel: Option<Channel>,
+) -> Result<Option<ResolvedVersion>> {
+    let requested = requested.unwrap_or("latest");
+    if requested != "latest" {
+        return resolve_requirement(crate_name, requested).await;
+    }
+
+    let docs_rs_default = match docs_rs_default(crate_name).await {
+        Ok(version) => Some(version),
+        Err(e) if channel.is_none() => {
+            debug!("Leaving latest to docs.rs for {}: {:#}", crate_name, e);
+            return Ok(None);
+        }
+        Err(e) => {
+            debug!(
+                "Failed to resolve the docs.rs default of {}: {:#}",
+                crate_name, e
+            );
+            None
+        }
+    };
+    // crates.io is only asked when the answer can change the outcome or warn about it
+    let versions = if channel.is_some() {
+        crates_io::published_versions(crate_name).await?
+    } else if docs_rs_default.as_deref().is_some_and(is_prerelease) {
+        crates_io::published_versions(crate_name)
+            .await
+            .unwrap_or_else(|e| {
+                debug!("Failed to list the versions of {}: {:#}", crate_name, e);
+                Vec::new()
+            })
+    } else {
+        Vec::new()
+    };
+
+    choose(crate_name, requested, channel, docs_rs_default, &versions).map(Some)
+}
+
+/// Resolves a semver requirement, such as `^1.0`, `1.*` or `>=0.28, <0.30`, to the highest
+/// release matching it that isn't yanked. Returns `None` for `latest` and exact versions, which
+/// docs.rs is asked for as they are, and when the registry index can't be read, leaving the
+/// requirement to docs.rs.
+pub(crate) async fn resolve_requirement(
+    crate_name: &str,
+    requested: &str,
+) -> Result<Option<ResolvedVersion>> {
+    let Some(req) = requirement(requested) else {
+        return Ok(None);
+    };
+    let versions = match sparse_index::versions(crate_name).await {
+        Ok(versions) => versions,
+        Err(e) => {
+            debug!(
+                "Leaving {} of {} to docs.rs: {:#}",
+                requested, crate_name, e
+            );
+            return Ok(None);
+        }
+    };
+    Ok(Some(choose_matching(
+        crate_name, requested, &req, &versions,
+    )?))
+}
+
+/// `requested` as a requirement, unless it is `latest` or an exact version. A bare `1.2` is
+/// taken as `^1.2`, as in `Cargo.toml`.
+fn requirement(requested: &str) -> Option<VersionReq> {
+    let requested = requested.trim();
+    if requested == "latest" || Version::parse(requested).is_ok() {
+        return None;
+    }
+    VersionReq::parse(requested).ok()
+}
+
+fn choose_matching(
+    crate_name: &str,
+    requested: &str,
+    req: &VersionReq,
+    versions: &[IndexVersion],
+) -> Result<ResolvedVersion, DocsRsMcpError> {
+    let version = sparse_index::select(versions, req)
+        .ok_or_else(|| {
+            DocsRsMcpError::NotFound(format!("release of {} matching {}", crate_name, requested))
+        })?
+        .version()
+        .to_string();
+    let latest_stable = sparse_index::select(versions, &VersionReq::STAR)
+        .map(IndexVersion::version)
+        .filter(|stable| *stable != version)
+        .map(str::to_string);
+
+    Ok(ResolvedVersion {
+        requested: requested.to_string(),
+        channel: None,
+        docs_rs_default: None,
+        prerelease: is_prerelease(&version),
+        latest_stable,
+        version,
+    })
+}
+
+fn choose(
+    crate_name: &str,
+    requested: &str,
+    channel: Option<Channel>,
+    docs_rs_default: Option<String>,
+    versions: &[String],
+) -> Result<ResolvedVersion> {
+    let highest = |stable: Option<bool>| {
+        versions
+            .iter()
+            .filter_map(|version| Version::parse(version).ok())
+            .filter(|version| stable.is_none_or(|stable| version.pre.is_empty() == stable))
+            .max()
+            .map(|version| version.to_string())
+    };
+    let latest_stable = highest(Some(true));
+
+    let version = match channel {
+        None => docs_rs_default
+            .clone()
+            .ok_or_else(|| anyhow!("docs.rs has no default release of {}", crate_name))?,
+        Some(Channel::Stable) => latest_stable
+            .clone()
+            .ok_or_else(|| anyhow!("{} has no stable release", crate_name))?,
+        Some(Channel::Prerelease) => {
+            highest(Some(false)).ok_or_else(|| anyhow!("{} has no pre-release", crate_name))?
+        }
+        Some(Channel::Any) => {
+            highest(None).ok_or_else(|| anyhow!("{} has no published release", crate_name))?
+        }
+    };
+
+    Ok(ResolvedVersion {
+        requested: requested.to_string(),
+        channel,
+        docs_rs_default,
+        prerelease: is_prerelease(&version),
+        latest_stable: latest_stable.filter(|stable| *stable != version),
+        version,
+    })
+}
+
+fn is_prerelease(version: &str) -> bool {
+    Version::parse(version).is_ok_and(|version| !version.pre.is_empty())
+}
+
+/// Body of docs.rs' `/crate/{name}/{version}/status.json`, reduced to what we use
+#[derive(Debug, Deserialize)]
+struct DocsStatus {
+    version: String,
+}
+
+/// Asks docs.rs which release it serves for `latest`.
+async fn docs_rs_default(crate_name: &str) -> Result<String> {
+    let url = format!(
+        "{}/crate/{}/latest/status.json",
+        config::current().sources().docs_rs_url(),
+        crate_name
+    );
+    let response = http::shared()?
+        .get(&url)
+        .await
+        .context(format!("Failed to fetch URL: {}", url))?;
+    if !response.status().is_success() {
+        return Err(anyhow!(
+            "Failed to fetch docs.rs status: {} - {}",
+            response.status(),
+            url
+        ));
+    }
+
+    let body = response
+        .text()
+        .await
+        .context(format!("Failed to get text from response for URL: {}", url))?;
+    parse_status(&body)
+}
+
+fn parse_status(body: &str) -> Result<String> {
+    let status: DocsStatus =
+        serde_json::from_str(body).context("Failed to parse docs.rs status response")?;
+    Ok(status.version)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn versions(versions: &[&str]) -> Vec<String> {
+        versions.iter().map(|version| version.to_string()).collect()
+    }
+
+    #[test]
+    fn test_choose_matching() -> Result<()> {
+        let versions = sparse_index::parse(&std::fs::read_to_string(
+            "test-data/dependency_tree/index/de/mo/demo-core",
+        )?)?;
+        let resolve = |requested: &str| {
+            let req = requirement(requested).expect("a requirement");
+            choose_matching("demo-core", requested, &req, &versions)
+        };
+
+        let resolved = resolve("^0.3")?;
+        assert_eq!(resolved.version(), "0.3.0", "The yanked 0.3.1 isn't picked");
+        assert_eq!(resolved.requested, "^0.3");
+        assert_eq!(resolved.latest_stable.as_deref(), Some("0.4.0"));
+
+        let resolved = resolve(">=0.3, <0.5")?;
+        assert_eq!(resolved.version(), "0.4.0");
+        assert_eq!(resolved.latest_stable, None);
+
+        assert_eq!(resolve("0.*")?.version(), "0.4.0");
+        assert!(matches!(
+            resolve("^1.0"),
+            Err(DocsRsMcpError::NotFound(what)) if what == "release of demo-core matching ^1.0"
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_requirement() {
+        assert_eq!(requirement("1.2"), Some(VersionReq::parse("^1.2").unwrap()));
+        assert!(requirement(">=0.28, <0.30").is_some());
+        assert_eq!(requirement("latest"), None);
+        assert_eq!(
+            requirement("1.43.0"),
+            None,
+            "Exact versions are used as they are"
+        );
+        assert_eq!(requirement("nightly"), None);
+    }
+
+    #[test]
+    fn test_parse_status() -> Result<()> {
+        assert_eq!(
+            parse_status(r#"{"doc_status": true, "version": "1.0.217"}"#)?,
+            "1.0.217"
+        );
+        assert!(parse_status("<html>Not Found</html>").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_choose_docs_rs_default() -> Result<()> {
+        let published = versions(&["0.15.0-rc.3", "0.15.0-rc.2", "0.14.2", "0.14.10"]);
+        let prerelease_default = choose(
+            "demo",
+            "latest",
+            None,
+            Some("0.15.0-rc.3".to_string()),
+            &published,
+        )?;
+        assert_eq!(prerelease_default.version(), "0.15.0-rc.3");
+        assert!(prerelease_default.prerelease);
+        assert_eq!(
+            prerelease_default.latest_stable.as_deref(),
+            Some("0.14.10"),
+            "The stable release is offered, compared as versions rather than strings"
+        );
+
+        let current = choose("demo", "latest", None, Some("1.5.1".to_string()), &[])?;
+        assert_eq!(current.version(), "1.5.1");
+        assert!(!current.prerelease);
+        assert_eq!(current.latest_stable, None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_choose_channel() -> Result<()> {
+        let published = versions(&["0.15.0-rc.3", "0.15.0-rc.10", "0.14.2", "0.14.1"]);
+        let default = || Some("0.14.1".to_string());
+
+        let stable = choose(
+            "demo",
+            "latest",
+            Some(Channel::Stable),
+            default(),
+            &published,
+        )?;
+        assert_eq!(
+            stable.version(),
+            "0.14.2",
+            "A newer stable release wins over a stale default"
+        );
+        assert_eq!(stable.latest_stable, None);
+
+        let prerelease = choose(
+            "demo",
+            "latest",
+            Some(Channel::Prerelease),
+            default(),
+            &published,
+        )?;
+        assert_eq!(prerelease.version(), "0.15.0-rc.10");
+        assert!(prerelease.prerelease);
+        assert_eq!(prerelease.latest_stable.as_deref(), Some("0.14.2"));
+
+        let any = choose("demo", "latest", Some(Channel::Any), None, &published)?;
+        assert_eq!(any.version(), "0.15.0-rc.10");
+
+        let prerelease_only = versions(&["0.1.0-alpha.1"]);
+        assert!(
+            choose(
+                "demo",
+                "latest",
+                Some(Channel::Stable),
+                None,
+                &prerelease_only
+            )
+            .is_err(),
+            "A crate without stable releases can't be resolved on the stable channel"
+        );
+        assert!(choose(
+            "demo",
+            "latest",
+            Some(Channel::Prerelease),
+            None,
+            &versions(&["1.0.0"])
+        )
+        .is_err());
+        Ok(())
+    }
+}
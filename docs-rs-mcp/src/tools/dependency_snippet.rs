@@ -0,0 +1,974 @@
+use std::collections::BTreeSet;
+
+use anyhow::{anyhow, Context, Result};
+use mcp_sdk::{
+    tools::Tool,
+    types::{CallToolResponse, ToolResponseContent},
+};
+use scraper::Html;
+use semver::VersionReq;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tracing::{debug, warn};
+
+use super::attribute_macro_docs::{parse_examples, Example};
+use super::dependency_tree::enabled_features;
+use super::example_dependencies::{self, ExampleDependency};
+use super::get_struct_docs::{HtmlFetcher, HttpHtmlFetcher};
+use super::sparse_index::IndexVersion;
+use super::{crates_io, docs_root, registry, runtime, selectors, sparse_index, SuggestImportsTool};
+use crate::{config, error};
+
+const DEFAULT_MAX_CRATES: usize = 2;
+const MAX_CRATES: usize = 5;
+/// Crates read from each crates.io search, best matches first
+const SEARCH_LIMIT: usize = 20;
+/// Keywords the whole task's search leaves uncovered that are searched for on their own
+const MAX_KEYWORD_SEARCHES: usize = 4;
+/// Crates downloaded less than this in the last 90 days are passed over as abandoned or
+/// squatted
+const MIN_RECENT_DOWNLOADS: u64 = 10_000;
+/// Paths of an example that are looked up for the features they need
+const MAX_IMPORTS: usize = 6;
+
+/// Words of a task description that say nothing about the crates it needs
+const STOP_WORDS: [&str; 58] = [
+    "a", "an", "and", "any", "app", "as", "at", "be", "build", "by", "can", "code", "crate",
+    "crates", "create", "do", "each", "for", "from", "get", "how", "i", "if", "in", "into", "is",
+    "it", "its", "library", "make", "me", "my", "need", "of", "on", "or", "our", "program", "rust",
+    "simple", "so", "some", "that", "the", "then", "this", "to", "tool", "up", "use", "using",
+    "via", "want", "way", "we", "with", "without", "you",
+];
+
+/// The crates, features, imports and starting code for a task
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DependencySnippet {
+    task: String,
+    /// Words of the task the crates were picked by
+    keywords: Vec<String>,
+    dependencies: Vec<SnippetDependency>,
+    /// `[dependencies]` section to add to Cargo.toml
+    cargo_toml: String,
+    /// `use` statements of the snippet
+    imports: Vec<String>,
+    /// Skeleton adapted from the examples in the crates' documentation
+    snippet: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    notes: Vec<String>,
+}
+
+impl DependencySnippet {
+    pub fn dependencies(&self) -> &[SnippetDependency] {
+        &self.dependencies
+    }
+
+    pub fn cargo_toml(&self) -> &str {
+        &self.cargo_toml
+    }
+
+    pub fn imports(&self) -> &[String] {
+        &self.imports
+    }
+
+    pub fn snippet(&self) -> &str {
+        &self.snippet
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SnippetDependency {
+    crate_name: String,
+    version: String,
+    description: Option<String>,
+    /// Downloads in the last 90 days
+    recent_downloads: Option<u64>,
+    /// Keywords of the task the crate's name or description covers
+    matched_keywords: Vec<String>,
+    /// Features to enable on top of the default ones, for the task's keywords and the
+    /// imports of the snippet
+    features: Vec<String>,
+    doc_link: String,
+    /// Other crates the crate's example uses, which the snippet needs as well
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    example_dependencies: Vec<ExampleDependency>,
+}
+
+impl SnippetDependency {
+    pub fn crate_name(&self) -> &str {
+        &self.crate_name
+    }
+
+    pub fn features(&self) -> &[String] {
+        &self.features
+    }
+
+    pub fn example_dependencies(&self) -> &[ExampleDependency] {
+        &self.example_dependencies
+    }
+}
+
+/// Response body of the crates.io `/api/v1/crates?q={query}` endpoint, reduced to what we use
+#[derive(Debug, Deserialize)]
+struct SearchResponse {
+    crates: Vec<ListedCrate>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct ListedCrate {
+    name: String,
+    description: Option<String>,
+    recent_downloads: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct DependencySnippetParams {
+    task: String,
+    crates: Option<Vec<String>>,
+    max_crates: Option<usize>,
+    registry: Option<String>,
+}
+
+pub struct DependencySnippetTool {
+    html_fetcher: Box<dyn HtmlFetcher>,
+}
+
+impl DependencySnippetTool {
+    pub fn new() -> Self {
+        Self {
+            html_fetcher: Box::new(HttpHtmlFetcher::new()),
+        }
+    }
+
+    fn dependency_snippet(
+        &self,
+        task: &str,
+        crates: Option<&[String]>,
+        max_crates: usize,
+    ) -> Result<DependencySnippet> {
+        let keywords = keywords(task);
+        let chosen = match crates {
+            Some(crates) => crates
+                .iter()
+                .map(|name| {
+                    let krate = ListedCrate {
+                        name: name.clone(),
+                        description: None,
+                        recent_downloads: None,
+                    };
+                    let matched = matched_keywords(&krate, &keywords);
+                    (krate, matched)
+                })
+                .collect(),
+            None => {
+                if keywords.is_empty() {
+                    return Err(anyhow!(
+                        "The task names nothing to search crates for; describe what the code \
+                        should do or pass crates"
+                    ));
+                }
+                let listed = self.search(&keywords)?;
+                choose_crates(&listed, &keywords, max_crates)
+            }
+        };
+        if chosen.is_empty() {
+            return Err(anyhow!(
+                "No established crate matches {}",
+                keywords.join(", ")
+            ));
+        }
+
+        let mut notes = Vec::new();
+        let mut planned = Vec::new();
+        for (krate, matched) in chosen {
+            match self.plan_crate(&krate, matched, &keywords, &mut notes) {
+                Ok(plan) => planned.push(plan),
+                Err(e) => {
+                    warn!("Failed to plan dependency {}: {:#}", krate.name, e);
+                    notes.push(format!("Left out {}: {:#}", krate.name, e));
+                }
+            }
+        }
+        if planned.is_empty() {
+            return Err(anyhow!("None of the crates for the task could be read"));
+        }
+        Ok(assemble(task, keywords, planned, notes))
+    }
+
+    /// Crates matching the whole task, followed by crates for the keywords those leave
+    /// uncovered
+    fn search(&self, keywords: &[String]) -> Result<Vec<ListedCrate>> {
+        let mut listed = self.search_crates(&keywords.join(" "))?;
+        let covered: BTreeSet<String> = listed
+            .iter()
+            .filter(|krate| established(krate))
+            .flat_map(|krate| matched_keywords(krate, keywords))
+            .collect();
+
+        for keyword in keywords
+            .iter()
+            .filter(|keyword| !covered.contains(*keyword))
+            .take(MAX_KEYWORD_SEARCHES)
+        {
+            for krate in self.search_crates(keyword)? {
+                if !listed.iter().any(|listed| listed.name == krate.name) {
+                    listed.push(krate);
+                }
+            }
+        }
+        listed.retain(|krate| config::current().ensure_crate_allowed(&krate.name).is_ok());
+        Ok(listed)
+    }
+
+    fn search_crates(&self, query: &str) -> Result<Vec<ListedCrate>> {
+        let url = format!(
+            "{}/api/v1/crates?q={}&per_page={}",
+            crates_io::crates_io_url(),
+            url::form_urlencoded::byte_serialize(query.as_bytes()).collect::<String>(),
+            SEARCH_LIMIT
+        );
+        debug!("Searching crates from URL: {}", url);
+        parse_search(&crates_io::fetch_blocking(&url)?)
+    }
+
+    /// Version, features and documentation example of a crate picked for the task
+    fn plan_crate(
+        &self,
+        krate: &ListedCrate,
+        matched_keywords: Vec<String>,
+        keywords: &[String],
+        notes: &mut Vec<String>,
+    ) -> Result<(SnippetDependency, Option<String>)> {
+        let versions = sparse_index::fetch(&krate.name)?;
+        let version = sparse_index::select(&versions, &VersionReq::STAR)
+            .ok_or_else(|| anyhow!("Crate {} has no release to depend on", krate.name))?;
+        let mut features = keyword_features(version, keywords);
+
+        let root_url = docs_root::crate_root_blocking(&krate.name, version.version())?;
+        let crate_ident = docs_root::lib_name(&root_url).to_string();
+        let examples = self
+            .html_fetcher
+            .fetch_html_blocking(&root_url)
+            .and_then(|html| parse_root_examples(&html))
+            .unwrap_or_else(|e| {
+                warn!("Failed to read the docs of {}: {:#}", krate.name, e);
+                Vec::new()
+            });
+        let example = pick_example(&examples, &crate_ident, keywords);
+
+        match example {
+            Some(example) => {
+                let suggest_imports = SuggestImportsTool::new();
+                for path in crate_paths(example.code(), &crate_ident) {
+                    match suggest_imports.suggest_imports(
+                        &krate.name,
+                        &path,
+                        Some(version.version()),
+                    ) {
+                        Ok(suggestions) => {
+                            let suggestions = suggestions.suggestions();
+                            if let Some(suggestion) = suggestions
+                                .iter()
+                                .find(|suggestion| suggestion.path() == path)
+                                .or(suggestions.first())
+                            {
+                                features.extend(suggestion.required_features().iter().cloned());
+                            }
+                        }
+                        Err(e) => debug!("No import found for {}: {:#}", path, e),
+                    }
+                }
+            }
+            None => notes.push(format!(
+                "The {} docs have no example to start from; see {}",
+                krate.name, root_url
+            )),
+        }
+
+        let dependency = SnippetDependency {
+            crate_name: krate.name.clone(),
+            version: version.version().to_string(),
+            description: krate
+                .description
+                .as_ref()
+                .map(|description| description.trim().replace('\n', " ")),
+            recent_downloads: krate.recent_downloads,
+            matched_keywords,
+            features: extra_features(version, features),
+            doc_link: root_url,
+            example_dependencies: example.map_or_else(Vec::new, |example| {
+                example_dependencies::resolve(
+                    &example_dependencies::imported_crates(example.code(), &crate_ident),
+                    version.deps(),
+                )
+            }),
+        };
+        Ok((
+            dependency,
+            example.map(|example| example.code().to_string()),
+        ))
+    }
+}
+
+impl Default for DependencySnippetTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn parse_search(body: &str) -> Result<Vec<ListedCrate>> {
+    let response: SearchResponse =
+        serde_json::from_str(body).context("Failed to parse crates.io search response")?;
+    Ok(response.crates)
+}
+
+/// Words of the task worth searching for, lowercased and in order
+fn keywords(task: &str) -> Vec<String> {
+    let mut keywords: Vec<String> = Vec::new();
+    for word in task.split(|c: char| !(c.is_alphanumeric() || c == '-' || c == '_')) {
+        let word = word.trim_matches(['-', '_']).to_lowercase();
+        if word.len() < 2 || STOP_WORDS.contains(&word.as_str()) || keywords.contains(&word) {
+            continue;
+        }
+        keywords.push(word);
+    }
+    keywords
+}
+
+/// Whether `word` is `keyword`, also when one is the plural of the other
+fn same_word(word: &str, keyword: &str) -> bool {
+    word == keyword
+        || word.strip_suffix('s') == Some(keyword)
+        || keyword.strip_suffix('s') == Some(word)
+}
+
+/// Keywords the crate's name or description mentions
+fn matched_keywords(krate: &ListedCrate, keywords: &[String]) -> Vec<String> {
+    let description = krate.description.as_deref().unwrap_or_default();
+    let words: Vec<String> = krate
+        .name
+        .split(['-', '_'])
+        .chain(description.split(|c: char| !c.is_alphanumeric()))
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect();
+    keywords
+        .iter()
+        .filter(|keyword| words.iter().any(|word| same_word(word, keyword)))
+        .cloned()
+        .collect()
+}
+
+/// Crates with too few recent downloads aren't suggested; registries that don't count
+/// downloads are trusted.
+fn established(krate: &ListedCrate) -> bool {
+    krate
+        .recent_downloads
+        .is_none_or(|downloads| downloads >= MIN_RECENT_DOWNLOADS)
+}
+
+/// Picks the crate covering the most keywords not yet covered, earlier search results
+/// winning ties, until every keyword is covered. Alternatives to a picked crate add nothing
+/// and are left out.
+fn choose_crates(
+    listed: &[ListedCrate],
+    keywords: &[String],
+    max_crates: usize,
+) -> Vec<(ListedCrate, Vec<String>)> {
+    let candidates: Vec<(&ListedCrate, Vec<String>)> = listed
+        .iter()
+        .filter(|krate| established(krate))
+        .map(|krate| (krate, matched_keywords(krate, keywords)))
+        .collect();
+
+    let mut covered: BTreeSet<&str> = BTreeSet::new();
+    let mut chosen: Vec<(ListedCrate, Vec<String>)> = Vec::new();
+    while chosen.len() < max_crates {
+        let mut best: Option<(usize, &(&ListedCrate, Vec<String>))> = None;
+        for candidate in &candidates {
+            let new = candidate
+                .1
+                .iter()
+                .filter(|keyword| !covered.contains(keyword.as_str()))
+                .count();
+            if new > best.map_or(0, |(count, _)| count) {
+                best = Some((new, candidate));
+            }
+        }
+        let Some((_, (krate, matched))) = best else {
+            break;
+        };
+        covered.extend(matched.iter().map(String::as_str));
+        chosen.push(((*krate).clone(), matched.clone()));
+    }
+    chosen
+}
+
+/// Features named like a keyword of the task, e.g. `json` or `derive`
+fn keyword_features(version: &IndexVersion, keywords: &[String]) -> BTreeSet<String> {
+    version
+        .features()
+        .keys()
+        .filter(|feature| **feature != "default")
+        .filter(|feature| keywords.iter().any(|keyword| same_word(feature, keyword)))
+        .map(|feature| feature.to_string())
+        .collect()
+}
+
+/// The wanted features the crate has and doesn't enable by default
+fn extra_features(version: &IndexVersion, wanted: BTreeSet<String>) -> Vec<String> {
+    let table = version.features();
+    let defaults = enabled_features(version, &BTreeSet::from(["default".to_string()]));
+    wanted
+        .into_iter()
+        .filter(|feature| !defaults.contains(feature))
+        .filter(|feature| {
+            table.contains_key(feature.as_str())
+                || version
+                    .deps()
+                    .iter()
+                    .any(|dep| dep.optional() && dep.name() == feature)
+        })
+        .collect()
+}
+
+/// Code examples of the crate root's documentation
+fn parse_root_examples(html: &str) -> Result<Vec<Example>> {
+    let document = Html::parse_document(html);
+    let docblock_selector =
+        selectors::parse("#main-content .toggle.top-doc .docblock", "crate root page")?;
+    Ok(document
+        .select(&docblock_selector)
+        .next()
+        .map(parse_examples)
+        .unwrap_or_default())
+}
+
+/// The example using the crate that mentions the most keywords, the first one on ties
+fn pick_example<'a>(
+    examples: &'a [Example],
+    crate_ident: &str,
+    keywords: &[String],
+) -> Option<&'a Example> {
+    let prefix = format!("{}::", crate_ident);
+    let mut best: Option<(usize, &Example)> = None;
+    for example in examples
+        .iter()
+        .filter(|example| example.code().contains(&prefix))
+    {
+        let text = format!(
+            "{} {}",
+            example.section().unwrap_or_default(),
+            example.code()
+        )
+        .to_lowercase();
+        let score = keywords
+            .iter()
+            .filter(|keyword| text.contains(keyword.as_str()))
+            .count();
+        if best.is_none_or(|(best_score, _)| score > best_score) {
+            best = Some((score, example));
+        }
+    }
+    best.map(|(_, example)| example)
+}
+
+/// Paths into the crate an example names, in order: the items it imports, with `{...}`
+/// groups expanded, then paths written out in the code such as `#[tokio::main]`. Glob
+/// imports are skipped.
+fn crate_paths(code: &str, crate_ident: &str) -> Vec<String> {
+    let prefix = format!("{}::", crate_ident);
+    let (imports, body) = split_imports(code);
+
+    let mut paths: Vec<String> = Vec::new();
+    for import in &imports {
+        let tree = import
+            .trim_start_matches("pub ")
+            .trim_start_matches("use ")
+            .trim_end_matches(';')
+            .trim()
+            .trim_start_matches("::");
+        if tree.starts_with(&prefix) {
+            paths.extend(expand_use(tree));
+        }
+    }
+
+    let mut rest = body.as_str();
+    while let Some(start) = rest.find(&prefix) {
+        let preceded_by_ident = rest[..start]
+            .chars()
+            .next_back()
+            .is_some_and(|c| c.is_alphanumeric() || c == '_' || c == ':');
+        let path: String = rest[start..]
+            .chars()
+            .take_while(|c| c.is_alphanumeric() || *c == '_' || *c == ':')
+            .collect();
+        rest = &rest[start + path.len()..];
+        let path = path.trim_end_matches(':');
+        if !preceded_by_ident && path.len() > prefix.len() {
+            paths.push(path.to_string());
+        }
+    }
+
+    let mut unique: Vec<String> = Vec::new();
+    for path in paths {
+        if !path.ends_with('*') && !unique.contains(&path) {
+            unique.push(path);
+        }
+    }
+    unique.truncate(MAX_IMPORTS);
+    unique
+}
+
+/// Paths a `use` tree imports: `a::{B, c::D as E, self}` gives `a::B`, `a::c::D` and `a`.
+/// Nested groups are left out.
+fn expand_use(tree: &str) -> Vec<String> {
+    let strip_alias = |path: &str| {
+        path.split(" as ")
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .to_string()
+    };
+    let Some((prefix, group)) = tree.split_once('{') else {
+        return vec![strip_alias(tree)];
+    };
+    let prefix = prefix.trim().trim_end_matches("::");
+    let group = group.trim_end().strip_suffix('}').unwrap_or(group);
+
+    let mut items = Vec::new();
+    let (mut depth, mut start) = (0, 0);
+    for (i, c) in group.char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' => depth -= 1,
+            ',' if depth == 0 => {
+                items.push(&group[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    items.push(&group[start..]);
+
+    items
+        .into_iter()
+        .map(str::trim)
+        .filter(|item| !item.is_empty() && !item.contains('{'))
+        .map(|item| match item {
+            "self" => prefix.to_string(),
+            item => format!("{}::{}", prefix, strip_alias(item)),
+        })
+        .collect()
+}
+
+/// Splits an example into its top-level `use` statements and the code after them
+fn split_imports(code: &str) -> (Vec<String>, String) {
+    let mut imports = Vec::new();
+    let mut body = Vec::new();
+    let mut statement: Option<String> = None;
+
+    for line in code.lines() {
+        if let Some(open) = statement.as_mut() {
+            open.push(' ');
+            open.push_str(line.trim());
+        } else if line.starts_with("use ") || line.starts_with("pub use ") {
+            statement = Some(line.trim().to_string());
+        } else {
+            body.push(line);
+            continue;
+        }
+        if line.trim_end().ends_with(';') {
+            imports.extend(statement.take());
+        }
+    }
+    imports.extend(statement);
+
+    let body = body.join("\n");
+    (imports, body.trim_matches('\n').to_string())
+}
+
+fn cargo_line(dependency: &SnippetDependency) -> String {
+    if dependency.features.is_empty() {
+        return format!("{} = \"{}\"", dependency.crate_name, dependency.version);
+    }
+    format!(
+        "{} = {{ version = \"{}\", features = [{}] }}",
+        dependency.crate_name,
+        dependency.version,
+        dependency
+            .features
+            .iter()
+            .map(|feature| format!("\"{}\"", feature))
+            .collect::<Vec<_>>()
+            .join(", ")
+    )
+}
+
+/// Puts the planned crates together: the Cargo.toml section, the imports of every example
+/// hoisted to the top, then each example's code.
+fn assemble(
+    task: &str,
+    keywords: Vec<String>,
+    planned: Vec<(SnippetDependency, Option<String>)>,
+    mut notes: Vec<String>,
+) -> DependencySnippet {
+    let mut cargo_toml = String::from("[dependencies]\n");
+    let mut imports: Vec<String> = Vec::new();
+    let mut bodies = Vec::new();
+    for (dependency, example) in &planned {
+        cargo_toml.push_str(&cargo_line(dependency));
+        cargo_toml.push('\n');
+        if let Some(code) = example {
+            let (example_imports, body) = split_imports(code);
+            for import in example_imports {
+                if !imports.contains(&import) {
+                    imports.push(import);
+                }
+            }
+            bodies.push(format!(
+                "// Adapted from the {} docs\n{}",
+                dependency.crate_name, body
+            ));
+        }
+    }
+
+    // Crates the examples use besides the picked ones, once each
+    let mut added: Vec<&str> = planned
+        .iter()
+        .map(|(dependency, _)| dependency.crate_name.as_str())
+        .collect();
+    for (dependency, _) in &planned {
+        for used in &dependency.example_dependencies {
+            if added.contains(&used.crate_name()) {
+                continue;
+            }
+            added.push(used.crate_name());
+            match used.version() {
+                Some(_) => {
+                    cargo_toml.push_str(&used.cargo_line());
+                    cargo_toml.push('\n');
+                }
+                None => notes.push(format!(
+                    "The {} example also uses `{}`, which {} doesn't depend on; add the crate \
+                     it names",
+                    dependency.crate_name,
+                    used.crate_name(),
+                    dependency.crate_name
+                )),
+            }
+        }
+    }
+
+    let uncovered: Vec<&str> = keywords
+        .iter()
+        .filter(|keyword| {
+            !planned
+                .iter()
+                .any(|(dependency, _)| dependency.matched_keywords.contains(keyword))
+        })
+        .map(String::as_str)
+        .collect();
+    if !uncovered.is_empty() {
+        notes.push(format!(
+            "No picked crate mentions {}; the standard library may cover it",
+            uncovered.join(", ")
+        ));
+    }
+    if bodies
+        .iter()
+        .filter(|body| body.contains("fn main"))
+        .count()
+        > 1
+    {
+        notes.push(
+            "The examples each define main; merge them into one before compiling".to_string(),
+        );
+    }
+
+    let mut snippet = imports.join("\n");
+    for body in &bodies {
+        if !snippet.is_empty() {
+            snippet.push_str("\n\n");
+        }
+        snippet.push_str(body);
+    }
+
+    DependencySnippet {
+        task: task.to_string(),
+        keywords,
+        dependencies: planned
+            .into_iter()
+            .map(|(dependency, _)| dependency)
+            .collect(),
+        cargo_toml,
+        imports,
+        snippet,
+        notes,
+    }
+}
+
+impl Tool for DependencySnippetTool {
+    fn name(&self) -> String {
+        "dependency_snippet".to_string()
+    }
+
+    fn description(&self) -> String {
+        "Given a short description of a task, e.g. \"async http client that parses json\", \
+        suggest the fewest established crates that cover it, with the Cargo.toml lines and \
+        features to enable, the other crates their examples use as example_dependencies, the use statements, and a skeleton snippet adapted from the \
+        crates' documentation examples. Pass crates to skip the crates.io search. Makes \
+        many requests; use the other tools to refine the result."
+            .to_string()
+    }
+
+    fn input_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "task": {
+                    "type": "string",
+                    "description": "What the code should do, in a few words"
+                },
+                "crates": {
+                    "type": "array",
+                    "items": {"type": "string"},
+                    "description": "Optional crates to build the snippet from instead of searching crates.io"
+                },
+                "max_crates": {
+                    "type": "integer",
+                    "description": "Maximum number of crates to suggest (defaults to 2, at most 5)"
+                },
+                "registry": registry::registry_schema()
+            },
+            "required": ["task"]
+        })
+    }
+
+    fn call(&self, input: Option<serde_json::Value>) -> Result<CallToolResponse> {
+        let args: DependencySnippetParams = error::parse_args(input)?;
+        for crate_name in args.crates.iter().flatten() {
+            config::current().ensure_crate_allowed(crate_name)?;
+        }
+        let max_crates = args
+            .max_crates
+            .unwrap_or(DEFAULT_MAX_CRATES)
+            .clamp(1, MAX_CRATES);
+        let snippet = runtime::blocking(|| {
+            registry::in_named_registry(args.registry.as_deref(), || {
+                self.dependency_snippet(&args.task, args.crates.as_deref(), max_crates)
+            })
+        })?;
+
+        Ok(CallToolResponse {
+            content: vec![ToolResponseContent::Text {
+                text: serde_json::to_string_pretty(&snippet)?,
+            }],
+            is_error: None,
+            meta: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::fs;
+
+    fn strings(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    fn demo_version() -> Result<IndexVersion> {
+        let versions = sparse_index::parse(&fs::read_to_string(
+            "test-data/dependency_tree/index/de/mo/demo",
+        )?)?;
+        Ok(versions[1].clone())
+    }
+
+    #[test]
+    fn test_keywords() {
+        assert_eq!(
+            keywords("I want to build an async HTTP client that parses JSON responses"),
+            strings(&["async", "http", "client", "parses", "json", "responses"])
+        );
+        assert_eq!(
+            keywords("Serialize structs to TOML, with serde-derive"),
+            strings(&["serialize", "structs", "toml", "serde-derive"])
+        );
+        assert!(keywords("a tool in Rust").is_empty());
+    }
+
+    #[test]
+    fn test_choose_crates() -> Result<()> {
+        let listed = parse_search(&fs::read_to_string(
+            "test-data/dependency_snippet/search.json",
+        )?)?;
+        let keywords = strings(&["async", "http", "client", "json"]);
+        let chosen: Vec<(String, Vec<String>)> = choose_crates(&listed, &keywords, 3)
+            .into_iter()
+            .map(|(krate, matched)| (krate.name, matched))
+            .collect();
+
+        assert_eq!(
+            chosen,
+            vec![
+                (
+                    "demo-http".to_string(),
+                    strings(&["async", "http", "client"])
+                ),
+                ("demo-json".to_string(), strings(&["json"])),
+            ],
+            "The squatted crate and the alternative client add nothing"
+        );
+        assert_eq!(choose_crates(&listed, &keywords, 1).len(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_features() -> Result<()> {
+        let version = demo_version()?;
+        let wanted = keyword_features(&version, &strings(&["bytes", "macros", "serde", "fast"]));
+        assert_eq!(
+            wanted.iter().map(String::as_str).collect::<Vec<_>>(),
+            vec!["bytes", "macros"]
+        );
+
+        let mut wanted = wanted;
+        wanted.extend(strings(&["serde", "docsrs"]));
+        assert_eq!(
+            extra_features(&version, wanted),
+            strings(&["bytes", "serde"]),
+            "Default features and names that aren't features are dropped"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_pick_example_and_paths() -> Result<()> {
+        let html = fs::read_to_string("test-data/dependency_snippet/root.html")?;
+        let examples = parse_root_examples(&html)?;
+        assert_eq!(examples.len(), 3);
+
+        let example = pick_example(&examples, "demo", &strings(&["tcp", "server"]))
+            .ok_or_else(|| anyhow!("No example picked"))?;
+        assert_eq!(example.section(), Some("A TCP echo server"));
+        assert_eq!(
+            crate_paths(example.code(), "demo"),
+            strings(&[
+                "demo::net::TcpListener",
+                "demo::io::AsyncReadExt",
+                "demo::io::AsyncWriteExt",
+                "demo::main",
+                "demo::spawn",
+            ])
+        );
+
+        let example =
+            pick_example(&examples, "demo", &[]).ok_or_else(|| anyhow!("No example picked"))?;
+        assert_eq!(
+            example.section(),
+            Some("Getting started"),
+            "Without keywords the first example using the crate is picked"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_expand_use() {
+        assert_eq!(
+            expand_use("demo::io::{self, AsyncReadExt, BufReader as Reader, util::{A, B}}"),
+            strings(&["demo::io", "demo::io::AsyncReadExt", "demo::io::BufReader"])
+        );
+        assert_eq!(
+            expand_use("demo::Runtime as Rt"),
+            strings(&["demo::Runtime"])
+        );
+    }
+
+    #[test]
+    fn test_assemble() -> Result<()> {
+        let html = fs::read_to_string("test-data/dependency_snippet/root.html")?;
+        let examples = parse_root_examples(&html)?;
+        let dependency = |name: &str, features: &[&str], matched: &[&str]| SnippetDependency {
+            crate_name: name.to_string(),
+            version: "1.0.0".to_string(),
+            description: None,
+            recent_downloads: None,
+            matched_keywords: strings(matched),
+            features: strings(features),
+            doc_link: format!("https://docs.rs/{}/1.0.0/{}/", name, name),
+            example_dependencies: Vec::new(),
+        };
+
+        let snippet = assemble(
+            "tcp echo server logging to a file",
+            strings(&["tcp", "echo", "server", "logging", "file"]),
+            vec![
+                (
+                    dependency("demo", &["net", "rt"], &["tcp", "echo", "server"]),
+                    Some(examples[1].code().to_string()),
+                ),
+                (dependency("demo-log", &[], &["logging"]), None),
+            ],
+            Vec::new(),
+        );
+
+        assert_eq!(
+            snippet.cargo_toml(),
+            "[dependencies]\n\
+            demo = { version = \"1.0.0\", features = [\"net\", \"rt\"] }\n\
+            demo-log = \"1.0.0\"\n"
+        );
+        assert_eq!(
+            snippet.imports(),
+            &[
+                "use demo::net::TcpListener;",
+                "use demo::io::{AsyncReadExt, AsyncWriteExt};",
+            ]
+        );
+        assert!(snippet
+            .snippet()
+            .starts_with("use demo::net::TcpListener;\nuse demo::io::{AsyncReadExt, AsyncWriteExt};\n\n// Adapted from the demo docs\n#[demo::main]"));
+        assert_eq!(
+            snippet.notes,
+            vec!["No picked crate mentions file; the standard library may cover it"]
+        );
+
+        let used = example_dependencies::resolve(
+            &strings(&["demo_core", "demo_log", "serde_json"]),
+            demo_version()?.deps(),
+        );
+        let snippet = assemble(
+            "tcp server",
+            strings(&["tcp", "server"]),
+            vec![
+                (
+                    SnippetDependency {
+                        example_dependencies: used,
+                        ..dependency("demo", &[], &["tcp", "server"])
+                    },
+                    None,
+                ),
+                (dependency("demo_log", &[], &[]), None),
+            ],
+            Vec::new(),
+        );
+        assert_eq!(
+            snippet.cargo_toml(),
+            "[dependencies]\n\
+            demo = \"1.0.0\"\n\
+            demo_log = \"1.0.0\"\n\
+            demo-core = \"^0.3\"\n",
+            "Crates the examples use are added once"
+        );
+        assert_eq!(
+            snippet.notes,
+            vec![
+                "The demo example also uses `serde_json`, which demo doesn't depend on; add the \
+                 crate it names"
+            ]
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,668 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+
+use anyhow::{anyhow, Result};
+use mcp_sdk::{
+    tools::Tool,
+    types::{CallToolResponse, ToolResponseContent},
+};
+use semver::{Version, VersionReq};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tracing::debug;
+
+use super::sparse_index::{self, IndexDependency, IndexVersion};
+use super::{registry, runtime};
+use crate::{config, error};
+
+/// Distinct crates whose index files are read for one tree; dependencies beyond are left
+/// unresolved
+const MAX_CRATES: usize = 400;
+
+/// The resolved dependencies of a crate version, as `cargo tree` prints them
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DependencyTree {
+    crate_name: String,
+    version: String,
+    /// Features activated on the crate itself
+    features: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    depth: Option<usize>,
+    /// Number of distinct crate versions in the tree, the crate included
+    packages: usize,
+    /// Whether some dependencies were left unresolved because the tree pulls in too many crates
+    truncated: bool,
+    dependencies: Vec<DependencyNode>,
+}
+
+impl DependencyTree {
+    pub fn features(&self) -> &[String] {
+        &self.features
+    }
+
+    pub fn dependencies(&self) -> &[DependencyNode] {
+        &self.dependencies
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DependencyNode {
+    name: String,
+    /// Requirement the depending crate declares
+    req: String,
+    /// Version the requirement resolves to, missing when it can't be resolved
+    version: Option<String>,
+    /// Features activated on this version across the whole tree
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    features: Vec<String>,
+    /// `normal` or `build`
+    kind: String,
+    /// Whether the dependency is optional and turned on by a feature
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    optional: bool,
+    /// Platform the dependency is limited to, e.g. `cfg(windows)`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    target: Option<String>,
+    /// Whether the dependencies of this version are listed elsewhere in the tree, as
+    /// `cargo tree` marks with `(*)`
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    duplicate: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    dependencies: Vec<DependencyNode>,
+}
+
+impl DependencyNode {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn version(&self) -> Option<&str> {
+        self.version.as_deref()
+    }
+
+    pub fn features(&self) -> &[String] {
+        &self.features
+    }
+
+    pub fn dependencies(&self) -> &[DependencyNode] {
+        &self.dependencies
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct DependencyTreeParams {
+    crate_name: String,
+    version: Option<String>,
+    features: Option<Vec<String>>,
+    default_features: Option<bool>,
+    depth: Option<usize>,
+    registry: Option<String>,
+}
+
+pub struct DependencyTreeTool;
+
+impl DependencyTreeTool {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for DependencyTreeTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A crate version: name and version
+type PackageId = (String, String);
+
+/// Features and dependencies turned on by a set of requested features
+#[derive(Debug, Default)]
+struct Activation {
+    features: BTreeSet<String>,
+    /// Enabled dependencies by index into the version's `deps`, with the features they get
+    deps: Vec<(usize, BTreeSet<String>)>,
+}
+
+/// Follows the feature table of a version from the `requested` features: `dep:x` turns on an
+/// optional dependency, `x/f` turns it on along with its feature `f`, `x?/f` only adds `f`
+/// when something else turns `x` on, and an optional dependency no `dep:` refers to doubles as
+/// a feature of its own name. Dev-dependencies never count.
+fn activate(version: &IndexVersion, requested: &BTreeSet<String>) -> Activation {
+    let table = version.features();
+    let deps: Vec<(usize, &IndexDependency)> = version
+        .deps()
+        .iter()
+        .enumerate()
+        .filter(|(_, dep)| dep.kind() != "dev")
+        .collect();
+    let explicit: HashSet<&str> = table
+        .values()
+        .flat_map(|values| values.iter())
+        .filter_map(|value| value.strip_prefix("dep:"))
+        .collect();
+    let implicit = |name: &str| {
+        !explicit.contains(name)
+            && deps
+                .iter()
+                .any(|(_, dep)| dep.optional() && dep.name() == name)
+    };
+
+    let mut features = BTreeSet::new();
+    let mut enabled: HashSet<&str> = deps
+        .iter()
+        .filter(|(_, dep)| !dep.optional())
+        .map(|(_, dep)| dep.name())
+        .collect();
+    let mut dep_features: HashMap<&str, BTreeSet<String>> = HashMap::new();
+    let mut weak = Vec::new();
+
+    let mut pending: Vec<&str> = requested.iter().map(String::as_str).collect();
+    while let Some(feature) = pending.pop() {
+        if features.contains(feature) {
+            continue;
+        }
+        if let Some(values) = table.get(feature) {
+            features.insert(feature.to_string());
+            for value in values.iter() {
+                if let Some(dep) = value.strip_prefix("dep:") {
+                    enabled.insert(dep);
+                } else if let Some((dep, dep_feature)) = value.split_once('/') {
+                    match dep.strip_suffix('?') {
+                        Some(dep) => weak.push((dep, dep_feature)),
+                        None => {
+                            enabled.insert(dep);
+                            dep_features
+                                .entry(dep)
+                                .or_default()
+                                .insert(dep_feature.to_string());
+                            if implicit(dep) {
+                                pending.push(dep);
+                            }
+                        }
+                    }
+                } else {
+                    pending.push(value);
+                }
+            }
+        } else if implicit(feature) {
+            features.insert(feature.to_string());
+            enabled.insert(feature);
+        }
+    }
+    for (dep, dep_feature) in weak {
+        if enabled.contains(dep) {
+            dep_features
+                .entry(dep)
+                .or_default()
+                .insert(dep_feature.to_string());
+        }
+    }
+
+    let deps = deps
+        .into_iter()
+        .filter(|(_, dep)| enabled.contains(dep.name()))
+        .map(|(i, dep)| {
+            let mut features: BTreeSet<String> = dep.features().iter().cloned().collect();
+            if dep.default_features() {
+                features.insert("default".to_string());
+            }
+            features.extend(dep_features.get(dep.name()).into_iter().flatten().cloned());
+            (i, features)
+        })
+        .collect();
+    Activation { features, deps }
+}
+
+/// Features a version ends up with when `requested` are enabled
+pub(crate) fn enabled_features(
+    version: &IndexVersion,
+    requested: &BTreeSet<String>,
+) -> BTreeSet<String> {
+    activate(version, requested).features
+}
+
+/// Features of `requested` that a version doesn't define. `default` is always accepted.
+fn unknown_features<'a>(version: &IndexVersion, requested: &'a [String]) -> Vec<&'a str> {
+    let table = version.features();
+    let activation = activate(version, &requested.iter().cloned().collect());
+    requested
+        .iter()
+        .map(String::as_str)
+        .filter(|feature| *feature != "default" && !table.contains_key(feature))
+        .filter(|feature| !activation.features.contains(*feature))
+        .collect()
+}
+
+/// The version of the crate itself: the highest stable release for `latest`, an exact
+/// version, or the highest release matching a requirement.
+fn root_version<'a>(
+    versions: &'a [IndexVersion],
+    crate_name: &str,
+    requested: Option<&str>,
+) -> Result<&'a IndexVersion> {
+    let found = match requested.unwrap_or("latest") {
+        "latest" => sparse_index::select(versions, &VersionReq::STAR).or_else(|| {
+            versions
+                .iter()
+                .filter(|version| Version::parse(version.version()).is_ok())
+                .max_by_key(|version| Version::parse(version.version()).ok())
+        }),
+        exact if Version::parse(exact).is_ok() => {
+            versions.iter().find(|version| version.version() == exact)
+        }
+        req => sparse_index::select(
+            versions,
+            &VersionReq::parse(req).map_err(|e| anyhow!("Invalid version {}: {}", req, e))?,
+        ),
+    };
+    found.ok_or_else(|| {
+        anyhow!(
+            "{} has no published version matching {}",
+            crate_name,
+            requested.unwrap_or("latest")
+        )
+    })
+}
+
+/// Resolves dependency versions from index files, unifying the features each crate version
+/// gets from everything depending on it, as Cargo does.
+struct Resolver<L> {
+    load: L,
+    index: HashMap<String, Vec<IndexVersion>>,
+    resolved: HashMap<(String, String), Result<PackageId, String>>,
+    features: BTreeMap<PackageId, BTreeSet<String>>,
+    depths: HashMap<PackageId, usize>,
+    truncated: bool,
+}
+
+impl<L: FnMut(&str) -> Result<Vec<IndexVersion>>> Resolver<L> {
+    fn new(load: L) -> Self {
+        Self {
+            load,
+            index: HashMap::new(),
+            resolved: HashMap::new(),
+            features: BTreeMap::new(),
+            depths: HashMap::new(),
+            truncated: false,
+        }
+    }
+
+    fn versions(&mut self, crate_name: &str) -> Result<&[IndexVersion]> {
+        if !self.index.contains_key(crate_name) {
+            if self.index.len() >= MAX_CRATES {
+                self.truncated = true;
+                return Err(anyhow!(
+                    "Not resolved, the tree reads more than {} crates",
+                    MAX_CRATES
+                ));
+            }
+            let versions = (self.load)(crate_name)?;
+            self.index.insert(crate_name.to_string(), versions);
+        }
+        Ok(&self.index[crate_name])
+    }
+
+    fn version(&self, id: &PackageId) -> Option<&IndexVersion> {
+        self.index
+            .get(&id.0)?
+            .iter()
+            .find(|version| version.version() == id.1)
+    }
+
+    /// The version a dependency resolves to, or why it doesn't
+    fn resolve(&mut self, dep: &IndexDependency) -> Result<PackageId, String> {
+        let key = (dep.package().to_string(), dep.req().to_string());
+        if let Some(resolved) = self.resolved.get(&key) {
+            return resolved.clone();
+        }
+        let resolved = self.select(dep).map_err(|e| format!("{:#}", e));
+        self.resolved.insert(key, resolved.clone());
+        resolved
+    }
+
+    fn select(&mut self, dep: &IndexDependency) -> Result<PackageId> {
+        let req = VersionReq::parse(dep.req())?;
+        let versions = self.versions(dep.package())?;
+        sparse_index::select(versions, &req)
+            .map(|version| (version.name().to_string(), version.version().to_string()))
+            .ok_or_else(|| anyhow!("No published version matches {}", dep.req()))
+    }
+
+    /// Walks the graph from `root` until no crate version gains features.
+    fn run(&mut self, root: &PackageId, requested: BTreeSet<String>, max_depth: Option<usize>) {
+        self.features.insert(root.clone(), requested);
+        self.depths.insert(root.clone(), 0);
+        let mut queue = VecDeque::from([(root.clone(), 0)]);
+
+        while let Some((id, depth)) = queue.pop_front() {
+            if max_depth.is_some_and(|max| depth >= max) {
+                continue;
+            }
+            let Some(version) = self.version(&id).cloned() else {
+                continue;
+            };
+            let activation = activate(&version, &self.features[&id]);
+            for (index, dep_features) in activation.deps {
+                let Ok(child) = self.resolve(&version.deps()[index]) else {
+                    continue;
+                };
+                let seen = self.features.get(&child).map_or(0, BTreeSet::len);
+                let known = self.depths.contains_key(&child);
+                let entry = self.features.entry(child.clone()).or_default();
+                entry.extend(dep_features);
+                let grew = entry.len() > seen;
+                let shallower = self.depths.get(&child).is_none_or(|d| depth + 1 < *d);
+                if shallower {
+                    self.depths.insert(child.clone(), depth + 1);
+                }
+                if !known || grew || shallower {
+                    let depth = self.depths[&child];
+                    queue.push_back((child, depth));
+                }
+            }
+        }
+    }
+
+    /// Nodes for the enabled dependencies of `id`, expanding each crate version once.
+    fn children(
+        &self,
+        id: &PackageId,
+        depth: usize,
+        max_depth: Option<usize>,
+        expanded: &mut HashSet<PackageId>,
+    ) -> Vec<DependencyNode> {
+        let Some(version) = self.version(id) else {
+            return Vec::new();
+        };
+        let activation = activate(version, &self.features[id]);
+        let mut nodes = Vec::new();
+
+        for (index, _) in activation.deps {
+            let dep = &version.deps()[index];
+            let resolved = self
+                .resolved
+                .get(&(dep.package().to_string(), dep.req().to_string()));
+            let mut node = DependencyNode {
+                name: dep.package().to_string(),
+                req: dep.req().to_string(),
+                version: None,
+                features: Vec::new(),
+                kind: dep.kind().to_string(),
+                optional: dep.optional(),
+                target: dep.target().map(str::to_string),
+                duplicate: false,
+                error: None,
+                dependencies: Vec::new(),
+            };
+            match resolved {
+                Some(Ok(child)) => {
+                    node.version = Some(child.1.clone());
+                    let activation = self
+                        .version(child)
+                        .map(|version| activate(version, &self.features[child]))
+                        .unwrap_or_default();
+                    node.features = activation.features.into_iter().collect();
+                    if max_depth.is_none_or(|max| depth + 1 < max) {
+                        if expanded.insert(child.clone()) {
+                            node.dependencies =
+                                self.children(child, depth + 1, max_depth, expanded);
+                        } else {
+                            node.duplicate = !activation.deps.is_empty();
+                        }
+                    }
+                }
+                Some(Err(e)) => node.error = Some(e.clone()),
+                None => node.error = Some("Not resolved".to_string()),
+            }
+            nodes.push(node);
+        }
+        nodes
+    }
+}
+
+/// Resolves the dependency tree of a crate version, reading index files through `load`.
+fn resolve_tree(
+    crate_name: &str,
+    version: Option<&str>,
+    features: &[String],
+    default_features: bool,
+    max_depth: Option<usize>,
+    load: impl FnMut(&str) -> Result<Vec<IndexVersion>>,
+) -> Result<DependencyTree> {
+    let mut resolver = Resolver::new(load);
+    let root_version = root_version(resolver.versions(crate_name)?, crate_name, version)?.clone();
+    let unknown = unknown_features(&root_version, features);
+    if !unknown.is_empty() {
+        return Err(anyhow!(
+            "{} {} has no feature {}; its features are: {}",
+            crate_name,
+            root_version.version(),
+            unknown.join(", "),
+            root_version
+                .features()
+                .keys()
+                .copied()
+                .collect::<Vec<_>>()
+                .join(", ")
+        ));
+    }
+
+    let mut requested: BTreeSet<String> = features.iter().cloned().collect();
+    if default_features {
+        requested.insert("default".to_string());
+    }
+    let root = (
+        root_version.name().to_string(),
+        root_version.version().to_string(),
+    );
+    resolver.run(&root, requested, max_depth);
+    debug!(
+        "Resolved {} crate versions for {} {}",
+        resolver.features.len(),
+        crate_name,
+        root.1
+    );
+
+    let mut expanded = HashSet::from([root.clone()]);
+    let dependencies = resolver.children(&root, 0, max_depth, &mut expanded);
+    Ok(DependencyTree {
+        crate_name: root.0.clone(),
+        version: root.1.clone(),
+        features: activate(&root_version, &resolver.features[&root])
+            .features
+            .into_iter()
+            .collect(),
+        depth: max_depth,
+        packages: resolver.features.len(),
+        truncated: resolver.truncated,
+        dependencies,
+    })
+}
+
+impl Tool for DependencyTreeTool {
+    fn name(&self) -> String {
+        "dependency_tree".to_string()
+    }
+
+    fn description(&self) -> String {
+        "Resolve the dependency tree of a crate version from the registry index, like \
+        `cargo tree` for a crate that hasn't been added to a project yet. Returns the nested \
+        dependencies with their resolved versions and the features activated on each, unified \
+        across the tree. Build dependencies are included and marked, dev-dependencies are \
+        left out, and platform-specific dependencies carry their target. Crate versions \
+        appearing more than once are expanded once and marked as duplicates."
+            .to_string()
+    }
+
+    fn input_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "crate_name": {
+                    "type": "string",
+                    "description": "Name of the crate to resolve the dependencies of"
+                },
+                "version": {
+                    "type": "string",
+                    "description": "Version or version requirement of the crate, e.g. 1.43.0 or ^1.40 (defaults to the latest stable release)"
+                },
+                "features": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Features to enable on the crate, as with cargo add --features"
+                },
+                "default_features": {
+                    "type": "boolean",
+                    "description": "Whether to enable the crate's default features (defaults to true)"
+                },
+                "depth": {
+                    "type": "integer",
+                    "description": "Maximum depth of the tree; 1 lists direct dependencies only (defaults to the full tree)"
+                },
+                "registry": registry::registry_schema()
+            },
+            "required": ["crate_name"]
+        })
+    }
+
+    fn call(&self, input: Option<serde_json::Value>) -> Result<CallToolResponse> {
+        let args: DependencyTreeParams = error::parse_args(input)?;
+        config::current().ensure_crate_allowed(&args.crate_name)?;
+        let tree = runtime::blocking(|| {
+            registry::in_registry(&args.crate_name, args.registry.as_deref(), || {
+                resolve_tree(
+                    &args.crate_name,
+                    args.version.as_deref(),
+                    args.features.as_deref().unwrap_or_default(),
+                    args.default_features.unwrap_or(true),
+                    args.depth,
+                    sparse_index::fetch,
+                )
+            })
+        })?;
+
+        Ok(CallToolResponse {
+            content: vec![ToolResponseContent::Text {
+                text: serde_json::to_string_pretty(&tree)?,
+            }],
+            is_error: None,
+            meta: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::fs;
+
+    fn load(crate_name: &str) -> Result<Vec<IndexVersion>> {
+        let path = format!(
+            "test-data/dependency_tree/index/{}",
+            sparse_index::index_path(crate_name)
+        );
+        sparse_index::parse(&fs::read_to_string(&path).map_err(|e| anyhow!("{}: {}", path, e))?)
+    }
+
+    fn resolve(
+        features: &[&str],
+        default_features: bool,
+        depth: Option<usize>,
+    ) -> Result<DependencyTree> {
+        let features: Vec<String> = features.iter().map(|f| f.to_string()).collect();
+        resolve_tree("demo", None, &features, default_features, depth, load)
+    }
+
+    /// `name version [features]` per node, indented by depth, duplicates marked with `(*)`
+    fn render(nodes: &[DependencyNode], depth: usize, lines: &mut Vec<String>) {
+        for node in nodes {
+            lines.push(format!(
+                "{}{} {} [{}]{}{}",
+                "  ".repeat(depth),
+                node.name(),
+                node.version().unwrap_or("?"),
+                node.features().join(","),
+                if node.kind == "build" { " (build)" } else { "" },
+                if node.duplicate { " (*)" } else { "" },
+            ));
+            render(node.dependencies(), depth + 1, lines);
+        }
+    }
+
+    fn lines(tree: &DependencyTree) -> Vec<String> {
+        let mut lines = Vec::new();
+        render(tree.dependencies(), 0, &mut lines);
+        lines
+    }
+
+    #[test]
+    fn test_default_features() -> Result<()> {
+        let tree = resolve(&[], true, None)?;
+
+        assert_eq!(tree.version, "1.1.0", "Pre-releases aren't the latest");
+        assert_eq!(tree.features(), &["default", "macros"]);
+        assert_eq!(
+            lines(&tree),
+            vec![
+                "cc 1.2.0 [] (build)",
+                "demo-core 0.3.0 [io,std]",
+                "demo-macros 1.2.0 []",
+                "  demo-core 0.3.0 [io,std]",
+                "winapi-demo 0.3.9 []",
+            ],
+            "demo-core gets std from demo-macros, and the yanked 0.3.1 is skipped"
+        );
+        assert_eq!(
+            tree.dependencies()[3].target.as_deref(),
+            Some("cfg(windows)")
+        );
+        assert_eq!(tree.packages, 5);
+        Ok(())
+    }
+
+    #[test]
+    fn test_requested_features() -> Result<()> {
+        let tree = resolve(&["full"], false, None)?;
+
+        assert_eq!(tree.features(), &["bytes", "full", "macros", "serde"]);
+        assert_eq!(
+            lines(&tree),
+            vec![
+                "bytes 1.10.0 [default,std]",
+                "cc 1.2.0 [] (build)",
+                "demo-core 0.3.0 [extra,io,std]",
+                "  bytes 1.10.0 [default,std]",
+                "demo-macros 1.2.0 [bytes]",
+                "  demo-core 0.3.0 [extra,io,std] (*)",
+                "serde 1.0.217 [default,std]",
+                "winapi-demo 0.3.9 []",
+            ],
+            "Weak features apply once the dependency is on, optional dependencies double as features"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_depth_and_versions() -> Result<()> {
+        let tree = resolve(&[], true, Some(1))?;
+        assert!(tree
+            .dependencies()
+            .iter()
+            .all(|node| node.dependencies().is_empty()));
+        assert_eq!(
+            tree.dependencies()[1].features(),
+            &["io"],
+            "Features from deeper levels aren't resolved"
+        );
+
+        let old = resolve_tree("demo", Some("1.0.0"), &[], true, None, load)?;
+        assert_eq!(lines(&old), vec!["demo-core 0.3.0 [default,std]"]);
+
+        let err = resolve(&["nope"], true, None).unwrap_err().to_string();
+        assert!(err.contains("has no feature nope"), "{}", err);
+        Ok(())
+    }
+}
@@ -0,0 +1,191 @@
+use anyhow::Result;
+use mcp_sdk::{
+    tools::Tool,
+    types::{CallToolResponse, ToolResponseContent},
+};
+use scraper::Html;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tracing::{debug, warn};
+
+use super::deprecation::{self, Deprecation};
+use super::get_struct_docs::{HtmlFetcher, HttpHtmlFetcher};
+use super::{registry, runtime, SearchInCrateTool};
+use crate::{config, error};
+
+/// Deprecation messages are read from item pages; crates deprecating items across more pages
+/// than this are listed without the remaining messages
+const MAX_PAGES: usize = 40;
+
+/// The deprecated items of a crate version
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DeprecatedItems {
+    crate_name: String,
+    version: String,
+    items: Vec<DeprecatedItem>,
+    /// Whether some items lack their deprecation message because of the page limit
+    truncated: bool,
+}
+
+impl DeprecatedItems {
+    pub fn items(&self) -> &[DeprecatedItem] {
+        &self.items
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DeprecatedItem {
+    path: String,
+    kind: String,
+    doc_link: String,
+    /// Version, message and suggested replacement from the item's deprecation banner
+    deprecation: Option<Deprecation>,
+}
+
+impl DeprecatedItem {
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    pub fn deprecation(&self) -> Option<&Deprecation> {
+        self.deprecation.as_ref()
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct DeprecatedItemsParams {
+    crate_name: String,
+    version: Option<String>,
+    registry: Option<String>,
+}
+
+pub struct DeprecatedItemsTool {
+    html_fetcher: Box<dyn HtmlFetcher>,
+}
+
+impl DeprecatedItemsTool {
+    pub fn new() -> Self {
+        Self {
+            html_fetcher: Box::new(HttpHtmlFetcher::new()),
+        }
+    }
+
+    /// Finds deprecated items through the search index, then reads their messages from the
+    /// item pages, fetching each page once.
+    fn list_deprecated(&self, crate_name: &str, version: Option<&str>) -> Result<DeprecatedItems> {
+        let index = SearchInCrateTool::new().fetch_index(crate_name, version)?;
+        let mut items: Vec<DeprecatedItem> = index
+            .iter()
+            .filter(|item| item.deprecated())
+            .map(|item| DeprecatedItem {
+                path: item.path().to_string(),
+                kind: item.kind().to_string(),
+                doc_link: item.doc_link().to_string(),
+                deprecation: None,
+            })
+            .collect();
+        debug!("{} has {} deprecated items", crate_name, items.len());
+
+        let mut pages: Vec<&str> = Vec::new();
+        for item in &items {
+            let page = page_url(&item.doc_link);
+            if !pages.contains(&page) {
+                pages.push(page);
+            }
+        }
+        let truncated = pages.len() > MAX_PAGES;
+        let pages: Vec<String> = pages
+            .into_iter()
+            .take(MAX_PAGES)
+            .map(str::to_string)
+            .collect();
+
+        for page in pages {
+            let html = match self.html_fetcher.fetch_html_blocking(&page) {
+                Ok(html) => html,
+                Err(e) => {
+                    warn!("Failed to fetch {}: {}", page, e);
+                    continue;
+                }
+            };
+            let document = Html::parse_document(&html);
+            for item in items
+                .iter_mut()
+                .filter(|item| page_url(&item.doc_link) == page)
+            {
+                item.deprecation = match item.doc_link.split_once('#') {
+                    Some((_, anchor)) => deprecation::member_banner(&document, anchor),
+                    None => deprecation::banner(document.root_element(), deprecation::ITEM_BANNER),
+                };
+            }
+        }
+
+        Ok(DeprecatedItems {
+            crate_name: crate_name.to_string(),
+            version: version.unwrap_or("latest").to_string(),
+            items,
+            truncated,
+        })
+    }
+}
+
+impl Default for DeprecatedItemsTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The page a documentation link points to, without the anchor of a method or field
+fn page_url(doc_link: &str) -> &str {
+    doc_link.split('#').next().unwrap_or(doc_link)
+}
+
+impl Tool for DeprecatedItemsTool {
+    fn name(&self) -> String {
+        "deprecated_items".to_string()
+    }
+
+    fn description(&self) -> String {
+        "List every deprecated item of a crate version, including methods, fields and \
+        variants, with the version it was deprecated in, the deprecation message and the \
+        suggested replacement. Useful before upgrading a crate or when generated code hits \
+        deprecation warnings."
+            .to_string()
+    }
+
+    fn input_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "crate_name": {
+                    "type": "string",
+                    "description": "Name of the crate to list deprecated items of"
+                },
+                "version": {
+                    "type": "string",
+                    "description": "Optional version of the crate (defaults to latest)"
+                },
+                "registry": registry::registry_schema()
+            },
+            "required": ["crate_name"]
+        })
+    }
+
+    fn call(&self, input: Option<serde_json::Value>) -> Result<CallToolResponse> {
+        let args: DeprecatedItemsParams = error::parse_args(input)?;
+        config::current().ensure_crate_allowed(&args.crate_name)?;
+        let deprecated = runtime::blocking(|| {
+            registry::in_registry(&args.crate_name, args.registry.as_deref(), || {
+                self.list_deprecated(&args.crate_name, args.version.as_deref())
+            })
+        })?;
+
+        Ok(CallToolResponse {
+            content: vec![ToolResponseContent::Text {
+                text: serde_json::to_string_pretty(&deprecated)?,
+            }],
+            is_error: None,
+            meta: None,
+        })
+    }
+}
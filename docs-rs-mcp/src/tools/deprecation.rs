@@ -0,0 +1,141 @@
+//! Reading the deprecation banners rustdoc renders on items, such as
+//! "👎 Deprecated since 0.27.0: use `with_span_processor` instead"
+
+use super::selectors;
+use scraper::{ElementRef, Html};
+use serde::{Deserialize, Serialize};
+
+/// Banner of the item a page documents, as opposed to those of its methods
+pub(crate) const ITEM_BANNER: &str = "#main-content > .item-info .stab.deprecated";
+
+/// Banner of a method or associated item, relative to its toggle block
+pub(crate) const MEMBER_BANNER: &str = ".item-info .stab.deprecated";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Deprecation {
+    /// Version the item was deprecated in, when given
+    since: Option<String>,
+    /// The deprecation message
+    note: Option<String>,
+    /// The first code span of the message, which usually names the replacement
+    replacement: Option<String>,
+}
+
+impl Deprecation {
+    pub fn since(&self) -> Option<&str> {
+        self.since.as_deref()
+    }
+
+    pub fn note(&self) -> Option<&str> {
+        self.note.as_deref()
+    }
+
+    pub fn replacement(&self) -> Option<&str> {
+        self.replacement.as_deref()
+    }
+}
+
+/// Deprecation of the first banner matching `selector` within `scope`.
+pub(crate) fn banner(scope: ElementRef, selector: &str) -> Option<Deprecation> {
+    let selector = selectors::parse(selector, "item page").ok()?;
+    scope.select(&selector).next().map(parse_banner)
+}
+
+/// Deprecation of the method, field or variant with the given anchor on an item page. Its
+/// banner follows the member's header, before the next member starts.
+pub(crate) fn member_banner(document: &Html, anchor: &str) -> Option<Deprecation> {
+    let selector = selectors::parse(
+        &format!("[id=\"{}\"]", anchor.replace('"', "")),
+        "item page",
+    )
+    .ok()?;
+    let header = document.select(&selector).next()?;
+
+    header
+        .next_siblings()
+        .filter_map(ElementRef::wrap)
+        .take_while(|sibling| sibling.value().id().is_none())
+        .find_map(|sibling| banner(sibling, ".stab.deprecated"))
+}
+
+fn parse_banner(banner: ElementRef) -> Deprecation {
+    let text = banner.text().collect::<String>().replace('👎', "");
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+
+    // "Deprecated since 1.2.0: note", "Deprecated: note" or "Deprecating in 2.0.0: note"
+    let (heading, note) = match text.split_once(": ") {
+        Some((heading, note)) => (heading, Some(note.trim().to_string())),
+        None => (text.as_str(), None),
+    };
+    let since = heading
+        .strip_prefix("Deprecated since ")
+        .or_else(|| heading.strip_prefix("Deprecating in "))
+        .map(|version| version.trim().to_string());
+
+    let replacement = selectors::parse("code", "item page").ok().and_then(|code| {
+        banner
+            .select(&code)
+            .next()
+            .map(|el| el.text().collect::<String>())
+    });
+
+    Deprecation {
+        since,
+        note: note.filter(|note| !note.is_empty()),
+        replacement,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::fs;
+
+    fn load() -> Html {
+        let html = fs::read_to_string("test-data/deprecated_items/struct-page.html")
+            .expect("Should be able to read test HTML file");
+        Html::parse_document(&html)
+    }
+
+    #[test]
+    fn test_item_banner() {
+        let document = load();
+
+        assert_eq!(
+            banner(document.root_element(), ITEM_BANNER),
+            Some(Deprecation {
+                since: Some("0.3.0".to_string()),
+                note: Some("use TracerProviderBuilder instead".to_string()),
+                replacement: Some("TracerProviderBuilder".to_string()),
+            })
+        );
+    }
+
+    #[test]
+    fn test_member_banner() {
+        let document = load();
+
+        assert_eq!(
+            member_banner(&document, "method.with_sampler"),
+            Some(Deprecation {
+                since: Some("0.2.0".to_string()),
+                note: Some("samplers are configured through with_config".to_string()),
+                replacement: Some("with_config".to_string()),
+            })
+        );
+        assert_eq!(
+            member_banner(&document, "method.build"),
+            None,
+            "The next method's banner doesn't belong to build"
+        );
+        assert_eq!(
+            member_banner(&document, "method.with_id_generator"),
+            Some(Deprecation {
+                since: None,
+                note: None,
+                replacement: None,
+            })
+        );
+    }
+}
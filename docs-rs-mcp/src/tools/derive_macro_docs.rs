@@ -0,0 +1,335 @@
+use anyhow::{anyhow, Result};
+#[cfg(feature = "native")]
+use mcp_sdk::{
+    tools::Tool,
+    types::{CallToolResponse, ToolResponseContent},
+};
+use scraper::{ElementRef, Html, Node};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tracing::debug;
+
+use super::get_struct_docs::{HtmlFetcher, HttpHtmlFetcher};
+#[cfg(feature = "native")]
+use super::runtime;
+use super::{markdown, registry, selectors, summary, CrateItemsTool};
+use crate::{config, error};
+
+/// Documentation of a derive macro, from its `derive.*.html` page
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DeriveMacroDocs {
+    crate_name: String,
+    version: String,
+    name: String,
+    doc_link: String,
+    /// Helper attributes the derive accepts, e.g. `serde` for `#[serde(...)]`
+    helper_attributes: Vec<String>,
+    /// Uses of the helper attributes the docs explain, such as container and field attributes
+    attributes: Vec<AttributeDoc>,
+    /// The macro's documentation as Markdown
+    docs: String,
+    /// URI of the full documentation, a resource, when `docs` is only a summary
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    full_docs: Option<String>,
+}
+
+impl DeriveMacroDocs {
+    pub fn helper_attributes(&self) -> &[String] {
+        &self.helper_attributes
+    }
+
+    pub fn attributes(&self) -> &[AttributeDoc] {
+        &self.attributes
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AttributeDoc {
+    /// The attribute as written in the docs, e.g. `#[builder(default)]`
+    attribute: String,
+    /// Heading the attribute is documented under, e.g. "Field attributes"
+    section: Option<String>,
+    /// The list item, paragraph or table row explaining the attribute
+    description: String,
+}
+
+impl AttributeDoc {
+    pub fn attribute(&self) -> &str {
+        &self.attribute
+    }
+
+    pub fn section(&self) -> Option<&str> {
+        self.section.as_deref()
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct DeriveMacroDocsParams {
+    crate_name: String,
+    derive_name: String,
+    version: Option<String>,
+    registry: Option<String>,
+}
+
+pub struct DeriveMacroDocsTool {
+    html_fetcher: Box<dyn HtmlFetcher>,
+}
+
+impl DeriveMacroDocsTool {
+    pub fn new() -> Self {
+        Self {
+            html_fetcher: Box::new(HttpHtmlFetcher::new()),
+        }
+    }
+
+    #[cfg(feature = "native")]
+    fn fetch_docs(
+        &self,
+        crate_name: &str,
+        derive_name: &str,
+        version: Option<&str>,
+    ) -> Result<DeriveMacroDocs> {
+        let items = CrateItemsTool::new().scrape_items_blocking(crate_name, version)?;
+        let derives = items
+            .items()
+            .get("Derives")
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        let suffix = format!("::{}", derive_name);
+        let item = derives
+            .iter()
+            .find(|item| item.name() == derive_name || item.name().ends_with(&suffix))
+            .ok_or_else(|| {
+                anyhow!(
+                    "Crate {} {} has no derive macro {}; it derives: {}",
+                    crate_name,
+                    items.version(),
+                    derive_name,
+                    derives
+                        .iter()
+                        .map(|item| item.name())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )
+            })?;
+        debug!("Found derive {} at {}", item.name(), item.doc_link());
+
+        let html = self.html_fetcher.fetch_html_blocking(item.doc_link())?;
+        let (helper_attributes, attributes, docs) = parse_derive_page(&html)?;
+
+        Ok(DeriveMacroDocs {
+            crate_name: items.crate_name().to_string(),
+            version: items.version().to_string(),
+            name: item.name().to_string(),
+            doc_link: item.doc_link().to_string(),
+            helper_attributes,
+            attributes,
+            docs,
+            full_docs: None,
+        })
+    }
+}
+
+impl Default for DeriveMacroDocsTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Reads the helper attributes from the macro's declaration and the attribute docs from its
+/// docblock.
+fn parse_derive_page(html: &str) -> Result<(Vec<String>, Vec<AttributeDoc>, String)> {
+    let document = Html::parse_document(html);
+    let decl_selector = selectors::parse("#main-content pre.item-decl", "derive macro page")?;
+    let docblock_selector = selectors::parse(
+        "#main-content .toggle.top-doc .docblock",
+        "derive macro page",
+    )?;
+
+    // The declaration lists helpers under "// Attributes available to this derive:"
+    let helper_attributes: Vec<String> = document
+        .select(&decl_selector)
+        .next()
+        .map(|decl| decl.text().collect::<String>())
+        .unwrap_or_default()
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.starts_with("#[derive("))
+        .filter_map(|line| line.strip_prefix("#[")?.strip_suffix(']'))
+        .map(str::to_string)
+        .collect();
+
+    let Some(docblock) = document.select(&docblock_selector).next() else {
+        return Ok((helper_attributes, Vec::new(), String::new()));
+    };
+    let attributes = parse_attribute_docs(docblock, &helper_attributes);
+    Ok((
+        helper_attributes,
+        attributes,
+        markdown::element_to_markdown(docblock),
+    ))
+}
+
+/// Collects inline code spans using one of the helper attributes, e.g. `#[serde(rename = "..")]`,
+/// with the heading they appear under. Code blocks are examples rather than reference docs, so
+/// they are skipped.
+pub(crate) fn parse_attribute_docs(docblock: ElementRef, helpers: &[String]) -> Vec<AttributeDoc> {
+    let mut attributes: Vec<AttributeDoc> = Vec::new();
+    let mut section = None;
+
+    for node in docblock.descendants() {
+        let Some(element) = ElementRef::wrap(node) else {
+            continue;
+        };
+        let name = element.value().name();
+        if matches!(name, "h1" | "h2" | "h3" | "h4" | "h5" | "h6") {
+            section = Some(text_of(element));
+            continue;
+        }
+        if name != "code" || has_ancestor(element, &["pre"]) {
+            continue;
+        }
+
+        let attribute = element.text().collect::<String>().trim().to_string();
+        let uses_helper = attribute_name(&attribute).is_some_and(|name| helpers.contains(&name));
+        if !uses_helper || attributes.iter().any(|doc| doc.attribute == attribute) {
+            continue;
+        }
+
+        let description = element
+            .ancestors()
+            .filter_map(ElementRef::wrap)
+            .take_while(|ancestor| ancestor.id() != docblock.id())
+            .find(|ancestor| matches!(ancestor.value().name(), "li" | "p" | "tr" | "dd"))
+            .map(text_of)
+            .unwrap_or_default();
+        attributes.push(AttributeDoc {
+            attribute,
+            section: section.clone(),
+            description,
+        });
+    }
+    attributes
+}
+
+/// Name an attribute is invoked by, without its path: `main` for `#[tokio::main(flavor = "..")]`
+pub(crate) fn attribute_name(attribute: &str) -> Option<String> {
+    let rest = attribute.strip_prefix("#[")?;
+    let path = &rest[..rest.find(['(', ']'])?];
+    let name = path.rsplit("::").next()?;
+    let is_path = path
+        .chars()
+        .all(|c| c.is_alphanumeric() || c == '_' || c == ':');
+    (is_path && !name.is_empty()).then(|| name.to_string())
+}
+
+fn has_ancestor(element: ElementRef, names: &[&str]) -> bool {
+    element.ancestors().any(|ancestor| match ancestor.value() {
+        Node::Element(ancestor) => names.contains(&ancestor.name()),
+        _ => false,
+    })
+}
+
+/// Text of an element with whitespace collapsed, leaving out heading anchors ("§")
+pub(crate) fn text_of(element: ElementRef) -> String {
+    element
+        .text()
+        .collect::<String>()
+        .replace('§', "")
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(feature = "native")]
+impl Tool for DeriveMacroDocsTool {
+    fn name(&self) -> String {
+        "derive_macro_docs".to_string()
+    }
+
+    fn description(&self) -> String {
+        "Get the documentation of a derive macro, e.g. serde's Serialize or clap's Parser. \
+        Returns the helper attributes the derive accepts (such as #[serde(...)]), the \
+        container and field attributes its docs explain, and the full docs as Markdown."
+            .to_string()
+    }
+
+    fn input_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "crate_name": {
+                    "type": "string",
+                    "description": "Name of the crate exporting the derive macro, e.g. serde or clap"
+                },
+                "derive_name": {
+                    "type": "string",
+                    "description": "Name of the derive macro, e.g. Serialize or Parser"
+                },
+                "version": {
+                    "type": "string",
+                    "description": "Optional version of the crate. Defaults to latest if not specified"
+                },
+                "registry": registry::registry_schema()
+            },
+            "required": ["crate_name", "derive_name"]
+        })
+    }
+
+    fn call(&self, input: Option<serde_json::Value>) -> Result<CallToolResponse> {
+        let args: DeriveMacroDocsParams = error::parse_args(input)?;
+        config::current().ensure_crate_allowed(&args.crate_name)?;
+        let mut docs = runtime::blocking(|| {
+            registry::in_registry(&args.crate_name, args.registry.as_deref(), || {
+                self.fetch_docs(&args.crate_name, &args.derive_name, args.version.as_deref())
+            })
+        })?;
+
+        docs.full_docs = summary::shorten(
+            &format!("Docs of {}::{}", docs.crate_name, docs.name),
+            &mut docs.docs,
+        );
+
+        Ok(CallToolResponse {
+            content: vec![ToolResponseContent::Text {
+                text: serde_json::to_string_pretty(&docs)?,
+            }],
+            is_error: None,
+            meta: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::fs;
+
+    #[test]
+    fn test_parse_derive_page() -> Result<()> {
+        let html = fs::read_to_string("test-data/derive_macro_docs/derive-page.html")?;
+        let (helpers, attributes, docs) = parse_derive_page(&html)?;
+
+        assert_eq!(helpers, vec!["builder".to_string()]);
+        let documented: Vec<(&str, Option<&str>)> = attributes
+            .iter()
+            .map(|doc| (doc.attribute(), doc.section()))
+            .collect();
+        assert_eq!(
+            documented,
+            vec![
+                ("#[builder(name = \"...\")]", Some("Container attributes")),
+                ("#[builder(default)]", Some("Field attributes")),
+                ("#[builder(skip)]", Some("Field attributes")),
+            ],
+            "Attributes in the example and other attributes are left out"
+        );
+        assert_eq!(
+            attributes[1].description,
+            "#[builder(default)] Uses the field type's Default value when the setter isn't called."
+        );
+        assert!(docs.starts_with("Derives a builder for a struct."));
+        Ok(())
+    }
+}
@@ -0,0 +1,186 @@
+//! Reading the `doc_cfg` portability banners rustdoc renders on items, such as
+//! "Available on crate feature `rt` only." or "Available on docsrs only."
+
+use super::selectors;
+use scraper::ElementRef;
+use std::collections::BTreeSet;
+
+/// Banner of the item a page documents, as opposed to those of its methods
+pub(crate) const ITEM_BANNER: &str = "#main-content > .item-info .stab.portability";
+
+/// Banner of a method or associated item, relative to its toggle block
+pub(crate) const MEMBER_BANNER: &str = ".item-info .stab.portability";
+
+/// Text of the first portability banner matching `selector` within `scope`.
+pub(crate) fn banner(scope: ElementRef, selector: &str) -> Option<String> {
+    let selector = selectors::parse(selector, "item page").ok()?;
+    let text = scope.select(&selector).next()?.text().collect::<String>();
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    (!text.is_empty()).then_some(text)
+}
+
+/// Whether a banner says the item only exists when `cfg(docsrs)` is set, i.e. it is a shim
+/// for the documentation build that a normal build of the crate doesn't have.
+///
+/// Accepts both the rendered banner (`Available on docsrs only.`) and the badge title used in
+/// item tables (``Available on `docsrs` only``). Every alternative of an `or` has to require
+/// `docsrs`; `non-docsrs` doesn't count.
+pub(crate) fn is_docs_only(banner: &str) -> bool {
+    let condition = banner.replace('`', "");
+    let condition = condition.trim();
+    let condition = condition.strip_prefix("Available on ").unwrap_or(condition);
+    let condition = condition
+        .trim_end_matches('.')
+        .trim_end_matches(" only")
+        .trim();
+
+    !condition.is_empty()
+        && condition.split(" or ").all(|alternative| {
+            alternative
+                .split(|c: char| !(c.is_alphanumeric() || c == '_' || c == '-'))
+                .any(|word| word == "docsrs")
+        })
+}
+
+/// Cargo features a banner mentions, e.g. `["rt", "net"]` for "Available on crate features rt
+/// and net only.". Negated features (`non-crate feature x`) aren't required and are skipped.
+pub(crate) fn required_features(banner: &str) -> Vec<String> {
+    let condition = banner.replace('`', "");
+    let is_name_char = |c: char| c.is_alphanumeric() || c == '_' || c == '-';
+    let mut features: Vec<String> = Vec::new();
+
+    let mut rest = condition.as_str();
+    while let Some(start) = rest.find("crate feature") {
+        let negated = rest[..start].ends_with("non-");
+        rest = &rest[start + "crate feature".len()..];
+        // "crate features a, b and c" lists several, all of them features
+        let plural = rest.starts_with('s');
+        rest = rest.trim_start_matches('s').trim_start();
+
+        loop {
+            let end = rest.find(|c| !is_name_char(c)).unwrap_or(rest.len());
+            let name = &rest[..end];
+            if !name.is_empty() && !negated && !features.iter().any(|f| f == name) {
+                features.push(name.to_string());
+            }
+            rest = &rest[end..];
+            if !plural {
+                break;
+            }
+            let Some(next) = [", and ", ", or ", " and ", " or ", ", "]
+                .iter()
+                .find_map(|separator| rest.strip_prefix(separator))
+            else {
+                break;
+            };
+            rest = next;
+        }
+    }
+    features
+}
+
+/// `features` spelled as the crate declares them, as Cargo only takes a feature name as
+/// written: a hand-written `doc(cfg(feature = "rt_multi_thread"))` banner gives
+/// `rt-multi-thread` when that's the declared name. Features the crate doesn't declare are
+/// kept as the banner has them.
+pub(crate) fn canonical_features(features: &[String], declared: &BTreeSet<String>) -> Vec<String> {
+    let key = |name: &str| name.replace('_', "-").to_lowercase();
+    let mut canonical: Vec<String> = Vec::new();
+    for feature in features {
+        let name = match declared.contains(feature) {
+            true => feature,
+            false => declared
+                .iter()
+                .find(|name| key(name) == key(feature))
+                .unwrap_or(feature),
+        };
+        if !canonical.contains(name) {
+            canonical.push(name.clone());
+        }
+    }
+    canonical
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use scraper::Html;
+    use std::fs;
+
+    #[test]
+    fn test_is_docs_only() {
+        let cases = [
+            ("Available on docsrs only.", true),
+            ("Available on `docsrs` only", true),
+            ("Available on docsrs and crate feature rt only.", true),
+            ("Available on crate feature rt or docsrs only.", false),
+            ("Available on docsrs or (docsrs and Unix) only.", true),
+            ("Available on non-docsrs only.", false),
+            ("Available on crate feature `udp` only", false),
+            ("", false),
+        ];
+
+        for (banner, expected) in cases {
+            assert_eq!(
+                is_docs_only(banner),
+                expected,
+                "Wrong result for `{}`",
+                banner
+            );
+        }
+    }
+
+    #[test]
+    fn test_required_features() {
+        let cases: [(&str, &[&str]); 7] = [
+            ("Available on crate feature trace only.", &["trace"]),
+            ("Available on crate feature `udp` only", &["udp"]),
+            (
+                "Available on crate features rt and net only.",
+                &["rt", "net"],
+            ),
+            (
+                "Available on crate features fs, io-util, or process only.",
+                &["fs", "io-util", "process"],
+            ),
+            ("Available on crate feature rt and Unix only.", &["rt"]),
+            ("Available on non-crate feature std only.", &[]),
+            ("Available on docsrs only.", &[]),
+        ];
+
+        for (banner, expected) in cases {
+            assert_eq!(
+                required_features(banner),
+                expected,
+                "Wrong features for `{}`",
+                banner
+            );
+        }
+    }
+
+    #[test]
+    fn test_canonical_features() {
+        let declared: BTreeSet<String> = ["rt", "rt-multi-thread", "io_util"]
+            .map(str::to_string)
+            .into();
+        let features = ["rt_multi_thread", "rt", "io-util", "RT", "tracing"].map(str::to_string);
+        assert_eq!(
+            canonical_features(&features, &declared),
+            ["rt-multi-thread", "rt", "io_util", "tracing"]
+        );
+    }
+
+    #[test]
+    fn test_item_banner() {
+        let html = fs::read_to_string(
+            "test-data/get_struct_docs/opentelemetry-sdk-0.28.0-tracer-provider-builder.html",
+        )
+        .expect("Should be able to read test HTML file");
+        let document = Html::parse_document(&html);
+
+        assert_eq!(
+            banner(document.root_element(), ITEM_BANNER).as_deref(),
+            Some("Available on crate feature trace only.")
+        );
+    }
+}
@@ -0,0 +1,445 @@
+//! How fresh the documentation served for a release is: when docs.rs built it and with which
+//! rustdoc, from the page itself and docs.rs' `/crate/{name}/{version}/builds.json`, along with
+//! the newest release on crates.io when the documented one is behind it.
+//!
+//! docs.rs serves `latest` from the newest release it built, so a release whose build is still
+//! queued or failed leaves `latest` on an older one. When that happens to a lookup of `latest`,
+//! the build says what became of the newest release's documentation.
+
+use anyhow::{anyhow, Context, Result};
+use scraper::Html;
+use semver::Version;
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+use super::{crates_io, dates, http, selectors};
+use crate::config;
+
+/// The docs.rs build behind the documentation of a release
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DocsBuild {
+    /// The release documented
+    version: String,
+    /// When docs.rs built the documentation, as an RFC 3339 timestamp, e.g.
+    /// `2024-01-05T10:20:30Z`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    built_at: Option<String>,
+    /// The rustdoc that rendered it, e.g. `1.77.0-nightly (bf8716f1c 2023-12-24)`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    rustdoc_version: Option<String>,
+    /// The newest release on crates.io, when it is newer than the documented one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    latest_release: Option<String>,
+    /// What docs.rs has for `latest_release` when `latest` was asked for; `version` is then the
+    /// nearest release with documentation
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    latest_release_docs: Option<LatestReleaseDocs>,
+}
+
+/// What docs.rs has for a release newer than the one `latest` serves
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LatestReleaseDocs {
+    /// Built, but `latest` hasn't caught up yet; the release can be asked for by version
+    Built,
+    /// Not built yet, the build is queued or running
+    Pending,
+    /// Every build of the release failed
+    Failed,
+}
+
+impl DocsBuild {
+    pub fn version(&self) -> &str {
+        &self.version
+    }
+
+    pub fn built_at(&self) -> Option<&str> {
+        self.built_at.as_deref()
+    }
+
+    pub fn rustdoc_version(&self) -> Option<&str> {
+        self.rustdoc_version.as_deref()
+    }
+
+    pub fn latest_release(&self) -> Option<&str> {
+        self.latest_release.as_deref()
+    }
+
+    pub fn latest_release_docs(&self) -> Option<LatestReleaseDocs> {
+        self.latest_release_docs
+    }
+
+    /// One sentence on the build, e.g. "Docs of 1.2.0 built 2024-01-05T10:20:30Z with rustdoc
+    /// 1.77.0; 1.3.0 is out.", with a warning when `latest` documented an older release than
+    /// the newest.
+    pub(crate) fn describe(&self) -> String {
+        let mut sentence = format!("Docs of {}", self.version);
+        if let Some(built_at) = &self.built_at {
+            sentence.push_str(&format!(" built {}", built_at));
+        }
+        if let Some(rustdoc) = &self.rustdoc_version {
+            sentence.push_str(&format!(" with rustdoc {}", rustdoc));
+        }
+        let Some(latest) = &self.latest_release else {
+            sentence.push('.');
+            return sentence;
+        };
+        sentence.push_str(&format!("; {} is out", latest));
+        let nearest = format!(
+            "so docs.rs' latest documentation is still that of {}, the nearest release with docs",
+            self.version
+        );
+        match self.latest_release_docs {
+            Some(LatestReleaseDocs::Built) => sentence.push_str(&format!(
+                ". Its docs are built, but docs.rs' latest hasn't caught up: ask for version {}.",
+                latest
+            )),
+            Some(LatestReleaseDocs::Pending) => sentence.push_str(&format!(
+                ". **Warning:** docs.rs hasn't built its docs yet, {}; ask for {} again later.",
+                nearest, latest
+            )),
+            Some(LatestReleaseDocs::Failed) => sentence.push_str(&format!(
+                ". **Warning:** docs.rs failed to build its docs, {}.",
+                nearest
+            )),
+            None => sentence.push('.'),
+        }
+        sentence
+    }
+}
+
+/// What a rendered page of the documentation says about its build
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct PageBuild {
+    /// The crate version in the sidebar
+    version: Option<String>,
+    /// `data-rustdoc-version` of the page's rustdoc variables
+    rustdoc_version: Option<String>,
+}
+
+impl PageBuild {
+    /// What a page documenting `version` would say, for lookups that read no page
+    pub(crate) fn of_release(version: &str) -> Self {
+        Self {
+            version: Some(version.to_string()),
+            rustdoc_version: None,
+        }
+    }
+
+    #[cfg(test)]
+    pub(crate) fn rustdoc_version(&self) -> Option<&str> {
+        self.rustdoc_version.as_deref()
+    }
+
+    /// The release the page documents when `requested` is `latest`, which docs.rs serves
+    /// without redirecting to a versioned URL; otherwise `requested` itself.
+    pub(crate) fn served_version(&self, requested: &str) -> String {
+        match &self.version {
+            Some(version) if requested == "latest" => version.clone(),
+            _ => requested.to_string(),
+        }
+    }
+}
+
+/// Reads the documented version and the rustdoc version off a documentation page.
+pub(crate) fn from_page(document: &Html) -> PageBuild {
+    let attr = |selector: &str, read: fn(scraper::ElementRef) -> Option<String>| {
+        selectors::parse(selector, "item page")
+            .ok()
+            .and_then(|selector| document.select(&selector).find_map(read))
+            .filter(|value| !value.is_empty())
+    };
+    PageBuild {
+        version: attr(".sidebar-crate .version, .sidebar .version", |version| {
+            Some(version.text().collect::<String>().trim().to_string())
+        }),
+        rustdoc_version: attr("#rustdoc-vars, meta[name='rustdoc-vars']", |vars| {
+            Some(
+                vars.value()
+                    .attr("data-rustdoc-version")?
+                    .trim()
+                    .to_string(),
+            )
+        }),
+    }
+}
+
+/// The build of the `requested` version of the crate, asking docs.rs for what `page` doesn't
+/// tell. Returns `None` when neither docs.rs nor crates.io could be asked.
+pub(crate) async fn fetch(crate_name: &str, requested: &str, page: PageBuild) -> Option<DocsBuild> {
+    let version = page.served_version(requested);
+    let build = latest_build(crate_name, &version)
+        .await
+        .unwrap_or_else(|e| {
+            debug!(
+                "No docs.rs build information for {} {}: {:#}",
+                crate_name, version, e
+            );
+            None
+        });
+    let latest = crates_io::latest_version(crate_name)
+        .await
+        .map_err(|e| debug!("No latest release of {}: {:#}", crate_name, e))
+        .ok();
+    if build.is_none() && latest.is_none() && page.rustdoc_version.is_none() {
+        return None;
+    }
+    let latest_release = latest.filter(|latest| is_newer(latest, &version));
+    let latest_release_docs = match &latest_release {
+        Some(latest) if requested == "latest" => builds(crate_name, latest)
+            .await
+            .map(|builds| release_docs(builds.as_deref()))
+            .map_err(|e| debug!("No docs.rs builds of {} {}: {:#}", crate_name, latest, e))
+            .ok(),
+        _ => None,
+    };
+
+    let (built_at, rustc_version) = build.map_or((None, None), |build| {
+        let built_at = build
+            .build_time
+            .map(|time| dates::timestamp(&time).unwrap_or(time));
+        (built_at, build.rustc_version.map(rustdoc_version))
+    });
+    Some(DocsBuild {
+        latest_release,
+        latest_release_docs,
+        version,
+        built_at,
+        rustdoc_version: page.rustdoc_version.or(rustc_version),
+    })
+}
+
+/// An entry of docs.rs' `builds.json`, reduced to what we use
+#[derive(Debug, Deserialize)]
+struct Build {
+    #[serde(default)]
+    rustc_version: Option<String>,
+    /// `"success"`, or `true` in older answers
+    #[serde(default)]
+    build_status: serde_json::Value,
+    #[serde(default)]
+    build_time: Option<String>,
+}
+
+impl Build {
+    fn succeeded(&self) -> bool {
+        self.build_status == "success" || self.build_status == true
+    }
+
+    fn in_progress(&self) -> bool {
+        self.build_status == "in_progress"
+    }
+}
+
+async fn latest_build(crate_name: &str, version: &str) -> Result<Option<Build>> {
+    let builds = builds(crate_name, version)
+        .await?
+        .ok_or_else(|| anyhow!("docs.rs doesn't know {} {}", crate_name, version))?;
+    Ok(newest_success(builds))
+}
+
+/// docs.rs' builds of `version` of the crate, `None` when it has none to tell of
+async fn builds(crate_name: &str, version: &str) -> Result<Option<Vec<Build>>> {
+    let url = format!(
+        "{}/crate/{}/{}/builds.json",
+        config::current().sources().docs_rs_url(),
+        crate_name,
+        version
+    );
+    let response = http::shared()?
+        .get(&url)
+        .await
+        .context(format!("Failed to fetch URL: {}", url))?;
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        return Ok(None);
+    }
+    if !response.status().is_success() {
+        return Err(anyhow!(
+            "Failed to fetch docs.rs builds: {} - {}",
+            response.status(),
+            url
+        ));
+    }
+    let body = response
+        .text()
+        .await
+        .context(format!("Failed to get text from response for URL: {}", url))?;
+    parse_builds(&body).map(Some)
+}
+
+fn parse_builds(body: &str) -> Result<Vec<Build>> {
+    serde_json::from_str(body).context("Failed to parse docs.rs builds response")
+}
+
+/// The most recent successful build; build times are RFC 3339 in UTC, so they sort as text.
+fn newest_success(builds: Vec<Build>) -> Option<Build> {
+    builds
+        .into_iter()
+        .filter(Build::succeeded)
+        .max_by(|a, b| a.build_time.cmp(&b.build_time))
+}
+
+/// What the builds of a release, `None` when docs.rs knows none, left of its documentation.
+/// A release docs.rs doesn't know yet is waiting in its queue.
+fn release_docs(builds: Option<&[Build]>) -> LatestReleaseDocs {
+    let builds = builds.unwrap_or_default();
+    if builds.iter().any(Build::succeeded) {
+        LatestReleaseDocs::Built
+    } else if builds.is_empty() || builds.iter().any(Build::in_progress) {
+        LatestReleaseDocs::Pending
+    } else {
+        LatestReleaseDocs::Failed
+    }
+}
+
+/// `1.77.0-nightly (bf8716f1c 2023-12-24)` from `rustc 1.77.0-nightly (bf8716f1c 2023-12-24)`,
+/// as docs.rs documents with the rustdoc of the rustc it builds with
+fn rustdoc_version(rustc_version: String) -> String {
+    match rustc_version.strip_prefix("rustc ") {
+        Some(version) => version.to_string(),
+        None => rustc_version,
+    }
+}
+
+fn is_newer(latest: &str, documented: &str) -> bool {
+    match (Version::parse(latest), Version::parse(documented)) {
+        (Ok(latest), Ok(documented)) => latest > documented,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_from_page() {
+        let document = Html::parse_document(
+            r#"<html><head>
+                <meta name="rustdoc-vars" data-root-path="../" data-current-crate="demo"
+                    data-rustdoc-version="1.77.0-nightly (bf8716f1c 2023-12-24)">
+            </head><body>
+                <nav class="sidebar"><div class="sidebar-crate">
+                    <h2><a href="../demo/index.html">demo</a><span class="version">0.3.1</span></h2>
+                </div></nav>
+            </body></html>"#,
+        );
+        assert_eq!(
+            from_page(&document),
+            PageBuild {
+                version: Some("0.3.1".to_string()),
+                rustdoc_version: Some("1.77.0-nightly (bf8716f1c 2023-12-24)".to_string()),
+            }
+        );
+        assert_eq!(from_page(&document).served_version("latest"), "0.3.1");
+        assert_eq!(from_page(&document).served_version("0.3.0"), "0.3.0");
+        assert_eq!(
+            from_page(&Html::parse_document("<html></html>")),
+            PageBuild::default()
+        );
+        assert_eq!(PageBuild::default().served_version("latest"), "latest");
+    }
+
+    #[test]
+    fn test_parse_builds() -> Result<()> {
+        let body = r#"[
+            {"id": 3, "rustc_version": "rustc 1.79.0-nightly (aaaaaaaaa 2024-04-20)",
+             "docs_rs_version": "docsrs 0.6.0", "build_status": "failure",
+             "build_time": "2024-04-21T08:00:00Z"},
+            {"id": 2, "rustc_version": "rustc 1.77.0-nightly (bf8716f1c 2023-12-24)",
+             "docs_rs_version": "docsrs 0.6.0", "build_status": "success",
+             "build_time": "2024-01-05T10:20:30Z"},
+            {"id": 1, "rustc_version": "rustc 1.70.0-nightly (cccccccc 2023-03-01)",
+             "docs_rs_version": "docsrs 0.6.0", "build_status": true,
+             "build_time": "2023-03-02T00:00:00Z"}
+        ]"#;
+        let build = newest_success(parse_builds(body)?).expect("a successful build");
+        assert_eq!(
+            build.build_time.as_deref(),
+            Some("2024-01-05T10:20:30Z"),
+            "The newest successful build, not the failed rebuild"
+        );
+        assert_eq!(
+            rustdoc_version(build.rustc_version.unwrap()),
+            "1.77.0-nightly (bf8716f1c 2023-12-24)"
+        );
+
+        assert!(newest_success(parse_builds("[]")?).is_none());
+        assert!(parse_builds("<html>Not Found</html>").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_release_docs() -> Result<()> {
+        let build = |status: &str| format!(r#"{{"build_status": "{}"}}"#, status);
+        let builds = |statuses: &[&str]| -> Result<Vec<Build>> {
+            let builds: Vec<String> = statuses.iter().map(|status| build(status)).collect();
+            parse_builds(&format!("[{}]", builds.join(",")))
+        };
+        assert_eq!(
+            release_docs(Some(&builds(&["failure", "success"])?)),
+            LatestReleaseDocs::Built
+        );
+        assert_eq!(
+            release_docs(Some(&builds(&["failure"])?)),
+            LatestReleaseDocs::Failed
+        );
+        assert_eq!(
+            release_docs(Some(&builds(&["failure", "in_progress"])?)),
+            LatestReleaseDocs::Pending,
+            "A rebuild may still succeed"
+        );
+        assert_eq!(release_docs(Some(&[])), LatestReleaseDocs::Pending);
+        assert_eq!(
+            release_docs(None),
+            LatestReleaseDocs::Pending,
+            "Releases docs.rs doesn't know yet are queued"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_describe() {
+        let build = DocsBuild {
+            version: "1.2.0".to_string(),
+            built_at: Some("2024-01-05T10:20:30Z".to_string()),
+            rustdoc_version: None,
+            latest_release: Some("1.3.0".to_string()),
+            latest_release_docs: None,
+        };
+        assert_eq!(
+            build.describe(),
+            "Docs of 1.2.0 built 2024-01-05T10:20:30Z; 1.3.0 is out."
+        );
+
+        let failed = DocsBuild {
+            latest_release_docs: Some(LatestReleaseDocs::Failed),
+            ..build.clone()
+        };
+        assert_eq!(
+            failed.describe(),
+            "Docs of 1.2.0 built 2024-01-05T10:20:30Z; 1.3.0 is out. **Warning:** docs.rs failed \
+             to build its docs, so docs.rs' latest documentation is still that of 1.2.0, the \
+             nearest release with docs."
+        );
+        let pending = DocsBuild {
+            latest_release_docs: Some(LatestReleaseDocs::Pending),
+            ..build.clone()
+        };
+        assert!(pending.describe().ends_with("ask for 1.3.0 again later."));
+        let built = DocsBuild {
+            latest_release_docs: Some(LatestReleaseDocs::Built),
+            ..build
+        };
+        assert!(built.describe().ends_with("ask for version 1.3.0."));
+    }
+
+    #[test]
+    fn test_is_newer() {
+        assert!(is_newer("1.10.0", "1.9.3"));
+        assert!(!is_newer("1.9.3", "1.9.3"));
+        assert!(!is_newer("1.9.3", "2.0.0-rc.1"));
+        assert!(!is_newer("1.0.0", "latest"));
+    }
+}
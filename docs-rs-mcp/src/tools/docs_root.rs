@@ -0,0 +1,182 @@
+//! Locating the root module of a crate's documentation. It usually lives at
+//! `/{crate}/{version}/{crate}/`, with hyphens turned into underscores, but crates whose
+//! library target is renamed (`[lib] name = "..."`) are documented under the library's name, so
+//! the root is taken from the redirect docs.rs answers `/{crate}/{version}/` with.
+
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+
+use anyhow::{anyhow, Context, Result};
+use tracing::debug;
+use url::Url;
+
+use super::http;
+#[cfg(feature = "native")]
+use super::runtime;
+use crate::config;
+
+/// URL of the crate's documentation root, ending in a slash, e.g.
+/// `https://docs.rs/tokio/1.43.0/tokio/`. Falls back to the conventional root when docs.rs
+/// can't be asked.
+pub(crate) async fn crate_root(crate_name: &str, version: &str) -> String {
+    let docs_rs_url = config::current().sources().docs_rs_url().to_string();
+    let version_url = format!("{}/{}/{}/", docs_rs_url, crate_name, version);
+
+    if let Some(root) = roots()
+        .lock()
+        .ok()
+        .and_then(|roots| roots.get(&version_url).cloned())
+    {
+        return root;
+    }
+    match resolve(&docs_rs_url, crate_name, &version_url).await {
+        Ok(root) => {
+            debug!(
+                "Documentation root of {} {} is {}",
+                crate_name, version, root
+            );
+            if let Ok(mut roots) = roots().lock() {
+                roots.insert(version_url, root.clone());
+            }
+            root
+        }
+        Err(e) => {
+            debug!("Assuming the conventional root for {}: {:#}", crate_name, e);
+            format!("{}{}/", version_url, crate_name.replace('-', "_"))
+        }
+    }
+}
+
+/// Like [`crate_root`], blocking until docs.rs answers, for tools that do their lookups
+/// synchronously.
+#[cfg(feature = "native")]
+pub(crate) fn crate_root_blocking(crate_name: &str, version: &str) -> Result<String> {
+    runtime::block_on(async { Ok(crate_root(crate_name, version).await) })
+}
+
+/// Name the crate's library is documented under: the last segment of its root.
+pub(crate) fn lib_name(root: &str) -> &str {
+    root.trim_end_matches('/')
+        .rsplit('/')
+        .next()
+        .unwrap_or_default()
+}
+
+/// Roots resolved so far, keyed by the version URL they were resolved from
+fn roots() -> &'static Mutex<HashMap<String, String>> {
+    static ROOTS: OnceLock<Mutex<HashMap<String, String>>> = OnceLock::new();
+    ROOTS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+async fn resolve(docs_rs_url: &str, crate_name: &str, version_url: &str) -> Result<String> {
+    let response = http::shared()?
+        .get(version_url)
+        .await
+        .context(format!("Failed to fetch URL: {}", version_url))?;
+    if !response.status().is_success() {
+        return Err(anyhow!(
+            "Failed to fetch docs.rs page: {} - {}",
+            response.status(),
+            version_url
+        ));
+    }
+    root_from_redirect(docs_rs_url, crate_name, response.url())
+}
+
+/// Root of the documentation page docs.rs redirected to:
+/// `https://docs.rs/foo-bar/1.0.0/foo/index.html` -> `https://docs.rs/foo-bar/1.0.0/foo/`.
+/// The version segment is kept as redirected to, as docs.rs resolves partial versions.
+fn root_from_redirect(docs_rs_url: &str, crate_name: &str, target: &Url) -> Result<String> {
+    let base_depth = Url::parse(docs_rs_url)
+        .context(format!("Invalid docs.rs URL: {}", docs_rs_url))?
+        .path_segments()
+        .map_or(0, |segments| segments.filter(|s| !s.is_empty()).count());
+    let segments: Vec<&str> = target
+        .path_segments()
+        .map(|segments| segments.filter(|s| !s.is_empty()).collect())
+        .unwrap_or_default();
+
+    // Crates without documentation redirect to their `/crate/{name}/{version}` page instead
+    match segments.get(base_depth..base_depth + 3) {
+        Some([name, _, lib]) if *name == crate_name && !lib.ends_with(".html") => {
+            let mut root = target.clone();
+            root.set_path(&format!("{}/", segments[..base_depth + 3].join("/")));
+            root.set_query(None);
+            root.set_fragment(None);
+            Ok(root.to_string())
+        }
+        _ => Err(anyhow!(
+            "{} has no documentation root at {}",
+            crate_name,
+            target
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn root(docs_rs_url: &str, crate_name: &str, target: &str) -> Option<String> {
+        root_from_redirect(docs_rs_url, crate_name, &Url::parse(target).unwrap()).ok()
+    }
+
+    #[test]
+    fn test_root_from_redirect() {
+        assert_eq!(
+            root(
+                "https://docs.rs",
+                "tokio",
+                "https://docs.rs/tokio/1.43.0/tokio/index.html"
+            )
+            .as_deref(),
+            Some("https://docs.rs/tokio/1.43.0/tokio/")
+        );
+        assert_eq!(
+            root(
+                "https://docs.rs",
+                "rustc-demangle",
+                "https://docs.rs/rustc-demangle/latest/rustc_demangle/"
+            )
+            .as_deref(),
+            Some("https://docs.rs/rustc-demangle/latest/rustc_demangle/")
+        );
+        assert_eq!(
+            root(
+                "https://docs.rs",
+                "new_debug_unreachable",
+                "https://docs.rs/new_debug_unreachable/1.0.6/debug_unreachable/index.html"
+            )
+            .as_deref(),
+            Some("https://docs.rs/new_debug_unreachable/1.0.6/debug_unreachable/"),
+            "Renamed libraries are documented under the library name"
+        );
+        assert_eq!(
+            root(
+                "http://localhost:3000/docs",
+                "demo",
+                "http://localhost:3000/docs/demo/0.1.0/demo/index.html"
+            )
+            .as_deref(),
+            Some("http://localhost:3000/docs/demo/0.1.0/demo/")
+        );
+        assert_eq!(
+            root(
+                "https://docs.rs",
+                "broken",
+                "https://docs.rs/crate/broken/0.1.0"
+            ),
+            None,
+            "Crates whose docs failed to build have no root"
+        );
+    }
+
+    #[test]
+    fn test_lib_name() {
+        assert_eq!(
+            lib_name("https://docs.rs/new_debug_unreachable/1.0.6/debug_unreachable/"),
+            "debug_unreachable"
+        );
+    }
+}
@@ -0,0 +1,313 @@
+use anyhow::Result;
+use mcp_sdk::{
+    tools::Tool,
+    types::{CallToolResponse, ToolResponseContent},
+};
+use scraper::{ElementRef, Html};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tracing::{debug, warn};
+
+use super::get_struct_docs::{code_header_text, impl_trait_name, HtmlFetcher, HttpHtmlFetcher};
+use super::{markdown, registry, runtime, selectors, SearchInCrateTool};
+use crate::{config, error};
+
+/// Item pages are read to find `Error` impls and variants; crates with more candidate types
+/// than this are listed without the rest
+const MAX_PAGES: usize = 40;
+
+/// Kinds of items that can be error types
+const ERROR_KINDS: [&str; 3] = ["struct", "enum", "type"];
+
+/// The error types of a crate version
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ErrorTypes {
+    crate_name: String,
+    version: String,
+    error_types: Vec<ErrorType>,
+    /// Whether some candidate types were left unread because of the page limit
+    truncated: bool,
+}
+
+impl ErrorTypes {
+    pub fn error_types(&self) -> &[ErrorType] {
+        &self.error_types
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ErrorType {
+    path: String,
+    /// Rustdoc item kind: `struct`, `enum` or `type`
+    kind: String,
+    doc_link: String,
+    /// Whether the page lists an impl of `std::error::Error`
+    implements_error: bool,
+    /// The type's documentation as Markdown
+    docs: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    variants: Vec<ErrorVariant>,
+}
+
+impl ErrorType {
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    pub fn implements_error(&self) -> bool {
+        self.implements_error
+    }
+
+    pub fn variants(&self) -> &[ErrorVariant] {
+        &self.variants
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ErrorVariant {
+    name: String,
+    /// The variant as declared, e.g. `InvalidDigit(char)` or `Parse { line: usize }`
+    signature: String,
+    docs: Option<String>,
+}
+
+impl ErrorVariant {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ErrorTypesParams {
+    crate_name: String,
+    version: Option<String>,
+    registry: Option<String>,
+}
+
+pub struct ErrorTypesTool {
+    html_fetcher: Box<dyn HtmlFetcher>,
+}
+
+impl ErrorTypesTool {
+    pub fn new() -> Self {
+        Self {
+            html_fetcher: Box::new(HttpHtmlFetcher::new()),
+        }
+    }
+
+    /// Finds candidate types through the search index: everything named `*Error`, then the
+    /// remaining enums, which are kept when their page shows an `Error` impl.
+    fn list_error_types(&self, crate_name: &str, version: Option<&str>) -> Result<ErrorTypes> {
+        let index = SearchInCrateTool::new().fetch_index(crate_name, version)?;
+        let (named, enums): (Vec<_>, Vec<_>) = index
+            .iter()
+            .filter(|item| ERROR_KINDS.contains(&item.kind()))
+            .filter(|item| item.name().ends_with("Error") || item.kind() == "enum")
+            .partition(|item| item.name().ends_with("Error"));
+        debug!(
+            "{} has {} types named *Error and {} other enums",
+            crate_name,
+            named.len(),
+            enums.len()
+        );
+
+        let candidates: Vec<_> = named.into_iter().chain(enums).collect();
+        let truncated = candidates.len() > MAX_PAGES;
+        let mut error_types = Vec::new();
+        for item in candidates.into_iter().take(MAX_PAGES) {
+            let html = match self.html_fetcher.fetch_html_blocking(item.doc_link()) {
+                Ok(html) => html,
+                Err(e) => {
+                    warn!("Failed to fetch {}: {}", item.doc_link(), e);
+                    continue;
+                }
+            };
+            let (implements_error, docs, variants) = parse_error_page(&html)?;
+            if !item.name().ends_with("Error") && !implements_error {
+                continue;
+            }
+            error_types.push(ErrorType {
+                path: item.path().to_string(),
+                kind: item.kind().to_string(),
+                doc_link: item.doc_link().to_string(),
+                implements_error,
+                docs,
+                variants,
+            });
+        }
+
+        Ok(ErrorTypes {
+            crate_name: crate_name.to_string(),
+            version: version.unwrap_or("latest").to_string(),
+            error_types,
+            truncated,
+        })
+    }
+}
+
+impl Default for ErrorTypesTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Reads whether an item page shows an `Error` impl, along with its docs and variants.
+fn parse_error_page(html: &str) -> Result<(bool, String, Vec<ErrorVariant>)> {
+    let document = Html::parse_document(html);
+    let docblock_selector =
+        selectors::parse("#main-content .toggle.top-doc .docblock", "error type page")?;
+    let impl_selector = selectors::parse(
+        "#trait-implementations-list > details > summary h3.code-header",
+        "error type page",
+    )?;
+    let error_trait_selector = selectors::parse(
+        r#"a.trait[href$="error/trait.Error.html"]"#,
+        "error type page",
+    )?;
+
+    // The trait link tells `std::error::Error` apart from traits that share its name, such as
+    // `serde::de::Error`
+    let implements_error = document.select(&impl_selector).any(|header| {
+        impl_trait_name(&code_header_text(header)).as_deref() == Some("Error")
+            && header.select(&error_trait_selector).next().is_some()
+    });
+
+    let docs = document
+        .select(&docblock_selector)
+        .next()
+        .map(markdown::element_to_markdown)
+        .unwrap_or_default();
+    Ok((implements_error, docs, parse_variants(&document)?))
+}
+
+/// Reads the `section.variant`s of an enum page. A variant's docs follow its section as a
+/// sibling `div.docblock`.
+fn parse_variants(document: &Html) -> Result<Vec<ErrorVariant>> {
+    let variant_selector = selectors::parse("#main-content section.variant", "error type page")?;
+    let header_selector = selectors::parse("h3.code-header", "error type page")?;
+
+    let mut variants = Vec::new();
+    for section in document.select(&variant_selector) {
+        let Some(name) = section
+            .value()
+            .id()
+            .and_then(|id| id.strip_prefix("variant."))
+        else {
+            continue;
+        };
+        let signature = section
+            .select(&header_selector)
+            .next()
+            .map(code_header_text)
+            .unwrap_or_else(|| name.to_string());
+        let docs = section
+            .next_siblings()
+            .filter_map(ElementRef::wrap)
+            .take_while(|sibling| sibling.value().name() != "section")
+            .find(|sibling| sibling.value().classes().any(|class| class == "docblock"))
+            .map(|docblock| docblock.text().collect::<String>().trim().to_string())
+            .filter(|docs| !docs.is_empty());
+
+        variants.push(ErrorVariant {
+            name: name.to_string(),
+            signature,
+            docs,
+        });
+    }
+    Ok(variants)
+}
+
+impl Tool for ErrorTypesTool {
+    fn name(&self) -> String {
+        "error_types".to_string()
+    }
+
+    fn description(&self) -> String {
+        "List the error types of a crate: types whose names end in Error, and enums \
+        implementing std::error::Error. Returns each type's documentation, whether it \
+        implements std::error::Error, and the variants of enums with their docs."
+            .to_string()
+    }
+
+    fn input_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "crate_name": {
+                    "type": "string",
+                    "description": "Name of the crate to list error types of"
+                },
+                "version": {
+                    "type": "string",
+                    "description": "Optional version of the crate (defaults to latest)"
+                },
+                "registry": registry::registry_schema()
+            },
+            "required": ["crate_name"]
+        })
+    }
+
+    fn call(&self, input: Option<serde_json::Value>) -> Result<CallToolResponse> {
+        let args: ErrorTypesParams = error::parse_args(input)?;
+        config::current().ensure_crate_allowed(&args.crate_name)?;
+        let error_types = runtime::blocking(|| {
+            registry::in_registry(&args.crate_name, args.registry.as_deref(), || {
+                self.list_error_types(&args.crate_name, args.version.as_deref())
+            })
+        })?;
+
+        Ok(CallToolResponse {
+            content: vec![ToolResponseContent::Text {
+                text: serde_json::to_string_pretty(&error_types)?,
+            }],
+            is_error: None,
+            meta: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::fs;
+
+    #[test]
+    fn test_parse_error_page() -> Result<()> {
+        let html = fs::read_to_string("test-data/error_types/enum-page.html")?;
+        let (implements_error, docs, variants) = parse_error_page(&html)?;
+
+        assert!(implements_error);
+        assert_eq!(docs, "Errors returned when parsing a color.");
+        assert_eq!(
+            variants,
+            vec![
+                ErrorVariant {
+                    name: "Empty".to_string(),
+                    signature: "Empty".to_string(),
+                    docs: Some("The input was empty.".to_string()),
+                },
+                ErrorVariant {
+                    name: "InvalidDigit".to_string(),
+                    signature: "InvalidDigit(char)".to_string(),
+                    docs: Some(
+                        "The input contains a character that isn’t a hex digit.".to_string()
+                    ),
+                },
+                ErrorVariant {
+                    name: "Io".to_string(),
+                    signature: "Io(Error)".to_string(),
+                    docs: None,
+                },
+            ]
+        );
+
+        // Implementing a trait named Error, or Error showing up in a where clause, doesn't count
+        let html = html.replace(
+            r#"<h3 class="code-header">impl <a class="trait" href="https://doc.rust-lang.org/nightly/core/error/trait.Error.html" title="trait core::error::Error">Error</a> for <a class="enum" href="enum.ParseColorError.html" title="enum demo::ParseColorError">ParseColorError</a></h3>"#,
+            r#"<h3 class="code-header">impl <a class="trait" href="https://docs.rs/serde/1.0.217/serde/de/trait.Error.html" title="trait serde::de::Error">Error</a> for <a class="enum" href="enum.ParseColorError.html" title="enum demo::ParseColorError">ParseColorError</a></h3>"#,
+        );
+        assert!(!parse_error_page(&html)?.0);
+        Ok(())
+    }
+}
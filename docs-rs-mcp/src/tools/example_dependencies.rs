@@ -0,0 +1,231 @@
+//! Crates a documentation example uses besides the one it documents, read off its `use` and
+//! `extern crate` lines. Code refers to crates by their library name, `futures_util` for
+//! `futures-util`, so the names are resolved against the dependencies the documented crate
+//! declares, dev-dependencies included as examples are compiled as doctests with them.
+
+use semver::VersionReq;
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+use super::sparse_index::{self, IndexDependency};
+
+/// Roots of paths that aren't crates to depend on
+const NOT_CRATES: [&str; 8] = [
+    "std",
+    "core",
+    "alloc",
+    "proc_macro",
+    "test",
+    "crate",
+    "self",
+    "super",
+];
+
+/// A crate an example needs in Cargo.toml besides the documented one
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExampleDependency {
+    /// Name of the crate to depend on, e.g. `futures-util`
+    crate_name: String,
+    /// Name the example refers to the crate by, when it isn't `crate_name`, e.g. `futures_util`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    used_as: Option<String>,
+    /// Requirement the documented crate declares on it, e.g. `^0.3`. Missing when the
+    /// documented crate doesn't depend on it, so `crate_name` is only the name in the code.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    version: Option<String>,
+}
+
+impl ExampleDependency {
+    pub fn crate_name(&self) -> &str {
+        &self.crate_name
+    }
+
+    pub fn used_as(&self) -> Option<&str> {
+        self.used_as.as_deref()
+    }
+
+    pub fn version(&self) -> Option<&str> {
+        self.version.as_deref()
+    }
+
+    /// The line adding the crate to `[dependencies]`, `*` when its version isn't known
+    pub(crate) fn cargo_line(&self) -> String {
+        format!(
+            "{} = \"{}\"",
+            self.crate_name,
+            self.version.as_deref().unwrap_or("*")
+        )
+    }
+}
+
+/// The crates the examples of `version` of `crate_name`, whose library is `crate_ident`, use
+/// besides it, resolved against the dependencies it declares in the registry index. Names are
+/// kept as written when the index can't be read.
+pub(crate) fn for_examples<'a>(
+    crate_name: &str,
+    version: &str,
+    crate_ident: &str,
+    examples: impl IntoIterator<Item = &'a str>,
+) -> Vec<ExampleDependency> {
+    let mut idents: Vec<String> = Vec::new();
+    for code in examples {
+        for ident in imported_crates(code, crate_ident) {
+            if !idents.contains(&ident) {
+                idents.push(ident);
+            }
+        }
+    }
+    if idents.is_empty() {
+        return Vec::new();
+    }
+    let versions = sparse_index::fetch(crate_name).unwrap_or_else(|e| {
+        debug!(
+            "No dependencies of {} to resolve against: {:#}",
+            crate_name, e
+        );
+        Vec::new()
+    });
+    let release = VersionReq::parse(&format!("={}", version))
+        .ok()
+        .and_then(|req| sparse_index::select(&versions, &req))
+        .or_else(|| sparse_index::select(&versions, &VersionReq::STAR));
+    resolve(
+        &idents,
+        release.map(|release| release.deps()).unwrap_or_default(),
+    )
+}
+
+/// Crates the `use` and `extern crate` lines of `code` start from, in order of first use,
+/// other than `crate_ident`, the standard library and the modules the example declares.
+pub(crate) fn imported_crates(code: &str, crate_ident: &str) -> Vec<String> {
+    let declared: Vec<&str> = code
+        .lines()
+        .filter_map(|line| {
+            let line = line.trim().strip_prefix("pub ").unwrap_or(line.trim());
+            let name = line.strip_prefix("mod ")?;
+            Some(name.trim_end_matches([';', '{', ' ']))
+        })
+        .collect();
+
+    let mut crates: Vec<String> = Vec::new();
+    for line in code.lines() {
+        let line = line.trim();
+        let line = line.strip_prefix("pub ").unwrap_or(line);
+        let root = if let Some(path) = line.strip_prefix("use ") {
+            path.trim_start_matches("::")
+                .split(|c: char| !(c.is_alphanumeric() || c == '_'))
+                .next()
+        } else if let Some(name) = line.strip_prefix("extern crate ") {
+            name.split(|c: char| !(c.is_alphanumeric() || c == '_'))
+                .next()
+        } else {
+            None
+        };
+        let Some(root) = root.filter(|root| !root.is_empty()) else {
+            continue;
+        };
+        // Enum variants and associated items are imported from types, not crates
+        let is_type = root.starts_with(|c: char| c.is_uppercase());
+        if is_type
+            || root == crate_ident
+            || NOT_CRATES.contains(&root)
+            || declared.contains(&root)
+            || crates.iter().any(|known| known == root)
+        {
+            continue;
+        }
+        crates.push(root.to_string());
+    }
+    crates
+}
+
+/// Resolves the library names `idents` against `dependencies`, those of the documented crate.
+pub(crate) fn resolve(
+    idents: &[String],
+    dependencies: &[IndexDependency],
+) -> Vec<ExampleDependency> {
+    idents
+        .iter()
+        .map(|ident| {
+            let declared = dependencies
+                .iter()
+                .find(|dependency| dependency.name().replace('-', "_") == *ident);
+            match declared {
+                Some(dependency) => ExampleDependency {
+                    crate_name: dependency.package().to_string(),
+                    used_as: (dependency.package() != ident.as_str()).then(|| ident.clone()),
+                    version: Some(dependency.req().to_string()),
+                },
+                None => ExampleDependency {
+                    crate_name: ident.clone(),
+                    used_as: None,
+                    version: None,
+                },
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Result;
+    use pretty_assertions::assert_eq;
+    use std::fs;
+
+    #[test]
+    fn test_imported_crates() {
+        let code = "\
+use demo::net::TcpListener;
+use demo_core::io::{self, Read};
+use ::serde_json::json;
+use std::collections::HashMap;
+use self::handlers::echo;
+use Ordering::*;
+extern crate demo_test;
+pub use futures_util::StreamExt as _;
+mod handlers;
+use handlers::echo as handle;
+use serde_json::Value;
+
+fn main() { let _ = HashMap::<u8, u8>::new(); }";
+        assert_eq!(
+            imported_crates(code, "demo"),
+            vec!["demo_core", "serde_json", "demo_test", "futures_util"]
+        );
+        assert!(imported_crates("demo::spawn(async {});", "demo").is_empty());
+    }
+
+    #[test]
+    fn test_resolve() -> Result<()> {
+        let versions = sparse_index::parse(&fs::read_to_string(
+            "test-data/dependency_tree/index/de/mo/demo",
+        )?)?;
+        let idents = ["demo_core", "demo_test", "serde_json"].map(String::from);
+        let resolved = resolve(&idents, versions[1].deps());
+        assert_eq!(
+            resolved,
+            vec![
+                ExampleDependency {
+                    crate_name: "demo-core".to_string(),
+                    used_as: Some("demo_core".to_string()),
+                    version: Some("^0.3".to_string()),
+                },
+                ExampleDependency {
+                    crate_name: "demo-test".to_string(),
+                    used_as: Some("demo_test".to_string()),
+                    version: Some("^1".to_string()),
+                },
+                ExampleDependency {
+                    crate_name: "serde_json".to_string(),
+                    used_as: None,
+                    version: None,
+                },
+            ],
+            "Dev-dependencies are resolved too, undeclared names are kept"
+        );
+        assert_eq!(resolved[0].cargo_line(), "demo-core = \"^0.3\"");
+        assert_eq!(resolved[2].cargo_line(), "serde_json = \"*\"");
+        Ok(())
+    }
+}
@@ -0,0 +1,265 @@
+use anyhow::Result;
+use mcp_sdk::{
+    tools::Tool,
+    types::{CallToolResponse, ToolResponseContent},
+};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tracing::debug;
+
+use super::search_in_crate::SearchResult;
+use super::{registry, runtime, SearchInCrateTool};
+use crate::{config, error};
+
+const DEFAULT_LIMIT: usize = 100;
+
+/// Functions and methods whose signature mentions a type
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ApiUsages {
+    crate_name: String,
+    version: String,
+    type_name: String,
+    usages: Vec<ApiUsage>,
+    /// Whether more usages were found than the limit allows
+    truncated: bool,
+}
+
+impl ApiUsages {
+    pub fn usages(&self) -> &[ApiUsage] {
+        &self.usages
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ApiUsage {
+    path: String,
+    /// Rustdoc item kind: `fn`, `method` or `tymethod`
+    kind: String,
+    doc_link: String,
+    /// Whether the type is taken as a parameter, or is a generic or bound of one
+    in_parameters: bool,
+    /// Whether the type is returned, or is a generic of the return type
+    in_return: bool,
+}
+
+impl ApiUsage {
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    pub fn in_parameters(&self) -> bool {
+        self.in_parameters
+    }
+
+    pub fn in_return(&self) -> bool {
+        self.in_return
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct FindUsagesParams {
+    crate_name: String,
+    type_name: String,
+    include_own_methods: Option<bool>,
+    limit: Option<usize>,
+    version: Option<String>,
+    registry: Option<String>,
+}
+
+pub struct FindUsagesInApiTool;
+
+impl FindUsagesInApiTool {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn find_usages(
+        &self,
+        crate_name: &str,
+        type_name: &str,
+        include_own_methods: bool,
+        limit: usize,
+        version: Option<&str>,
+    ) -> Result<ApiUsages> {
+        let index = SearchInCrateTool::new().fetch_index(crate_name, version)?;
+        let mut usages = usages_of(&index, type_name, include_own_methods);
+        debug!(
+            "{} functions of {} mention {}",
+            usages.len(),
+            crate_name,
+            type_name
+        );
+
+        let truncated = usages.len() > limit;
+        usages.truncate(limit);
+        Ok(ApiUsages {
+            crate_name: crate_name.to_string(),
+            version: version.unwrap_or("latest").to_string(),
+            type_name: type_name.to_string(),
+            usages,
+            truncated,
+        })
+    }
+}
+
+impl Default for FindUsagesInApiTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Functions and methods of the index whose signature mentions `type_name`, functions returning
+/// the type first. Methods of the type itself are left out unless `include_own_methods` is set,
+/// as every one of them takes it as its receiver.
+fn usages_of(index: &[SearchResult], type_name: &str, include_own_methods: bool) -> Vec<ApiUsage> {
+    let (crate_prefix, name) = match type_name.rsplit_once("::") {
+        Some((prefix, name)) => (prefix.split("::").next(), name),
+        None => (None, type_name),
+    };
+    // Types are recorded under their defining module, so `reqwest::Response` is matched by
+    // crate and name rather than by its full path
+    let matches = |path: &String| {
+        let (module, ty) = path.rsplit_once("::").unwrap_or(("", path));
+        ty == name && crate_prefix.is_none_or(|prefix| module.split("::").next() == Some(prefix))
+    };
+
+    let mut usages: Vec<ApiUsage> = index
+        .iter()
+        .filter(|item| {
+            include_own_methods
+                || !matches!(item.kind(), "method" | "tymethod")
+                || item.path().rsplit("::").nth(1) != Some(name)
+        })
+        .filter_map(|item| {
+            let signature = item.signature()?;
+            let in_parameters = signature.inputs().iter().any(matches);
+            let in_return = signature.output().iter().any(matches);
+            (in_parameters || in_return).then(|| ApiUsage {
+                path: item.path().to_string(),
+                kind: item.kind().to_string(),
+                doc_link: item.doc_link().to_string(),
+                in_parameters,
+                in_return,
+            })
+        })
+        .collect();
+    usages.sort_by_key(|usage| !usage.in_return);
+    usages
+}
+
+impl Tool for FindUsagesInApiTool {
+    fn name(&self) -> String {
+        "find_usages_in_api".to_string()
+    }
+
+    fn description(&self) -> String {
+        "Find every public function and method of a crate whose signature mentions a type, as \
+        a parameter or in the return type, including as a generic argument or a bound. Answers \
+        questions like 'how do I get a Response, and what can I pass it to?'. Functions \
+        returning the type come first. Methods of the type itself are left out by default."
+            .to_string()
+    }
+
+    fn input_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "crate_name": {
+                    "type": "string",
+                    "description": "Name of the crate to search"
+                },
+                "type_name": {
+                    "type": "string",
+                    "description": "Name of the type, e.g. Response, optionally prefixed with the crate defining it, e.g. http::Response"
+                },
+                "include_own_methods": {
+                    "type": "boolean",
+                    "description": "Include the methods of the type itself. Defaults to false"
+                },
+                "limit": {
+                    "type": "integer",
+                    "description": "Maximum number of usages to return (defaults to 100)"
+                },
+                "version": {
+                    "type": "string",
+                    "description": "Optional version of the crate (defaults to latest)"
+                },
+                "registry": registry::registry_schema()
+            },
+            "required": ["crate_name", "type_name"]
+        })
+    }
+
+    fn call(&self, input: Option<serde_json::Value>) -> Result<CallToolResponse> {
+        let args: FindUsagesParams = error::parse_args(input)?;
+        config::current().ensure_crate_allowed(&args.crate_name)?;
+        let usages = runtime::blocking(|| {
+            registry::in_registry(&args.crate_name, args.registry.as_deref(), || {
+                self.find_usages(
+                    &args.crate_name,
+                    &args.type_name,
+                    args.include_own_methods.unwrap_or(false),
+                    args.limit.unwrap_or(DEFAULT_LIMIT),
+                    args.version.as_deref(),
+                )
+            })
+        })?;
+
+        Ok(CallToolResponse {
+            content: vec![ToolResponseContent::Text {
+                text: serde_json::to_string_pretty(&usages)?,
+            }],
+            is_error: None,
+            meta: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::search_in_crate::parse_search_index;
+    use pretty_assertions::assert_eq;
+    use std::fs;
+
+    fn load_index() -> Result<Vec<SearchResult>> {
+        let js = fs::read_to_string("test-data/find_usages_in_api/search-index.js")?;
+        parse_search_index(&js, "demo", "https://docs.rs/demo/0.1.0/")
+    }
+
+    fn summary(usages: &[ApiUsage]) -> Vec<(&str, bool, bool)> {
+        usages
+            .iter()
+            .map(|usage| (usage.path(), usage.in_parameters(), usage.in_return()))
+            .collect()
+    }
+
+    #[test]
+    fn test_usages_of() -> Result<()> {
+        let index = load_index()?;
+
+        assert_eq!(
+            summary(&usages_of(&index, "Response", false)),
+            vec![
+                ("demo::Client::send", false, true),
+                ("demo::log_response", true, false),
+                ("demo::collect", true, false),
+            ],
+            "Returning functions come first; bounds such as IntoIterator<Item = Response> count"
+        );
+        assert_eq!(
+            summary(&usages_of(&index, "Response", true)).len(),
+            5,
+            "Response::status and Response::text take it as their receiver"
+        );
+        assert_eq!(
+            summary(&usages_of(&index, "alloc::String", false)),
+            vec![
+                ("demo::Response::text", false, true),
+                ("demo::collect", false, true),
+            ]
+        );
+        assert!(usages_of(&index, "other::Response", false).is_empty());
+        Ok(())
+    }
+}
@@ -0,0 +1,315 @@
+use anyhow::{anyhow, Context, Result};
+use mcp_sdk::{
+    tools::Tool,
+    types::{CallToolResponse, ToolResponseContent},
+};
+use scraper::{Html, Node};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tracing::debug;
+use url::Url;
+
+use super::get_struct_docs::{HtmlFetcher, HttpHtmlFetcher};
+use super::{registry, runtime, selectors, CrateItemsTool};
+use crate::config;
+use crate::error::{self, DocsRsMcpError};
+
+/// Source code of an item, taken from the docs.rs source view its `[src]` link points to
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ItemSource {
+    crate_name: String,
+    version: String,
+    item: String,
+    /// Item category as listed by `crate_items`, e.g. `Structs`
+    kind: String,
+    /// Path of the file within the crate, e.g. `trace/provider.rs`
+    file: String,
+    start_line: usize,
+    end_line: usize,
+    source: String,
+    source_url: String,
+}
+
+impl ItemSource {
+    pub fn file(&self) -> &str {
+        &self.file
+    }
+
+    pub fn start_line(&self) -> usize {
+        self.start_line
+    }
+
+    pub fn end_line(&self) -> usize {
+        self.end_line
+    }
+
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+}
+
+/// Where an item's `[src]` link points: a source page and the lines the item spans
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct SourceLink {
+    pub(crate) url: String,
+    pub(crate) file: String,
+    pub(crate) start_line: usize,
+    pub(crate) end_line: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct GetSourceParams {
+    crate_name: String,
+    item_path: String,
+    version: Option<String>,
+    registry: Option<String>,
+}
+
+pub struct GetSourceTool {
+    html_fetcher: Box<dyn HtmlFetcher>,
+}
+
+impl GetSourceTool {
+    pub fn new() -> Self {
+        Self {
+            html_fetcher: Box::new(HttpHtmlFetcher::new()),
+        }
+    }
+
+    fn fetch_source(
+        &self,
+        crate_name: &str,
+        item_path: &str,
+        version: Option<&str>,
+    ) -> Result<ItemSource> {
+        let items = CrateItemsTool::new().scrape_items_blocking(crate_name, version)?;
+        let (kind, item) = items.find(item_path)?;
+        debug!("Found {} {} at {}", kind, item.name(), item.doc_link());
+
+        let item_html = self.html_fetcher.fetch_html_blocking(item.doc_link())?;
+        let link = find_source_link(item.doc_link(), &item_html)?;
+        debug!(
+            "Source of {} is {} lines {}-{}",
+            item.name(),
+            link.file,
+            link.start_line,
+            link.end_line
+        );
+
+        let source_html = self.html_fetcher.fetch_html_blocking(&link.url)?;
+        let source = extract_lines(&source_html, link.start_line, link.end_line)?;
+
+        Ok(ItemSource {
+            crate_name: items.crate_name().to_string(),
+            version: items.version().to_string(),
+            item: item.name().to_string(),
+            kind: kind.to_string(),
+            file: link.file,
+            start_line: link.start_line,
+            end_line: link.end_line,
+            source,
+            source_url: link.url,
+        })
+    }
+}
+
+impl Default for GetSourceTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Reads the `[src]` link of an item page. The first link belongs to the item itself; later
+/// ones are for its methods and trait impls.
+pub(crate) fn find_source_link(page_url: &str, html: &str) -> Result<SourceLink> {
+    let document = Html::parse_document(html);
+    let selector = selectors::parse("a.src, a.srclink", "source page")?;
+    let href = document
+        .select(&selector)
+        .find_map(|link| link.value().attr("href"))
+        .ok_or_else(|| anyhow!("Item page has no source link: {}", page_url))?;
+
+    let mut url = Url::parse(page_url)
+        .context(format!("Invalid item URL: {}", page_url))?
+        .join(href)
+        .context(format!("Invalid source link: {}", href))?;
+    let span = url
+        .fragment()
+        .ok_or_else(|| anyhow!("Source link has no line span: {}", href))?;
+    let (start_line, end_line) = parse_line_span(span)?;
+    url.set_fragment(None);
+
+    // Source pages live at /<crate>/<version>/src/<crate_ident>/<file>.html
+    let path = url.path();
+    let file = path
+        .split_once("/src/")
+        .and_then(|(_, rest)| rest.split_once('/'))
+        .map(|(_, file)| file.trim_end_matches(".html").to_string())
+        .ok_or_else(|| anyhow!("Unexpected source link: {}", url))?;
+
+    Ok(SourceLink {
+        url: url.to_string(),
+        file,
+        start_line,
+        end_line,
+    })
+}
+
+/// Parses a rustdoc line anchor, `293-296` or `42`
+pub(crate) fn parse_line_span(span: &str) -> Result<(usize, usize)> {
+    let (start, end) = span.split_once('-').unwrap_or((span, span));
+    let start: usize = start
+        .parse()
+        .context(format!("Invalid line span: {}", span))?;
+    let end: usize = end
+        .parse()
+        .context(format!("Invalid line span: {}", span))?;
+    if start == 0 || end < start {
+        return Err(anyhow!("Invalid line span: {}", span));
+    }
+    Ok((start, end))
+}
+
+/// Extracts lines `start..=end` (1-based) from a rustdoc source page. Line numbers are rendered
+/// either in a separate column or inline as `data-nosnippet` anchors; both are skipped.
+fn extract_lines(html: &str, start: usize, end: usize) -> Result<String> {
+    let document = Html::parse_document(html);
+    let selector = selectors::parse("pre.rust", "source page")?;
+    let code = document.select(&selector).next().ok_or_else(|| {
+        DocsRsMcpError::UnsupportedLayout("no source code on the source page".to_string())
+    })?;
+
+    let text: String = code
+        .descendants()
+        .filter_map(|node| match node.value() {
+            Node::Text(text) => Some((node, text)),
+            _ => None,
+        })
+        .filter(|(node, _)| {
+            !node.ancestors().any(|ancestor| {
+                ancestor
+                    .value()
+                    .as_element()
+                    .is_some_and(|element| element.attr("data-nosnippet").is_some())
+            })
+        })
+        .map(|(_, text)| &**text)
+        .collect();
+
+    let lines: Vec<&str> = text.lines().collect();
+    if end > lines.len() {
+        return Err(anyhow!(
+            "Source file has {} lines, but the item spans {}-{}",
+            lines.len(),
+            start,
+            end
+        ));
+    }
+    Ok(lines[start - 1..end].join("\n"))
+}
+
+impl Tool for GetSourceTool {
+    fn name(&self) -> String {
+        "get_source".to_string()
+    }
+
+    fn description(&self) -> String {
+        "Get the Rust source code of an item (struct, enum, trait, function, ...) from \
+        docs.rs. Follows the item's [src] link and returns the source along with the \
+        file path and line span within the crate."
+            .to_string()
+    }
+
+    fn input_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "crate_name": {
+                    "type": "string",
+                    "description": "Name of the crate containing the item"
+                },
+                "item_path": {
+                    "type": "string",
+                    "description": "Path of the item within the crate, e.g. sync::Mutex. \
+                        A bare name works when it is unique"
+                },
+                "version": {
+                    "type": "string",
+                    "description": "Optional version of the crate. Defaults to latest if not specified"
+                },
+                "registry": registry::registry_schema()
+            },
+            "required": ["crate_name", "item_path"]
+        })
+    }
+
+    fn call(&self, input: Option<serde_json::Value>) -> Result<CallToolResponse> {
+        let args: GetSourceParams = error::parse_args(input)?;
+        config::current().ensure_crate_allowed(&args.crate_name)?;
+        let source = runtime::blocking(|| {
+            registry::in_registry(&args.crate_name, args.registry.as_deref(), || {
+                self.fetch_source(&args.crate_name, &args.item_path, args.version.as_deref())
+            })
+        })?;
+
+        Ok(CallToolResponse {
+            content: vec![ToolResponseContent::Text {
+                text: serde_json::to_string_pretty(&source)?,
+            }],
+            is_error: None,
+            meta: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::fs;
+
+    #[test]
+    fn test_find_source_link() -> Result<()> {
+        let html = fs::read_to_string(
+            "test-data/get_struct_docs/opentelemetry-sdk-0.28.0-tracer-provider-builder.html",
+        )?;
+        let link = find_source_link(
+            "https://docs.rs/opentelemetry_sdk/0.28.0/opentelemetry_sdk/trace/struct.TracerProviderBuilder.html",
+            &html,
+        )?;
+
+        assert_eq!(
+            link,
+            SourceLink {
+                url: "https://docs.rs/opentelemetry_sdk/0.28.0/src/opentelemetry_sdk/trace/provider.rs.html".to_string(),
+                file: "trace/provider.rs".to_string(),
+                start_line: 293,
+                end_line: 296,
+            }
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_extract_lines() -> Result<()> {
+        let html = fs::read_to_string("test-data/get_source/src-page.html")?;
+
+        assert_eq!(
+            extract_lines(&html, 3, 5)?,
+            "impl Display for Version {\n    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {\n        write!(formatter, \"{}.{}.{}\", self.major, self.minor, self.patch)"
+        );
+        assert!(extract_lines(&html, 3, 100).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_line_span() -> Result<()> {
+        assert_eq!(parse_line_span("293-296")?, (293, 296));
+        assert_eq!(parse_line_span("42")?, (42, 42));
+        assert!(parse_line_span("10-2").is_err());
+        assert!(parse_line_span("src").is_err());
+        Ok(())
+    }
+}
@@ -1,42 +1,71 @@
+use std::collections::{BTreeSet, HashMap};
+use std::time::Duration;
+
 use anyhow::{anyhow, Context, Result};
-use mcp_sdk::{
-    tools::Tool,
-    types::{CallToolResponse, ToolResponseContent},
-};
-use reqwest::blocking::Client;
-use scraper::{Html, Selector};
+#[cfg(feature = "native")]
+use mcp_sdk::{tools::Tool, types::CallToolResponse};
+use scraper::{CaseSensitivity, ElementRef, Html, Node, Selector};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
-use tracing::{debug, error, info, warn};
+use tracing::{debug, error, info};
+use url::Url;
+
+use super::all_items_cache::{self, StructLink};
+use super::associated_items::{self, AssociatedConst, AssociatedType};
+use super::default_version::{self, ResolvedVersion};
+use super::deprecation::{self, Deprecation};
+use super::derive_macro_docs::text_of;
+use super::docs_build::{self, DocsBuild, PageBuild};
+use super::opaque_return::{self, OpaqueReturn};
+use super::output_format::{self, Format};
+#[cfg(feature = "native")]
+use super::runtime;
+use super::scraped_examples::{self, UsageExample};
+use super::{
+    doc_cfg, docs_root, http, module_items, near_matches, registry, selectors, sparse_index,
+    summary,
+};
+use crate::config;
+use crate::error::{self, DocsRsMcpError};
+use crate::resources::{self, Kind};
 
 /// Trait for fetching HTML content from a URL
 #[async_trait::async_trait]
 pub trait HtmlFetcher: Send + Sync {
     /// Fetches HTML content from a URL
-    fn fetch_html(&self, url: &str) -> Result<String>;
+    async fn fetch_html(&self, url: &str) -> Result<String>;
+
+    /// Fetches HTML content from a URL, blocking until it arrives, for tools that do their
+    /// lookups synchronously.
+    #[cfg(feature = "native")]
+    fn fetch_html_blocking(&self, url: &str) -> Result<String> {
+        runtime::block_on(self.fetch_html(url))
+    }
 }
 
-/// Production implementation of HtmlFetcher that fetches from actual URLs
+/// Production implementation of HtmlFetcher that fetches from actual URLs through the
+/// [shared](http::shared) HTTP client
 #[derive(Default)]
-pub struct HttpHtmlFetcher {
-    client: Client,
-}
+pub struct HttpHtmlFetcher;
 
 impl HttpHtmlFetcher {
     pub fn new() -> Self {
-        Self {
-            client: Client::new(),
-        }
+        Self
     }
 }
 
+#[async_trait::async_trait]
 impl HtmlFetcher for HttpHtmlFetcher {
-    fn fetch_html(&self, url: &str) -> Result<String> {
+    async fn fetch_html(&self, url: &str) -> Result<String> {
+        #[cfg(feature = "native")]
+        if let Some(html) = crate::cache::get(url) {
+            return Ok(html);
+        }
+
         debug!("Fetching HTML from URL: {}", url);
-        let response = self
-            .client
+        let response = http::shared()?
             .get(url)
-            .send()
+            .await
             .context(format!("Failed to fetch URL: {}", url))?;
 
         let status = response.status();
@@ -44,17 +73,23 @@ impl HtmlFetcher for HttpHtmlFetcher {
 
         if !status.is_success() {
             error!("HTTP error response: {} for URL: {}", status, url);
-            if let Ok(text) = response.text() {
+            if let Ok(text) = response.text().await {
                 error!("Response body: {}", text);
             }
-            return Err(anyhow!("Failed to fetch URL: HTTP {}", status));
+            return Err(
+                anyhow::Error::new(DocsRsMcpError::from_status(url, status.as_u16()))
+                    .context(format!("Failed to fetch URL: HTTP {}", status)),
+            );
         }
 
         let html = response
             .text()
+            .await
             .context(format!("Failed to get text from response for URL: {}", url))?;
 
         debug!("Successfully fetched HTML ({} bytes)", html.len());
+        #[cfg(feature = "native")]
+        crate::cache::put(url, &html);
         Ok(html)
     }
 }
@@ -63,8 +98,9 @@ impl HtmlFetcher for HttpHtmlFetcher {
 pub struct TestHtmlFetcher;
 
 #[cfg(test)]
+#[async_trait::async_trait]
 impl HtmlFetcher for TestHtmlFetcher {
-    fn fetch_html(&self, url: &str) -> Result<String> {
+    async fn fetch_html(&self, url: &str) -> Result<String> {
         debug!("TestHtmlFetcher: Fetching HTML from URL: {}", url);
         // Extract crate name and version from URL
         let parts: Vec<&str> = url.split('/').collect();
@@ -79,13 +115,20 @@ impl HtmlFetcher for TestHtmlFetcher {
             "all-items".to_string()
         } else {
             // Extract the struct name from the URL and convert to kebab case
-            url.split('/')
-                .last()
+            let name = url
+                .split('/')
+                .next_back()
                 .ok_or_else(|| anyhow!("Invalid URL: no path segments"))?
                 .trim_end_matches(".html")
-                .trim_start_matches("struct.")
-                .to_lowercase()
-                .replace('_', "-")
+                .trim_start_matches("struct.");
+            let mut kebab = String::new();
+            for (i, c) in name.chars().enumerate() {
+                if c.is_ascii_uppercase() && i > 0 {
+                    kebab.push('-');
+                }
+                kebab.push(c.to_ascii_lowercase());
+            }
+            kebab.replace('_', "-")
         };
 
         let test_file = format!(
@@ -100,324 +143,1674 @@ impl HtmlFetcher for TestHtmlFetcher {
     }
 }
 
+/// Serves doc.rust-lang.org pages of std, core and alloc from the snapshots under
+/// `test-data/std_docs`, which hold one directory per rustdoc version mirroring the site's
+/// paths below the channel, e.g. `1.86.0/std/string/struct.String.html`.
+#[cfg(test)]
+pub struct StdDocsFetcher {
+    root: std::path::PathBuf,
+}
+
+#[cfg(test)]
+impl StdDocsFetcher {
+    const SNAPSHOTS: &'static str = "test-data/std_docs";
+
+    pub fn new(rustdoc_version: &str) -> Self {
+        Self {
+            root: std::path::Path::new(Self::SNAPSHOTS).join(rustdoc_version),
+        }
+    }
+
+    /// Rustdoc versions with a std docs snapshot, oldest first
+    pub fn versions() -> Result<Vec<String>> {
+        let mut versions = std::fs::read_dir(Self::SNAPSHOTS)
+            .context(format!("Failed to list {}", Self::SNAPSHOTS))?
+            .map(|entry| Ok(entry?.file_name().to_string_lossy().into_owned()))
+            .collect::<Result<Vec<String>>>()?;
+        versions.sort_by_key(|version| {
+            version
+                .split('.')
+                .map(|part| part.parse::<u32>().unwrap_or_default())
+                .collect::<Vec<_>>()
+        });
+        Ok(versions)
+    }
+}
+
+#[cfg(test)]
+#[async_trait::async_trait]
+impl HtmlFetcher for StdDocsFetcher {
+    async fn fetch_html(&self, url: &str) -> Result<String> {
+        // Any channel maps onto the snapshot: stable, nightly or a version number
+        let page = url
+            .strip_prefix("https://doc.rust-lang.org/")
+            .and_then(|path| path.split_once('/'))
+            .map(|(_channel, page)| page.split('#').next().unwrap_or(page))
+            .ok_or_else(|| anyhow!("Not a std docs URL: {}", url))?;
+
+        let snapshot = self.root.join(page);
+        debug!("StdDocsFetcher: Reading {}", snapshot.display());
+        std::fs::read_to_string(&snapshot)
+            .context(format!("No std docs snapshot at {}", snapshot.display()))
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct StructDocs {
     name: String,
     crate_name: String,
+    /// Path of the struct as requested, e.g. `futures::channel::mpsc::Sender`
+    requested_path: String,
+    /// Path of the page the struct is documented on, when it differs from the requested path
+    /// because the struct is re-exported
+    canonical_path: Option<String>,
+    doc_url: String,
+    /// The release documented: the version the page shows when `latest` was asked for, as
+    /// docs.rs serves it without redirecting to a versioned URL
+    version: String,
+    /// The release a version requirement such as `^1.0` resolved to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    resolved_version: Option<ResolvedVersion>,
+    /// When docs.rs built the documentation and with which rustdoc, and whether a newer
+    /// release is out
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    docs_build: Option<DocsBuild>,
+    /// What was left out to fit `max_length`, and where the full docs are
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    truncated: Option<Truncation>,
     description: String,
-    methods: Vec<MethodDoc>,
+    /// URI of the full description, a resource, when `description` is only a summary
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    full_description: Option<String>,
+    /// The `doc_cfg` banner of the struct, e.g. "Available on crate feature trace only."
+    availability: Option<String>,
+    /// Cargo features the banner mentions, which need enabling to use the struct, spelled as
+    /// the crate declares them when its index entry could be read
+    required_features: Vec<String>,
+    /// Whether the struct only exists in the docs build (`cfg(docsrs)`), so code must not use it
+    docs_only: bool,
+    /// The struct's deprecation notice, with the suggested replacement when one is named
+    deprecated: Option<Deprecation>,
+    /// Methods grouped by the impl block defining them, so the bounds gating each are kept
+    impls: Vec<ImplBlock>,
+    /// Methods of the `Deref` target that can be called on the struct, e.g. `str` methods on
+    /// `String`
+    deref_methods: Vec<DerefMethods>,
+    /// Constants of inherent and trait impls, e.g. `u32::MAX`
+    associated_constants: Vec<AssociatedConst>,
+    /// Types the struct's trait impls assign, e.g. `FromStr::Err`
+    associated_types: Vec<AssociatedType>,
     traits: Vec<String>,
     fields: Vec<FieldDoc>,
+    ownership: OwnershipHints,
+    capabilities: Capabilities,
+    serde: SerdeSupport,
+    /// `unsafe impl`s of the struct, such as manual `Send`/`Sync` impls, with their safety docs
+    unsafe_impls: Vec<UnsafeImpl>,
+    /// Types and traits the fields and method signatures link to, when asked for
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    linked_items: Vec<LinkedItem>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct MethodDoc {
-    name: String,
-    signature: String,
-    description: String,
-}
+impl StructDocs {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct FieldDoc {
-    name: String,
-    type_name: String,
-    description: String,
-}
+    pub fn crate_name(&self) -> &str {
+        &self.crate_name
+    }
 
-#[derive(Debug, Serialize, Deserialize)]
-struct StructDocsParams {
-    crate_name: String,
-    struct_name: String,
-    version: Option<String>,
-}
+    pub fn doc_url(&self) -> &str {
+        &self.doc_url
+    }
 
-pub struct StructDocsTool {
-    html_fetcher: Box<dyn HtmlFetcher>,
-}
+    /// The release documented, even when `latest` was asked for
+    pub fn version(&self) -> &str {
+        &self.version
+    }
 
-impl StructDocsTool {
-    /// Creates a new instance of the StructDocsTool with the default production HTML fetcher.
-    pub fn new() -> Self {
-        Self {
-            html_fetcher: Box::new(HttpHtmlFetcher::new()),
-        }
+    pub fn resolved_version(&self) -> Option<&ResolvedVersion> {
+        self.resolved_version.as_ref()
     }
 
-    /// Creates a new instance with a test fetcher for testing purposes.
-    #[cfg(test)]
-    pub fn new_with_test_fetcher() -> Self {
-        debug!("Creating StructDocsTool with test fetcher");
-        Self {
-            html_fetcher: Box::new(TestHtmlFetcher),
-        }
+    pub fn docs_build(&self) -> Option<&DocsBuild> {
+        self.docs_build.as_ref()
     }
 
-    /// Gets the docs.rs URL, either from the environment variable DOCS_RS_URL or the default value.
-    fn get_docs_rs_url(&self) -> String {
-        std::env::var("DOCS_RS_URL").unwrap_or_else(|_| "https://docs.rs".to_string())
+    pub fn description(&self) -> &str {
+        &self.description
     }
 
-    /// Fetches HTML content from a URL.
-    fn fetch_html(&self, url: &str) -> Result<String> {
-        self.html_fetcher.fetch_html(url)
+    pub fn required_features(&self) -> &[String] {
+        &self.required_features
     }
 
-    fn find_struct_url(
-        &self,
-        crate_name: &str,
-        struct_name: &str,
-        version: Option<&str>,
-    ) -> Result<String> {
-        let version = version.unwrap_or("latest");
-        let all_items_url = format!(
-            "{}/{}/{}/{}/all.html",
-            self.get_docs_rs_url(),
-            crate_name,
-            version,
-            crate_name
-        );
-        debug!("Fetching all items from URL: {}", all_items_url);
-        let html = self.fetch_html(&all_items_url)?;
-        debug!("Successfully fetched all items HTML ({} bytes)", html.len());
-        let document = Html::parse_document(&html);
+    pub fn traits(&self) -> &[String] {
+        &self.traits
+    }
 
-        // Try both old and new docs.rs HTML structures
-        let selectors = [
-            "h3#structs + ul.all-items > li > a",
-            "div[id='structs'] > div.item-table > div.item-row > a",
-        ];
+    pub fn impls(&self) -> &[ImplBlock] {
+        &self.impls
+    }
 
-        // Extract the struct name without module path
-        let struct_name_without_path = struct_name
-            .split("::")
-            .last()
-            .ok_or_else(|| anyhow!("Invalid struct name: no parts found"))?;
-        let module_path = struct_name
-            .split("::")
-            .take(struct_name.split("::").count() - 1)
-            .collect::<Vec<_>>()
-            .join("::");
+    pub fn fields(&self) -> &[FieldDoc] {
+        &self.fields
+    }
 
-        debug!(
-            "Looking for struct: {} (without path: {}, module path: {})",
-            struct_name, struct_name_without_path, module_path
-        );
+    /// The docs as Markdown: the description, fields and methods under headings, with
+    /// signatures in code fences.
+    pub fn to_markdown(&self) -> String {
+        let mut about = vec![format!("Crate `{}`: {}", self.crate_name, self.doc_url)];
+        if let Some(path) = &self.canonical_path {
+            about.push(format!("Documented as `{}`", path));
+        }
+        if let Some(resolved) = &self.resolved_version {
+            about.push(format!(
+                "Version `{}` resolved to {}",
+                resolved.requested(),
+                resolved.version()
+            ));
+        }
+        if let Some(build) = &self.docs_build {
+            about.push(build.describe());
+        }
+        if let Some(truncated) = &self.truncated {
+            let mut note = format!("*Shortened, leaving out {}.", truncated.elided.join(", "));
+            if let Some(uri) = &truncated.full_response {
+                note.push_str(&format!(" The full docs are at {}", uri));
+            }
+            note.push('*');
+            about.push(note);
+        }
+        about.extend(notices(
+            self.availability.as_deref(),
+            self.docs_only,
+            self.deprecated.as_ref(),
+        ));
+        let mut blocks = vec![format!("# Struct `{}`", self.name), about.join("\n")];
+        if !self.description.trim().is_empty() {
+            blocks.push(self.description.trim().to_string());
+        }
 
-        for selector in &selectors {
-            debug!("Trying selector: {}", selector);
-            let link_selector = Selector::parse(selector)
-                .map_err(|e| anyhow!("Failed to parse selector '{}': {}", selector, e))?;
+        if !self.fields.is_empty() {
+            blocks.push("## Fields".to_string());
+            blocks.push(
+                self.fields
+                    .iter()
+                    .map(|field| {
+                        let mut line = format!("- `{}: {}`", field.name, field.type_name);
+                        let description = one_line(&field.description);
+                        if !description.is_empty() {
+                            line.push_str(&format!(" — {}", description));
+                        }
+                        line
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            );
+        }
+        // Trait methods are documented by their traits, so only the impls are listed
+        let (inherent, trait_impls): (Vec<_>, Vec<_>) = self
+            .impls
+            .iter()
+            .partition(|block| block.trait_name.is_none());
+        for block in inherent
+            .into_iter()
+            .filter(|block| !block.methods.is_empty())
+        {
+            blocks.push(format!("## `{}`", one_line(&block.header)));
+            blocks.extend(block.methods.iter().map(MethodDoc::to_markdown));
+        }
+        for deref in self
+            .deref_methods
+            .iter()
+            .filter(|deref| !deref.methods.is_empty())
+        {
+            blocks.push(format!(
+                "## Methods from `Deref<Target = {}>`",
+                deref.target
+            ));
+            blocks.extend(deref.methods.iter().map(MethodDoc::to_markdown));
+        }
+        if !trait_impls.is_empty() {
+            blocks.push("## Trait implementations".to_string());
+            blocks.push(
+                trait_impls
+                    .iter()
+                    .map(|block| format!("- `{}`", one_line(&block.header)))
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            );
+        } else if !self.traits.is_empty() {
+            blocks.push("## Traits".to_string());
+            blocks.push(
+                self.traits
+                    .iter()
+                    .map(|name| format!("`{}`", name))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            );
+        }
+        if !self.linked_items.is_empty() {
+            blocks.push("## Linked items".to_string());
+            blocks.push(
+                self.linked_items
+                    .iter()
+                    .map(|item| match &item.summary {
+                        Some(summary) => format!(
+                            "- [`{}`]({}) ({}): {}",
+                            item.path,
+                            item.doc_url,
+                            item.kind,
+                            one_line(summary)
+                        ),
+                        None => format!("- [`{}`]({}) ({})", item.path, item.doc_url, item.kind),
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            );
+        }
+        blocks.join("\n\n")
+    }
 
-            let mut found_links = Vec::new();
-            for element in document.select(&link_selector) {
-                let text = element.text().collect::<String>();
-                let href = element.value().attr("href").unwrap_or_default();
-                found_links.push(format!("text: '{}', href: '{}'", text, href));
-            }
-            debug!(
-                "Found {} links with selector: {}",
-                found_links.len(),
-                selector
+    /// The docs as a digest, one `path | kind | signature | summary` line for the struct and
+    /// each of its fields, methods and trait impls.
+    pub fn to_digest(&self) -> String {
+        let path = match self.requested_path.contains("::") {
+            true => self.requested_path.clone(),
+            false => format!("{}::{}", self.crate_name, self.requested_path),
+        };
+        let path = path.as_str();
+        let kind = |kind: &str, deprecated: bool| match deprecated {
+            true => format!("{}, deprecated", kind),
+            false => kind.to_string(),
+        };
+        let mut lines = vec![output_format::digest_line(
+            path,
+            &kind("struct", self.deprecated.is_some()),
+            "",
+            &self.description,
+        )];
+        lines.extend(self.fields.iter().map(|field| {
+            output_format::digest_line(
+                &format!("{}.{}", path, field.name),
+                "field",
+                &format!("{}: {}", field.name, field.type_name),
+                &field.description,
+            )
+        }));
+        let method_line = |method: &MethodDoc, method_kind: &str| {
+            output_format::digest_line(
+                &format!("{}::{}", path, method.name),
+                &kind(method_kind, method.deprecated.is_some()),
+                &method.signature,
+                &method.description,
+            )
+        };
+        let (inherent, trait_impls): (Vec<_>, Vec<_>) = self
+            .impls
+            .iter()
+            .partition(|block| block.trait_name.is_none());
+        for block in inherent {
+            lines.extend(
+                block
+                    .methods
+                    .iter()
+                    .map(|method| method_line(method, "method")),
             );
-            if !found_links.is_empty() {
-                debug!("Links found:\n{}", found_links.join("\n"));
-            }
+        }
+        for deref in &self.deref_methods {
+            let deref_kind = format!("method of Deref<Target = {}>", deref.target);
+            lines.extend(
+                deref
+                    .methods
+                    .iter()
+                    .map(|method| method_line(method, &deref_kind)),
+            );
+        }
+        lines.extend(
+            trait_impls
+                .iter()
+                .map(|block| output_format::digest_line(path, "impl", &block.header, "")),
+        );
+        if let Some(uri) = self
+            .truncated
+            .as_ref()
+            .and_then(|truncated| truncated.full_response.as_ref())
+        {
+            lines.push(format!("# Shortened; the full docs are at {}", uri));
+        }
+        lines.join("\n")
+    }
+}
 
-            if let Some(struct_path) = document
-                .select(&link_selector)
-                .find(|element| {
-                    let text = element.text().collect::<String>();
-                    let href = element.value().attr("href").unwrap_or_default();
-                    let matches_name = if module_path.is_empty() {
-                        text == struct_name_without_path
-                    } else {
-                        text == struct_name
-                            || text == format!("{}::{}", module_path, struct_name_without_path)
-                    };
-                    debug!(
-                        "Checking link - text: '{}', href: '{}', matches_name: {}",
-                        text, href, matches_name
-                    );
-                    matches_name && href.contains("struct")
-                })
-                .and_then(|element| element.value().attr("href"))
-            {
-                let base_url = format!(
-                    "{}/{}/{}/{}",
-                    self.get_docs_rs_url(),
-                    crate_name,
-                    version,
-                    crate_name
-                );
-                debug!("Found struct path: {}", struct_path);
-                if struct_path.starts_with("http") {
-                    debug!("Using absolute URL: {}", struct_path);
-                    return Ok(struct_path.to_string());
-                } else {
-                    // If we have a module path, we need to check if it's in the URL
-                    let path_parts: Vec<&str> = struct_path.split('/').collect();
-                    let mut final_path = struct_path.to_string();
-                    if !module_path.is_empty()
-                        && !path_parts.iter().any(|p| p.contains(&module_path))
-                    {
-                        // Insert the module path before the struct name
-                        let last_slash = struct_path.rfind('/').unwrap_or(0);
-                        final_path = format!(
-                            "{}/{}/{}",
-                            &struct_path[..last_slash],
-                            module_path.replace("::", "/"),
-                            &struct_path[last_slash + 1..]
-                        );
-                    }
-                    let full_url = format!("{}{}", base_url, final_path);
-                    debug!("Using constructed URL: {}", full_url);
-                    return Ok(full_url);
-                }
+/// Leaves out one kind of content, returning how many pieces of it there were
+type Shortening = fn(&mut StructDocs) -> usize;
+
+/// Ways to shorten [`StructDocs`], least useful first, with what each leaves out
+const SHORTENINGS: [(&str, Shortening); 8] = [
+    ("linked item summaries", |docs| {
+        std::mem::take(&mut docs.linked_items).len()
+    }),
+    ("example calls", |docs| {
+        docs.methods_mut()
+            .map(|method| std::mem::take(&mut method.usage_examples).len())
+            .sum()
+    }),
+    ("methods of trait impls", |docs| {
+        docs.impls
+            .iter_mut()
+            .filter(|block| block.trait_name.is_some())
+            .map(|block| std::mem::take(&mut block.methods).len())
+            .sum()
+    }),
+    ("methods from Deref", |docs| {
+        docs.deref_methods
+            .iter_mut()
+            .map(|deref| std::mem::take(&mut deref.methods).len())
+            .sum()
+    }),
+    ("return type notes", |docs| {
+        docs.methods_mut()
+            .map(|method| {
+                let notes =
+                    method.notable_traits.len() + usize::from(method.opaque_return.is_some());
+                method.notable_traits.clear();
+                method.opaque_return = None;
+                notes
+            })
+            .sum()
+    }),
+    ("method docs past the first sentence", |docs| {
+        docs.methods_mut()
+            .map(|method| {
+                let first = output_format::first_sentence(&method.description);
+                let shortened = first.len() < method.description.trim().len();
+                method.description = first;
+                usize::from(shortened)
+            })
+            .sum()
+    }),
+    ("field docs", |docs| {
+        docs.fields
+            .iter_mut()
+            .map(|field| usize::from(!std::mem::take(&mut field.description).is_empty()))
+            .sum()
+    }),
+    ("method docs", |docs| {
+        docs.methods_mut()
+            .map(|method| usize::from(!std::mem::take(&mut method.description).is_empty()))
+            .sum()
+    }),
+];
+
+/// What [`StructDocs::shorten`] left out of a response over `max_length`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Truncation {
+    /// Characters of the full response
+    full_length: usize,
+    /// What was left out, e.g. "12 example calls"
+    elided: Vec<String>,
+    /// URI of the full response, a resource to read with `resources/read`
+    full_response: Option<String>,
+}
+
+impl StructDocs {
+    /// Spells the features the struct and its methods require as the crate declares them.
+    fn canonicalize_features(&mut self, declared: &BTreeSet<String>) {
+        self.required_features = doc_cfg::canonical_features(&self.required_features, declared);
+        for method in self.methods_mut() {
+            method.required_features =
+                doc_cfg::canonical_features(&method.required_features, declared);
+        }
+    }
+
+    fn methods_mut(&mut self) -> impl Iterator<Item = &mut MethodDoc> {
+        self.impls
+            .iter_mut()
+            .flat_map(|block| &mut block.methods)
+            .chain(
+                self.deref_methods
+                    .iter_mut()
+                    .flat_map(|deref| &mut deref.methods),
+            )
+    }
+
+    /// Leaves out parts of the docs, least useful first, until `render` makes at most
+    /// `max_length` characters of them. The description and method signatures are always
+    /// kept. The full rendering is kept as a resource, which the docs then point to.
+    fn shorten(
+        &mut self,
+        max_length: usize,
+        render: impl Fn(&Self) -> Result<String>,
+    ) -> Result<()> {
+        let full = render(self)?;
+        let full_length = full.chars().count();
+        if full_length <= max_length {
+            return Ok(());
+        }
+
+        let mut elided = Vec::new();
+        for (what, shorten) in SHORTENINGS {
+            match shorten(self) {
+                0 => continue,
+                count => elided.push(format!("{} {}", count, what)),
+            }
+            if render(self)?.chars().count() <= max_length {
+                break;
             }
         }
+        let full_response = resources::keep(
+            Kind::FullText,
+            format!("Docs of {}::{}", self.crate_name, self.name),
+            vec![full],
+        )
+        .pop();
+        self.truncated = Some(Truncation {
+            full_length,
+            elided,
+            full_response,
+        });
+        Ok(())
+    }
+}
 
-        error!(
-            "Could not find struct {} in crate {} (version: {})",
-            struct_name, crate_name, version
+/// The availability, docs-only and deprecation notices of an item, one line each
+fn notices(
+    availability: Option<&str>,
+    docs_only: bool,
+    deprecated: Option<&Deprecation>,
+) -> Vec<String> {
+    let mut notices = Vec::new();
+    if let Some(availability) = availability {
+        notices.push(format!("*{}*", one_line(availability)));
+    }
+    if docs_only {
+        notices.push(
+            "*Only exists in the docs build (`cfg(docsrs)`), not for code to use*".to_string(),
         );
-        Err(anyhow!(
-            "Could not find struct {} in crate {}",
-            struct_name,
-            crate_name
-        ))
     }
+    if let Some(deprecated) = deprecated {
+        let mut notice = "**Deprecated**".to_string();
+        if let Some(since) = deprecated.since() {
+            notice.push_str(&format!(" since {}", since));
+        }
+        if let Some(note) = deprecated.note() {
+            notice.push_str(&format!(": {}", one_line(note)));
+        }
+        notices.push(notice);
+    }
+    notices
+}
 
-    fn fetch_docs(
-        &self,
-        crate_name: &str,
-        struct_name: &str,
-        version: Option<&str>,
-    ) -> Result<StructDocs> {
-        info!(
-            "Fetching docs for struct {} in crate {} (version: {:?})",
-            struct_name, crate_name, version
-        );
+/// `text` with its whitespace, line breaks included, collapsed to single spaces
+fn one_line(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
 
-        // Find the correct URL for the struct
-        let url = self.find_struct_url(crate_name, struct_name, version)?;
-        debug!("Found struct URL: {}", url);
+/// An item linked from the struct's page, with the opening paragraph of its docs
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LinkedItem {
+    /// Full path of the item, e.g. `surrealdb::engine::any::Any`
+    path: String,
+    kind: String,
+    doc_url: String,
+    /// Missing when the page couldn't be read within the time budget
+    summary: Option<String>,
+}
 
-        let html = self.fetch_html(&url)?;
-        debug!("Successfully fetched struct HTML ({} bytes)", html.len());
-        let document = Html::parse_document(&html);
+impl LinkedItem {
+    pub fn path(&self) -> &str {
+        &self.path
+    }
 
-        // Parse main description
-        let desc_selector = Selector::parse(".toggle.top-doc .docblock")
-            .map_err(|e| anyhow!("Failed to parse description selector: {}", e))?;
-        let description = document
-            .select(&desc_selector)
-            .next()
-            .map(|el| el.text().collect::<String>())
-            .unwrap_or_default()
-            .trim()
-            .to_string();
+    pub fn summary(&self) -> Option<&str> {
+        self.summary.as_deref()
+    }
+}
 
-        // Parse methods
-        let method_selector = Selector::parse(".impl-items .toggle.method-toggle")
-            .map_err(|e| anyhow!("Failed to parse method selector: {}", e))?;
-        let fn_selector = Selector::parse(".code-header .fn")
-            .map_err(|e| anyhow!("Failed to parse function name selector: {}", e))?;
-        let code_header_selector = Selector::parse(".code-header")
-            .map_err(|e| anyhow!("Failed to parse code header selector: {}", e))?;
-        let docblock_selector = Selector::parse(".docblock")
-            .map_err(|e| anyhow!("Failed to parse docblock selector: {}", e))?;
-
-        let methods = document
-            .select(&method_selector)
-            .map(|method| {
-                let name = method
-                    .select(&fn_selector)
-                    .next()
-                    .map(|el| el.text().collect::<String>())
-                    .unwrap_or_default()
-                    .trim()
-                    .to_string();
+/// An impl block of the struct and the documented methods inside it
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ImplBlock {
+    /// The full impl header, e.g. `impl<C> Surreal<C> where C: Connection,`
+    header: String,
+    /// The implemented trait; `None` for inherent impls
+    trait_name: Option<String>,
+    methods: Vec<MethodDoc>,
+}
 
-                let signature = method
-                    .select(&code_header_selector)
-                    .next()
-                    .map(|el| el.text().collect::<String>())
-                    .unwrap_or_default()
-                    .trim()
-                    .to_string();
+impl ImplBlock {
+    pub fn header(&self) -> &str {
+        &self.header
+    }
 
-                let description = method
-                    .select(&docblock_selector)
-                    .next()
-                    .map(|el| el.text().collect::<String>())
-                    .unwrap_or_default()
-                    .trim()
-                    .to_string();
+    pub fn methods(&self) -> &[MethodDoc] {
+        &self.methods
+    }
+}
 
-                MethodDoc {
-                    name,
-                    signature,
-                    description,
-                }
-            })
-            .collect();
+/// The methods a struct gets through `Deref`, as listed on its page
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DerefMethods {
+    /// The `Deref::Target`, e.g. `[u8]`
+    target: String,
+    methods: Vec<MethodDoc>,
+}
 
-        // Extract trait implementations
-        let mut traits: Vec<String> = Vec::new();
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MethodDoc {
+    name: String,
+    signature: String,
+    description: String,
+    /// The `doc_cfg` banner of the method, when it has other requirements than the struct
+    availability: Option<String>,
+    /// Cargo features the banner mentions, which need enabling to call the method, e.g.
+    /// `["rt-multi-thread"]`, for adding to the dependency in `Cargo.toml`
+    required_features: Vec<String>,
+    /// Whether the method only exists in the docs build (`cfg(docsrs)`)
+    docs_only: bool,
+    deprecated: Option<Deprecation>,
+    /// Traits rustdoc highlights on the return type, such as `Iterator` or `Future`
+    notable_traits: Vec<NotableTrait>,
+    /// What the value can be used for when the method returns `impl Trait` or is `async`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    opaque_return: Option<OpaqueReturn>,
+    /// Calls found in the crate's examples, for crates documented with `--scrape-examples`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    usage_examples: Vec<UsageExample>,
+}
 
-        // Parse selectors for trait implementations
-        let trait_impl_selector = Selector::parse("#trait-implementations .impl")
-            .map_err(|e| anyhow!("Failed to parse trait implementation selector: {}", e))?;
-        let trait_name_selector = Selector::parse("h3 .trait")
-            .map_err(|e| anyhow!("Failed to parse trait name selector: {}", e))?;
+impl MethodDoc {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
 
-        // Check trait implementations
-        for trait_section in document.select(&trait_impl_selector) {
-            if let Some(trait_name) = trait_section.select(&trait_name_selector).next() {
-                let trait_text = trait_name.text().collect::<String>();
-                if !trait_text.is_empty() {
-                    traits.push(trait_text);
-                }
-            }
-        }
+    pub fn signature(&self) -> &str {
+        &self.signature
+    }
 
-        // Check synthetic implementations
-        let synthetic_impl_selector = Selector::parse("#synthetic-implementations .impl")
-            .map_err(|e| anyhow!("Failed to parse synthetic implementation selector: {}", e))?;
+    pub fn description(&self) -> &str {
+        &self.description
+    }
 
-        if traits.is_empty() {
-            for synthetic_section in document.select(&synthetic_impl_selector) {
-                if let Some(trait_name) = synthetic_section.select(&trait_name_selector).next() {
-                    let trait_text = trait_name.text().collect::<String>();
-                    if !trait_text.is_empty() {
-                        traits.push(trait_text);
-                    }
-                }
-            }
-        }
+    pub fn opaque_return(&self) -> Option<&OpaqueReturn> {
+        self.opaque_return.as_ref()
+    }
 
-        // Check blanket implementations
-        let blanket_impl_selector = Selector::parse("#blanket-implementations .impl")
-            .map_err(|e| anyhow!("Failed to parse blanket implementation selector: {}", e))?;
+    pub fn usage_examples(&self) -> &[UsageExample] {
+        &self.usage_examples
+    }
 
-        if traits.is_empty() {
-            for blanket_section in document.select(&blanket_impl_selector) {
-                if let Some(trait_name) = blanket_section.select(&trait_name_selector).next() {
-                    let trait_text = trait_name.text().collect::<String>();
-                    if !trait_text.is_empty() {
-                        traits.push(trait_text);
-                    }
-                }
+    /// The method as a Markdown section: its signature, notices, docs and example calls
+    fn to_markdown(&self) -> String {
+        let mut blocks = vec![
+            format!("### `{}`", self.name),
+            output_format::code_block(&self.signature),
+        ];
+        let notices = notices(
+            self.availability.as_deref(),
+            self.docs_only,
+            self.deprecated.as_ref(),
+        );
+        if !notices.is_empty() {
+            blocks.push(notices.join("\n"));
+        }
+        if !self.description.trim().is_empty() {
+            blocks.push(self.description.trim().to_string());
+        }
+        for example in &self.usage_examples {
+            if let Some(code) = example.code() {
+                blocks.push(format!("From `{}`:", example.file()));
+                blocks.push(output_format::code_block(code));
             }
         }
+        blocks.join("\n\n")
+    }
+}
 
-        // Parse fields
-        let field_selector = Selector::parse(".structfield")
-            .map_err(|e| anyhow!("Failed to parse struct field selector: {}", e))?;
-        let field_name_selector = Selector::parse(".structfield-name")
-            .map_err(|e| anyhow!("Failed to parse field name selector: {}", e))?;
-        let field_type_selector = Selector::parse(".type")
-            .map_err(|e| anyhow!("Failed to parse field type selector: {}", e))?;
+/// An `unsafe impl` block and the invariants its doc comment states
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UnsafeImpl {
+    trait_name: String,
+    impl_header: String,
+    /// The impl's doc comment; `None` when the crate doesn't document why the impl is sound
+    safety: Option<String>,
+}
+
+impl UnsafeImpl {
+    fn from_trait_impls(trait_impls: &[TraitImpl]) -> Vec<Self> {
+        trait_impls
+            .iter()
+            .filter(|trait_impl| trait_impl.is_unsafe())
+            .map(|trait_impl| Self {
+                trait_name: trait_impl.trait_name.clone(),
+                impl_header: trait_impl.header.clone(),
+                safety: trait_impl.docs.clone(),
+            })
+            .collect()
+    }
+}
+
+/// A "notable trait" implemented by a method's return type, from rustdoc's ⓘ popover
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NotableTrait {
+    trait_name: String,
+    /// e.g. `impl<'a, T> Iterator for Iter<'a, T>`
+    impl_header: String,
+    /// e.g. `type Item = &'a T;`
+    associated_types: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FieldDoc {
+    name: String,
+    type_name: String,
+    description: String,
+}
+
+impl FieldDoc {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn type_name(&self) -> &str {
+        &self.type_name
+    }
+
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+}
+
+/// Ownership semantics of a type, derived from its trait implementations and method receivers
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OwnershipHints {
+    implements_drop: bool,
+    implements_copy: bool,
+    implements_clone: bool,
+    /// Methods that take `self` by value and therefore consume the receiver
+    consuming_methods: Vec<String>,
+}
+
+impl OwnershipHints {
+    /// Builds the hints from the implemented trait names and the parsed methods of a type
+    fn new<'a>(traits: &[String], methods: impl IntoIterator<Item = &'a MethodDoc>) -> Self {
+        let implements = |name: &str| traits.iter().any(|t| t == name);
+        let consuming_methods = methods
+            .into_iter()
+            .filter(|method| takes_self_by_value(&method.signature))
+            .map(|method| method.name.clone())
+            .collect();
+
+        Self {
+            implements_drop: implements("Drop"),
+            implements_copy: implements("Copy"),
+            implements_clone: implements("Clone"),
+            consuming_methods,
+        }
+    }
+}
+
+/// Returns the first parameter of a function signature, skipping the generic parameter list.
+fn first_parameter(signature: &str) -> Option<&str> {
+    let after_fn = &signature[signature.find("fn ")? + 3..];
+    let mut depth = 0usize;
+    let mut previous = ' ';
+    let mut params_start = None;
+    for (i, c) in after_fn.char_indices() {
+        match c {
+            '<' => depth += 1,
+            // `->` inside generic bounds such as `F: Fn() -> T` doesn't close a bracket
+            '>' if previous != '-' => depth = depth.saturating_sub(1),
+            '(' if depth == 0 => {
+                params_start = Some(i + 1);
+                break;
+            }
+            _ => {}
+        }
+        previous = c;
+    }
+
+    let params = &after_fn[params_start?..];
+    let mut depth = 0usize;
+    for (i, c) in params.char_indices() {
+        match c {
+            '<' | '(' | '[' => depth += 1,
+            '>' | ']' => depth = depth.saturating_sub(1),
+            ')' if depth == 0 => return Some(params[..i].trim()),
+            ')' => depth -= 1,
+            ',' if depth == 0 => return Some(params[..i].trim()),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Whether a method signature takes its receiver by value (`self`, `mut self`, `self: Box<Self>`).
+fn takes_self_by_value(signature: &str) -> bool {
+    let Some(receiver) = first_parameter(signature) else {
+        return false;
+    };
+    let receiver = receiver.trim_start_matches("mut ").trim();
+    match receiver.strip_prefix("self") {
+        Some("") => true,
+        Some(typed) => typed
+            .trim_start()
+            .strip_prefix(':')
+            .is_some_and(|ty| !ty.contains('&')),
+        None => false,
+    }
+}
+
+/// Async and iteration traits implemented by a type, with the associated types needed to
+/// `.await` or loop over it
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Capabilities {
+    is_future: bool,
+    future_output: Option<String>,
+    is_stream: bool,
+    stream_item: Option<String>,
+    is_iterator: bool,
+    iterator_item: Option<String>,
+    is_into_iterator: bool,
+    into_iterator_item: Option<String>,
+    is_async_read: bool,
+    is_async_write: bool,
+    /// Whether the type is `Unpin`, i.e. can be polled without pinning it first
+    is_unpin: bool,
+}
+
+impl Capabilities {
+    /// Classifies a type from all of its trait, auto trait and blanket implementations
+    fn new(trait_impls: &[TraitImpl]) -> Self {
+        let find = |name: &str| trait_impls.iter().find(|i| i.trait_name == name);
+        let associated = |name: &str, assoc: &str| {
+            find(name).and_then(|i| i.associated_type(assoc).map(str::to_string))
+        };
+
+        Self {
+            is_future: find("Future").is_some(),
+            future_output: associated("Future", "Output"),
+            is_stream: find("Stream").is_some(),
+            stream_item: associated("Stream", "Item"),
+            is_iterator: find("Iterator").is_some(),
+            iterator_item: associated("Iterator", "Item"),
+            is_into_iterator: find("IntoIterator").is_some(),
+            into_iterator_item: associated("IntoIterator", "Item"),
+            is_async_read: find("AsyncRead").is_some(),
+            is_async_write: find("AsyncWrite").is_some(),
+            is_unpin: find("Unpin").is_some(),
+        }
+    }
+}
+
+/// Whether a type can be serialized with serde, plus what its docs say about the serialized form
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct SerdeSupport {
+    implements_serialize: bool,
+    implements_deserialize: bool,
+    /// `#[serde(...)]` attributes and sentences from the docs describing the representation
+    notes: Vec<String>,
+}
+
+impl SerdeSupport {
+    fn new(trait_impls: &[TraitImpl], description: &str) -> Self {
+        let implements = |name: &str| trait_impls.iter().any(|i| i.trait_name == name);
+
+        Self {
+            implements_serialize: implements("Serialize"),
+            implements_deserialize: implements("Deserialize"),
+            notes: serde_notes(description),
+        }
+    }
+}
+
+/// Collects serde attributes and serde-related sentences from a type's description.
+fn serde_notes(description: &str) -> Vec<String> {
+    let mut notes: Vec<String> = Vec::new();
+    for line in description.lines().map(str::trim) {
+        let candidates: Vec<&str> = if line.contains("#[serde(") {
+            vec![line]
+        } else {
+            line.split_inclusive(". ").map(str::trim).collect()
+        };
+
+        for candidate in candidates {
+            let lower = candidate.to_lowercase();
+            let relevant = lower.contains("serde")
+                || lower.contains("serializ")
+                || lower.contains("deserializ");
+            if relevant && !notes.iter().any(|note| note == candidate) {
+                notes.push(candidate.to_string());
+            }
+        }
+    }
+    notes
+}
+
+/// A trait implementation block parsed from an item page
+#[derive(Debug)]
+pub(crate) struct TraitImpl {
+    /// Last path segment of the implemented trait, prefixed with `!` for negative impls
+    trait_name: String,
+    /// Associated type names and their values, e.g. `("Output", "Result<T>")`
+    associated_types: Vec<(String, String)>,
+    /// Fragment linking to the impl block on the item page, e.g. `impl-Clone-for-Bytes`
+    anchor: Option<String>,
+    /// The whole impl header, e.g. `impl<T: Clone> From<Vec<T>> for Buffer<T>`
+    header: String,
+    /// Target of the impl's `Source` link, relative to the item page. Auto trait impls have none.
+    source: Option<String>,
+    /// Signatures of the methods the impl block defines
+    methods: Vec<String>,
+    /// Doc comment on the impl block itself; for `unsafe impl`s usually the safety argument
+    docs: Option<String>,
+}
+
+impl TraitImpl {
+    pub(crate) fn trait_name(&self) -> &str {
+        &self.trait_name
+    }
+
+    pub(crate) fn anchor(&self) -> Option<&str> {
+        self.anchor.as_deref()
+    }
+
+    pub(crate) fn header(&self) -> &str {
+        &self.header
+    }
+
+    pub(crate) fn source(&self) -> Option<&str> {
+        self.source.as_deref()
+    }
+
+    pub(crate) fn methods(&self) -> &[String] {
+        &self.methods
+    }
+
+    pub(crate) fn associated_types(&self) -> &[(String, String)] {
+        &self.associated_types
+    }
+
+    pub(crate) fn docs(&self) -> Option<&str> {
+        self.docs.as_deref()
+    }
+
+    pub(crate) fn is_unsafe(&self) -> bool {
+        self.header.starts_with("unsafe ")
+    }
+
+    fn associated_type(&self, name: &str) -> Option<&str> {
+        self.associated_types
+            .iter()
+            .find(|(assoc, _)| assoc == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// Parses the documented methods of a type page, grouped by the impl block defining them.
+/// Blocks without documented methods, such as most auto trait impls, are left out.
+fn parse_impl_blocks(document: &Html, page_url: &str) -> Result<Vec<ImplBlock>> {
+    let impl_block_selector = selectors::parse("details.implementors-toggle", "struct page")?;
+    let impl_header_selector = selectors::parse("summary h3.code-header", "struct page")?;
+    let method_selector = selectors::parse(".impl-items details.method-toggle", "struct page")?;
+    let method_parser = MethodParser::new(document, page_url)?;
+
+    let impls = document
+        .select(&impl_block_selector)
+        .filter_map(|block| {
+            let header = code_header_text(block.select(&impl_header_selector).next()?);
+            let methods: Vec<MethodDoc> = block
+                .select(&method_selector)
+                .map(|method| method_parser.parse(method))
+                .collect();
+            if methods.is_empty() {
+                return None;
+            }
+            Some(ImplBlock {
+                trait_name: impl_trait_name(&header),
+                header,
+                methods,
+            })
+        })
+        .collect();
+
+    Ok(impls)
+}
+
+/// Parses the methods listed under "Methods from Deref<Target = ...>". They have no impl
+/// header: older rustdoc puts them right after the heading, newer wraps them in a
+/// `deref-methods-*` div.
+fn parse_deref_methods(document: &Html, page_url: &str) -> Result<Vec<DerefMethods>> {
+    let heading_selector = selectors::parse(r#"h2[id^="deref-methods"]"#, "struct page")?;
+    let method_selector = selectors::parse("details.method-toggle", "struct page")?;
+    let method_parser = MethodParser::new(document, page_url)?;
+
+    let mut sections = Vec::new();
+    for heading in document.select(&heading_selector) {
+        let Some(target) = deref_target(&text_of(heading)) else {
+            continue;
+        };
+        let methods: Vec<MethodDoc> = heading
+            .next_siblings()
+            .filter_map(ElementRef::wrap)
+            .take_while(|element| element.value().name() != "h2")
+            .flat_map(|element| element.select(&method_selector))
+            .map(|method| method_parser.parse(method))
+            .collect();
+        if !methods.is_empty() {
+            sections.push(DerefMethods { target, methods });
+        }
+    }
+    Ok(sections)
+}
+
+/// The target of a "Methods from Deref<Target = [u8]>" heading
+fn deref_target(heading: &str) -> Option<String> {
+    let (_, target) = heading.split_once("Target = ")?;
+    let target = target.trim().strip_suffix('>')?.trim();
+    (!target.is_empty()).then(|| target.to_string())
+}
+
+/// Reads the methods of an item page, in any of the layouts rustdoc has used: `fnname` was
+/// renamed to `fn`, and `rustdoc-toggle` to `toggle`.
+struct MethodParser {
+    fn_selector: Selector,
+    code_header_selector: Selector,
+    docblock_selector: Selector,
+    notable_selector: Selector,
+    notable_traits: HashMap<String, Vec<NotableTrait>>,
+    /// URL of the page, which links to the source of usage examples are relative to
+    page_url: String,
+}
+
+impl MethodParser {
+    fn new(document: &Html, page_url: &str) -> Result<Self> {
+        Ok(Self {
+            fn_selector: selectors::parse(".code-header .fn, .code-header .fnname", "struct page")?,
+            code_header_selector: selectors::parse(".code-header", "struct page")?,
+            // Scraped examples come in a docblock too
+            docblock_selector: selectors::parse(
+                ".docblock:not(.scraped-example-list)",
+                "struct page",
+            )?,
+            notable_selector: selectors::parse("a.tooltip[data-notable-ty]", "struct page")?,
+            notable_traits: parse_notable_traits(document),
+            page_url: page_url.to_string(),
+        })
+    }
+
+    fn parse(&self, method: ElementRef) -> MethodDoc {
+        let name = method
+            .select(&self.fn_selector)
+            .next()
+            .map(|el| el.text().collect::<String>())
+            .unwrap_or_default()
+            .trim()
+            .to_string();
+
+        let code_header = method.select(&self.code_header_selector).next();
+        // The ⓘ marks a return type with notable traits
+        let signature = code_header
+            .map(|el| el.text().collect::<String>().replace('ⓘ', ""))
+            .unwrap_or_default()
+            .trim()
+            .to_string();
+        let notable_traits = code_header
+            .and_then(|el| el.select(&self.notable_selector).next())
+            .and_then(|tooltip| tooltip.value().attr("data-notable-ty"))
+            .and_then(|ty| self.notable_traits.get(ty))
+            .cloned()
+            .unwrap_or_default();
+
+        let description = method
+            .select(&self.docblock_selector)
+            .next()
+            .map(|el| el.text().collect::<String>())
+            .unwrap_or_default()
+            .trim()
+            .to_string();
+
+        let availability = doc_cfg::banner(method, doc_cfg::MEMBER_BANNER);
+        let required_features = availability
+            .as_deref()
+            .map(doc_cfg::required_features)
+            .unwrap_or_default();
+        let docs_only = availability.as_deref().is_some_and(doc_cfg::is_docs_only);
+        let deprecated = deprecation::banner(method, deprecation::MEMBER_BANNER);
+        let opaque_return = opaque_return::parse(&signature);
+        let usage_examples = scraped_examples::parse(method, &self.page_url);
+
+        MethodDoc {
+            name,
+            signature,
+            description,
+            availability,
+            required_features,
+            docs_only,
+            deprecated,
+            notable_traits,
+            opaque_return,
+            usage_examples,
+        }
+    }
+}
+
+/// Parses every trait, auto trait and blanket implementation listed on an item page.
+pub(crate) fn parse_all_trait_impls(document: &Html) -> Result<Vec<TraitImpl>> {
+    let mut trait_impls = parse_trait_impls(document, "trait-implementations-list")?;
+    trait_impls.extend(parse_trait_impls(
+        document,
+        "synthetic-implementations-list",
+    )?);
+    trait_impls.extend(parse_trait_impls(document, "blanket-implementations-list")?);
+    Ok(trait_impls)
+}
+
+/// Parses the impl blocks listed in one of the implementation sections of an item page,
+/// e.g. `trait-implementations-list` or `synthetic-implementations-list`.
+pub(crate) fn parse_trait_impls(document: &Html, list_id: &str) -> Result<Vec<TraitImpl>> {
+    let impl_selector = selectors::parse(
+        &format!("#{list_id} > details.implementors-toggle, #{list_id} > section.impl"),
+        "item page",
+    )?;
+    let header_selector = selectors::parse("h3.code-header", "item page")?;
+    let assoc_selector = selectors::parse(
+        ".impl-items section.associatedtype .code-header",
+        "item page",
+    )?;
+    let section_selector = selectors::parse("section.impl[id]", "item page")?;
+    let source_selector = selectors::parse("section.impl a.src", "item page")?;
+    let method_selector = selectors::parse(".impl-items section.method .code-header", "item page")?;
+
+    let impls = document
+        .select(&impl_selector)
+        .filter_map(|block| {
+            let header = block
+                .select(&header_selector)
+                .next()
+                .map(code_header_text)?;
+            let trait_name = impl_trait_name(&header)?;
+            let associated_types = block
+                .select(&assoc_selector)
+                .filter_map(|el| parse_associated_type(&el))
+                .collect();
+
+            // Collapsible impls wrap their `section.impl` in a `details` block
+            let anchor = if block.value().name() == "section" {
+                block.value().id()
+            } else {
+                block
+                    .select(&section_selector)
+                    .next()
+                    .and_then(|section| section.value().id())
+            }
+            .map(str::to_string);
+
+            let source = block
+                .select(&source_selector)
+                .next()
+                .and_then(|link| link.value().attr("href"))
+                .map(str::to_string);
+            let methods = block
+                .select(&method_selector)
+                .map(code_header_text)
+                .collect();
+            // Docs of the impl block follow its summary; those inside `.impl-items` are methods'
+            let docs = block
+                .children()
+                .filter_map(ElementRef::wrap)
+                .find(|child| {
+                    child
+                        .value()
+                        .has_class("docblock", CaseSensitivity::CaseSensitive)
+                })
+                .map(|docblock| docblock.text().collect::<String>().trim().to_string())
+                .filter(|docs| !docs.is_empty());
+
+            Some(TraitImpl {
+                trait_name,
+                associated_types,
+                anchor,
+                header,
+                source,
+                methods,
+                docs,
+            })
+        })
+        .collect();
+
+    Ok(impls)
+}
+
+/// Collapses the line breaks and indentation rustdoc puts in long headers, e.g. before `where`.
+fn normalize_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Text of a code header on one line. The where clause is rendered as a separate block with no
+/// space before it, so one is added to keep it apart from the type.
+pub(crate) fn code_header_text(header: ElementRef) -> String {
+    let mut text = String::new();
+    for node in header.descendants() {
+        match node.value() {
+            Node::Text(fragment) => text.push_str(fragment),
+            Node::Element(element)
+                if element.has_class("where", CaseSensitivity::CaseSensitive) =>
+            {
+                text.push(' ')
+            }
+            _ => {}
+        }
+    }
+    normalize_whitespace(&text)
+}
+
+/// Reads the notable traits of return types from the `notable-traits-data` JSON rustdoc embeds
+/// in item pages. It maps each type, as written in `data-notable-ty`, to the HTML of its popover.
+fn parse_notable_traits(document: &Html) -> HashMap<String, Vec<NotableTrait>> {
+    let Ok(data_selector) = selectors::parse("script#notable-traits-data", "struct page") else {
+        return HashMap::new();
+    };
+    let Some(data) = document.select(&data_selector).next() else {
+        return HashMap::new();
+    };
+    let popovers: HashMap<String, String> =
+        match serde_json::from_str(&data.text().collect::<String>()) {
+            Ok(popovers) => popovers,
+            Err(e) => {
+                debug!("Failed to parse notable traits data: {}", e);
+                return HashMap::new();
+            }
+        };
+
+    popovers
+        .into_iter()
+        .map(|(ty, popover)| (ty, parse_notable_popover(&popover)))
+        .collect()
+}
+
+/// Each line of a popover is a `div.where`: an impl header followed by its associated types.
+fn parse_notable_popover(popover: &str) -> Vec<NotableTrait> {
+    let fragment = Html::parse_fragment(popover);
+    let Ok(line_selector) = selectors::parse("div.where", "struct page") else {
+        return Vec::new();
+    };
+
+    let mut notable: Vec<NotableTrait> = Vec::new();
+    for line in fragment.select(&line_selector) {
+        let text = normalize_whitespace(&line.text().collect::<String>());
+        if let Some(trait_name) = impl_trait_name(&text) {
+            notable.push(NotableTrait {
+                trait_name,
+                impl_header: text,
+                associated_types: Vec::new(),
+            });
+        } else if let Some(current) = notable.last_mut().filter(|_| text.starts_with("type ")) {
+            current.associated_types.push(text);
+        }
+    }
+    notable
+}
+
+/// Parses `type Output = Value` from an associated type code header. A generic associated
+/// type keeps its parameters and where clause: `type Item<'a> = &'a T where Self: 'a` gives
+/// `Item<'a>` and `&'a T where Self: 'a`.
+fn parse_associated_type(code_header: &ElementRef) -> Option<(String, String)> {
+    let text = code_header_text(*code_header);
+    let declaration = text.strip_prefix("type ")?.trim_end_matches(';');
+    let (name, value) = associated_items::split_top_level(declaration, " = ");
+    Some((name.trim().to_string(), value?.trim().to_string()))
+}
+
+/// Splits an impl header such as `impl<T: Clone> From<Vec<T>> for Buffer<T>where T: Send`
+/// into the implemented trait (`From<Vec<T>>`) and the rest after ` for `
+/// (`Buffer<T>where T: Send`).
+fn split_impl_header(header: &str) -> Option<(&str, &str)> {
+    let header = header.trim();
+    let header = header.strip_prefix("unsafe ").unwrap_or(header);
+    let mut rest = header.strip_prefix("impl")?;
+
+    if rest.starts_with('<') {
+        let mut depth = 0usize;
+        let mut previous = ' ';
+        let mut end = None;
+        for (i, c) in rest.char_indices() {
+            match c {
+                '<' => depth += 1,
+                '>' if previous != '-' => {
+                    depth = depth.saturating_sub(1);
+                    if depth == 0 {
+                        end = Some(i + 1);
+                        break;
+                    }
+                }
+                _ => {}
+            }
+            previous = c;
+        }
+        rest = &rest[end?..];
+    }
+
+    let mut depth = 0usize;
+    let mut previous = ' ';
+    for (i, c) in rest.char_indices() {
+        match c {
+            '<' => depth += 1,
+            '>' if previous != '-' => depth = depth.saturating_sub(1),
+            _ if depth == 0 && rest[i..].starts_with(" for ") => {
+                return Some((rest[..i].trim(), &rest[i + " for ".len()..]));
+            }
+            _ => {}
+        }
+        previous = c;
+    }
+    None
+}
+
+/// Extracts the implemented trait's name from an impl header such as
+/// `impl<T: Clone> From<Vec<T>> for Buffer<T>`, returning `From`.
+pub(crate) fn impl_trait_name(header: &str) -> Option<String> {
+    let (trait_path, _) = split_impl_header(header)?;
+    let (negative, trait_path) = match trait_path.strip_prefix('!') {
+        Some(path) => (true, path),
+        None => (false, trait_path),
+    };
+    let without_generics = trait_path.split('<').next()?;
+    let name = without_generics.rsplit("::").next()?.trim();
+    if name.is_empty() {
+        return None;
+    }
+
+    Some(if negative {
+        format!("!{name}")
+    } else {
+        name.to_string()
+    })
+}
+
+/// Extracts the implementing type from an impl header, dropping any where clause:
+/// `impl<T> AsyncRead for Box<T>where T: AsyncRead + ?Sized` gives `Box<T>`.
+pub(crate) fn impl_self_type(header: &str) -> Option<String> {
+    let (_, rest) = split_impl_header(header)?;
+    let (self_type, _) = associated_items::split_where_clause(rest);
+    let self_type = self_type.trim();
+    (!self_type.is_empty()).then(|| self_type.to_string())
+}
+
+/// Redirect stubs are chained at most this deep before the page is used as is
+const MAX_REDIRECT_STUBS: usize = 3;
+
+/// Linked item pages read at most
+const MAX_LINKED_ITEMS: usize = 10;
+/// Time spent reading linked item pages; items left when it runs out are listed without a
+/// summary
+const LINKED_ITEMS_BUDGET: Duration = Duration::from_secs(5);
+/// Length linked item summaries are cut to
+const MAX_SUMMARY_CHARS: usize = 300;
+/// Kinds of linked items summarized: the types and traits signatures name
+const LINKED_KINDS: [&str; 6] = ["struct", "enum", "trait", "type", "union", "traitalias"];
+/// Crates of the standard library, whose items need no summary
+const STD_CRATES: [&str; 4] = ["std", "core", "alloc", "proc_macro"];
+
+/// Target of a rustdoc redirect stub page (`<meta http-equiv="refresh" content="0;URL=...">`)
+fn redirect_target(page_url: &str, html: &str) -> Option<String> {
+    let document = Html::parse_document(html);
+    let selector = selectors::parse(r#"meta[http-equiv="refresh"]"#, "redirect page").ok()?;
+    let content = document.select(&selector).next()?.value().attr("content")?;
+    let (_, target) = content.split_once('=')?;
+    let target = Url::parse(page_url).ok()?.join(target.trim()).ok()?;
+    Some(target.to_string())
+}
+
+/// Item path of a rustdoc page URL below `docs_rs_url`:
+/// `https://docs.rs/tokio/1.43.0/tokio/sync/struct.Mutex.html` -> `tokio::sync::Mutex`
+fn canonical_path(docs_rs_url: &str, page_url: &str) -> Option<String> {
+    let base_segments = Url::parse(docs_rs_url)
+        .ok()?
+        .path_segments()
+        .map_or(0, |segments| segments.filter(|s| !s.is_empty()).count());
+    let url = Url::parse(page_url).ok()?;
+    // Skip the crate name and version that follow the base URL
+    let segments: Vec<&str> = url.path_segments()?.skip(base_segments + 2).collect();
+    let (file, modules) = segments.split_last()?;
+    let (_, name) = file.strip_suffix(".html")?.split_once('.')?;
+
+    let mut path = modules.to_vec();
+    path.push(name);
+    Some(path.join("::"))
+}
+
+/// Whether a canonical item path names the requested path, which may omit the crate name.
+fn same_item_path(crate_name: &str, canonical: &str, requested: &str) -> bool {
+    let crate_ident = crate_name.replace('-', "_");
+    canonical == requested || canonical == format!("{}::{}", crate_ident, requested)
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct StructDocsParams {
+    crate_name: String,
+    struct_name: String,
+    version: Option<String>,
+    linked_items: Option<usize>,
+    #[serde(default)]
+    format: Format,
+    max_length: Option<usize>,
+    registry: Option<String>,
+}
+
+pub struct StructDocsTool {
+    html_fetcher: Box<dyn HtmlFetcher>,
+}
+
+impl StructDocsTool {
+    /// Creates a new instance of the StructDocsTool with the default production HTML fetcher.
+    pub fn new() -> Self {
+        Self {
+            html_fetcher: Box::new(HttpHtmlFetcher::new()),
+        }
+    }
+
+    /// Creates a new instance with a test fetcher for testing purposes.
+    #[cfg(test)]
+    pub fn new_with_test_fetcher() -> Self {
+        debug!("Creating StructDocsTool with test fetcher");
+        Self {
+            html_fetcher: Box::new(TestHtmlFetcher),
+        }
+    }
+
+    /// Gets the docs.rs URL from the server configuration.
+    fn get_docs_rs_url(&self) -> String {
+        config::current().sources().docs_rs_url().to_string()
+    }
+
+    /// Fetches HTML content from a URL.
+    async fn fetch_html(&self, url: &str) -> Result<String> {
+        self.html_fetcher.fetch_html(url).await
+    }
+
+    async fn find_struct_url(
+        &self,
+        crate_name: &str,
+        struct_name: &str,
+        version: Option<&str>,
+    ) -> Result<String> {
+        let version = version.unwrap_or("latest");
+        let root_url = docs_root::crate_root(crate_name, version).await;
+        let all_items_url = format!("{}all.html", root_url);
+        let page = all_items_cache::get_or_fetch(&all_items_url, async {
+            debug!("Fetching all items from URL: {}", all_items_url);
+            self.fetch_html(&all_items_url).await
+        })
+        .await?;
+
+        // Extract the struct name without module path
+        let struct_name_without_path = struct_name
+            .split("::")
+            .last()
+            .ok_or_else(|| anyhow!("Invalid struct name: no parts found"))?;
+        let module_path = struct_name
+            .split("::")
+            .take(struct_name.split("::").count() - 1)
+            .collect::<Vec<_>>()
+            .join("::");
+
+        debug!(
+            "Looking for struct: {} (without path: {}, module path: {})",
+            struct_name, struct_name_without_path, module_path
+        );
+
+        let links = page.struct_links()?;
+        debug!("Found {} struct links", links.len());
+        if let Some(struct_path) = links
+            .iter()
+            .find(|link| {
+                // Newer rustdoc lists items by their full path
+                let matches_name = if module_path.is_empty() {
+                    link.text.rsplit("::").next() == Some(struct_name_without_path)
+                } else {
+                    link.text == struct_name
+                        || link.text == format!("{}::{}", module_path, struct_name_without_path)
+                };
+                matches_name && link.href.contains("struct")
+            })
+            .map(|link| link.href.as_str())
+        {
+            debug!("Found struct path: {}", struct_path);
+            if struct_path.starts_with("http") {
+                debug!("Using absolute URL: {}", struct_path);
+                return Ok(struct_path.to_string());
+            }
+            // If we have a module path, we need to check if it's in the URL
+            let path_parts: Vec<&str> = struct_path.split('/').collect();
+            let mut final_path = struct_path.to_string();
+            if !module_path.is_empty() && !path_parts.iter().any(|p| p.contains(&module_path)) {
+                // Insert the module path before the struct name
+                let last_slash = struct_path.rfind('/').unwrap_or(0);
+                final_path = format!(
+                    "{}/{}/{}",
+                    &struct_path[..last_slash],
+                    module_path.replace("::", "/"),
+                    &struct_path[last_slash + 1..]
+                );
+            }
+            let full_url = format!("{}{}", root_url, final_path);
+            debug!("Using constructed URL: {}", full_url);
+            return Ok(full_url);
+        }
+
+        if let Some(url) = self
+            .resolve_reexport(crate_name, struct_name, version)
+            .await
+        {
+            debug!("Resolved re-export of {} to {}", struct_name, url);
+            return Ok(url);
+        }
+
+        error!(
+            "Could not find struct {} in crate {} (version: {})",
+            struct_name, crate_name, version
+        );
+        Err(DocsRsMcpError::ItemNotFound {
+            what: format!("struct {} in crate {}", struct_name, crate_name),
+            did_you_mean: near_structs(links, struct_name),
+        }
+        .into())
+    }
+
+    /// Looks up a struct missing from `all.html` in the module it was requested from, following
+    /// the module's re-exports to the page of the item they point to.
+    async fn resolve_reexport(
+        &self,
+        crate_name: &str,
+        struct_name: &str,
+        version: &str,
+    ) -> Option<String> {
+        let (module, name) = struct_name.rsplit_once("::").unwrap_or(("", struct_name));
+        let module = module_items::module_path(crate_name, module);
+        let root_url = docs_root::crate_root(crate_name, version).await;
+        let url = module_items::module_url(&root_url, &module);
+        let html = self
+            .fetch_html(&url)
+            .await
+            .map_err(|e| debug!("No module page at {}: {}", url, e))
+            .ok()?;
+        let items = module_items::parse_module_page(crate_name, version, &module, &url, &html)
+            .map_err(|e| debug!("Failed to parse module page {}: {}", url, e))
+            .ok()?;
+        items.resolve(name).map(str::to_string)
+    }
+
+    /// Follows the redirect stubs rustdoc leaves at the definition path of re-exported items.
+    async fn follow_redirect_stubs(
+        &self,
+        mut url: String,
+        mut html: String,
+    ) -> Result<(String, String)> {
+        for _ in 0..MAX_REDIRECT_STUBS {
+            let Some(target) = redirect_target(&url, &html) else {
+                break;
+            };
+            debug!("Following redirect stub {} -> {}", url, target);
+            html = self.fetch_html(&target).await?;
+            url = target;
+        }
+        Ok((url, html))
+    }
+
+    pub(crate) async fn fetch_docs(
+        &self,
+        crate_name: &str,
+        struct_name: &str,
+        version: Option<&str>,
+        linked_items: usize,
+    ) -> Result<StructDocs> {
+        info!(
+            "Fetching docs for struct {} in crate {} (version: {:?})",
+            struct_name, crate_name, version
+        );
+        let resolved_version =
+            default_version::resolve_requirement(crate_name, version.unwrap_or("latest")).await?;
+        let version = resolved_version
+            .as_ref()
+            .map(|resolved| resolved.version().to_string())
+            .or(version.map(str::to_string));
+        let version = version.as_deref();
+
+        // Find the correct URL for the struct
+        let url = self
+            .find_struct_url(crate_name, struct_name, version)
+            .await?;
+        debug!("Found struct URL: {}", url);
+
+        let html = self.fetch_html(&url).await?;
+        let (url, html) = self.follow_redirect_stubs(url, html).await?;
+        debug!("Successfully fetched struct HTML ({} bytes)", html.len());
+
+        // The parsed page can't be held across an await, so the linked items and the build are
+        // read after
+        let (mut docs, linked, page) =
+            self.parse_docs(crate_name, struct_name, url, &html, linked_items)?;
+        docs.linked_items = self.summarize(linked).await;
+        docs.version = page.served_version(version.unwrap_or("latest"));
+        docs.resolved_version = resolved_version;
+        docs.docs_build = docs_build::fetch(crate_name, version.unwrap_or("latest"), page).await;
+
+        let requires_features = !docs.required_features.is_empty()
+            || docs
+                .methods_mut()
+                .any(|method| !method.required_features.is_empty());
+        if requires_features {
+            let version = docs
+                .docs_build
+                .as_ref()
+                .map_or(version.unwrap_or("latest"), DocsBuild::version);
+            match sparse_index::feature_names(crate_name, version).await {
+                Ok(declared) => docs.canonicalize_features(&declared),
+                Err(e) => debug!("No feature list of {} {}: {:#}", crate_name, version, e),
+            }
+        }
+        Ok(docs)
+    }
+
+    /// Builds the docs of the struct page at `url`, returning up to `linked_items` of the items
+    /// it links to for [`Self::summarize`] and what the page says about its build along with
+    /// them.
+    fn parse_docs(
+        &self,
+        crate_name: &str,
+        struct_name: &str,
+        url: String,
+        html: &str,
+        linked_items: usize,
+    ) -> Result<(StructDocs, Vec<LinkedItem>, PageBuild)> {
+        let document = Html::parse_document(html);
+
+        // Parse main description
+        let desc_selector = selectors::parse(".toggle.top-doc .docblock", "struct page")?;
+        let description = document
+            .select(&desc_selector)
+            .next()
+            .map(|el| el.text().collect::<String>())
+            .unwrap_or_default()
+            .trim()
+            .to_string();
+
+        let availability = doc_cfg::banner(document.root_element(), doc_cfg::ITEM_BANNER);
+        let required_features = availability
+            .as_deref()
+            .map(doc_cfg::required_features)
+            .unwrap_or_default();
+        let docs_only = availability.as_deref().is_some_and(doc_cfg::is_docs_only);
+        let deprecated = deprecation::banner(document.root_element(), deprecation::ITEM_BANNER);
+
+        let impls = parse_impl_blocks(&document, &url)?;
+        let deref_methods = parse_deref_methods(&document, &url)?;
+        let (associated_constants, associated_types) =
+            associated_items::parse_impl_items(&document)?;
+        let docblock_selector = selectors::parse(".docblock", "struct page")?;
+
+        // Extract trait implementations
+        let mut traits: Vec<String> = Vec::new();
+
+        // Parse selectors for trait implementations. Newer rustdoc puts them in a list next to
+        // the heading rather than under it.
+        let trait_impl_selector = selectors::parse(
+            "#trait-implementations .impl, #trait-implementations-list .impl",
+            "struct page",
+        )?;
+        let trait_name_selector = selectors::parse("h3 .trait", "struct page")?;
+
+        // Check trait implementations
+        for trait_section in document.select(&trait_impl_selector) {
+            if let Some(trait_name) = trait_section.select(&trait_name_selector).next() {
+                let trait_text = trait_name.text().collect::<String>();
+                if !trait_text.is_empty() {
+                    traits.push(trait_text);
+                }
+            }
+        }
+
+        // Check synthetic implementations
+        let synthetic_impl_selector = selectors::parse(
+            "#synthetic-implementations .impl, #synthetic-implementations-list .impl",
+            "struct page",
+        )?;
+
+        if traits.is_empty() {
+            for synthetic_section in document.select(&synthetic_impl_selector) {
+                if let Some(trait_name) = synthetic_section.select(&trait_name_selector).next() {
+                    let trait_text = trait_name.text().collect::<String>();
+                    if !trait_text.is_empty() {
+                        traits.push(trait_text);
+                    }
+                }
+            }
+        }
+
+        // Check blanket implementations
+        let blanket_impl_selector = selectors::parse(
+            "#blanket-implementations .impl, #blanket-implementations-list .impl",
+            "struct page",
+        )?;
+
+        if traits.is_empty() {
+            for blanket_section in document.select(&blanket_impl_selector) {
+                if let Some(trait_name) = blanket_section.select(&trait_name_selector).next() {
+                    let trait_text = trait_name.text().collect::<String>();
+                    if !trait_text.is_empty() {
+                        traits.push(trait_text);
+                    }
+                }
+            }
+        }
+
+        // Parse fields
+        let field_selector = selectors::parse(".structfield", "struct page")?;
+        let field_name_selector = selectors::parse(".structfield-name", "struct page")?;
+        let field_type_selector = selectors::parse(".type", "struct page")?;
 
         let fields = document
             .select(&field_selector)
@@ -428,151 +1821,1185 @@ impl StructDocsTool {
                     .map(|el| el.text().collect::<String>())
                     .unwrap_or_default();
 
-                let type_name = field
-                    .select(&field_type_selector)
-                    .next()
-                    .map(|el| el.text().collect::<String>())
-                    .unwrap_or_default();
+                let type_name = field
+                    .select(&field_type_selector)
+                    .next()
+                    .map(|el| el.text().collect::<String>())
+                    .unwrap_or_default();
+
+                let description = field
+                    .select(&docblock_selector)
+                    .next()
+                    .map(|el| el.text().collect::<String>())
+                    .unwrap_or_default();
+
+                FieldDoc {
+                    name,
+                    type_name,
+                    description,
+                }
+            })
+            .collect();
+
+        let ownership = OwnershipHints::new(&traits, impls.iter().flat_map(|block| &block.methods));
+
+        let trait_impls = parse_all_trait_impls(&document)?;
+        let capabilities = Capabilities::new(&trait_impls);
+        let serde = SerdeSupport::new(&trait_impls, &description);
+        let unsafe_impls = UnsafeImpl::from_trait_impls(&trait_impls);
+
+        let canonical_path = canonical_path(&self.get_docs_rs_url(), &url)
+            .filter(|path| !same_item_path(crate_name, path, struct_name));
+
+        let linked = match linked_items.min(MAX_LINKED_ITEMS) {
+            0 => Vec::new(),
+            limit => linked_item_refs(&document, &url, crate_name, limit),
+        };
+
+        let page = docs_build::from_page(&document);
+        let docs = StructDocs {
+            name: struct_name.to_string(),
+            crate_name: crate_name.to_string(),
+            requested_path: struct_name.to_string(),
+            canonical_path,
+            doc_url: url,
+            version: page.served_version("latest"),
+            resolved_version: None,
+            docs_build: None,
+            truncated: None,
+            description,
+            full_description: None,
+            availability,
+            required_features,
+            docs_only,
+            deprecated,
+            impls,
+            deref_methods,
+            associated_constants,
+            associated_types,
+            traits,
+            fields,
+            ownership,
+            capabilities,
+            serde,
+            unsafe_impls,
+            linked_items: Vec::new(),
+        };
+        Ok((docs, linked, page))
+    }
+
+    /// Reads the opening paragraph of each linked item until the time budget runs out.
+    async fn summarize(&self, items: Vec<LinkedItem>) -> Vec<LinkedItem> {
+        self.summarize_within(items, LINKED_ITEMS_BUDGET).await
+    }
+
+    /// Reads the opening paragraph of each linked item until `budget` runs out, abandoning the
+    /// fetch in flight when it does.
+    async fn summarize_within(
+        &self,
+        mut items: Vec<LinkedItem>,
+        budget: Duration,
+    ) -> Vec<LinkedItem> {
+        let deadline = tokio::time::Instant::now() + budget;
+        for item in &mut items {
+            match tokio::time::timeout_at(deadline, self.fetch_html(&item.doc_url)).await {
+                Ok(Ok(html)) => item.summary = parse_summary(&html),
+                Ok(Err(e)) => debug!("Failed to read linked item {}: {}", item.doc_url, e),
+                Err(_) => {
+                    debug!("Time budget for linked items spent at {}", item.path);
+                    break;
+                }
+            }
+        }
+        items
+    }
+}
+
+/// Types and traits the fields and method signatures link to, the crate's own first, up to
+/// `limit`. Items of the standard library and links back to the page itself are left out.
+fn linked_item_refs(
+    document: &Html,
+    page_url: &str,
+    crate_name: &str,
+    limit: usize,
+) -> Vec<LinkedItem> {
+    let Ok(page) = Url::parse(page_url) else {
+        return Vec::new();
+    };
+    let Ok(link_selector) = selectors::parse(
+        ".structfield a[title], #implementations-list .code-header a[title]",
+        "struct page",
+    ) else {
+        return Vec::new();
+    };
+
+    let mut items: Vec<LinkedItem> = Vec::new();
+    for link in document.select(&link_selector) {
+        let title = link.value().attr("title").unwrap_or_default();
+        let Some((kind, path)) = title.split_once(' ') else {
+            continue;
+        };
+        let krate = path.split("::").next().unwrap_or_default();
+        if !LINKED_KINDS.contains(&kind) || STD_CRATES.contains(&krate) {
+            continue;
+        }
+        let Some(mut url) = link
+            .value()
+            .attr("href")
+            .and_then(|href| page.join(href).ok())
+        else {
+            continue;
+        };
+        url.set_fragment(None);
+        if url.path() == page.path() || items.iter().any(|item| item.doc_url == url.as_str()) {
+            continue;
+        }
+        items.push(LinkedItem {
+            path: path.to_string(),
+            kind: kind.to_string(),
+            doc_url: url.to_string(),
+            summary: None,
+        });
+    }
+
+    let own_prefix = format!("{}::", crate_name.replace('-', "_"));
+    items.sort_by_key(|item| !item.path.starts_with(&own_prefix));
+    items.truncate(limit);
+    items
+}
+
+/// The opening paragraph of an item page, cut to `MAX_SUMMARY_CHARS`
+fn parse_summary(html: &str) -> Option<String> {
+    let document = Html::parse_document(html);
+    let selector = selectors::parse(".toggle.top-doc .docblock > p", "item page").ok()?;
+    let paragraph = document.select(&selector).next()?;
+    let summary = normalize_whitespace(&paragraph.text().collect::<String>());
+    if summary.is_empty() {
+        return None;
+    }
+    match summary.char_indices().nth(MAX_SUMMARY_CHARS) {
+        Some((end, _)) => Some(format!("{}…", summary[..end].trim_end())),
+        None => Some(summary),
+    }
+}
+
+/// Structs of the all items page named closest to `struct_name`, for a lookup that failed
+fn near_structs(links: &[StructLink], struct_name: &str) -> Vec<String> {
+    near_matches::nearest(
+        struct_name,
+        links
+            .iter()
+            .filter(|link| link.href.contains("struct."))
+            .map(|link| link.text.as_str()),
+    )
+    .into_iter()
+    .map(str::to_string)
+    .collect()
+}
+
+impl Default for StructDocsTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "native")]
+impl Tool for StructDocsTool {
+    fn name(&self) -> String {
+        "get_struct_docs".to_string()
+    }
+
+    fn description(&self) -> String {
+        "Fetches and parses documentation for a Rust struct from docs.rs. Set linked_items to \
+        also get the opening paragraph of the types and traits its fields and methods use. \
+        docs_build tells when docs.rs built the documentation, with which rustdoc, and the \
+        latest release when a newer one is out, with latest_release_docs telling whether its docs \
+        are built, pending or failed when docs.rs' latest still documents an older release. A version requirement such as ^1.0 is resolved \
+        to the highest matching release, given as resolved_version."
+            .to_string()
+    }
+
+    fn input_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "required": ["crate_name", "struct_name"],
+            "properties": {
+                "crate_name": {
+                    "type": "string",
+                    "description": "Name of the crate containing the struct"
+                },
+                "struct_name": {
+                    "type": "string",
+                    "description": "Name of the struct to look up"
+                },
+                "version": {
+                    "type": "string",
+                    "description": "Optional version of the crate, or a semver requirement such as ^1.0 or >=0.28, <0.30 resolved to the highest matching release. Defaults to latest if not specified"
+                },
+                "linked_items": {
+                    "type": "integer",
+                    "description": "Number of types and traits linked from the fields and method signatures to summarize, at most 10. Pages not read within a few seconds are listed without a summary (defaults to 0)"
+                },
+                "format": output_format::format_schema(),
+                "max_length": {
+                    "type": "integer",
+                    "description": "Characters the response should fit in. Over it, the least useful parts are left out until it fits: linked items, example calls, trait impl methods, Deref methods, return type notes, then method and field docs. The description and method signatures are kept. truncated lists what was left out and the resource holding the full docs"
+                },
+                "registry": registry::registry_schema()
+            }
+        })
+    }
+
+    fn call(&self, input: Option<serde_json::Value>) -> Result<CallToolResponse> {
+        let params: StructDocsParams = error::parse_args(input)?;
+        config::current().ensure_crate_allowed(&params.crate_name)?;
+
+        let mut docs = runtime::block_on(registry::in_registry_async(
+            &params.crate_name,
+            params.registry.as_deref(),
+            self.fetch_docs(
+                &params.crate_name,
+                &params.struct_name,
+                params.version.as_deref(),
+                params.linked_items.unwrap_or_default(),
+            ),
+        ))?;
+        docs.full_description = summary::shorten(
+            &format!("Description of {}::{}", docs.crate_name, docs.name),
+            &mut docs.description,
+        );
+
+        if let Some(max_length) = params.max_length {
+            docs.shorten(max_length, |docs| {
+                params
+                    .format
+                    .render(docs, StructDocs::to_markdown, StructDocs::to_digest)
+            })?;
+        }
+        params
+            .format
+            .response(&docs, StructDocs::to_markdown, StructDocs::to_digest)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mcp_sdk::types::ToolResponseContent;
+    use std::fs;
+
+    #[tokio::test]
+    async fn test_find_struct_url() -> Result<()> {
+        let tool = StructDocsTool::new_with_test_fetcher();
+
+        // Test with exact name
+        let url = tool
+            .find_struct_url("opentelemetry_sdk", "TracerProviderBuilder", Some("0.28.0"))
+            .await?;
+        assert!(
+            url.contains("opentelemetry_sdk/trace/struct.TracerProviderBuilder.html"),
+            "URL should contain correct path"
+        );
+
+        // Test with module path
+        let url = tool
+            .find_struct_url(
+                "opentelemetry_sdk",
+                "trace::TracerProviderBuilder",
+                Some("0.28.0"),
+            )
+            .await?;
+        assert!(
+            url.contains("opentelemetry_sdk/trace/struct.TracerProviderBuilder.html"),
+            "URL should contain correct path"
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_fetch_docs() -> Result<()> {
+        let tool = StructDocsTool::new_with_test_fetcher();
+
+        // Test with exact name
+        let docs = tool
+            .fetch_docs(
+                "opentelemetry_sdk",
+                "TracerProviderBuilder",
+                Some("0.28.0"),
+                0,
+            )
+            .await?;
+        assert_eq!(docs.name, "TracerProviderBuilder", "Wrong struct name");
+        assert_eq!(docs.crate_name, "opentelemetry_sdk", "Wrong crate name");
+        assert!(!docs.description.is_empty(), "Should have a description");
+        assert!(!docs.traits.is_empty(), "Should have traits");
+
+        // Test with module path
+        let docs = tool
+            .fetch_docs(
+                "opentelemetry_sdk",
+                "trace::TracerProviderBuilder",
+                Some("0.28.0"),
+                0,
+            )
+            .await?;
+        assert_eq!(
+            docs.name, "trace::TracerProviderBuilder",
+            "Wrong struct name"
+        );
+        assert_eq!(docs.crate_name, "opentelemetry_sdk", "Wrong crate name");
+        assert!(!docs.description.is_empty(), "Should have a description");
+        assert!(!docs.traits.is_empty(), "Should have traits");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_call() -> Result<()> {
+        let tool = StructDocsTool::new_with_test_fetcher();
+        let response = tool.call(Some(serde_json::json!({
+            "crate_name": "opentelemetry_sdk",
+            "struct_name": "TracerProviderBuilder",
+            "version": "0.28.0"
+        })))?;
+        let ToolResponseContent::Text { text } = &response.content[0] else {
+            panic!("Expected a text response");
+        };
+        let docs: serde_json::Value = serde_json::from_str(text)?;
+
+        assert_eq!(docs["name"], "TracerProviderBuilder");
+        assert_eq!(docs["crate_name"], "opentelemetry_sdk");
+        assert!(docs["description"].as_str().is_some_and(|d| !d.is_empty()));
+        let first_impl = &docs["impls"][0];
+        assert!(
+            first_impl["header"]
+                .as_str()
+                .is_some_and(|h| h.starts_with("impl")),
+            "Impl blocks should carry their header"
+        );
+        let method = &first_impl["methods"][0];
+        for field in ["name", "signature", "description"] {
+            assert!(
+                method[field].as_str().is_some_and(|v| !v.is_empty()),
+                "Methods should have a {}",
+                field
+            );
+        }
+        assert!(docs["traits"].as_array().is_some_and(|t| !t.is_empty()));
+        Ok(())
+    }
+
+    /// Answers every request after `delay`
+    struct SlowFetcher {
+        delay: Duration,
+    }
+
+    #[async_trait::async_trait]
+    impl HtmlFetcher for SlowFetcher {
+        async fn fetch_html(&self, _url: &str) -> Result<String> {
+            tokio::time::sleep(self.delay).await;
+            Ok("<div class=\"docblock\"><p>Slow.</p></div>".to_string())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_summarize_within_budget() {
+        let tool = StructDocsTool {
+            html_fetcher: Box::new(SlowFetcher {
+                delay: Duration::from_secs(30),
+            }),
+        };
+        let items = vec![LinkedItem {
+            path: "demo::Slow".to_string(),
+            kind: "struct".to_string(),
+            doc_url: "https://docs.rs/demo/1.0.0/demo/struct.Slow.html".to_string(),
+            summary: None,
+        }];
+        let start = std::time::Instant::now();
+        let items = tool
+            .summarize_within(items, Duration::from_millis(50))
+            .await;
+        assert!(
+            start.elapsed() < Duration::from_secs(5),
+            "A slow page is abandoned when the budget runs out"
+        );
+        assert_eq!(items[0].summary, None);
+    }
+
+    #[test]
+    fn test_linked_item_refs() -> Result<()> {
+        let html = fs::read_to_string("test-data/surreal-struct-surrealdb.html")?;
+        let items = linked_item_refs(
+            &Html::parse_document(&html),
+            "https://docs.rs/surrealdb/2.1.4/surrealdb/struct.Surreal.html#method.init",
+            "surrealdb",
+            4,
+        );
+
+        let paths: Vec<&str> = items.iter().map(LinkedItem::path).collect();
+        assert_eq!(
+            paths,
+            vec![
+                "surrealdb::engine::any::Any",
+                "surrealdb::engine::any::IntoEndpoint",
+                "surrealdb::Connect",
+                "surrealdb::engine::local::Db",
+            ],
+            "Surreal itself is skipped"
+        );
+        assert_eq!(
+            items[0].doc_url,
+            "https://docs.rs/surrealdb/2.1.4/surrealdb/engine/any/struct.Any.html"
+        );
+
+        let items = linked_item_refs(
+            &Html::parse_document(&html),
+            "https://docs.rs/surrealdb/2.1.4/surrealdb/struct.Surreal.html",
+            "surrealdb",
+            MAX_LINKED_ITEMS * 10,
+        );
+        let last = items.last().map(LinkedItem::path).unwrap_or_default();
+        assert!(
+            !last.starts_with("surrealdb::"),
+            "Other crates' items come last, got {}",
+            last
+        );
+        assert!(
+            !items
+                .iter()
+                .any(|item| item.path.starts_with("alloc::") || item.path.starts_with("core::")),
+            "Standard library items are skipped"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_summary() -> Result<()> {
+        let html = fs::read_to_string(
+            "test-data/get_struct_docs/opentelemetry-sdk-0.28.0-tracer-provider-builder.html",
+        )?;
+        assert_eq!(
+            parse_summary(&html).as_deref(),
+            Some("Builder for provider attributes.")
+        );
+
+        let long = format!(
+            r#"<details class="toggle top-doc"><div class="docblock"><p>{}</p><p>More.</p></div></details>"#,
+            "word ".repeat(100)
+        );
+        let summary = parse_summary(&long).unwrap_or_default();
+        assert_eq!(summary.chars().count(), MAX_SUMMARY_CHARS);
+        assert!(summary.ends_with("word…"));
+        assert_eq!(parse_summary("<p>No docblock</p>"), None);
+        Ok(())
+    }
 
-                let description = field
-                    .select(&docblock_selector)
-                    .next()
-                    .map(|el| el.text().collect::<String>())
-                    .unwrap_or_default();
+    #[test]
+    fn test_redirect_stub() {
+        let stub = r#"<!DOCTYPE html><html lang="en"><head><meta http-equiv="refresh" content="0;URL=../../../tokio/sync/struct.Mutex.html"><title>Redirection</title></head><body><p>Redirecting to <a href="../../../tokio/sync/struct.Mutex.html">../../../tokio/sync/struct.Mutex.html</a>...</p></body></html>"#;
+        assert_eq!(
+            redirect_target(
+                "https://docs.rs/tokio/1.43.0/tokio/sync/mutex/struct.Mutex.html",
+                stub
+            ),
+            Some("https://docs.rs/tokio/1.43.0/tokio/sync/struct.Mutex.html".to_string())
+        );
+        assert_eq!(
+            redirect_target(
+                "https://docs.rs/tokio/1.43.0/tokio/sync/struct.Mutex.html",
+                "<html></html>"
+            ),
+            None
+        );
+    }
 
-                FieldDoc {
-                    name,
-                    type_name,
-                    description,
-                }
-            })
-            .collect();
+    #[test]
+    fn test_canonical_path() {
+        assert_eq!(
+            canonical_path(
+                "https://docs.rs",
+                "https://docs.rs/futures/0.3.31/futures_channel/mpsc/struct.Sender.html"
+            ),
+            Some("futures_channel::mpsc::Sender".to_string())
+        );
+        assert_eq!(
+            canonical_path(
+                "https://docs.internal/rustdoc",
+                "https://docs.internal/rustdoc/acme/1.0.0/acme/struct.Client.html"
+            ),
+            Some("acme::Client".to_string())
+        );
+        assert!(same_item_path("tokio", "tokio::sync::Mutex", "sync::Mutex"));
+        assert!(!same_item_path(
+            "futures",
+            "futures_channel::mpsc::Sender",
+            "channel::mpsc::Sender"
+        ));
+    }
 
-        Ok(StructDocs {
-            name: struct_name.to_string(),
-            crate_name: crate_name.to_string(),
-            description,
-            methods,
-            traits,
-            fields,
-        })
+    #[test]
+    fn test_takes_self_by_value() {
+        let cases = [
+            ("pub fn build(self) -> SdkTracerProvider", true),
+            (
+                "pub fn with_sampler<T: ShouldSample + 'static>(mut self, sampler: T) -> Self",
+                true,
+            ),
+            ("pub fn into_inner(self: Box<Self>) -> T", true),
+            ("fn fmt(&self, f: &mut Formatter<'_>) -> Result", false),
+            ("pub fn set(&mut self, value: u32)", false),
+            (
+                "fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()>",
+                false,
+            ),
+            ("fn default() -> TracerProviderBuilder", false),
+            ("pub fn map<F: Fn(u32) -> u32>(self, f: F) -> Self", true),
+            ("pub fn selfish(selfie: u32)", false),
+        ];
+
+        for (signature, expected) in cases {
+            assert_eq!(
+                takes_self_by_value(signature),
+                expected,
+                "Wrong receiver detection for `{}`",
+                signature
+            );
+        }
     }
-}
 
-impl Default for StructDocsTool {
-    fn default() -> Self {
-        Self::new()
+    #[test]
+    fn test_ownership_hints() {
+        let method = |name: &str, signature: &str| MethodDoc {
+            name: name.to_string(),
+            signature: signature.to_string(),
+            description: String::new(),
+            availability: None,
+            required_features: Vec::new(),
+            docs_only: false,
+            deprecated: None,
+            notable_traits: Vec::new(),
+            opaque_return: None,
+            usage_examples: Vec::new(),
+        };
+        let methods = [
+            method(
+                "with_batch_exporter",
+                "pub fn with_batch_exporter<T: SpanExporter + 'static>(self, exporter: T) -> Self",
+            ),
+            method("build", "pub fn build(self) -> SdkTracerProvider"),
+            method("fmt", "fn fmt(&self, f: &mut Formatter<'_>) -> Result"),
+        ];
+        let traits = ["Clone".to_string(), "Debug".to_string()];
+
+        let ownership = OwnershipHints::new(&traits, &methods);
+
+        assert!(ownership.implements_clone, "Clone impl should be detected");
+        assert!(!ownership.implements_copy, "Type should not be Copy");
+        assert!(!ownership.implements_drop, "Type should not implement Drop");
+        assert_eq!(
+            ownership.consuming_methods,
+            vec!["with_batch_exporter".to_string(), "build".to_string()],
+            "Only methods taking self by value should be listed"
+        );
     }
-}
 
-impl Tool for StructDocsTool {
-    fn name(&self) -> String {
-        "get_struct_docs".to_string()
+    #[test]
+    fn test_impl_trait_name() {
+        let cases = [
+            (
+                "impl<C> Clone for Surreal<C>where C: Connection,",
+                Some("Clone"),
+            ),
+            ("impl<T: Clone> From<Vec<T>> for Buffer<T>", Some("From")),
+            (
+                "impl<F> Future for Timeout<F>where F: Future,",
+                Some("Future"),
+            ),
+            (
+                "impl !RefUnwindSafe for TracerProviderBuilder",
+                Some("!RefUnwindSafe"),
+            ),
+            ("unsafe impl Send for Bytes", Some("Send")),
+            (
+                "impl<F: Fn() -> u32> core::ops::Deref for Lazy<F>",
+                Some("Deref"),
+            ),
+            ("impl Surreal<Any>", None),
+        ];
+
+        for (header, expected) in cases {
+            assert_eq!(
+                impl_trait_name(header).as_deref(),
+                expected,
+                "Wrong trait name for `{}`",
+                header
+            );
+        }
     }
 
-    fn description(&self) -> String {
-        "Fetches and parses documentation for a Rust struct from docs.rs".to_string()
+    #[test]
+    fn test_parse_notable_traits() {
+        let html = r##"<html><body><section id="main-content">
+            <h4 class="code-header">pub fn <a href="#method.iter" class="fn">iter</a>(&amp;self) -&gt; <a class="struct" href="struct.Iter.html">Iter</a>&lt;'_, T&gt; <a href="#" class="tooltip" data-notable-ty="Iter&lt;'_, T&gt;">ⓘ</a></h4>
+            </section>
+            <script type="text/json" id="notable-traits-data">{"Iter<'_, T>":"<h3>Notable traits for <code><a class=\"struct\" href=\"struct.Iter.html\">Iter</a>&lt;'a, T&gt;</code></h3><pre><code><div class=\"where\">impl&lt;'a, T&gt; <a class=\"trait\" href=\"https://doc.rust-lang.org/nightly/core/iter/traits/iterator/trait.Iterator.html\">Iterator</a> for <a class=\"struct\" href=\"struct.Iter.html\">Iter</a>&lt;'a, T&gt;</div><div class=\"where\">    type <a href=\"https://doc.rust-lang.org/nightly/core/iter/traits/iterator/trait.Iterator.html#associatedtype.Item\" class=\"associatedtype\">Item</a> = &amp;'a T;</div></code></pre>"}</script>
+            </body></html>"##;
+        let document = Html::parse_document(html);
+
+        let notable = parse_notable_traits(&document);
+        assert_eq!(
+            notable.get("Iter<'_, T>"),
+            Some(&vec![NotableTrait {
+                trait_name: "Iterator".to_string(),
+                impl_header: "impl<'a, T> Iterator for Iter<'a, T>".to_string(),
+                associated_types: vec!["type Item = &'a T;".to_string()],
+            }])
+        );
+        assert!(parse_notable_traits(&Html::parse_document("<html></html>")).is_empty());
     }
 
-    fn input_schema(&self) -> serde_json::Value {
-        json!({
-            "type": "object",
-            "required": ["crate_name", "struct_name"],
-            "properties": {
-                "crate_name": {
-                    "type": "string",
-                    "description": "Name of the crate containing the struct"
-                },
-                "struct_name": {
-                    "type": "string",
-                    "description": "Name of the struct to look up"
-                },
-                "version": {
-                    "type": "string",
-                    "description": "Optional version of the crate. Defaults to latest if not specified"
-                }
-            }
-        })
+    #[test]
+    fn test_impl_self_type() {
+        let cases = [
+            ("impl AsyncRead for TcpStream", Some("TcpStream")),
+            (
+                "impl<T: AsyncRead + ?Sized + Unpin> AsyncRead for Box<T>",
+                Some("Box<T>"),
+            ),
+            (
+                "impl<F> Future for Timeout<F>where F: Future,",
+                Some("Timeout<F>"),
+            ),
+            (
+                "impl<P> AsyncRead for Pin<P>where P: DerefMut + Unpin, P::Target: AsyncRead,",
+                Some("Pin<P>"),
+            ),
+            ("impl AsyncRead for Somewhere", Some("Somewhere")),
+            ("impl<T> Read for &[T]", Some("&[T]")),
+            ("impl Surreal<Any>", None),
+        ];
+
+        for (header, expected) in cases {
+            assert_eq!(
+                impl_self_type(header).as_deref(),
+                expected,
+                "Wrong self type for `{}`",
+                header
+            );
+        }
     }
 
-    fn call(&self, input: Option<serde_json::Value>) -> Result<CallToolResponse> {
-        let params: StructDocsParams = serde_json::from_value(input.unwrap_or_default())?;
-
-        // Clone the parameters for the blocking task
-        let crate_name = params.crate_name.clone();
-        let struct_name = params.struct_name.clone();
-        let version = params.version.clone();
-
-        // Run the blocking HTTP requests in a blocking task
-        let docs = tokio::task::block_in_place(|| {
-            self.fetch_docs(&crate_name, &struct_name, version.as_deref())
-        })?;
-
-        Ok(CallToolResponse {
-            content: vec![ToolResponseContent::Text {
-                text: serde_json::to_string_pretty(&docs)?,
-            }],
-            is_error: None,
-            meta: None,
-        })
+    #[test]
+    fn test_parse_impl_blocks_surreal() -> Result<()> {
+        let html = fs::read_to_string("test-data/surreal-struct-surrealdb.html")?;
+        let impls = parse_impl_blocks(
+            &Html::parse_document(&html),
+            "https://docs.rs/surrealdb/latest/surrealdb/struct.Surreal.html",
+        )?;
+        let headers: Vec<&str> = impls.iter().map(|i| i.header.as_str()).collect();
+
+        assert_eq!(&headers[..2], ["impl Surreal<Any>", "impl Surreal<Db>"]);
+        let generic = impls
+            .iter()
+            .find(|i| i.header.starts_with("impl<C> Surreal<C>"))
+            .expect("The generic impl block should be listed");
+        assert!(
+            generic.header.contains("where C: Connection"),
+            "The bounds gating the methods are kept: {}",
+            generic.header
+        );
+        assert_eq!(generic.trait_name, None);
+        assert!(generic.methods.iter().any(|m| m.name == "use_ns"));
+        assert!(
+            !impls[0].methods.iter().any(|m| m.name == "use_ns"),
+            "Methods belong to the block defining them"
+        );
+
+        let clone = impls
+            .iter()
+            .find(|i| i.trait_name.as_deref() == Some("Clone"))
+            .expect("Trait impl blocks with methods are listed");
+        assert_eq!(clone.methods[0].name, "clone");
+
+        let wait_for = generic
+            .methods
+            .iter()
+            .find(|m| m.name == "wait_for")
+            .expect("wait_for");
+        let opaque = wait_for
+            .opaque_return()
+            .expect("async fns return an opaque future");
+        assert_eq!(opaque.bounds(), ["Future<Output = ()>"]);
+        assert!(
+            clone.methods[0].opaque_return().is_none(),
+            "clone returns a concrete type"
+        );
+        Ok(())
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use std::fs;
+    #[test]
+    fn test_to_markdown() -> Result<()> {
+        let html = fs::read_to_string("test-data/surreal-struct-surrealdb.html")?;
+        let (docs, _, page) = StructDocsTool::new().parse_docs(
+            "surrealdb",
+            "Surreal",
+            "https://docs.rs/surrealdb/latest/surrealdb/struct.Surreal.html".to_string(),
+            &html,
+            0,
+        )?;
+        assert!(page.rustdoc_version().is_some(), "{:?}", page);
+        assert_eq!(
+            docs.version(),
+            "2.2.0",
+            "latest is the release the page shows"
+        );
+
+        let markdown = docs.to_markdown();
+        assert!(
+            markdown.starts_with("# Struct `Surreal`\n\nCrate `surrealdb`: https://docs.rs/"),
+            "{}",
+            markdown
+        );
+        assert!(markdown.contains("\n## `impl<C> Surreal<C> where C: Connection"));
+        assert!(
+            markdown.contains("### `wait_for`\n\n```rust\npub async fn wait_for("),
+            "Signatures are fenced: {}",
+            markdown
+        );
+        assert!(
+            markdown.contains("## Trait implementations\n\n- `impl<C> Clone for Surreal<C>"),
+            "{}",
+            markdown
+        );
+        assert!(
+            !markdown.contains("### `clone`"),
+            "Trait methods are left to the traits' docs"
+        );
+        let json = serde_json::to_string_pretty(&docs)?;
+        assert!(
+            markdown.len() < json.len() * 2 / 3,
+            "Markdown leaves out the JSON structure: {} vs {} bytes",
+            markdown.len(),
+            json.len()
+        );
+        Ok(())
+    }
 
     #[test]
-    fn test_find_struct_url() -> Result<()> {
-        let tool = StructDocsTool::new_with_test_fetcher();
+    fn test_near_structs() {
+        let link = |text: &str, href: &str| StructLink {
+            text: text.to_string(),
+            href: href.to_string(),
+        };
+        let links = [
+            link(
+                "trace::SdkTracerProvider",
+                "trace/struct.SdkTracerProvider.html",
+            ),
+            link("trace::Tracer", "trace/struct.Tracer.html"),
+            link(
+                "trace::TracerProviderBuilder",
+                "trace/struct.TracerProviderBuilder.html",
+            ),
+            link("trace::TracerProvider", "trace/trait.TracerProvider.html"),
+        ];
+        assert_eq!(
+            near_structs(&links, "opentelemetry_sdk::TracerProvider"),
+            ["trace::SdkTracerProvider", "trace::TracerProviderBuilder"],
+            "Only structs are suggested"
+        );
+    }
 
-        // Test with exact name
-        let url =
-            tool.find_struct_url("opentelemetry_sdk", "TracerProviderBuilder", Some("0.28.0"))?;
+    #[test]
+    fn test_to_digest() -> Result<()> {
+        let html = fs::read_to_string("test-data/surreal-struct-surrealdb.html")?;
+        let (docs, _, _) = StructDocsTool::new().parse_docs(
+            "surrealdb",
+            "Surreal",
+            "https://docs.rs/surrealdb/latest/surrealdb/struct.Surreal.html".to_string(),
+            &html,
+            0,
+        )?;
+        let digest = docs.to_digest();
+        let lines: Vec<&str> = digest.lines().collect();
+        assert_eq!(
+            lines[0],
+            "surrealdb::Surreal | struct | - | A database client instance for embedded or remote databases"
+        );
         assert!(
-            url.contains("opentelemetry_sdk/trace/struct.TracerProviderBuilder.html"),
-            "URL should contain correct path"
+            lines.contains(
+                &"surrealdb::Surreal::wait_for | method | pub async fn wait_for(&self, event: WaitFor) | Wait for the selected event to happen before proceeding"
+            ),
+            "{}",
+            digest
+        );
+        assert!(lines.contains(
+            &"surrealdb::Surreal | impl | impl<C> Clone for Surreal<C> where C: Connection,"
+        ));
+        assert!(lines.iter().all(|line| line.split(" | ").count() >= 2));
+        assert!(
+            digest.len() < docs.to_markdown().len() / 3,
+            "Denser than the Markdown: {} bytes",
+            digest.len()
         );
+        Ok(())
+    }
 
-        // Test with module path
-        let url = tool.find_struct_url(
-            "opentelemetry_sdk",
-            "trace::TracerProviderBuilder",
-            Some("0.28.0"),
+    #[test]
+    fn test_shorten() -> Result<()> {
+        let html = fs::read_to_string("test-data/surreal-struct-surrealdb.html")?;
+        let (mut docs, _, _) = StructDocsTool::new().parse_docs(
+            "surrealdb",
+            "Surreal",
+            "https://docs.rs/surrealdb/latest/surrealdb/struct.Surreal.html".to_string(),
+            &html,
+            0,
         )?;
+        let full = docs.to_markdown();
+        let render = |docs: &StructDocs| {
+            Format::Markdown.render(docs, StructDocs::to_markdown, StructDocs::to_digest)
+        };
+
+        docs.shorten(full.chars().count(), render)?;
+        assert_eq!(docs.truncated, None, "Fits already");
+
+        docs.shorten(full.chars().count() / 3, render)?;
+        let truncation = docs.truncated.clone().expect("shortened");
+        assert_eq!(truncation.full_length, full.chars().count());
+        assert!(!truncation.elided.is_empty());
         assert!(
-            url.contains("opentelemetry_sdk/trace/struct.TracerProviderBuilder.html"),
-            "URL should contain correct path"
+            truncation.elided.iter().all(|what| !what.starts_with('0')),
+            "{:?}",
+            truncation.elided
         );
+        let uri = truncation.full_response.expect("full docs kept");
+        assert_eq!(resources::read(&uri), Some(full));
 
+        let markdown = docs.to_markdown();
+        assert!(markdown.contains(&uri), "Points to the full docs");
+        assert!(
+            markdown.contains("```rust\npub async fn wait_for("),
+            "Signatures are kept: {}",
+            markdown
+        );
         Ok(())
     }
 
     #[test]
-    fn test_fetch_docs() -> Result<()> {
-        let tool = StructDocsTool::new_with_test_fetcher();
+    fn test_method_buckets_across_layouts() -> Result<()> {
+        // The same struct as rendered by three rustdoc generations: `rustdoc-toggle` and
+        // `fnname` (1.58), `section` headers and a wrapped deref list (1.70), and the current
+        // `section-header` layout (1.86)
+        for version in ["1.58.0", "1.70.0", "1.86.0"] {
+            let html = fs::read_to_string(format!(
+                "test-data/get_struct_docs/layouts/struct.Buffer-{}.html",
+                version
+            ))?;
+            let document = Html::parse_document(&html);
+
+            let page_url = "https://docs.rs/demo/latest/demo/struct.Buffer.html";
+            let impls = parse_impl_blocks(&document, page_url)?;
+            let buckets: Vec<(Option<&str>, Vec<&str>)> = impls
+                .iter()
+                .map(|i| {
+                    (
+                        i.trait_name.as_deref(),
+                        i.methods.iter().map(|m| m.name.as_str()).collect(),
+                    )
+                })
+                .collect();
+            assert_eq!(
+                buckets,
+                vec![
+                    (None, vec!["new", "len"]),
+                    (Some("Clone"), vec!["clone"]),
+                    (Some("Deref"), vec!["deref"]),
+                    (Some("From"), vec!["from"]),
+                ],
+                "Wrong impl blocks for rustdoc {}",
+                version
+            );
+            assert_eq!(impls[0].header, "impl Buffer");
+            assert_eq!(
+                impls[0].methods[1].signature, "pub fn len(&self) -> usize",
+                "Wrong signature for rustdoc {}",
+                version
+            );
 
-        // Test with exact name
-        let docs = tool.fetch_docs("opentelemetry_sdk", "TracerProviderBuilder", Some("0.28.0"))?;
-        assert_eq!(docs.name, "TracerProviderBuilder", "Wrong struct name");
-        assert_eq!(docs.crate_name, "opentelemetry_sdk", "Wrong crate name");
-        assert!(!docs.description.is_empty(), "Should have a description");
-        assert!(!docs.traits.is_empty(), "Should have traits");
+            let deref = parse_deref_methods(&document, page_url)?;
+            assert_eq!(
+                deref.len(),
+                1,
+                "Wrong deref sections for rustdoc {}",
+                version
+            );
+            assert_eq!(deref[0].target, "[u8]");
+            let names: Vec<&str> = deref[0].methods.iter().map(|m| m.name.as_str()).collect();
+            assert_eq!(
+                names,
+                vec!["first", "is_ascii"],
+                "Wrong deref methods for rustdoc {}",
+                version
+            );
+            assert_eq!(
+                deref[0].methods[0].description,
+                "Returns the first element of the slice, or None if it is empty."
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_method_usage_examples() -> Result<()> {
+        let html = r##"<details class="toggle method-toggle" open>
+            <summary><section id="method.run" class="method">
+                <h4 class="code-header">pub fn <a class="fn" href="#method.run">run</a>(self)</h4>
+            </section></summary>
+            <div class="docblock scraped-example-list"><span></span>
+                <h5 id="scraped-examples">Examples found in repository</h5>
+                <div class="scraped-example">
+                    <div class="scraped-example-title">examples/basic.rs (<a href="../src/basic/basic.rs.html#9">line 9</a>)</div>
+                    <div class="example-wrap"><pre class="rust"><code>app.<span class="highlight focus">run</span>();</code></pre></div>
+                </div>
+            </div>
+        </details>"##;
+        let document = Html::parse_fragment(html);
+        let parser =
+            MethodParser::new(&document, "https://docs.rs/demo/1.0.0/demo/struct.App.html")?;
+        let method = parser.parse(document.root_element());
 
-        // Test with module path
-        let docs = tool.fetch_docs(
-            "opentelemetry_sdk",
-            "trace::TracerProviderBuilder",
-            Some("0.28.0"),
-        )?;
         assert_eq!(
-            docs.name, "trace::TracerProviderBuilder",
-            "Wrong struct name"
+            method.description, "",
+            "Scraped examples aren't the method's docs"
         );
-        assert_eq!(docs.crate_name, "opentelemetry_sdk", "Wrong crate name");
-        assert!(!docs.description.is_empty(), "Should have a description");
-        assert!(!docs.traits.is_empty(), "Should have traits");
+        assert_eq!(method.usage_examples.len(), 1);
+        assert_eq!(method.usage_examples[0].file(), "examples/basic.rs");
+        assert_eq!(method.usage_examples[0].code(), Some("app.run();"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_deref_target() {
+        assert_eq!(
+            deref_target("Methods from Deref<Target = [u8]>").as_deref(),
+            Some("[u8]")
+        );
+        assert_eq!(
+            deref_target("Methods from Deref<Target = Vec<T, A>>").as_deref(),
+            Some("Vec<T, A>")
+        );
+        assert_eq!(deref_target("Implementations"), None);
+    }
+
+    #[test]
+    fn test_parse_trait_impls_surreal() -> Result<()> {
+        let html = fs::read_to_string("test-data/surreal-struct-surrealdb.html")?;
+        let document = Html::parse_document(&html);
+
+        let trait_impls = parse_trait_impls(&document, "trait-implementations-list")?;
+        let names: Vec<&str> = trait_impls.iter().map(|i| i.trait_name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec!["Clone", "Debug"],
+            "Wrong trait impls for Surreal"
+        );
+
+        let blanket_impls = parse_trait_impls(&document, "blanket-implementations-list")?;
+        let pointable = blanket_impls
+            .iter()
+            .find(|i| i.trait_name == "Pointable")
+            .ok_or_else(|| anyhow!("Pointable blanket impl should be parsed"))?;
+        assert!(
+            pointable.associated_type("Init").is_some(),
+            "Associated type Init should be parsed from the Pointable impl"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_capabilities() -> Result<()> {
+        let html = r#"<div id="trait-implementations-list">
+            <details class="toggle implementors-toggle"><summary><section class="impl">
+                <h3 class="code-header">impl&lt;T&gt; Future for JoinHandle&lt;T&gt;</h3>
+            </section></summary><div class="impl-items">
+                <details class="toggle"><summary><section class="associatedtype trait-impl">
+                    <h4 class="code-header">type Output = Result&lt;T, JoinError&gt;</h4>
+                </section></summary></details>
+            </div></details>
+        </div>
+        <div id="synthetic-implementations-list">
+            <section class="impl"><h3 class="code-header">impl&lt;T&gt; Unpin for JoinHandle&lt;T&gt;</h3></section>
+        </div>"#;
+        let document = Html::parse_document(html);
+
+        let mut trait_impls = parse_trait_impls(&document, "trait-implementations-list")?;
+        trait_impls.extend(parse_trait_impls(
+            &document,
+            "synthetic-implementations-list",
+        )?);
+        let capabilities = Capabilities::new(&trait_impls);
+
+        assert!(capabilities.is_future, "JoinHandle should be a Future");
+        assert_eq!(
+            capabilities.future_output.as_deref(),
+            Some("Result<T, JoinError>"),
+            "Wrong Future::Output"
+        );
+        assert!(capabilities.is_unpin, "JoinHandle should be Unpin");
+        assert!(!capabilities.is_iterator, "JoinHandle is not an Iterator");
+        assert!(!capabilities.is_stream, "JoinHandle is not a Stream");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_serde_support() -> Result<()> {
+        let html = r#"<div id="trait-implementations-list">
+            <section class="impl"><h3 class="code-header">impl Serialize for Value</h3></section>
+            <section class="impl"><h3 class="code-header">impl&lt;'de&gt; Deserialize&lt;'de&gt; for Value</h3></section>
+        </div>"#;
+        let document = Html::parse_document(html);
+        let trait_impls = parse_trait_impls(&document, "trait-implementations-list")?;
+        let description = "Represents any valid JSON value.\n\
+            Values are serialized untagged. See the docs for details.\n\
+            #[serde(untagged)]";
+
+        let serde = SerdeSupport::new(&trait_impls, description);
+
+        assert!(
+            serde.implements_serialize,
+            "Serialize impl should be detected"
+        );
+        assert!(
+            serde.implements_deserialize,
+            "Deserialize<'de> impl should be detected"
+        );
+        assert_eq!(
+            serde.notes,
+            vec![
+                "Values are serialized untagged.".to_string(),
+                "#[serde(untagged)]".to_string()
+            ],
+            "Only serde-related sentences and attributes should be kept"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_unsafe_impls() -> Result<()> {
+        let html = fs::read_to_string("test-data/trait_impls/unsafe-impls.html")?;
+        let document = Html::parse_document(&html);
+        let trait_impls = parse_all_trait_impls(&document)?;
+
+        let unsafe_impls = UnsafeImpl::from_trait_impls(&trait_impls);
+        let safety: Vec<(&str, Option<&str>)> = unsafe_impls
+            .iter()
+            .map(|i| (i.trait_name.as_str(), i.safety.as_deref()))
+            .collect();
+        assert_eq!(
+            safety,
+            vec![
+                (
+                    "Send",
+                    Some(
+                        "The value is only ever moved in and out as a whole, so the cell can be sent to another\nthread whenever T can."
+                    )
+                ),
+                (
+                    "Sync",
+                    Some("All access goes through a lock, so shared references never observe a partial write.")
+                ),
+                ("GlobalAlloc", None),
+            ]
+        );
+        assert_eq!(
+            unsafe_impls[0].impl_header,
+            "unsafe impl<T: Send> Send for AtomicCell<T>"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_trait_impls_operators() -> Result<()> {
+        let html = fs::read_to_string("test-data/trait_impls/std-ops-impls.html")?;
+        let trait_impls =
+            parse_trait_impls(&Html::parse_document(&html), "trait-implementations-list")?;
+
+        let impls: Vec<(&str, &str)> = trait_impls
+            .iter()
+            .map(|i| (i.trait_name.as_str(), i.header.as_str()))
+            .collect();
+        assert_eq!(
+            impls,
+            vec![
+                ("Add", "impl Add for Point"),
+                ("Add", "impl Add<&Point> for Point"),
+                ("Mul", "impl Mul<fn(f64) -> f64> for Point"),
+            ],
+            "Impls relying on the default `Rhs = Self` and those naming it are told apart by their header"
+        );
+        for trait_impl in &trait_impls {
+            assert_eq!(
+                trait_impl.associated_types(),
+                [("Output".to_string(), "Point".to_string())]
+            );
+        }
+        assert_eq!(
+            impl_self_type(&trait_impls[2].header).as_deref(),
+            Some("Point")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_trait_impls_generic_associated_types() -> Result<()> {
+        let html = fs::read_to_string("test-data/trait_impls/lending-iterator-impls.html")?;
+        let trait_impls =
+            parse_trait_impls(&Html::parse_document(&html), "trait-implementations-list")?;
+
+        assert_eq!(trait_impls.len(), 2);
+        assert_eq!(trait_impls[0].trait_name, "LendingIterator");
+        assert_eq!(
+            trait_impls[0].associated_types(),
+            [(
+                "Item<'a>".to_string(),
+                "&'a mut [T] where Self: 'a".to_string()
+            )],
+            "The where clause is kept apart from the type"
+        );
+        assert_eq!(
+            trait_impls[0].methods,
+            ["fn next<'a>(&'a mut self) -> Option<Self::Item<'a>>"]
+        );
+        assert_eq!(
+            trait_impls[1].associated_types(),
+            [(
+                "Window<'a, const N: usize>".to_string(),
+                "Window<'a, T, N> where Self: 'a, T: 'a".to_string()
+            )]
+        );
+        assert_eq!(
+            impl_self_type(&trait_impls[1].header).as_deref(),
+            Some("WindowsMut<'w, T>")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_serde_support_absent() -> Result<()> {
+        let html = fs::read_to_string("test-data/surreal-struct-surrealdb.html")?;
+        let document = Html::parse_document(&html);
+        let trait_impls = parse_trait_impls(&document, "trait-implementations-list")?;
+
+        let serde = SerdeSupport::new(&trait_impls, "");
+
+        assert!(!serde.implements_serialize, "Surreal is not Serialize");
+        assert!(!serde.implements_deserialize, "Surreal is not Deserialize");
+        assert!(serde.notes.is_empty(), "No notes expected for empty docs");
 
         Ok(())
     }
@@ -0,0 +1,436 @@
+//! The HTTP client shared by every tool, so connections to docs.rs and crates.io are pooled
+//! across tool calls instead of being opened by a fresh client each time, and requests are
+//! held to the configured rate (see [`rate_limit`](super::rate_limit)). The client is only
+//! rebuilt when the `[http]` settings or the registries change, e.g. after a config reload.
+//!
+//! Base URLs stay out of the client: they are read from `config::current().sources()` per
+//! request, because registry lookups point the sources at another registry for a single call.
+//!
+//! A URL that answered Not Found is answered so again for a while without sending the request,
+//! see [`negative_cache`](super::negative_cache).
+//!
+//! Offline, requests fail here without being sent, so only what is cached can be looked up.
+//!
+//! Requests and their responses are recorded here when the session is, and answered from the
+//! recording when one is replayed, see [`session`](crate::session).
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Instant;
+
+use anyhow::{bail, Result};
+use reqwest::header::{HeaderMap, CONTENT_TYPE};
+use reqwest::{RequestBuilder, StatusCode};
+use serde::Serialize;
+use tracing::{debug, Instrument};
+use url::Url;
+
+use super::negative_cache::NegativeCache;
+use super::rate_limit::RateLimiter;
+use crate::config::{self, HttpConfig, RegistryConfig};
+#[cfg(feature = "native")]
+use crate::session::{self, Fetch};
+use crate::telemetry;
+
+/// The client built from the `[http]` settings, cheap to clone
+#[derive(Debug, Clone)]
+pub struct HttpClient {
+    inner: Arc<Inner>,
+}
+
+#[derive(Debug)]
+struct Inner {
+    settings: HttpConfig,
+    registries: Vec<RegistryConfig>,
+    client: reqwest::Client,
+    limiter: RateLimiter,
+    headers: HeaderMap,
+    host_headers: HashMap<String, HeaderMap>,
+    not_found: NegativeCache,
+}
+
+impl Inner {
+    /// Headers to send with a request for `url`: those configured for every host, then those of
+    /// its host. A documentation host named by a tool call's `docs_base_url` gets none, as the
+    /// configuration never vouched for it.
+    fn headers(&self, url: &str) -> HeaderMap {
+        let Ok(parsed) = Url::parse(url) else {
+            return HeaderMap::new();
+        };
+        let overridden = config::current()
+            .docs_url_override()
+            .and_then(|base| Url::parse(base).ok())
+            .is_some_and(|base| base.origin() == parsed.origin());
+        if overridden {
+            return HeaderMap::new();
+        }
+        let mut headers = self.headers.clone();
+        if let Some(host_headers) = parsed
+            .host_str()
+            .and_then(|host| self.host_headers.get(host))
+        {
+            headers.extend(host_headers.clone());
+        }
+        headers
+    }
+
+    /// A stand-in for the response of `url` when it answered Not Found recently
+    fn known_missing(&self, method: &str, url: &str) -> Option<http::Response<String>> {
+        let ttl = config::current().cache().not_found_ttl()?;
+        let missing = self.not_found.is_missing(url, ttl, Instant::now());
+        telemetry::record_cache_lookup("not_found", missing);
+        if !missing {
+            return None;
+        }
+        debug!("{} answered Not Found recently, not asking again", url);
+        #[cfg(feature = "native")]
+        session::record_fetch(Fetch::known_missing(method, url));
+        #[cfg(not(feature = "native"))]
+        let _ = method;
+        http::Response::builder()
+            .status(StatusCode::NOT_FOUND)
+            .body(String::new())
+            .ok()
+    }
+
+    fn remember(&self, url: &str, status: Option<u16>) {
+        if let (Some(ttl), Some(status)) = (config::current().cache().not_found_ttl(), status) {
+            self.not_found.record(url, status, ttl, Instant::now());
+        }
+    }
+}
+
+impl HttpClient {
+    /// Builds the client for `settings`, with the timeout, proxy, user agent and headers
+    /// applied to every request, and the token of each of `registries` sent to its hosts.
+    pub fn new(settings: &HttpConfig, registries: &[RegistryConfig]) -> Result<Self> {
+        let mut host_headers: HashMap<String, HeaderMap> = HashMap::new();
+        // Configured host headers come last, so they win over registry tokens
+        for headers in registries
+            .iter()
+            .map(RegistryConfig::auth_headers)
+            .chain(std::iter::once(settings.host_headers()))
+        {
+            for (host, headers) in headers? {
+                host_headers.entry(host).or_default().extend(headers);
+            }
+        }
+        Ok(Self {
+            inner: Arc::new(Inner {
+                settings: settings.clone(),
+                registries: registries.to_vec(),
+                client: settings.async_client()?,
+                limiter: RateLimiter::new(settings),
+                headers: settings.headers()?,
+                host_headers,
+                not_found: NegativeCache::new(),
+            }),
+        })
+    }
+
+    /// Forgets that the URLs that `matches` answered Not Found, returning how many were
+    /// remembered.
+    #[cfg(feature = "native")]
+    pub(crate) fn forget_not_found(&self, matches: impl Fn(&str) -> bool) -> usize {
+        self.inner.not_found.forget(matches)
+    }
+
+    /// Sends a GET request, once the rate limit allows it.
+    pub async fn get(&self, url: &str) -> Result<reqwest::Response> {
+        let span = tracing::info_span!("http_get", url, status = tracing::field::Empty);
+        self.send("GET", url, self.inner.client.get(url))
+            .instrument(span)
+            .await
+    }
+
+    /// Sends `body` as JSON in a POST request, once the rate limit allows it.
+    pub async fn post_json(&self, url: &str, body: &impl Serialize) -> Result<reqwest::Response> {
+        let span = tracing::info_span!("http_post", url, status = tracing::field::Empty);
+        let request = self
+            .inner
+            .client
+            .post(url)
+            .header(CONTENT_TYPE, "application/json")
+            .body(serde_json::to_vec(body)?);
+        self.send("POST", url, request).instrument(span).await
+    }
+
+    async fn send(
+        &self,
+        method: &str,
+        url: &str,
+        request: RequestBuilder,
+    ) -> Result<reqwest::Response> {
+        #[cfg(feature = "native")]
+        if let Some(replayed) = session::replayed(method, url) {
+            return Ok(replayed?.into());
+        }
+        if let Some(response) = self.inner.known_missing(method, url) {
+            return Ok(response.into());
+        }
+        refuse_offline(url)?;
+        self.inner.limiter.acquire(url).await;
+        let start = Instant::now();
+        let response = request.headers(self.inner.headers(url)).send().await;
+        let status = response.as_ref().ok().map(|r| r.status().as_u16());
+        record(url, status, start);
+        self.inner.remember(url, status);
+        #[cfg(feature = "native")]
+        if session::is_recording() {
+            let response =
+                response.inspect_err(|e| session::record_fetch(Fetch::failed(method, url, e)))?;
+            let (status, final_url) = (response.status().as_u16(), response.url().clone());
+            let body = response
+                .bytes()
+                .await
+                .inspect_err(|e| session::record_fetch(Fetch::failed(method, url, e)))?;
+            return Ok(recorded(Fetch::received(method, url, &final_url, status, &body))?.into());
+        }
+        Ok(response?)
+    }
+}
+
+/// Adds `fetch` to the recorded session, handing back the response it recorded: the body has
+/// been read to record it, and the caller then gets what a replay will give.
+#[cfg(feature = "native")]
+fn recorded(fetch: Fetch) -> Result<http::Response<Vec<u8>>> {
+    let response = fetch.to_response();
+    session::record_fetch(fetch);
+    response
+}
+
+/// Fails when the server is offline, before `url` is requested.
+fn refuse_offline(url: &str) -> Result<()> {
+    if config::current().offline() {
+        bail!("{} isn't cached, and the server is offline", url);
+    }
+    Ok(())
+}
+
+/// Records the outcome of a request on the current span and in the metrics.
+fn record(url: &str, status: Option<u16>, start: Instant) {
+    if let Some(status) = status {
+        tracing::Span::current().record("status", status);
+    }
+    telemetry::record_fetch(url, status, start.elapsed());
+}
+
+/// The client for the `[http]` settings and registries currently in effect, shared by every
+/// tool.
+pub fn shared() -> Result<HttpClient> {
+    static SHARED: OnceLock<Mutex<Option<HttpClient>>> = OnceLock::new();
+    let mut shared = match SHARED.get_or_init(|| Mutex::new(None)).lock() {
+        Ok(shared) => shared,
+        Err(poisoned) => poisoned.into_inner(),
+    };
+    let config = config::current();
+    reuse_or_build(&mut shared, config.http(), config.registries())
+}
+
+/// The client in `slot` if it was built for `settings` and `registries`, otherwise a new one
+/// stored there.
+fn reuse_or_build(
+    slot: &mut Option<HttpClient>,
+    settings: &HttpConfig,
+    registries: &[RegistryConfig],
+) -> Result<HttpClient> {
+    if let Some(client) = slot.as_ref().filter(|client| {
+        client.inner.settings == *settings && client.inner.registries == registries
+    }) {
+        return Ok(client.clone());
+    }
+    let client = HttpClient::new(settings, registries)?;
+    *slot = Some(client.clone());
+    Ok(client)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_reused_until_settings_change() -> Result<()> {
+        let defaults = Config::default().http().clone();
+        let slower = Config::from_toml("[http]\ntimeout_secs = 90\n")?
+            .http()
+            .clone();
+        let mut slot = None;
+
+        let first = reuse_or_build(&mut slot, &defaults, &[])?;
+        let second = reuse_or_build(&mut slot, &defaults, &[])?;
+        assert!(Arc::ptr_eq(&first.inner, &second.inner), "Same settings");
+
+        let rebuilt = reuse_or_build(&mut slot, &slower, &[])?;
+        assert!(
+            !Arc::ptr_eq(&first.inner, &rebuilt.inner),
+            "Changed settings"
+        );
+        assert_eq!(rebuilt.inner.settings, slower);
+        Ok(())
+    }
+
+    #[test]
+    fn test_host_headers() -> Result<()> {
+        let settings = Config::from_toml(
+            "[http.host_headers.\"docs.acme.internal\"]\nAuthorization = \"Bearer secret\"\n",
+        )?
+        .http()
+        .clone();
+        let client = HttpClient::new(&settings, &[])?;
+        assert_eq!(
+            client
+                .inner
+                .headers("https://docs.acme.internal/acme-utils")["authorization"],
+            "Bearer secret"
+        );
+        assert!(
+            client.inner.headers("https://docs.rs/tokio").is_empty(),
+            "Credentials shouldn't be sent to other hosts"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_no_headers_for_docs_base_url() -> Result<()> {
+        let config = Config::from_toml(
+            r#"
+            [sources]
+            allow_docs_base_url = true
+
+            [http.headers]
+            X-Api-Key = "secret"
+
+            [http.host_headers."mirror.example"]
+            Authorization = "Bearer secret"
+            "#,
+        )?;
+        let client = HttpClient::new(config.http(), config.registries())?;
+        assert_eq!(
+            client.inner.headers("https://docs.rs/tokio")["x-api-key"],
+            "secret"
+        );
+        assert_eq!(
+            client.inner.headers("https://mirror.example/tokio").len(),
+            2
+        );
+
+        let mirrored = config.with_docs_url("https://mirror.example")?;
+        config::scoped(mirrored, || {
+            assert!(
+                client
+                    .inner
+                    .headers("https://mirror.example/tokio")
+                    .is_empty(),
+                "A host the client named gets no configured headers"
+            );
+            assert_eq!(
+                client
+                    .inner
+                    .headers("https://crates.io/api/v1/crates/tokio")["x-api-key"],
+                "secret"
+            );
+        });
+        Ok(())
+    }
+
+    #[test]
+    fn test_registry_tokens() -> Result<()> {
+        let config = Config::from_toml(
+            r#"
+            [[registries]]
+            name = "acme"
+            api_url = "https://registry.acme.internal"
+            docs_url = "https://docs.acme.internal"
+            token = "acme-token"
+
+            [http.host_headers."docs.acme.internal"]
+            Authorization = "Bearer docs-token"
+            "#,
+        )?;
+        let client = HttpClient::new(config.http(), config.registries())?;
+        assert_eq!(
+            client
+                .inner
+                .headers("https://registry.acme.internal/api/v1/crates/acme-utils")
+                ["authorization"],
+            "acme-token"
+        );
+        assert_eq!(
+            client
+                .inner
+                .headers("https://docs.acme.internal/acme-utils")["authorization"],
+            "Bearer docs-token",
+            "Configured host headers win over the registry token"
+        );
+        assert!(client
+            .inner
+            .headers("https://crates.io/api/v1/crates/serde")
+            .is_empty());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_not_found_is_remembered() -> Result<()> {
+        use wiremock::matchers::path;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        // Once per client: the second request of the first one is answered from memory
+        Mock::given(path("/missing"))
+            .respond_with(ResponseTemplate::new(404))
+            .expect(2)
+            .mount(&server)
+            .await;
+        Mock::given(path("/busy"))
+            .respond_with(ResponseTemplate::new(503))
+            .expect(2)
+            .mount(&server)
+            .await;
+
+        let config = Config::default();
+        let client = HttpClient::new(config.http(), config.registries())?;
+        config::scoped_task(config, async {
+            for _ in 0..2 {
+                let response = client.get(&format!("{}/missing", server.uri())).await?;
+                assert_eq!(response.status(), StatusCode::NOT_FOUND);
+                let response = client.get(&format!("{}/busy", server.uri())).await?;
+                assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+            }
+            anyhow::Ok(())
+        })
+        .await?;
+
+        let no_memory = Config::from_toml("[cache]\nnot_found_ttl_secs = 0\n")?;
+        let client = HttpClient::new(no_memory.http(), no_memory.registries())?;
+        config::scoped_task(no_memory, async {
+            client.get(&format!("{}/missing", server.uri())).await
+        })
+        .await?;
+        server.verify().await;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_offline() -> Result<()> {
+        use wiremock::matchers::path;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(path("/tokio"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(0)
+            .mount(&server)
+            .await;
+
+        let config = Config::from_toml("offline = true\n")?;
+        let client = HttpClient::new(config.http(), config.registries())?;
+        let url = format!("{}/tokio", server.uri());
+        let error = config::scoped_task(config, client.get(&url))
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("offline"), "{}", error);
+        Ok(())
+    }
+}
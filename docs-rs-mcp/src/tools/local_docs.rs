@@ -0,0 +1,265 @@
+//! Documentation built on this machine, for crates docs.rs doesn't have: crates of private
+//! registries and unpublished git dependencies. Opt-in with `local_docs.enabled`, as it
+//! downloads and compiles the crate's dependencies.
+//!
+//! The crate is added as the only dependency of a scratch package under
+//! `<cache dir>/local-docs/<registry>/<crate>-<version>/`, so cargo fetches it with the
+//! registry credentials it already has, and documented twice with `--no-deps`: once as
+//! rustdoc JSON, which the tools read, and once as HTML, which the items link to.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use anyhow::{anyhow, Context, Result};
+use tracing::debug;
+use url::Url;
+
+use super::rustdoc_json;
+use crate::config::{self, DEFAULT_REGISTRY};
+use crate::paths;
+use crate::validate;
+
+/// Documentation of a crate built locally
+pub(crate) struct LocalDocs {
+    json: rustdoc_json::Crate,
+    /// `file://` URL of the HTML documentation root, ending in a slash
+    root_url: String,
+}
+
+impl LocalDocs {
+    pub(crate) fn json(&self) -> &rustdoc_json::Crate {
+        &self.json
+    }
+
+    pub(crate) fn root_url(&self) -> &str {
+        &self.root_url
+    }
+}
+
+/// Builds the documentation of `crate_name` at `version` (`latest` for the newest release),
+/// reusing an earlier build of a concrete version.
+pub(crate) fn build(crate_name: &str, version: &str) -> Result<LocalDocs> {
+    // Both end up in a path and in the scratch package's manifest
+    validate::crate_name(crate_name)?;
+    validate::version(version)?;
+    let config = config::current();
+    let cache_dir = config
+        .cache()
+        .resolved_dir()
+        .ok_or_else(|| anyhow!("Building documentation locally needs a cache directory"))?;
+    let package_dir = cache_dir
+        .join(paths::LOCAL_DOCS_DIR)
+        .join(config.active_registry())
+        .join(format!("{}-{}", crate_name, version));
+    let doc_dir = package_dir.join("target").join("doc");
+
+    // "latest" moves, so only concrete versions are reused
+    if version != "latest" {
+        if let Ok(docs) = read(&doc_dir) {
+            debug!("Using local documentation in {}", doc_dir.display());
+            return Ok(docs);
+        }
+    }
+
+    fs::create_dir_all(&package_dir).context(format!(
+        "Failed to create directory: {}",
+        package_dir.display()
+    ))?;
+    fs::write(package_dir.join("lib.rs"), "")?;
+    fs::write(
+        package_dir.join("Cargo.toml"),
+        manifest(
+            crate_name,
+            version,
+            config.active_registry(),
+            config.local_docs().git(crate_name),
+        )?,
+    )?;
+
+    let toolchain = config.local_docs().toolchain();
+    debug!(
+        "Building documentation of {} {} with cargo +{}",
+        crate_name, version, toolchain
+    );
+    cargo_doc(&package_dir, crate_name, toolchain, true)?;
+    cargo_doc(&package_dir, crate_name, toolchain, false)?;
+    read(&doc_dir)
+}
+
+/// Manifest of the scratch package depending on the crate to document
+fn manifest(crate_name: &str, version: &str, registry: &str, git: Option<&str>) -> Result<String> {
+    let mut dependency = toml::Table::new();
+    match git {
+        Some(url) => {
+            dependency.insert("git".to_string(), url.into());
+        }
+        None => {
+            let requirement = if version == "latest" {
+                "*".to_string()
+            } else {
+                format!("={}", version)
+            };
+            dependency.insert("version".to_string(), requirement.into());
+            if registry != DEFAULT_REGISTRY {
+                dependency.insert("registry".to_string(), registry.into());
+            }
+        }
+    }
+    let mut manifest = toml::toml! {
+        [package]
+        name = "docs-rs-mcp-local-docs"
+        version = "0.0.0"
+        edition = "2021"
+        publish = false
+
+        [lib]
+        path = "lib.rs"
+
+        [workspace]
+    };
+    manifest.insert(
+        "dependencies".to_string(),
+        toml::Table::from_iter([(crate_name.to_string(), dependency.into())]).into(),
+    );
+    toml::to_string(&manifest).context("Failed to write the scratch package's manifest")
+}
+
+/// Documents the dependency `crate_name` of the scratch package, as rustdoc JSON or as HTML.
+fn cargo_doc(package_dir: &Path, crate_name: &str, toolchain: &str, json: bool) -> Result<()> {
+    let mut command = Command::new("cargo");
+    command
+        .arg(format!("+{}", toolchain))
+        .arg("doc")
+        .arg("--no-deps")
+        .arg("--package")
+        .arg(crate_name)
+        .arg("--manifest-path")
+        .arg(package_dir.join("Cargo.toml"))
+        .arg("--target-dir")
+        .arg(package_dir.join("target"))
+        .env_remove("RUSTDOCFLAGS");
+    if json {
+        command.env("RUSTDOCFLAGS", "-Z unstable-options --output-format json");
+    }
+
+    let output = command
+        .output()
+        .context("Failed to run cargo; is a Rust toolchain installed?")?;
+    if !output.status.success() {
+        return Err(anyhow!(
+            "cargo doc failed for {}: {}",
+            crate_name,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(())
+}
+
+/// Reads a build from the `target/doc` directory of the scratch package. It holds a single
+/// JSON file, named after the crate's library.
+fn read(doc_dir: &Path) -> Result<LocalDocs> {
+    let json_path = json_file(doc_dir)?;
+    let json = rustdoc_json::parse(
+        &fs::read_to_string(&json_path)
+            .context(format!("Failed to read {}", json_path.display()))?,
+    )
+    .context(format!("Failed to parse {}", json_path.display()))?
+    .with_source(&json_path.display().to_string());
+
+    let lib_name = json_path
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or_default();
+    let root_url = Url::from_directory_path(doc_dir.join(lib_name))
+        .map_err(|_| anyhow!("Not an absolute path: {}", doc_dir.display()))?;
+    Ok(LocalDocs {
+        json,
+        root_url: root_url.to_string(),
+    })
+}
+
+fn json_file(doc_dir: &Path) -> Result<PathBuf> {
+    fs::read_dir(doc_dir)
+        .context(format!("No documentation in {}", doc_dir.display()))?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .find(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .ok_or_else(|| anyhow!("No rustdoc JSON in {}", doc_dir.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DocsRsMcpError;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_manifest_dependency() -> Result<()> {
+        let dependency = |version, registry, git| -> Result<toml::Value> {
+            let manifest: toml::Table = toml::from_str(&manifest("demo", version, registry, git)?)?;
+            assert_eq!(
+                manifest["package"]["name"].as_str(),
+                Some("docs-rs-mcp-local-docs")
+            );
+            Ok(manifest["dependencies"]["demo"].clone())
+        };
+
+        assert_eq!(
+            dependency("0.4.2", DEFAULT_REGISTRY, None)?,
+            toml::toml! { version = "=0.4.2" }.into()
+        );
+        assert_eq!(
+            dependency("latest", "acme", None)?,
+            toml::toml! { version = "*" registry = "acme" }.into()
+        );
+        assert_eq!(
+            dependency(
+                "latest",
+                DEFAULT_REGISTRY,
+                Some("https://git.acme.internal/demo")
+            )?,
+            toml::toml! { git = "https://git.acme.internal/demo" }.into()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_build_refuses_unsafe_input() {
+        for (crate_name, version) in [
+            ("../../etc", "1.0.0"),
+            ("demo = { path = \"/\" }\nx", "1.0.0"),
+            ("demo", "1.0.0\", path = \"/"),
+            ("demo", "../../1.0.0"),
+        ] {
+            let error = build(crate_name, version).err().unwrap();
+            assert!(
+                matches!(
+                    DocsRsMcpError::classify(&error),
+                    DocsRsMcpError::InvalidInput(_)
+                ),
+                "{} {}",
+                crate_name,
+                version
+            );
+        }
+    }
+
+    #[test]
+    fn test_read() -> Result<()> {
+        let doc_dir = std::env::temp_dir().join(format!("local-docs-test-{}", std::process::id()));
+        fs::create_dir_all(doc_dir.join("demo"))?;
+        fs::copy(
+            "test-data/rustdoc_json/demo.json",
+            doc_dir.join("demo.json"),
+        )?;
+
+        let docs = read(&doc_dir)?;
+        fs::remove_dir_all(&doc_dir)?;
+        assert_eq!(docs.json().format_version(), 39);
+        assert_eq!(
+            docs.root_url(),
+            format!("file://{}/demo/", doc_dir.display())
+        );
+        Ok(())
+    }
+}
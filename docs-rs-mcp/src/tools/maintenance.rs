@@ -0,0 +1,320 @@
+//! Signs that a crate shouldn't be picked for new code: a RustSec advisory declaring it
+//! unmaintained, an archived repository, or a description announcing its deprecation. Without
+//! them assistants keep recommending crates such as `failure` or `rustc-serialize`.
+//!
+//! Each source is asked on its own and one that can't be reached is skipped, as the warnings
+//! only add to what `crate_info` reports.
+
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tracing::debug;
+use url::Url;
+
+use super::{http, runtime};
+use crate::config::{self, DEFAULT_REGISTRY};
+
+/// Phrases of descriptions announcing the crate is deprecated or abandoned
+const DEPRECATION_PHRASES: [&str; 5] = [
+    "deprecated",
+    "unmaintained",
+    "no longer maintained",
+    "not maintained",
+    "abandoned",
+];
+
+/// A reason not to use a crate in new code
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MaintenanceWarning {
+    source: WarningSource,
+    message: String,
+    /// Where to read more, e.g. the RustSec advisory
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    url: Option<String>,
+}
+
+impl MaintenanceWarning {
+    pub fn source(&self) -> WarningSource {
+        self.source
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+/// What flagged the crate
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WarningSource {
+    /// An informational RustSec advisory declaring the crate unmaintained
+    Rustsec,
+    /// The crate's repository is archived
+    Repository,
+    /// The crate's description says it is deprecated or unmaintained
+    Description,
+}
+
+/// The warnings for `version` of the crate, from its `description` and `repository` and the
+/// RustSec advisories, which only cover crates.io.
+pub(crate) fn check(
+    crate_name: &str,
+    version: &str,
+    description: &str,
+    repository: Option<&str>,
+) -> Vec<MaintenanceWarning> {
+    let config = config::current();
+    let mut warnings = Vec::new();
+    if let Some(osv_url) = config.sources().osv_url() {
+        if config.active_registry() == DEFAULT_REGISTRY {
+            match runtime::block_on(unmaintained_advisories(osv_url, crate_name, version)) {
+                Ok(advisories) => warnings.extend(advisories),
+                Err(e) => debug!("No RustSec advisories for {}: {:#}", crate_name, e),
+            }
+        }
+    }
+    if let (Some(api_url), Some(repository)) = (config.sources().github_api_url(), repository) {
+        match runtime::block_on(archived_repository(api_url, repository)) {
+            Ok(warning) => warnings.extend(warning),
+            Err(e) => debug!("Couldn't tell whether {} is archived: {:#}", repository, e),
+        }
+    }
+    warnings.extend(description_warning(description));
+    warnings
+}
+
+fn description_warning(description: &str) -> Option<MaintenanceWarning> {
+    let lowercase = description.to_lowercase();
+    DEPRECATION_PHRASES
+        .iter()
+        .any(|phrase| lowercase.contains(phrase))
+        .then(|| MaintenanceWarning {
+            source: WarningSource::Description,
+            message: format!("The description says: {}", description.trim()),
+            url: None,
+        })
+}
+
+/// Asks OSV for the advisories concerning `version` of the crate, keeping the RustSec ones
+/// declaring it unmaintained.
+async fn unmaintained_advisories(
+    osv_url: &str,
+    crate_name: &str,
+    version: &str,
+) -> Result<Vec<MaintenanceWarning>> {
+    let mut query = json!({ "package": { "name": crate_name, "ecosystem": "crates.io" } });
+    if !version.is_empty() {
+        query["version"] = json!(version);
+    }
+    let url = format!("{}/v1/query", osv_url);
+    let response = http::shared()?.post_json(&url, &query).await?;
+    if !response.status().is_success() {
+        return Err(anyhow!(
+            "Failed to query advisories: {} - {}",
+            response.status(),
+            url
+        ));
+    }
+    parse_advisories(&response.text().await?)
+}
+
+/// Body of the OSV `/v1/query` endpoint, reduced to what we use
+#[derive(Debug, Deserialize)]
+struct OsvResponse {
+    #[serde(default)]
+    vulns: Vec<Advisory>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Advisory {
+    id: String,
+    #[serde(default)]
+    summary: Option<String>,
+    #[serde(default)]
+    withdrawn: Option<String>,
+    #[serde(default)]
+    database_specific: Option<RustsecFields>,
+    #[serde(default)]
+    affected: Vec<Affected>,
+    #[serde(default)]
+    references: Vec<Reference>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Affected {
+    #[serde(default)]
+    database_specific: Option<RustsecFields>,
+}
+
+/// The RustSec fields of an advisory: `informational` is `unmaintained`, `unsound` or
+/// `notice` for advisories that aren't vulnerabilities
+#[derive(Debug, Deserialize)]
+struct RustsecFields {
+    #[serde(default)]
+    informational: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Reference {
+    #[serde(rename = "type")]
+    kind: String,
+    url: String,
+}
+
+impl Advisory {
+    fn is_unmaintained(&self) -> bool {
+        self.database_specific
+            .iter()
+            .chain(
+                self.affected
+                    .iter()
+                    .filter_map(|a| a.database_specific.as_ref()),
+            )
+            .any(|fields| fields.informational.as_deref() == Some("unmaintained"))
+    }
+}
+
+fn parse_advisories(body: &str) -> Result<Vec<MaintenanceWarning>> {
+    let response: OsvResponse =
+        serde_json::from_str(body).context("Failed to parse OSV response")?;
+    Ok(response
+        .vulns
+        .into_iter()
+        .filter(|advisory| {
+            advisory.id.starts_with("RUSTSEC-")
+                && advisory.withdrawn.is_none()
+                && advisory.is_unmaintained()
+        })
+        .map(|advisory| {
+            let url = advisory
+                .references
+                .iter()
+                .find(|reference| reference.kind == "ADVISORY")
+                .map(|reference| reference.url.clone())
+                .unwrap_or_else(|| format!("https://rustsec.org/advisories/{}.html", advisory.id));
+            MaintenanceWarning {
+                source: WarningSource::Rustsec,
+                message: match &advisory.summary {
+                    Some(summary) => format!("{}: {}", advisory.id, summary),
+                    None => format!("{}: the crate is unmaintained", advisory.id),
+                },
+                url: Some(url),
+            }
+        })
+        .collect())
+}
+
+/// Asks the GitHub API whether `repository` is archived. Repositories hosted elsewhere aren't
+/// checked.
+async fn archived_repository(
+    api_url: &str,
+    repository: &str,
+) -> Result<Option<MaintenanceWarning>> {
+    let Some((owner, repo)) = github_repository(repository) else {
+        return Ok(None);
+    };
+    let url = format!("{}/repos/{}/{}", api_url, owner, repo);
+    let response = http::shared()?.get(&url).await?;
+    if !response.status().is_success() {
+        return Err(anyhow!(
+            "Failed to fetch the repository: {} - {}",
+            response.status(),
+            url
+        ));
+    }
+    let body: serde_json::Value =
+        serde_json::from_str(&response.text().await?).context("Failed to parse GitHub response")?;
+    Ok((body["archived"] == true).then(|| MaintenanceWarning {
+        source: WarningSource::Repository,
+        message: format!("The repository github.com/{}/{} is archived", owner, repo),
+        url: Some(repository.to_string()),
+    }))
+}
+
+/// Owner and name of a GitHub repository URL, e.g. `https://github.com/owner/repo.git`
+fn github_repository(repository: &str) -> Option<(String, String)> {
+    let url = Url::parse(repository.trim_end_matches('/')).ok()?;
+    if url.host_str() != Some("github.com") {
+        return None;
+    }
+    let mut segments = url.path_segments()?.filter(|s| !s.is_empty());
+    let owner = segments.next()?;
+    let repo = segments.next()?.trim_end_matches(".git");
+    Some((owner.to_string(), repo.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_parse_advisories() -> Result<()> {
+        let body = r#"{"vulns": [
+            {
+                "id": "RUSTSEC-2020-0036",
+                "summary": "failure is officially deprecated/unmaintained",
+                "affected": [{
+                    "package": {"name": "failure", "ecosystem": "crates.io"},
+                    "database_specific": {"categories": [], "informational": "unmaintained"}
+                }],
+                "references": [
+                    {"type": "PACKAGE", "url": "https://crates.io/crates/failure"},
+                    {"type": "ADVISORY", "url": "https://rustsec.org/advisories/RUSTSEC-2020-0036.html"}
+                ]
+            },
+            {
+                "id": "RUSTSEC-2019-0036",
+                "summary": "Type confusion if __private_get_type_id__ is overridden",
+                "affected": [{"database_specific": {"categories": ["memory-corruption"]}}]
+            },
+            {
+                "id": "RUSTSEC-2021-0001",
+                "summary": "Withdrawn",
+                "withdrawn": "2021-02-01T00:00:00Z",
+                "database_specific": {"informational": "unmaintained"}
+            }
+        ]}"#;
+        let warnings = parse_advisories(body)?;
+        assert_eq!(
+            warnings,
+            vec![MaintenanceWarning {
+                source: WarningSource::Rustsec,
+                message: "RUSTSEC-2020-0036: failure is officially deprecated/unmaintained"
+                    .to_string(),
+                url: Some("https://rustsec.org/advisories/RUSTSEC-2020-0036.html".to_string()),
+            }],
+            "Only unmaintained advisories still in effect are kept"
+        );
+
+        assert!(parse_advisories("{}")?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_description_warning() {
+        let warning =
+            description_warning("Deprecated in favor of serde: generic serialization for Rust")
+                .expect("flagged");
+        assert_eq!(warning.source(), WarningSource::Description);
+        assert!(warning.message().contains("in favor of serde"));
+
+        assert_eq!(
+            description_warning("An event-driven, non-blocking I/O platform"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_github_repository() {
+        assert_eq!(
+            github_repository("https://github.com/rust-lang-deprecated/failure.git/"),
+            Some(("rust-lang-deprecated".to_string(), "failure".to_string()))
+        );
+        assert_eq!(
+            github_repository("https://github.com/tokio-rs/tokio/tree/master/tokio"),
+            Some(("tokio-rs".to_string(), "tokio".to_string()))
+        );
+        assert_eq!(github_repository("https://gitlab.com/demo/demo"), None);
+    }
+}
@@ -0,0 +1,343 @@
+use scraper::{node::Node, ElementRef, Html};
+
+/// Converts a rendered HTML document or fragment (README, rustdoc docblock) into Markdown.
+///
+/// Only the subset of HTML that rustdoc and crates.io produce is handled: headings, paragraphs,
+/// lists, links, emphasis, inline code, code blocks, block quotes, images and simple tables.
+pub(crate) fn html_to_markdown(html: &str) -> String {
+    let fragment = Html::parse_fragment(html);
+    let mut renderer = Renderer::default();
+    renderer.render_children(fragment.root_element());
+    renderer.finish()
+}
+
+/// Converts an already parsed element (e.g. a `.docblock`) into Markdown.
+pub(crate) fn element_to_markdown(element: ElementRef) -> String {
+    let mut renderer = Renderer::default();
+    renderer.render_children(element);
+    renderer.finish()
+}
+
+#[derive(Default)]
+struct Renderer {
+    out: String,
+    /// One entry per open list: `None` for unordered lists, the next number for ordered ones
+    lists: Vec<Option<usize>>,
+    quote_depth: usize,
+}
+
+impl Renderer {
+    fn render_children(&mut self, element: ElementRef) {
+        for child in element.children() {
+            match child.value() {
+                Node::Text(text) => self.push_text(text),
+                Node::Element(_) => {
+                    if let Some(child) = ElementRef::wrap(child) {
+                        self.render_element(child);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn render_element(&mut self, element: ElementRef) {
+        let name = element.value().name();
+        match name {
+            "script" | "style" | "button" => {}
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                let level = name[1..].parse::<usize>().unwrap_or(1);
+                self.block_break();
+                self.out.push_str(&"#".repeat(level));
+                self.out.push(' ');
+                self.render_children(element);
+                self.block_break();
+            }
+            "p" | "div" | "section" | "article" | "details" | "summary" => {
+                self.block_break();
+                self.render_children(element);
+                self.block_break();
+            }
+            "br" => self.line_break(),
+            "hr" => {
+                self.block_break();
+                self.out.push_str("---");
+                self.block_break();
+            }
+            "pre" => self.render_code_block(element),
+            "code" => {
+                let code = element.text().collect::<String>();
+                self.out.push('`');
+                self.out.push_str(&code);
+                self.out.push('`');
+            }
+            "strong" | "b" => self.wrap_inline(element, "**"),
+            "em" | "i" => self.wrap_inline(element, "*"),
+            "del" | "s" => self.wrap_inline(element, "~~"),
+            "a" => self.render_link(element),
+            "img" => {
+                let alt = element.value().attr("alt").unwrap_or_default();
+                if let Some(src) = element.value().attr("src") {
+                    self.out.push_str(&format!("![{}]({})", alt, src));
+                }
+            }
+            "ul" | "ol" => {
+                self.block_break();
+                self.lists.push((name == "ol").then_some(1));
+                self.render_children(element);
+                self.lists.pop();
+                self.block_break();
+            }
+            "li" => self.render_list_item(element),
+            "blockquote" => {
+                self.block_break();
+                self.quote_depth += 1;
+                self.out.push_str(&self.quote_prefix());
+                self.render_children(element);
+                self.quote_depth -= 1;
+                self.block_break();
+            }
+            "table" => self.render_table(element),
+            _ => self.render_children(element),
+        }
+    }
+
+    fn render_code_block(&mut self, element: ElementRef) {
+        let language = std::iter::once(element)
+            .chain(element.children().filter_map(ElementRef::wrap))
+            .filter_map(|el| el.value().attr("class"))
+            .flat_map(str::split_whitespace)
+            .find_map(|class| {
+                class
+                    .strip_prefix("language-")
+                    .or((class == "rust").then_some("rust"))
+            })
+            .unwrap_or_default()
+            .to_string();
+        let code = element.text().collect::<String>();
+
+        self.block_break();
+        self.out.push_str("```");
+        self.out.push_str(&language);
+        self.out.push('\n');
+        self.out.push_str(code.trim_end_matches('\n'));
+        self.out.push_str("\n```");
+        self.block_break();
+    }
+
+    fn render_link(&mut self, element: ElementRef) {
+        match element.value().attr("href") {
+            // Anchor-only links (heading permalinks, `§` markers) carry no information
+            Some(href) if !href.starts_with('#') => {
+                self.out.push('[');
+                self.render_children(element);
+                self.out.push_str(&format!("]({})", href));
+            }
+            _ => {
+                let text = element.text().collect::<String>();
+                if text.trim() != "§" {
+                    self.render_children(element);
+                }
+            }
+        }
+    }
+
+    fn render_list_item(&mut self, element: ElementRef) {
+        self.line_break();
+        let depth = self.lists.len().saturating_sub(1);
+        self.out.push_str(&"  ".repeat(depth));
+        match self.lists.last_mut() {
+            Some(Some(number)) => {
+                self.out.push_str(&format!("{}. ", number));
+                *number += 1;
+            }
+            _ => self.out.push_str("- "),
+        }
+        self.render_children(element);
+    }
+
+    fn render_table(&mut self, table: ElementRef) {
+        self.block_break();
+        let rows = table
+            .descendants()
+            .filter_map(ElementRef::wrap)
+            .filter(|el| el.value().name() == "tr");
+        for (index, row) in rows.enumerate() {
+            let cells: Vec<String> = row
+                .children()
+                .filter_map(ElementRef::wrap)
+                .filter(|el| matches!(el.value().name(), "td" | "th"))
+                .map(|cell| {
+                    let mut renderer = Renderer::default();
+                    renderer.render_children(cell);
+                    renderer.finish().replace('\n', " ")
+                })
+                .collect();
+            self.out.push_str(&format!("| {} |\n", cells.join(" | ")));
+            if index == 0 {
+                self.out
+                    .push_str(&format!("|{}\n", " --- |".repeat(cells.len())));
+            }
+        }
+        self.block_break();
+    }
+
+    fn wrap_inline(&mut self, element: ElementRef, marker: &str) {
+        self.out.push_str(marker);
+        self.render_children(element);
+        self.out.push_str(marker);
+    }
+
+    fn push_text(&mut self, text: &str) {
+        let collapsed = text.split_whitespace().collect::<Vec<_>>().join(" ");
+        if collapsed.is_empty() {
+            if !self.at_line_start() && text.chars().any(char::is_whitespace) {
+                self.push_space();
+            }
+            return;
+        }
+        if text.starts_with(char::is_whitespace) && !self.at_line_start() {
+            self.push_space();
+        }
+        self.out.push_str(&collapsed);
+        if text.ends_with(char::is_whitespace) {
+            self.push_space();
+        }
+    }
+
+    fn push_space(&mut self) {
+        if !self.out.ends_with(' ') {
+            self.out.push(' ');
+        }
+    }
+
+    fn at_line_start(&self) -> bool {
+        self.out.is_empty() || self.out.ends_with('\n') || self.out.ends_with("> ")
+    }
+
+    fn quote_prefix(&self) -> String {
+        "> ".repeat(self.quote_depth)
+    }
+
+    fn line_break(&mut self) {
+        self.trim_trailing_spaces();
+        if !self.out.is_empty() && !self.out.ends_with('\n') {
+            self.out.push('\n');
+            self.out.push_str(&self.quote_prefix());
+        }
+    }
+
+    fn block_break(&mut self) {
+        self.trim_trailing_spaces();
+        if self.out.is_empty() || self.out.ends_with("\n\n") {
+            return;
+        }
+        if self.lists.is_empty() {
+            let prefix = self.quote_prefix();
+            self.out.push('\n');
+            self.out.push_str(prefix.trim_end());
+            self.out.push('\n');
+            self.out.push_str(&prefix);
+        } else {
+            self.line_break();
+        }
+    }
+
+    fn trim_trailing_spaces(&mut self) {
+        let trimmed = self.out.trim_end_matches(' ').len();
+        self.out.truncate(trimmed);
+    }
+
+    /// Collapses runs of blank lines and trims trailing spaces, leaving fenced code as it is.
+    fn finish(self) -> String {
+        let mut result = String::new();
+        let mut blank_lines = 0;
+        let mut in_fence = false;
+        for line in self.out.lines() {
+            if line.trim_start_matches(['>', ' ']).starts_with("```") {
+                in_fence = !in_fence;
+            } else if in_fence {
+                result.push_str(line);
+                result.push('\n');
+                continue;
+            }
+            if line.trim().is_empty() {
+                blank_lines += 1;
+                if blank_lines > 1 {
+                    continue;
+                }
+            } else {
+                blank_lines = 0;
+            }
+            result.push_str(line.trim_end());
+            result.push('\n');
+        }
+        result.trim().to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_headings_and_paragraphs() {
+        let html = "<h1>Tokio</h1><p>A runtime for writing <strong>reliable</strong>, \
+                    asynchronous applications.</p>";
+        assert_eq!(
+            html_to_markdown(html),
+            "# Tokio\n\nA runtime for writing **reliable**, asynchronous applications.",
+            "Headings and paragraphs should be separated by a blank line"
+        );
+    }
+
+    #[test]
+    fn test_code_blocks_and_inline_code() {
+        let html = r#"<p>Add <code>tokio</code> to your dependencies:</p>
+<pre><code class="language-toml">[dependencies]
+tokio = { version = "1", features = ["full"] }
+</code></pre>"#;
+        assert_eq!(
+            html_to_markdown(html),
+            "Add `tokio` to your dependencies:\n\n```toml\n[dependencies]\ntokio = { version = \"1\", features = [\"full\"] }\n```",
+            "Code blocks should keep their language and content verbatim"
+        );
+    }
+
+    #[test]
+    fn test_code_block_layout() {
+        let html = "<p>Spawn a task:</p>\n<pre><code class=\"language-rust\">fn main() {\n    \
+                    let x = 1;\n\n\n    if x &gt; 0 {\n        println!(\"{}\", x); \n    }\n}\n\
+                    </code></pre>";
+        assert_eq!(
+            html_to_markdown(html),
+            "Spawn a task:\n\n```rust\nfn main() {\n    let x = 1;\n\n\n    if x > 0 {\n        \
+             println!(\"{}\", x); \n    }\n}\n```",
+            "Indentation, blank lines and trailing spaces inside code blocks should be kept"
+        );
+    }
+
+    #[test]
+    fn test_links_and_lists() {
+        let html = r##"<h2 id="features"><a class="anchor" href="#features">§</a>Features</h2>
+<ul><li>Fast: <a href="https://tokio.rs">zero-cost</a></li><li>Reliable
+<ol><li>one</li><li>two</li></ol></li></ul>"##;
+        assert_eq!(
+            html_to_markdown(html),
+            "## Features\n\n- Fast: [zero-cost](https://tokio.rs)\n- Reliable\n  1. one\n  2. two",
+            "Lists should nest and anchor links should be dropped"
+        );
+    }
+
+    #[test]
+    fn test_table() {
+        let html = "<table><thead><tr><th>Feature</th><th>Description</th></tr></thead>\
+                    <tbody><tr><td><code>rt</code></td><td>Runtime</td></tr></tbody></table>";
+        assert_eq!(
+            html_to_markdown(html),
+            "| Feature | Description |\n| --- | --- |\n| `rt` | Runtime |",
+            "Tables should render as pipe tables"
+        );
+    }
+}
@@ -1,7 +1,143 @@
+// Without `native` only the async lookups behind `DocsClient` are built; the parsing helpers
+// they share with the MCP tools are partly unused then.
+#![cfg_attr(not(feature = "native"), allow(dead_code, unused_imports))]
+
+#[cfg(feature = "native")]
+pub mod admin;
+pub(crate) mod all_items_cache;
+pub(crate) mod associated_items;
+#[cfg(feature = "native")]
+pub mod attribute_macro_docs;
+#[cfg(feature = "native")]
+pub mod browse_source;
+#[cfg(feature = "native")]
+pub mod capabilities;
+#[cfg(feature = "native")]
+pub(crate) mod chunked;
+#[cfg(feature = "native")]
+pub mod compare_versions;
+#[cfg(feature = "native")]
+pub mod crate_categories;
+#[cfg(feature = "native")]
+pub mod crate_changelog;
+#[cfg(feature = "native")]
+pub mod crate_features;
+#[cfg(feature = "native")]
 pub mod crate_info;
 pub mod crate_items;
+#[cfg(feature = "native")]
+pub mod crate_license;
+#[cfg(feature = "native")]
+pub mod crate_owners;
+#[cfg(feature = "native")]
+pub mod crate_readme;
+pub(crate) mod crates_io;
+pub(crate) mod dates;
+pub(crate) mod default_version;
+#[cfg(feature = "native")]
+pub mod dependency_snippet;
+#[cfg(feature = "native")]
+pub mod dependency_tree;
+#[cfg(feature = "native")]
+pub mod deprecated_items;
+pub(crate) mod deprecation;
+pub mod derive_macro_docs;
+pub(crate) mod doc_cfg;
+pub(crate) mod docs_build;
+pub(crate) mod docs_root;
+#[cfg(feature = "native")]
+pub mod error_types;
+#[cfg(feature = "native")]
+pub(crate) mod example_dependencies;
+#[cfg(feature = "native")]
+pub mod find_usages_in_api;
+#[cfg(feature = "native")]
+pub mod get_source;
 pub mod get_struct_docs;
+pub mod http;
+#[cfg(feature = "native")]
+pub(crate) mod local_docs;
+#[cfg(feature = "native")]
+pub(crate) mod maintenance;
+pub(crate) mod markdown;
+pub mod module_items;
+pub(crate) mod near_matches;
+pub(crate) mod negative_cache;
+pub(crate) mod opaque_return;
+pub(crate) mod output_format;
+pub(crate) mod rate_limit;
+pub(crate) mod registry;
+#[cfg(feature = "native")]
+pub(crate) mod runtime;
+pub(crate) mod rustdoc_json;
+pub(crate) mod scraped_examples;
+#[cfg(feature = "native")]
+pub mod search_in_crate;
+pub(crate) mod selectors;
+pub(crate) mod sparse_index;
+#[cfg(feature = "native")]
+pub mod suggest_imports;
+pub(crate) mod summary;
+#[cfg(feature = "native")]
+pub mod supported_targets;
+#[cfg(feature = "native")]
+pub mod trait_implementors;
+#[cfg(feature = "native")]
+pub mod trait_impls;
+#[cfg(feature = "native")]
+pub mod trait_matrix;
 
+#[cfg(feature = "native")]
+pub use admin::{CachePurgeTool, PrefetchTool, ReloadConfigTool, StatsTool};
+#[cfg(feature = "native")]
+pub use attribute_macro_docs::AttributeMacroDocsTool;
+#[cfg(feature = "native")]
+pub use browse_source::BrowseSourceTool;
+#[cfg(feature = "native")]
+pub use capabilities::CapabilitiesTool;
+#[cfg(feature = "native")]
+pub use compare_versions::CompareVersionsTool;
+#[cfg(feature = "native")]
+pub use crate_categories::CrateCategoriesTool;
+#[cfg(feature = "native")]
+pub use crate_changelog::CrateChangelogTool;
+#[cfg(feature = "native")]
+pub use crate_features::CrateFeaturesTool;
+#[cfg(feature = "native")]
 pub use crate_info::CrateInfoTool;
 pub use crate_items::CrateItemsTool;
+#[cfg(feature = "native")]
+pub use crate_license::CrateLicenseTool;
+#[cfg(feature = "native")]
+pub use crate_owners::CrateOwnersTool;
+#[cfg(feature = "native")]
+pub use crate_readme::CrateReadmeTool;
+#[cfg(feature = "native")]
+pub use dependency_snippet::DependencySnippetTool;
+#[cfg(feature = "native")]
+pub use dependency_tree::DependencyTreeTool;
+#[cfg(feature = "native")]
+pub use deprecated_items::DeprecatedItemsTool;
+#[cfg(feature = "native")]
+pub use derive_macro_docs::DeriveMacroDocsTool;
+#[cfg(feature = "native")]
+pub use error_types::ErrorTypesTool;
+#[cfg(feature = "native")]
+pub use find_usages_in_api::FindUsagesInApiTool;
+#[cfg(feature = "native")]
+pub use get_source::GetSourceTool;
 pub use get_struct_docs::StructDocsTool;
+#[cfg(feature = "native")]
+pub use module_items::ModuleItemsTool;
+#[cfg(feature = "native")]
+pub use search_in_crate::SearchInCrateTool;
+#[cfg(feature = "native")]
+pub use suggest_imports::SuggestImportsTool;
+#[cfg(feature = "native")]
+pub use supported_targets::SupportedTargetsTool;
+#[cfg(feature = "native")]
+pub use trait_implementors::TraitImplementorsTool;
+#[cfg(feature = "native")]
+pub use trait_impls::TraitImplsTool;
+#[cfg(feature = "native")]
+pub use trait_matrix::TraitMatrixTool;
@@ -0,0 +1,505 @@
+use anyhow::{anyhow, Result};
+#[cfg(feature = "native")]
+use mcp_sdk::{
+    tools::Tool,
+    types::{CallToolResponse, ToolResponseContent},
+};
+use scraper::{CaseSensitivity, ElementRef, Html};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tracing::debug;
+use url::Url;
+
+use super::get_struct_docs::{HtmlFetcher, HttpHtmlFetcher};
+#[cfg(feature = "native")]
+use super::runtime;
+use super::{doc_cfg, docs_root, registry, selectors};
+use crate::{config, error};
+
+/// Direct children of a module, as listed on its `index.html`
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ModuleItems {
+    crate_name: String,
+    version: String,
+    /// Module path within the crate, empty for the crate root
+    path: String,
+    /// Sections in page order, e.g. `Re-exports`, `Modules`, `Structs`
+    sections: Vec<ModuleSection>,
+}
+
+impl ModuleItems {
+    pub fn sections(&self) -> &[ModuleSection] {
+        &self.sections
+    }
+
+    pub fn section(&self, title: &str) -> Option<&ModuleSection> {
+        self.sections.iter().find(|section| section.title == title)
+    }
+
+    /// Documentation link of the item the module exports as `name`: an item listed under its
+    /// own name, or else the target of a `pub use` re-exporting it (possibly renamed).
+    pub fn resolve(&self, name: &str) -> Option<&str> {
+        let (reexports, items): (Vec<_>, Vec<_>) = self
+            .sections
+            .iter()
+            .partition(|section| section.title == "Re-exports");
+
+        let listed = items
+            .iter()
+            .flat_map(|section| &section.items)
+            .find(|item| item.name == name);
+        let reexported = || {
+            reexports
+                .iter()
+                .flat_map(|section| &section.items)
+                .find(|item| {
+                    let statement = item.name.trim_end_matches(';');
+                    statement.ends_with(&format!("::{}", name))
+                        || statement.ends_with(&format!(" as {}", name))
+                        || statement.ends_with(&format!("use {}", name))
+                })
+        };
+
+        listed.or_else(reexported)?.doc_link()
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ModuleSection {
+    title: String,
+    items: Vec<ModuleItem>,
+}
+
+impl ModuleSection {
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    pub fn items(&self) -> &[ModuleItem] {
+        &self.items
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ModuleItem {
+    /// Item name, or the `pub use` statement for re-exports
+    name: String,
+    /// First line of the item's docs
+    summary: Option<String>,
+    doc_link: Option<String>,
+    /// Cargo features that need enabling to use the item, from its feature badge
+    required_features: Vec<String>,
+    /// Whether the item only exists in the docs build (`cfg(docsrs)`), so code must not use it
+    docs_only: bool,
+}
+
+impl ModuleItem {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn summary(&self) -> Option<&str> {
+        self.summary.as_deref()
+    }
+
+    pub fn doc_link(&self) -> Option<&str> {
+        self.doc_link.as_deref()
+    }
+
+    pub fn required_features(&self) -> &[String] {
+        &self.required_features
+    }
+
+    pub fn docs_only(&self) -> bool {
+        self.docs_only
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ModuleItemsParams {
+    crate_name: String,
+    path: Option<String>,
+    version: Option<String>,
+    registry: Option<String>,
+}
+
+pub struct ModuleItemsTool {
+    html_fetcher: Box<dyn HtmlFetcher>,
+}
+
+impl ModuleItemsTool {
+    pub fn new() -> Self {
+        Self {
+            html_fetcher: Box::new(HttpHtmlFetcher::new()),
+        }
+    }
+
+    #[cfg(feature = "native")]
+    pub(crate) fn fetch_module(
+        &self,
+        crate_name: &str,
+        path: &str,
+        version: Option<&str>,
+    ) -> Result<ModuleItems> {
+        let version = version.unwrap_or("latest");
+        let path = module_path(crate_name, path);
+        let url = module_url(&docs_root::crate_root_blocking(crate_name, version)?, &path);
+        debug!("Fetching module index: {}", url);
+        let html = self.html_fetcher.fetch_html_blocking(&url)?;
+        parse_module_page(crate_name, version, &path, &url, &html)
+    }
+}
+
+impl Default for ModuleItemsTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Normalizes a module path: `tokio::sync`, `sync` and `sync::` all become `sync`.
+pub(crate) fn module_path(crate_name: &str, path: &str) -> String {
+    let crate_ident = crate_name.replace('-', "_");
+    path.trim_matches(':')
+        .split("::")
+        .filter(|segment| !segment.is_empty())
+        .enumerate()
+        .filter(|(i, segment)| !(*i == 0 && (*segment == crate_ident || *segment == "crate")))
+        .map(|(_, segment)| segment)
+        .collect::<Vec<_>>()
+        .join("::")
+}
+
+/// URL of a module's index page below the crate's documentation root.
+pub(crate) fn module_url(root_url: &str, path: &str) -> String {
+    let mut url = root_url.to_string();
+    for segment in path.split("::").filter(|segment| !segment.is_empty()) {
+        url.push_str(segment);
+        url.push('/');
+    }
+    url.push_str("index.html");
+    url
+}
+
+pub(crate) fn parse_module_page(
+    crate_name: &str,
+    version: &str,
+    path: &str,
+    page_url: &str,
+    html: &str,
+) -> Result<ModuleItems> {
+    let document = Html::parse_document(html);
+    let heading_selector = selectors::parse("#main-content h2.section-header[id]", "module page")?;
+    let link_selector = selectors::parse("a", "module page")?;
+    let badge_selector = selectors::parse(".stab.portability[title]", "module page")?;
+    let base = Url::parse(page_url).ok();
+
+    let mut sections = Vec::new();
+    for heading in document.select(&heading_selector) {
+        let Some(table) = heading
+            .next_siblings()
+            .filter_map(ElementRef::wrap)
+            .next()
+            .filter(|el| {
+                el.value()
+                    .has_class("item-table", CaseSensitivity::CaseSensitive)
+            })
+        else {
+            continue;
+        };
+        // The heading ends with a `§` anchor link
+        let title = heading
+            .text()
+            .collect::<String>()
+            .trim_end_matches('§')
+            .trim()
+            .to_string();
+
+        let items = item_rows(table)
+            .into_iter()
+            .filter_map(|(name_cell, desc_cell)| {
+                // Re-exports show the whole `pub use` with a link per path segment, the last
+                // being the item itself. Other rows link the item first, followed by badges
+                // such as feature gates.
+                let links: Vec<ElementRef> = name_cell.select(&link_selector).collect();
+                let is_reexport = name_cell
+                    .children()
+                    .filter_map(ElementRef::wrap)
+                    .next()
+                    .is_some_and(|el| el.value().name() == "code");
+                let (name, link) = if is_reexport {
+                    (name_cell.text().collect::<String>(), links.last())
+                } else {
+                    match links.first() {
+                        Some(link) => (link.text().collect::<String>(), Some(link)),
+                        None => (name_cell.text().collect::<String>(), None),
+                    }
+                };
+                let name = name.trim().to_string();
+                if name.is_empty() {
+                    return None;
+                }
+                let summary = desc_cell
+                    .map(|desc| desc.text().collect::<String>().trim().to_string())
+                    .filter(|summary| !summary.is_empty());
+                let doc_link = link
+                    .and_then(|link| link.value().attr("href"))
+                    .and_then(|href| match &base {
+                        Some(base) => base.join(href).ok().map(String::from),
+                        None => Some(href.to_string()),
+                    });
+                // Badges carry the full `doc_cfg` condition in their title
+                let badges: Vec<&str> = name_cell
+                    .select(&badge_selector)
+                    .filter_map(|badge| badge.value().attr("title"))
+                    .collect();
+                let required_features = badges
+                    .iter()
+                    .flat_map(|badge| doc_cfg::required_features(badge))
+                    .collect();
+                let docs_only = badges.iter().any(|badge| doc_cfg::is_docs_only(badge));
+                Some(ModuleItem {
+                    name,
+                    summary,
+                    doc_link,
+                    required_features,
+                    docs_only,
+                })
+            })
+            .collect::<Vec<_>>();
+
+        if !items.is_empty() {
+            sections.push(ModuleSection { title, items });
+        }
+    }
+
+    if sections.is_empty() {
+        return Err(anyhow!(
+            "No items found in module {} of {} {}",
+            if path.is_empty() { crate_name } else { path },
+            crate_name,
+            version
+        ));
+    }
+
+    Ok(ModuleItems {
+        crate_name: crate_name.to_string(),
+        version: version.to_string(),
+        path: path.to_string(),
+        sections,
+    })
+}
+
+/// Name and description cells of an item table. Newer rustdoc renders a `dl` of `dt`/`dd`
+/// pairs, older versions a `ul` of `li` rows holding `.item-name` and `.desc`.
+fn item_rows(table: ElementRef<'_>) -> Vec<(ElementRef<'_>, Option<ElementRef<'_>>)> {
+    let children: Vec<ElementRef> = table.children().filter_map(ElementRef::wrap).collect();
+    let has_class =
+        |el: &ElementRef, class: &str| el.value().has_class(class, CaseSensitivity::CaseSensitive);
+
+    children
+        .iter()
+        .enumerate()
+        .filter_map(|(i, child)| match child.value().name() {
+            "dt" => {
+                let desc = children.get(i + 1).filter(|el| el.value().name() == "dd");
+                Some((*child, desc.copied()))
+            }
+            "li" | "div" => {
+                let cells: Vec<ElementRef> =
+                    child.children().filter_map(ElementRef::wrap).collect();
+                let name = cells.iter().find(|el| has_class(el, "item-name"))?;
+                let desc = cells.iter().find(|el| has_class(el, "desc"));
+                Some((*name, desc.copied()))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+#[cfg(feature = "native")]
+impl Tool for ModuleItemsTool {
+    fn name(&self) -> String {
+        "module_items".to_string()
+    }
+
+    fn description(&self) -> String {
+        "List what a single module of a crate exports: its submodules, re-exports and \
+        items with their one-line summaries. Only fetches that module's page, so it is \
+        cheaper and more focused than crate_items for exploring a crate step by step."
+            .to_string()
+    }
+
+    fn input_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "crate_name": {
+                    "type": "string",
+                    "description": "Name of the crate containing the module"
+                },
+                "path": {
+                    "type": "string",
+                    "description": "Module path within the crate, e.g. sync::mpsc. Defaults to the crate root"
+                },
+                "version": {
+                    "type": "string",
+                    "description": "Optional version of the crate (defaults to latest)"
+                },
+                "registry": registry::registry_schema()
+            },
+            "required": ["crate_name"]
+        })
+    }
+
+    fn call(&self, input: Option<serde_json::Value>) -> Result<CallToolResponse> {
+        let args: ModuleItemsParams = error::parse_args(input)?;
+        config::current().ensure_crate_allowed(&args.crate_name)?;
+        let module = runtime::blocking(|| {
+            registry::in_registry(&args.crate_name, args.registry.as_deref(), || {
+                self.fetch_module(
+                    &args.crate_name,
+                    args.path.as_deref().unwrap_or_default(),
+                    args.version.as_deref(),
+                )
+            })
+        })?;
+
+        Ok(CallToolResponse {
+            content: vec![ToolResponseContent::Text {
+                text: serde_json::to_string_pretty(&module)?,
+            }],
+            is_error: None,
+            meta: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::fs;
+
+    const SYNC_URL: &str = "https://docs.rs/tokio/1.43.0/tokio/sync/index.html";
+
+    #[test]
+    fn test_module_path_and_url() {
+        assert_eq!(module_path("tokio", "tokio::sync::mpsc"), "sync::mpsc");
+        assert_eq!(module_path("tokio", "sync::"), "sync");
+        assert_eq!(module_path("opentelemetry-sdk", "opentelemetry_sdk"), "");
+        assert_eq!(
+            module_url("https://docs.rs/tokio/1.43.0/tokio/", "sync"),
+            SYNC_URL
+        );
+        assert_eq!(
+            module_url(
+                "https://docs.rs/opentelemetry-sdk/latest/opentelemetry_sdk/",
+                ""
+            ),
+            "https://docs.rs/opentelemetry-sdk/latest/opentelemetry_sdk/index.html"
+        );
+    }
+
+    #[test]
+    fn test_parse_module_page() -> Result<()> {
+        let url = "https://docs.rs/demo/0.1.0/demo/net/index.html";
+        let html = fs::read_to_string("test-data/module_items/module-index.html")?;
+        let module = parse_module_page("demo", "0.1.0", "net", url, &html)?;
+
+        let titles: Vec<&str> = module.sections().iter().map(|s| s.title()).collect();
+        assert_eq!(
+            titles,
+            vec!["Re-exports", "Modules", "Structs", "Functions"]
+        );
+
+        let udp = &module.section("Modules").unwrap().items()[1];
+        assert_eq!(udp.name(), "udp", "Feature badges aren't part of the name");
+        assert_eq!(
+            udp.summary(),
+            Some("UDP sockets for sending and receiving datagrams.")
+        );
+        assert_eq!(
+            udp.doc_link(),
+            Some("https://docs.rs/demo/0.1.0/demo/net/udp/index.html")
+        );
+
+        let reexport = &module.section("Re-exports").unwrap().items()[0];
+        assert_eq!(reexport.name(), "pub use crate::error::ConnectError;");
+        assert_eq!(reexport.summary(), None);
+        assert_eq!(
+            reexport.doc_link(),
+            Some("https://docs.rs/demo/0.1.0/demo/error/struct.ConnectError.html")
+        );
+
+        let lookup_host = &module.section("Functions").unwrap().items()[0];
+        assert_eq!(lookup_host.name(), "lookup_host");
+
+        let docs_only: Vec<(&str, bool)> = module
+            .section("Structs")
+            .unwrap()
+            .items()
+            .iter()
+            .map(|item| (item.name(), item.docs_only()))
+            .collect();
+        assert_eq!(
+            docs_only,
+            vec![
+                ("TcpListener", false),
+                ("TcpStream", false),
+                ("UnixShim", true)
+            ]
+        );
+        assert!(
+            !udp.docs_only(),
+            "Feature gated items exist outside docs builds"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_resolve() -> Result<()> {
+        let url = "https://docs.rs/demo/0.1.0/demo/net/index.html";
+        let html = fs::read_to_string("test-data/module_items/module-index.html")?;
+        let module = parse_module_page("demo", "0.1.0", "net", url, &html)?;
+
+        assert_eq!(
+            module.resolve("ConnectError"),
+            Some("https://docs.rs/demo/0.1.0/demo/error/struct.ConnectError.html"),
+            "Re-exports resolve to the page of the re-exported item"
+        );
+        assert_eq!(
+            module.resolve("TcpStream"),
+            Some("https://docs.rs/demo/0.1.0/demo/net/struct.TcpStream.html")
+        );
+        assert_eq!(module.resolve("Error"), None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_legacy_item_table() -> Result<()> {
+        let html = r##"<section id="main-content">
+            <h2 id="structs" class="section-header">Structs<a href="#structs" class="anchor">§</a></h2>
+            <ul class="item-table">
+                <li><div class="item-name"><a class="struct" href="struct.Mutex.html">Mutex</a></div>
+                <div class="desc docblock-short">An asynchronous mutex.</div></li>
+            </ul></section>"##;
+        let module = parse_module_page("tokio", "1.43.0", "sync", SYNC_URL, html)?;
+
+        assert_eq!(
+            module.section("Structs").unwrap().items(),
+            &[ModuleItem {
+                name: "Mutex".to_string(),
+                summary: Some("An asynchronous mutex.".to_string()),
+                doc_link: Some(
+                    "https://docs.rs/tokio/1.43.0/tokio/sync/struct.Mutex.html".to_string()
+                ),
+                required_features: Vec::new(),
+                docs_only: false,
+            }]
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,141 @@
+//! "Did you mean" suggestions for an item name that isn't in a crate. Agents often ask for a
+//! name close to the real one: another case (`Tracer_Provider`), a typo (`TracerProvidr`),
+//! or the name without the prefix the crate gives it (`TracerProvider` for
+//! `SdkTracerProvider`).
+
+/// Names suggested at most
+pub(crate) const MAX_SUGGESTIONS: usize = 5;
+
+/// How close a candidate is to the name asked for; lower is closer
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+struct Closeness {
+    /// Characters added, removed or changed, discounted when one name contains the other
+    distance: usize,
+    /// Whether the names only match within the edit distance, rather than one containing
+    /// the other
+    typo: bool,
+}
+
+/// The `names` closest to `query`, closest first, comparing the last path segments: `query`
+/// `opentelemetry::TracerProvider` finds `trace::SdkTracerProvider`.
+pub(crate) fn nearest<'a>(query: &str, names: impl IntoIterator<Item = &'a str>) -> Vec<&'a str> {
+    let query = normalize(last_segment(query));
+    if query.is_empty() {
+        return Vec::new();
+    }
+    let mut matches: Vec<(Closeness, &str)> = names
+        .into_iter()
+        .filter_map(|name| closeness(&query, &normalize(last_segment(name))).map(|c| (c, name)))
+        .collect();
+    matches.sort_by(|(a, a_name), (b, b_name)| a.cmp(b).then(a_name.cmp(b_name)));
+    let mut nearest: Vec<&str> = matches.into_iter().map(|(_, name)| name).collect();
+    nearest.dedup();
+    nearest.truncate(MAX_SUGGESTIONS);
+    nearest
+}
+
+fn closeness(query: &str, name: &str) -> Option<Closeness> {
+    let length_difference = name.len().abs_diff(query.len());
+    // A name much shorter than the other is contained in too many others to suggest them all
+    let (shorter, longer) = match name.len() < query.len() {
+        true => (name, query),
+        false => (query, name),
+    };
+    let contained = shorter.len() * 2 >= longer.len() && longer.contains(shorter);
+    if contained {
+        return Some(Closeness {
+            distance: length_difference.div_ceil(3),
+            typo: false,
+        });
+    }
+    let max_distance = (query.chars().count() / 4).max(1);
+    if length_difference > max_distance {
+        return None;
+    }
+    let distance = edit_distance(query, name);
+    (distance <= max_distance).then_some(Closeness {
+        distance,
+        typo: true,
+    })
+}
+
+fn last_segment(path: &str) -> &str {
+    path.rsplit("::").next().unwrap_or(path).trim()
+}
+
+/// The name in lowercase without underscores, so `TracerProvider` and `tracer_provider` match
+fn normalize(name: &str) -> String {
+    name.chars()
+        .filter(|c| *c != '_')
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Edit distance counting insertions, deletions, substitutions and swaps of adjacent
+/// characters, as rustdoc does.
+pub(crate) fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut rows = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for (i, row) in rows.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in rows[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let substitution = usize::from(a[i - 1] != b[j - 1]);
+            let mut distance = (rows[i - 1][j] + 1)
+                .min(rows[i][j - 1] + 1)
+                .min(rows[i - 1][j - 1] + substitution);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                distance = distance.min(rows[i - 2][j - 2] + 1);
+            }
+            rows[i][j] = distance;
+        }
+    }
+    rows[a.len()][b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    const NAMES: [&str; 6] = [
+        "trace::SdkTracerProvider",
+        "trace::SdkTracer",
+        "trace::Config",
+        "logs::SdkLoggerProvider",
+        "metrics::SdkMeterProvider",
+        "Resource",
+    ];
+
+    #[test]
+    fn test_nearest() {
+        assert_eq!(
+            nearest("opentelemetry::TracerProvider", NAMES),
+            ["trace::SdkTracerProvider"],
+            "The name without the crate's prefix"
+        );
+        assert_eq!(nearest("resource", NAMES), ["Resource"]);
+        assert_eq!(nearest("Sdk_Tracer", NAMES)[0], "trace::SdkTracer");
+        assert_eq!(
+            nearest("SdkTracerProvidr", NAMES),
+            ["trace::SdkTracerProvider", "trace::SdkTracer"],
+            "Typos, closest first"
+        );
+        assert!(nearest("Histogram", NAMES).is_empty());
+        assert!(nearest("", NAMES).is_empty());
+    }
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("mutex", "mutex"), 0);
+        assert_eq!(edit_distance("mutex", "mtuex"), 1, "Swaps count once");
+        assert_eq!(edit_distance("mutex", "mutexes"), 2);
+        assert_eq!(edit_distance("", "abc"), 3);
+    }
+}
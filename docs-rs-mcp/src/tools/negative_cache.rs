@@ -0,0 +1,125 @@
+//! Memory of the URLs that answered 404 Not Found or 410 Gone, so an agent retrying the same
+//! made-up crate, version or item name in a loop gets the same answer without each attempt
+//! reaching docs.rs or crates.io again.
+//!
+//! Entries expire after `cache.not_found_ttl_secs`, much sooner than cached pages, as a missing
+//! page may simply not be built or published yet.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// URLs remembered at most; expired entries, then the oldest, make room for new ones
+const CAPACITY: usize = 1024;
+
+#[derive(Debug, Default)]
+pub(crate) struct NegativeCache {
+    /// When each URL last answered Not Found
+    missing: Mutex<HashMap<String, Instant>>,
+}
+
+impl NegativeCache {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether `url` answered Not Found less than `ttl` before `now`.
+    pub(crate) fn is_missing(&self, url: &str, ttl: Duration, now: Instant) -> bool {
+        self.lock()
+            .get(url)
+            .is_some_and(|since| now.saturating_duration_since(*since) < ttl)
+    }
+
+    /// Remembers `url` as missing when `status` says so, and forgets it otherwise.
+    pub(crate) fn record(&self, url: &str, status: u16, ttl: Duration, now: Instant) {
+        let mut missing = self.lock();
+        if !is_not_found(status) {
+            missing.remove(url);
+            return;
+        }
+        if missing.len() >= CAPACITY && !missing.contains_key(url) {
+            missing.retain(|_, since| now.saturating_duration_since(*since) < ttl);
+            if missing.len() >= CAPACITY {
+                if let Some(oldest) = missing
+                    .iter()
+                    .min_by_key(|(_, since)| **since)
+                    .map(|(url, _)| url.clone())
+                {
+                    missing.remove(&oldest);
+                }
+            }
+        }
+        missing.insert(url.to_string(), now);
+    }
+
+    /// Forgets the URLs that `matches`, returning how many were remembered.
+    #[cfg(feature = "native")]
+    pub(crate) fn forget(&self, matches: impl Fn(&str) -> bool) -> usize {
+        let mut missing = self.lock();
+        let before = missing.len();
+        missing.retain(|url, _| !matches(url));
+        before - missing.len()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, Instant>> {
+        match self.missing.lock() {
+            Ok(missing) => missing,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+}
+
+/// Statuses meaning the page doesn't exist, rather than that it couldn't be served right now
+pub(crate) fn is_not_found(status: u16) -> bool {
+    matches!(status, 404 | 410)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TTL: Duration = Duration::from_secs(60);
+
+    #[test]
+    fn test_not_found_expires() {
+        let cache = NegativeCache::new();
+        let url = "https://docs.rs/demo/latest/demo/struct.Imaginary.html";
+        let start = Instant::now();
+
+        assert!(!cache.is_missing(url, TTL, start));
+        cache.record(url, 404, TTL, start);
+        assert!(cache.is_missing(url, TTL, start + Duration::from_secs(59)));
+        assert!(!cache.is_missing(url, TTL, start + TTL), "Expired");
+
+        cache.record(url, 200, TTL, start);
+        assert!(!cache.is_missing(url, TTL, start), "Found since");
+    }
+
+    #[test]
+    fn test_only_not_found_is_remembered() {
+        let cache = NegativeCache::new();
+        let now = Instant::now();
+        for (status, remembered) in [(404, true), (410, true), (429, false), (503, false)] {
+            let url = format!("https://docs.rs/status/{}", status);
+            cache.record(&url, status, TTL, now);
+            assert_eq!(cache.is_missing(&url, TTL, now), remembered, "{}", status);
+        }
+    }
+
+    #[test]
+    fn test_capacity() {
+        let cache = NegativeCache::new();
+        let start = Instant::now();
+        for i in 0..=CAPACITY {
+            let at = start + Duration::from_millis(i as u64);
+            cache.record(&format!("https://docs.rs/{}", i), 404, TTL, at);
+        }
+        let now = start + Duration::from_secs(1);
+        assert_eq!(cache.lock().len(), CAPACITY);
+        assert!(
+            !cache.is_missing("https://docs.rs/0", TTL, now),
+            "Oldest dropped"
+        );
+        assert!(cache.is_missing(&format!("https://docs.rs/{}", CAPACITY), TTL, now));
+    }
+}
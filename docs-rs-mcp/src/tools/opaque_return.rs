@@ -0,0 +1,270 @@
+//! What can be done with the value of a function returning `impl Trait`. Its type can't be
+//! named, so all there is to go on are the bounds in the signature, e.g. the `Item` of
+//! `impl Iterator<Item = &T> + '_`. An `async fn` returns such a value too, a future of the
+//! written return type.
+
+use serde::{Deserialize, Serialize};
+
+/// Capabilities of an opaque return type
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OpaqueReturn {
+    /// Traits the value implements, e.g. `Iterator<Item = &T>` or `Send`
+    bounds: Vec<String>,
+    /// Associated types the bounds fix, e.g. `Item = &T`
+    associated_types: Vec<String>,
+    /// Lifetimes the value may borrow for, e.g. `'_`
+    lifetimes: Vec<String>,
+    /// Whether the value is the future of an `async fn`
+    is_async: bool,
+    /// How to use the value, for the traits that call for it
+    hints: Vec<String>,
+}
+
+impl OpaqueReturn {
+    pub fn bounds(&self) -> &[String] {
+        &self.bounds
+    }
+
+    pub fn associated_types(&self) -> &[String] {
+        &self.associated_types
+    }
+
+    pub fn is_async(&self) -> bool {
+        self.is_async
+    }
+}
+
+/// The opaque return type of the function `signature`, if it has one.
+pub(crate) fn parse(signature: &str) -> Option<OpaqueReturn> {
+    let (before, output) = split_output(signature)?;
+    let is_async = before.split_whitespace().any(|word| word == "async");
+    let bounds: Vec<String> = if is_async {
+        vec![format!("Future<Output = {}>", output.unwrap_or("()"))]
+    } else {
+        split_top_level(output?.strip_prefix("impl ")?, '+')
+            .into_iter()
+            .map(str::to_string)
+            .collect()
+    };
+
+    let (lifetimes, bounds): (Vec<String>, Vec<String>) = bounds
+        .into_iter()
+        .partition(|bound| bound.starts_with('\''));
+    let associated_types = bounds
+        .iter()
+        .flat_map(|bound| associated_types(bound))
+        .collect();
+    let mut hints: Vec<String> = bounds
+        .iter()
+        .filter_map(|bound| hint(trait_name(bound)))
+        .map(str::to_string)
+        .collect();
+    if !lifetimes.is_empty() {
+        hints.push(format!(
+            "Borrows for {}, so what it borrows from must outlive it",
+            lifetimes.join(" + ")
+        ));
+    }
+    hints.push(
+        "The concrete type can't be named: keep it in a local, pass it on as `impl Trait` or a \
+         generic, or box it as `Box<dyn Trait>`"
+            .to_string(),
+    );
+
+    Some(OpaqueReturn {
+        bounds,
+        associated_types,
+        lifetimes,
+        is_async,
+        hints,
+    })
+}
+
+/// How to use a value implementing `trait_name`, for the traits that call for it
+fn hint(trait_name: &str) -> Option<&'static str> {
+    Some(match trait_name {
+        "Iterator" => "Iterate with `for` or adapters; `.collect()` it to keep the items",
+        "DoubleEndedIterator" => "Can also be iterated from the back with `.rev()`",
+        "ExactSizeIterator" => "`.len()` gives the number of remaining items",
+        "IntoIterator" => "Loop over it with `for` or call `.into_iter()`",
+        "Future" => "`.await` it; nothing happens until it is polled",
+        "Stream" => "Take items with `StreamExt::next(&mut stream).await` in a loop",
+        "Fn" => "Call it like a function",
+        "FnMut" => "Call it like a function; it needs to be `mut`",
+        "FnOnce" => "Call it like a function, at most once",
+        "Display" => "Format it with `{}` or `.to_string()`",
+        _ => return None,
+    })
+}
+
+/// Splits a function signature into what comes before its parameters and its return type,
+/// without the where clause; the return type is `None` for `()`.
+fn split_output(signature: &str) -> Option<(&str, Option<&str>)> {
+    let fn_start = signature.find("fn ")?;
+    let after_fn = &signature[fn_start..];
+    let mut depth = 0usize;
+    let mut previous = ' ';
+    let mut params_end = None;
+    for (i, c) in after_fn.char_indices() {
+        match c {
+            '<' | '(' | '[' => depth += 1,
+            // `->` inside bounds such as `F: Fn() -> T` doesn't close a bracket
+            '>' if previous != '-' => depth = depth.saturating_sub(1),
+            ')' | ']' => {
+                depth = depth.saturating_sub(1);
+                if depth == 0 && c == ')' {
+                    params_end = Some(i + 1);
+                    break;
+                }
+            }
+            _ => {}
+        }
+        previous = c;
+    }
+
+    let rest = &after_fn[params_end?..];
+    let output = rest
+        .trim_start()
+        .strip_prefix("->")
+        .map(|output| strip_where_clause(output).trim())
+        .filter(|output| !output.is_empty());
+    Some((&signature[..fn_start], output))
+}
+
+/// `output` up to a `where` clause, which rustdoc may run into the type, e.g. `Tokenswhere`
+fn strip_where_clause(output: &str) -> &str {
+    let mut depth = 0usize;
+    let mut previous = ' ';
+    for (i, c) in output.char_indices() {
+        match c {
+            '<' | '(' | '[' => depth += 1,
+            '>' if previous != '-' => depth = depth.saturating_sub(1),
+            ')' | ']' => depth = depth.saturating_sub(1),
+            'w' if depth == 0 && output[i..].starts_with("where") => {
+                let after = output[i + "where".len()..].chars().next();
+                if after.is_none_or(char::is_whitespace) {
+                    return &output[..i];
+                }
+            }
+            _ => {}
+        }
+        previous = c;
+    }
+    output
+}
+
+/// Splits `text` at each `separator` outside brackets.
+fn split_top_level(text: &str, separator: char) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0usize;
+    let mut previous = ' ';
+    let mut start = 0;
+    for (i, c) in text.char_indices() {
+        match c {
+            '<' | '(' | '[' => depth += 1,
+            '>' if previous != '-' => depth = depth.saturating_sub(1),
+            ')' | ']' => depth = depth.saturating_sub(1),
+            c if c == separator && depth == 0 => {
+                parts.push(text[start..i].trim());
+                start = i + c.len_utf8();
+            }
+            _ => {}
+        }
+        previous = c;
+    }
+    parts.push(text[start..].trim());
+    parts.retain(|part| !part.is_empty());
+    parts
+}
+
+/// The trait of a bound without its path and arguments, e.g. `Iterator` for
+/// `std::iter::Iterator<Item = u8>`
+fn trait_name(bound: &str) -> &str {
+    let name = bound
+        .split(['<', '('])
+        .next()
+        .unwrap_or(bound)
+        .trim()
+        .trim_start_matches('?');
+    name.rsplit("::").next().unwrap_or(name)
+}
+
+/// The `Name = Type` arguments of a bound such as `Iterator<Item = &T>`
+fn associated_types(bound: &str) -> Vec<String> {
+    let Some(arguments) = bound
+        .split_once('<')
+        .and_then(|(_, rest)| rest.strip_suffix('>'))
+    else {
+        return Vec::new();
+    };
+    split_top_level(arguments, ',')
+        .into_iter()
+        .filter(|argument| {
+            argument.split_once('=').is_some_and(|(name, _)| {
+                let name = name.trim();
+                !name.is_empty() && name.chars().all(|c| c.is_alphanumeric() || c == '_')
+            })
+        })
+        .map(str::to_string)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_parse_impl_trait() {
+        let opaque =
+            parse("pub fn iter(&self) -> impl Iterator<Item = &T> + Send + '_where T: Sync")
+                .expect("opaque");
+        assert_eq!(opaque.bounds(), ["Iterator<Item = &T>", "Send"]);
+        assert_eq!(opaque.associated_types(), ["Item = &T"]);
+        assert_eq!(opaque.lifetimes, ["'_"]);
+        assert!(!opaque.is_async());
+        assert!(opaque.hints[0].contains(".collect()"), "{:?}", opaque.hints);
+        assert!(opaque.hints.iter().any(|hint| hint.contains("'_")));
+
+        let closure = parse("pub fn adder(n: u32) -> impl Fn(u32) -> u32").expect("opaque");
+        assert_eq!(closure.bounds(), ["Fn(u32) -> u32"]);
+        assert!(closure.associated_types().is_empty());
+        assert_eq!(closure.hints[0], "Call it like a function");
+
+        let nested = parse(
+            "pub fn map<F>(self, f: F) -> impl Stream<Item = Result<F::Output, Error>>\nwhere\n    F: FnMut(Bytes) -> u8",
+        )
+        .expect("opaque");
+        assert_eq!(
+            nested.associated_types(),
+            ["Item = Result<F::Output, Error>"]
+        );
+    }
+
+    #[test]
+    fn test_parse_async_fn() {
+        let opaque = parse("pub async fn connect(addr: &str) -> Result<Client, Error>")
+            .expect("async fns return futures");
+        assert!(opaque.is_async());
+        assert_eq!(opaque.bounds(), ["Future<Output = Result<Client, Error>>"]);
+        assert_eq!(
+            opaque.associated_types(),
+            ["Output = Result<Client, Error>"]
+        );
+        assert!(opaque.hints[0].contains(".await"));
+
+        let unit = parse("pub async fn close(self)").expect("opaque");
+        assert_eq!(unit.bounds(), ["Future<Output = ()>"]);
+    }
+
+    #[test]
+    fn test_concrete_returns() {
+        assert_eq!(parse("pub fn len(&self) -> usize"), None);
+        assert_eq!(parse("pub fn clear(&mut self)"), None);
+        assert_eq!(
+            parse("pub fn boxed<F: Fn() -> impl Sized>(f: F) -> Box<dyn Fn()>"),
+            None,
+            "impl in the generics isn't the return type"
+        );
+    }
+}
@@ -0,0 +1,183 @@
+//! The `format` argument of the tools returning documentation: pretty-printed JSON for
+//! programs, Markdown with headings, signatures in code fences and the doc text, which an LLM
+//! reads in far fewer tokens, or a digest of one `path | kind | signature | summary` line per
+//! item, the densest of the three, for planning prompts.
+
+use anyhow::Result;
+#[cfg(feature = "native")]
+use mcp_sdk::types::{CallToolResponse, ToolResponseContent};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+/// How a tool renders its response
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum Format {
+    #[default]
+    Json,
+    Markdown,
+    Digest,
+}
+
+impl Format {
+    /// `value` serialized as JSON, or rendered by `markdown` or `digest`
+    pub(crate) fn render<T: Serialize>(
+        self,
+        value: &T,
+        markdown: impl FnOnce(&T) -> String,
+        digest: impl FnOnce(&T) -> String,
+    ) -> Result<String> {
+        Ok(match self {
+            Format::Json => serde_json::to_string_pretty(value)?,
+            Format::Markdown => markdown(value),
+            Format::Digest => digest(value),
+        })
+    }
+
+    /// Tool response with `value` serialized as JSON, or rendered by `markdown` or `digest`.
+    #[cfg(feature = "native")]
+    pub(crate) fn response<T: Serialize>(
+        self,
+        value: &T,
+        markdown: impl FnOnce(&T) -> String,
+        digest: impl FnOnce(&T) -> String,
+    ) -> Result<CallToolResponse> {
+        Ok(CallToolResponse {
+            content: vec![ToolResponseContent::Text {
+                text: self.render(value, markdown, digest)?,
+            }],
+            is_error: None,
+            meta: None,
+        })
+    }
+}
+
+/// Schema of the `format` argument
+pub(crate) fn format_schema() -> serde_json::Value {
+    json!({
+        "type": "string",
+        "enum": ["json", "markdown", "digest"],
+        "description": "Render the response as JSON, as Markdown with headings, signatures in code fences and the doc text, which is more compact to read, or as a digest of one `path | kind | signature | summary` line per item, the most compact (defaults to json)"
+    })
+}
+
+/// A line of a digest: the columns on one line each, separated by ` | `, with `|` inside them
+/// escaped, the summary cut to its first sentence, empty trailing columns left out and other
+/// empty ones shown as `-`
+pub(crate) fn digest_line(path: &str, kind: &str, signature: &str, summary: &str) -> String {
+    let summary = first_sentence(summary);
+    let mut columns: Vec<String> = [path, kind, signature, &summary]
+        .iter()
+        .map(|column| {
+            column
+                .split_whitespace()
+                .collect::<Vec<_>>()
+                .join(" ")
+                .replace('|', "\\|")
+        })
+        .collect();
+    while columns.last().is_some_and(String::is_empty) {
+        columns.pop();
+    }
+    columns
+        .iter()
+        .map(|column| if column.is_empty() { "-" } else { column })
+        .collect::<Vec<_>>()
+        .join(" | ")
+}
+
+/// The first sentence of `text`, or its first paragraph when that comes sooner, on one line
+pub(crate) fn first_sentence(text: &str) -> String {
+    let paragraph = text.trim().split("\n\n").next().unwrap_or_default();
+    let paragraph = paragraph.split_whitespace().collect::<Vec<_>>().join(" ");
+    match paragraph.find(". ") {
+        Some(end) => paragraph[..=end].to_string(),
+        None => paragraph,
+    }
+}
+
+/// `code` in a fenced Rust block, with a fence longer than any run of backticks inside it
+pub(crate) fn code_block(code: &str) -> String {
+    let longest = code
+        .split(|c| c != '`')
+        .map(str::len)
+        .max()
+        .unwrap_or_default();
+    let fence = "`".repeat(longest.max(2) + 1);
+    format!("{}rust\n{}\n{}", fence, code.trim(), fence)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_code_block() {
+        assert_eq!(
+            code_block("pub fn len(&self) -> usize\n"),
+            "```rust\npub fn len(&self) -> usize\n```"
+        );
+        assert_eq!(
+            code_block("/// ```\n/// demo();\n/// ```\nfn demo()"),
+            "````rust\n/// ```\n/// demo();\n/// ```\nfn demo()\n````",
+            "Fences inside the code don't close the block"
+        );
+    }
+
+    #[test]
+    fn test_digest_line() {
+        assert_eq!(
+            digest_line(
+                "demo::Parser::parse",
+                "method",
+                "pub fn parse(\n    &self,\n    input: &str,\n) -> Result<Ast, Error>",
+                "Parses `input`. Errors on the first unexpected token.\n\nMore on errors."
+            ),
+            "demo::Parser::parse | method | pub fn parse( &self, input: &str, ) -> Result<Ast, Error> | Parses `input`."
+        );
+        assert_eq!(
+            digest_line("demo::Or", "struct", "", "Either `a || b`"),
+            "demo::Or | struct | - | Either `a \\|\\| b`",
+            "Pipes inside a column don't split it"
+        );
+        assert_eq!(
+            digest_line("demo::Parser", "struct", "", ""),
+            "demo::Parser | struct"
+        );
+    }
+
+    #[test]
+    fn test_response() -> Result<()> {
+        let value = json!({ "name": "Mutex" });
+        let render = |value: &serde_json::Value| format!("# {}", value["name"].as_str().unwrap());
+        let digest = |value: &serde_json::Value| value["name"].as_str().unwrap().to_string();
+
+        let ToolResponseContent::Text { text } =
+            &Format::Markdown.response(&value, render, digest)?.content[0]
+        else {
+            panic!("Expected a text response");
+        };
+        assert_eq!(text, "# Mutex");
+
+        let ToolResponseContent::Text { text } =
+            &Format::Digest.response(&value, render, digest)?.content[0]
+        else {
+            panic!("Expected a text response");
+        };
+        assert_eq!(text, "Mutex");
+
+        let ToolResponseContent::Text { text } =
+            &Format::Json.response(&value, render, digest)?.content[0]
+        else {
+            panic!("Expected a text response");
+        };
+        assert_eq!(serde_json::from_str::<serde_json::Value>(text)?, value);
+
+        assert_eq!(
+            serde_json::from_value::<Format>(json!("markdown"))?,
+            Format::Markdown
+        );
+        Ok(())
+    }
+}
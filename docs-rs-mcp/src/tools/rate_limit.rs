@@ -0,0 +1,152 @@
+//! Per-host token buckets keeping bulk operations, such as `crate_items` on huge crates or
+//! batch lookups, within the request rates docs.rs and crates.io ask crawlers to respect.
+//!
+//! Each host may take `burst` requests at once, refilled at `requests_per_second`. Requests
+//! beyond that aren't rejected but queued: each one reserves the next free slot and waits for
+//! it, so they go out in the order they arrived.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use url::Url;
+
+use crate::config::HttpConfig;
+
+#[derive(Debug)]
+struct TokenBucket {
+    /// Tokens available, negative while requests are queued for future slots
+    tokens: f64,
+    updated: Instant,
+}
+
+impl TokenBucket {
+    fn new(burst: f64, now: Instant) -> Self {
+        Self {
+            tokens: burst,
+            updated: now,
+        }
+    }
+
+    /// Takes a token, returning how long to wait until it is available.
+    fn reserve(&mut self, rate: f64, burst: f64, now: Instant) -> Duration {
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * rate).min(burst);
+        self.updated = now;
+        self.tokens -= 1.0;
+        match self.tokens < 0.0 {
+            true => Duration::from_secs_f64(-self.tokens / rate),
+            false => Duration::ZERO,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct RateLimiter {
+    /// Tokens added per second, `None` when requests aren't limited
+    rate: Option<f64>,
+    burst: f64,
+    buckets: Mutex<HashMap<String, TokenBucket>>,
+}
+
+impl RateLimiter {
+    pub(crate) fn new(settings: &HttpConfig) -> Self {
+        let rate = settings.requests_per_second();
+        Self {
+            rate: (rate > 0.0 && rate.is_finite()).then_some(rate),
+            burst: f64::from(settings.burst().max(1)),
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Reserves a request to the host of `url`, returning how long to wait before sending it.
+    fn reserve(&self, url: &str, now: Instant) -> Duration {
+        let Some(rate) = self.rate else {
+            return Duration::ZERO;
+        };
+        let host = Url::parse(url)
+            .ok()
+            .and_then(|url| url.host_str().map(str::to_string))
+            .unwrap_or_default();
+        let mut buckets = match self.buckets.lock() {
+            Ok(buckets) => buckets,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        buckets
+            .entry(host)
+            .or_insert_with(|| TokenBucket::new(self.burst, now))
+            .reserve(rate, self.burst, now)
+    }
+
+    /// Waits until a request to the host of `url` may be sent.
+    pub(crate) async fn acquire(&self, url: &str) {
+        let wait = self.reserve(url, Instant::now());
+        if !wait.is_zero() {
+            tracing::debug!("Rate limited, waiting {:?} to fetch {}", wait, url);
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use anyhow::Result;
+    use pretty_assertions::assert_eq;
+
+    fn limiter(toml: &str) -> Result<RateLimiter> {
+        Ok(RateLimiter::new(Config::from_toml(toml)?.http()))
+    }
+
+    #[test]
+    fn test_queues_beyond_burst() -> Result<()> {
+        let limiter = limiter("[http]\nrequests_per_second = 2.0\nburst = 2\n")?;
+        let start = Instant::now();
+        let waits: Vec<Duration> = (0..4)
+            .map(|_| limiter.reserve("https://docs.rs/tokio", start))
+            .collect();
+        assert_eq!(
+            waits,
+            [0, 0, 500, 1000].map(Duration::from_millis),
+            "Excess requests should wait for successive slots"
+        );
+
+        assert_eq!(
+            limiter.reserve("https://crates.io/api/v1/crates/tokio", start),
+            Duration::ZERO,
+            "Hosts should have separate buckets"
+        );
+        // The queued requests used up the tokens refilled in the meantime
+        assert_eq!(
+            limiter.reserve("https://docs.rs/serde", start + Duration::from_secs(1)),
+            Duration::from_millis(500)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_refills_up_to_burst() -> Result<()> {
+        let limiter = limiter("[http]\nrequests_per_second = 1.0\nburst = 1\n")?;
+        let start = Instant::now();
+        assert_eq!(limiter.reserve("https://docs.rs/a", start), Duration::ZERO);
+        let later = start + Duration::from_secs(60);
+        assert_eq!(limiter.reserve("https://docs.rs/b", later), Duration::ZERO);
+        assert_eq!(
+            limiter.reserve("https://docs.rs/c", later),
+            Duration::from_secs(1),
+            "Idle time shouldn't accumulate beyond the burst"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_unlimited() -> Result<()> {
+        let limiter = limiter("[http]\nrequests_per_second = 0.0\n")?;
+        let now = Instant::now();
+        for _ in 0..100 {
+            assert_eq!(limiter.reserve("https://docs.rs/", now), Duration::ZERO);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,333 @@
+use std::future::Future;
+
+use anyhow::{bail, Result};
+use serde_json::json;
+use tracing::debug;
+
+use super::crates_io;
+#[cfg(feature = "native")]
+use super::runtime;
+use crate::config::{self, Config};
+use crate::error::DocsRsMcpError;
+
+/// Schema of the `registry` parameter shared by every tool that looks up a crate.
+pub(crate) fn registry_schema() -> serde_json::Value {
+    json!({
+        "type": "string",
+        "description": "Optional registry to look the crate up in (defaults to crates-io). \
+            Required when the crate name exists in more than one configured registry"
+    })
+}
+
+/// Adds the `docs_base_url` parameter to the schema of tools that take a `registry`, i.e. those
+/// looking crates up.
+pub(crate) fn with_docs_base_url_schema(mut schema: serde_json::Value) -> serde_json::Value {
+    if let Some(properties) = schema["properties"].as_object_mut() {
+        if properties.contains_key("registry") {
+            properties.insert(
+                "docs_base_url".to_string(),
+                json!({
+                    "type": "string",
+                    "description": "Optional base URL of a docs.rs-compatible host to fetch \
+                        documentation from for this call only, e.g. a mirror. Refused unless \
+                        the server sets sources.allow_docs_base_url"
+                }),
+            );
+        }
+    }
+    schema
+}
+
+/// Calls `f` with `input`, fetching documentation from its `docs_base_url` argument if any.
+pub(crate) fn with_docs_base_url<T>(
+    input: Option<serde_json::Value>,
+    f: impl FnOnce(Option<serde_json::Value>) -> Result<T>,
+) -> Result<T> {
+    let url = match input.as_ref().map(|input| &input["docs_base_url"]) {
+        None | Some(serde_json::Value::Null) => return f(input),
+        Some(serde_json::Value::String(url)) => url,
+        Some(other) => {
+            return Err(DocsRsMcpError::InvalidInput(format!(
+                "docs_base_url must be a string, got {}",
+                other
+            ))
+            .into())
+        }
+    };
+    let config = config::current().with_docs_url(url)?;
+    config::scoped(config, || f(input))
+}
+
+/// Runs `f` with the configuration pointed at the registry `crate_name` should be looked up in.
+#[cfg(feature = "native")]
+pub(crate) fn in_registry<T>(
+    crate_name: &str,
+    requested: Option<&str>,
+    f: impl FnOnce() -> Result<T>,
+) -> Result<T> {
+    let config = resolve(&config::current(), crate_name, requested, |config| {
+        runtime::block_on(crate_exists(config, crate_name))
+    })?;
+    config::scoped(config, f)
+}
+
+/// Awaits `lookup` with the configuration pointed at the registry `crate_name` should be looked
+/// up in, the async counterpart of [`in_registry`].
+pub(crate) async fn in_registry_async<T>(
+    crate_name: &str,
+    requested: Option<&str>,
+    lookup: impl Future<Output = Result<T>>,
+) -> Result<T> {
+    let current = config::current();
+    // The registries are asked up front, as `resolve` checks them synchronously
+    let mut found = Vec::new();
+    if requested.is_none()
+        && current.routed_registry(crate_name).is_none()
+        && !current.registries().is_empty()
+    {
+        for name in current.registry_names() {
+            if crate_exists(&current.for_registry(name)?, crate_name).await? {
+                found.push(name);
+            }
+        }
+    }
+    let config = resolve(&current, crate_name, requested, |config| {
+        Ok(found.contains(&config.active_registry()))
+    })?;
+    config::scoped_task(config, lookup).await
+}
+
+/// Runs `f` against the `requested` registry, or the default one, for lookups that aren't about a
+/// single crate, such as listing the crates of a category.
+pub(crate) fn in_named_registry<T>(
+    requested: Option<&str>,
+    f: impl FnOnce() -> Result<T>,
+) -> Result<T> {
+    let config = match requested {
+        Some(name) => config::current().for_registry(name)?,
+        None => config::current().as_ref().clone(),
+    };
+    config::scoped(config, f)
+}
+
+/// Picks the registry for `crate_name`. An explicit `requested` registry always wins, then a
+/// registry whose `crates` patterns claim the crate, so internal crate names are never sent to
+/// crates.io. Otherwise every configured registry is asked for the crate so that two different
+/// crates sharing a name are never mixed up silently.
+fn resolve(
+    config: &Config,
+    crate_name: &str,
+    requested: Option<&str>,
+    exists: impl Fn(&Config) -> Result<bool>,
+) -> Result<Config> {
+    if let Some(name) = requested {
+        return config.for_registry(name);
+    }
+    if let Some(name) = config.routed_registry(crate_name) {
+        return config.for_registry(name);
+    }
+    if config.registries().is_empty() {
+        return Ok(config.clone());
+    }
+
+    let mut candidates = Vec::new();
+    for name in config.registry_names() {
+        let registry = config.for_registry(name)?;
+        if exists(&registry)? {
+            candidates.push(registry);
+        }
+    }
+
+    match candidates.len() {
+        // Let the tool report the missing crate against the default registry
+        0 => Ok(config.clone()),
+        1 => Ok(candidates.remove(0)),
+        _ => bail!(
+            "Crate {} exists in several registries ({}); pass the `registry` parameter to choose one",
+            crate_name,
+            candidates
+                .iter()
+                .map(Config::active_registry)
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    }
+}
+
+/// Whether the registry of `config` has `crate_name`. Only Not Found means it hasn't: a
+/// registry that can't be asked fails the lookup rather than passing for one without the crate.
+async fn crate_exists(config: &Config, crate_name: &str) -> Result<bool> {
+    let url = format!(
+        "{}/api/v1/crates/{}",
+        config.sources().crates_io_url(),
+        crate_name
+    );
+    let exists = match config::scoped_task(config.clone(), crates_io::fetch(&url)).await {
+        Ok(_) => true,
+        Err(e) if matches!(DocsRsMcpError::classify(&e), DocsRsMcpError::NotFound(_)) => false,
+        Err(e) => {
+            return Err(e.context(format!(
+                "Failed to ask registry {} for {}",
+                config.active_registry(),
+                crate_name
+            )))
+        }
+    };
+    debug!(
+        "{} {} in registry {}",
+        crate_name,
+        if exists { "found" } else { "not found" },
+        config.active_registry()
+    );
+    Ok(exists)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config_with_acme() -> Result<Config> {
+        Config::from_toml(
+            r#"
+            [[registries]]
+            name = "acme"
+            api_url = "https://registry.acme.internal"
+            docs_url = "https://docs.acme.internal"
+            "#,
+        )
+    }
+
+    #[test]
+    fn test_name_collision_requires_registry() -> Result<()> {
+        let config = config_with_acme()?;
+
+        let result = resolve(&config, "utils", None, |_| Ok(true));
+        assert!(
+            result.is_err(),
+            "A crate found in two registries should be ambiguous"
+        );
+
+        let resolved = resolve(&config, "utils", Some("acme"), |_| Ok(true))?;
+        assert_eq!(resolved.active_registry(), "acme");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_unique_crate_picks_its_registry() -> Result<()> {
+        let config = config_with_acme()?;
+
+        let resolved = resolve(&config, "acme-billing", None, |registry| {
+            Ok(registry.active_registry() == "acme")
+        })?;
+        assert_eq!(resolved.active_registry(), "acme");
+        assert_eq!(
+            resolved.sources().docs_rs_url(),
+            "https://docs.acme.internal"
+        );
+
+        let resolved = resolve(&Config::default(), "serde", None, |_| {
+            panic!("Registries shouldn't be probed when only crates.io is configured")
+        })?;
+        assert_eq!(resolved.active_registry(), "crates-io");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_routed_crate_skips_probing() -> Result<()> {
+        let config = Config::from_toml(
+            r#"
+            [[registries]]
+            name = "acme"
+            api_url = "https://registry.acme.internal"
+            docs_url = "https://docs.acme.internal"
+            crates = ["acme-*"]
+            "#,
+        )?;
+
+        let resolved = resolve(&config, "acme-billing", None, |_| {
+            panic!("Routed crates shouldn't be looked up in every registry")
+        })?;
+        assert_eq!(resolved.active_registry(), "acme");
+
+        let resolved = resolve(&config, "acme-billing", Some("crates-io"), |_| Ok(true))?;
+        assert_eq!(
+            resolved.active_registry(),
+            "crates-io",
+            "An explicit registry wins over routing"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_docs_base_url() -> Result<()> {
+        let input = json!({ "crate_name": "serde", "docs_base_url": "https://mirror.example" });
+        assert!(
+            with_docs_base_url(Some(input.clone()), |_| Ok(())).is_err(),
+            "Off unless enabled"
+        );
+
+        let allowed = Config::from_toml("[sources]\nallow_docs_base_url = true\n")?;
+        config::scoped(allowed, || {
+            let url = with_docs_base_url(Some(input), |_| {
+                Ok(config::current().sources().docs_rs_url().to_string())
+            })?;
+            assert_eq!(url, "https://mirror.example");
+
+            for invalid in [json!("ftp://mirror.example"), json!("not a url"), json!(7)] {
+                let input = json!({ "docs_base_url": invalid.clone() });
+                assert!(
+                    with_docs_base_url(Some(input), |_| Ok(())).is_err(),
+                    "{} should be rejected",
+                    invalid
+                );
+            }
+            anyhow::Ok(())
+        })?;
+        assert_eq!(with_docs_base_url(None, |_| Ok(1))?, 1);
+
+        let schema = with_docs_base_url_schema(json!({
+            "type": "object",
+            "properties": { "crate_name": {}, "registry": registry_schema() }
+        }));
+        assert!(schema["properties"]["docs_base_url"].is_object());
+        let schema = with_docs_base_url_schema(json!({ "type": "object", "properties": {} }));
+        assert!(schema["properties"].get("docs_base_url").is_none());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_only_not_found_means_absent() -> Result<()> {
+        use wiremock::matchers::path;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        for (name, status) in [
+            ("serde", 200),
+            ("missing", 404),
+            ("busy", 503),
+            ("secret", 401),
+        ] {
+            Mock::given(path(format!("/api/v1/crates/{}", name)))
+                .respond_with(ResponseTemplate::new(status).set_body_string("{}"))
+                .mount(&server)
+                .await;
+        }
+        let config = Config::from_toml(&format!(
+            "[sources]\ncrates_io_url = \"{}\"\n",
+            server.uri()
+        ))?;
+
+        assert!(crate_exists(&config, "serde").await?);
+        assert!(!crate_exists(&config, "missing").await?);
+        for name in ["busy", "secret"] {
+            assert!(
+                crate_exists(&config, name).await.is_err(),
+                "{} should fail the lookup",
+                name
+            );
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,75 @@
+//! Bridge from the synchronous parts of the server to its async I/O. The MCP SDK calls tools
+//! synchronously, so a tool driving an async lookup blocks its worker thread until the lookup
+//! is done, handing the worker's other tasks to another thread meanwhile.
+//!
+//! A current-thread runtime has no other thread to hand them to, so there a lookup fails with
+//! an error instead of blocking the whole runtime or panicking.
+
+use std::future::Future;
+
+use anyhow::{bail, Context, Result};
+use tokio::runtime::{Builder, Handle, RuntimeFlavor};
+
+/// Runs `future` to completion from synchronous code. Inside a multi-threaded tokio runtime the
+/// future runs on the calling thread; outside of one, e.g. in unit tests, on a runtime made for
+/// the purpose.
+pub(crate) fn block_on<T>(future: impl Future<Output = Result<T>>) -> Result<T> {
+    match Handle::try_current() {
+        Ok(handle) => {
+            ensure_multi_thread(&handle)?;
+            tokio::task::block_in_place(|| handle.block_on(future))
+        }
+        Err(_) => Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .context("Failed to start a runtime")?
+            .block_on(future),
+    }
+}
+
+/// Runs blocking code, such as a lookup driving several requests, from synchronous code that
+/// may be running on a worker thread of a multi-threaded tokio runtime.
+pub(crate) fn blocking<T>(f: impl FnOnce() -> Result<T>) -> Result<T> {
+    match Handle::try_current() {
+        Ok(handle) => {
+            ensure_multi_thread(&handle)?;
+            tokio::task::block_in_place(f)
+        }
+        Err(_) => f(),
+    }
+}
+
+fn ensure_multi_thread(handle: &Handle) -> Result<()> {
+    match handle.runtime_flavor() {
+        RuntimeFlavor::MultiThread => Ok(()),
+        flavor => bail!(
+            "Lookups block their thread, which needs tokio's multi-threaded runtime, not {:?}",
+            flavor
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_outside_a_runtime() -> Result<()> {
+        assert_eq!(block_on(async { Ok(1) })?, 1);
+        assert_eq!(blocking(|| Ok(2))?, 2);
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_multi_thread() -> Result<()> {
+        assert_eq!(block_on(async { Ok(1) })?, 1);
+        assert_eq!(blocking(|| Ok(2))?, 2);
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn test_current_thread_is_an_error() {
+        assert!(block_on(async { Ok(1) }).is_err());
+        assert!(blocking(|| Ok(2)).is_err());
+    }
+}
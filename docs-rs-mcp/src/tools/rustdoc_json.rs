@@ -0,0 +1,262 @@
+//! Rustdoc JSON, which docs.rs serves at `/crate/{name}/{version}/json` for crates built since
+//! it started generating it. Reading items from it doesn't depend on the markup of the HTML
+//! pages, so tools prefer it and scrape the pages only when a release has no JSON.
+//!
+//! Only the parts of the format the tools read are deserialized, mirroring
+//! `rustdoc_types::Crate`; they have been stable across format versions, unlike the
+//! kind-specific item details, which are skipped.
+
+use std::collections::HashMap;
+use std::io::Read;
+
+use anyhow::{Context, Result};
+use flate2::read::GzDecoder;
+use serde::Deserialize;
+use tracing::debug;
+
+use super::http;
+use crate::cache_key::{fnv1a, DerivedKey};
+use crate::config;
+
+/// The rustdoc JSON of a crate
+#[derive(Debug, Deserialize)]
+pub(crate) struct Crate {
+    /// Documented paths of the items, by item ID
+    paths: HashMap<String, ItemSummary>,
+    /// The documented release, e.g. `1.43.0`
+    #[serde(default)]
+    crate_version: Option<String>,
+    format_version: u32,
+    /// Where the JSON was read from
+    #[serde(skip)]
+    source_url: String,
+    #[serde(skip)]
+    content_hash: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct ItemSummary {
+    /// 0 for the crate the JSON documents, others for the crates it links to
+    crate_id: u32,
+    /// Path the item is documented at, starting with the crate's library name
+    path: Vec<String>,
+    /// e.g. `struct`, `function` or `proc_derive`
+    kind: String,
+}
+
+/// A public item of the documented crate
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct LocalItem<'a> {
+    /// Path below the crate root, e.g. `["sync", "Mutex"]`
+    path: &'a [String],
+    kind: &'a str,
+}
+
+impl<'a> LocalItem<'a> {
+    pub(crate) fn kind(&self) -> &'a str {
+        self.kind
+    }
+
+    /// Path below the crate root as written in Rust, e.g. `sync::Mutex`
+    pub(crate) fn name(&self) -> String {
+        self.path.join("::")
+    }
+
+    /// Path of the item's page below the crate root, e.g. `sync/struct.Mutex.html`. `None`
+    /// for modules and the details of other items, such as fields and methods, which have no
+    /// page of their own.
+    pub(crate) fn page(&self) -> Option<String> {
+        let (name, modules) = self.path.split_last()?;
+        let file = format!("{}.{}.html", page_prefix(self.kind)?, name);
+        let mut segments: Vec<&str> = modules.iter().map(String::as_str).collect();
+        segments.push(&file);
+        Some(segments.join("/"))
+    }
+}
+
+impl Crate {
+    pub(crate) fn format_version(&self) -> u32 {
+        self.format_version
+    }
+
+    pub(crate) fn crate_version(&self) -> Option<&str> {
+        self.crate_version.as_deref()
+    }
+
+    /// Records where the JSON was read from, for [`Self::key`].
+    pub(crate) fn with_source(self, url: &str) -> Self {
+        Self {
+            source_url: url.to_string(),
+            ..self
+        }
+    }
+
+    /// The key of data derived from this JSON, built for docs.rs' default target
+    pub(crate) fn key(&self) -> DerivedKey {
+        DerivedKey::with_hash(&self.source_url, self.content_hash, None)
+    }
+
+    /// Public items of the documented crate, other than its root module
+    pub(crate) fn local_items(&self) -> impl Iterator<Item = LocalItem<'_>> {
+        self.paths
+            .values()
+            .filter(|summary| summary.crate_id == 0 && summary.path.len() > 1)
+            .map(|summary| LocalItem {
+                path: &summary.path[1..],
+                kind: &summary.kind,
+            })
+    }
+}
+
+/// Prefix rustdoc gives the page of an item of `kind`, e.g. `fn` for `function`. `typedef` is
+/// what format versions before 24 call type aliases.
+fn page_prefix(kind: &str) -> Option<&'static str> {
+    let prefix = match kind {
+        "struct" => "struct",
+        "enum" => "enum",
+        "union" => "union",
+        "trait" => "trait",
+        "trait_alias" => "traitalias",
+        "function" => "fn",
+        "type_alias" | "typedef" => "type",
+        "constant" => "constant",
+        "static" => "static",
+        "macro" => "macro",
+        "proc_attribute" => "attr",
+        "proc_derive" => "derive",
+        _ => return None,
+    };
+    Some(prefix)
+}
+
+/// Fetches the rustdoc JSON of a crate. `None` when docs.rs has none for the release, e.g.
+/// because it was built before docs.rs generated JSON.
+pub(crate) async fn fetch(crate_name: &str, version: &str) -> Result<Option<Crate>> {
+    let url = format!(
+        "{}/crate/{}/{}/json.gz",
+        config::current().sources().docs_rs_url(),
+        crate_name,
+        version
+    );
+    debug!("Fetching rustdoc JSON: {}", url);
+    let response = http::shared()?
+        .get(&url)
+        .await
+        .context(format!("Failed to fetch URL: {}", url))?;
+    if !response.status().is_success() {
+        debug!(
+            "No rustdoc JSON for {} {}: {}",
+            crate_name,
+            version,
+            response.status()
+        );
+        return Ok(None);
+    }
+    let compressed = response
+        .bytes()
+        .await
+        .context(format!("Failed to read the rustdoc JSON from {}", url))?;
+    let json = parse_gz(&compressed)
+        .context(format!("Failed to parse the rustdoc JSON from {}", url))?
+        .with_source(&url);
+    debug!(
+        "Read rustdoc JSON of {} {} (format version {})",
+        crate_name,
+        version,
+        json.format_version()
+    );
+    Ok(Some(json))
+}
+
+/// Like [`fetch`], but `None` as well when the JSON couldn't be fetched or read, so the caller
+/// scrapes the HTML pages instead.
+pub(crate) async fn fetch_or_fallback(crate_name: &str, version: &str) -> Option<Crate> {
+    fetch(crate_name, version).await.unwrap_or_else(|e| {
+        debug!(
+            "Falling back to the HTML pages of {} {}: {:#}",
+            crate_name, version, e
+        );
+        None
+    })
+}
+
+/// Decompresses and parses gzipped rustdoc JSON.
+fn parse_gz(compressed: &[u8]) -> Result<Crate> {
+    let mut json = String::new();
+    GzDecoder::new(compressed)
+        .read_to_string(&mut json)
+        .context("Failed to decompress the rustdoc JSON")?;
+    parse(&json)
+}
+
+pub(crate) fn parse(json: &str) -> Result<Crate> {
+    Ok(Crate {
+        content_hash: fnv1a(json.as_bytes()),
+        ..serde_json::from_str(json)?
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::{write::GzEncoder, Compression};
+    use pretty_assertions::assert_eq;
+    use std::io::Write;
+
+    fn load_demo() -> Crate {
+        let json = std::fs::read("test-data/rustdoc_json/demo.json")
+            .expect("Should be able to read test JSON file");
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&json).unwrap();
+        parse_gz(&encoder.finish().unwrap()).expect("The JSON should parse")
+    }
+
+    #[test]
+    fn test_local_items() {
+        let json = load_demo();
+        assert_eq!(json.format_version(), 39);
+        assert_eq!(json.crate_version(), Some("0.4.2"));
+
+        let mut items: Vec<(String, &str, Option<String>)> = json
+            .local_items()
+            .map(|item| (item.name(), item.kind(), item.page()))
+            .collect();
+        items.sort();
+        assert_eq!(
+            items,
+            vec![
+                (
+                    "Error".to_string(),
+                    "enum",
+                    Some("enum.Error.html".to_string())
+                ),
+                (
+                    "Serialize".to_string(),
+                    "proc_derive",
+                    Some("derive.Serialize.html".to_string())
+                ),
+                ("codec".to_string(), "module", None),
+                (
+                    "codec::Decoder".to_string(),
+                    "trait",
+                    Some("codec/trait.Decoder.html".to_string())
+                ),
+                (
+                    "codec::Frame".to_string(),
+                    "struct",
+                    Some("codec/struct.Frame.html".to_string())
+                ),
+                (
+                    "codec::Result".to_string(),
+                    "type_alias",
+                    Some("codec/type.Result.html".to_string())
+                ),
+                (
+                    "decode".to_string(),
+                    "function",
+                    Some("fn.decode.html".to_string())
+                ),
+            ]
+        );
+    }
+}
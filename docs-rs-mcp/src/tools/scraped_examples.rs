@@ -0,0 +1,199 @@
+//! Reading the "Examples found in repository" sections rustdoc renders under functions and
+//! methods of crates documented with `--scrape-examples`: call sites taken from the crate's own
+//! `examples/`, which show real usage better than a made-up snippet.
+
+use scraper::{ElementRef, Node};
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use super::selectors;
+
+/// The list of examples, relative to the toggle block of a function or method
+const LIST: &str = ".scraped-example-list";
+
+/// Lines kept on each side of the call, as rustdoc shows the whole enclosing function
+const CONTEXT_LINES: usize = 8;
+
+/// A call site rustdoc found in the crate's examples
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UsageExample {
+    /// File of the call, e.g. `examples/server.rs`
+    file: String,
+    /// Link to the call in the crate's rendered source
+    source_url: Option<String>,
+    /// The code around the call; missing for the examples rustdoc only links to
+    code: Option<String>,
+}
+
+impl UsageExample {
+    pub fn file(&self) -> &str {
+        &self.file
+    }
+
+    pub fn source_url(&self) -> Option<&str> {
+        self.source_url.as_deref()
+    }
+
+    pub fn code(&self) -> Option<&str> {
+        self.code.as_deref()
+    }
+}
+
+/// The examples listed within `scope`, with links resolved against `page_url`: those shown
+/// with their code first, then those rustdoc only links to.
+pub(crate) fn parse(scope: ElementRef, page_url: &str) -> Vec<UsageExample> {
+    let (Ok(list), Ok(example), Ok(title), Ok(title_link), Ok(code), Ok(more_links)) = (
+        selectors::parse(LIST, "item page"),
+        selectors::parse(".scraped-example", "item page"),
+        selectors::parse(".scraped-example-title", "item page"),
+        selectors::parse(".scraped-example-title a[href]", "item page"),
+        selectors::parse("pre.rust", "item page"),
+        selectors::parse(".example-links a[href]", "item page"),
+    ) else {
+        return Vec::new();
+    };
+    let Some(list) = scope.select(&list).next() else {
+        return Vec::new();
+    };
+    let page = Url::parse(page_url).ok();
+    let resolve = |link: ElementRef| {
+        let href = link.value().attr("href")?;
+        match &page {
+            Some(page) => page.join(href).ok().map(String::from),
+            None => Some(href.to_string()),
+        }
+    };
+
+    let shown = list.select(&example).filter_map(|example| {
+        // "examples/server.rs (line 22)"
+        let title = example.select(&title).next()?.text().collect::<String>();
+        let file = title.split(" (").next().unwrap_or_default().trim();
+        Some(UsageExample {
+            file: file.to_string(),
+            source_url: example.select(&title_link).next().and_then(resolve),
+            code: example.select(&code).next().map(call_site),
+        })
+    });
+    let linked = list.select(&more_links).map(|link| UsageExample {
+        file: link.text().collect::<String>().trim().to_string(),
+        source_url: resolve(link),
+        code: None,
+    });
+    shown
+        .chain(linked)
+        .filter(|example| !example.file.is_empty())
+        .collect()
+}
+
+/// The lines of `code` around the call rustdoc highlights, or all of them when none is.
+fn call_site(code: ElementRef) -> String {
+    let mut text = String::new();
+    let mut focus_line = None;
+    for node in code.descendants() {
+        match node.value() {
+            Node::Text(chunk) => text.push_str(chunk),
+            Node::Element(element)
+                if focus_line.is_none() && element.classes().any(|class| class == "focus") =>
+            {
+                focus_line = Some(text.matches('\n').count());
+            }
+            _ => {}
+        }
+    }
+
+    let lines: Vec<&str> = text.trim_end().lines().collect();
+    let (start, end) = match focus_line {
+        Some(line) => (
+            line.saturating_sub(CONTEXT_LINES),
+            (line + CONTEXT_LINES + 1).min(lines.len()),
+        ),
+        None => (0, lines.len()),
+    };
+    lines[start.min(end)..end].join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use scraper::Html;
+
+    const PAGE: &str = "https://docs.rs/demo/1.0.0/demo/struct.Server.html";
+
+    fn method_html(body: &str) -> String {
+        format!(
+            r#"<details class="toggle method-toggle" open>
+                <summary><section id="method.bind" class="method">
+                    <h4 class="code-header">pub fn bind(addr: &amp;str) -&gt; Server</h4>
+                </section></summary>
+                <div class="docblock"><p>Binds the server.</p></div>
+                {}
+            </details>"#,
+            body
+        )
+    }
+
+    #[test]
+    fn test_parse() {
+        let filler: String = (0..20)
+            .map(|i| format!("    let x{} = {};\n", i, i))
+            .collect();
+        let html = method_html(&format!(
+            r##"<div class="docblock scraped-example-list"><span></span>
+                <h5 id="scraped-examples"><a href="#scraped-examples">Examples found in repository</a></h5>
+                <div class="scraped-example expanded" data-locs="[]">
+                    <div class="scraped-example-title">examples/hello.rs (<a href="../src/hello/hello.rs.html#30">line 30</a>)</div>
+                    <div class="code-wrapper"><div class="example-wrap">
+                        <pre class="src-line-numbers">1
+2</pre>
+                        <pre class="rust"><code>fn main() {{
+{filler}    let server = <span class="highlight focus">Server::bind</span>("0.0.0.0:80");
+{filler}}}</code></pre>
+                    </div></div>
+                </div>
+                <details class="toggle more-examples-toggle">
+                    <summary class="hideme"><span>More examples</span></summary>
+                    <div class="example-links">Additional examples can be found in:<br>
+                        <ul><li><a href="../src/tls/tls.rs.html#12">examples/tls.rs</a></li></ul>
+                    </div>
+                </details>
+            </div>"##
+        ));
+        let document = Html::parse_fragment(&html);
+
+        let examples = parse(document.root_element(), PAGE);
+        assert_eq!(examples.len(), 2, "{:#?}", examples);
+        assert_eq!(examples[0].file(), "examples/hello.rs");
+        assert_eq!(
+            examples[0].source_url(),
+            Some("https://docs.rs/demo/1.0.0/src/hello/hello.rs.html#30")
+        );
+        let code = examples[0].code().expect("code");
+        assert!(
+            code.contains("let server = Server::bind(\"0.0.0.0:80\");"),
+            "{}",
+            code
+        );
+        assert_eq!(
+            code.lines().count(),
+            2 * CONTEXT_LINES + 1,
+            "Only the lines around the call are kept: {}",
+            code
+        );
+        assert!(!code.contains("fn main"));
+
+        assert_eq!(examples[1].file(), "examples/tls.rs");
+        assert_eq!(examples[1].code(), None);
+        assert_eq!(
+            examples[1].source_url(),
+            Some("https://docs.rs/demo/1.0.0/src/tls/tls.rs.html#12")
+        );
+    }
+
+    #[test]
+    fn test_parse_without_examples() {
+        let html = method_html("");
+        let document = Html::parse_fragment(&html);
+        assert!(parse(document.root_element(), PAGE).is_empty());
+    }
+}
@@ -0,0 +1,1117 @@
+use std::collections::{HashMap, HashSet};
+
+use anyhow::{anyhow, Context, Result};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use mcp_sdk::{
+    tools::Tool,
+    types::{CallToolResponse, ToolResponseContent},
+};
+use scraper::Html;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tracing::debug;
+use url::Url;
+
+use super::get_struct_docs::{HtmlFetcher, HttpHtmlFetcher};
+use super::near_matches::edit_distance;
+use super::{docs_root, registry, runtime, selectors};
+use crate::{config, error};
+
+const DEFAULT_LIMIT: usize = 20;
+
+/// Item kinds in the order rustdoc numbers them in the search index (`ItemType`), as used in
+/// page file names and anchors. 22 was `opaque` and is no longer emitted.
+const ITEM_KINDS: [&str; 26] = [
+    "keyword",
+    "primitive",
+    "mod",
+    "externcrate",
+    "import",
+    "struct",
+    "enum",
+    "fn",
+    "type",
+    "static",
+    "trait",
+    "impl",
+    "tymethod",
+    "method",
+    "structfield",
+    "variant",
+    "macro",
+    "associatedtype",
+    "constant",
+    "associatedconstant",
+    "union",
+    "foreigntype",
+    "opaque",
+    "attr",
+    "derive",
+    "traitalias",
+];
+
+/// An item of a crate as listed in its rustdoc search index
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SearchResult {
+    /// Full path of the item, e.g. `opentelemetry_sdk::trace::TracerProviderBuilder::build`
+    path: String,
+    name: String,
+    /// Rustdoc item kind, e.g. `struct`, `fn` or `method`
+    kind: String,
+    doc_link: String,
+    deprecated: bool,
+    /// Types the signature of a function or method mentions
+    #[serde(skip)]
+    signature: Option<Signature>,
+}
+
+impl SearchResult {
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn kind(&self) -> &str {
+        &self.kind
+    }
+
+    pub fn doc_link(&self) -> &str {
+        &self.doc_link
+    }
+
+    pub fn deprecated(&self) -> bool {
+        self.deprecated
+    }
+
+    pub fn signature(&self) -> Option<&Signature> {
+        self.signature.as_ref()
+    }
+}
+
+/// Types a function signature mentions, by full path where the search index records one, e.g.
+/// `core::result::Result`. Generics count along with the type they parametrize, and generic
+/// parameters are replaced by their bounds.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Signature {
+    /// Types of the parameters, including the receiver of methods
+    inputs: Vec<String>,
+    /// Types of the return value
+    output: Vec<String>,
+}
+
+impl Signature {
+    pub fn inputs(&self) -> &[String] {
+        &self.inputs
+    }
+
+    pub fn output(&self) -> &[String] {
+        &self.output
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct SearchInCrateParams {
+    crate_name: String,
+    query: String,
+    kind: Option<String>,
+    limit: Option<usize>,
+    version: Option<String>,
+    registry: Option<String>,
+}
+
+pub struct SearchInCrateTool {
+    html_fetcher: Box<dyn HtmlFetcher>,
+}
+
+impl SearchInCrateTool {
+    pub fn new() -> Self {
+        Self {
+            html_fetcher: Box::new(HttpHtmlFetcher::new()),
+        }
+    }
+
+    /// Downloads and parses the search index of a crate. Its file name carries a per-build
+    /// suffix, which is read from the crate's root page.
+    pub(crate) fn fetch_index(
+        &self,
+        crate_name: &str,
+        version: Option<&str>,
+    ) -> Result<Vec<SearchResult>> {
+        let root_url = docs_root::crate_root_blocking(crate_name, version.unwrap_or("latest"))?;
+        let crate_ident = docs_root::lib_name(&root_url).to_string();
+        let root_page = format!("{}index.html", root_url);
+        let html = self.html_fetcher.fetch_html_blocking(&root_page)?;
+        let index_url = search_index_url(&root_page, &html)?;
+        debug!("Fetching search index: {}", index_url);
+        let js = self.html_fetcher.fetch_html_blocking(&index_url)?;
+
+        let root_url = Url::parse(&index_url)?.join("./")?;
+        parse_search_index(&js, &crate_ident, root_url.as_str())
+    }
+}
+
+impl Default for SearchInCrateTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Location of `search-index<suffix>.js`, from the `rustdoc-vars` of a page of the crate.
+fn search_index_url(page_url: &str, html: &str) -> Result<String> {
+    let document = Html::parse_document(html);
+    let selector = selectors::parse("#rustdoc-vars, meta[name='rustdoc-vars']", "crate page")?;
+    let vars = document
+        .select(&selector)
+        .next()
+        .ok_or_else(|| anyhow!("Page has no rustdoc variables: {}", page_url))?;
+    let root_path = vars.value().attr("data-root-path").unwrap_or("./");
+    let suffix = vars
+        .value()
+        .attr("data-resource-suffix")
+        .unwrap_or_default();
+
+    let url = Url::parse(page_url)
+        .context(format!("Invalid page URL: {}", page_url))?
+        .join(&format!("{}search-index{}.js", root_path, suffix))?;
+    Ok(url.to_string())
+}
+
+/// Extracts the items of `crate_ident` from a `search-index.js` file. `root_url` is the
+/// directory the crate docs live in, e.g. `https://docs.rs/tokio/1.43.0/`.
+pub(crate) fn parse_search_index(
+    js: &str,
+    crate_ident: &str,
+    root_url: &str,
+) -> Result<Vec<SearchResult>> {
+    let json = json_string_literal(js)?;
+    let index: Value = serde_json::from_str(&json).context("Invalid search index JSON")?;
+
+    // Newer rustdoc emits `[[crate, data], ...]`, older versions `{crate: data, ...}`
+    let corpus = match &index {
+        Value::Array(entries) => entries
+            .iter()
+            .find(|entry| entry[0] == crate_ident)
+            .map(|entry| &entry[1]),
+        Value::Object(crates) => crates.get(crate_ident),
+        _ => None,
+    }
+    .ok_or_else(|| anyhow!("Search index has no entry for {}", crate_ident))?;
+
+    let kinds = corpus["t"]
+        .as_str()
+        .ok_or_else(|| anyhow!("Search index has no item kinds"))?;
+    let names = corpus["n"]
+        .as_array()
+        .ok_or_else(|| anyhow!("Search index has no item names"))?;
+    // Module paths are only listed where they change from the previous item
+    let mut module_paths = HashMap::new();
+    for entry in corpus["q"].as_array().into_iter().flatten() {
+        if let (Some(index), Some(path)) = (entry[0].as_u64(), entry[1].as_str()) {
+            module_paths.insert(index as usize, path);
+        }
+    }
+    let parents: Vec<(usize, &str)> = corpus["p"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|entry| {
+            (
+                entry[0].as_u64().unwrap_or_default() as usize,
+                entry[1].as_str().unwrap_or_default(),
+            )
+        })
+        .collect();
+    // The same paths serve as the types of function signatures. Their module path is looked up
+    // in `q` too, carrying over the previous one when the key isn't listed.
+    let mut last_module = None;
+    let type_paths: Vec<String> = corpus["p"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|entry| {
+            let name = entry[1].as_str().unwrap_or_default();
+            let module = match entry.get(2).and_then(Value::as_u64) {
+                Some(key) => {
+                    last_module = module_paths.get(&(key as usize)).copied().or(last_module);
+                    last_module
+                }
+                None => None,
+            };
+            match module {
+                Some(module) => format!("{}::{}", module, name),
+                None => name.to_string(),
+            }
+        })
+        .collect();
+    // Indices built before rustdoc encoded signatures have none
+    let mut functions = corpus["f"].as_str().map(SignatureDecoder::new);
+    // Older indices don't record deprecation
+    let deprecated = match corpus["c"].as_str() {
+        Some(encoded) => decode_roaring_bitmap(encoded).context("Invalid deprecated items")?,
+        None => HashSet::new(),
+    };
+    let mut parent_indices = match &corpus["i"] {
+        Value::String(encoded) => ParentIndices::Encoded(VlqHexDecoder::new(encoded)),
+        Value::Array(indices) => ParentIndices::Plain(indices.iter()),
+        _ => ParentIndices::Missing,
+    };
+
+    let mut results = Vec::new();
+    let mut module_path = crate_ident;
+    for (i, (kind_code, name)) in kinds.chars().zip(names).enumerate() {
+        if let Some(path) = module_paths.get(&i) {
+            module_path = path;
+        }
+        let parent = parent_indices
+            .next()?
+            .checked_sub(1)
+            .and_then(|index| parents.get(index));
+        let function = match functions.as_mut() {
+            Some(decoder) => decoder.next()?,
+            None => None,
+        };
+        let Some(kind) = ITEM_KINDS.get((kind_code as usize).wrapping_sub('A' as usize)) else {
+            continue;
+        };
+        let name = name.as_str().unwrap_or_default();
+        // The crate root, re-export statements and impl blocks aren't searchable items
+        let is_crate_root = i == 0 && *kind == "mod";
+        if is_crate_root || name.is_empty() || matches!(*kind, "externcrate" | "import" | "impl") {
+            continue;
+        }
+
+        let module_dir = module_path.replace("::", "/");
+        let (path, doc_link) = match parent.and_then(|(kind, parent)| {
+            ITEM_KINDS
+                .get(*kind)
+                .map(|parent_kind| (parent_kind, parent))
+        }) {
+            Some((parent_kind, parent)) => (
+                format!("{}::{}::{}", module_path, parent, name),
+                format!(
+                    "{}{}/{}.{}.html#{}.{}",
+                    root_url, module_dir, parent_kind, parent, kind, name
+                ),
+            ),
+            None if *kind == "mod" => (
+                format!("{}::{}", module_path, name),
+                format!("{}{}/{}/index.html", root_url, module_dir, name),
+            ),
+            None => (
+                format!("{}::{}", module_path, name),
+                format!("{}{}/{}.{}.html", root_url, module_dir, kind, name),
+            ),
+        };
+
+        results.push(SearchResult {
+            path,
+            name: name.to_string(),
+            kind: kind.to_string(),
+            doc_link,
+            deprecated: deprecated.contains(&i),
+            signature: function.map(|function| signature(&function, &type_paths)),
+        });
+    }
+
+    Ok(results)
+}
+
+/// Contents of the `JSON.parse('...')` string in a search index file, unescaped.
+fn json_string_literal(js: &str) -> Result<String> {
+    let start = js
+        .find("JSON.parse('")
+        .ok_or_else(|| anyhow!("Search index contains no JSON"))?
+        + "JSON.parse('".len();
+
+    let mut json = String::new();
+    let mut chars = js[start..].chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => return Ok(json),
+            '\\' => match chars.next() {
+                // Line continuation
+                Some('\n') => {}
+                Some(escaped) => json.push(escaped),
+                None => break,
+            },
+            c => json.push(c),
+        }
+    }
+    Err(anyhow!("Unterminated JSON string in search index"))
+}
+
+/// Decodes a base64 `RoaringBitmap` in its portable serialization, which rustdoc uses for the
+/// set of deprecated items (`c`), into the item indices it contains.
+fn decode_roaring_bitmap(encoded: &str) -> Result<HashSet<usize>> {
+    const SERIAL_COOKIE: u32 = 12347;
+    const SERIAL_COOKIE_NO_RUNCONTAINER: u32 = 12346;
+    // Container offsets are left out of bitmaps with run containers and fewer containers
+    const NO_OFFSET_THRESHOLD: usize = 4;
+
+    let bytes = STANDARD.decode(encoded.trim())?;
+    let u16_at = |offset: usize| -> Result<usize> {
+        bytes
+            .get(offset..offset + 2)
+            .map(|b| u16::from_le_bytes([b[0], b[1]]) as usize)
+            .ok_or_else(|| anyhow!("Truncated bitmap"))
+    };
+    let u32_at = |offset: usize| -> Result<u32> {
+        bytes
+            .get(offset..offset + 4)
+            .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+            .ok_or_else(|| anyhow!("Truncated bitmap"))
+    };
+
+    let cookie = u32_at(0)?;
+    let (size, run_flags, mut offset) = if cookie & 0xFFFF == SERIAL_COOKIE {
+        let size = (cookie >> 16) as usize + 1;
+        let flags_len = size.div_ceil(8);
+        let flags = bytes
+            .get(4..4 + flags_len)
+            .ok_or_else(|| anyhow!("Truncated bitmap"))?;
+        (size, Some(flags), 4 + flags_len)
+    } else if cookie == SERIAL_COOKIE_NO_RUNCONTAINER {
+        (u32_at(4)? as usize, None, 8)
+    } else {
+        return Err(anyhow!("Unknown bitmap format {}", cookie));
+    };
+
+    let mut headers = Vec::with_capacity(size);
+    for _ in 0..size {
+        // Key (high 16 bits of the values) and cardinality - 1
+        headers.push((u16_at(offset)?, u16_at(offset + 2)? + 1));
+        offset += 4;
+    }
+    if run_flags.is_none() || size >= NO_OFFSET_THRESHOLD {
+        offset += 4 * size;
+    }
+
+    let mut values = HashSet::new();
+    for (i, (key, cardinality)) in headers.into_iter().enumerate() {
+        let high = key << 16;
+        let is_run = run_flags.is_some_and(|flags| flags[i / 8] & (1 << (i % 8)) != 0);
+        if is_run {
+            let runs = u16_at(offset)?;
+            offset += 2;
+            for _ in 0..runs {
+                let (start, extra) = (u16_at(offset)?, u16_at(offset + 2)?);
+                values.extend((start..=start + extra).map(|low| high | low));
+                offset += 4;
+            }
+        } else if cardinality <= 4096 {
+            for _ in 0..cardinality {
+                values.insert(high | u16_at(offset)?);
+                offset += 2;
+            }
+        } else {
+            let words = bytes
+                .get(offset..offset + 8192)
+                .ok_or_else(|| anyhow!("Truncated bitmap"))?;
+            for (byte_index, byte) in words.iter().enumerate() {
+                for bit in 0..8 {
+                    if byte & (1 << bit) != 0 {
+                        values.insert(high | (byte_index * 8 + bit));
+                    }
+                }
+            }
+            offset += 8192;
+        }
+    }
+    Ok(values)
+}
+
+/// 1-based indices into the parent list (`p`), 0 meaning no parent
+enum ParentIndices<'a> {
+    Encoded(VlqHexDecoder<'a>),
+    Plain(std::slice::Iter<'a, Value>),
+    Missing,
+}
+
+impl ParentIndices<'_> {
+    fn next(&mut self) -> Result<usize> {
+        match self {
+            ParentIndices::Encoded(decoder) => decoder.next(),
+            ParentIndices::Plain(indices) => {
+                Ok(indices.next().and_then(Value::as_u64).unwrap_or_default() as usize)
+            }
+            ParentIndices::Missing => Ok(0),
+        }
+    }
+}
+
+/// Decoder for rustdoc's compact integer lists. Each number is written as hex digits, `@`-`O`
+/// for leading and `` ` ``-`o` for the final digit, with the sign in the lowest bit. `0`-`?`
+/// repeat one of the last 16 distinct non-zero values.
+struct VlqHexDecoder<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+    backrefs: Vec<usize>,
+}
+
+impl<'a> VlqHexDecoder<'a> {
+    fn new(encoded: &'a str) -> Self {
+        Self {
+            bytes: encoded.as_bytes(),
+            offset: 0,
+            backrefs: Vec::new(),
+        }
+    }
+
+    fn next(&mut self) -> Result<usize> {
+        let c = *self
+            .bytes
+            .get(self.offset)
+            .ok_or_else(|| anyhow!("Search index parent list is too short"))?;
+        if (b'0'..b'@').contains(&c) {
+            self.offset += 1;
+            return self
+                .backrefs
+                .get((c - b'0') as usize)
+                .copied()
+                .ok_or_else(|| anyhow!("Invalid backreference in search index"));
+        }
+        if c == b'`' {
+            self.offset += 1;
+            return Ok(0);
+        }
+
+        let mut n: usize = 0;
+        loop {
+            let c = *self
+                .bytes
+                .get(self.offset)
+                .ok_or_else(|| anyhow!("Truncated number in search index"))?;
+            self.offset += 1;
+            n = (n << 4) | (c & 0xF) as usize;
+            if c >= b'`' {
+                break;
+            }
+        }
+        // Indices are never negative
+        let value = n >> 1;
+        self.backrefs.insert(0, value);
+        self.backrefs.truncate(16);
+        Ok(value)
+    }
+}
+
+/// A value of the search index's signature list (`f`): a number or a list of values
+#[derive(Debug, Clone, PartialEq)]
+enum Encoded {
+    Number(i64),
+    List(Vec<Encoded>),
+}
+
+impl Encoded {
+    /// Type lists hold a lone type without generics as a bare number
+    fn types(&self) -> &[Encoded] {
+        match self {
+            Encoded::Number(_) => std::slice::from_ref(self),
+            Encoded::List(types) => types,
+        }
+    }
+}
+
+/// Decoder for the signature list, which nests the numbers of [`VlqHexDecoder`] in `{` `}`
+/// lists. Backreferences repeat one of the last 16 signatures.
+struct SignatureDecoder<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+    backrefs: Vec<Encoded>,
+}
+
+impl<'a> SignatureDecoder<'a> {
+    fn new(encoded: &'a str) -> Self {
+        Self {
+            bytes: encoded.as_bytes(),
+            offset: 0,
+            backrefs: Vec::new(),
+        }
+    }
+
+    /// The signature of the next item, `None` for items that aren't functions. rustdoc leaves
+    /// out the trailing items without one.
+    fn next(&mut self) -> Result<Option<Encoded>> {
+        let Some(&c) = self.bytes.get(self.offset) else {
+            return Ok(None);
+        };
+        if (b'0'..b'@').contains(&c) {
+            self.offset += 1;
+            return self
+                .backrefs
+                .get((c - b'0') as usize)
+                .cloned()
+                .map(Some)
+                .ok_or_else(|| anyhow!("Invalid backreference in search index"));
+        }
+        if c == b'`' {
+            self.offset += 1;
+            return Ok(None);
+        }
+
+        let value = self.decode()?;
+        self.backrefs.insert(0, value.clone());
+        self.backrefs.truncate(16);
+        Ok(Some(value))
+    }
+
+    fn decode(&mut self) -> Result<Encoded> {
+        let byte = |decoder: &Self| {
+            decoder
+                .bytes
+                .get(decoder.offset)
+                .copied()
+                .ok_or_else(|| anyhow!("Truncated signature in search index"))
+        };
+        if byte(self)? == b'{' {
+            self.offset += 1;
+            let mut values = Vec::new();
+            while byte(self)? != b'}' {
+                values.push(self.decode()?);
+            }
+            self.offset += 1;
+            return Ok(Encoded::List(values));
+        }
+
+        let mut n: i64 = 0;
+        loop {
+            let c = byte(self)?;
+            self.offset += 1;
+            n = (n << 4) | (c & 0xF) as i64;
+            if c >= b'`' {
+                break;
+            }
+        }
+        // The sign is in the lowest bit; generic parameters are negative
+        let value = n >> 1;
+        Ok(Encoded::Number(if n & 1 == 1 { -value } else { value }))
+    }
+}
+
+/// Reads a decoded signature, `[inputs, output, where clauses...]`, where types are
+/// 1-based indices into `type_paths`, or `[index, generics, bindings]` for generic types.
+/// Generic parameters are negative and refer to their where clause.
+fn signature(function: &Encoded, type_paths: &[String]) -> Signature {
+    let Encoded::List(parts) = function else {
+        return Signature::default();
+    };
+    let where_clauses = parts.get(2..).unwrap_or_default();
+    let mut signature = Signature::default();
+    for (part, types) in [(0, &mut signature.inputs), (1, &mut signature.output)] {
+        for ty in parts.get(part).map(Encoded::types).unwrap_or_default() {
+            collect_types(ty, type_paths, where_clauses, 0, types);
+        }
+    }
+    signature
+}
+
+fn collect_types(
+    ty: &Encoded,
+    type_paths: &[String],
+    where_clauses: &[Encoded],
+    depth: usize,
+    types: &mut Vec<String>,
+) {
+    // Bounds may mention the generic they bound, as in `T: PartialEq<T>`
+    const MAX_DEPTH: usize = 8;
+    if depth > MAX_DEPTH {
+        return;
+    }
+
+    let (id, generics, bindings) = match ty {
+        Encoded::Number(id) => (*id, &[][..], &[][..]),
+        Encoded::List(parts) => (
+            match parts.first() {
+                Some(Encoded::Number(id)) => *id,
+                _ => 0,
+            },
+            parts.get(1).map(Encoded::types).unwrap_or_default(),
+            parts.get(2).map(Encoded::types).unwrap_or_default(),
+        ),
+    };
+    if id > 0 {
+        if let Some(path) = type_paths.get(id as usize - 1) {
+            if !types.contains(path) {
+                types.push(path.clone());
+            }
+        }
+    } else if id < 0 {
+        for bound in where_clauses
+            .get((-id - 1) as usize)
+            .map(Encoded::types)
+            .unwrap_or_default()
+        {
+            collect_types(bound, type_paths, where_clauses, depth + 1, types);
+        }
+    }
+
+    let nested = generics.iter().chain(bindings.iter().flat_map(|binding| {
+        // A binding is `[associated type, constraints]`, as in `IntoIterator<Item = T>`
+        match binding {
+            Encoded::List(parts) => parts.get(1).map(Encoded::types).unwrap_or_default(),
+            Encoded::Number(_) => &[],
+        }
+    }));
+    for ty in nested {
+        collect_types(ty, type_paths, where_clauses, depth + 1, types);
+    }
+}
+
+/// A parsed query: `trace::Builder` looks for `builder` in a path containing `trace`
+struct Query {
+    path: Vec<String>,
+    name: String,
+    /// The name without underscores, as rustdoc compares `with_sampler` and `withsampler`
+    normalized_name: String,
+}
+
+impl Query {
+    fn parse(query: &str) -> Self {
+        let mut segments: Vec<String> = query
+            .trim()
+            .to_lowercase()
+            .split("::")
+            .map(|segment| segment.trim().to_string())
+            .filter(|segment| !segment.is_empty())
+            .collect();
+        let name = segments.pop().unwrap_or_default();
+        Self {
+            path: segments,
+            normalized_name: name.replace('_', ""),
+            name,
+        }
+    }
+}
+
+/// How an item matches a query. Fields are compared in order, following the tie-breakers of
+/// `sortResults` in rustdoc's `search.js`, so results come out as on docs.rs.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct Rank<'a> {
+    /// Exact name matches first
+    inexact: bool,
+    /// Then names containing the query, before those only within the edit distance
+    no_literal_match: bool,
+    /// Then by how closely the module path matches the path part of the query
+    path_distance: usize,
+    /// Then by where in the name the query occurs
+    index: usize,
+    /// Then by edit distance between the name and the query
+    distance: usize,
+    /// Then deprecated items last
+    deprecated: bool,
+    /// Then shorter names, then alphabetically
+    name_length: usize,
+    name: String,
+    /// Then by kind, in rustdoc's `ItemType` order: modules before structs before functions...
+    kind: usize,
+    path: &'a str,
+}
+
+fn rank<'a>(item: &'a SearchResult, query: &Query) -> Option<Rank<'a>> {
+    let name = item.name.to_lowercase();
+    let normalized_name = name.replace('_', "");
+    let max_distance = query.normalized_name.chars().count() / 3;
+
+    let path_distance = if query.path.is_empty() {
+        0
+    } else {
+        let segments: Vec<String> = item
+            .path
+            .to_lowercase()
+            .split("::")
+            .map(str::to_string)
+            .collect();
+        path_distance(&query.path, &segments[..segments.len() - 1])?
+    };
+
+    let index = normalized_name.find(&query.normalized_name);
+    let distance = edit_distance(&normalized_name, &query.normalized_name);
+    if index.is_none() && distance > max_distance {
+        return None;
+    }
+
+    Some(Rank {
+        inexact: name != query.name,
+        no_literal_match: index.is_none(),
+        path_distance,
+        index: index.unwrap_or_default(),
+        distance,
+        deprecated: item.deprecated,
+        name_length: name.len(),
+        name,
+        kind: ITEM_KINDS
+            .iter()
+            .position(|kind| *kind == item.kind)
+            .unwrap_or(ITEM_KINDS.len()),
+        path: &item.path,
+    })
+}
+
+/// Distance between the path part of a query and an item's module path, like rustdoc's
+/// `checkPath`: the best window of consecutive segments, each either containing the query
+/// segment or within the edit distance of it. `None` when no window is close enough.
+fn path_distance(query: &[String], segments: &[String]) -> Option<usize> {
+    if query.len() > segments.len() {
+        return None;
+    }
+    let max_distance = query.iter().map(|q| q.chars().count()).sum::<usize>() / 3;
+
+    segments
+        .windows(query.len())
+        .filter_map(|window| {
+            let mut total = 0;
+            for (segment, q) in window.iter().zip(query) {
+                let length_difference = segment.len().saturating_sub(q.len()) / 3;
+                if length_difference <= max_distance && segment.contains(q.as_str()) {
+                    // Substring matches are discounted
+                    total += length_difference;
+                } else {
+                    let distance = edit_distance(segment, q);
+                    if distance > max_distance {
+                        return None;
+                    }
+                    total += distance;
+                }
+            }
+            Some((total as f64 / query.len() as f64).round() as usize)
+        })
+        .min()
+        .filter(|distance| *distance <= max_distance)
+}
+
+/// Ranks the items matching `query` the way docs.rs search does. A query containing `::`
+/// matches its last segment against names and the rest against the items' paths.
+pub(crate) fn search<'a>(
+    items: &'a [SearchResult],
+    query: &str,
+    kind: Option<&str>,
+    limit: usize,
+) -> Vec<&'a SearchResult> {
+    let query = Query::parse(query);
+    if query.name.is_empty() {
+        return Vec::new();
+    }
+
+    let mut matches: Vec<(Rank, &SearchResult)> = items
+        .iter()
+        .filter(|item| kind.is_none_or(|kind| item.kind == kind))
+        .filter_map(|item| rank(item, &query).map(|rank| (rank, item)))
+        .collect();
+    matches.sort_by(|(a, _), (b, _)| a.cmp(b));
+    matches
+        .into_iter()
+        .take(limit)
+        .map(|(_, item)| item)
+        .collect()
+}
+
+impl Tool for SearchInCrateTool {
+    fn name(&self) -> String {
+        "search_in_crate".to_string()
+    }
+
+    fn description(&self) -> String {
+        "Search for items within a crate by name using its rustdoc search index, ranked \
+        the same way as the search on docs.rs. Matches names containing the query and, to \
+        tolerate typos, names within a small edit distance of it, including methods, fields \
+        and variants. Returns item paths, kinds and documentation links, best matches first."
+            .to_string()
+    }
+
+    fn input_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "crate_name": {
+                    "type": "string",
+                    "description": "Name of the crate to search in"
+                },
+                "query": {
+                    "type": "string",
+                    "description": "Name to search for, e.g. Builder. Include :: to match against full paths, e.g. trace::Builder"
+                },
+                "kind": {
+                    "type": "string",
+                    "description": "Optional rustdoc item kind to restrict results to, e.g. struct, trait, fn or method"
+                },
+                "limit": {
+                    "type": "integer",
+                    "description": "Maximum number of results (defaults to 20)"
+                },
+                "version": {
+                    "type": "string",
+                    "description": "Optional version of the crate (defaults to latest)"
+                },
+                "registry": registry::registry_schema()
+            },
+            "required": ["crate_name", "query"]
+        })
+    }
+
+    fn call(&self, input: Option<serde_json::Value>) -> Result<CallToolResponse> {
+        let args: SearchInCrateParams = error::parse_args(input)?;
+        config::current().ensure_crate_allowed(&args.crate_name)?;
+        let items = runtime::blocking(|| {
+            registry::in_registry(&args.crate_name, args.registry.as_deref(), || {
+                self.fetch_index(&args.crate_name, args.version.as_deref())
+            })
+        })?;
+        let results = search(
+            &items,
+            &args.query,
+            args.kind.as_deref(),
+            args.limit.unwrap_or(DEFAULT_LIMIT),
+        );
+
+        Ok(CallToolResponse {
+            content: vec![ToolResponseContent::Text {
+                text: serde_json::to_string_pretty(&results)?,
+            }],
+            is_error: None,
+            meta: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::fs;
+
+    const ROOT_URL: &str = "https://docs.rs/demo/0.1.0/";
+
+    fn load_index() -> Result<Vec<SearchResult>> {
+        let js = fs::read_to_string("test-data/search_in_crate/search-index.js")?;
+        parse_search_index(&js, "demo", ROOT_URL)
+    }
+
+    #[test]
+    fn test_parse_search_index() -> Result<()> {
+        let items = load_index()?;
+        let paths: Vec<(&str, &str)> = items.iter().map(|i| (i.kind(), i.path())).collect();
+
+        assert_eq!(
+            paths,
+            vec![
+                ("mod", "demo::trace"),
+                ("struct", "demo::trace::TracerProvider"),
+                ("struct", "demo::trace::TracerProviderBuilder"),
+                ("method", "demo::trace::TracerProviderBuilder::build"),
+                ("method", "demo::trace::TracerProviderBuilder::with_sampler"),
+                ("method", "demo::trace::TracerProvider::builder"),
+                ("trait", "demo::trace::ShouldSample"),
+                ("fn", "demo::init"),
+                ("macro", "demo::span"),
+                ("macro", "demo::trace"),
+            ]
+        );
+
+        let build = &items[3];
+        assert_eq!(
+            build.doc_link(),
+            "https://docs.rs/demo/0.1.0/demo/trace/struct.TracerProviderBuilder.html#method.build"
+        );
+        assert_eq!(
+            items[0].doc_link(),
+            "https://docs.rs/demo/0.1.0/demo/trace/index.html"
+        );
+
+        let deprecated: Vec<&str> = items
+            .iter()
+            .filter(|item| item.deprecated())
+            .map(|item| item.path())
+            .collect();
+        assert_eq!(
+            deprecated,
+            vec!["demo::trace::TracerProviderBuilder::with_sampler"]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_search_ranking() -> Result<()> {
+        let items = load_index()?;
+        let found = |query: &str, kind: Option<&str>| -> Vec<String> {
+            search(&items, query, kind, DEFAULT_LIMIT)
+                .iter()
+                .map(|item| format!("{} {}", item.kind(), item.path()))
+                .collect()
+        };
+
+        // Expected orders are those docs.rs shows for the same queries
+        assert_eq!(
+            found("builder", None),
+            vec![
+                "method demo::trace::TracerProvider::builder",
+                "struct demo::trace::TracerProviderBuilder",
+                "method demo::trace::TracerProviderBuilder::build",
+            ],
+            "Exact matches, then substrings, then names within the edit distance"
+        );
+        assert_eq!(
+            found("trace", None),
+            vec![
+                "mod demo::trace",
+                "macro demo::trace",
+                "struct demo::trace::TracerProvider",
+                "struct demo::trace::TracerProviderBuilder",
+            ],
+            "Equal matches are ordered by name length, then by kind"
+        );
+        assert_eq!(
+            found("withsampler", None),
+            vec!["method demo::trace::TracerProviderBuilder::with_sampler"],
+            "Underscores are ignored"
+        );
+        assert_eq!(found("spn", None), vec!["macro demo::span"]);
+        assert_eq!(
+            found("builder", Some("struct")),
+            vec!["struct demo::trace::TracerProviderBuilder"]
+        );
+        assert_eq!(found("tpb", None), Vec::<String>::new());
+        assert_eq!(found("", None), Vec::<String>::new());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_search_by_path() -> Result<()> {
+        let items = load_index()?;
+        let found = |query: &str| -> Vec<&str> {
+            search(&items, query, None, DEFAULT_LIMIT)
+                .iter()
+                .map(|item| item.path())
+                .collect()
+        };
+
+        let expected = vec![
+            "demo::trace::TracerProviderBuilder::build",
+            "demo::trace::TracerProvider::builder",
+            "demo::trace::TracerProviderBuilder",
+        ];
+        assert_eq!(found("trace::build"), expected);
+        assert_eq!(
+            found("trce::build"),
+            expected,
+            "Path segments tolerate typos"
+        );
+        assert_eq!(
+            found("TracerProviderBuilder::build"),
+            vec![
+                "demo::trace::TracerProviderBuilder::build",
+                "demo::trace::TracerProvider::builder",
+            ],
+            "Parents are part of the path, closer ones first"
+        );
+        assert_eq!(found("trace::init"), Vec::<&str>::new());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("builder", "builder"), 0);
+        assert_eq!(edit_distance("builder", "buidler"), 1, "Swaps count once");
+        assert_eq!(edit_distance("build", "builder"), 2);
+        assert_eq!(edit_distance("span", "spn"), 1);
+        assert_eq!(edit_distance("", "abc"), 3);
+    }
+
+    #[test]
+    fn test_search_index_url() -> Result<()> {
+        let html = fs::read_to_string("test-data/list-of-all-items-tokio-1.43.0.html")?;
+        assert_eq!(
+            search_index_url("https://docs.rs/tokio/1.43.0/tokio/all.html", &html)?,
+            "https://docs.rs/tokio/1.43.0/search-index-20250107-1.86.0-nightly-ad211ced8.js"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_decode_roaring_bitmap() -> Result<()> {
+        let sorted = |encoded: &str| -> Result<Vec<usize>> {
+            let mut values: Vec<usize> = decode_roaring_bitmap(encoded)?.into_iter().collect();
+            values.sort();
+            Ok(values)
+        };
+
+        assert_eq!(sorted("OjAAAAAAAAA=")?, Vec::<usize>::new());
+        assert_eq!(sorted("OjAAAAEAAAAAAAAAEAAAAAUA")?, vec![5]);
+        // A run container covering 3..=5
+        assert_eq!(sorted("OzAAAAEAAAIAAQADAAIA")?, vec![3, 4, 5]);
+        assert!(decode_roaring_bitmap("AAAA").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_signatures() -> Result<()> {
+        let js = fs::read_to_string("test-data/find_usages_in_api/search-index.js")?;
+        let items = parse_search_index(&js, "demo", ROOT_URL)?;
+        let signature = |path: &str| {
+            items
+                .iter()
+                .find(|item| item.path() == path)
+                .and_then(SearchResult::signature)
+                .cloned()
+        };
+
+        assert_eq!(signature("demo::Client"), None, "Structs have no signature");
+        assert_eq!(
+            signature("demo::Client::send"),
+            Some(Signature {
+                inputs: vec!["demo::Client".to_string(), "demo::Request".to_string()],
+                output: vec![
+                    "core::result::Result".to_string(),
+                    "demo::Response".to_string(),
+                    "demo::Error".to_string()
+                ],
+            })
+        );
+        assert_eq!(
+            signature("demo::collect"),
+            Some(Signature {
+                inputs: vec![
+                    "core::iter::traits::collect::IntoIterator".to_string(),
+                    "demo::Response".to_string()
+                ],
+                output: vec![
+                    "alloc::vec::Vec".to_string(),
+                    "alloc::string::String".to_string()
+                ],
+            }),
+            "Generic parameters are replaced by their bounds"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_signature_decoder() -> Result<()> {
+        // No signature, `{1 {2 -1}}`, then a backreference to it
+        let mut decoder = SignatureDecoder::new("`{b{dc}}0");
+        let list = Encoded::List(vec![
+            Encoded::Number(1),
+            Encoded::List(vec![Encoded::Number(2), Encoded::Number(-1)]),
+        ]);
+        assert_eq!(decoder.next()?, None);
+        assert_eq!(decoder.next()?, Some(list.clone()));
+        assert_eq!(decoder.next()?, Some(list));
+        assert_eq!(decoder.next()?, None, "Trailing items have no signature");
+        assert!(SignatureDecoder::new("{b").next().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_vlq_hex_decoder() -> Result<()> {
+        // 0, 1, 2, 8, then a backreference to the most recent value (8)
+        let mut decoder = VlqHexDecoder::new("`bdA`0");
+        let decoded = (0..5).map(|_| decoder.next()).collect::<Result<Vec<_>>>()?;
+        assert_eq!(decoded, vec![0, 1, 2, 8, 8]);
+        assert!(decoder.next().is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,58 @@
+//! Parsing the CSS selectors tools scrape docs pages with. Some are built from page data, such
+//! as a section id or an anchor, so a malformed one is reported as an error instead of
+//! panicking, which would take the whole stdio session down with it.
+
+use std::fmt;
+
+use scraper::Selector;
+
+/// A selector that failed to parse, along with the page it was meant to scrape
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ParseError {
+    selector: String,
+    page: String,
+    reason: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Invalid selector `{}` for the {}: {}",
+            self.selector, self.page, self.reason
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Parses `selector`, naming the kind of `page` it is for (e.g. "struct page") in the error.
+pub(crate) fn parse(selector: &str, page: &str) -> Result<Selector, ParseError> {
+    Selector::parse(selector).map_err(|e| ParseError {
+        selector: selector.to_string(),
+        page: page.to_string(),
+        reason: e.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        assert!(parse("#main-content .docblock", "struct page").is_ok());
+
+        // A section id with a quote in it, as a dynamic selector might be built from
+        let error = parse("[id='it's']", "all items page").unwrap_err();
+        assert_eq!(error.selector, "[id='it's']");
+        assert_eq!(error.page, "all items page");
+        assert!(
+            error
+                .to_string()
+                .starts_with("Invalid selector `[id='it's']` for the all items page: "),
+            "{}",
+            error
+        );
+    }
+}
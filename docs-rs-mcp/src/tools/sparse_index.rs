@@ -0,0 +1,260 @@
+//! Reading the sparse registry index (`https://index.crates.io`), which lists every published
+//! version of a crate with its dependencies and features, one JSON object per line.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use anyhow::{anyhow, Context, Result};
+use semver::{Version, VersionReq};
+use serde::Deserialize;
+
+use super::crates_io;
+#[cfg(feature = "native")]
+use super::runtime;
+use crate::config;
+
+/// A published version of a crate, as recorded in the index
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct IndexVersion {
+    name: String,
+    vers: String,
+    #[serde(default)]
+    deps: Vec<IndexDependency>,
+    #[serde(default)]
+    features: BTreeMap<String, Vec<String>>,
+    /// Features using the `dep:` and `?/` syntax, kept apart for older Cargo versions
+    #[serde(default)]
+    features2: BTreeMap<String, Vec<String>>,
+    #[serde(default)]
+    yanked: bool,
+}
+
+impl IndexVersion {
+    pub(crate) fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub(crate) fn version(&self) -> &str {
+        &self.vers
+    }
+
+    pub(crate) fn deps(&self) -> &[IndexDependency] {
+        &self.deps
+    }
+
+    /// Every feature of the version, merging `features2` into `features`
+    pub(crate) fn features(&self) -> BTreeMap<&str, &[String]> {
+        self.features
+            .iter()
+            .chain(&self.features2)
+            .map(|(name, values)| (name.as_str(), values.as_slice()))
+            .collect()
+    }
+
+    /// Names `--features` takes: the declared features, and the optional dependencies no
+    /// feature refers to as `dep:name`, which Cargo makes features of their own
+    pub(crate) fn feature_names(&self) -> BTreeSet<&str> {
+        let features = self.features();
+        let hidden: BTreeSet<&str> = features
+            .values()
+            .flat_map(|values| values.iter())
+            .filter_map(|value| value.strip_prefix("dep:"))
+            .collect();
+        let implicit = self
+            .deps
+            .iter()
+            .filter(|dep| dep.optional && !hidden.contains(dep.name.as_str()))
+            .map(|dep| dep.name.as_str());
+        features.keys().copied().chain(implicit).collect()
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct IndexDependency {
+    /// Name the dependency is known by in the depending crate, which differs from the crate's
+    /// name when renamed
+    name: String,
+    req: String,
+    #[serde(default)]
+    features: Vec<String>,
+    #[serde(default)]
+    optional: bool,
+    #[serde(default = "default_true")]
+    default_features: bool,
+    /// Platform the dependency is limited to, e.g. `cfg(windows)`
+    target: Option<String>,
+    /// `normal`, `build` or `dev`; missing means `normal`
+    kind: Option<String>,
+    /// Name of the crate when the dependency is renamed
+    package: Option<String>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl IndexDependency {
+    pub(crate) fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Name of the crate depended on
+    pub(crate) fn package(&self) -> &str {
+        self.package.as_deref().unwrap_or(&self.name)
+    }
+
+    pub(crate) fn req(&self) -> &str {
+        &self.req
+    }
+
+    pub(crate) fn features(&self) -> &[String] {
+        &self.features
+    }
+
+    pub(crate) fn optional(&self) -> bool {
+        self.optional
+    }
+
+    pub(crate) fn default_features(&self) -> bool {
+        self.default_features
+    }
+
+    pub(crate) fn target(&self) -> Option<&str> {
+        self.target.as_deref()
+    }
+
+    pub(crate) fn kind(&self) -> &str {
+        self.kind.as_deref().unwrap_or("normal")
+    }
+}
+
+/// Location of a crate's file below the index root: `1/a`, `2/ab`, `3/a/abc`, or
+/// `se/rd/serde` for longer names.
+pub(crate) fn index_path(crate_name: &str) -> String {
+    let name = crate_name.to_lowercase();
+    match name.len() {
+        1 => format!("1/{}", name),
+        2 => format!("2/{}", name),
+        3 => format!("3/{}/{}", &name[..1], name),
+        _ => format!("{}/{}/{}", &name[..2], &name[2..4], name),
+    }
+}
+
+/// Fetches every published version of a crate from the sparse index of the active registry.
+pub(crate) async fn versions(crate_name: &str) -> Result<Vec<IndexVersion>> {
+    let config = config::current();
+    let index_url = config.sources().index_url().ok_or_else(|| {
+        anyhow!(
+            "Registry {} has no sparse index configured (index_url)",
+            config.active_registry()
+        )
+    })?;
+    let body = crates_io::fetch(&format!("{}/{}", index_url, index_path(crate_name))).await?;
+    parse(&body).context(format!("Invalid index file of {}", crate_name))
+}
+
+/// Like [`versions`], blocking until the response arrives.
+#[cfg(feature = "native")]
+pub(crate) fn fetch(crate_name: &str) -> Result<Vec<IndexVersion>> {
+    runtime::block_on(versions(crate_name))
+}
+
+/// The names `--features` takes for `version` of the crate, or its newest release for
+/// `latest`.
+pub(crate) async fn feature_names(crate_name: &str, version: &str) -> Result<BTreeSet<String>> {
+    let versions = versions(crate_name).await?;
+    let req = match version {
+        "latest" => VersionReq::STAR,
+        version => VersionReq::parse(&format!("={}", version))
+            .context(format!("Invalid version {}", version))?,
+    };
+    let release = select(&versions, &req)
+        .ok_or_else(|| anyhow!("{} {} isn't in the index", crate_name, version))?;
+    Ok(release
+        .feature_names()
+        .into_iter()
+        .map(str::to_string)
+        .collect())
+}
+
+/// Parses an index file, one version per line.
+pub(crate) fn parse(body: &str) -> Result<Vec<IndexVersion>> {
+    body.lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| serde_json::from_str(line).context("Invalid index entry"))
+        .collect()
+}
+
+/// The highest version that isn't yanked and satisfies `req`. Pre-releases only match
+/// requirements that name one, as with Cargo.
+pub(crate) fn select<'a>(
+    versions: &'a [IndexVersion],
+    req: &VersionReq,
+) -> Option<&'a IndexVersion> {
+    versions
+        .iter()
+        .filter(|version| !version.yanked)
+        .filter_map(|version| Some((Version::parse(&version.vers).ok()?, version)))
+        .filter(|(parsed, _)| req.matches(parsed))
+        .max_by(|(a, _), (b, _)| a.cmp(b))
+        .map(|(_, version)| version)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::fs;
+
+    #[test]
+    fn test_index_path() {
+        assert_eq!(index_path("a"), "1/a");
+        assert_eq!(index_path("cc"), "2/cc");
+        assert_eq!(index_path("syn"), "3/s/syn");
+        assert_eq!(index_path("Tokio"), "to/ki/tokio");
+    }
+
+    #[test]
+    fn test_parse_and_select() -> Result<()> {
+        let versions = parse(&fs::read_to_string(
+            "test-data/dependency_tree/index/de/mo/demo-core",
+        )?)?;
+        assert_eq!(versions.len(), 3);
+
+        let select = |req: &str| -> Result<Option<&str>> {
+            Ok(select(&versions, &VersionReq::parse(req)?).map(IndexVersion::version))
+        };
+        assert_eq!(
+            select("^0.3")?,
+            Some("0.3.0"),
+            "The yanked 0.3.1 isn't picked"
+        );
+        assert_eq!(select("*")?, Some("0.4.0"));
+        assert_eq!(select("^0.5")?, None);
+
+        let features = versions[0].features();
+        assert_eq!(
+            features.keys().copied().collect::<Vec<_>>(),
+            vec!["default", "extra", "io", "std"],
+            "features2 is merged into features"
+        );
+        assert_eq!(
+            versions[0].feature_names().into_iter().collect::<Vec<_>>(),
+            vec!["default", "extra", "io", "std"],
+            "The optional bytes is only enabled through extra"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_feature_names() -> Result<()> {
+        let versions = parse(
+            r#"{"name":"demo","vers":"1.0.0","deps":[{"name":"serde","req":"^1","optional":true},{"name":"log","req":"^0.4"}],"features":{"rt-multi-thread":[]},"yanked":false}"#,
+        )?;
+        assert_eq!(
+            versions[0].feature_names().into_iter().collect::<Vec<_>>(),
+            vec!["rt-multi-thread", "serde"],
+            "Optional dependencies are features of their own"
+        );
+        Ok(())
+    }
+}
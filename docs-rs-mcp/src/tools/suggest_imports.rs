@@ -0,0 +1,694 @@
+use std::collections::BTreeMap;
+
+use anyhow::{anyhow, Result};
+use mcp_sdk::{
+    tools::Tool,
+    types::{CallToolResponse, ToolResponseContent},
+};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tracing::{debug, warn};
+
+use super::get_struct_docs::{HtmlFetcher, HttpHtmlFetcher};
+use super::module_items::{module_url, parse_module_page, ModuleItem, ModuleItems};
+use super::search_in_crate::SearchResult;
+use super::{docs_root, registry, runtime, SearchInCrateTool};
+use crate::{config, error};
+
+/// Module pages read for feature badges and `pub use` statements; the crate root and preludes
+/// come first
+const MAX_PAGES: usize = 10;
+
+/// Kinds of items a `use` statement can import
+const IMPORTABLE_KINDS: [&str; 14] = [
+    "mod",
+    "struct",
+    "enum",
+    "fn",
+    "type",
+    "static",
+    "trait",
+    "macro",
+    "constant",
+    "union",
+    "foreigntype",
+    "attr",
+    "derive",
+    "traitalias",
+];
+
+/// `use` statements that bring an item of a crate into scope
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ImportSuggestions {
+    crate_name: String,
+    version: String,
+    item: String,
+    /// Explains why another item than the one asked for is imported, e.g. the trait of a method
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    note: Option<String>,
+    /// Shortest paths first, then preludes; the first suggestion is the one to use
+    suggestions: Vec<ImportSuggestion>,
+}
+
+impl ImportSuggestions {
+    pub fn suggestions(&self) -> &[ImportSuggestion] {
+        &self.suggestions
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ImportSuggestion {
+    /// The statement to write, e.g. `use tokio::io::AsyncReadExt; // requires feature "io-util"`
+    statement: String,
+    /// Path imported: the item, or the prelude module for glob imports
+    path: String,
+    /// Rustdoc item kind of the imported item, e.g. `trait` or `derive`
+    kind: String,
+    source: ImportSource,
+    /// Cargo features that need enabling for the import to resolve
+    required_features: Vec<String>,
+    doc_link: String,
+}
+
+impl ImportSuggestion {
+    pub fn statement(&self) -> &str {
+        &self.statement
+    }
+
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    pub fn source(&self) -> ImportSource {
+        self.source
+    }
+
+    pub fn required_features(&self) -> &[String] {
+        &self.required_features
+    }
+}
+
+/// How a path reaches the item
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportSource {
+    /// The item is documented at the path, where it is defined or inlined
+    Documented,
+    /// A `pub use` of a module re-exports the item
+    Reexport,
+    /// A prelude module re-exports the item, to be glob imported
+    Prelude,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct SuggestImportsParams {
+    crate_name: String,
+    item: String,
+    version: Option<String>,
+    registry: Option<String>,
+}
+
+pub struct SuggestImportsTool {
+    html_fetcher: Box<dyn HtmlFetcher>,
+}
+
+impl SuggestImportsTool {
+    pub fn new() -> Self {
+        Self {
+            html_fetcher: Box::new(HttpHtmlFetcher::new()),
+        }
+    }
+
+    pub(crate) fn suggest_imports(
+        &self,
+        crate_name: &str,
+        item: &str,
+        version: Option<&str>,
+    ) -> Result<ImportSuggestions> {
+        let version = version.unwrap_or("latest");
+        let index = SearchInCrateTool::new().fetch_index(crate_name, Some(version))?;
+        let root_url = docs_root::crate_root_blocking(crate_name, version)?;
+        let crate_ident = docs_root::lib_name(&root_url).to_string();
+
+        let query = normalize_query(&crate_ident, item);
+        let (candidates, note) = candidates(&query, &index);
+        if candidates.is_empty() {
+            return Err(anyhow!(
+                "Crate {} {} has no item {}",
+                crate_name,
+                version,
+                item
+            ));
+        }
+        debug!(
+            "{} resolves to {}",
+            item,
+            candidates
+                .iter()
+                .map(|candidate| candidate.path())
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+
+        let mut pages = BTreeMap::new();
+        for module in modules_to_read(&crate_ident, &candidates, &index) {
+            let url = module_url(&root_url, &module);
+            let page = self
+                .html_fetcher
+                .fetch_html_blocking(&url)
+                .and_then(|html| parse_module_page(crate_name, version, &module, &url, &html));
+            match page {
+                Ok(page) => {
+                    pages.insert(module, page);
+                }
+                Err(e) => warn!("Failed to read module page {}: {}", url, e),
+            }
+        }
+
+        let suggestions = suggestions(&crate_ident, &candidates, &pages);
+        if suggestions.is_empty() {
+            return Err(anyhow!(
+                "{} of {} only exists in the docs.rs build (cfg(docsrs)) and can't be imported",
+                item,
+                crate_name
+            ));
+        }
+
+        Ok(ImportSuggestions {
+            crate_name: crate_name.to_string(),
+            version: version.to_string(),
+            item: item.to_string(),
+            note,
+            suggestions,
+        })
+    }
+}
+
+impl Default for SuggestImportsTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Reduces the item as written to a path below the crate root: `use tokio::io::AsyncReadExt;`,
+/// `crate::io::AsyncReadExt` and `io::AsyncReadExt` all become `io::AsyncReadExt`.
+fn normalize_query(crate_ident: &str, item: &str) -> String {
+    let item = item
+        .trim()
+        .trim_start_matches("use ")
+        .trim_end_matches(';')
+        .trim()
+        .trim_start_matches("::");
+    match item.split_once("::") {
+        Some((first, rest)) if first == crate_ident || first == "crate" => rest.to_string(),
+        _ => item.to_string(),
+    }
+}
+
+/// Importable items documented at a path ending in `query`. Members such as methods or
+/// variants can't be imported themselves, so their parent is taken instead, with a note.
+fn candidates<'a>(
+    query: &str,
+    index: &'a [SearchResult],
+) -> (Vec<&'a SearchResult>, Option<String>) {
+    let suffix = format!("::{}", query);
+    let (importable, members): (Vec<_>, Vec<_>) = index
+        .iter()
+        .filter(|item| item.path().ends_with(&suffix))
+        .partition(|item| IMPORTABLE_KINDS.contains(&item.kind()));
+    if !importable.is_empty() || members.is_empty() {
+        return (importable, None);
+    }
+
+    let mut parents: Vec<&str> = Vec::new();
+    for member in &members {
+        if let Some((parent, _)) = member.path().rsplit_once("::") {
+            if !parents.contains(&parent) {
+                parents.push(parent);
+            }
+        }
+    }
+    let candidates: Vec<_> = index
+        .iter()
+        .filter(|item| parents.contains(&item.path()) && IMPORTABLE_KINDS.contains(&item.kind()))
+        .collect();
+    let note = format!(
+        "{} is a {} of {}, which is imported instead",
+        query,
+        members[0].kind(),
+        parents.join(", ")
+    );
+    (candidates, Some(note))
+}
+
+/// Module path below the crate root, empty for the root itself
+fn relative_module<'a>(crate_ident: &str, module: &'a str) -> &'a str {
+    if module == crate_ident {
+        return "";
+    }
+    module
+        .strip_prefix(crate_ident)
+        .and_then(|rest| rest.strip_prefix("::"))
+        .unwrap_or(module)
+}
+
+/// Module of an item, below the crate root
+fn parent_module<'a>(crate_ident: &str, item: &'a SearchResult) -> &'a str {
+    let module = item
+        .path()
+        .rsplit_once("::")
+        .map(|(module, _)| module)
+        .unwrap_or_default();
+    relative_module(crate_ident, module)
+}
+
+/// Full path of a module below the crate root
+fn full_module(crate_ident: &str, module: &str) -> String {
+    if module.is_empty() {
+        crate_ident.to_string()
+    } else {
+        format!("{}::{}", crate_ident, module)
+    }
+}
+
+fn is_prelude(module: &str) -> bool {
+    module.rsplit("::").next() == Some("prelude")
+}
+
+/// Modules whose pages tell how the candidates are imported: the crate root for its
+/// re-exports, the preludes, and the modules defining the candidates for their feature badges.
+fn modules_to_read(
+    crate_ident: &str,
+    candidates: &[&SearchResult],
+    index: &[SearchResult],
+) -> Vec<String> {
+    let preludes = index
+        .iter()
+        .filter(|item| item.kind() == "mod" && item.name() == "prelude")
+        .map(|item| relative_module(crate_ident, item.path()));
+    let parents = candidates
+        .iter()
+        .map(|candidate| parent_module(crate_ident, candidate));
+
+    let mut modules: Vec<String> = Vec::new();
+    for module in std::iter::once("").chain(preludes).chain(parents) {
+        if !modules.iter().any(|read| read == module) {
+            modules.push(module.to_string());
+        }
+    }
+    modules.truncate(MAX_PAGES);
+    modules
+}
+
+/// Whether a row of a module page lists `item`. Rows link the item's page, named after its
+/// kind, which tells a trait apart from the derive macro sharing its name.
+fn row_lists(row: &ModuleItem, item: &SearchResult) -> bool {
+    match row.doc_link() {
+        Some(link) => {
+            let page = link.split('#').next().unwrap_or_default();
+            if item.kind() == "mod" {
+                page.ends_with(&format!("/{}/index.html", item.name()))
+            } else {
+                page.ends_with(&format!("/{}.{}.html", item.kind(), item.name()))
+            }
+        }
+        None => row.name() == item.name(),
+    }
+}
+
+/// Rows of a module page, listed items or `pub use` statements
+fn rows(page: &ModuleItems, reexports: bool) -> impl Iterator<Item = &ModuleItem> {
+    page.sections()
+        .iter()
+        .filter(move |section| (section.title() == "Re-exports") == reexports)
+        .flat_map(|section| section.items())
+}
+
+/// A `pub use` statement of a module page
+#[derive(Debug, PartialEq)]
+struct Reexport {
+    /// Full path of the re-exported item, or of the module for glob re-exports
+    source: String,
+    /// Name the item is exported under, `*` for glob re-exports
+    name: String,
+}
+
+/// Reads a statement such as `pub use crate::io::AsyncReadExt;` found in `module`, resolving
+/// `crate`, `self` and `super` against the crate.
+fn parse_reexport(crate_ident: &str, module: &str, statement: &str) -> Option<Reexport> {
+    let statement = statement
+        .trim()
+        .strip_prefix("pub use ")?
+        .trim_end_matches(';')
+        .trim();
+    let (path, alias) = match statement.split_once(" as ") {
+        Some((path, alias)) => (path.trim(), Some(alias.trim())),
+        None => (statement, None),
+    };
+
+    let mut segments: Vec<&str> = Vec::new();
+    let mut module_segments: Vec<&str> = std::iter::once(crate_ident)
+        .chain(module.split("::").filter(|segment| !segment.is_empty()))
+        .collect();
+    for (i, segment) in path.trim_start_matches("::").split("::").enumerate() {
+        match segment {
+            "crate" if i == 0 => segments.push(crate_ident),
+            "self" if i == 0 => segments.extend(&module_segments),
+            "super" => {
+                module_segments.pop();
+                segments = module_segments.clone();
+            }
+            segment => segments.push(segment),
+        }
+    }
+
+    let last = segments.pop()?;
+    let (source, name) = match (last, alias) {
+        ("*", _) => (segments.join("::"), "*".to_string()),
+        (last, alias) => {
+            segments.push(last);
+            (segments.join("::"), alias.unwrap_or(last).to_string())
+        }
+    };
+    Some(Reexport { source, name })
+}
+
+/// Suggestions for each candidate: where it is documented, and where the crate root or a
+/// prelude re-exports it. Items only documented for docs.rs (`cfg(docsrs)`) are left out.
+fn suggestions(
+    crate_ident: &str,
+    candidates: &[&SearchResult],
+    pages: &BTreeMap<String, ModuleItems>,
+) -> Vec<ImportSuggestion> {
+    let mut suggestions = Vec::new();
+
+    for candidate in candidates {
+        let parent = parent_module(crate_ident, candidate);
+        let listing = pages
+            .get(parent)
+            .and_then(|page| rows(page, false).find(|row| row_lists(row, candidate)));
+        if listing.is_some_and(|row| row.docs_only()) {
+            debug!(
+                "Skipping {}, which only exists on docs.rs",
+                candidate.path()
+            );
+            continue;
+        }
+        let features = listing
+            .map(|row| row.required_features().to_vec())
+            .unwrap_or_default();
+
+        let suggest = |path: String, source: ImportSource, extra: &[String]| {
+            let mut required_features = features.clone();
+            for feature in extra {
+                if !required_features.contains(feature) {
+                    required_features.push(feature.clone());
+                }
+            }
+            suggestion(candidate, path, source, required_features)
+        };
+
+        if is_prelude(parent) {
+            let path = candidate.path().rsplit_once("::").unwrap_or_default().0;
+            suggestions.push(suggest(path.to_string(), ImportSource::Prelude, &[]));
+        } else {
+            suggestions.push(suggest(
+                candidate.path().to_string(),
+                ImportSource::Documented,
+                &[],
+            ));
+        }
+
+        for (module, page) in pages {
+            let module_path = full_module(crate_ident, module);
+            for row in rows(page, true) {
+                let Some(reexport) = parse_reexport(crate_ident, module, row.name()) else {
+                    continue;
+                };
+                let reexported = if reexport.name == "*" {
+                    reexport.source == full_module(crate_ident, parent)
+                } else {
+                    let local = reexport.source.starts_with(&format!("{}::", crate_ident));
+                    let same_name = reexport.source.rsplit("::").next() == Some(candidate.name());
+                    (reexport.source == candidate.path() || (!local && same_name))
+                        && row_lists(row, candidate)
+                };
+                if !reexported {
+                    continue;
+                }
+
+                if is_prelude(module) {
+                    suggestions.push(suggest(
+                        module_path.clone(),
+                        ImportSource::Prelude,
+                        row.required_features(),
+                    ));
+                } else if reexport.name != "*" {
+                    suggestions.push(suggest(
+                        format!("{}::{}", module_path, reexport.name),
+                        ImportSource::Reexport,
+                        row.required_features(),
+                    ));
+                }
+            }
+        }
+    }
+
+    suggestions.sort_by_key(|suggestion| {
+        (
+            suggestion.source == ImportSource::Prelude,
+            suggestion.path.split("::").count(),
+        )
+    });
+    let mut seen = Vec::new();
+    suggestions.retain(|suggestion| {
+        let new = !seen.contains(&suggestion.statement);
+        seen.push(suggestion.statement.clone());
+        new
+    });
+    suggestions
+}
+
+fn suggestion(
+    item: &SearchResult,
+    path: String,
+    source: ImportSource,
+    required_features: Vec<String>,
+) -> ImportSuggestion {
+    let target = match source {
+        ImportSource::Prelude => format!("{}::*", path),
+        _ => path.clone(),
+    };
+    let comment = match required_features.as_slice() {
+        [] => String::new(),
+        [feature] => format!(" // requires feature \"{}\"", feature),
+        features => format!(
+            " // requires features {}",
+            features
+                .iter()
+                .map(|feature| format!("\"{}\"", feature))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    };
+
+    ImportSuggestion {
+        statement: format!("use {};{}", target, comment),
+        path,
+        kind: item.kind().to_string(),
+        source,
+        required_features,
+        doc_link: item.doc_link().to_string(),
+    }
+}
+
+impl Tool for SuggestImportsTool {
+    fn name(&self) -> String {
+        "suggest_imports".to_string()
+    }
+
+    fn description(&self) -> String {
+        "Suggest the use statement for an item of a crate, e.g. AsyncReadExt in tokio gives \
+        `use tokio::io::AsyncReadExt; // requires feature \"io-util\"`. Considers re-exports \
+        at the crate root and prelude modules, and names the Cargo features the import needs. \
+        For a method or variant, the type or trait it belongs to is imported. The first \
+        suggestion is the one to use."
+            .to_string()
+    }
+
+    fn input_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "crate_name": {
+                    "type": "string",
+                    "description": "Name of the crate defining the item"
+                },
+                "item": {
+                    "type": "string",
+                    "description": "Name of the item, e.g. AsyncReadExt, optionally with its module path, e.g. io::AsyncReadExt"
+                },
+                "version": {
+                    "type": "string",
+                    "description": "Optional version of the crate (defaults to latest)"
+                },
+                "registry": registry::registry_schema()
+            },
+            "required": ["crate_name", "item"]
+        })
+    }
+
+    fn call(&self, input: Option<serde_json::Value>) -> Result<CallToolResponse> {
+        let args: SuggestImportsParams = error::parse_args(input)?;
+        config::current().ensure_crate_allowed(&args.crate_name)?;
+        let suggestions = runtime::blocking(|| {
+            registry::in_registry(&args.crate_name, args.registry.as_deref(), || {
+                self.suggest_imports(&args.crate_name, &args.item, args.version.as_deref())
+            })
+        })?;
+
+        Ok(CallToolResponse {
+            content: vec![ToolResponseContent::Text {
+                text: serde_json::to_string_pretty(&suggestions)?,
+            }],
+            is_error: None,
+            meta: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::search_in_crate::parse_search_index;
+    use pretty_assertions::assert_eq;
+    use std::fs;
+
+    const ROOT: &str = "https://docs.rs/demo/0.1.0/demo/";
+
+    fn load_index() -> Result<Vec<SearchResult>> {
+        let js = fs::read_to_string("test-data/suggest_imports/search-index.js")?;
+        parse_search_index(&js, "demo", "https://docs.rs/demo/0.1.0/")
+    }
+
+    fn load_pages() -> Result<BTreeMap<String, ModuleItems>> {
+        let mut pages = BTreeMap::new();
+        for (module, file) in [
+            ("", "root"),
+            ("io", "io"),
+            ("prelude", "prelude"),
+            ("runtime", "runtime"),
+            ("task", "task"),
+        ] {
+            let html = fs::read_to_string(format!("test-data/suggest_imports/{}.html", file))?;
+            let url = module_url(ROOT, module);
+            let page = parse_module_page("demo", "0.1.0", module, &url, &html)?;
+            pages.insert(module.to_string(), page);
+        }
+        Ok(pages)
+    }
+
+    fn statements(item: &str) -> Result<(Vec<String>, Option<String>)> {
+        let index = load_index()?;
+        let (candidates, note) = candidates(&normalize_query("demo", item), &index);
+        let statements = suggestions("demo", &candidates, &load_pages()?)
+            .iter()
+            .map(|suggestion| suggestion.statement().to_string())
+            .collect();
+        Ok((statements, note))
+    }
+
+    #[test]
+    fn test_suggestions() -> Result<()> {
+        assert_eq!(
+            statements("AsyncReadExt")?.0,
+            vec![
+                "use demo::io::AsyncReadExt; // requires feature \"io-util\"",
+                "use demo::prelude::*; // requires feature \"io-util\"",
+            ]
+        );
+        assert_eq!(
+            statements("Runtime")?.0,
+            vec![
+                "use demo::Runtime; // requires feature \"rt\"",
+                "use demo::runtime::Runtime; // requires feature \"rt\"",
+            ],
+            "The root re-export is the shortest path"
+        );
+        assert_eq!(
+            statements("use demo::task::spawn;")?.0,
+            vec!["use demo::task::spawn; // requires feature \"rt\""]
+        );
+        assert_eq!(
+            statements("spawn")?.0,
+            vec![
+                "use demo::spawn; // requires feature \"rt\"",
+                "use demo::task::spawn; // requires feature \"rt\"",
+            ]
+        );
+        assert_eq!(
+            statements("select")?.0,
+            vec!["use demo::select; // requires feature \"macros\""]
+        );
+        assert_eq!(
+            statements("Encode")?.0,
+            vec![
+                "use demo::Encode;",
+                "use demo::Encode; // requires feature \"derive\"",
+                "use demo::prelude::*;",
+            ],
+            "The derive macro needs a feature the trait of the same name doesn't"
+        );
+        assert!(statements("UnixShim")?.0.is_empty());
+        assert!(statements("Missing")?.0.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_member_imports_parent() -> Result<()> {
+        let (statements, note) = statements("AsyncReadExt::read_to_end")?;
+        assert_eq!(
+            statements[0],
+            "use demo::io::AsyncReadExt; // requires feature \"io-util\""
+        );
+        assert_eq!(
+            note.as_deref(),
+            Some("AsyncReadExt::read_to_end is a method of demo::io::AsyncReadExt, which is imported instead")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_reexport() {
+        let reexport = |module, statement| parse_reexport("demo", module, statement);
+        assert_eq!(
+            reexport("", "pub use crate::runtime::Runtime;"),
+            Some(Reexport {
+                source: "demo::runtime::Runtime".to_string(),
+                name: "Runtime".to_string(),
+            })
+        );
+        assert_eq!(
+            reexport("net", "pub use self::tcp::TcpStream as Stream;"),
+            Some(Reexport {
+                source: "demo::net::tcp::TcpStream".to_string(),
+                name: "Stream".to_string(),
+            })
+        );
+        assert_eq!(
+            reexport("io::util", "pub use super::*;"),
+            Some(Reexport {
+                source: "demo::io".to_string(),
+                name: "*".to_string(),
+            })
+        );
+        assert_eq!(
+            reexport("", "pub use demo_macros::main;"),
+            Some(Reexport {
+                source: "demo_macros::main".to_string(),
+                name: "main".to_string(),
+            })
+        );
+        assert_eq!(reexport("", "extern crate alloc;"), None);
+    }
+}
@@ -0,0 +1,174 @@
+//! Summaries of descriptions too long to be useful in a tool response, such as the README of
+//! diesel at tens of KB. Past `summary.threshold_chars` the description is replaced by a
+//! summary and the full text is kept as a resource the client can read when it needs more.
+//!
+//! Summaries take the first paragraph and the section headings, or come from an
+//! OpenAI-compatible chat completions endpoint when `summary.endpoint` is set, e.g. a local
+//! model served by Ollama. Authentication for the endpoint goes in `[http.host_headers]`.
+
+use anyhow::{anyhow, Context, Result};
+use serde_json::json;
+use tracing::warn;
+
+#[cfg(feature = "native")]
+use super::{http, runtime};
+use crate::config::{self, SummaryConfig};
+use crate::resources::{self, Kind};
+
+/// Replaces `text` with a summary when it is over the configured threshold, keeping the full
+/// text as a resource titled `title`. Returns the URI of the full text when summarized.
+pub(crate) fn shorten(title: &str, text: &mut String) -> Option<String> {
+    let config = config::current();
+    let settings = config.summary();
+    let length = text.chars().count();
+    if length <= settings.threshold_chars()? {
+        return None;
+    }
+
+    let summary = match settings.endpoint() {
+        #[cfg(feature = "native")]
+        Some(endpoint) => runtime::block_on(ask(endpoint, settings, text)).unwrap_or_else(|e| {
+            warn!(
+                "Summarizing with {} failed, using the first paragraph instead: {:#}",
+                endpoint, e
+            );
+            outline(text, settings.max_chars())
+        }),
+        // Asking the endpoint blocks, which only native builds can do
+        _ => outline(text, settings.max_chars()),
+    };
+    let full_text = std::mem::replace(text, summary);
+    let uri = resources::keep(Kind::FullText, title.to_string(), vec![full_text]).pop()?;
+    text.push_str(&format!(
+        "\n\n[Summarized from {} characters. The full text is at {}]",
+        length, uri
+    ));
+    Some(uri)
+}
+
+/// The first paragraph followed by the headings, as Markdown, kept under `max_chars`.
+fn outline(text: &str, max_chars: usize) -> String {
+    let mut in_code = false;
+    let mut paragraph: Vec<&str> = Vec::new();
+    let mut paragraph_done = false;
+    let mut headings = Vec::new();
+    for line in text.lines().map(str::trim) {
+        if line.starts_with("```") {
+            in_code = !in_code;
+        } else if in_code {
+            continue;
+        } else if line.starts_with('#') {
+            headings.push(line);
+            paragraph_done |= !paragraph.is_empty();
+        } else if line.is_empty() {
+            paragraph_done |= !paragraph.is_empty();
+        } else if !paragraph_done && !is_decoration(line) {
+            paragraph.push(line);
+        }
+    }
+
+    let mut summary = truncate(&paragraph.join("\n"), max_chars);
+    let mut length = summary.chars().count();
+    for (i, heading) in headings.into_iter().enumerate() {
+        let separator = match (i, summary.is_empty()) {
+            (_, true) => "",
+            (0, false) => "\n\n",
+            _ => "\n",
+        };
+        length += separator.len() + heading.chars().count();
+        if length > max_chars {
+            break;
+        }
+        summary.push_str(separator);
+        summary.push_str(heading);
+    }
+    summary
+}
+
+/// Badges, images and raw HTML that open many READMEs without saying anything
+fn is_decoration(line: &str) -> bool {
+    line.starts_with("[![") || line.starts_with("![") || line.starts_with('<')
+}
+
+fn truncate(text: &str, max_chars: usize) -> String {
+    match text.char_indices().nth(max_chars.saturating_sub(1)) {
+        Some((end, _)) if text.chars().count() > max_chars => format!("{}…", &text[..end]),
+        _ => text.to_string(),
+    }
+}
+
+/// Asks the chat completions `endpoint` for a summary.
+#[cfg(feature = "native")]
+async fn ask(endpoint: &str, settings: &SummaryConfig, text: &str) -> Result<String> {
+    let request = json!({
+        "model": settings.model(),
+        "messages": [
+            {
+                "role": "system",
+                "content": format!(
+                    "Summarize this Rust crate documentation in at most {} characters of \
+                     Markdown. Say what the item is for, then list its main sections.",
+                    settings.max_chars()
+                )
+            },
+            { "role": "user", "content": text }
+        ]
+    });
+    let response = http::shared()?.post_json(endpoint, &request).await?;
+    if !response.status().is_success() {
+        return Err(anyhow!("The endpoint answered {}", response.status()));
+    }
+    let body: serde_json::Value = serde_json::from_str(&response.text().await?)
+        .context("The endpoint didn't answer with JSON")?;
+    let summary = body["choices"][0]["message"]["content"]
+        .as_str()
+        .map(str::trim)
+        .filter(|summary| !summary.is_empty())
+        .ok_or_else(|| anyhow!("The endpoint answered without a summary"))?;
+    Ok(truncate(summary, settings.max_chars()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_outline() {
+        let readme = "[![Build](https://ci.example/badge.svg)](https://ci.example)\n\n\
+                      # Diesel\n\n\
+                      A safe, extensible ORM\nand query builder.\n\n\
+                      More text that isn't part of the summary.\n\n\
+                      ## Getting started\n\n\
+                      ```toml\n# not a heading\n```\n\n\
+                      ## Contributing\n";
+        assert_eq!(
+            outline(readme, 200),
+            "A safe, extensible ORM\nand query builder.\n\n# Diesel\n## Getting started\n## Contributing"
+        );
+        assert_eq!(outline(readme, 10), "A safe, e…", "Kept under max_chars");
+    }
+
+    #[test]
+    fn test_shorten() {
+        let config =
+            Config::from_toml("[summary]\nthreshold_chars = 100\nmax_chars = 50\n").unwrap();
+        config::scoped(config, || {
+            let mut short = "Fits.".to_string();
+            assert_eq!(shorten("Short", &mut short), None);
+            assert_eq!(short, "Fits.");
+
+            let full = format!("First paragraph.\n\n{}", "Details. ".repeat(20));
+            let mut text = full.clone();
+            let uri = shorten("Docs of demo", &mut text).expect("summarized");
+            assert!(
+                text.starts_with("First paragraph.\n\n[Summarized from"),
+                "{}",
+                text
+            );
+            assert!(text.contains(&uri));
+            assert_eq!(resources::read(&uri), Some(full));
+        });
+    }
+}
@@ -0,0 +1,333 @@
+use anyhow::{anyhow, Result};
+use mcp_sdk::{
+    tools::Tool,
+    types::{CallToolResponse, ToolResponseContent},
+};
+use scraper::Html;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tracing::debug;
+
+use super::get_struct_docs::{HtmlFetcher, HttpHtmlFetcher};
+use super::{docs_root, registry, runtime, selectors};
+use crate::{config, error};
+
+/// Platforms recognised in target triples, checked in order: Android triples also name Linux
+const PLATFORMS: [(&str, &str); 12] = [
+    ("android", "android"),
+    ("windows", "windows"),
+    ("darwin", "macos"),
+    ("ios", "ios"),
+    ("linux", "linux"),
+    ("freebsd", "freebsd"),
+    ("netbsd", "netbsd"),
+    ("openbsd", "openbsd"),
+    ("illumos", "illumos"),
+    ("fuchsia", "fuchsia"),
+    ("wasi", "wasi"),
+    ("none", "bare-metal"),
+];
+
+/// The targets docs.rs built a crate's documentation for
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SupportedTargets {
+    crate_name: String,
+    version: String,
+    /// Target documented by the pages without a target in their path
+    default_target: String,
+    /// Platforms among the targets, e.g. `windows` or `wasm`, for telling at a glance whether
+    /// platform-specific APIs are documented
+    platforms: Vec<String>,
+    targets: Vec<DocTarget>,
+}
+
+impl SupportedTargets {
+    pub fn crate_name(&self) -> &str {
+        &self.crate_name
+    }
+
+    pub fn version(&self) -> &str {
+        &self.version
+    }
+
+    pub fn default_target(&self) -> &str {
+        &self.default_target
+    }
+
+    pub fn platforms(&self) -> &[String] {
+        &self.platforms
+    }
+
+    pub fn targets(&self) -> &[DocTarget] {
+        &self.targets
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DocTarget {
+    triple: String,
+    platform: String,
+    /// Root of the crate's documentation as built for this target
+    doc_url: String,
+}
+
+impl DocTarget {
+    pub fn triple(&self) -> &str {
+        &self.triple
+    }
+
+    pub fn platform(&self) -> &str {
+        &self.platform
+    }
+
+    pub fn doc_url(&self) -> &str {
+        &self.doc_url
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct SupportedTargetsParams {
+    crate_name: String,
+    version: Option<String>,
+    registry: Option<String>,
+}
+
+pub struct SupportedTargetsTool {
+    html_fetcher: Box<dyn HtmlFetcher>,
+}
+
+impl SupportedTargetsTool {
+    pub fn new() -> Self {
+        Self {
+            html_fetcher: Box::new(HttpHtmlFetcher::new()),
+        }
+    }
+
+    fn supported_targets(
+        &self,
+        crate_name: &str,
+        version: Option<&str>,
+    ) -> Result<SupportedTargets> {
+        let version = version.unwrap_or("latest");
+        let root = docs_root::crate_root_blocking(crate_name, version)?;
+        let lib_name = docs_root::lib_name(&root);
+        let url = format!(
+            "{}/crate/{}/{}/menus/platforms/{}/",
+            config::current().sources().docs_rs_url(),
+            crate_name,
+            version,
+            lib_name
+        );
+        debug!("Fetching platforms menu: {}", url);
+        let html = self.html_fetcher.fetch_html_blocking(&url)?;
+
+        let triples = parse_targets(&Html::parse_fragment(&html))?;
+        if triples.is_empty() {
+            return Err(anyhow!(
+                "docs.rs lists no targets for {} {}; its documentation may have failed to build",
+                crate_name,
+                version
+            ));
+        }
+
+        Ok(targets_from_triples(crate_name, version, &root, triples))
+    }
+}
+
+impl Default for SupportedTargetsTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Target triples listed in the platforms menu of a docs.rs page, in menu order.
+fn parse_targets(document: &Html) -> Result<Vec<String>> {
+    let selector = selectors::parse("a.pure-menu-link", "platforms menu")?;
+
+    let mut triples: Vec<String> = Vec::new();
+    for link in document.select(&selector) {
+        let triple = link.text().collect::<String>().trim().to_string();
+        if !triple.is_empty() && !triples.contains(&triple) {
+            triples.push(triple);
+        }
+    }
+    Ok(triples)
+}
+
+/// Builds the listing from the menu's triples. docs.rs builds the default target first and
+/// lists it first; it is documented at the crate root, the others under `/{triple}/`.
+fn targets_from_triples(
+    crate_name: &str,
+    version: &str,
+    root: &str,
+    triples: Vec<String>,
+) -> SupportedTargets {
+    let lib_name = docs_root::lib_name(root);
+    let version_url = root
+        .trim_end_matches('/')
+        .strip_suffix(lib_name)
+        .unwrap_or(root);
+
+    let targets: Vec<DocTarget> = triples
+        .into_iter()
+        .enumerate()
+        .map(|(index, triple)| DocTarget {
+            platform: platform(&triple).to_string(),
+            doc_url: if index == 0 {
+                root.to_string()
+            } else {
+                format!("{}{}/{}/", version_url, triple, lib_name)
+            },
+            triple,
+        })
+        .collect();
+
+    let mut platforms: Vec<String> = Vec::new();
+    for target in &targets {
+        if !platforms.contains(&target.platform) {
+            platforms.push(target.platform.clone());
+        }
+    }
+
+    SupportedTargets {
+        crate_name: crate_name.to_string(),
+        version: version.to_string(),
+        default_target: targets[0].triple.clone(),
+        platforms,
+        targets,
+    }
+}
+
+/// Platform a target triple is for, e.g. `windows` for `x86_64-pc-windows-msvc`. Falls back to
+/// the triple's OS component for platforms not in [`PLATFORMS`].
+fn platform(triple: &str) -> &str {
+    if triple.starts_with("wasm") && !triple.contains("wasi") {
+        return "wasm";
+    }
+    let parts: Vec<&str> = triple.split('-').collect();
+    PLATFORMS
+        .iter()
+        .find(|(marker, _)| parts.iter().any(|part| part.starts_with(marker)))
+        .map(|(_, platform)| *platform)
+        .unwrap_or_else(|| parts.get(2).copied().unwrap_or(triple))
+}
+
+impl Tool for SupportedTargetsTool {
+    fn name(&self) -> String {
+        "supported_targets".to_string()
+    }
+
+    fn description(&self) -> String {
+        "List the targets a crate's documentation was built for on docs.rs, as offered by the \
+        platform menu. Tells whether Windows-, macOS- or wasm-specific APIs are documented for a \
+        crate like winapi or web-sys, which target the default pages show, and where the docs \
+        for each other target live."
+            .to_string()
+    }
+
+    fn input_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "crate_name": {
+                    "type": "string",
+                    "description": "Name of the crate to list the documented targets of"
+                },
+                "version": {
+                    "type": "string",
+                    "description": "Optional version of the crate (defaults to latest)"
+                },
+                "registry": registry::registry_schema()
+            },
+            "required": ["crate_name"]
+        })
+    }
+
+    fn call(&self, input: Option<serde_json::Value>) -> Result<CallToolResponse> {
+        let args: SupportedTargetsParams = error::parse_args(input)?;
+        config::current().ensure_crate_allowed(&args.crate_name)?;
+        let targets = runtime::blocking(|| {
+            registry::in_registry(&args.crate_name, args.registry.as_deref(), || {
+                self.supported_targets(&args.crate_name, args.version.as_deref())
+            })
+        })?;
+
+        Ok(CallToolResponse {
+            content: vec![ToolResponseContent::Text {
+                text: serde_json::to_string_pretty(&targets)?,
+            }],
+            is_error: None,
+            meta: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::fs;
+
+    fn load_triples() -> Vec<String> {
+        let html = fs::read_to_string("test-data/supported_targets/platforms.html")
+            .expect("Should be able to read test HTML file");
+        parse_targets(&Html::parse_fragment(&html)).expect("The menu should parse")
+    }
+
+    #[test]
+    fn test_parse_targets() {
+        assert_eq!(
+            load_triples(),
+            vec![
+                "x86_64-pc-windows-msvc",
+                "aarch64-pc-windows-msvc",
+                "i686-pc-windows-msvc",
+                "x86_64-unknown-linux-gnu",
+                "aarch64-linux-android",
+                "aarch64-apple-darwin",
+                "wasm32-unknown-unknown",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_targets_from_triples() {
+        let targets = targets_from_triples(
+            "demo",
+            "0.3.9",
+            "https://docs.rs/demo/0.3.9/demo/",
+            load_triples(),
+        );
+
+        assert_eq!(targets.default_target(), "x86_64-pc-windows-msvc");
+        assert_eq!(
+            targets.platforms(),
+            ["windows", "linux", "android", "macos", "wasm"]
+        );
+        assert_eq!(
+            targets.targets()[0].doc_url(),
+            "https://docs.rs/demo/0.3.9/demo/"
+        );
+        let wasm = targets
+            .targets()
+            .iter()
+            .find(|t| t.platform() == "wasm")
+            .expect("The wasm target should be listed");
+        assert_eq!(
+            wasm.doc_url(),
+            "https://docs.rs/demo/0.3.9/wasm32-unknown-unknown/demo/"
+        );
+    }
+
+    #[test]
+    fn test_platform() {
+        assert_eq!(platform("x86_64-pc-windows-gnu"), "windows");
+        assert_eq!(platform("aarch64-linux-android"), "android");
+        assert_eq!(platform("x86_64-unknown-linux-musl"), "linux");
+        assert_eq!(platform("aarch64-apple-ios"), "ios");
+        assert_eq!(platform("wasm32-unknown-unknown"), "wasm");
+        assert_eq!(platform("wasm32-wasip1"), "wasi");
+        assert_eq!(platform("thumbv7em-none-eabihf"), "bare-metal");
+        assert_eq!(platform("x86_64-unknown-redox"), "redox");
+    }
+}
@@ -0,0 +1,291 @@
+use anyhow::{anyhow, Result};
+use mcp_sdk::{
+    tools::Tool,
+    types::{CallToolResponse, ToolResponseContent},
+};
+use scraper::{ElementRef, Html, Node};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tracing::debug;
+
+use super::get_struct_docs::{code_header_text, impl_self_type, HtmlFetcher, HttpHtmlFetcher};
+use super::{registry, runtime, selectors, CrateItemsTool};
+use crate::{config, error};
+
+/// Types implementing a trait, as listed on the trait's documentation page
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TraitImplementors {
+    crate_name: String,
+    version: String,
+    trait_path: String,
+    doc_link: String,
+    /// Implementations for types of any crate, listed under "Implementors"
+    implementors: Vec<Implementor>,
+    /// Implementations for types from other crates, listed under "Implementations on Foreign
+    /// Types"
+    foreign_implementors: Vec<Implementor>,
+}
+
+impl TraitImplementors {
+    pub fn implementors(&self) -> &[Implementor] {
+        &self.implementors
+    }
+
+    pub fn foreign_implementors(&self) -> &[Implementor] {
+        &self.foreign_implementors
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Implementor {
+    /// The implementing type, e.g. `Box<T>`
+    type_name: String,
+    /// The full impl header, including any where clause
+    impl_header: String,
+    /// Link to the impl block on the trait page
+    link: Option<String>,
+}
+
+impl Implementor {
+    pub fn type_name(&self) -> &str {
+        &self.type_name
+    }
+
+    pub fn impl_header(&self) -> &str {
+        &self.impl_header
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct TraitImplementorsParams {
+    crate_name: String,
+    trait_path: String,
+    version: Option<String>,
+    registry: Option<String>,
+}
+
+pub struct TraitImplementorsTool {
+    html_fetcher: Box<dyn HtmlFetcher>,
+}
+
+impl TraitImplementorsTool {
+    pub fn new() -> Self {
+        Self {
+            html_fetcher: Box::new(HttpHtmlFetcher::new()),
+        }
+    }
+
+    fn list_implementors(
+        &self,
+        crate_name: &str,
+        trait_path: &str,
+        version: Option<&str>,
+    ) -> Result<TraitImplementors> {
+        let items = CrateItemsTool::new().scrape_items_blocking(crate_name, version)?;
+        let (kind, item) = items.find(trait_path)?;
+        if kind != "Traits" {
+            return Err(anyhow!(
+                "{} in crate {} is not a trait (found in {})",
+                item.name(),
+                crate_name,
+                kind
+            ));
+        }
+        debug!("Found trait {} at {}", item.name(), item.doc_link());
+
+        let html = self.html_fetcher.fetch_html_blocking(item.doc_link())?;
+        let document = Html::parse_document(&html);
+
+        Ok(TraitImplementors {
+            crate_name: items.crate_name().to_string(),
+            version: items.version().to_string(),
+            trait_path: item.name().to_string(),
+            doc_link: item.doc_link().to_string(),
+            implementors: parse_implementors(&document, item.doc_link())?,
+            foreign_implementors: parse_foreign_implementors(&document, item.doc_link())?,
+        })
+    }
+}
+
+impl Default for TraitImplementorsTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Parses the "Implementors" and "Auto implementors" lists of a trait page. Implementors from
+/// other crates are loaded by JavaScript and aren't part of the page.
+fn parse_implementors(document: &Html, page_url: &str) -> Result<Vec<Implementor>> {
+    let selector = selectors::parse(
+        "#implementors-list > details.implementors-toggle, #implementors-list > section.impl, \
+        #synthetic-implementors-list > details.implementors-toggle, \
+        #synthetic-implementors-list > section.impl",
+        "trait page",
+    )?;
+
+    Ok(document
+        .select(&selector)
+        .filter_map(|block| parse_implementor(block, page_url))
+        .collect())
+}
+
+/// Parses the impl blocks following the "Implementations on Foreign Types" heading. They
+/// aren't wrapped in a list, so they are read up to the next heading.
+fn parse_foreign_implementors(document: &Html, page_url: &str) -> Result<Vec<Implementor>> {
+    let selector = selectors::parse("h2#foreign-impls", "trait page")?;
+    let Some(heading) = document.select(&selector).next() else {
+        return Ok(Vec::new());
+    };
+
+    Ok(heading
+        .next_siblings()
+        .filter_map(ElementRef::wrap)
+        .take_while(|sibling| sibling.value().name() != "h2")
+        .filter_map(|block| parse_implementor(block, page_url))
+        .collect())
+}
+
+/// Reads an impl block, either a bare `section.impl` or one wrapped in a collapsible `details`.
+fn parse_implementor(block: ElementRef, page_url: &str) -> Option<Implementor> {
+    let header_selector = selectors::parse("h3.code-header", "trait page").ok()?;
+    let section_selector = selectors::parse("section.impl[id]", "trait page").ok()?;
+
+    let header = block.select(&header_selector).next()?;
+    let impl_header = code_header_text(header);
+    let type_name = impl_self_type(&header_text_without_where(header))?;
+
+    let anchor = if block.value().name() == "section" {
+        block.value().id()
+    } else {
+        block
+            .select(&section_selector)
+            .next()
+            .and_then(|section| section.value().id())
+    };
+
+    Some(Implementor {
+        type_name,
+        impl_header,
+        link: anchor.map(|anchor| format!("{}#{}", page_url, anchor)),
+    })
+}
+
+/// Header text with the `where` clause left out, so it can't run into the type name.
+fn header_text_without_where(header: ElementRef) -> String {
+    header
+        .descendants()
+        .filter_map(|node| match node.value() {
+            Node::Text(text) => Some((node, text)),
+            _ => None,
+        })
+        .filter(|(node, _)| {
+            !node.ancestors().any(|ancestor| {
+                ancestor
+                    .value()
+                    .as_element()
+                    .is_some_and(|element| element.classes().any(|class| class == "where"))
+            })
+        })
+        .map(|(_, text)| &**text)
+        .collect()
+}
+
+impl Tool for TraitImplementorsTool {
+    fn name(&self) -> String {
+        "trait_implementors".to_string()
+    }
+
+    fn description(&self) -> String {
+        "List the types implementing a trait, e.g. which types implement \
+        tokio::io::AsyncRead. Returns the implementors documented on the trait's page, \
+        including implementations on foreign types such as Box<T> or &[u8], with their impl \
+        headers and links. Implementations in other crates are not included."
+            .to_string()
+    }
+
+    fn input_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "crate_name": {
+                    "type": "string",
+                    "description": "Name of the crate defining the trait"
+                },
+                "trait_path": {
+                    "type": "string",
+                    "description": "Path of the trait within the crate, e.g. io::AsyncRead. \
+                        A bare name works when it is unique"
+                },
+                "version": {
+                    "type": "string",
+                    "description": "Optional version of the crate. Defaults to latest if not specified"
+                },
+                "registry": registry::registry_schema()
+            },
+            "required": ["crate_name", "trait_path"]
+        })
+    }
+
+    fn call(&self, input: Option<serde_json::Value>) -> Result<CallToolResponse> {
+        let args: TraitImplementorsParams = error::parse_args(input)?;
+        config::current().ensure_crate_allowed(&args.crate_name)?;
+        let implementors = runtime::blocking(|| {
+            registry::in_registry(&args.crate_name, args.registry.as_deref(), || {
+                self.list_implementors(&args.crate_name, &args.trait_path, args.version.as_deref())
+            })
+        })?;
+
+        Ok(CallToolResponse {
+            content: vec![ToolResponseContent::Text {
+                text: serde_json::to_string_pretty(&implementors)?,
+            }],
+            is_error: None,
+            meta: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::fs;
+
+    const PAGE_URL: &str = "https://docs.rs/demo/0.3.0/demo/io/trait.AsyncSource.html";
+
+    fn load() -> Html {
+        let html = fs::read_to_string("test-data/trait_implementors/trait-page.html")
+            .expect("Should be able to read test HTML file");
+        Html::parse_document(&html)
+    }
+
+    #[test]
+    fn test_parse_implementors() -> Result<()> {
+        let implementors = parse_implementors(&load(), PAGE_URL)?;
+        let types: Vec<&str> = implementors.iter().map(Implementor::type_name).collect();
+
+        assert_eq!(types, vec!["TcpStream", "Take<R>"]);
+        assert_eq!(
+            implementors[1].impl_header(),
+            "impl<R: AsyncSource> AsyncSource for Take<R>"
+        );
+        assert_eq!(
+            implementors[0].link.as_deref(),
+            Some(format!("{}#impl-AsyncSource-for-TcpStream", PAGE_URL).as_str())
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_foreign_implementors() -> Result<()> {
+        let implementors = parse_foreign_implementors(&load(), PAGE_URL)?;
+        let types: Vec<&str> = implementors.iter().map(Implementor::type_name).collect();
+
+        assert_eq!(types, vec!["&[u8]", "Box<T>"]);
+        assert_eq!(
+            implementors[1].impl_header(),
+            "impl<T> AsyncSource for Box<T> where T: AsyncSource + Unpin + ?Sized,"
+        );
+        Ok(())
+    }
+}
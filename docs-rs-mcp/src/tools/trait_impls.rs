@@ -0,0 +1,323 @@
+use std::collections::BTreeMap;
+
+use anyhow::{Context, Result};
+use mcp_sdk::{
+    tools::Tool,
+    types::{CallToolResponse, ToolResponseContent},
+};
+use scraper::Html;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tracing::debug;
+use url::Url;
+
+use super::get_source::{find_source_link, parse_line_span, SourceLink};
+use super::get_struct_docs::{parse_trait_impls, HtmlFetcher, HttpHtmlFetcher, TraitImpl};
+use super::{registry, runtime, CrateItemsTool};
+use crate::{config, error};
+
+/// A derive expands to a one-line impl pointing at the `#[derive]` attribute, which sits at most
+/// this many lines above the type's own source span
+const DERIVE_ATTRIBUTE_LINES: usize = 10;
+
+/// Every trait implementation of a type, with full impl signatures
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TypeTraitImpls {
+    crate_name: String,
+    version: String,
+    type_path: String,
+    doc_link: String,
+    impls: Vec<TraitImplDoc>,
+}
+
+impl TypeTraitImpls {
+    pub fn impls(&self) -> &[TraitImplDoc] {
+        &self.impls
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TraitImplDoc {
+    /// Last path segment of the trait, prefixed with `!` for negative impls
+    trait_name: String,
+    /// The whole impl header, e.g. `impl<T: Serialize> From<T> for Value`
+    signature: String,
+    origin: ImplOrigin,
+    /// Whether this is an `unsafe impl`, whose soundness the crate vouches for
+    is_unsafe: bool,
+    /// Doc comment on the impl block; for `unsafe impl`s usually the safety invariants
+    docs: Option<String>,
+    /// Signatures of the methods the impl defines; provided trait methods it doesn't override
+    /// aren't listed
+    methods: Vec<String>,
+    associated_types: BTreeMap<String, String>,
+    link: Option<String>,
+}
+
+impl TraitImplDoc {
+    pub fn trait_name(&self) -> &str {
+        &self.trait_name
+    }
+
+    pub fn signature(&self) -> &str {
+        &self.signature
+    }
+
+    pub fn origin(&self) -> ImplOrigin {
+        self.origin
+    }
+
+    pub fn methods(&self) -> &[String] {
+        &self.methods
+    }
+}
+
+/// Where an implementation comes from
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImplOrigin {
+    /// Written out in the crate
+    Manual,
+    /// Generated by `#[derive]`
+    Derived,
+    /// Implemented for all types meeting some bounds, e.g. `impl<T> From<T> for T`
+    Blanket,
+    /// Auto traits the compiler implements, e.g. `Send` or `Unpin`
+    Auto,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct TraitImplsParams {
+    crate_name: String,
+    type_path: String,
+    version: Option<String>,
+    registry: Option<String>,
+}
+
+pub struct TraitImplsTool {
+    html_fetcher: Box<dyn HtmlFetcher>,
+}
+
+impl TraitImplsTool {
+    pub fn new() -> Self {
+        Self {
+            html_fetcher: Box::new(HttpHtmlFetcher::new()),
+        }
+    }
+
+    fn list_impls(
+        &self,
+        crate_name: &str,
+        type_path: &str,
+        version: Option<&str>,
+    ) -> Result<TypeTraitImpls> {
+        let items = CrateItemsTool::new().scrape_items_blocking(crate_name, version)?;
+        let (kind, item) = items.find(type_path)?;
+        debug!("Found {} {} at {}", kind, item.name(), item.doc_link());
+
+        let html = self.html_fetcher.fetch_html_blocking(item.doc_link())?;
+
+        Ok(TypeTraitImpls {
+            crate_name: items.crate_name().to_string(),
+            version: items.version().to_string(),
+            type_path: item.name().to_string(),
+            doc_link: item.doc_link().to_string(),
+            impls: parse_impl_docs(item.doc_link(), &html)?,
+        })
+    }
+}
+
+impl Default for TraitImplsTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn parse_impl_docs(page_url: &str, html: &str) -> Result<Vec<TraitImplDoc>> {
+    let document = Html::parse_document(html);
+    let page = Url::parse(page_url).context(format!("Invalid item URL: {}", page_url))?;
+    // Without a source link, derives can't be told apart from manual impls
+    let item_source = find_source_link(page_url, html).ok();
+
+    let lists = [
+        ("trait-implementations-list", None),
+        ("synthetic-implementations-list", Some(ImplOrigin::Auto)),
+        ("blanket-implementations-list", Some(ImplOrigin::Blanket)),
+    ];
+    let mut impls = Vec::new();
+    for (list_id, origin) in lists {
+        for trait_impl in parse_trait_impls(&document, list_id)? {
+            let origin = origin.unwrap_or_else(|| {
+                if is_derived(&page, &trait_impl, item_source.as_ref()) {
+                    ImplOrigin::Derived
+                } else {
+                    ImplOrigin::Manual
+                }
+            });
+            impls.push(TraitImplDoc {
+                trait_name: trait_impl.trait_name().to_string(),
+                signature: trait_impl.header().to_string(),
+                origin,
+                is_unsafe: trait_impl.is_unsafe(),
+                docs: trait_impl.docs().map(str::to_string),
+                methods: trait_impl.methods().to_vec(),
+                associated_types: trait_impl.associated_types().iter().cloned().collect(),
+                link: trait_impl
+                    .anchor()
+                    .map(|anchor| format!("{}#{}", page_url, anchor)),
+            });
+        }
+    }
+    Ok(impls)
+}
+
+/// Whether an impl was generated by a derive: its source is a single line at the type's
+/// `#[derive]` attribute, just above or within the type's own source span.
+fn is_derived(page: &Url, trait_impl: &TraitImpl, item_source: Option<&SourceLink>) -> bool {
+    let (Some(item_source), Some(href)) = (item_source, trait_impl.source()) else {
+        return false;
+    };
+    let Ok(mut url) = page.join(href) else {
+        return false;
+    };
+    let Some(Ok((start, end))) = url.fragment().map(parse_line_span) else {
+        return false;
+    };
+    url.set_fragment(None);
+
+    url.as_str() == item_source.url
+        && start == end
+        && start + DERIVE_ATTRIBUTE_LINES >= item_source.start_line
+        && start <= item_source.end_line
+}
+
+impl Tool for TraitImplsTool {
+    fn name(&self) -> String {
+        "trait_impls".to_string()
+    }
+
+    fn description(&self) -> String {
+        "List every trait implementation of a type (struct, enum, union, ...) with its full \
+        impl signature, e.g. impl<T: Serialize> From<T> for Value, whether it is written \
+        out manually, derived, a blanket impl or an auto trait, and the methods and \
+        associated types it defines. unsafe impls are flagged and come with the doc comment \
+        explaining their safety invariants."
+            .to_string()
+    }
+
+    fn input_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "crate_name": {
+                    "type": "string",
+                    "description": "Name of the crate containing the type"
+                },
+                "type_path": {
+                    "type": "string",
+                    "description": "Path of the type within the crate, e.g. trace::TracerProviderBuilder. \
+                        A bare name works when it is unique"
+                },
+                "version": {
+                    "type": "string",
+                    "description": "Optional version of the crate. Defaults to latest if not specified"
+                },
+                "registry": registry::registry_schema()
+            },
+            "required": ["crate_name", "type_path"]
+        })
+    }
+
+    fn call(&self, input: Option<serde_json::Value>) -> Result<CallToolResponse> {
+        let args: TraitImplsParams = error::parse_args(input)?;
+        config::current().ensure_crate_allowed(&args.crate_name)?;
+        let impls = runtime::blocking(|| {
+            registry::in_registry(&args.crate_name, args.registry.as_deref(), || {
+                self.list_impls(&args.crate_name, &args.type_path, args.version.as_deref())
+            })
+        })?;
+
+        Ok(CallToolResponse {
+            content: vec![ToolResponseContent::Text {
+                text: serde_json::to_string_pretty(&impls)?,
+            }],
+            is_error: None,
+            meta: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::fs;
+
+    #[test]
+    fn test_parse_impl_docs() -> Result<()> {
+        let html = fs::read_to_string(
+            "test-data/get_struct_docs/opentelemetry-sdk-0.28.0-tracer-provider-builder.html",
+        )?;
+        let page_url = "https://docs.rs/opentelemetry_sdk/0.28.0/opentelemetry_sdk/trace/struct.TracerProviderBuilder.html";
+        let impls = parse_impl_docs(page_url, &html)?;
+        let origin = |name: &str| {
+            impls
+                .iter()
+                .find(|i| i.trait_name() == name)
+                .map(TraitImplDoc::origin)
+        };
+
+        assert_eq!(origin("Debug"), Some(ImplOrigin::Derived));
+        assert_eq!(origin("Default"), Some(ImplOrigin::Derived));
+        assert_eq!(origin("Send"), Some(ImplOrigin::Auto));
+        assert_eq!(origin("!RefUnwindSafe"), Some(ImplOrigin::Auto));
+        assert_eq!(origin("From"), Some(ImplOrigin::Blanket));
+
+        let default = impls.iter().find(|i| i.trait_name() == "Default").unwrap();
+        assert_eq!(
+            default.signature(),
+            "impl Default for TracerProviderBuilder"
+        );
+        assert_eq!(
+            default.methods(),
+            &["fn default() -> TracerProviderBuilder".to_string()]
+        );
+
+        let try_from = impls.iter().find(|i| i.trait_name() == "TryFrom").unwrap();
+        assert_eq!(
+            try_from.signature(),
+            "impl<T, U> TryFrom<U> for T where U: Into<T>,"
+        );
+        assert_eq!(
+            try_from.associated_types.get("Error").map(String::as_str),
+            Some("Infallible")
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_unsafe_impl_docs() -> Result<()> {
+        let html = fs::read_to_string("test-data/trait_impls/unsafe-impls.html")?;
+        let impls = parse_impl_docs(
+            "https://docs.rs/demo/0.1.0/demo/sync/struct.AtomicCell.html",
+            &html,
+        )?;
+        let flagged: Vec<(&str, ImplOrigin, bool, bool)> = impls
+            .iter()
+            .map(|i| (i.trait_name(), i.origin(), i.is_unsafe, i.docs.is_some()))
+            .collect();
+
+        assert_eq!(
+            flagged,
+            vec![
+                ("Debug", ImplOrigin::Derived, false, false),
+                ("Send", ImplOrigin::Manual, true, true),
+                ("Sync", ImplOrigin::Manual, true, true),
+                ("Unpin", ImplOrigin::Manual, false, false),
+                ("GlobalAlloc", ImplOrigin::Manual, true, false),
+            ]
+        );
+        Ok(())
+    }
+}
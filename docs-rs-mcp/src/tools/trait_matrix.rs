@@ -0,0 +1,397 @@
+use std::collections::HashMap;
+
+use anyhow::{anyhow, Context, Result};
+use mcp_sdk::{
+    tools::Tool,
+    types::{CallToolResponse, ToolResponseContent},
+};
+use scraper::Html;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tracing::debug;
+use url::Url;
+
+#[cfg(test)]
+use super::get_struct_docs::StdDocsFetcher;
+use super::get_struct_docs::{parse_all_trait_impls, HtmlFetcher, HttpHtmlFetcher, TraitImpl};
+use super::{registry, runtime, CrateItemsTool};
+use crate::{config, error};
+
+/// Standard library crates, documented on doc.rust-lang.org instead of docs.rs
+const STD_CRATES: [&str; 3] = ["std", "core", "alloc"];
+const STD_DOCS_URL: &str = "https://doc.rust-lang.org/stable";
+
+/// Which of a set of types implement which of a set of traits
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TraitMatrix {
+    traits: Vec<String>,
+    types: Vec<TypeRow>,
+}
+
+impl TraitMatrix {
+    pub fn types(&self) -> &[TypeRow] {
+        &self.types
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TypeRow {
+    /// The type as requested, e.g. `bytes::Bytes`
+    type_path: String,
+    doc_link: Option<String>,
+    /// One cell per requested trait, in the same order
+    implements: Vec<ImplCell>,
+    /// Why the type's page couldn't be read; its cells are empty then
+    error: Option<String>,
+}
+
+impl TypeRow {
+    pub fn implements(&self) -> &[ImplCell] {
+        &self.implements
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ImplCell {
+    trait_name: String,
+    implemented: bool,
+    /// Link to the impl block, including negative impls such as `impl !Send`
+    link: Option<String>,
+}
+
+impl ImplCell {
+    pub fn trait_name(&self) -> &str {
+        &self.trait_name
+    }
+
+    pub fn implemented(&self) -> bool {
+        self.implemented
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct TraitMatrixParams {
+    types: Vec<String>,
+    traits: Vec<String>,
+    /// Versions to use per crate, defaulting to latest
+    #[serde(default)]
+    versions: HashMap<String, String>,
+}
+
+pub struct TraitMatrixTool {
+    html_fetcher: Box<dyn HtmlFetcher>,
+}
+
+impl TraitMatrixTool {
+    pub fn new() -> Self {
+        Self {
+            html_fetcher: Box::new(HttpHtmlFetcher::new()),
+        }
+    }
+
+    /// Creates an instance reading std docs from the snapshot of the given rustdoc version.
+    #[cfg(test)]
+    fn new_with_std_snapshot(rustdoc_version: &str) -> Self {
+        Self {
+            html_fetcher: Box::new(StdDocsFetcher::new(rustdoc_version)),
+        }
+    }
+
+    fn build_matrix(
+        &self,
+        types: &[String],
+        traits: &[String],
+        versions: &HashMap<String, String>,
+    ) -> TraitMatrix {
+        let types = types
+            .iter()
+            .map(|type_path| {
+                let result = self.type_page(type_path, versions).and_then(|url| {
+                    let html = self.html_fetcher.fetch_html_blocking(&url)?;
+                    let implements = trait_cells(&url, &html, traits)?;
+                    Ok((url, implements))
+                });
+                match result {
+                    Ok((url, implements)) => TypeRow {
+                        type_path: type_path.clone(),
+                        doc_link: Some(url),
+                        implements,
+                        error: None,
+                    },
+                    Err(e) => TypeRow {
+                        type_path: type_path.clone(),
+                        doc_link: None,
+                        implements: Vec::new(),
+                        error: Some(format!("{:#}", e)),
+                    },
+                }
+            })
+            .collect();
+
+        TraitMatrix {
+            traits: traits.to_vec(),
+            types,
+        }
+    }
+
+    /// Finds the documentation page of a type given as `crate::path::Type`.
+    fn type_page(&self, type_path: &str, versions: &HashMap<String, String>) -> Result<String> {
+        let (crate_name, item_path) = type_path
+            .split_once("::")
+            .ok_or_else(|| anyhow!("Type {} should be written as crate::path::Type", type_path))?;
+        let version = versions.get(crate_name).map(String::as_str);
+
+        if STD_CRATES.contains(&crate_name) {
+            let all_items_url = format!("{}/{}/all.html", STD_DOCS_URL, crate_name);
+            let html = self.html_fetcher.fetch_html_blocking(&all_items_url)?;
+            let root_url = format!("{}/{}/", STD_DOCS_URL, crate_name);
+            let items =
+                CrateItemsTool::new().parse_items(crate_name, "stable", &root_url, &html)?;
+            let (_, item) = items.find(item_path)?;
+            return Ok(Url::parse(&all_items_url)?.join(item.path())?.to_string());
+        }
+
+        config::current().ensure_crate_allowed(crate_name)?;
+        let items = registry::in_registry(crate_name, None, || {
+            CrateItemsTool::new().scrape_items_blocking(crate_name, version)
+        })?;
+        let (kind, item) = items.find(item_path)?;
+        debug!("Found {} {} at {}", kind, item.name(), item.doc_link());
+        Ok(item.doc_link().to_string())
+    }
+}
+
+impl Default for TraitMatrixTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Last path segment of a trait without generics: `std::convert::AsRef<[u8]>` -> `AsRef`
+fn trait_key(trait_path: &str) -> &str {
+    let without_generics = trait_path.split('<').next().unwrap_or(trait_path);
+    without_generics
+        .rsplit("::")
+        .next()
+        .unwrap_or(without_generics)
+        .trim()
+}
+
+fn trait_cells(page_url: &str, html: &str, traits: &[String]) -> Result<Vec<ImplCell>> {
+    let document = Html::parse_document(html);
+    let impls = parse_all_trait_impls(&document).context(format!(
+        "Failed to parse trait implementations of {}",
+        page_url
+    ))?;
+    let link = |trait_impl: &TraitImpl| {
+        trait_impl
+            .anchor()
+            .map(|anchor| format!("{}#{}", page_url, anchor))
+    };
+
+    Ok(traits
+        .iter()
+        .map(|trait_path| {
+            let key = trait_key(trait_path);
+            let negative = format!("!{}", key);
+            let positive = impls.iter().find(|i| i.trait_name() == key);
+            let found = positive.or_else(|| impls.iter().find(|i| i.trait_name() == negative));
+            ImplCell {
+                trait_name: trait_path.clone(),
+                implemented: positive.is_some(),
+                link: found.and_then(link),
+            }
+        })
+        .collect())
+}
+
+impl Tool for TraitMatrixTool {
+    fn name(&self) -> String {
+        "trait_matrix".to_string()
+    }
+
+    fn description(&self) -> String {
+        "Compare which traits a set of types implement. Takes type paths such as \
+        bytes::Bytes or std::string::String and trait names such as Clone or AsRef, \
+        and returns a matrix of which types implement which traits, with links to the \
+        impl blocks. Includes auto trait and blanket implementations."
+            .to_string()
+    }
+
+    fn input_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "types": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Types to compare, written crate::path::Type. std, core and alloc types are looked up on doc.rust-lang.org"
+                },
+                "traits": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Traits to check, e.g. Clone, Send or std::io::Write. Generic parameters are ignored"
+                },
+                "versions": {
+                    "type": "object",
+                    "additionalProperties": { "type": "string" },
+                    "description": "Optional crate versions keyed by crate name, e.g. {\"bytes\": \"1.9.0\"}. Defaults to latest"
+                }
+            },
+            "required": ["types", "traits"]
+        })
+    }
+
+    fn call(&self, input: Option<serde_json::Value>) -> Result<CallToolResponse> {
+        let args: TraitMatrixParams = error::parse_args(input)?;
+        if args.types.is_empty() || args.traits.is_empty() {
+            return Err(anyhow!("types and traits must not be empty"));
+        }
+        let matrix =
+            runtime::blocking(|| Ok(self.build_matrix(&args.types, &args.traits, &args.versions)))?;
+
+        Ok(CallToolResponse {
+            content: vec![ToolResponseContent::Text {
+                text: serde_json::to_string_pretty(&matrix)?,
+            }],
+            is_error: None,
+            meta: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::fs;
+
+    fn implemented(cells: &[ImplCell]) -> Vec<(&str, bool)> {
+        cells
+            .iter()
+            .map(|cell| (cell.trait_name(), cell.implemented()))
+            .collect()
+    }
+
+    #[test]
+    fn test_trait_cells() -> Result<()> {
+        let traits: Vec<String> = ["Clone", "std::fmt::Debug", "Send", "From<T>", "Serialize"]
+            .iter()
+            .map(|t| t.to_string())
+            .collect();
+
+        let builder_url = "https://docs.rs/opentelemetry_sdk/0.28.0/opentelemetry_sdk/trace/struct.TracerProviderBuilder.html";
+        let html = fs::read_to_string(
+            "test-data/get_struct_docs/opentelemetry-sdk-0.28.0-tracer-provider-builder.html",
+        )?;
+        let builder = trait_cells(builder_url, &html, &traits)?;
+        assert_eq!(
+            implemented(&builder),
+            vec![
+                ("Clone", false),
+                ("std::fmt::Debug", true),
+                ("Send", true),
+                ("From<T>", true),
+                ("Serialize", false),
+            ]
+        );
+        assert_eq!(
+            builder[1].link.as_deref(),
+            Some(format!("{}#impl-Debug-for-TracerProviderBuilder", builder_url).as_str())
+        );
+
+        let html = fs::read_to_string("test-data/surreal-struct-surrealdb.html")?;
+        let surreal = trait_cells(
+            "https://docs.rs/surrealdb/latest/surrealdb/struct.Surreal.html",
+            &html,
+            &traits,
+        )?;
+        assert!(surreal[0].implemented(), "Surreal implements Clone");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_std_type_page() -> Result<()> {
+        for version in StdDocsFetcher::versions()? {
+            let tool = TraitMatrixTool::new_with_std_snapshot(&version);
+            let versions = HashMap::new();
+
+            assert_eq!(
+                tool.type_page("std::string::String", &versions)?,
+                "https://doc.rust-lang.org/stable/std/string/struct.String.html",
+                "rustdoc {}",
+                version
+            );
+            assert_eq!(
+                tool.type_page("std::Rc", &versions)?,
+                "https://doc.rust-lang.org/stable/std/rc/struct.Rc.html",
+                "rustdoc {}",
+                version
+            );
+            assert!(
+                tool.type_page("std::HashMap", &versions).is_err(),
+                "HashMap is documented under two paths in rustdoc {}",
+                version
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_std_matrix() -> Result<()> {
+        let types = ["std::string::String".to_string(), "std::rc::Rc".to_string()];
+        let traits: Vec<String> = ["Clone", "Send", "Copy", "From<T>"]
+            .iter()
+            .map(|t| t.to_string())
+            .collect();
+
+        for version in StdDocsFetcher::versions()? {
+            let tool = TraitMatrixTool::new_with_std_snapshot(&version);
+            let matrix = tool.build_matrix(&types, &traits, &HashMap::new());
+            let [string, rc] = matrix.types() else {
+                panic!("Expected a row per type for rustdoc {}", version);
+            };
+
+            assert_eq!(string.error, None, "rustdoc {}", version);
+            assert_eq!(
+                implemented(string.implements()),
+                vec![
+                    ("Clone", true),
+                    ("Send", true),
+                    ("Copy", false),
+                    ("From<T>", true),
+                ],
+                "rustdoc {}",
+                version
+            );
+            assert_eq!(
+                implemented(rc.implements()),
+                vec![
+                    ("Clone", true),
+                    ("Send", false),
+                    ("Copy", false),
+                    ("From<T>", true),
+                ],
+                "rustdoc {}",
+                version
+            );
+            assert!(
+                rc.implements()[1]
+                    .link
+                    .as_deref()
+                    .is_some_and(|link| link.ends_with("#impl-Send-for-Rc%3CT,+A%3E")),
+                "The negative Send impl is linked for rustdoc {}",
+                version
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_trait_key() {
+        assert_eq!(trait_key("Clone"), "Clone");
+        assert_eq!(trait_key("std::convert::AsRef<[u8]>"), "AsRef");
+        assert_eq!(trait_key("tokio::io::AsyncRead"), "AsyncRead");
+    }
+}
@@ -0,0 +1,823 @@
+//! MCP over HTTP, for clients that connect to a running server instead of starting their own.
+//!
+//! The streamable HTTP transport takes JSON-RPC messages, or batches of them, POSTed to `/mcp`
+//! and answers the requests among them in the response body. `initialize` opens a session
+//! whose id comes back in the `Mcp-Session-Id` header, for the client to send with every later
+//! message; `DELETE /mcp` ends it. The legacy HTTP+SSE transport is served as well: `GET /sse`
+//! opens an event stream whose first `endpoint` event names the URL to POST messages to, and
+//! the responses arrive on the stream as `message` events.
+//!
+//! Every session gets a server of its own. At most `transport.max_sessions` are open at once,
+//! and a streamable HTTP session that goes `transport.session_idle_secs` without a message is
+//! ended, since clients don't always send `DELETE`. With `transport.token` set, requests must
+//! carry it as a bearer token; without one, browsers may only call from pages served by this
+//! machine.
+
+use std::collections::{HashMap, HashSet};
+use std::convert::Infallible;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Result};
+use http_body_util::combinators::BoxBody;
+use http_body_util::{BodyExt, Full, Limited};
+use hyper::body::{Body, Bytes, Frame, Incoming};
+use hyper::header::{
+    HeaderValue, AUTHORIZATION, CACHE_CONTROL, CONTENT_TYPE, ORIGIN, WWW_AUTHENTICATE,
+};
+use hyper::server::conn::http1;
+use hyper::service::service_fn;
+use hyper::{Method, Request, Response, StatusCode};
+use hyper_util::rt::TokioIo;
+use mcp_sdk::transport::{JsonRpcMessage, Message, Transport};
+use serde_json::Value;
+use tokio::net::TcpListener;
+use tokio::sync::{mpsc, oneshot};
+use tracing::{debug, info, warn};
+use url::Url;
+use uuid::Uuid;
+
+use super::Closed;
+use crate::config;
+use crate::server;
+use crate::tools::runtime;
+
+/// Path of the streamable HTTP endpoint
+pub const MCP_PATH: &str = "/mcp";
+/// Path the legacy transport's event stream is opened on
+pub const SSE_PATH: &str = "/sse";
+/// Path the legacy transport's clients POST their messages to
+const MESSAGES_PATH: &str = "/messages";
+const SESSION_HEADER: &str = "mcp-session-id";
+/// Largest request body read, well over any message a client has reason to send
+const MAX_REQUEST_BYTES: usize = 4 * 1024 * 1024;
+
+type HttpBody = BoxBody<Bytes, Infallible>;
+
+/// Serves MCP over HTTP on `listener` until accepting connections fails for good.
+pub async fn serve(listener: TcpListener, admin_enabled: bool) -> Result<()> {
+    info!(
+        "Serving MCP on http://{}{} (legacy SSE on {})",
+        listener.local_addr()?,
+        MCP_PATH,
+        SSE_PATH
+    );
+    let transport = config::current().transport().clone();
+    let sessions = Sessions::new(
+        admin_enabled,
+        transport.max_sessions(),
+        transport.session_idle(),
+    );
+    accept(listener, Arc::new(sessions)).await
+}
+
+/// Serves every connection made to `listener` from `sessions`.
+async fn accept(listener: TcpListener, sessions: Arc<Sessions>) -> Result<()> {
+    tokio::spawn(Arc::clone(&sessions).expire_idle());
+    loop {
+        let (stream, peer) = match listener.accept().await {
+            Ok(connection) => connection,
+            Err(e) => {
+                // Running out of file descriptors passes once other connections close
+                warn!("Failed to accept a connection: {}", e);
+                tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+                continue;
+            }
+        };
+        let sessions = Arc::clone(&sessions);
+        tokio::spawn(async move {
+            let service = service_fn(move |request| {
+                let sessions = Arc::clone(&sessions);
+                async move { Ok::<_, Infallible>(sessions.handle(request).await) }
+            });
+            if let Err(e) = http1::Builder::new()
+                .serve_connection(TokioIo::new(stream), service)
+                .await
+            {
+                debug!("Connection from {} ended: {}", peer, e);
+            }
+        });
+    }
+}
+
+/// Where a session's server sends what it has to say
+enum Outbound {
+    /// Responses go back in the body of the POST that carried their request, by request id
+    Waiting(Mutex<HashMap<u64, oneshot::Sender<String>>>),
+    /// Everything goes down the event stream the client opened, as ready-made events
+    Stream(mpsc::UnboundedSender<String>),
+}
+
+/// One client's way into its server
+#[derive(Clone)]
+struct Session {
+    inbound: mpsc::UnboundedSender<Message>,
+    outbound: Arc<Outbound>,
+    /// When the client last sent a message
+    active: Arc<Mutex<Instant>>,
+}
+
+/// The transport of one session's server
+struct SessionTransport {
+    inbound: tokio::sync::Mutex<mpsc::UnboundedReceiver<Message>>,
+    outbound: Arc<Outbound>,
+}
+
+impl Transport for SessionTransport {
+    fn send(&self, message: &Message) -> Result<()> {
+        let encoded = super::encode(message, config::current().transport().max_message_bytes())?;
+        match (&*self.outbound, message) {
+            (Outbound::Waiting(waiters), JsonRpcMessage::Response(response)) => {
+                let waiter = lock(waiters).remove(&response.id);
+                match waiter {
+                    // Failing means the client hung up before the response was ready
+                    Some(waiter) => drop(waiter.send(encoded)),
+                    None => debug!(
+                        "Dropping the response to {}, nobody waits for it",
+                        response.id
+                    ),
+                }
+            }
+            (Outbound::Waiting(_), _) => {
+                debug!("Dropping a message to a client without an event stream")
+            }
+            (Outbound::Stream(events), _) => events
+                .send(format!("event: message\ndata: {}\n\n", encoded))
+                .map_err(|_| anyhow!("The client closed its event stream"))?,
+        }
+        Ok(())
+    }
+
+    /// Waits for the client's next message; the session ends once it is closed.
+    fn receive(&self) -> Result<Message> {
+        runtime::block_on(async {
+            let mut inbound = self.inbound.lock().await;
+            inbound.recv().await.ok_or_else(|| Closed.into())
+        })
+    }
+
+    fn open(&self) -> Result<()> {
+        Ok(())
+    }
+
+    fn close(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// The open sessions, by id
+struct Sessions {
+    open: Mutex<HashMap<String, Session>>,
+    admin_enabled: bool,
+    max_sessions: usize,
+    idle: Duration,
+}
+
+impl Sessions {
+    fn new(admin_enabled: bool, max_sessions: usize, idle: Duration) -> Self {
+        Self {
+            open: Mutex::new(HashMap::new()),
+            admin_enabled,
+            max_sessions,
+            idle,
+        }
+    }
+
+    async fn handle(self: Arc<Self>, request: Request<Incoming>) -> Response<HttpBody> {
+        let header = |name: &str| {
+            request
+                .headers()
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string)
+        };
+        let transport = config::current().transport().clone();
+        if !transport.authorize(header(AUTHORIZATION.as_str()).as_deref()) {
+            let mut response = text(StatusCode::UNAUTHORIZED, "Missing or wrong bearer token");
+            response
+                .headers_mut()
+                .insert(WWW_AUTHENTICATE, HeaderValue::from_static("Bearer"));
+            return response;
+        }
+        if !transport.has_token() && !is_local_origin(header(ORIGIN.as_str()).as_deref()) {
+            return text(
+                StatusCode::FORBIDDEN,
+                "Requests from other sites need transport.token to be set",
+            );
+        }
+
+        let session_id = header(SESSION_HEADER);
+        let query_session = request.uri().query().and_then(|query| {
+            query
+                .split('&')
+                .find_map(|pair| pair.strip_prefix("sessionId="))
+                .map(str::to_string)
+        });
+        match (request.method().clone(), request.uri().path()) {
+            (Method::POST, MCP_PATH) => match read_messages(request).await {
+                Ok((messages, batch)) => self.post(session_id, messages, batch).await,
+                Err(response) => response,
+            },
+            (Method::DELETE, MCP_PATH) => match session_id.and_then(|id| self.close(&id)) {
+                Some(_) => text(StatusCode::OK, "Session ended"),
+                None => text(StatusCode::NOT_FOUND, "Unknown session"),
+            },
+            (Method::GET, MCP_PATH) => text(
+                StatusCode::METHOD_NOT_ALLOWED,
+                "This server sends nothing unasked, there is no stream to open",
+            ),
+            (Method::GET, SSE_PATH) => self.open_stream(),
+            (Method::POST, MESSAGES_PATH) => {
+                let Some(session) = query_session.as_deref().and_then(|id| self.get(id)) else {
+                    return text(StatusCode::NOT_FOUND, "Unknown session");
+                };
+                match read_messages(request).await {
+                    Ok((messages, _)) => {
+                        for message in messages {
+                            if session.inbound.send(message).is_err() {
+                                return text(StatusCode::NOT_FOUND, "The session has ended");
+                            }
+                        }
+                        text(StatusCode::ACCEPTED, "")
+                    }
+                    Err(response) => response,
+                }
+            }
+            _ => text(StatusCode::NOT_FOUND, "Not found"),
+        }
+    }
+
+    /// Handles messages POSTed to the streamable HTTP endpoint.
+    async fn post(
+        self: Arc<Self>,
+        session_id: Option<String>,
+        messages: Vec<Message>,
+        batch: bool,
+    ) -> Response<HttpBody> {
+        let initializes = messages.iter().any(|message| {
+            matches!(message, JsonRpcMessage::Request(request) if request.method == "initialize")
+        });
+        let ids: Vec<u64> = messages
+            .iter()
+            .filter_map(|message| match message {
+                JsonRpcMessage::Request(request) => Some(request.id),
+                _ => None,
+            })
+            .collect();
+        let mut seen = HashSet::new();
+        if let Some(id) = ids.iter().find(|id| !seen.insert(**id)) {
+            return text(
+                StatusCode::BAD_REQUEST,
+                &format!("Request id {} appears twice in the batch", id),
+            );
+        }
+        let (id, session) = match session_id {
+            Some(id) => match self.get(&id) {
+                Some(session) => (id, session),
+                None => return text(StatusCode::NOT_FOUND, "Unknown session"),
+            },
+            None if initializes => {
+                let outbound = Outbound::Waiting(Mutex::new(HashMap::new()));
+                match Arc::clone(&self).open(outbound) {
+                    Some(opened) => opened,
+                    None => return too_many_sessions(),
+                }
+            }
+            None => {
+                return text(
+                    StatusCode::BAD_REQUEST,
+                    "Missing Mcp-Session-Id header, send initialize first",
+                )
+            }
+        };
+        let Outbound::Waiting(waiters) = &*session.outbound else {
+            return text(
+                StatusCode::BAD_REQUEST,
+                "The session belongs to an event stream, POST to its endpoint",
+            );
+        };
+
+        let replies = {
+            let mut waiters = lock(waiters);
+            // A second reply channel under the same id would take the first one's response
+            if let Some(id) = ids.iter().find(|id| waiters.contains_key(id)) {
+                return text(
+                    StatusCode::CONFLICT,
+                    &format!("Request id {} is already waiting for a response", id),
+                );
+            }
+            ids.iter()
+                .map(|id| {
+                    let (reply, wait) = oneshot::channel();
+                    waiters.insert(*id, reply);
+                    wait
+                })
+                .collect::<Vec<_>>()
+        };
+        for message in messages {
+            if session.inbound.send(message).is_err() {
+                return text(StatusCode::NOT_FOUND, "The session has ended");
+            }
+        }
+        if replies.is_empty() {
+            return text(StatusCode::ACCEPTED, "");
+        }
+        let mut bodies = Vec::new();
+        for wait in replies {
+            match wait.await {
+                Ok(body) => bodies.push(body),
+                Err(_) => {
+                    return text(
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        "The session ended before answering",
+                    )
+                }
+            }
+        }
+        let body = if batch {
+            format!("[{}]", bodies.join(","))
+        } else {
+            bodies.swap_remove(0)
+        };
+        let mut response = Response::new(full(body));
+        let headers = response.headers_mut();
+        headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+        if let Ok(id) = id.parse() {
+            headers.insert(SESSION_HEADER, id);
+        }
+        response
+    }
+
+    /// Opens a legacy HTTP+SSE session, answering with its event stream.
+    fn open_stream(self: Arc<Self>) -> Response<HttpBody> {
+        let (events, receiver) = mpsc::unbounded_channel();
+        let Some((id, _)) = Arc::clone(&self).open(Outbound::Stream(events.clone())) else {
+            return too_many_sessions();
+        };
+        // Can't fail, the receiver is right here
+        let _ = events.send(format!(
+            "event: endpoint\ndata: {}?sessionId={}\n\n",
+            MESSAGES_PATH, id
+        ));
+        let body = EventStream {
+            events: receiver,
+            _session: OpenSession { sessions: self, id },
+        };
+        let mut response = Response::new(body.boxed());
+        let headers = response.headers_mut();
+        headers.insert(CONTENT_TYPE, HeaderValue::from_static("text/event-stream"));
+        headers.insert(CACHE_CONTROL, HeaderValue::from_static("no-cache"));
+        response
+    }
+
+    /// Starts a server for a new session sending to `outbound`, returning the session's id, or
+    /// `None` when `max_sessions` are open.
+    fn open(self: Arc<Self>, outbound: Outbound) -> Option<(String, Session)> {
+        if lock(&self.open).len() >= self.max_sessions {
+            self.close_idle();
+            if lock(&self.open).len() >= self.max_sessions {
+                return None;
+            }
+        }
+        let id = session_id();
+        let (inbound, receiver) = mpsc::unbounded_channel();
+        let session = Session {
+            inbound,
+            outbound: Arc::new(outbound),
+            active: Arc::new(Mutex::new(Instant::now())),
+        };
+        let transport = SessionTransport {
+            inbound: tokio::sync::Mutex::new(receiver),
+            outbound: Arc::clone(&session.outbound),
+        };
+        let server = server::build(transport, self.admin_enabled);
+        lock(&self.open).insert(id.clone(), session.clone());
+        debug!("Opened session {}", id);
+
+        let sessions = Arc::clone(&self);
+        let ended = id.clone();
+        tokio::spawn(async move {
+            if let Err(e) = server::listen(&server).await {
+                debug!("Session {} ended: {}", ended, e);
+            }
+            sessions.close(&ended);
+        });
+        Some((id, session))
+    }
+
+    /// The session `id`, marked active
+    fn get(&self, id: &str) -> Option<Session> {
+        let session = lock(&self.open).get(id).cloned()?;
+        *lock(&session.active) = Instant::now();
+        Some(session)
+    }
+
+    /// Ends streamable HTTP sessions that have gone `idle` without a message. Sessions of the
+    /// legacy transport end with their event stream instead.
+    fn close_idle(&self) {
+        lock(&self.open).retain(|id, session| {
+            let waiting = matches!(*session.outbound, Outbound::Waiting(_));
+            let expired = waiting && lock(&session.active).elapsed() >= self.idle;
+            if expired {
+                debug!("Closing session {}, idle for {:?}", id, self.idle);
+            }
+            !expired
+        });
+    }
+
+    /// Closes idle sessions for as long as the server runs.
+    async fn expire_idle(self: Arc<Self>) {
+        let mut ticks = tokio::time::interval(
+            (self.idle / 4).clamp(Duration::from_millis(10), Duration::from_secs(60)),
+        );
+        loop {
+            ticks.tick().await;
+            self.close_idle();
+        }
+    }
+
+    /// Ends a session; its server stops once it has read what was sent before.
+    fn close(&self, id: &str) -> Option<Session> {
+        lock(&self.open).remove(id)
+    }
+}
+
+/// Closes its session when the client hangs up on the event stream
+struct OpenSession {
+    sessions: Arc<Sessions>,
+    id: String,
+}
+
+impl Drop for OpenSession {
+    fn drop(&mut self) {
+        self.sessions.close(&self.id);
+    }
+}
+
+/// The body of a legacy transport's event stream
+struct EventStream {
+    events: mpsc::UnboundedReceiver<String>,
+    _session: OpenSession,
+}
+
+impl Body for EventStream {
+    type Data = Bytes;
+    type Error = Infallible;
+
+    fn poll_frame(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Bytes>, Infallible>>> {
+        self.events
+            .poll_recv(cx)
+            .map(|event| event.map(|event| Ok(Frame::data(Bytes::from(event)))))
+    }
+}
+
+/// Reads the JSON-RPC message, or batch of them, in a request body. Errors are the response
+/// to send instead.
+async fn read_messages(
+    request: Request<Incoming>,
+) -> Result<(Vec<Message>, bool), Response<HttpBody>> {
+    let body = Limited::new(request.into_body(), MAX_REQUEST_BYTES)
+        .collect()
+        .await
+        .map_err(|e| {
+            text(
+                StatusCode::BAD_REQUEST,
+                &format!("Failed to read the body: {}", e),
+            )
+        })?
+        .to_bytes();
+    parse_messages(&body).map_err(|e| text(StatusCode::BAD_REQUEST, &format!("{:#}", e)))
+}
+
+/// Parses a JSON-RPC message or batch, and whether it was a batch.
+fn parse_messages(body: &[u8]) -> Result<(Vec<Message>, bool)> {
+    let invalid = |e| anyhow!("Invalid JSON-RPC message: {}", e);
+    match serde_json::from_slice(body).map_err(invalid)? {
+        Value::Array(batch) => Ok((
+            batch
+                .into_iter()
+                .map(serde_json::from_value)
+                .collect::<Result<_, _>>()
+                .map_err(invalid)?,
+            true,
+        )),
+        message => Ok((
+            vec![serde_json::from_value(message).map_err(invalid)?],
+            false,
+        )),
+    }
+}
+
+/// Whether a request with this `Origin` header comes from this machine, or from no browser at
+/// all. Refusing the rest keeps web pages from reaching the server through DNS rebinding.
+fn is_local_origin(origin: Option<&str>) -> bool {
+    let Some(origin) = origin else {
+        return true;
+    };
+    Url::parse(origin)
+        .is_ok_and(|url| matches!(url.host_str(), Some("localhost" | "127.0.0.1" | "[::1]")))
+}
+
+/// A random version 4 UUID, from the OS's random number generator
+fn session_id() -> String {
+    Uuid::new_v4().simple().to_string()
+}
+
+/// Locks `mutex` even if a session's task panicked while holding it: every critical section is
+/// a single map insert or removal, which leaves the map whole.
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    match mutex.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    }
+}
+
+fn full(body: String) -> HttpBody {
+    Full::new(Bytes::from(body)).boxed()
+}
+
+fn too_many_sessions() -> Response<HttpBody> {
+    text(
+        StatusCode::SERVICE_UNAVAILABLE,
+        "Too many open sessions, end one or try again later",
+    )
+}
+
+fn text(status: StatusCode, message: &str) -> Response<HttpBody> {
+    let mut response = Response::new(full(message.to_string()));
+    *response.status_mut() = status;
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    async fn start() -> Result<String> {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let url = format!("http://{}", listener.local_addr()?);
+        tokio::spawn(serve(listener, false));
+        Ok(url)
+    }
+
+    /// Serves sessions that end after `idle`, at most `max_sessions` at a time
+    async fn start_limited(max_sessions: usize, idle: Duration) -> Result<(String, Arc<Sessions>)> {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let url = format!("http://{}{}", listener.local_addr()?, MCP_PATH);
+        let sessions = Arc::new(Sessions::new(false, max_sessions, idle));
+        tokio::spawn(accept(listener, Arc::clone(&sessions)));
+        Ok((url, sessions))
+    }
+
+    /// Ends `session`, whose server would otherwise keep the runtime from shutting down
+    async fn end(client: &reqwest::Client, url: &str, session: &str) -> Result<()> {
+        let response = client
+            .delete(url)
+            .header(SESSION_HEADER, session)
+            .send()
+            .await?;
+        assert_eq!(response.status(), StatusCode::OK);
+        Ok(())
+    }
+
+    fn session_of(response: &reqwest::Response) -> String {
+        response.headers()[SESSION_HEADER]
+            .to_str()
+            .unwrap()
+            .to_string()
+    }
+
+    fn client() -> reqwest::Client {
+        reqwest::Client::builder().no_proxy().build().unwrap()
+    }
+
+    async fn post(
+        client: &reqwest::Client,
+        url: &str,
+        session: Option<&str>,
+        message: Value,
+    ) -> Result<reqwest::Response> {
+        let mut post = client
+            .post(url)
+            .header(CONTENT_TYPE.as_str(), "application/json")
+            .body(message.to_string());
+        if let Some(session) = session {
+            post = post.header(SESSION_HEADER, session);
+        }
+        Ok(post.send().await?)
+    }
+
+    async fn json_of(response: reqwest::Response) -> Result<Value> {
+        Ok(serde_json::from_str(&response.text().await?)?)
+    }
+
+    /// The next event on an event stream, without its closing blank line
+    async fn next_event(stream: &mut reqwest::Response, buffer: &mut Vec<u8>) -> Result<String> {
+        loop {
+            if let Some(end) = buffer.windows(2).position(|pair| pair == b"\n\n") {
+                let event = String::from_utf8(buffer[..end].to_vec())?;
+                buffer.drain(..end + 2);
+                return Ok(event);
+            }
+            let chunk = stream
+                .chunk()
+                .await?
+                .ok_or_else(|| anyhow!("The stream ended"))?;
+            buffer.extend_from_slice(&chunk);
+        }
+    }
+
+    fn request(id: u64, method: &str) -> Value {
+        json!({"jsonrpc": "2.0", "id": id, "method": method, "params": {}})
+    }
+
+    #[test]
+    fn test_parse_messages() -> Result<()> {
+        let (messages, batch) = parse_messages(br#"{"jsonrpc":"2.0","id":1,"method":"ping"}"#)?;
+        assert_eq!((messages.len(), batch), (1, false));
+        let (messages, batch) = parse_messages(
+            br#"[{"jsonrpc":"2.0","id":1,"method":"ping"},{"jsonrpc":"2.0","method":"notifications/initialized"}]"#,
+        )?;
+        assert_eq!((messages.len(), batch), (2, true));
+        assert!(parse_messages(b"{").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_is_local_origin() {
+        assert!(is_local_origin(None));
+        assert!(is_local_origin(Some("http://localhost:3000")));
+        assert!(is_local_origin(Some("http://127.0.0.1")));
+        assert!(is_local_origin(Some("http://[::1]:8080")));
+        assert!(!is_local_origin(Some("https://evil.example")));
+        assert!(!is_local_origin(Some("null")));
+    }
+
+    #[test]
+    fn test_session_id() {
+        let id = session_id();
+        assert_eq!(id.len(), 32);
+        assert!(id.chars().all(|c| c.is_ascii_hexdigit()));
+        assert_ne!(id, session_id());
+    }
+
+    #[test]
+    fn test_lock_survives_poisoning() {
+        let open = Arc::new(Mutex::new(HashMap::from([(1u8, "open")])));
+        let poisoner = Arc::clone(&open);
+        let _ = std::thread::spawn(move || {
+            let _guard = poisoner.lock();
+            panic!("A session's task panics");
+        })
+        .join();
+        assert!(open.is_poisoned());
+        assert_eq!(lock(&open).remove(&1), Some("open"));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_streamable_http_session() -> Result<()> {
+        let url = format!("{}{}", start().await?, MCP_PATH);
+        let client = client();
+
+        let response = post(&client, &url, None, request(1, "tools/list")).await?;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST, "No session yet");
+
+        let response = post(&client, &url, None, request(1, "initialize")).await?;
+        assert_eq!(response.status(), StatusCode::OK);
+        let session = response
+            .headers()
+            .get(SESSION_HEADER)
+            .expect("initialize opens a session")
+            .to_str()?
+            .to_string();
+        let body = json_of(response).await?;
+        assert_eq!(body["id"], 1);
+        assert!(body["result"]["protocolVersion"].is_string());
+
+        let initialized = json!({"jsonrpc": "2.0", "method": "notifications/initialized"});
+        let response = post(&client, &url, Some(&session), initialized).await?;
+        assert_eq!(response.status(), StatusCode::ACCEPTED);
+
+        let batch = json!([request(2, "tools/list"), request(3, "resources/list")]);
+        let body = json_of(post(&client, &url, Some(&session), batch).await?).await?;
+        assert_eq!(body[0]["id"], 2);
+        assert!(!body[0]["result"]["tools"].as_array().unwrap().is_empty());
+        assert_eq!(body[1]["id"], 3);
+
+        let response = client
+            .delete(&url)
+            .header(SESSION_HEADER, &session)
+            .send()
+            .await?;
+        assert_eq!(response.status(), StatusCode::OK);
+        let response = post(&client, &url, Some(&session), request(4, "tools/list")).await?;
+        assert_eq!(
+            response.status(),
+            StatusCode::NOT_FOUND,
+            "The session ended"
+        );
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_legacy_sse_session() -> Result<()> {
+        let base = start().await?;
+        let client = client();
+
+        let mut stream = client.get(format!("{}{}", base, SSE_PATH)).send().await?;
+        assert_eq!(stream.headers()[CONTENT_TYPE], "text/event-stream");
+        let mut buffer = Vec::new();
+        let endpoint = next_event(&mut stream, &mut buffer).await?;
+        let path = endpoint
+            .strip_prefix("event: endpoint\ndata: ")
+            .expect("The stream starts with the endpoint")
+            .to_string();
+        assert!(path.starts_with("/messages?sessionId="));
+        let url = format!("{}{}", base, path);
+
+        let response = post(&client, &url, None, request(1, "initialize")).await?;
+        assert_eq!(response.status(), StatusCode::ACCEPTED);
+        let event = next_event(&mut stream, &mut buffer).await?;
+        let data = event
+            .strip_prefix("event: message\ndata: ")
+            .expect("Responses arrive as message events");
+        let message: Value = serde_json::from_str(data)?;
+        assert_eq!(message["id"], 1);
+        assert!(message["result"]["protocolVersion"].is_string());
+
+        drop(stream);
+        for _ in 0..50 {
+            let response = post(&client, &url, None, request(2, "tools/list")).await?;
+            if response.status() == StatusCode::NOT_FOUND {
+                return Ok(());
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        }
+        panic!("The session outlived its event stream");
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_session_cap_and_idle_expiry() -> Result<()> {
+        let (url, _) = start_limited(1, Duration::from_millis(300)).await?;
+        let client = client();
+
+        let response = post(&client, &url, None, request(1, "initialize")).await?;
+        assert_eq!(response.status(), StatusCode::OK);
+        let first = session_of(&response);
+        let response = post(&client, &url, None, request(1, "initialize")).await?;
+        assert_eq!(
+            response.status(),
+            StatusCode::SERVICE_UNAVAILABLE,
+            "One session is the cap"
+        );
+        let response = post(&client, &url, Some(&first), request(2, "tools/list")).await?;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        tokio::time::sleep(Duration::from_millis(600)).await;
+        let response = post(&client, &url, Some(&first), request(3, "tools/list")).await?;
+        assert_eq!(
+            response.status(),
+            StatusCode::NOT_FOUND,
+            "The idle session was ended"
+        );
+        let response = post(&client, &url, None, request(1, "initialize")).await?;
+        assert_eq!(response.status(), StatusCode::OK, "Its place is free again");
+        end(&client, &url, &session_of(&response)).await
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_duplicate_request_ids() -> Result<()> {
+        let (url, sessions) = start_limited(1, Duration::from_secs(60)).await?;
+        let client = client();
+
+        let batch = json!([request(1, "initialize"), request(1, "ping")]);
+        let response = post(&client, &url, None, batch).await?;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let response = post(&client, &url, None, request(1, "initialize")).await?;
+        assert_eq!(
+            response.status(),
+            StatusCode::OK,
+            "The refused batch opened no session"
+        );
+        let session = session_of(&response);
+
+        // A request the server is still working on
+        let Outbound::Waiting(waiters) = &*sessions.get(&session).unwrap().outbound else {
+            panic!("Streamable HTTP sessions answer in the response body");
+        };
+        let (reply, _wait) = oneshot::channel();
+        lock(waiters).insert(2, reply);
+        let response = post(&client, &url, Some(&session), request(2, "tools/list")).await?;
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+        let response = post(&client, &url, Some(&session), request(3, "tools/list")).await?;
+        assert_eq!(response.status(), StatusCode::OK);
+        end(&client, &url, &session).await
+    }
+}
@@ -0,0 +1,371 @@
+//! The stdio transport the server talks to its client over: one JSON-RPC message per line.
+//! The [`http`] transport serves any number of clients over HTTP instead, and [`socket`] over
+//! local sockets.
+//!
+//! The SDK's transports are synchronous, so reads and writes block their worker thread through
+//! [`runtime::block_on`] while tokio does the I/O. Reads are buffered, so a message arriving
+//! over several partial reads is put back together, and blank or malformed lines are skipped
+//! with a warning instead of ending the session. Writes are serialized, so responses to
+//! concurrent requests never interleave. Once the client hangs up, `receive` fails with
+//! [`Closed`], which [`server::listen`](crate::server::listen) takes as the end of the session.
+//!
+//! Clients tend to drop messages over their size limit without a word, so a tool response
+//! longer than `transport.max_message_bytes` is not sent as is. Its text is split into parts
+//! kept as resources, and the client gets a short response listing their URIs to fetch with
+//! `resources/read`.
+
+pub mod http;
+pub mod socket;
+
+use anyhow::Result;
+use mcp_sdk::transport::{JsonRpcMessage, Message, Transport};
+use mcp_sdk::types::{CallToolResponse, ToolResponseContent};
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncWrite, AsyncWriteExt, BufReader};
+use tracing::warn;
+
+use crate::config;
+use crate::resources::{self, Kind};
+use crate::tools::runtime;
+
+/// Room left in each part for the `resources/read` response wrapped around it
+const PART_OVERHEAD: usize = 1024;
+
+/// What [`Transport::receive`] fails with once the client has hung up
+#[derive(Debug, thiserror::Error)]
+#[error("The client closed the connection")]
+pub struct Closed;
+
+/// Newline-delimited JSON-RPC over a reader and a writer
+pub struct LineTransport<R, W> {
+    reader: tokio::sync::Mutex<R>,
+    writer: tokio::sync::Mutex<W>,
+}
+
+/// The transport on the process's stdin and stdout
+pub type StdioTransport = LineTransport<BufReader<tokio::io::Stdin>, tokio::io::Stdout>;
+
+impl StdioTransport {
+    pub fn new() -> Self {
+        Self::with_io(BufReader::new(tokio::io::stdin()), tokio::io::stdout())
+    }
+}
+
+impl Default for StdioTransport {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<R, W> LineTransport<R, W> {
+    pub fn with_io(reader: R, writer: W) -> Self {
+        Self {
+            reader: tokio::sync::Mutex::new(reader),
+            writer: tokio::sync::Mutex::new(writer),
+        }
+    }
+}
+
+impl<R, W> Transport for LineTransport<R, W>
+where
+    R: AsyncBufRead + Unpin + Send + 'static,
+    W: AsyncWrite + Unpin + Send + 'static,
+{
+    fn send(&self, message: &Message) -> Result<()> {
+        let line = encode(message, config::current().transport().max_message_bytes())?;
+        runtime::block_on(async {
+            let mut writer = self.writer.lock().await;
+            writer.write_all(line.as_bytes()).await?;
+            writer.write_all(b"\n").await?;
+            writer.flush().await?;
+            Ok(())
+        })
+    }
+
+    fn receive(&self) -> Result<Message> {
+        runtime::block_on(async {
+            let mut reader = self.reader.lock().await;
+            let mut line = Vec::new();
+            loop {
+                line.clear();
+                if reader.read_until(b'\n', &mut line).await? == 0 {
+                    return Err(Closed.into());
+                }
+                if line.trim_ascii().is_empty() {
+                    continue;
+                }
+                match serde_json::from_slice(&line) {
+                    Ok(message) => return Ok(message),
+                    Err(e) => warn!("Ignoring malformed message from the client: {}", e),
+                }
+            }
+        })
+    }
+
+    fn open(&self) -> Result<()> {
+        Ok(())
+    }
+
+    fn close(&self) -> Result<()> {
+        runtime::block_on(async {
+            self.writer.lock().await.flush().await?;
+            Ok(())
+        })
+    }
+}
+
+/// Serializes `message` as a line, spilling a tool response over `limit` bytes into parts.
+fn encode(message: &Message, limit: Option<usize>) -> Result<String> {
+    let line = serde_json::to_string(message)?;
+    let Some(limit) = limit.filter(|limit| line.len() > *limit) else {
+        return Ok(line);
+    };
+    let tool_response = match message {
+        JsonRpcMessage::Response(response) => response
+            .result
+            .clone()
+            .and_then(|result| serde_json::from_value::<CallToolResponse>(result).ok()),
+        _ => None,
+    };
+    let (JsonRpcMessage::Response(response), Some(tool_response)) = (message, tool_response) else {
+        warn!(
+            "Sending a {} byte message over the {} byte limit, the client may drop it",
+            line.len(),
+            limit
+        );
+        return Ok(line);
+    };
+
+    let summary = spill(tool_response, line.len(), limit);
+    let mut response = response.clone();
+    response.result = Some(serde_json::to_value(summary)?);
+    Ok(serde_json::to_string(&JsonRpcMessage::Response(response))?)
+}
+
+/// Stores the text of `response` as parts of at most `limit` bytes once serialized, returning
+/// the response pointing the client at them.
+fn spill(response: CallToolResponse, size: usize, limit: usize) -> CallToolResponse {
+    let budget = limit.saturating_sub(PART_OVERHEAD).max(PART_OVERHEAD);
+    let mut parts = Vec::new();
+    let mut inline = Vec::new();
+    for content in response.content {
+        match content {
+            ToolResponseContent::Text { text } => parts.extend(split(&text, budget)),
+            other => inline.push(other),
+        }
+    }
+
+    let uris = resources::keep(
+        Kind::ResponseParts,
+        "Oversized tool response".to_string(),
+        parts,
+    );
+    let text = format!(
+        "This response is {} bytes, over the client's {} byte message limit, so it was split \
+         into {} parts. Read them in order with resources/read:\n{}",
+        size,
+        limit,
+        uris.len(),
+        uris.join("\n")
+    );
+    CallToolResponse {
+        content: std::iter::once(ToolResponseContent::Text { text })
+            .chain(inline)
+            .collect(),
+        is_error: response.is_error,
+        meta: response.meta,
+    }
+}
+
+/// Bytes `c` takes inside a JSON string
+fn escaped_len(c: char) -> usize {
+    match c {
+        '"' | '\\' | '\n' | '\r' | '\t' | '\u{08}' | '\u{0c}' => 2,
+        c if c < ' ' => 6,
+        c => c.len_utf8(),
+    }
+}
+
+/// Splits `text` into parts taking at most `budget` bytes as JSON strings, breaking after a
+/// newline where possible so JSON Lines chunks stay whole.
+fn split(text: &str, budget: usize) -> Vec<String> {
+    let mut parts = Vec::new();
+    let mut current = String::new();
+    let mut current_len = 0;
+    for line in text.split_inclusive('\n') {
+        let line_len: usize = line.chars().map(escaped_len).sum();
+        if current_len + line_len > budget && !current.is_empty() {
+            parts.push(std::mem::take(&mut current));
+            current_len = 0;
+        }
+        if line_len <= budget {
+            current.push_str(line);
+            current_len += line_len;
+            continue;
+        }
+        for c in line.chars() {
+            if current_len + escaped_len(c) > budget && !current.is_empty() {
+                parts.push(std::mem::take(&mut current));
+                current_len = 0;
+            }
+            current.push(c);
+            current_len += escaped_len(c);
+        }
+    }
+    if !current.is_empty() {
+        parts.push(current);
+    }
+    parts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mcp_sdk::transport::JsonRpcResponse;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+    use std::sync::Arc;
+
+    fn tool_response(text: &str) -> Message {
+        let result = CallToolResponse {
+            content: vec![ToolResponseContent::Text {
+                text: text.to_string(),
+            }],
+            is_error: None,
+            meta: None,
+        };
+        JsonRpcMessage::Response(JsonRpcResponse {
+            id: 7,
+            result: Some(serde_json::to_value(result).unwrap()),
+            error: None,
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn test_split() {
+        let text = "{\"name\":\"alpha\"}\n{\"name\":\"beta\"}\n";
+        assert_eq!(
+            split(text, 24),
+            ["{\"name\":\"alpha\"}\n", "{\"name\":\"beta\"}\n"],
+            "Parts should break after newlines"
+        );
+
+        let long = "é\"".repeat(40);
+        let parts = split(&long, 10);
+        assert_eq!(parts.concat(), long);
+        assert!(parts
+            .iter()
+            .all(|part| serde_json::to_string(part).unwrap().len() - 2 <= 10));
+    }
+
+    #[test]
+    fn test_small_messages_are_sent_as_is() -> Result<()> {
+        let message = tool_response("short");
+        assert_eq!(
+            encode(&message, Some(4096))?,
+            serde_json::to_string(&message)?
+        );
+        assert_eq!(
+            encode(&tool_response(&"x".repeat(8192)), None)?.len(),
+            serde_json::to_string(&tool_response(&"x".repeat(8192)))?.len(),
+            "No limit when unset"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_oversized_response_is_spilled() -> Result<()> {
+        let text: String = (0..500)
+            .map(|i| format!("{{\"name\":\"item_{}\"}}\n", i))
+            .collect();
+        let line = encode(&tool_response(&text), Some(4096))?;
+        assert!(line.len() <= 4096, "{} bytes", line.len());
+
+        let message: serde_json::Value = serde_json::from_str(&line)?;
+        assert_eq!(message["id"], 7);
+        let summary = message["result"]["content"][0]["text"]
+            .as_str()
+            .expect("summary text");
+        let uris: Vec<&str> = summary
+            .lines()
+            .filter(|line| line.starts_with("docs-rs-mcp://responses/"))
+            .collect();
+        assert!(uris.len() > 1, "{}", summary);
+
+        let parts: Vec<String> = uris
+            .iter()
+            .map(|uri| resources::read(uri).expect("kept part"))
+            .collect();
+        assert_eq!(parts.concat(), text, "Parts should add up to the response");
+        assert!(parts
+            .iter()
+            .all(|part| serde_json::to_string(part).unwrap().len() <= 4096 - PART_OVERHEAD));
+        assert!(
+            parts.iter().all(|part| part.ends_with('\n')),
+            "Parts should hold whole lines"
+        );
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_receive_partial_and_malformed_lines() -> Result<()> {
+        let (client, server) = tokio::io::duplex(64);
+        let transport = LineTransport::with_io(BufReader::new(server), tokio::io::sink());
+        let (_, mut client) = tokio::io::split(client);
+
+        let writer = tokio::spawn(async move {
+            for chunk in [
+                "{\"jsonrpc\":\"2.0\",\"id\":1,",
+                "\"method\":\"tools/list\"}\r\n",
+                "\n",
+                "not json\n",
+                "{\"jsonrpc\":\"2.0\",\"id\":2,\"method\":\"tools/list\"}\n",
+            ] {
+                client.write_all(chunk.as_bytes()).await?;
+                client.flush().await?;
+                tokio::task::yield_now().await;
+            }
+            anyhow::Ok(())
+        });
+
+        let mut ids = Vec::new();
+        for _ in 0..2 {
+            match transport.receive()? {
+                JsonRpcMessage::Request(request) => ids.push(request.id),
+                other => panic!("Expected requests, got {:?}", other),
+            }
+        }
+        writer.await??;
+        assert_eq!(ids, [1, 2]);
+        let error = transport.receive().unwrap_err();
+        assert!(error.is::<Closed>(), "{}", error);
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_concurrent_sends_do_not_interleave() -> Result<()> {
+        let (reader, writer) = tokio::io::duplex(1024);
+        let transport = Arc::new(LineTransport::with_io(tokio::io::empty(), writer));
+
+        let sends: Vec<_> = (0..20)
+            .map(|i| {
+                let transport = transport.clone();
+                tokio::spawn(async move {
+                    transport.send(&tool_response(&format!("{}", i).repeat(500)))
+                })
+            })
+            .collect();
+        let mut lines = BufReader::new(reader).lines();
+        let mut received = 0;
+        while received < 20 {
+            let line = lines.next_line().await?.expect("line");
+            let message: serde_json::Value = serde_json::from_str(&line)?;
+            assert_eq!(message["id"], json!(7));
+            received += 1;
+        }
+        for send in sends {
+            send.await??;
+        }
+        Ok(())
+    }
+}
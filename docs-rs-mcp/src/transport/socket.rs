@@ -0,0 +1,250 @@
+//! The line transport of [`LineTransport`] on local sockets, for clients and supervisors that
+//! connect to a running server instead of spawning one per session: a TCP port on the loopback
+//! interface, or a Unix domain socket on macOS and Linux. Every connection is a session with a
+//! server of its own.
+//!
+//! Under systemd socket activation (`LISTEN_FDS`), the socket passed in is used instead of
+//! binding one.
+
+use std::net::TcpListener as StdTcpListener;
+#[cfg(unix)]
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+use tokio::io::{AsyncRead, AsyncWrite, BufReader};
+use tokio::net::TcpListener;
+#[cfg(unix)]
+use tokio::net::UnixListener;
+use tracing::{debug, info, warn};
+
+use super::LineTransport;
+use crate::server;
+
+/// The first file descriptor systemd passes, see `sd_listen_fds(3)`
+#[cfg(unix)]
+const LISTEN_FDS_START: std::os::fd::RawFd = 3;
+
+/// A TCP listener on `bind`, or the socket systemd passed in.
+pub fn tcp_listener(bind: &str) -> Result<TcpListener> {
+    #[cfg(unix)]
+    if let Some(fd) = activated_socket() {
+        // SAFETY: systemd passed the descriptor to this process, and nothing else claims it
+        let listener = unsafe { <StdTcpListener as std::os::fd::FromRawFd>::from_raw_fd(fd) };
+        listener.set_nonblocking(true)?;
+        return Ok(TcpListener::from_std(listener)?);
+    }
+    let listener = StdTcpListener::bind(bind).context(format!("Failed to listen on {}", bind))?;
+    listener.set_nonblocking(true)?;
+    Ok(TcpListener::from_std(listener)?)
+}
+
+/// A Unix socket listener on `path`, or the socket systemd passed in. A socket left over from
+/// an earlier run is replaced, and the new one is only accessible to the current user.
+#[cfg(unix)]
+pub fn unix_listener(path: &Path) -> Result<UnixListener> {
+    use std::os::unix::fs::{FileTypeExt, PermissionsExt};
+    use std::os::unix::net::UnixListener as StdUnixListener;
+
+    if let Some(fd) = activated_socket() {
+        // SAFETY: systemd passed the descriptor to this process, and nothing else claims it
+        let listener = unsafe { <StdUnixListener as std::os::fd::FromRawFd>::from_raw_fd(fd) };
+        listener.set_nonblocking(true)?;
+        return Ok(UnixListener::from_std(listener)?);
+    }
+    if let Ok(metadata) = std::fs::symlink_metadata(path) {
+        if !metadata.file_type().is_socket() {
+            bail!("{} exists and isn't a socket", path.display());
+        }
+        std::fs::remove_file(path).context(format!(
+            "Failed to remove the old socket {}",
+            path.display()
+        ))?;
+    }
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let listener =
+        UnixListener::bind(path).context(format!("Failed to listen on {}", path.display()))?;
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))?;
+    Ok(listener)
+}
+
+/// Serves MCP to every connection to `listener`, which must be on the loopback interface: the
+/// transport has no authentication, the http transport with a token is for other machines.
+pub async fn serve_tcp(listener: TcpListener, admin_enabled: bool) -> Result<()> {
+    let address = listener.local_addr()?;
+    if !address.ip().is_loopback() {
+        bail!(
+            "The tcp transport only listens on loopback addresses, not {}; serve other machines \
+             with the http transport and a token",
+            address
+        );
+    }
+    info!("Serving MCP on tcp://{}", address);
+    loop {
+        match listener.accept().await {
+            Ok((stream, peer)) => spawn_session(stream, peer.to_string(), admin_enabled),
+            Err(e) => accept_failed(e).await,
+        }
+    }
+}
+
+/// Serves MCP to every connection to `listener`.
+#[cfg(unix)]
+pub async fn serve_unix(listener: UnixListener, admin_enabled: bool) -> Result<()> {
+    let address = listener.local_addr()?;
+    info!(
+        "Serving MCP on {}",
+        address
+            .as_pathname()
+            .unwrap_or(Path::new("(unnamed socket)"))
+            .display()
+    );
+    let mut sessions = 0u64;
+    loop {
+        match listener.accept().await {
+            Ok((stream, _)) => {
+                sessions += 1;
+                spawn_session(stream, format!("connection {}", sessions), admin_enabled)
+            }
+            Err(e) => accept_failed(e).await,
+        }
+    }
+}
+
+/// Starts a server for the client on the other end of `stream`.
+fn spawn_session<S>(stream: S, peer: String, admin_enabled: bool)
+where
+    S: AsyncRead + AsyncWrite + Send + 'static,
+{
+    let (reader, writer) = tokio::io::split(stream);
+    let transport = LineTransport::with_io(BufReader::new(reader), writer);
+    let server = server::build(transport, admin_enabled);
+    tokio::spawn(async move {
+        debug!("Session with {} opened", peer);
+        match server::listen(&server).await {
+            Ok(()) => debug!("Session with {} closed", peer),
+            Err(e) => debug!("Session with {} ended: {}", peer, e),
+        }
+    });
+}
+
+/// Waits out a failure to accept a connection; running out of file descriptors passes once
+/// other connections close.
+async fn accept_failed(e: std::io::Error) {
+    warn!("Failed to accept a connection: {}", e);
+    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+}
+
+/// The socket systemd passed to this process, if it was socket activated
+#[cfg(unix)]
+fn activated_socket() -> Option<std::os::fd::RawFd> {
+    activated_socket_in(|key| std::env::var(key).ok(), std::process::id())
+}
+
+#[cfg(unix)]
+fn activated_socket_in(
+    lookup: impl Fn(&str) -> Option<String>,
+    pid: u32,
+) -> Option<std::os::fd::RawFd> {
+    // The variables are meant for this process, not for children that inherited them
+    let for_us = lookup("LISTEN_PID")?.parse::<u32>().ok()? == pid;
+    let count = lookup("LISTEN_FDS")?.parse::<u32>().ok()?;
+    if !for_us || count == 0 {
+        return None;
+    }
+    if count > 1 {
+        warn!("Socket activated with {} sockets, using the first", count);
+    }
+    Some(LISTEN_FDS_START)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use serde_json::{json, Value};
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt};
+
+    /// Sends `initialize` over `stream`, returning the response.
+    async fn initialize<S: AsyncRead + AsyncWrite + Unpin>(stream: S) -> Result<Value> {
+        let (reader, mut writer) = tokio::io::split(stream);
+        let request = json!({"jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {}});
+        writer
+            .write_all(format!("{}\n", request).as_bytes())
+            .await?;
+        let mut line = String::new();
+        BufReader::new(reader).read_line(&mut line).await?;
+        Ok(serde_json::from_str(&line)?)
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_tcp_sessions() -> Result<()> {
+        let listener = tcp_listener("127.0.0.1:0")?;
+        let address = listener.local_addr()?;
+        tokio::spawn(serve_tcp(listener, false));
+
+        // Each connection is a session of its own
+        for _ in 0..2 {
+            let response = initialize(tokio::net::TcpStream::connect(address).await?).await?;
+            assert_eq!(response["id"], 1);
+            assert!(response["result"]["protocolVersion"].is_string());
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_tcp_refuses_other_interfaces() -> Result<()> {
+        let listener = tcp_listener("0.0.0.0:0")?;
+        let error = serve_tcp(listener, false).await.unwrap_err();
+        assert!(error.to_string().contains("loopback"), "{}", error);
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_unix_sessions() -> Result<()> {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = std::env::temp_dir().join(format!("docs-rs-mcp-socket-{}", std::process::id()));
+        let path = dir.join("mcp.sock");
+        // A socket left over from an earlier run is replaced
+        drop(unix_listener(&path)?);
+        let listener = unix_listener(&path)?;
+        assert_eq!(
+            std::fs::metadata(&path)?.permissions().mode() & 0o777,
+            0o600
+        );
+        tokio::spawn(serve_unix(listener, false));
+
+        let response = initialize(tokio::net::UnixStream::connect(&path).await?).await?;
+        assert_eq!(response["id"], 1);
+
+        std::fs::remove_dir_all(&dir)?;
+        std::fs::create_dir_all(&dir)?;
+        std::fs::write(&path, "not a socket")?;
+        assert!(unix_listener(&path).is_err(), "Other files are left alone");
+        std::fs::remove_dir_all(&dir)?;
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_activated_socket() {
+        let env = |pid: &'static str, fds: &'static str| {
+            move |key: &str| match key {
+                "LISTEN_PID" => Some(pid.to_string()),
+                "LISTEN_FDS" => Some(fds.to_string()),
+                _ => None,
+            }
+        };
+        assert_eq!(activated_socket_in(env("42", "1"), 42), Some(3));
+        assert_eq!(
+            activated_socket_in(env("41", "1"), 42),
+            None,
+            "Inherited from the parent"
+        );
+        assert_eq!(activated_socket_in(env("42", "0"), 42), None);
+        assert_eq!(activated_socket_in(|_| None, 42), None);
+    }
+}
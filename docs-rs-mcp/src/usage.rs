@@ -0,0 +1,133 @@
+//! Shell completions and man pages, generated from the command line definitions in
+//! [`args`](crate::args).
+//!
+//! ```text
+//! docs-rs-mcp completions bash > ~/.local/share/bash-completion/completions/docs-rs-mcp
+//! docs-rs-mcp man > ~/.local/share/man/man1/docs-rs-mcp.1
+//! docs-rs-mcp man --out ~/.local/share/man/man1
+//! ```
+
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use clap::CommandFactory;
+use clap_complete::Shell;
+use clap_mangen::Man;
+use roff::{bold, italic, roman, Roff};
+
+use crate::args::Cli;
+use crate::config::CONFIG_ENV;
+
+const BIN: &str = "docs-rs-mcp";
+
+/// Environment variables read besides the ones mirroring config settings
+const ENVIRONMENT: &[(&str, &str)] = &[
+    (CONFIG_ENV, "Config file to read, like --config"),
+    (
+        "PAGER",
+        "Pager for --pretty output on a terminal, less -FRX by default",
+    ),
+    ("NO_COLOR", "Disables colors in --pretty output when set"),
+    (
+        "FORCE_COLOR",
+        "Enables colors in --pretty output even when stdout isn't a terminal",
+    ),
+];
+
+/// Where the config file and cache are kept unless configured otherwise
+const FILES: &[(&str, &str)] = &[
+    (
+        "~/.config/docs-rs-mcp/config.toml",
+        "The config file, on Linux. ~/Library/Application Support/docs-rs-mcp on macOS",
+    ),
+    (
+        "~/.cache/docs-rs-mcp",
+        "Cached pages, the item index and local documentation builds, on Linux. \
+         ~/Library/Caches/docs-rs-mcp on macOS",
+    ),
+];
+
+/// The completion script for `shell`.
+pub fn completions(shell: Shell) -> String {
+    let mut out = Vec::new();
+    clap_complete::generate(shell, &mut Cli::command(), BIN, &mut out);
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+/// Writes the man page of the binary to `out`.
+pub fn man_page(out: &mut dyn Write) -> Result<()> {
+    Man::new(Cli::command()).render(out)?;
+    extra_sections().to_writer(out)?;
+    Ok(())
+}
+
+/// Writes the man page of the binary and one per command, `docs-rs-mcp-<command>.1`, to `dir`.
+pub fn write_man_pages(dir: &Path) -> Result<()> {
+    fs::create_dir_all(dir).context(format!("Failed to create {}", dir.display()))?;
+    clap_mangen::generate_to(Cli::command(), dir)
+        .context(format!("Failed to write man pages to {}", dir.display()))?;
+    // The binary's page again, with the sections clap doesn't know about
+    let path = dir.join(format!("{}.1", BIN));
+    let mut file = File::create(&path).context(format!("Failed to write {}", path.display()))?;
+    man_page(&mut file)
+}
+
+/// The ENVIRONMENT and FILES sections
+fn extra_sections() -> Roff {
+    let mut roff = Roff::new();
+    roff.control("SH", ["ENVIRONMENT"]);
+    for (name, help) in ENVIRONMENT {
+        roff.control("TP", [])
+            .text([bold(*name)])
+            .text([roman(*help)]);
+    }
+    roff.control("SH", ["FILES"]);
+    for (path, help) in FILES {
+        roff.control("TP", [])
+            .text([italic(*path)])
+            .text([roman(*help)]);
+    }
+    roff.control("PP", [])
+        .text([roman("docs-rs-mcp paths prints the locations in effect.")]);
+    roff
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_completions() {
+        let bash = completions(Shell::Bash);
+        assert!(bash.contains("--lockfile"), "Command flags are completed");
+        assert!(bash.contains("--offline"), "Config flags are completed");
+        assert!(bash.contains("complete -F _docs-rs-mcp"));
+
+        let zsh = completions(Shell::Zsh);
+        assert!(zsh.starts_with("#compdef docs-rs-mcp\n"));
+        assert!(zsh.contains("Directory to write the files to"));
+
+        let fish = completions(Shell::Fish);
+        assert!(fish.contains("-l struct -d 'Path of the struct, e.g. sync::Mutex'"));
+    }
+
+    #[test]
+    fn test_man_pages() -> Result<()> {
+        let mut page = Vec::new();
+        man_page(&mut page)?;
+        let page = String::from_utf8(page)?;
+        assert!(page.contains(".TH docs-rs-mcp 1 "));
+        assert!(page.contains("\\-\\-offline"));
+        assert!(page.contains(".SH ENVIRONMENT\n.TP\n\\fBDOCS_RS_MCP_CONFIG\\fR\n"));
+
+        let dir = std::env::temp_dir().join(format!("docs-rs-mcp-man-{}", std::process::id()));
+        write_man_pages(&dir)?;
+        let index = fs::read_to_string(dir.join("docs-rs-mcp-index.1"))?;
+        assert!(index.contains("\\-\\-lockfile"));
+        assert!(fs::read_to_string(dir.join("docs-rs-mcp.1"))?.contains(".SH FILES"));
+        fs::remove_dir_all(&dir)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,61 @@
+//! Checks on the crate names and versions clients send, for the places that put them into
+//! paths on disk or into a generated manifest.
+
+use crate::error::DocsRsMcpError;
+
+/// Longest crate name crates.io accepts
+const MAX_CRATE_NAME_LEN: usize = 64;
+
+/// Refuses crate names crates.io wouldn't publish: anything but ASCII letters, digits, `-`
+/// and `_`, or longer than 64 characters.
+pub(crate) fn crate_name(name: &str) -> Result<(), DocsRsMcpError> {
+    let allowed = |c: char| c.is_ascii_alphanumeric() || c == '-' || c == '_';
+    if name.is_empty() || name.len() > MAX_CRATE_NAME_LEN || !name.chars().all(allowed) {
+        return Err(DocsRsMcpError::InvalidInput(format!(
+            "Invalid crate name '{}', expected up to {} ASCII letters, digits, '-' or '_'",
+            name, MAX_CRATE_NAME_LEN
+        )));
+    }
+    Ok(())
+}
+
+/// Refuses versions that are neither `latest` nor a semver version.
+pub(crate) fn version(version: &str) -> Result<(), DocsRsMcpError> {
+    if version == "latest" {
+        return Ok(());
+    }
+    semver::Version::parse(version).map(drop).map_err(|e| {
+        DocsRsMcpError::InvalidInput(format!(
+            "Invalid version '{}', expected 'latest' or a semver version: {}",
+            version, e
+        ))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_crate_name() {
+        assert!(crate_name("serde").is_ok());
+        assert!(crate_name("tokio-util").is_ok());
+        assert!(crate_name("proc_macro2").is_ok());
+        assert!(crate_name(&"a".repeat(64)).is_ok());
+        assert!(crate_name("").is_err());
+        assert!(crate_name(&"a".repeat(65)).is_err());
+        assert!(crate_name("../etc").is_err());
+        assert!(crate_name("serde = { path = \"/\" }").is_err());
+        assert!(crate_name("ſerde").is_err());
+    }
+
+    #[test]
+    fn test_version() {
+        assert!(version("latest").is_ok());
+        assert!(version("1.0.210").is_ok());
+        assert!(version("0.1.0-alpha.1+build").is_ok());
+        assert!(version("1.0").is_err());
+        assert!(version("../../1.0.0").is_err());
+        assert!(version("1.0.0\", path = \"/").is_err());
+    }
+}
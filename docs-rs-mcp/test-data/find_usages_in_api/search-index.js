@@ -0,0 +1,5 @@
+var searchIndex = new Map(JSON.parse('[\
+["demo",{"t":"CFFFNNNHHF","n":["demo","Client","Request","Response","send","status","text","log_response","collect","Error"],"q":[[1,"demo"],[10,"core::result"],[11,"alloc::string"],[12,"core::iter::traits::collect"],[13,"alloc::vec"]],"i":[0,0,0,0,1,3,3,0,0,0],"f":"````{{bd}{{h{fj}}}}{fl}{fn}{f}{c{{Ad{n}}}{{A`{}{{Ab{f}}}}}}`","D":"`","p":[[5,"Client",1],[5,"Request",1],[5,"Response",1],[6,"Result",10],[5,"Error",1],[1,"u16"],[5,"String",11],[10,"IntoIterator",12],[17,"Item"],[5,"Vec",13]],"r":[],"b":[],"c":"OjAAAAAAAAA=","e":"OjAAAAAAAAA="}]\
+]'));
+if (typeof exports !== 'undefined') exports.searchIndex = searchIndex;
+else if (window.initSearch) window.initSearch(searchIndex);
@@ -0,0 +1,5 @@
+var searchIndex = new Map(JSON.parse('[\
+["demo",{"t":"CCFFNNNKHQQE","n":["demo","trace","TracerProvider","TracerProviderBuilder","build","with_sampler","builder","ShouldSample","init","span","trace","Sampler"],"q":[[1,"demo"],[2,"demo::trace"],[8,"demo"]],"i":"````d0b`````","f":"```````","D":"`","p":[[5,"TracerProvider"],[5,"TracerProviderBuilder"]],"r":[[11,"demo::trace::Sampler"]],"b":[],"c":"OjAAAAEAAAAAAAAAEAAAAAUA","e":"OjAAAAAAAAA="}]\
+]'));
+if (typeof exports !== 'undefined') exports.searchIndex = searchIndex;
+else if (window.initSearch) window.initSearch(searchIndex);
@@ -0,0 +1,5 @@
+var searchIndex = new Map(JSON.parse('[\
+["demo",{"t":"CCCCCQFHKYKNFH","n":["demo","io","prelude","runtime","task","select","UnixShim","spawn","Encode","Encode","AsyncReadExt","read_to_end","Runtime","spawn"],"q":[[1,"demo"],[10,"demo::io"],[12,"demo::runtime"],[13,"demo::task"]],"i":[0,0,0,0,0,0,0,0,0,0,0,1,0,0],"D":"`","p":[[10,"AsyncReadExt"]],"r":[],"b":[],"c":"OjAAAAAAAAA=","e":"OjAAAAAAAAA="}]\
+]'));
+if (typeof exports !== 'undefined') exports.searchIndex = searchIndex;
+else if (window.initSearch) window.initSearch(searchIndex);
@@ -0,0 +1,34 @@
+use anyhow::Result;
+use docs_rs_mcp::tools::AttributeMacroDocsTool;
+use mcp_sdk::{tools::Tool, types::ToolResponseContent};
+use serde_json::json;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_attribute_macro_docs_tokio_main() -> Result<()> {
+    let tool = AttributeMacroDocsTool::new();
+
+    let response = tool.call(Some(json!({
+        "crate_name": "tokio",
+        "attribute_name": "#[tokio::main]",
+        "version": "1.43.0"
+    })))?;
+
+    let content = match &response.content[0] {
+        ToolResponseContent::Text { text } => text.as_str(),
+        _ => panic!("Expected text response"),
+    };
+    let result: serde_json::Value = serde_json::from_str(content)?;
+
+    assert_eq!(result["name"], "main");
+    assert!(result["doc_link"]
+        .as_str()
+        .is_some_and(|link| link.ends_with("attr.main.html")));
+    let arguments = result["arguments"]
+        .as_array()
+        .expect("arguments should be a list");
+    assert!(arguments.contains(&json!("flavor")));
+    assert!(arguments.contains(&json!("worker_threads")));
+    assert!(!result["examples"].as_array().unwrap().is_empty());
+
+    Ok(())
+}
@@ -0,0 +1,43 @@
+use anyhow::Result;
+use docs_rs_mcp::tools::BrowseSourceTool;
+use mcp_sdk::{tools::Tool, types::ToolResponseContent};
+use serde_json::json;
+
+fn text(response: &mcp_sdk::types::CallToolResponse) -> &str {
+    match &response.content[0] {
+        ToolResponseContent::Text { text } => text.as_str(),
+        _ => panic!("Expected text response"),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_browse_source_semver() -> Result<()> {
+    let tool = BrowseSourceTool::new();
+
+    let response = tool.call(Some(json!({
+        "crate_name": "semver",
+        "path": "src/",
+        "version": "1.0.25"
+    })))?;
+    let listing: serde_json::Value = serde_json::from_str(text(&response))?;
+    assert_eq!(listing["type"], "directory");
+    assert!(listing["entries"]
+        .as_array()
+        .expect("entries should be an array")
+        .iter()
+        .any(|entry| entry["name"] == "lib.rs"));
+
+    let response = tool.call(Some(json!({
+        "crate_name": "semver",
+        "path": "src/lib.rs",
+        "version": "1.0.25"
+    })))?;
+    let file: serde_json::Value = serde_json::from_str(text(&response))?;
+    assert_eq!(file["type"], "file");
+    assert!(file["contents"]
+        .as_str()
+        .expect("contents should be a string")
+        .contains("pub struct Version"));
+
+    Ok(())
+}
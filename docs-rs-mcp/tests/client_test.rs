@@ -0,0 +1,32 @@
+use anyhow::Result;
+use docs_rs_mcp::{DocsClient, DocsRsMcpError};
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_client_struct_docs_and_search() -> Result<()> {
+    let client = DocsClient::new();
+
+    let docs = client
+        .struct_docs("semver", "Version", Some("1.0.25"))
+        .await?;
+    assert_eq!(docs.name(), "Version");
+    assert!(docs.description().contains("SemVer version"));
+
+    let hits = client
+        .search("semver", "VersionReq", Some("1.0.25"), 3)
+        .await?;
+    assert_eq!(hits[0].path(), "semver::VersionReq");
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_client_not_found() {
+    let result = DocsClient::new()
+        .crate_items("this-crate-definitely-does-not-exist-12345", Some("0.1.0"))
+        .await;
+    assert!(
+        matches!(result, Err(DocsRsMcpError::NotFound(_))),
+        "{:?}",
+        result.map(|_| ())
+    );
+}
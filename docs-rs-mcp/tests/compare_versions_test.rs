@@ -0,0 +1,28 @@
+use anyhow::Result;
+use docs_rs_mcp::tools::CompareVersionsTool;
+use mcp_sdk::{tools::Tool, types::ToolResponseContent};
+use serde_json::json;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_compare_versions_scraper() -> Result<()> {
+    let tool = CompareVersionsTool::new();
+
+    let response = tool.call(Some(json!({
+        "crate_name": "scraper",
+        "from_version": "0.12.0",
+        "to_version": "0.22.0"
+    })))?;
+
+    let content = match &response.content[0] {
+        ToolResponseContent::Text { text } => text.as_str(),
+        _ => panic!("Expected text response"),
+    };
+    let diff: serde_json::Value = serde_json::from_str(content)?;
+
+    assert_eq!(diff["from_version"].as_str(), Some("0.12.0"));
+    assert_eq!(diff["to_version"].as_str(), Some("0.22.0"));
+    let added = diff["added"].as_array().expect("added should be an array");
+    assert!(!added.is_empty(), "scraper gained items since 0.12");
+
+    Ok(())
+}
@@ -0,0 +1,55 @@
+use anyhow::Result;
+use docs_rs_mcp::tools::CrateCategoriesTool;
+use mcp_sdk::{tools::Tool, types::ToolResponseContent};
+use serde_json::json;
+
+fn call(input: serde_json::Value) -> Result<serde_json::Value> {
+    let response = CrateCategoriesTool::new().call(Some(input))?;
+    let content = match &response.content[0] {
+        ToolResponseContent::Text { text } => text.as_str(),
+        _ => panic!("Expected text response"),
+    };
+    Ok(serde_json::from_str(content)?)
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_crate_categories_tokio() -> Result<()> {
+    let categories = call(json!({ "crate_name": "tokio" }))?;
+
+    assert!(
+        categories["categories"]
+            .as_array()
+            .is_some_and(|c| c.iter().any(|category| category["slug"] == "asynchronous")),
+        "tokio should be in the asynchronous category"
+    );
+    assert!(categories["keywords"]
+        .as_array()
+        .is_some_and(|k| !k.is_empty()));
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_category_crates_asynchronous() -> Result<()> {
+    let crates = call(json!({ "category": "asynchronous", "limit": 5 }))?;
+
+    let names: Vec<&str> = crates["crates"]
+        .as_array()
+        .map(|c| {
+            c.iter()
+                .filter_map(|krate| krate["name"].as_str())
+                .collect()
+        })
+        .unwrap_or_default();
+    assert_eq!(names.len(), 5);
+    assert!(
+        names.contains(&"tokio"),
+        "tokio should be a top crate: {names:?}"
+    );
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_crate_categories_requires_one_mode() {
+    assert!(call(json!({})).is_err());
+    assert!(call(json!({ "crate_name": "tokio", "category": "asynchronous" })).is_err());
+}
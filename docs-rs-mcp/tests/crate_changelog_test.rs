@@ -0,0 +1,38 @@
+use anyhow::Result;
+use docs_rs_mcp::tools::CrateChangelogTool;
+use mcp_sdk::{tools::Tool, types::ToolResponseContent};
+use serde_json::json;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_crate_changelog_tokio() -> Result<()> {
+    let tool = CrateChangelogTool::new();
+
+    let response = tool.call(Some(json!({
+        "crate_name": "tokio",
+        "from_version": "1.41.0",
+        "to_version": "1.43.0"
+    })))?;
+
+    let content = match &response.content[0] {
+        ToolResponseContent::Text { text } => text.as_str(),
+        _ => panic!("Expected text response"),
+    };
+    let changelog: serde_json::Value = serde_json::from_str(content)?;
+
+    let versions: Vec<&str> = changelog["entries"]
+        .as_array()
+        .expect("entries should be an array")
+        .iter()
+        .filter_map(|entry| entry["version"].as_str())
+        .collect();
+    assert!(
+        versions.contains(&"1.43.0"),
+        "Upper bound should be included"
+    );
+    assert!(
+        !versions.contains(&"1.41.0"),
+        "Lower bound should be excluded"
+    );
+
+    Ok(())
+}
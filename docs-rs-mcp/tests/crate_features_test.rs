@@ -0,0 +1,37 @@
+use anyhow::Result;
+use docs_rs_mcp::tools::CrateFeaturesTool;
+use mcp_sdk::{tools::Tool, types::ToolResponseContent};
+use serde_json::json;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_crate_features_tokio() -> Result<()> {
+    let tool = CrateFeaturesTool::new();
+
+    let response = tool.call(Some(json!({
+        "crate_name": "tokio",
+        "version": "1.43.0"
+    })))?;
+
+    let content = match &response.content[0] {
+        ToolResponseContent::Text { text } => text.as_str(),
+        _ => panic!("Expected text response"),
+    };
+    let features: serde_json::Value = serde_json::from_str(content)?;
+
+    let full = features["features"]
+        .as_array()
+        .expect("features should be an array")
+        .iter()
+        .find(|feature| feature["name"] == "full")
+        .expect("tokio should have a full feature");
+    let closure = full["closure"]
+        .as_array()
+        .expect("closure should be an array");
+    assert!(
+        closure.iter().any(|entry| entry == "rt-multi-thread"),
+        "full should enable rt-multi-thread: {:?}",
+        closure
+    );
+
+    Ok(())
+}
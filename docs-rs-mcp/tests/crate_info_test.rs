@@ -1,3 +1,5 @@
+#![cfg(feature = "native")]
+
 use anyhow::Result;
 use docs_rs_mcp::tools::CrateInfoTool;
 use mcp_sdk::{tools::Tool, types::ToolResponseContent};
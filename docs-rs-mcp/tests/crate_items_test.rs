@@ -1,5 +1,6 @@
 use anyhow::Result;
 use docs_rs_mcp::tools::CrateItemsTool;
+use docs_rs_mcp::{DocsRsMcpError, TypedCall};
 use mcp_sdk::{tools::Tool, types::ToolResponseContent};
 use serde_json::json;
 
@@ -189,3 +190,42 @@ fn test_crate_items_invalid_version() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_crate_items_resolves_latest() -> Result<()> {
+    let tool = CrateItemsTool::new();
+
+    let response = tool.call(Some(json!({
+        "crate_name": "serde",
+        "channel": "stable"
+    })))?;
+
+    let content = match &response.content[0] {
+        ToolResponseContent::Text { text } => text.as_str(),
+        _ => panic!("Expected text response"),
+    };
+    let info: serde_json::Value = serde_json::from_str(content)?;
+
+    let resolved = &info["resolved_version"];
+    assert_eq!(resolved["requested"], "latest");
+    assert_eq!(
+        info["version"], resolved["version"],
+        "Items are listed for the resolved release rather than latest"
+    );
+    assert_eq!(resolved["channel"], "stable");
+    assert_eq!(resolved["prerelease"], false, "serde has stable releases");
+
+    Ok(())
+}
+
+#[test]
+fn test_crate_items_typed_errors() {
+    let tool = CrateItemsTool::new();
+
+    let result = tool.call_typed(Some(json!({ "version": "1.0.0" })));
+    assert!(
+        matches!(result, Err(DocsRsMcpError::InvalidInput(_))),
+        "A missing crate_name is invalid input: {:?}",
+        result.map(|_| ())
+    );
+}
@@ -0,0 +1,50 @@
+use anyhow::Result;
+use docs_rs_mcp::tools::CrateLicenseTool;
+use mcp_sdk::{tools::Tool, types::ToolResponseContent};
+use serde_json::json;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_crate_license_serde() -> Result<()> {
+    let tool = CrateLicenseTool::new();
+
+    let response = tool.call(Some(json!({
+        "crate_name": "serde",
+        "version": "1.0.217",
+        "include_text": false
+    })))?;
+
+    let content = match &response.content[0] {
+        ToolResponseContent::Text { text } => text.as_str(),
+        _ => panic!("Expected text response"),
+    };
+    let license: serde_json::Value = serde_json::from_str(content)?;
+
+    assert_eq!(
+        license["expression"]["licenses"],
+        json!(["MIT", "Apache-2.0"])
+    );
+    assert_eq!(
+        license["expression"]["options"],
+        json!([["MIT"], ["Apache-2.0"]])
+    );
+    let files: Vec<&str> = license["files"]
+        .as_array()
+        .expect("Expected license files")
+        .iter()
+        .filter_map(|file| file["path"].as_str())
+        .collect();
+    assert!(files.contains(&"LICENSE-MIT"));
+    assert!(files.contains(&"LICENSE-APACHE"));
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_crate_license_unknown_version() {
+    let tool = CrateLicenseTool::new();
+
+    let result = tool.call(Some(json!({
+        "crate_name": "serde",
+        "version": "0.0.0-nonexistent"
+    })));
+    assert!(result.is_err());
+}
@@ -0,0 +1,46 @@
+use anyhow::Result;
+use docs_rs_mcp::tools::CrateOwnersTool;
+use mcp_sdk::{tools::Tool, types::ToolResponseContent};
+use serde_json::json;
+
+#[test]
+fn test_crate_owners_tokio() -> Result<()> {
+    let tool = CrateOwnersTool::new();
+
+    let response = tool.call(Some(json!({
+        "crate_name": "tokio"
+    })))?;
+
+    let content = match &response.content[0] {
+        ToolResponseContent::Text { text } => text.as_str(),
+        _ => panic!("Expected text response"),
+    };
+    let owners: serde_json::Value = serde_json::from_str(content)?;
+
+    assert_eq!(owners["crate_name"].as_str(), Some("tokio"));
+    assert!(
+        owners["users"].as_array().is_some_and(|u| !u.is_empty()),
+        "tokio should have at least one user owner"
+    );
+    assert!(
+        owners["teams"]
+            .as_array()
+            .is_some_and(|t| t.iter().any(|team| team["login"]
+                .as_str()
+                .is_some_and(|login| login.starts_with("github:tokio-rs")))),
+        "tokio should be owned by a tokio-rs team"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_crate_owners_nonexistent() {
+    let tool = CrateOwnersTool::new();
+
+    let result = tool.call(Some(json!({
+        "crate_name": "this-crate-definitely-does-not-exist-12345"
+    })));
+
+    assert!(result.is_err(), "Unknown crate should return an error");
+}
@@ -0,0 +1,35 @@
+use anyhow::Result;
+use docs_rs_mcp::tools::CrateReadmeTool;
+use mcp_sdk::{tools::Tool, types::ToolResponseContent};
+use serde_json::json;
+
+#[test]
+fn test_crate_readme_serde() -> Result<()> {
+    let tool = CrateReadmeTool::new();
+
+    let response = tool.call(Some(json!({
+        "crate_name": "serde",
+        "version": "1.0.217"
+    })))?;
+
+    let content = match &response.content[0] {
+        ToolResponseContent::Text { text } => text.as_str(),
+        _ => panic!("Expected text response"),
+    };
+    let readme: serde_json::Value = serde_json::from_str(content)?;
+
+    assert_eq!(readme["crate_name"].as_str(), Some("serde"));
+    assert_eq!(readme["version"].as_str(), Some("1.0.217"));
+    let markdown = readme["markdown"].as_str().unwrap_or_default();
+    assert!(
+        markdown.contains("Serde"),
+        "README should mention Serde: {}",
+        markdown
+    );
+    assert!(
+        !markdown.contains("<p>"),
+        "README should be converted to Markdown"
+    );
+
+    Ok(())
+}
@@ -0,0 +1,41 @@
+use anyhow::Result;
+use docs_rs_mcp::tools::DependencySnippetTool;
+use mcp_sdk::{tools::Tool, types::ToolResponseContent};
+use serde_json::json;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_dependency_snippet_tokio_tcp_server() -> Result<()> {
+    let tool = DependencySnippetTool::new();
+
+    let response = tool.call(Some(json!({
+        "task": "tcp echo server",
+        "crates": ["tokio"]
+    })))?;
+
+    let content = match &response.content[0] {
+        ToolResponseContent::Text { text } => text.as_str(),
+        _ => panic!("Expected text response"),
+    };
+    let snippet: serde_json::Value = serde_json::from_str(content)?;
+
+    assert_eq!(snippet["dependencies"][0]["crate_name"], "tokio");
+    let features = snippet["dependencies"][0]["features"]
+        .as_array()
+        .expect("Expected features");
+    assert!(features.contains(&json!("net")));
+    assert!(snippet["cargo_toml"]
+        .as_str()
+        .is_some_and(|toml| toml.starts_with("[dependencies]\ntokio = {")));
+    assert!(snippet["snippet"]
+        .as_str()
+        .is_some_and(|code| code.contains("TcpListener")));
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_dependency_snippet_needs_a_task() {
+    let tool = DependencySnippetTool::new();
+
+    let result = tool.call(Some(json!({ "task": "a tool in Rust" })));
+    assert!(result.is_err());
+}
@@ -0,0 +1,48 @@
+use anyhow::Result;
+use docs_rs_mcp::tools::DependencyTreeTool;
+use mcp_sdk::{tools::Tool, types::ToolResponseContent};
+use serde_json::json;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_dependency_tree_tokio() -> Result<()> {
+    let tool = DependencyTreeTool::new();
+
+    let response = tool.call(Some(json!({
+        "crate_name": "tokio",
+        "version": "1.43.0",
+        "features": ["rt-multi-thread", "macros"],
+        "depth": 2
+    })))?;
+
+    let content = match &response.content[0] {
+        ToolResponseContent::Text { text } => text.as_str(),
+        _ => panic!("Expected text response"),
+    };
+    let tree: serde_json::Value = serde_json::from_str(content)?;
+
+    assert_eq!(tree["version"].as_str(), Some("1.43.0"));
+    let dependencies = tree["dependencies"].as_array().expect("dependencies");
+    assert!(
+        dependencies
+            .iter()
+            .any(|dep| dep["name"] == "tokio-macros" && dep["optional"] == true),
+        "The macros feature should pull in tokio-macros"
+    );
+    assert!(
+        dependencies.iter().all(|dep| dep["kind"] != "dev"),
+        "Dev-dependencies aren't part of the tree"
+    );
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_dependency_tree_unknown_feature() {
+    let tool = DependencyTreeTool::new();
+
+    let result = tool.call(Some(json!({
+        "crate_name": "tokio",
+        "version": "1.43.0",
+        "features": ["no-such-feature"]
+    })));
+    assert!(result.is_err());
+}
@@ -0,0 +1,35 @@
+use anyhow::Result;
+use docs_rs_mcp::tools::DeprecatedItemsTool;
+use mcp_sdk::{tools::Tool, types::ToolResponseContent};
+use serde_json::json;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_deprecated_items_chrono() -> Result<()> {
+    let tool = DeprecatedItemsTool::new();
+
+    let response = tool.call(Some(json!({
+        "crate_name": "chrono",
+        "version": "0.4.39"
+    })))?;
+
+    let content = match &response.content[0] {
+        ToolResponseContent::Text { text } => text.as_str(),
+        _ => panic!("Expected text response"),
+    };
+    let result: serde_json::Value = serde_json::from_str(content)?;
+    let items = result["items"].as_array().expect("items should be a list");
+    let date = items
+        .iter()
+        .find(|i| i["path"] == "chrono::Date" && i["kind"] == "struct")
+        .expect("chrono::Date should be deprecated");
+
+    assert_eq!(date["deprecation"]["since"], "0.4.23");
+    assert!(
+        items.iter().any(|i| i["doc_link"]
+            .as_str()
+            .is_some_and(|link| link.contains('#'))),
+        "Deprecated methods are listed alongside types"
+    );
+
+    Ok(())
+}
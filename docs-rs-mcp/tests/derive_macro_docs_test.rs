@@ -0,0 +1,29 @@
+use anyhow::Result;
+use docs_rs_mcp::tools::DeriveMacroDocsTool;
+use mcp_sdk::{tools::Tool, types::ToolResponseContent};
+use serde_json::json;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_derive_macro_docs_serde() -> Result<()> {
+    let tool = DeriveMacroDocsTool::new();
+
+    let response = tool.call(Some(json!({
+        "crate_name": "serde",
+        "derive_name": "Serialize",
+        "version": "1.0.217"
+    })))?;
+
+    let content = match &response.content[0] {
+        ToolResponseContent::Text { text } => text.as_str(),
+        _ => panic!("Expected text response"),
+    };
+    let result: serde_json::Value = serde_json::from_str(content)?;
+
+    assert_eq!(result["name"], "Serialize");
+    assert!(result["doc_link"]
+        .as_str()
+        .is_some_and(|link| link.ends_with("derive.Serialize.html")));
+    assert_eq!(result["helper_attributes"], json!(["serde"]));
+
+    Ok(())
+}
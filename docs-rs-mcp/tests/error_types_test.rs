@@ -0,0 +1,37 @@
+use anyhow::Result;
+use docs_rs_mcp::tools::ErrorTypesTool;
+use mcp_sdk::{tools::Tool, types::ToolResponseContent};
+use serde_json::json;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_error_types_url() -> Result<()> {
+    let tool = ErrorTypesTool::new();
+
+    let response = tool.call(Some(json!({
+        "crate_name": "url",
+        "version": "2.5.4"
+    })))?;
+
+    let content = match &response.content[0] {
+        ToolResponseContent::Text { text } => text.as_str(),
+        _ => panic!("Expected text response"),
+    };
+    let result: serde_json::Value = serde_json::from_str(content)?;
+    let error_types = result["error_types"]
+        .as_array()
+        .expect("error_types should be a list");
+    let parse_error = error_types
+        .iter()
+        .find(|t| t["path"] == "url::ParseError")
+        .expect("url::ParseError should be listed");
+
+    assert_eq!(parse_error["kind"], "enum");
+    assert_eq!(parse_error["implements_error"], true);
+    assert!(parse_error["variants"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .any(|v| v["name"] == "EmptyHost"));
+
+    Ok(())
+}
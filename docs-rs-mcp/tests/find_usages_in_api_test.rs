@@ -0,0 +1,35 @@
+use anyhow::Result;
+use docs_rs_mcp::tools::FindUsagesInApiTool;
+use mcp_sdk::{tools::Tool, types::ToolResponseContent};
+use serde_json::json;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_find_usages_reqwest_response() -> Result<()> {
+    let tool = FindUsagesInApiTool::new();
+
+    let response = tool.call(Some(json!({
+        "crate_name": "reqwest",
+        "type_name": "Response",
+        "version": "0.12.12"
+    })))?;
+
+    let content = match &response.content[0] {
+        ToolResponseContent::Text { text } => text.as_str(),
+        _ => panic!("Expected text response"),
+    };
+    let result: serde_json::Value = serde_json::from_str(content)?;
+    let usages = result["usages"]
+        .as_array()
+        .expect("usages should be a list");
+
+    assert!(!usages.is_empty(), "reqwest hands out Responses");
+    assert!(
+        usages.iter().all(|u| !u["path"]
+            .as_str()
+            .unwrap()
+            .starts_with("reqwest::Response::")),
+        "Methods of Response itself are left out by default"
+    );
+
+    Ok(())
+}
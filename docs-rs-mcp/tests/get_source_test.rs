@@ -0,0 +1,33 @@
+use anyhow::Result;
+use docs_rs_mcp::tools::GetSourceTool;
+use mcp_sdk::{tools::Tool, types::ToolResponseContent};
+use serde_json::json;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_get_source_semver_version() -> Result<()> {
+    let tool = GetSourceTool::new();
+
+    let response = tool.call(Some(json!({
+        "crate_name": "semver",
+        "item_path": "Version",
+        "version": "1.0.25"
+    })))?;
+
+    let content = match &response.content[0] {
+        ToolResponseContent::Text { text } => text.as_str(),
+        _ => panic!("Expected text response"),
+    };
+    let source: serde_json::Value = serde_json::from_str(content)?;
+
+    assert_eq!(source["file"], "lib.rs");
+    let code = source["source"]
+        .as_str()
+        .expect("source should be a string");
+    assert!(
+        code.contains("pub struct Version"),
+        "Source should contain the struct definition: {}",
+        code
+    );
+
+    Ok(())
+}
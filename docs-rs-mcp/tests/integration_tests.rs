@@ -78,7 +78,7 @@ fn test_get_struct_docs_integration() {
 
                     // Verify we have methods or traits or fields
                     // Some structs might not have all of these
-                    let has_content = docs["methods"].as_array().is_some_and(|m| !m.is_empty())
+                    let has_content = docs["impls"].as_array().is_some_and(|m| !m.is_empty())
                         || docs["traits"].as_array().is_some_and(|t| !t.is_empty())
                         || docs["fields"].as_array().is_some_and(|f| !f.is_empty());
 
@@ -88,9 +88,13 @@ fn test_get_struct_docs_integration() {
                     );
 
                     // Verify specific methods we know should exist
-                    let methods = docs["methods"].as_array().unwrap();
-                    let method_names: Vec<&str> =
-                        methods.iter().filter_map(|m| m["name"].as_str()).collect();
+                    let impls = docs["impls"].as_array().unwrap();
+                    let method_names: Vec<&str> = impls
+                        .iter()
+                        .filter_map(|block| block["methods"].as_array())
+                        .flatten()
+                        .filter_map(|m| m["name"].as_str())
+                        .collect();
 
                     // The Surreal struct should have these methods
                     assert!(
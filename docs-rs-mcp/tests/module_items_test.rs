@@ -0,0 +1,43 @@
+use anyhow::Result;
+use docs_rs_mcp::tools::ModuleItemsTool;
+use mcp_sdk::{tools::Tool, types::ToolResponseContent};
+use serde_json::json;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_module_items_tokio_sync() -> Result<()> {
+    let tool = ModuleItemsTool::new();
+
+    let response = tool.call(Some(json!({
+        "crate_name": "tokio",
+        "path": "tokio::sync",
+        "version": "1.43.0"
+    })))?;
+
+    let content = match &response.content[0] {
+        ToolResponseContent::Text { text } => text.as_str(),
+        _ => panic!("Expected text response"),
+    };
+    let module: serde_json::Value = serde_json::from_str(content)?;
+
+    assert_eq!(module["path"], "sync");
+    let sections = module["sections"]
+        .as_array()
+        .expect("sections should be an array");
+    let names = |title: &str| -> Vec<String> {
+        sections
+            .iter()
+            .find(|section| section["title"] == title)
+            .and_then(|section| section["items"].as_array())
+            .map(|items| {
+                items
+                    .iter()
+                    .filter_map(|item| item["name"].as_str().map(String::from))
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+    assert!(names("Modules").contains(&"mpsc".to_string()));
+    assert!(names("Structs").contains(&"Mutex".to_string()));
+
+    Ok(())
+}
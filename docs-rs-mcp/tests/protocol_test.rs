@@ -0,0 +1,333 @@
+#![cfg(feature = "native")]
+
+//! Drives the server the way an MCP client does, over an in-memory transport that carries
+//! serialized JSON-RPC messages, so protocol regressions surface and not just tool ones.
+
+use std::collections::BTreeSet;
+use std::sync::mpsc;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use docs_rs_mcp::server;
+use docs_rs_mcp::transport::Closed;
+use mcp_sdk::transport::{Message, Transport};
+use serde_json::{json, Value};
+
+/// One end of an in-memory duplex carrying serialized messages, as a stdio transport would
+struct MemoryTransport {
+    tx: mpsc::Sender<String>,
+    rx: Mutex<mpsc::Receiver<String>>,
+}
+
+fn duplex() -> (MemoryTransport, MemoryTransport) {
+    let (client_tx, server_rx) = mpsc::channel();
+    let (server_tx, client_rx) = mpsc::channel();
+    (
+        MemoryTransport {
+            tx: client_tx,
+            rx: Mutex::new(client_rx),
+        },
+        MemoryTransport {
+            tx: server_tx,
+            rx: Mutex::new(server_rx),
+        },
+    )
+}
+
+impl Transport for MemoryTransport {
+    fn send(&self, message: &Message) -> Result<()> {
+        self.tx.send(serde_json::to_string(message)?)?;
+        Ok(())
+    }
+
+    /// Blocks until the client sends something, like a read from stdin; the runtime moves the
+    /// worker's other tasks elsewhere meanwhile.
+    fn receive(&self) -> Result<Message> {
+        let rx = self.rx.lock().map_err(|_| anyhow!("Poisoned"))?;
+        let line = tokio::task::block_in_place(|| rx.recv()).map_err(|_| Closed)?;
+        Ok(serde_json::from_str(&line)?)
+    }
+
+    fn open(&self) -> Result<()> {
+        Ok(())
+    }
+
+    fn close(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// A client speaking raw JSON-RPC to a server running on the other end of a duplex. Its calls
+/// block the test's thread while the server runs on the runtime's workers.
+struct TestClient {
+    transport: MemoryTransport,
+    next_id: u64,
+}
+
+impl TestClient {
+    fn start(admin_enabled: bool) -> Self {
+        let (client, server) = duplex();
+        let server = server::build(server, admin_enabled);
+        tokio::spawn(async move { server::listen(&server).await });
+        Self {
+            transport: client,
+            next_id: 1,
+        }
+    }
+
+    fn send(&self, message: Value) -> Result<()> {
+        self.transport.tx.send(message.to_string())?;
+        Ok(())
+    }
+
+    fn receive(&self) -> Result<Value> {
+        let rx = self.transport.rx.lock().map_err(|_| anyhow!("Poisoned"))?;
+        let line = rx
+            .recv_timeout(Duration::from_secs(30))
+            .map_err(|e| match e {
+                mpsc::RecvTimeoutError::Timeout => anyhow!("Timed out waiting for the server"),
+                mpsc::RecvTimeoutError::Disconnected => anyhow!("The server closed the connection"),
+            })?;
+        Ok(serde_json::from_str(&line)?)
+    }
+
+    /// Sends a request and returns its response, checking the JSON-RPC envelope.
+    fn request(&mut self, method: &str, params: Value) -> Result<Value> {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.send(json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params }))?;
+
+        let response = self.receive()?;
+        assert_eq!(response["jsonrpc"], "2.0", "{}", response);
+        assert_eq!(response["id"], id, "Responses should answer the request");
+        assert!(
+            response.get("result").is_some() != response.get("error").is_some(),
+            "A response carries exactly one of result and error: {}",
+            response
+        );
+        Ok(response)
+    }
+
+    /// Sends a request that should succeed, returning its result.
+    fn call(&mut self, method: &str, params: Value) -> Result<Value> {
+        let response = self.request(method, params)?;
+        match response.get("error") {
+            Some(error) => Err(anyhow!("{} failed: {}", method, error)),
+            None => Ok(response["result"].clone()),
+        }
+    }
+
+    fn initialize(&mut self) -> Result<Value> {
+        let result = self.call(
+            "initialize",
+            json!({
+                "protocolVersion": "2024-11-05",
+                "capabilities": {},
+                "clientInfo": { "name": "protocol-test", "version": "0.0.0" }
+            }),
+        )?;
+        self.send(json!({ "jsonrpc": "2.0", "method": "notifications/initialized" }))?;
+        Ok(result)
+    }
+
+    fn tool_names(&mut self) -> Result<BTreeSet<String>> {
+        let result = self.call("tools/list", json!({}))?;
+        let tools = result["tools"].as_array().expect("tools array");
+        Ok(tools
+            .iter()
+            .map(|tool| tool["name"].as_str().expect("tool name").to_string())
+            .collect())
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_initialize_advertises_callable_tools() -> Result<()> {
+    let mut client = TestClient::start(true);
+    let result = client.initialize()?;
+
+    assert!(result["protocolVersion"].is_string());
+    assert!(result["serverInfo"]["name"].is_string());
+    let advertised: BTreeSet<String> = result["capabilities"]["tools"]
+        .as_object()
+        .expect("tools capability")
+        .keys()
+        .cloned()
+        .collect();
+
+    // The notification gets no reply, so the next message answers tools/list
+    assert_eq!(
+        advertised,
+        client.tool_names()?,
+        "The tools capability should match tools/list"
+    );
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_tools_list() -> Result<()> {
+    let mut client = TestClient::start(true);
+    client.initialize()?;
+
+    let result = client.call("tools/list", json!({}))?;
+    let tools = result["tools"].as_array().expect("tools array");
+    for tool in tools {
+        assert!(
+            tool["description"].as_str().is_some_and(|d| !d.is_empty()),
+            "{} should have a description",
+            tool["name"]
+        );
+        assert!(
+            tool["inputSchema"].is_object(),
+            "{} should have an input schema",
+            tool["name"]
+        );
+    }
+    let struct_docs = tools
+        .iter()
+        .find(|tool| tool["name"] == "get_struct_docs")
+        .expect("get_struct_docs");
+    assert!(
+        struct_docs["inputSchema"]["properties"]["docs_base_url"].is_object(),
+        "Crate lookups should take a docs_base_url"
+    );
+    let names = client.tool_names()?;
+    for expected in [
+        "crate_info",
+        "crate_items",
+        "get_struct_docs",
+        "admin/cache_purge",
+        "admin/prefetch",
+        "admin/stats",
+    ] {
+        assert!(names.contains(expected), "Missing {}", expected);
+    }
+
+    let mut without_admin = TestClient::start(false);
+    without_admin.initialize()?;
+    assert!(
+        without_admin
+            .tool_names()?
+            .iter()
+            .all(|name| !name.starts_with("admin/")),
+        "Admin tools should be hidden when disabled"
+    );
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_tools_call() -> Result<()> {
+    let mut client = TestClient::start(true);
+    client.initialize()?;
+
+    let result = client.call(
+        "tools/call",
+        json!({ "name": "admin/stats", "arguments": {} }),
+    )?;
+    assert_eq!(result["content"][0]["type"], "text", "{}", result);
+    assert_ne!(result["isError"], true, "{}", result);
+
+    let failed = client.call(
+        "tools/call",
+        json!({ "name": "crate_info", "arguments": {} }),
+    )?;
+    assert_eq!(
+        failed["isError"], true,
+        "Tool failures are reported in the result: {}",
+        failed
+    );
+    let payload: serde_json::Value =
+        serde_json::from_str(failed["content"][0]["text"].as_str().unwrap_or_default())?;
+    assert_eq!(payload["error"]["kind"], "invalid_input", "{}", payload);
+    assert!(payload["error"]["suggestions"].is_array(), "{}", payload);
+
+    let unknown = client.call(
+        "tools/call",
+        json!({ "name": "no_such_tool", "arguments": {} }),
+    )?;
+    assert_eq!(unknown["isError"], true, "{}", unknown);
+    assert!(
+        unknown["content"][0]["text"]
+            .as_str()
+            .is_some_and(|text| text.contains("no_such_tool")),
+        "{}",
+        unknown
+    );
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_list_capabilities() -> Result<()> {
+    let mut client = TestClient::start(true);
+    client.initialize()?;
+
+    let listed = client.call("tools/list", json!({}))?;
+    let result = client.call(
+        "tools/call",
+        json!({ "name": "list_capabilities", "arguments": {} }),
+    )?;
+    assert_ne!(result["isError"], true, "{}", result);
+    let catalog: Value =
+        serde_json::from_str(result["content"][0]["text"].as_str().unwrap_or_default())?;
+    assert!(catalog["error_schema"].is_object(), "{}", catalog);
+
+    let entries = catalog["tools"].as_array().expect("tools array");
+    for tool in listed["tools"].as_array().expect("tools array") {
+        let name = tool["name"].as_str().expect("tool name");
+        let entry = entries
+            .iter()
+            .find(|entry| entry["name"] == name)
+            .unwrap_or_else(|| panic!("{} is missing from the catalog", name));
+        let properties = tool["inputSchema"]["properties"]
+            .as_object()
+            .cloned()
+            .unwrap_or_default();
+        let arguments = entry["example"]["params"]["arguments"]
+            .as_object()
+            .unwrap_or_else(|| panic!("{} has no example", name));
+        assert!(
+            arguments.keys().all(|key| properties.contains_key(key)),
+            "The example of {} should only use its parameters: {}",
+            name,
+            entry["example"]
+        );
+        for required in tool["inputSchema"]["required"]
+            .as_array()
+            .into_iter()
+            .flatten()
+        {
+            let required = required.as_str().expect("parameter name");
+            assert!(
+                arguments.contains_key(required),
+                "The example of {} should give {}",
+                name,
+                required
+            );
+        }
+    }
+    assert_eq!(entries.len(), client.tool_names()?.len());
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_resources_list() -> Result<()> {
+    let mut client = TestClient::start(true);
+    client.initialize()?;
+
+    let result = client.call("resources/list", json!({}))?;
+    assert_eq!(result["resources"], json!([]));
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_unknown_method() -> Result<()> {
+    let mut client = TestClient::start(true);
+    client.initialize()?;
+
+    let response = client.request("no/such_method", json!({}))?;
+    assert!(response["error"]["code"].is_i64(), "{}", response);
+
+    // The server keeps serving after an error
+    client.call("resources/list", json!({}))?;
+    Ok(())
+}
@@ -0,0 +1,30 @@
+use anyhow::Result;
+use docs_rs_mcp::tools::SearchInCrateTool;
+use mcp_sdk::{tools::Tool, types::ToolResponseContent};
+use serde_json::json;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_search_in_crate_opentelemetry_sdk() -> Result<()> {
+    let tool = SearchInCrateTool::new();
+
+    let response = tool.call(Some(json!({
+        "crate_name": "opentelemetry_sdk",
+        "query": "TracerProviderBuilder",
+        "version": "0.28.0"
+    })))?;
+
+    let content = match &response.content[0] {
+        ToolResponseContent::Text { text } => text.as_str(),
+        _ => panic!("Expected text response"),
+    };
+    let results: serde_json::Value = serde_json::from_str(content)?;
+    let first = &results[0];
+
+    assert_eq!(first["kind"], "struct");
+    assert_eq!(
+        first["path"],
+        "opentelemetry_sdk::trace::TracerProviderBuilder"
+    );
+
+    Ok(())
+}
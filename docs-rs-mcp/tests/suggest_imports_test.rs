@@ -0,0 +1,38 @@
+use anyhow::Result;
+use docs_rs_mcp::tools::SuggestImportsTool;
+use mcp_sdk::{tools::Tool, types::ToolResponseContent};
+use serde_json::json;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_suggest_imports_tokio_async_read_ext() -> Result<()> {
+    let tool = SuggestImportsTool::new();
+
+    let response = tool.call(Some(json!({
+        "crate_name": "tokio",
+        "item": "AsyncReadExt",
+        "version": "1.43.0"
+    })))?;
+
+    let content = match &response.content[0] {
+        ToolResponseContent::Text { text } => text.as_str(),
+        _ => panic!("Expected text response"),
+    };
+    let suggestions: serde_json::Value = serde_json::from_str(content)?;
+
+    assert_eq!(
+        suggestions["suggestions"][0]["statement"].as_str(),
+        Some("use tokio::io::AsyncReadExt; // requires feature \"io-util\"")
+    );
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_suggest_imports_unknown_item() {
+    let tool = SuggestImportsTool::new();
+
+    let result = tool.call(Some(json!({
+        "crate_name": "tokio",
+        "item": "NoSuchItemAnywhere"
+    })));
+    assert!(result.is_err());
+}
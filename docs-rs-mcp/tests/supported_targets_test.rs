@@ -0,0 +1,30 @@
+use anyhow::Result;
+use docs_rs_mcp::tools::SupportedTargetsTool;
+use mcp_sdk::{tools::Tool, types::ToolResponseContent};
+use serde_json::json;
+
+fn text(response: &mcp_sdk::types::CallToolResponse) -> &str {
+    match &response.content[0] {
+        ToolResponseContent::Text { text } => text.as_str(),
+        _ => panic!("Expected text response"),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_supported_targets_winapi() -> Result<()> {
+    let tool = SupportedTargetsTool::new();
+
+    let response = tool.call(Some(json!({
+        "crate_name": "winapi",
+        "version": "0.3.9"
+    })))?;
+    let targets: serde_json::Value = serde_json::from_str(text(&response))?;
+    assert_eq!(targets["default_target"], "x86_64-pc-windows-msvc");
+    assert!(targets["platforms"]
+        .as_array()
+        .expect("platforms should be an array")
+        .iter()
+        .any(|platform| platform == "windows"));
+
+    Ok(())
+}
@@ -1,3 +1,5 @@
+#![cfg(feature = "native")]
+
 use anyhow::Result;
 use docs_rs_mcp::{
     tools::{CrateInfoTool, CrateItemsTool},
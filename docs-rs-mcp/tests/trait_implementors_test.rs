@@ -0,0 +1,45 @@
+use anyhow::Result;
+use docs_rs_mcp::tools::TraitImplementorsTool;
+use mcp_sdk::{tools::Tool, types::ToolResponseContent};
+use serde_json::json;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_trait_implementors_tokio_async_read() -> Result<()> {
+    let tool = TraitImplementorsTool::new();
+
+    let response = tool.call(Some(json!({
+        "crate_name": "tokio",
+        "trait_path": "io::AsyncRead",
+        "version": "1.43.0"
+    })))?;
+
+    let content = match &response.content[0] {
+        ToolResponseContent::Text { text } => text.as_str(),
+        _ => panic!("Expected text response"),
+    };
+    let implementors: serde_json::Value = serde_json::from_str(content)?;
+    let types = |list: &str| -> Vec<String> {
+        implementors[list]
+            .as_array()
+            .map(|entries| {
+                entries
+                    .iter()
+                    .filter_map(|entry| entry["type_name"].as_str().map(str::to_string))
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+
+    assert!(
+        types("implementors").iter().any(|t| t == "TcpStream"),
+        "TcpStream implements AsyncRead"
+    );
+    assert!(
+        types("foreign_implementors")
+            .iter()
+            .any(|t| t.starts_with("Box<")),
+        "Box<T> implements AsyncRead"
+    );
+
+    Ok(())
+}
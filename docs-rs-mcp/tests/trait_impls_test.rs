@@ -0,0 +1,40 @@
+use anyhow::Result;
+use docs_rs_mcp::tools::TraitImplsTool;
+use mcp_sdk::{tools::Tool, types::ToolResponseContent};
+use serde_json::json;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_trait_impls_serde_json_value() -> Result<()> {
+    let tool = TraitImplsTool::new();
+
+    let response = tool.call(Some(json!({
+        "crate_name": "serde_json",
+        "type_path": "Value",
+        "version": "1.0.138"
+    })))?;
+
+    let content = match &response.content[0] {
+        ToolResponseContent::Text { text } => text.as_str(),
+        _ => panic!("Expected text response"),
+    };
+    let result: serde_json::Value = serde_json::from_str(content)?;
+    let impls = result["impls"].as_array().expect("impls should be a list");
+    let find = |trait_name: &str| {
+        impls
+            .iter()
+            .find(|i| i["trait_name"] == trait_name)
+            .unwrap_or_else(|| panic!("Value should implement {}", trait_name))
+    };
+
+    assert_eq!(find("Clone")["origin"], "derived");
+    assert_eq!(find("Display")["origin"], "manual");
+    assert_eq!(find("Send")["origin"], "auto");
+    assert!(
+        impls
+            .iter()
+            .any(|i| i["signature"].as_str() == Some("impl From<bool> for Value")),
+        "From impls carry their full signature"
+    );
+
+    Ok(())
+}
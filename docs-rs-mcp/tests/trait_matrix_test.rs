@@ -0,0 +1,31 @@
+use anyhow::Result;
+use docs_rs_mcp::tools::TraitMatrixTool;
+use mcp_sdk::{tools::Tool, types::ToolResponseContent};
+use serde_json::json;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_trait_matrix_bytes() -> Result<()> {
+    let tool = TraitMatrixTool::new();
+
+    let response = tool.call(Some(json!({
+        "types": ["bytes::Bytes", "bytes::BytesMut", "std::string::String"],
+        "traits": ["Clone", "bytes::BufMut", "Send"],
+        "versions": { "bytes": "1.9.0" }
+    })))?;
+
+    let content = match &response.content[0] {
+        ToolResponseContent::Text { text } => text.as_str(),
+        _ => panic!("Expected text response"),
+    };
+    let matrix: serde_json::Value = serde_json::from_str(content)?;
+    let implemented = |row: usize, column: usize| {
+        matrix["types"][row]["implements"][column]["implemented"].as_bool()
+    };
+
+    assert_eq!(implemented(0, 0), Some(true), "Bytes is Clone");
+    assert_eq!(implemented(0, 1), Some(false), "Bytes isn't BufMut");
+    assert_eq!(implemented(1, 1), Some(true), "BytesMut is BufMut");
+    assert_eq!(implemented(2, 2), Some(true), "String is Send");
+
+    Ok(())
+}